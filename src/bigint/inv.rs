@@ -265,7 +265,7 @@ impl<const N_BITS: u32, const LIMB_SIZE: u32> BigIntImpl<N_BITS, LIMB_SIZE> {
 pub fn limb_shr1_carry(num_bits: u32) -> Script {
     let powers_of_2_script = if num_bits < 7 {
         script! {
-            for i in 0..num_bits - 1 {
+            for i in 1..num_bits {
                 { 2_u32.pow(i) }
             }
         }
@@ -408,6 +408,24 @@ mod test {
             let exec_result = execute_script(script);
             assert!(exec_result.success);
         }
+
+        // short limbs, such as the 4-bit head limb of U381
+        for num_bits in 2..7 {
+            for a in 0..1u32 << num_bits {
+                for carry in 0..2u32 {
+                    let script = script! {
+                        { a }
+                        { carry }
+                        { limb_shr1_carry(num_bits) }
+                        { a & 1 } OP_EQUALVERIFY
+                        { (carry << (num_bits - 1)) | (a >> 1) } OP_EQUAL
+                    };
+
+                    let exec_result = execute_script(script);
+                    assert!(exec_result.success);
+                }
+            }
+        }
     }
 
     #[test]
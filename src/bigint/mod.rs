@@ -5,6 +5,7 @@ pub mod inv;
 pub mod mul;
 pub mod std;
 pub mod sub;
+pub mod u29x14;
 pub mod u29x9;
 
 pub struct BigIntImpl<const N_BITS: u32, const LIMB_SIZE: u32> {}
@@ -17,4 +18,5 @@ impl<const N_BITS: u32, const LIMB_SIZE: u32> BigIntImpl<N_BITS, LIMB_SIZE> {
 }
 
 pub type U254 = BigIntImpl<254, 29>;
+pub type U381 = BigIntImpl<381, 29>;
 pub type U64 = BigIntImpl<64, 16>;
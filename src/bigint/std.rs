@@ -6,8 +6,39 @@ use crate::bigint::BigIntImpl;
 use crate::pseudo::push_to_stack;
 use crate::treepp::*;
 
+// Minimal encoding of a script number, as pushed by `OP_0` to `OP_16` or the shortest data push.
+pub(crate) fn script_num(n: u32) -> Vec<u8> {
+    let mut bytes = n.to_le_bytes().to_vec();
+    while bytes.last() == Some(&0) {
+        bytes.pop();
+    }
+    if bytes.last().is_some_and(|byte| byte & 0x80 != 0) {
+        bytes.push(0);
+    }
+    bytes
+}
+
 impl<const N_BITS: u32, const LIMB_SIZE: u32> BigIntImpl<N_BITS, LIMB_SIZE> {
     pub fn push_u32_le(v: &[u32]) -> Script {
+        let limbs = Self::u32_le_to_limbs(v);
+
+        script! {
+            for limb in &limbs {
+                { *limb }
+            }
+            { push_to_stack(0,Self::N_LIMBS as usize - limbs.len()) }
+        }
+    }
+
+    /// The stack items `push_u32_le` leaves on the stack, e.g. to put the number in a witness.
+    pub fn witness_u32_le(v: &[u32]) -> Vec<Vec<u8>> {
+        let mut limbs = Self::u32_le_to_limbs(v);
+        limbs.resize(Self::N_LIMBS as usize, 0);
+        limbs.into_iter().map(script_num).collect()
+    }
+
+    // Limbs of the number, most significant first.
+    fn u32_le_to_limbs(v: &[u32]) -> Vec<u32> {
         let mut bits = vec![];
         for elem in v.iter() {
             for i in 0..32 {
@@ -32,13 +63,7 @@ impl<const N_BITS: u32, const LIMB_SIZE: u32> BigIntImpl<N_BITS, LIMB_SIZE> {
         }
 
         limbs.reverse();
-
-        script! {
-            for limb in &limbs {
-                { *limb }
-            }
-            { push_to_stack(0,Self::N_LIMBS as usize - limbs.len()) }
-        }
+        limbs
     }
 
     pub fn push_u64_le(v: &[u64]) -> Script {
@@ -290,6 +315,7 @@ impl<const N_BITS: u32, const LIMB_SIZE: u32> BigIntImpl<N_BITS, LIMB_SIZE> {
 #[cfg(test)]
 mod test {
     use crate::bigint::{BigIntImpl, U254};
+    use crate::execute_script_with_witness_without_stack_limit;
     use crate::treepp::execute_script;
     use bitcoin_script::script;
     use rand::{Rng, SeedableRng};
@@ -539,6 +565,26 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_witness_u32_le() {
+        let mut prng = ChaCha20Rng::seed_from_u64(0);
+
+        let mut values = vec![vec![0], vec![5], vec![0x80]];
+        for _ in 0..10 {
+            values.push((0..8).map(|_| prng.gen::<u32>()).collect());
+        }
+        for v in values {
+            let script = script! {
+                { U254::push_u32_le(&v) }
+                { U254::equalverify(1, 0) }
+                OP_TRUE
+            };
+            let exec_result =
+                execute_script_with_witness_without_stack_limit(script, U254::witness_u32_le(&v));
+            assert!(exec_result.success);
+        }
+    }
+
     #[test]
     fn push_hex() {
         let exec_result = execute_script(script! {
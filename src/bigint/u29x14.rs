@@ -0,0 +1,275 @@
+use std::cmp::max;
+use std::ops::Range;
+
+use crate::bigint::u29x9::{
+    u29_mul_carry_29, u29_mul_carry_29_imm, u29x2_sub_noborrow, u30_mul_to_u29_carry_31,
+    u30_mul_to_u29_carry_31_imm,
+};
+use crate::treepp::*;
+
+// Products over the 14 limbs of `U381`, as `u29x9` computes them over the 9 limbs of `U254`:
+// the cross terms of each column come from the Karatsuba identity
+//     Aᵢ⋅Bⱼ+Aⱼ⋅Bᵢ = (Aᵢ+Aⱼ)⋅(Bᵢ+Bⱼ) - Aᵢ⋅Bᵢ - Aⱼ⋅Bⱼ
+// so a full product takes 105 limb multiplications instead of 196.
+
+const N_LIMBS: u32 = 14;
+
+// The second factor of a product, on the stack below the first one or a constant.
+#[derive(Clone, Copy)]
+enum Factor<'a> {
+    Stack,
+    Constant(&'a [u32; N_LIMBS as usize]),
+}
+
+fn op_pick(n: u32) -> Script {
+    match n {
+        0 => script! { OP_DUP },
+        1 => script! { OP_OVER },
+        _ => script! { { n } OP_PICK },
+    }
+}
+
+// Adds a two limb term to the accumulator of the current column, keeping the limbs of the
+// accumulator below 2²⁹.
+// W₀ W₁ W₂ P₂₈…₀ P₅₈…₂₉
+fn u29x3_add_u29u30() -> Script {
+    script! {
+        // W₀ W₁ W₂ P₂₈…₀ P₅₈…₂₉
+        3 OP_ROLL OP_ADD
+        // W₀ W₂ P₂₈…₀ W₁+P₅₈…₂₉
+        OP_SWAP 3 OP_ROLL OP_ADD
+        // W₂ W₁+P₅₈…₂₉ W₀+P₂₈…₀
+        { 1 << 29 } OP_2DUP OP_GREATERTHANOREQUAL
+        OP_IF OP_SUB OP_SWAP OP_1ADD OP_ELSE OP_DROP OP_SWAP OP_ENDIF
+        // W₂ (W+P)₂₈…₀ W₁+P₅₈…₂₉+(W₀+P₂₈…₀)₂₉
+        OP_ROT OP_SWAP
+        // (W+P)₂₈…₀ W₂ W₁+P₅₈…₂₉+(W₀+P₂₈…₀)₂₉, the last one below 3⋅2²⁹
+        for _ in 0..2 {
+            { 1 << 29 } OP_2DUP OP_GREATERTHANOREQUAL
+            OP_IF OP_SUB OP_SWAP OP_1ADD OP_SWAP OP_ELSE OP_DROP OP_ENDIF
+        }
+        OP_SWAP
+        // (W+P)₂₈…₀ (W+P)₅₇…₂₉ (W+P)₈₆…₅₈
+    }
+}
+
+// Limbs `kept` of the product, column by column from the least significant one.
+//
+// input:  ⋯ A₁₃ ⋯ A₀ B₁₃ ⋯ B₀  (B only for `Factor::Stack`)
+// output: ⋯ (A⋅B)ₖ₋₁ ⋯ (A⋅B)ⱼ  for kept = j..k
+fn mul_columns(factor: Factor, kept: Range<u32>) -> Script {
+    let n = N_LIMBS;
+    let num_b = match factor {
+        Factor::Stack => n,
+        Factor::Constant(_) => 0,
+    };
+    // the diagonal products Aᵢ⋅Bᵢ, two limbs each, then the three limbs of the accumulator
+    let num_diagonal = 2 * n;
+    let num_items = n + num_b + num_diagonal + 3;
+
+    // depths with the accumulator on top and `above` items over it
+    let a_depth = |i: u32, above: u32| above + 3 + num_diagonal + num_b + i;
+    let b_depth = |i: u32, above: u32| above + 3 + num_diagonal + i;
+    let diagonal_hi_depth = |i: u32, above: u32| above + 3 + 2 * (n - 1 - i);
+
+    // Aᵢ⋅Bᵢ, with Aᵢ on top of the stack
+    let diagonal_product = |i: u32| match factor {
+        Factor::Stack => script! {
+            { op_pick(3 * i + 1) }
+            { u29_mul_carry_29() }
+        },
+        Factor::Constant(constant) => u29_mul_carry_29_imm(constant[i as usize]),
+    };
+    // (Aᵢ+Aⱼ)⋅(Bᵢ+Bⱼ), with Aᵢ+Aⱼ on top of the stack above the accumulator
+    let sum_product = |i: u32, j: u32| match factor {
+        Factor::Stack => script! {
+            { op_pick(b_depth(i, 1)) }
+            { op_pick(b_depth(j, 2)) }
+            OP_ADD
+            { u30_mul_to_u29_carry_31() }
+        },
+        Factor::Constant(constant) => {
+            u30_mul_to_u29_carry_31_imm(constant[i as usize] + constant[j as usize])
+        }
+    };
+
+    script! {
+        // Aᵢ⋅Bᵢ for every i
+        for i in 0..n {
+            { op_pick(num_b + 3 * i) }
+            { diagonal_product(i) }
+        }
+        // ⋯ A B (A₀⋅B₀)₂₈…₀ (A₀⋅B₀)₅₇…₂₉ ⋯ (A₁₃⋅B₁₃)₂₈…₀ (A₁₃⋅B₁₃)₅₇…₂₉
+
+        0 0 0
+        // ⋯ A B Aᵢ⋅Bᵢ W₀ W₁ W₂
+
+        for k in 0..kept.end {
+            // Aᵢ⋅Bⱼ+Aⱼ⋅Bᵢ for i < j and i + j = k
+            for i in max(k, n - 1) - (n - 1)..k.div_ceil(2) {
+                { op_pick(a_depth(i, 0)) }
+                { op_pick(a_depth(k - i, 1)) }
+                OP_ADD
+                { sum_product(i, k - i) }
+                // ⋯ W₀ W₁ W₂ (Aᵢ₊ⱼ⋅Bᵢ₊ⱼ)₂₈…₀ (Aᵢ₊ⱼ⋅Bᵢ₊ⱼ)₅₉…₂₉
+                { op_pick(diagonal_hi_depth(i, 2) + 1) }
+                { op_pick(diagonal_hi_depth(i, 2) + 1) }
+                { u29x2_sub_noborrow() }
+                { op_pick(diagonal_hi_depth(k - i, 2) + 1) }
+                { op_pick(diagonal_hi_depth(k - i, 2) + 1) }
+                { u29x2_sub_noborrow() }
+                // ⋯ W₀ W₁ W₂ (Aᵢ⋅Bⱼ+Aⱼ⋅Bᵢ)₂₈…₀ (Aᵢ⋅Bⱼ+Aⱼ⋅Bᵢ)₅₈…₂₉
+                { u29x3_add_u29u30() }
+            }
+
+            // Aᵢ⋅Bᵢ for 2i = k
+            if k % 2 == 0 && k / 2 < n {
+                { op_pick(diagonal_hi_depth(k / 2, 0) + 1) }
+                { op_pick(diagonal_hi_depth(k / 2, 0) + 1) }
+                { u29x3_add_u29u30() }
+            }
+
+            // the column is complete, W₀ is the limb k of the product
+            OP_ROT
+            if kept.contains(&k) {
+                OP_TOALTSTACK
+            } else {
+                OP_DROP
+            }
+            0
+            // ⋯ W₁ W₂ 0
+        }
+
+        for _ in 0..num_items / 2 {
+            OP_2DROP
+        }
+        if num_items % 2 == 1 {
+            OP_DROP
+        }
+        for _ in kept {
+            OP_FROMALTSTACK
+        }
+    }
+}
+
+// ⋯ A₁₃ ⋯ A₀ B₁₃ ⋯ B₀
+pub fn u29x14_mul() -> Script {
+    script! {
+        { mul_columns(Factor::Stack, 0..2 * N_LIMBS) }
+        // ⋯ (A⋅B)₂₇ ⋯ (A⋅B)₀
+    }
+}
+
+// ⋯ A₁₃ ⋯ A₀
+pub fn u29x14_mul_imm(u29x14_constant: [u32; 14]) -> Script {
+    script! {
+        { mul_columns(Factor::Constant(&u29x14_constant), 0..2 * N_LIMBS) }
+        // ⋯ (A⋅B)₂₇ ⋯ (A⋅B)₀
+    }
+}
+
+// ⋯ A₁₃ ⋯ A₀
+pub fn u29x14_mulhi_imm(u29x14_constant: [u32; 14]) -> Script {
+    script! {
+        { mul_columns(Factor::Constant(&u29x14_constant), N_LIMBS..2 * N_LIMBS) }
+        // ⋯ (A⋅B)₂₇ ⋯ (A⋅B)₁₄
+    }
+}
+
+// ⋯ A₁₃ ⋯ A₀
+pub fn u29x14_mullo_imm(u29x14_constant: [u32; 14]) -> Script {
+    script! {
+        { mul_columns(Factor::Constant(&u29x14_constant), 0..N_LIMBS) }
+        // ⋯ (A⋅B)₁₃ ⋯ (A⋅B)₀
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use num_bigint::{BigUint, RandomBits};
+    use num_traits::One;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha20Rng;
+
+    // 29-bit limbs of v, least significant first
+    fn limbs(v: &BigUint, n: u32) -> Vec<u32> {
+        let mask = (BigUint::one() << 29) - 1u32;
+        (0..n)
+            .map(|i| {
+                ((v >> (29 * i)) & &mask)
+                    .to_u32_digits()
+                    .first()
+                    .copied()
+                    .unwrap_or(0)
+            })
+            .collect()
+    }
+
+    fn push(v: &BigUint, n: u32) -> Script {
+        script! {
+            for limb in limbs(v, n).into_iter().rev() {
+                { limb }
+            }
+        }
+    }
+
+    fn equalverify(v: &BigUint, n: u32) -> Script {
+        script! {
+            for limb in limbs(v, n) {
+                { limb } OP_EQUALVERIFY
+            }
+        }
+    }
+
+    fn random_pairs() -> Vec<(BigUint, BigUint)> {
+        let mut prng = ChaCha20Rng::seed_from_u64(0);
+        let max: BigUint = (BigUint::one() << 406) - 1u32;
+        let mut pairs = vec![(max.clone(), max)];
+        for _ in 0..5 {
+            pairs.push((
+                prng.sample(RandomBits::new(406)),
+                prng.sample(RandomBits::new(406)),
+            ));
+        }
+        pairs
+    }
+
+    #[test]
+    fn test_u29x14_mul() {
+        println!("u29x14_mul: {} bytes", u29x14_mul().len());
+        for (a, b) in random_pairs() {
+            let script = script! {
+                { push(&a, 14) }
+                { push(&b, 14) }
+                { u29x14_mul() }
+                { equalverify(&(&a * &b), 28) }
+                OP_TRUE
+            };
+            let exec_result = execute_script(script);
+            assert!(exec_result.success);
+        }
+    }
+
+    #[test]
+    fn test_u29x14_mul_imm() {
+        for (a, b) in random_pairs() {
+            let constant: [u32; 14] = limbs(&b, 14).try_into().unwrap();
+            let product = &a * &b;
+            let script = script! {
+                { push(&a, 14) }
+                { u29x14_mul_imm(constant) }
+                { equalverify(&product, 28) }
+                { push(&a, 14) }
+                { u29x14_mulhi_imm(constant) }
+                { equalverify(&(&product >> 406), 14) }
+                { push(&a, 14) }
+                { u29x14_mullo_imm(constant) }
+                { equalverify(&product, 14) }
+                OP_TRUE
+            };
+            let exec_result = execute_script(script);
+            assert!(exec_result.success);
+        }
+    }
+}
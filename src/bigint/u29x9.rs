@@ -65,7 +65,7 @@ fn u29_bits_to_altstack() -> Script {
 
 // (A₂₈…₀ ⋅ B₂₈…₀)₂₈…₀ (A₂₈…₀ ⋅ B₂₈…₀)₅₇…₂₉
 // A₂₈…₀ B₂₈…₀
-fn u29_mul_carry_29() -> Script {
+pub(super) fn u29_mul_carry_29() -> Script {
     script! {
         // A₂₈…₀ B₂₈…₀
         { assert_nn_le(0x1FFFFFFF) } // 0≤A₂₈…₀<2²⁹
@@ -187,7 +187,7 @@ fn u29_mul_carry_29() -> Script {
 
 // (A₂₉…₀ ⋅ B₂₉…₀)₂₈…₀ (A₂₉…₀ ⋅ B₂₉…₀)₅₉…₂₉
 // A₂₉…₀ B₂₉…₀
-fn u30_mul_to_u29_carry_31() -> Script {
+pub(super) fn u30_mul_to_u29_carry_31() -> Script {
     script! {
         // A₂₉…₀ B₂₉…₀
         { assert_nn_le(0x3FFFFFFF) } // 0≤A₂₉…₀<2³⁰
@@ -281,7 +281,7 @@ fn u30_mul_to_u29_carry_31() -> Script {
     }
 }
 
-fn u29x2_sub_noborrow() -> Script {
+pub(super) fn u29x2_sub_noborrow() -> Script {
     script! {
         OP_2SWAP
         // A₂₈…₀ A₅₇…₂₉ B₂₈…₀ B₅₉…₂₉
@@ -1190,7 +1190,7 @@ pub fn u29x9_square(a: u32) -> Script {
 
 // (A₂₈…₀ ⋅ B₂₈…₀)₂₈…₀ (A₂₈…₀ ⋅ B₂₈…₀)₅₇…₂₉
 // A₂₈…₀
-fn u29_mul_carry_29_imm(u29_constant: u32) -> Script {
+pub(super) fn u29_mul_carry_29_imm(u29_constant: u32) -> Script {
     script! {
         // A₂₈…₀
         { assert_nn_le(0x1FFFFFFF) } // 0≤A₂₈…₀<2²⁹
@@ -1325,7 +1325,7 @@ fn u29_mul_carry_29_imm(u29_constant: u32) -> Script {
 
 // (A₂₉…₀ ⋅ B₂₉…₀)₂₈…₀ (A₂₉…₀ ⋅ B₂₉…₀)₅₉…₂₉
 // A₂₉…₀
-fn u30_mul_to_u29_carry_31_imm(u30_constant: u32) -> Script {
+pub(super) fn u30_mul_to_u29_carry_31_imm(u30_constant: u32) -> Script {
     script! {
         // ⋯ A₂₉…₀
         { assert_nn_le(0x3FFFFFFF) } // 0≤A₂₉…₀<2³⁰
@@ -0,0 +1,19 @@
+use bitvm::bridge::client::cli::commands::{run, Cli};
+use clap::Parser;
+use tracing_subscriber::EnvFilter;
+
+#[tokio::main]
+async fn main() {
+    // Logs go to stderr so that the command output on stdout stays machine readable.
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
+        )
+        .with_writer(std::io::stderr)
+        .init();
+
+    if let Err(err) = run(Cli::parse()).await {
+        eprintln!("Error: {}", err);
+        std::process::exit(1);
+    }
+}
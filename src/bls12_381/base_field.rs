@@ -0,0 +1,99 @@
+use crate::bls12_381::fp381impl::Fp381Impl;
+use crate::bn254::fp254impl::Fp254Impl;
+use crate::treepp::Script;
+use ark_ec::bls12::Bls12Config;
+use ark_ff::{Fp12Config, Fp2Config, Fp6Config, PrimeField};
+
+/// The base field `GenericFq2`, `GenericFq6` and `GenericFq12` are built on: the gadgets of the
+/// field, one element per `copy` / `roll` index, and the arkworks configs of the extensions.
+///
+/// The tower is Fq2 = Fq[u] / (u² + 1), Fq6 = Fq2[v] / (v³ - β) and Fq12 = Fq6[w] / (w² - v), as
+/// for both BN254 and BLS12-381.
+pub trait BaseField {
+    type Fp: PrimeField;
+    type Fp2Config: Fp2Config<Fp = Self::Fp>;
+    type Fp6Config: Fp6Config<Fp2Config = Self::Fp2Config>;
+    type Fp12Config: Fp12Config<Fp6Config = Self::Fp6Config>;
+
+    fn copy(a: u32) -> Script;
+    fn roll(a: u32) -> Script;
+    fn drop() -> Script;
+    fn toaltstack() -> Script;
+    fn fromaltstack() -> Script;
+    fn push_zero() -> Script;
+    fn push_one() -> Script;
+    fn push_u32_le(v: &[u32]) -> Script;
+    fn witness_u32_le(v: &[u32]) -> Vec<Vec<u8>>;
+    fn equalverify(a: u32, b: u32) -> Script;
+    fn add(a: u32, b: u32) -> Script;
+    fn sub(a: u32, b: u32) -> Script;
+    fn double(a: u32) -> Script;
+    fn neg(a: u32) -> Script;
+    fn mul() -> Script;
+    fn square() -> Script;
+    fn mul_by_constant(constant: &Self::Fp) -> Script;
+    fn inv() -> Script;
+}
+
+/// The base field of a BLS12 curve, for the miller loop of `GenericPairing`.
+pub trait Bls12BaseField: BaseField {
+    type Config: Bls12Config<
+        Fp = Self::Fp,
+        Fp2Config = Self::Fp2Config,
+        Fp6Config = Self::Fp6Config,
+        Fp12Config = Self::Fp12Config,
+    >;
+}
+
+// `BaseField` from the gadgets of `Fp254Impl` or `Fp381Impl`.
+macro_rules! impl_base_field {
+    ($field:ty, $fp_impl:path, $fp2:ty, $fp6:ty, $fp12:ty) => {
+        impl BaseField for $field {
+            type Fp = <$field as $fp_impl>::ConstantType;
+            type Fp2Config = $fp2;
+            type Fp6Config = $fp6;
+            type Fp12Config = $fp12;
+
+            fn copy(a: u32) -> Script { <$field as $fp_impl>::copy(a) }
+            fn roll(a: u32) -> Script { <$field as $fp_impl>::roll(a) }
+            fn drop() -> Script { <$field as $fp_impl>::drop() }
+            fn toaltstack() -> Script { <$field as $fp_impl>::toaltstack() }
+            fn fromaltstack() -> Script { <$field as $fp_impl>::fromaltstack() }
+            fn push_zero() -> Script { <$field as $fp_impl>::push_zero() }
+            fn push_one() -> Script { <$field as $fp_impl>::push_one() }
+            fn push_u32_le(v: &[u32]) -> Script { <$field as $fp_impl>::push_u32_le(v) }
+            fn witness_u32_le(v: &[u32]) -> Vec<Vec<u8>> { <$field as $fp_impl>::witness_u32_le(v) }
+            fn equalverify(a: u32, b: u32) -> Script { <$field as $fp_impl>::equalverify(a, b) }
+            fn add(a: u32, b: u32) -> Script { <$field as $fp_impl>::add(a, b) }
+            fn sub(a: u32, b: u32) -> Script { <$field as $fp_impl>::sub(a, b) }
+            fn double(a: u32) -> Script { <$field as $fp_impl>::double(a) }
+            fn neg(a: u32) -> Script { <$field as $fp_impl>::neg(a) }
+            fn mul() -> Script { <$field as $fp_impl>::mul() }
+            fn square() -> Script { <$field as $fp_impl>::square() }
+            fn mul_by_constant(constant: &Self::Fp) -> Script {
+                <$field as $fp_impl>::mul_by_constant(constant)
+            }
+            fn inv() -> Script { <$field as $fp_impl>::inv() }
+        }
+    };
+}
+
+impl_base_field!(
+    crate::bls12_381::fq::Fq,
+    Fp381Impl,
+    ark_bls12_381::Fq2Config,
+    ark_bls12_381::Fq6Config,
+    ark_bls12_381::Fq12Config
+);
+
+impl_base_field!(
+    crate::bn254::fq::Fq,
+    Fp254Impl,
+    ark_bn254::Fq2Config,
+    ark_bn254::Fq6Config,
+    ark_bn254::Fq12Config
+);
+
+impl Bls12BaseField for crate::bls12_381::fq::Fq {
+    type Config = ark_bls12_381::Config;
+}
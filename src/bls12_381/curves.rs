@@ -0,0 +1,1113 @@
+use crate::bls12_381::fp381impl::Fp381Impl;
+use crate::bls12_381::fq::Fq;
+use crate::bls12_381::fq2::Fq2;
+use crate::bls12_381::fr::Fr;
+use crate::bls12_381::utils::{fq2_push, fq_push};
+use crate::treepp::{script, Script};
+use ark_ec::bls12::Bls12Config;
+use ark_ec::short_weierstrass::SWCurveConfig;
+use ark_ff::{AdditiveGroup, PrimeField};
+use num_bigint::BigUint;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+static G1_DOUBLE_PROJECTIVE: OnceLock<Script> = OnceLock::new();
+static G1_NONZERO_ADD_PROJECTIVE: OnceLock<Script> = OnceLock::new();
+static G2_DOUBLE_PROJECTIVE: OnceLock<Script> = OnceLock::new();
+static G2_NONZERO_ADD_PROJECTIVE: OnceLock<Script> = OnceLock::new();
+
+// Coefficients of psi, the untwist-Frobenius-twist endomorphism of G2, see `G2Affine::is_in_subgroup`:
+// psi(x, y) = (x^p * PSI_COEFF_X, y^p * PSI_COEFF_Y). arkworks keeps them private.
+const PSI_COEFF_X_C1: &str = "4002409555221667392624310435006688643935503118305586438271171395842971157480381377015405980053539358417135540939437";
+const PSI_COEFF_Y_C0: &str = "2973677408986561043442465346520108879172042883009249989176415018091420807192182638567116318576472649347015917690530";
+const PSI_COEFF_Y_C1: &str = "1028732146235106349975324479215795277384839936929757896155643118032610843298655225875571310552543014690878354869257";
+
+/// Points of the curve over Fq in Jacobian coordinates, [x, y, z] on the stack.
+pub struct G1Projective;
+
+impl G1Projective {
+    pub fn push(element: ark_bls12_381::G1Projective) -> Script {
+        script! {
+            { fq_push(element.x) }
+            { fq_push(element.y) }
+            { fq_push(element.z) }
+        }
+    }
+
+    pub fn push_zero() -> Script {
+        script! {
+            { Fq::push_zero() }
+            { Fq::push_zero() }
+            { Fq::push_zero() }
+        }
+    }
+
+    pub fn is_zero_keep_element(a: u32) -> Script {
+        script! {
+            // Check if the third coordinate(z) is zero
+            { Fq::is_zero_keep_element(a * 3) }
+        }
+    }
+
+    // Input Stack: [x, y, z]
+    // Output Stack: [2P]
+    // The point isn't the identity.
+    pub fn nonzero_double() -> Script {
+        G1_DOUBLE_PROJECTIVE
+            .get_or_init(|| {
+                script! {
+                    { Fq::copy(2) }
+                    { Fq::square() }
+                    { Fq::copy(2) }
+                    { Fq::square() }
+                    { Fq::copy(0) }
+                    { Fq::square() }
+                    { Fq::add(5, 1) }
+                    { Fq::square() }
+                    { Fq::copy(1) }
+                    { Fq::sub(1, 0) }
+                    { Fq::copy(2) }
+                    { Fq::sub(1, 0) }
+                    { Fq::double(0) }
+                    { Fq::copy(2) }
+                    { Fq::double(0) }
+                    { Fq::add(3, 0) }
+                    { Fq::copy(0) }
+                    { Fq::square() }
+                    { Fq::copy(2) }
+                    { Fq::double(0) }
+                    { Fq::sub(1, 0) }
+                    { Fq::copy(0) }
+                    { Fq::sub(3, 0) }
+                    { Fq::roll(2) }
+                    { Fq::mul() }
+                    { Fq::double(2) }
+                    { Fq::double(0) }
+                    { Fq::double(0) }
+                    { Fq::sub(1, 0) }
+                    { Fq::roll(2) }
+                    { Fq::roll(3) }
+                    { Fq::mul() }
+                    { Fq::double(0) }
+                }
+            })
+            .clone()
+    }
+
+    pub fn double() -> Script {
+        script! {
+            { G1Projective::copy(0) }
+            { G1Projective::toaltstack() }
+            // Check if the first point is zero
+            { G1Projective::is_zero_keep_element(0) }
+            OP_TOALTSTACK
+            // Perform a regular doubling
+            { G1Projective::nonzero_double() }
+
+            // Select result
+            OP_FROMALTSTACK
+            OP_IF
+                // Return original point
+                { G1Projective::drop() }
+                { G1Projective::fromaltstack() }
+            OP_ELSE
+                // Return regular doubling result
+                { G1Projective::fromaltstack() }
+                { G1Projective::drop() }
+            OP_ENDIF
+        }
+    }
+
+    // Input Stack: [x1, y1, z1, x2, y2, z2]
+    // Output Stack: [P1 + P2]
+    // Neither point is the identity, and the points are different.
+    pub fn nonzero_add() -> Script {
+        G1_NONZERO_ADD_PROJECTIVE
+            .get_or_init(|| {
+                script! {
+                    { Fq::copy(3) }
+                    { Fq::square() }
+                    { Fq::copy(1) }
+                    { Fq::square() }
+                    { Fq::roll(7) }
+                    { Fq::copy(1) }
+                    { Fq::mul() }
+                    { Fq::roll(5) }
+                    { Fq::copy(3) }
+                    { Fq::mul() }
+                    { Fq::copy(2) }
+                    { Fq::roll(8) }
+                    { Fq::mul() }
+                    { Fq::copy(5) }
+                    { Fq::mul() }
+                    { Fq::copy(4) }
+                    { Fq::roll(7) }
+                    { Fq::mul() }
+                    { Fq::copy(7) }
+                    { Fq::mul() }
+                    { Fq::add(7, 6)}
+                    { Fq::copy(4) }
+                    { Fq::sub(4, 0)}
+                    { Fq::copy(0) }
+                    { Fq::double(0) }
+                    { Fq::square() }
+                    { Fq::copy(1) }
+                    { Fq::copy(1) }
+                    { Fq::mul() }
+                    { Fq::copy(5) }
+                    { Fq::sub(5, 0) }
+                    { Fq::double(0) }
+                    { Fq::roll(6) }
+                    { Fq::roll(3) }
+                    { Fq::mul() }
+                    { Fq::copy(1) }
+                    { Fq::square() }
+                    { Fq::copy(3) }
+                    { Fq::sub(1, 0) }
+                    { Fq::copy(1) }
+                    { Fq::double(0) }
+                    { Fq::sub(1, 0) }
+                    { Fq::copy(0) }
+                    { Fq::sub(2, 0) }
+                    { Fq::roll(2) }
+                    { Fq::mul() }
+                    { Fq::roll(5) }
+                    { Fq::roll(3) }
+                    { Fq::mul() }
+                    { Fq::double(0) }
+                    { Fq::sub(1, 0) }
+                    { Fq::roll(3) }
+                    { Fq::square() }
+                    { Fq::sub(0, 5) }
+                    { Fq::sub(0, 4) }
+                    { Fq::roll(3) }
+                    { Fq::mul() }
+                }
+            })
+            .clone()
+    }
+
+    pub fn add() -> Script {
+        script! {
+            { G1Projective::copy(0) }
+            { G1Projective::toaltstack() }
+            { G1Projective::copy(1) }
+            { G1Projective::toaltstack() }
+
+            // Check if the first point is zero
+            { G1Projective::is_zero_keep_element(0) }
+            OP_TOALTSTACK
+            // Check if the second point is zero
+            { G1Projective::is_zero_keep_element(1) }
+            OP_TOALTSTACK
+
+            // Perform a regular addition
+            { G1Projective::nonzero_add() }
+
+            // Select result
+            OP_FROMALTSTACK
+            OP_FROMALTSTACK
+            OP_IF
+                // First point is zero
+                OP_DROP
+                { G1Projective::drop() }
+                { G1Projective::fromaltstack() }
+                { G1Projective::fromaltstack() }
+                { G1Projective::drop() }
+            OP_ELSE
+                OP_IF
+                    // Second point is zero
+                    { G1Projective::drop() }
+                    { G1Projective::fromaltstack() }
+                    { G1Projective::drop() }
+                    { G1Projective::fromaltstack() }
+                OP_ELSE
+                    // Both summands are non-zero
+                    { G1Projective::fromaltstack() }
+                    { G1Projective::fromaltstack() }
+                    { G1Projective::drop() }
+                    { G1Projective::drop() }
+                OP_ENDIF
+            OP_ENDIF
+        }
+    }
+
+    // Input Stack: [P]
+    // Output Stack: [k * P]
+    // The point isn't the identity, and it has no multiple [m]P = ±P for the prefixes m of k.
+    pub fn scalar_mul_by_constant(k: &BigUint) -> Script {
+        let bits = (0..k.bits()).rev().map(|i| k.bit(i)).collect::<Vec<_>>();
+        script! {
+            { G1Projective::copy(0) }
+            for bit in bits.iter().skip(1) {
+                { G1Projective::nonzero_double() }
+                if *bit {
+                    { G1Projective::copy(1) }
+                    { G1Projective::nonzero_add() }
+                }
+            }
+            { G1Projective::roll(1) }
+            { G1Projective::drop() }
+        }
+    }
+
+    // Input Stack: [s]
+    // Output Stack: [s * p]
+    // s is an element of Fr, a double-and-add over its bits with p added from the script.
+    pub fn scalar_mul_by_constant_g1(p: ark_bls12_381::G1Projective) -> Script {
+        let n_bits = ark_bls12_381::Fr::MODULUS_BIT_SIZE;
+        script! {
+            { Fr::decode_montgomery() }
+            { Fr::convert_to_le_bits_toaltstack() }
+            // the bits above the ones of the modulus are zero
+            for _ in n_bits..Fr::N_BITS {
+                OP_FROMALTSTACK OP_DROP
+            }
+
+            { G1Projective::push_zero() }
+            for i in 0..n_bits {
+                if i > 0 {
+                    { G1Projective::double() }
+                }
+                OP_FROMALTSTACK
+                OP_IF
+                    { G1Projective::push(p) }
+                    { G1Projective::add() }
+                OP_ENDIF
+            }
+        }
+    }
+
+    pub fn copy(mut a: u32) -> Script {
+        a *= 3;
+        script! {
+            { Fq::copy(a + 2) }
+            { Fq::copy(a + 2) }
+            { Fq::copy(a + 2) }
+        }
+    }
+
+    pub fn roll(mut a: u32) -> Script {
+        a *= 3;
+        script! {
+            { Fq::roll(a + 2) }
+            { Fq::roll(a + 2) }
+            { Fq::roll(a + 2) }
+        }
+    }
+
+    pub fn drop() -> Script {
+        script! {
+            { Fq::drop() }
+            { Fq::drop() }
+            { Fq::drop() }
+        }
+    }
+
+    pub fn toaltstack() -> Script {
+        script! {
+            { Fq::toaltstack() }
+            { Fq::toaltstack() }
+            { Fq::toaltstack() }
+        }
+    }
+
+    pub fn fromaltstack() -> Script {
+        script! {
+            { Fq::fromaltstack() }
+            { Fq::fromaltstack() }
+            { Fq::fromaltstack() }
+        }
+    }
+
+    // Input Stack: [x, y, z]
+    // Output Stack: [x/z^2, y/z^3], [0, 0] for the identity
+    pub fn into_affine() -> Script {
+        script! {
+            { G1Projective::is_zero_keep_element(0) }
+            OP_IF
+                { G1Projective::drop() }
+                { G1Affine::identity() }
+            OP_ELSE
+                { Fq::inv() }
+                { Fq::copy(0) }
+                { Fq::square() }
+                { Fq::copy(0) }
+                { Fq::roll(2) }
+                { Fq::mul() }
+                // [x, y, 1/z^2, 1/z^3]
+                { Fq::roll(2) }
+                { Fq::mul() }
+                { Fq::toaltstack() }
+                { Fq::mul() }
+                { Fq::fromaltstack() }
+            OP_ENDIF
+        }
+    }
+}
+
+/// Points of the curve over Fq in affine coordinates, [x, y] on the stack. The identity is
+/// [0, 0].
+pub struct G1Affine;
+
+impl G1Affine {
+    pub fn push(element: ark_bls12_381::G1Affine) -> Script {
+        script! {
+            { fq_push(element.x) }
+            { fq_push(element.y) }
+        }
+    }
+
+    pub fn identity() -> Script {
+        script! {
+            { Fq::push_zero() }
+            { Fq::push_zero() }
+        }
+    }
+
+    // Input Stack: [x, y]
+    // Output Stack: [y^2 == x^3 + b]
+    pub fn is_on_curve() -> Script {
+        script! {
+            { Fq::copy(1) }
+            { Fq::square() }
+            { Fq::roll(2) }
+            { Fq::mul() }
+            { fq_push(ark_bls12_381::g1::Config::COEFF_B) }
+            { Fq::add(1, 0) }
+            { Fq::roll(1) }
+            { Fq::square() }
+            { Fq::equal(1, 0) }
+        }
+    }
+
+    // Input Stack: [x, y]
+    // Output Stack: [phi(P) == -x^2 * P]
+    // The point is on the curve and isn't the identity. phi(x, y) = (beta * x, y) with beta a cube
+    // root of unity acts as multiplication by -x^2 on the subgroup of order r, see section 6 of
+    // https://eprint.iacr.org/2021/1130.pdf. The curve has a cofactor, unlike the one of BN254.
+    pub fn is_in_subgroup() -> Script {
+        let x = BigUint::from(ark_bls12_381::Config::X[0]);
+        script! {
+            { G1Affine::copy(0) }
+            { G1Affine::toaltstack() }
+
+            // x^2 * P, a point of order r has no multiple [m]P = ±P with m < r
+            { G1Affine::into_projective() }
+            { G1Projective::scalar_mul_by_constant(&(&x * &x)) }
+            { G1Projective::into_affine() }
+
+            // -x^2 * P == phi(P), namely x^2 * P == (beta * x, -y)
+            { G1Affine::fromaltstack() }
+            { Fq::neg(0) }
+            { Fq::roll(1) }
+            { Fq::mul_by_constant(&ark_bls12_381::g1::BETA) }
+            { Fq::equal(3, 0) }
+            OP_TOALTSTACK
+            { Fq::equal(1, 0) }
+            OP_FROMALTSTACK
+            OP_BOOLAND
+        }
+    }
+
+    // Checks a point received in a witness, say a proof point: its coordinates are field
+    // elements, it is on the curve and in the subgroup of order r. The identity isn't valid.
+    // Input Stack: [x, y]
+    // Output Stack: [is_valid]
+    pub fn is_valid() -> Script {
+        script! {
+            { Fq::copy(1) }
+            { Fq::is_field() }
+            OP_TOALTSTACK
+            { Fq::copy(0) }
+            { Fq::is_field() }
+            OP_TOALTSTACK
+            { G1Affine::copy(0) }
+            { G1Affine::is_on_curve() }
+            OP_TOALTSTACK
+            // only meaningful on the curve, hence the conjunction with the check above
+            { G1Affine::is_in_subgroup() }
+            OP_FROMALTSTACK OP_BOOLAND
+            OP_FROMALTSTACK OP_BOOLAND
+            OP_FROMALTSTACK OP_BOOLAND
+        }
+    }
+
+    // Init stack: [x1, y1, x2, y2]
+    pub fn equalverify() -> Script {
+        script! {
+            { Fq::roll(2) }
+            { Fq::equalverify(1, 0) }
+            { Fq::equalverify(1, 0) }
+        }
+    }
+
+    pub fn copy(mut a: u32) -> Script {
+        a *= 2;
+        script! {
+            { Fq::copy(a + 1) }
+            { Fq::copy(a + 1) }
+        }
+    }
+
+    pub fn toaltstack() -> Script {
+        script! {
+            { Fq::toaltstack() }
+            { Fq::toaltstack() }
+        }
+    }
+
+    pub fn fromaltstack() -> Script {
+        script! {
+            { Fq::fromaltstack() }
+            { Fq::fromaltstack() }
+        }
+    }
+
+    // Input Stack: [x, y]
+    // Output Stack: [x, y, z] (z=1)
+    pub fn into_projective() -> Script { script!({ Fq::push_one() }) }
+}
+
+/// Points of the twist over Fq2 in Jacobian coordinates, [x, y, z] on the stack.
+pub struct G2Projective;
+
+impl G2Projective {
+    pub fn push(element: ark_bls12_381::G2Projective) -> Script {
+        script! {
+            { fq2_push(element.x) }
+            { fq2_push(element.y) }
+            { fq2_push(element.z) }
+        }
+    }
+
+    pub fn push_zero() -> Script {
+        script! {
+            { Fq2::push_one() }
+            { Fq2::push_one() }
+            { Fq2::push_zero() }
+        }
+    }
+
+    pub fn is_zero_keep_element(a: u32) -> Script {
+        script! {
+            // Check if the third coordinate(z) is zero
+            { Fq::is_zero_keep_element(a * 6) }
+            OP_TOALTSTACK
+            { Fq::is_zero_keep_element(a * 6 + 1) }
+            OP_FROMALTSTACK
+            OP_BOOLAND
+        }
+    }
+
+    // Input Stack: [x, y, z]
+    // Output Stack: [2P]
+    // The point isn't the identity.
+    pub fn nonzero_double() -> Script {
+        G2_DOUBLE_PROJECTIVE
+            .get_or_init(|| {
+                script! {
+                    // A = x^2
+                    { Fq2::copy(4) }
+                    { Fq2::square() }
+                    // B = y^2
+                    { Fq2::copy(4) }
+                    { Fq2::square() }
+                    // C = B^2
+                    { Fq2::copy(0) }
+                    { Fq2::square() }
+                    // [x, y, z, A, B, C]
+
+                    // D = 2 * ((x + B)^2 - A - C)
+                    { Fq2::roll(2) }
+                    { Fq2::roll(10) }
+                    { Fq2::add(2, 0) }
+                    { Fq2::square() }
+                    { Fq2::copy(4) }
+                    { Fq2::sub(2, 0) }
+                    { Fq2::copy(2) }
+                    { Fq2::sub(2, 0) }
+                    { Fq2::double(0) }
+                    // [y, z, A, C, D]
+
+                    // E = 3 * A, F = E^2
+                    { Fq2::roll(4) }
+                    { Fq2::copy(0) }
+                    { Fq2::double(0) }
+                    { Fq2::add(2, 0) }
+                    { Fq2::copy(0) }
+                    { Fq2::square() }
+                    // [y, z, C, D, E, F]
+
+                    // x' = F - 2 * D
+                    { Fq2::copy(4) }
+                    { Fq2::double(0) }
+                    { Fq2::sub(2, 0) }
+                    // [y, z, C, D, E, x']
+
+                    // y' = E * (D - x') - 8 * C
+                    { Fq2::copy(0) }
+                    { Fq2::roll(6) }
+                    { Fq2::sub(0, 2) }
+                    { Fq2::mul(4, 0) }
+                    { Fq2::roll(4) }
+                    { Fq2::double(0) }
+                    { Fq2::double(0) }
+                    { Fq2::double(0) }
+                    { Fq2::sub(2, 0) }
+                    // [y, z, x', y']
+
+                    // z' = 2 * y * z
+                    { Fq2::roll(6) }
+                    { Fq2::roll(6) }
+                    { Fq2::mul(2, 0) }
+                    { Fq2::double(0) }
+                }
+            })
+            .clone()
+    }
+
+    // Input Stack: [x1, y1, z1, x2, y2, z2]
+    // Output Stack: [P1 + P2]
+    // Neither point is the identity, and the points are different.
+    pub fn nonzero_add() -> Script {
+        G2_NONZERO_ADD_PROJECTIVE
+            .get_or_init(|| {
+                script! {
+                    // z1z1 = z1^2, z2z2 = z2^2
+                    { Fq2::copy(6) }
+                    { Fq2::square() }
+                    { Fq2::copy(2) }
+                    { Fq2::square() }
+
+                    // u1 = x1 * z2z2
+                    { Fq2::copy(0) }
+                    { Fq2::roll(16) }
+                    { Fq2::mul(2, 0) }
+                    // u2 = x2 * z1z1
+                    { Fq2::copy(4) }
+                    { Fq2::roll(12) }
+                    { Fq2::mul(2, 0) }
+                    // [y1, z1, y2, z2, z1z1, z2z2, u1, u2]
+
+                    // s1 = y1 * z2 * z2z2
+                    { Fq2::copy(4) }
+                    { Fq2::copy(10) }
+                    { Fq2::mul(2, 0) }
+                    { Fq2::roll(16) }
+                    { Fq2::mul(2, 0) }
+                    // s2 = y2 * z1 * z1z1
+                    { Fq2::copy(14) }
+                    { Fq2::copy(10) }
+                    { Fq2::mul(2, 0) }
+                    { Fq2::roll(14) }
+                    { Fq2::mul(2, 0) }
+                    // [z1, z2, z1z1, z2z2, u1, u2, s1, s2]
+
+                    // (z1 + z2)^2 - z1z1 - z2z2
+                    { Fq2::roll(14) }
+                    { Fq2::roll(14) }
+                    { Fq2::add(2, 0) }
+                    { Fq2::square() }
+                    { Fq2::roll(12) }
+                    { Fq2::sub(2, 0) }
+                    { Fq2::roll(10) }
+                    { Fq2::sub(2, 0) }
+                    // [u1, u2, s1, s2, (z1 + z2)^2 - z1z1 - z2z2]
+
+                    // h = u2 - u1
+                    { Fq2::roll(6) }
+                    { Fq2::copy(8) }
+                    { Fq2::sub(2, 0) }
+                    // z' = ((z1 + z2)^2 - z1z1 - z2z2) * h
+                    { Fq2::copy(0) }
+                    { Fq2::roll(4) }
+                    { Fq2::mul(2, 0) }
+                    { Fq2::toaltstack() }
+                    // [u1, s1, s2, h]
+
+                    // i = (2 * h)^2, j = h * i
+                    { Fq2::copy(0) }
+                    { Fq2::double(0) }
+                    { Fq2::square() }
+                    { Fq2::roll(2) }
+                    { Fq2::copy(2) }
+                    { Fq2::mul(2, 0) }
+                    // r = 2 * (s2 - s1)
+                    { Fq2::roll(4) }
+                    { Fq2::copy(6) }
+                    { Fq2::sub(2, 0) }
+                    { Fq2::double(0) }
+                    // v = u1 * i
+                    { Fq2::roll(8) }
+                    { Fq2::roll(6) }
+                    { Fq2::mul(2, 0) }
+                    // [s1, j, r, v]
+
+                    // x' = r^2 - j - 2 * v
+                    { Fq2::copy(2) }
+                    { Fq2::square() }
+                    { Fq2::copy(6) }
+                    { Fq2::sub(2, 0) }
+                    { Fq2::copy(2) }
+                    { Fq2::double(0) }
+                    { Fq2::sub(2, 0) }
+                    // [s1, j, r, v, x']
+
+                    // y' = r * (v - x') - 2 * s1 * j
+                    { Fq2::copy(0) }
+                    { Fq2::roll(4) }
+                    { Fq2::sub(0, 2) }
+                    { Fq2::roll(4) }
+                    { Fq2::mul(2, 0) }
+                    { Fq2::roll(6) }
+                    { Fq2::roll(6) }
+                    { Fq2::mul(2, 0) }
+                    { Fq2::double(0) }
+                    { Fq2::sub(2, 0) }
+                    // [x', y']
+
+                    { Fq2::fromaltstack() }
+                }
+            })
+            .clone()
+    }
+
+    // Input Stack: [P]
+    // Output Stack: [k * P]
+    // The point isn't the identity, and it has no multiple [m]P = ±P for the prefixes m of k.
+    pub fn scalar_mul_by_constant(k: &BigUint) -> Script {
+        let bits = (0..k.bits()).rev().map(|i| k.bit(i)).collect::<Vec<_>>();
+        script! {
+            { G2Projective::copy(0) }
+            for bit in bits.iter().skip(1) {
+                { G2Projective::nonzero_double() }
+                if *bit {
+                    { G2Projective::copy(1) }
+                    { G2Projective::nonzero_add() }
+                }
+            }
+            { G2Projective::roll(1) }
+            { G2Projective::drop() }
+        }
+    }
+
+    pub fn copy(mut a: u32) -> Script {
+        a *= 6;
+        script! {
+            { Fq2::copy(a + 4) }
+            { Fq2::copy(a + 4) }
+            { Fq2::copy(a + 4) }
+        }
+    }
+
+    pub fn roll(mut a: u32) -> Script {
+        a *= 6;
+        script! {
+            { Fq2::roll(a + 4) }
+            { Fq2::roll(a + 4) }
+            { Fq2::roll(a + 4) }
+        }
+    }
+
+    pub fn drop() -> Script {
+        script! {
+            { Fq2::drop() }
+            { Fq2::drop() }
+            { Fq2::drop() }
+        }
+    }
+
+    // Input Stack: [x, y, z]
+    // Output Stack: [x/z^2, y/z^3], [0, 0] for the identity
+    pub fn into_affine() -> Script {
+        script! {
+            { G2Projective::is_zero_keep_element(0) }
+            OP_IF
+                { G2Projective::drop() }
+                { G2Affine::identity() }
+            OP_ELSE
+                { Fq2::inv() }
+                { Fq2::copy(0) }
+                { Fq2::square() }
+                { Fq2::copy(0) }
+                { Fq2::roll(4) }
+                { Fq2::mul(2, 0) }
+                // [x, y, 1/z^2, 1/z^3]
+                { Fq2::roll(4) }
+                { Fq2::mul(2, 0) }
+                { Fq2::toaltstack() }
+                { Fq2::mul(2, 0) }
+                { Fq2::fromaltstack() }
+            OP_ENDIF
+        }
+    }
+}
+
+/// Points of the twist over Fq2 in affine coordinates, [x, y] on the stack. The identity is
+/// [0, 0].
+pub struct G2Affine;
+
+impl G2Affine {
+    pub fn push(element: ark_bls12_381::G2Affine) -> Script {
+        script! {
+            { fq2_push(element.x) }
+            { fq2_push(element.y) }
+        }
+    }
+
+    pub fn identity() -> Script {
+        script! {
+            { Fq2::push_zero() }
+            { Fq2::push_zero() }
+        }
+    }
+
+    // Input Stack: [x, y]
+    // Output Stack: [y^2 == x^3 + b]
+    pub fn is_on_curve() -> Script {
+        script! {
+            { Fq2::copy(2) }
+            { Fq2::square() }
+            { Fq2::roll(4) }
+            { Fq2::mul(2, 0) }
+            { fq2_push(ark_bls12_381::g2::Config::COEFF_B) }
+            { Fq2::add(2, 0) }
+            { Fq2::roll(2) }
+            { Fq2::square() }
+            { G2Affine::fq2_equal() }
+        }
+    }
+
+    // Input Stack: [x, y]
+    // Output Stack: [psi(P) == -x * P]
+    // The point is on the curve and isn't the identity. psi is the untwist-Frobenius-twist
+    // endomorphism, which acts as multiplication by p = x on the subgroup of order r, see
+    // section 4 of https://eprint.iacr.org/2021/1130.pdf. x is negative: -x is
+    // `ark_bls12_381::Config::X`.
+    pub fn is_in_subgroup() -> Script {
+        let coeff_x = ark_bls12_381::Fq2::new(
+            ark_bls12_381::Fq::ZERO,
+            ark_bls12_381::Fq::from_str(PSI_COEFF_X_C1).unwrap(),
+        );
+        let coeff_y = ark_bls12_381::Fq2::new(
+            ark_bls12_381::Fq::from_str(PSI_COEFF_Y_C0).unwrap(),
+            ark_bls12_381::Fq::from_str(PSI_COEFF_Y_C1).unwrap(),
+        );
+        script! {
+            // -psi(P)
+            { G2Affine::copy(0) }
+            { Fq2::roll(2) }
+            { Fq2::frobenius_map(1) }
+            { Fq2::mul_by_constant(&coeff_x) }
+            { Fq2::roll(2) }
+            { Fq2::frobenius_map(1) }
+            { Fq2::mul_by_constant(&-coeff_y) }
+            { G2Affine::toaltstack() }
+
+            // -x * P, a point of order r has no multiple [m]P = ±P with m < r
+            { G2Affine::into_projective() }
+            { G2Projective::scalar_mul_by_constant(&BigUint::from(ark_bls12_381::Config::X[0])) }
+            { G2Projective::into_affine() }
+
+            { G2Affine::fromaltstack() }
+            { Fq2::roll(4) }
+            { G2Affine::fq2_equal() }
+            OP_TOALTSTACK
+            { G2Affine::fq2_equal() }
+            OP_FROMALTSTACK
+            OP_BOOLAND
+        }
+    }
+
+    // Input Stack: [x, y]
+    // Output Stack: [is_valid]
+    // Checks a point received in a witness, say a proof point: its coordinates are field
+    // elements, it is on the curve and in the subgroup of order r. The identity isn't valid.
+    pub fn is_valid() -> Script {
+        script! {
+            for i in 0..4 {
+                { Fq::copy(i) }
+                { Fq::is_field() }
+                OP_TOALTSTACK
+            }
+            { G2Affine::copy(0) }
+            { G2Affine::is_on_curve() }
+            OP_TOALTSTACK
+            // only meaningful on the curve, hence the conjunction with the check above
+            { G2Affine::is_in_subgroup() }
+            for _ in 0..5 {
+                OP_FROMALTSTACK
+                OP_BOOLAND
+            }
+        }
+    }
+
+    // Init stack: [x1, y1, x2, y2]
+    pub fn equalverify() -> Script {
+        script! {
+            { Fq2::roll(4) }
+            { Fq2::equalverify() }
+            { Fq2::equalverify() }
+        }
+    }
+
+    pub fn copy(mut a: u32) -> Script {
+        a *= 4;
+        script! {
+            { Fq2::copy(a + 2) }
+            { Fq2::copy(a + 2) }
+        }
+    }
+
+    pub fn toaltstack() -> Script {
+        script! {
+            { Fq2::toaltstack() }
+            { Fq2::toaltstack() }
+        }
+    }
+
+    pub fn fromaltstack() -> Script {
+        script! {
+            { Fq2::fromaltstack() }
+            { Fq2::fromaltstack() }
+        }
+    }
+
+    // Input Stack: [x, y]
+    // Output Stack: [x, y, z] (z=1)
+    pub fn into_projective() -> Script {
+        script! {
+            { Fq2::push_one() }
+        }
+    }
+
+    // Input Stack: [a, b]
+    // Output Stack: [a == b]
+    fn fq2_equal() -> Script {
+        script! {
+            { Fq::equal(3, 1) }
+            OP_TOALTSTACK
+            { Fq::equal(1, 0) }
+            OP_FROMALTSTACK
+            OP_BOOLAND
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::bls12_381::curves::{G1Affine, G1Projective, G2Affine, G2Projective};
+    use crate::bls12_381::fp381impl::Fp381Impl;
+    use crate::bls12_381::fq::Fq;
+    use crate::bls12_381::fr::Fr;
+    use crate::bls12_381::utils::fq2_push;
+    use crate::execute_script_without_stack_limit;
+    use crate::treepp::*;
+    use ark_ec::CurveGroup;
+    use ark_ff::AdditiveGroup;
+    use ark_std::UniformRand;
+    use num_bigint::BigUint;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha20Rng;
+    use std::ops::Mul;
+
+    // A point of the curve out of the subgroup of order r, as is almost any point of the curve.
+    fn random_g1_point_on_curve(prng: &mut ChaCha20Rng) -> ark_bls12_381::G1Affine {
+        loop {
+            let x = ark_bls12_381::Fq::rand(prng);
+            if let Some(p) = ark_bls12_381::G1Affine::get_point_from_x_unchecked(x, prng.gen()) {
+                assert!(!p.is_in_correct_subgroup_assuming_on_curve());
+                return p;
+            }
+        }
+    }
+
+    fn random_g2_point_on_curve(prng: &mut ChaCha20Rng) -> ark_bls12_381::G2Affine {
+        loop {
+            let x = ark_bls12_381::Fq2::rand(prng);
+            if let Some(p) = ark_bls12_381::G2Affine::get_point_from_x_unchecked(x, prng.gen()) {
+                assert!(!p.is_in_correct_subgroup_assuming_on_curve());
+                return p;
+            }
+        }
+    }
+
+    #[test]
+    fn test_bls12_381_g1_projective_add_double() {
+        let mut prng = ChaCha20Rng::seed_from_u64(0);
+
+        for _ in 0..1 {
+            let a = ark_bls12_381::G1Projective::rand(&mut prng);
+            let b = ark_bls12_381::G1Projective::rand(&mut prng);
+
+            let script = script! {
+                { G1Projective::push(a) }
+                { G1Projective::push(b) }
+                { G1Projective::add() }
+                { G1Projective::double() }
+                { G1Projective::into_affine() }
+                { G1Affine::push((a + b).double().into_affine()) }
+                { G1Affine::equalverify() }
+                { G1Projective::push_zero() }
+                { G1Projective::push(a) }
+                { G1Projective::add() }
+                { G1Projective::into_affine() }
+                { G1Affine::push(a.into_affine()) }
+                { G1Affine::equalverify() }
+                OP_TRUE
+            };
+            let exec_result = execute_script(script);
+            assert!(exec_result.success);
+        }
+    }
+
+    #[test]
+    fn test_bls12_381_g1_scalar_mul_by_constant_g1() {
+        let mut prng = ChaCha20Rng::seed_from_u64(0);
+
+        for _ in 0..1 {
+            let p = ark_bls12_381::G1Projective::rand(&mut prng);
+            let s = ark_bls12_381::Fr::rand(&mut prng);
+
+            let script = script! {
+                { Fr::push_u32_le(&BigUint::from(s).to_u32_digits()) }
+                { G1Projective::scalar_mul_by_constant_g1(p) }
+                { G1Projective::into_affine() }
+                { G1Affine::push(p.mul(s).into_affine()) }
+                { G1Affine::equalverify() }
+                OP_TRUE
+            };
+            let exec_result = execute_script_without_stack_limit(script);
+            assert!(exec_result.success);
+        }
+    }
+
+    #[test]
+    fn test_bls12_381_g1_affine_is_valid() {
+        println!("G1Affine.is_valid: {} bytes", G1Affine::is_valid().len());
+        let mut prng = ChaCha20Rng::seed_from_u64(0);
+
+        for _ in 0..1 {
+            let p = ark_bls12_381::G1Projective::rand(&mut prng).into_affine();
+            let q = random_g1_point_on_curve(&mut prng);
+
+            let script = script! {
+                { G1Affine::push(p) }
+                { G1Affine::is_valid() }
+                OP_VERIFY
+                { G1Affine::push(q) }
+                { G1Affine::copy(0) }
+                { G1Affine::is_on_curve() }
+                OP_VERIFY
+                { G1Affine::is_valid() }
+                OP_NOT
+            };
+            let exec_result = execute_script(script);
+            assert!(exec_result.success);
+        }
+    }
+
+    #[test]
+    fn test_bls12_381_g2_projective_add_double() {
+        let mut prng = ChaCha20Rng::seed_from_u64(0);
+
+        for _ in 0..1 {
+            let a = ark_bls12_381::G2Projective::rand(&mut prng);
+            let b = ark_bls12_381::G2Projective::rand(&mut prng);
+
+            let script = script! {
+                { G2Projective::push(a) }
+                { G2Projective::push(b) }
+                { G2Projective::nonzero_add() }
+                { G2Projective::nonzero_double() }
+                { G2Projective::into_affine() }
+                { G2Affine::push((a + b).double().into_affine()) }
+                { G2Affine::equalverify() }
+                OP_TRUE
+            };
+            let exec_result = execute_script(script);
+            assert!(exec_result.success);
+        }
+    }
+
+    #[test]
+    fn test_bls12_381_g2_affine_is_valid() {
+        println!("G2Affine.is_valid: {} bytes", G2Affine::is_valid().len());
+        let mut prng = ChaCha20Rng::seed_from_u64(0);
+
+        for _ in 0..1 {
+            let p = ark_bls12_381::G2Projective::rand(&mut prng).into_affine();
+            let q = random_g2_point_on_curve(&mut prng);
+
+            let script = script! {
+                { G2Affine::push(p) }
+                { G2Affine::is_valid() }
+                OP_VERIFY
+                { G2Affine::push(q) }
+                { G2Affine::copy(0) }
+                { G2Affine::is_on_curve() }
+                OP_VERIFY
+                { G2Affine::is_valid() }
+                OP_NOT
+            };
+            let exec_result = execute_script(script);
+            assert!(exec_result.success);
+        }
+    }
+
+    #[test]
+    fn test_bls12_381_g1_affine_is_on_curve() {
+        println!(
+            "G1Affine.is_on_curve: {} bytes",
+            G1Affine::is_on_curve().len()
+        );
+        let mut prng = ChaCha20Rng::seed_from_u64(0);
+
+        for _ in 0..3 {
+            let p = ark_bls12_381::G1Projective::rand(&mut prng).into_affine();
+
+            let script = script! {
+                { G1Affine::push(p) }
+                { G1Affine::is_on_curve() }
+                OP_VERIFY
+                { G1Affine::push(p) }
+                { G1Affine::is_valid() }
+                OP_VERIFY
+                { G1Affine::push(p) }
+                { Fq::push_one() }
+                { Fq::add(1, 0) }
+                { G1Affine::is_on_curve() }
+                OP_NOT
+            };
+            let exec_result = execute_script(script);
+            assert!(exec_result.success);
+        }
+    }
+
+    #[test]
+    fn test_bls12_381_g2_affine_is_on_curve() {
+        println!(
+            "G2Affine.is_on_curve: {} bytes",
+            G2Affine::is_on_curve().len()
+        );
+        let mut prng = ChaCha20Rng::seed_from_u64(0);
+
+        for _ in 0..1 {
+            let p = ark_bls12_381::G2Projective::rand(&mut prng).into_affine();
+            let q = ark_bls12_381::G2Projective::rand(&mut prng).into_affine();
+
+            let script = script! {
+                { G2Affine::push(p) }
+                { G2Affine::is_on_curve() }
+                OP_VERIFY
+                { fq2_push(p.x) }
+                { fq2_push(q.y) }
+                { G2Affine::is_on_curve() }
+                OP_NOT
+            };
+            let exec_result = execute_script(script);
+            assert!(exec_result.success);
+        }
+    }
+}
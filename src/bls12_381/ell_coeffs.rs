@@ -0,0 +1,93 @@
+// Affine line coefficients of the miller loop of a BLS12 curve, BLS12-381 by default, as
+// `bn254::ell_coeffs::G2Prepared` computes them for BN254.
+
+use ark_ec::bls12::{Bls12Config, G2Affine};
+use ark_ff::{AdditiveGroup, BitIteratorBE, Field, Fp2};
+use num_traits::One;
+
+pub struct G2Prepared<P: Bls12Config = ark_bls12_381::Config> {
+    /// Coefficients (1, alpha, -bias) of the lines of the doubling and addition steps, in the
+    /// order of the miller loop.
+    pub ell_coeffs: Vec<EllCoeff<P>>,
+    pub infinity: bool,
+}
+
+// aka. line in miller loop.
+pub type EllCoeff<P = ark_bls12_381::Config> = (
+    Fp2<<P as Bls12Config>::Fp2Config>,
+    Fp2<<P as Bls12Config>::Fp2Config>,
+    Fp2<<P as Bls12Config>::Fp2Config>,
+);
+
+/// Bits of |x| after the most significant one, most significant first: the steps of the miller
+/// loop. There is an addition step after the doubling step of each bit set.
+pub fn loop_bits<P: Bls12Config>() -> Vec<bool> {
+    BitIteratorBE::without_leading_zeros(P::X).skip(1).collect()
+}
+
+impl<P: Bls12Config> G2Prepared<P> {
+    fn affine_double_in_place(t: &mut G2Affine<P>, three_div_two: &P::Fp) -> EllCoeff<P> {
+        //  for affine coordinates
+        //  slope: alpha = 3 * x^2 / 2 * y
+        // intercept: bias = y - alpha * x
+        let mut alpha = t.x.square();
+        alpha /= t.y;
+        alpha.mul_assign_by_fp(three_div_two);
+        let bias = t.y - alpha * t.x;
+
+        // update T
+        // T.x = alpha^2 - 2 * t.x
+        // T.y = -bias - alpha * T.x
+        let tx = alpha.square() - t.x.double();
+        t.y = -bias - alpha * tx;
+        t.x = tx;
+
+        (Fp2::ONE, alpha, -bias)
+    }
+
+    fn affine_add_in_place(t: &mut G2Affine<P>, q: &G2Affine<P>) -> EllCoeff<P> {
+        // alpha = (t.y - q.y) / (t.x - q.x)
+        // bias = t.y - alpha * t.x
+        let alpha = (t.y - q.y) / (t.x - q.x);
+        let bias = t.y - alpha * t.x;
+
+        // update T
+        // T.x = alpha^2 - t.x - q.x
+        // T.y = -bias - alpha * T.x
+        let tx = alpha.square() - t.x - q.x;
+        t.y = -bias - alpha * tx;
+        t.x = tx;
+
+        (Fp2::ONE, alpha, -bias)
+    }
+
+    /// Lines of the miller loop over the bits of |x|. Unlike BN254 there are no lines of the
+    /// frobenius maps of q after the loop, and the sign of x is left to the caller: the miller
+    /// loop these lines evaluate is f_{|x|, q}, the conjugate of the one of arkworks.
+    pub fn from_affine(q: G2Affine<P>) -> Self {
+        if q.infinity {
+            Self {
+                ell_coeffs: vec![],
+                infinity: true,
+            }
+        } else {
+            let two_inv = P::Fp::one().double().inverse().unwrap();
+            let three_div_two = (P::Fp::one().double() + P::Fp::one()) * two_inv;
+
+            let mut ell_coeffs = vec![];
+            let mut r = q;
+
+            for bit in loop_bits::<P>() {
+                ell_coeffs.push(Self::affine_double_in_place(&mut r, &three_div_two));
+                if bit {
+                    ell_coeffs.push(Self::affine_add_in_place(&mut r, &q));
+                }
+            }
+
+            Self {
+                ell_coeffs,
+                infinity: false,
+            }
+        }
+    }
+}
@@ -0,0 +1,252 @@
+use crate::bigint::u29x14::{u29x14_mul, u29x14_mul_imm, u29x14_mulhi_imm, u29x14_mullo_imm};
+use crate::bigint::{BigIntImpl, U381};
+use crate::treepp::*;
+use ark_ff::{BigInteger, PrimeField};
+use num_bigint::BigUint;
+use num_traits::{Num, One};
+use std::ops::{Div, Mul, Rem, Shl};
+
+// Same 14 limbs as `U381` with a wider head limb, for sums of two field elements before they are
+// reduced: those may not fit in 381 bits.
+type U384 = BigIntImpl<384, 29>;
+
+/// Prime fields of up to 381 bits, e.g. the base field of BLS12-381.
+///
+/// As for `Fp254Impl`, elements are kept on the stack in Montgomery form, here with R = 2⁴⁰⁶
+/// for the 14 limbs of `U381`.
+pub trait Fp381Impl {
+    const MODULUS: &'static str;
+    const MONTGOMERY_ONE: &'static str;
+    const N_LIMBS: u32 = U381::N_LIMBS;
+    const N_BITS: u32 = U381::N_BITS;
+
+    // Modulus as 29-bit limbs
+    const MODULUS_LIMBS: [u32; U381::N_LIMBS as usize];
+    const MODULUS_INV_406: [u32; U381::N_LIMBS as usize];
+
+    type ConstantType: PrimeField;
+
+    #[inline]
+    fn copy(a: u32) -> Script { U381::copy(a) }
+
+    #[inline]
+    fn roll(a: u32) -> Script { U381::roll(a) }
+
+    #[inline]
+    fn drop() -> Script { U381::drop() }
+
+    #[inline]
+    fn zip(a: u32, b: u32) -> Script { U381::zip(a, b) }
+
+    #[inline]
+    fn push_u32_le(v: &[u32]) -> Script {
+        let r = BigUint::from_str_radix(Self::MONTGOMERY_ONE, 16).unwrap();
+        let p = BigUint::from_str_radix(Self::MODULUS, 16).unwrap();
+        script! {
+            { U381::push_u32_le(&BigUint::from_slice(v).mul(r).rem(p).to_u32_digits()) }
+        }
+    }
+
+    /// The stack items `push_u32_le` leaves on the stack, e.g. to put the element in a witness.
+    fn witness_u32_le(v: &[u32]) -> Vec<Vec<u8>> {
+        let r = BigUint::from_str_radix(Self::MONTGOMERY_ONE, 16).unwrap();
+        let p = BigUint::from_str_radix(Self::MODULUS, 16).unwrap();
+        U381::witness_u32_le(&BigUint::from_slice(v).mul(r).rem(p).to_u32_digits())
+    }
+
+    #[inline]
+    fn equal(a: u32, b: u32) -> Script { U381::equal(a, b) }
+
+    #[inline]
+    fn equalverify(a: u32, b: u32) -> Script { U381::equalverify(a, b) }
+
+    #[inline]
+    fn push_dec(dec_string: &str) -> Script {
+        let v = BigUint::from_str_radix(dec_string, 10).unwrap();
+        Self::push_u32_le(&v.to_u32_digits())
+    }
+
+    #[inline]
+    fn push_hex(hex_string: &str) -> Script {
+        let v = BigUint::from_str_radix(hex_string, 16).unwrap();
+        Self::push_u32_le(&v.to_u32_digits())
+    }
+
+    #[inline]
+    fn convert_to_be_bits() -> Script { U381::convert_to_be_bits() }
+
+    #[inline]
+    fn convert_to_be_bits_toaltstack() -> Script { U381::convert_to_be_bits_toaltstack() }
+
+    #[inline]
+    fn convert_to_le_bits() -> Script { U381::convert_to_le_bits() }
+
+    #[inline]
+    fn convert_to_le_bits_toaltstack() -> Script { U381::convert_to_le_bits_toaltstack() }
+
+    #[inline]
+    fn push_modulus() -> Script { U381::push_hex(Self::MODULUS) }
+
+    #[inline]
+    fn push_zero() -> Script { U381::push_zero() }
+
+    #[inline]
+    fn push_one() -> Script { U381::push_hex(Self::MONTGOMERY_ONE) }
+
+    fn decode_montgomery() -> Script {
+        script! {
+            // a ⋅ p⁻¹
+            { u29x14_mullo_imm(Self::MODULUS_INV_406) }
+            // ❨a ⋅ p⁻¹❩ ⋅ p
+            { u29x14_mulhi_imm(Self::MODULUS_LIMBS) }
+            // - ❨a ⋅ p⁻¹❩ ⋅ p
+            { Self::neg(0) }
+        }
+    }
+
+    #[inline]
+    fn is_zero(a: u32) -> Script { U381::is_zero(a) }
+
+    #[inline]
+    fn is_zero_keep_element(a: u32) -> Script { U381::is_zero_keep_element(a) }
+
+    #[inline]
+    fn toaltstack() -> Script { U381::toaltstack() }
+
+    #[inline]
+    fn fromaltstack() -> Script { U381::fromaltstack() }
+
+    // Input Stack: [a]
+    // Output Stack: [0 <= a < p]
+    fn is_field() -> Script {
+        script! {
+            // Each limb must not be negative
+            for i in 0..Self::N_LIMBS - 1 {
+                { i } OP_PICK
+                0 OP_GREATERTHANOREQUAL OP_TOALTSTACK
+            }
+            { Self::N_LIMBS - 1 } OP_PICK
+            0 OP_GREATERTHANOREQUAL
+            for _ in 0..Self::N_LIMBS - 1 {
+                OP_FROMALTSTACK OP_BOOLAND
+            }
+            OP_TOALTSTACK
+
+            { Self::push_modulus() }
+            { U381::lessthan(1, 0) }
+
+            OP_FROMALTSTACK OP_BOOLAND
+        }
+    }
+
+    // Subtracts p from the top element if it isn't below p, to reduce a sum below 2p.
+    fn reduce_once() -> Script {
+        script! {
+            { U384::copy(0) }
+            { U384::push_hex(Self::MODULUS) }
+            { U384::greaterthanorequal(1, 0) }
+            OP_IF
+                { U384::push_hex(Self::MODULUS) }
+                { U384::sub(1, 0) }
+            OP_ENDIF
+        }
+    }
+
+    // A + B mod M
+    fn add(a: u32, b: u32) -> Script {
+        script! {
+            { U384::add(a, b) }
+            { Self::reduce_once() }
+        }
+    }
+
+    // M - A, as for `Fp254Impl::neg` the negation of zero is the modulus itself.
+    fn neg(a: u32) -> Script {
+        script! {
+            { Self::roll(a) }
+            { Self::push_modulus() }
+            { U381::sub(0, 1) }
+        }
+    }
+
+    // A - B mod M, as A + (M - B) mod M
+    fn sub(a: u32, b: u32) -> Script {
+        let a = if a > b { a } else { a + 1 };
+        script! {
+            { Self::neg(b) }
+            { Self::add(a, 0) }
+        }
+    }
+
+    fn double(a: u32) -> Script {
+        script! {
+            { U384::double(a) }
+            { Self::reduce_once() }
+        }
+    }
+
+    // Montgomery multiplication, as `Fp254Impl::mul`: with (A⋅B)₈₁₁…₄₀₆ = h and
+    // (A⋅B)₄₀₅…₀ = l, q = l⋅p⁻¹ mod 2⁴⁰⁶ makes l - q⋅p a multiple of 2⁴⁰⁶, so
+    // A⋅B⋅2⁻⁴⁰⁶ = h - (q⋅p)₈₁₁…₄₀₆ mod p.
+    // Input Stack: [a, b]
+    // Output Stack: [a * b]
+    fn mul() -> Script {
+        script! {
+            { u29x14_mul() }
+            // ⋯ (A⋅B)₈₁₁…₄₀₆ (A⋅B)₄₀₅…₀
+            { u29x14_mullo_imm(Self::MODULUS_INV_406) }
+            // ⋯ (A⋅B)₈₁₁…₄₀₆ ❨(A⋅B)₄₀₅…₀⋅p⁻¹❩₄₀₅…₀
+            { u29x14_mulhi_imm(Self::MODULUS_LIMBS) }
+            // ⋯ (A⋅B)₈₁₁…₄₀₆ ❨❨(A⋅B)₄₀₅…₀⋅p⁻¹❩₄₀₅…₀⋅p❩₈₁₁…₄₀₆
+            { Self::sub(1, 0) }
+        }
+    }
+
+    fn square() -> Script {
+        script! {
+            { Self::copy(0) }
+            { Self::mul() }
+        }
+    }
+
+    fn mul_by_constant(constant: &Self::ConstantType) -> Script {
+        // the constant in Montgomery form, as 29-bit limbs
+        let constant = BigUint::from_bytes_be(&constant.into_bigint().to_bytes_be())
+            .mul(BigUint::from_str_radix(Self::MONTGOMERY_ONE, 16).unwrap())
+            .rem(BigUint::from_str_radix(Self::MODULUS, 16).unwrap());
+        let mut u29x14_montgomery = [0u32; 14];
+        for (i, limb) in u29x14_montgomery.iter_mut().enumerate() {
+            *limb = constant
+                .clone()
+                .div(BigUint::one().shl(29 * i) as BigUint)
+                .rem(BigUint::one().shl(29) as BigUint)
+                .to_u32_digits()
+                .first()
+                .copied()
+                .unwrap_or(0);
+        }
+
+        script! {
+            { u29x14_mul_imm(u29x14_montgomery) }
+            { u29x14_mullo_imm(Self::MODULUS_INV_406) }
+            { u29x14_mulhi_imm(Self::MODULUS_LIMBS) }
+            { Self::sub(1, 0) }
+        }
+    }
+
+    // `inv_stage1` leaves an almost inverse s and its exponent k, `inv_stage2` replaces k by the
+    // power of 2⁻¹ that turns s into the inverse of the Montgomery form a⋅R, R = 2⁴⁰⁶. The
+    // Montgomery multiplication leaves a⁻¹⋅R⁻², which R³ turns into the Montgomery form a⁻¹⋅R.
+    fn inv() -> Script {
+        let r = BigUint::from_str_radix(Self::MONTGOMERY_ONE, 16).unwrap();
+        let p = BigUint::from_str_radix(Self::MODULUS, 16).unwrap();
+        script! {
+            { Self::push_modulus() }
+            { Self::roll(1) }
+            { U381::inv_stage1() }
+            { U381::inv_stage2(Self::MODULUS) }
+            { Self::mul() }
+            { Self::mul_by_constant(&Self::ConstantType::from(r.pow(3).rem(p))) }
+        }
+    }
+}
@@ -0,0 +1,187 @@
+use crate::bigint::U381;
+use crate::bls12_381::fp381impl::Fp381Impl;
+
+pub struct Fq;
+
+impl Fp381Impl for Fq {
+    const MODULUS: &'static str = "1a0111ea397fe69a4b1ba7b6434bacd764774b84f38512bf6730d2a0f6b0f6241eabfffeb153ffffb9feffffffffaaab";
+
+    // 2⁴⁰⁶ mod p
+    const MONTGOMERY_ONE: &'static str = "13317c30f3a0d636d56a23c34fdd80b891ecbde7c2b7d6e16b0f4b0b7e6d26cb6147acde629c4a23c57400d203a9fb84";
+
+    const MODULUS_LIMBS: [u32; U381::N_LIMBS as usize] = [
+        0x1fffaaab, 0xff7ffff, 0x14ffffee, 0x17fffd62, 0xf6241ea, 0x9507b58, 0xafd9cc3, 0x109e70a2,
+        0x1764774b, 0x121a5d66, 0x12c6e9ed, 0x12ffcd34, 0x111ea3, 0xd,
+    ];
+
+    // inv₄₀₆ p  <=>  0x1d85f314f9ef90155036b974ce9011d9730a7e61335f1714d24b3e910d10f371cf4b7d795246d262eec17760c000300030003
+    const MODULUS_INV_406: [u32; U381::N_LIMBS as usize] = [
+        0x30003, 0x10600018, 0xbbb05dd, 0xa48da4c, 0xf4b7d79, 0x8879b8e, 0x12cfa443, 0x1e2e29a4,
+        0x7e61335, 0x8ecb985, 0x5d33a40, 0x2aa06d7, 0x14f9ef90, 0xec2f98,
+    ];
+
+    type ConstantType = ark_bls12_381::Fq;
+}
+
+#[cfg(test)]
+mod test {
+    use crate::bls12_381::fp381impl::Fp381Impl;
+    use crate::bls12_381::fq::Fq;
+    use crate::bls12_381::utils::fq_witness;
+    use crate::bn254::differential::{check_gadget, Case, Limits};
+    use crate::treepp::*;
+    use ark_ff::Field;
+    use ark_std::UniformRand;
+    use core::ops::{Add, Mul, Rem, Sub};
+    use num_bigint::{BigUint, RandomBits};
+    use num_traits::Num;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha20Rng;
+
+    #[test]
+    fn test_bls12_381_fq_add_sub() {
+        println!("Fq.add: {} bytes", Fq::add(1, 0).len());
+        println!("Fq.sub: {} bytes", Fq::sub(1, 0).len());
+
+        let m = BigUint::from_str_radix(Fq::MODULUS, 16).unwrap();
+        let mut prng = ChaCha20Rng::seed_from_u64(0);
+
+        for _ in 0..20 {
+            let a: BigUint = prng.sample::<BigUint, _>(RandomBits::new(381)).rem(&m);
+            let b: BigUint = prng.sample::<BigUint, _>(RandomBits::new(381)).rem(&m);
+            let c = a.clone().add(&b).rem(&m);
+            let d = a.clone().add(&m).sub(&b).rem(&m);
+            let e = b.clone().add(&m).sub(&a).rem(&m);
+
+            let script = script! {
+                { Fq::push_u32_le(&a.to_u32_digits()) }
+                { Fq::push_u32_le(&b.to_u32_digits()) }
+                { Fq::copy(1) }
+                { Fq::copy(1) }
+                { Fq::add(1, 0) }
+                { Fq::push_u32_le(&c.to_u32_digits()) }
+                { Fq::equalverify(1, 0) }
+                { Fq::copy(1) }
+                { Fq::copy(1) }
+                { Fq::sub(1, 0) }
+                { Fq::push_u32_le(&d.to_u32_digits()) }
+                { Fq::equalverify(1, 0) }
+                { Fq::sub(0, 1) }
+                { Fq::push_u32_le(&e.to_u32_digits()) }
+                { Fq::equalverify(1, 0) }
+                OP_TRUE
+            };
+            let exec_result = execute_script(script);
+            assert!(exec_result.success);
+        }
+    }
+
+    #[test]
+    fn test_bls12_381_fq_double_neg() {
+        let mut prng = ChaCha20Rng::seed_from_u64(0);
+
+        for _ in 0..20 {
+            let a = ark_bls12_381::Fq::rand(&mut prng);
+            let b = a.add(&a);
+            let c = -a;
+
+            let script = script! {
+                { Fq::push_u32_le(&BigUint::from(a).to_u32_digits()) }
+                { Fq::copy(0) }
+                { Fq::double(0) }
+                { Fq::push_u32_le(&BigUint::from(b).to_u32_digits()) }
+                { Fq::equalverify(1, 0) }
+                { Fq::neg(0) }
+                { Fq::push_u32_le(&BigUint::from(c).to_u32_digits()) }
+                { Fq::equalverify(1, 0) }
+                OP_TRUE
+            };
+            let exec_result = execute_script(script);
+            assert!(exec_result.success);
+        }
+    }
+
+    #[test]
+    fn test_bls12_381_fq_mul() {
+        println!("Fq.mul: {} bytes", Fq::mul().len());
+        let mut prng = ChaCha20Rng::seed_from_u64(0);
+
+        for _ in 0..3 {
+            let a = ark_bls12_381::Fq::rand(&mut prng);
+            let b = ark_bls12_381::Fq::rand(&mut prng);
+            let c = a.mul(&b);
+
+            let script = script! {
+                { Fq::push_u32_le(&BigUint::from(a).to_u32_digits()) }
+                { Fq::push_u32_le(&BigUint::from(b).to_u32_digits()) }
+                { Fq::mul() }
+                { Fq::push_u32_le(&BigUint::from(c).to_u32_digits()) }
+                { Fq::equalverify(1, 0) }
+                { Fq::push_u32_le(&BigUint::from(a).to_u32_digits()) }
+                { Fq::mul_by_constant(&b) }
+                { Fq::push_u32_le(&BigUint::from(c).to_u32_digits()) }
+                { Fq::equalverify(1, 0) }
+                OP_TRUE
+            };
+            let exec_result = execute_script(script);
+            assert!(exec_result.success);
+        }
+    }
+
+    // Measured size and stack maximum of the Montgomery multiplication, plus a 1% margin on the
+    // size and 10 stack items.
+    const FQ_MUL_LIMITS: Limits = Limits {
+        script_size: 330_500,
+        max_stack_items: 132,
+    };
+
+    #[test]
+    fn test_bls12_381_fq_mul_differential() {
+        let report = check_gadget("Fq::mul", Fq::mul(), FQ_MUL_LIMITS, 0, 10, |prng| {
+            let a = ark_bls12_381::Fq::rand(prng);
+            let b = ark_bls12_381::Fq::rand(prng);
+            Case {
+                inputs: [fq_witness(a), fq_witness(b)].concat(),
+                outputs: fq_witness(a * b),
+            }
+        });
+        println!("{}", report);
+    }
+
+    #[test]
+    fn test_bls12_381_fq_inv() {
+        println!("Fq.inv: {} bytes", Fq::inv().len());
+        let mut prng = ChaCha20Rng::seed_from_u64(0);
+
+        for _ in 0..1 {
+            let a = ark_bls12_381::Fq::rand(&mut prng);
+            let c = a.inverse().unwrap();
+
+            let script = script! {
+                { Fq::push_u32_le(&BigUint::from(a).to_u32_digits()) }
+                { Fq::inv() }
+                { Fq::push_u32_le(&BigUint::from(c).to_u32_digits()) }
+                { Fq::equalverify(1, 0) }
+                OP_TRUE
+            };
+            let exec_result = execute_script(script);
+            assert!(exec_result.success);
+        }
+    }
+
+    #[test]
+    fn test_bls12_381_fq_is_field() {
+        let m = BigUint::from_str_radix(Fq::MODULUS, 16).unwrap();
+
+        let script = script! {
+            { Fq::push_u32_le(&m.clone().sub(1u32).to_u32_digits()) }
+            { Fq::is_field() }
+            OP_VERIFY
+            { crate::bigint::U381::push_hex(Fq::MODULUS) }
+            { Fq::is_field() }
+            OP_NOT
+        };
+        let exec_result = execute_script(script);
+        assert!(exec_result.success);
+    }
+}
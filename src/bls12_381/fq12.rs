@@ -0,0 +1,480 @@
+use crate::bls12_381::base_field::BaseField;
+use crate::bls12_381::fq::Fq;
+use crate::bls12_381::fq2::GenericFq2;
+use crate::bls12_381::fq6::GenericFq6;
+use crate::treepp::{script, Script};
+use ark_ff::Fp12Config;
+use std::marker::PhantomData;
+
+/// Fq12 = Fq6[w] / (w² - v) over the base field `F`, two `GenericFq6` on the stack, c0 below c1.
+pub struct GenericFq12<F: BaseField>(PhantomData<F>);
+
+pub type Fq12 = GenericFq12<Fq>;
+
+impl<F: BaseField> GenericFq12<F> {
+    pub fn add(mut a: u32, mut b: u32) -> Script {
+        if a < b {
+            (a, b) = (b, a);
+        }
+        script! {
+            { GenericFq6::<F>::add(a + 6, b + 6) }
+            { GenericFq6::<F>::add(a, b + 6) }
+        }
+    }
+
+    pub fn sub(a: u32, b: u32) -> Script {
+        if a > b {
+            script! {
+                { GenericFq6::<F>::sub(a + 6, b + 6) }
+                { GenericFq6::<F>::sub(a, b + 6) }
+            }
+        } else {
+            script! {
+                { GenericFq6::<F>::sub(a + 6, b + 6) }
+                { GenericFq6::<F>::sub(a + 6, b) }
+            }
+        }
+    }
+
+    pub fn double(a: u32) -> Script {
+        script! {
+            { GenericFq6::<F>::double(a + 6) }
+            { GenericFq6::<F>::double(a + 6) }
+        }
+    }
+
+    pub fn equalverify() -> Script {
+        script! {
+            for i in 0..12 {
+                { F::equalverify(23 - i * 2, 11 - i) }
+            }
+        }
+    }
+
+    pub fn mul_fq6_by_nonresidue() -> Script {
+        script! {
+            { GenericFq6::<F>::mul_fq2_by_nonresidue() }
+            { GenericFq2::<F>::roll(4) }
+            { GenericFq2::<F>::roll(4) }
+        }
+    }
+
+    pub fn push_one() -> Script {
+        script! {
+            { GenericFq6::<F>::push_one() }
+            { GenericFq6::<F>::push_zero() }
+        }
+    }
+
+    pub fn push_zero() -> Script {
+        script! {
+            { GenericFq6::<F>::push_zero() }
+            { GenericFq6::<F>::push_zero() }
+        }
+    }
+
+    pub fn mul(mut a: u32, mut b: u32) -> Script {
+        if a < b {
+            (a, b) = (b, a);
+        }
+
+        // The degree-12 extension on Fq6 is under the polynomial z^2 - y
+
+        script! {
+            { GenericFq6::<F>::copy(a + 6) }
+            { GenericFq6::<F>::copy(b + 12) }
+            { GenericFq6::<F>::mul(6, 0) }
+            { GenericFq6::<F>::copy(a + 6) }
+            { GenericFq6::<F>::copy(b + 12) }
+            { GenericFq6::<F>::mul(6, 0) }
+            { GenericFq6::<F>::add(a + 12, a + 18) }
+            { GenericFq6::<F>::add(b + 18, b + 24) }
+            { GenericFq6::<F>::mul(6, 0) }
+            { GenericFq6::<F>::copy(12) }
+            { GenericFq6::<F>::copy(12) }
+            { Self::mul_fq6_by_nonresidue() }
+            { GenericFq6::<F>::add(6, 0) }
+            { GenericFq6::<F>::add(18, 12)}
+            { GenericFq6::<F>::sub(12, 0) }
+        }
+    }
+
+    // Multiplies by a line of the miller loop, (c0 + c1 * v) + v * w where c0, c1 are Fq2, the
+    // sparse element of `mul_by_014` of arkworks with c4 = 1.
+    //
+    // input:
+    //    p   (12 elements)
+    //    c0  (2 elements)
+    //    c1  (2 elements)
+    pub fn mul_by_01() -> Script {
+        script! {
+            // copy p.c0, c0, c1
+            { GenericFq6::<F>::copy(10) }
+            { GenericFq2::<F>::copy(8) }
+            { GenericFq2::<F>::copy(8) }
+            // [p, c0, c1, p.c0, c0, c1]
+
+            // compute a = p.c0 * (c0, c1)
+            { GenericFq6::<F>::mul_by_01() }
+            // [p, c0, c1, a]
+
+            // compute b = p.c1 * v
+            { GenericFq6::<F>::copy(10) }
+            { Self::mul_fq6_by_nonresidue() }
+            // [p, c0, c1, a, b]
+
+            // compute final c0 = a + v * b
+            { GenericFq6::<F>::copy(0) }
+            { Self::mul_fq6_by_nonresidue() }
+            { GenericFq6::<F>::copy(12) }
+            { GenericFq6::<F>::add(6, 0) }
+            // [p, c0, c1, a, b, c0']
+
+            // compute e = p.c0 + p.c1
+            { GenericFq6::<F>::add(28, 22) }
+            // [c0, c1, a, b, c0', e]
+
+            // compute c1 + 1
+            { GenericFq2::<F>::roll(26) }
+            { GenericFq2::<F>::roll(26) }
+            { GenericFq2::<F>::push_one() }
+            { GenericFq2::<F>::add(2, 0) }
+            // [a, b, c0', e, c0, c1 + 1]
+
+            // update e = e * (c0, c1 + 1)
+            { GenericFq6::<F>::mul_by_01() }
+            // [a, b, c0', e]
+
+            // sum a and b
+            { GenericFq6::<F>::add(18, 12) }
+            // [c0', e, a + b]
+
+            // compute final c1 = e - (a + b)
+            { GenericFq6::<F>::sub(6, 0) }
+        }
+    }
+
+    // input:
+    //    p   (12 elements)
+    //    c   (6 elements)
+    pub fn mul_by_fq6() -> Script {
+        script! {
+            { GenericFq6::<F>::copy(0) }
+            { GenericFq6::<F>::roll(18) }
+            { GenericFq6::<F>::mul(6, 0) }
+            // [p.c1, c, p.c0 * c]
+            { GenericFq6::<F>::roll(12) }
+            { GenericFq6::<F>::roll(12) }
+            { GenericFq6::<F>::mul(6, 0) }
+            // [p.c0 * c, p.c1 * c]
+        }
+    }
+
+    pub fn copy(a: u32) -> Script {
+        script! {
+            { GenericFq6::<F>::copy(a + 6) }
+            { GenericFq6::<F>::copy(a + 6) }
+        }
+    }
+
+    pub fn roll(a: u32) -> Script {
+        script! {
+            { GenericFq6::<F>::roll(a + 6) }
+            { GenericFq6::<F>::roll(a + 6) }
+        }
+    }
+
+    pub fn square() -> Script {
+        script! {
+            // v0 = c0 + c1
+            { GenericFq6::<F>::copy(6) }
+            { GenericFq6::<F>::copy(6) }
+            { GenericFq6::<F>::add(6, 0) }
+
+            // v3 = c0 + beta * c1
+            { GenericFq6::<F>::copy(6) }
+            { Self::mul_fq6_by_nonresidue() }
+            { GenericFq6::<F>::copy(18) }
+            { GenericFq6::<F>::add(0, 6) }
+
+            // v2 = c0 * c1
+            { GenericFq6::<F>::mul(12, 18) }
+
+            // v0 = v0 * v3
+            { GenericFq6::<F>::mul(12, 6) }
+
+            // final c0 = v0 - (beta + 1) * v2
+            { GenericFq6::<F>::copy(6) }
+            { Self::mul_fq6_by_nonresidue() }
+            { GenericFq6::<F>::copy(12) }
+            { GenericFq6::<F>::add(6, 0) }
+            { GenericFq6::<F>::sub(6, 0) }
+
+            // final c1 = 2 * v2
+            { GenericFq6::<F>::double(6) }
+        }
+    }
+
+    pub fn cyclotomic_inverse() -> Script {
+        script! {
+            { GenericFq6::<F>::neg(0) }
+        }
+    }
+
+    pub fn inv() -> Script {
+        script! {
+            // copy c1
+            { GenericFq6::<F>::copy(0) }
+
+            // compute beta * v1 = beta * c1^2
+            { GenericFq6::<F>::square() }
+            { Self::mul_fq6_by_nonresidue() }
+
+            // copy c0
+            { GenericFq6::<F>::copy(12) }
+
+            // compute v0 = c0^2 + beta * v1
+            { GenericFq6::<F>::square() }
+            { GenericFq6::<F>::sub(0, 6) }
+
+            // compute inv v0
+            { GenericFq6::<F>::inv() }
+
+            // dup inv v0
+            { GenericFq6::<F>::copy(0) }
+
+            // compute c0
+            { GenericFq6::<F>::mul(18, 0) }
+
+            // compute c1
+            { GenericFq6::<F>::neg(12) }
+            { GenericFq6::<F>::mul(12, 0) }
+        }
+    }
+
+    pub fn frobenius_map(i: usize) -> Script {
+        script! {
+            { GenericFq6::<F>::roll(6) }
+            { GenericFq6::<F>::frobenius_map(i) }
+            { GenericFq6::<F>::roll(6) }
+            { GenericFq6::<F>::frobenius_map(i) }
+            { GenericFq6::<F>::mul_by_fp2_constant(&F::Fp12Config::FROBENIUS_COEFF_FP12_C1[i % F::Fp12Config::FROBENIUS_COEFF_FP12_C1.len()]) }
+        }
+    }
+
+    pub fn toaltstack() -> Script {
+        script! {
+            { GenericFq6::<F>::toaltstack() }
+            { GenericFq6::<F>::toaltstack() }
+        }
+    }
+
+    pub fn fromaltstack() -> Script {
+        script! {
+            { GenericFq6::<F>::fromaltstack() }
+            { GenericFq6::<F>::fromaltstack() }
+        }
+    }
+
+    pub fn drop() -> Script {
+        script! {
+            { GenericFq6::<F>::drop() }
+            { GenericFq6::<F>::drop() }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::bls12_381::fq12::{Fq12, GenericFq12};
+    use crate::bls12_381::utils::{fq12_push, fq2_push, fq6_push};
+    use crate::execute_script_without_stack_limit;
+    use crate::treepp::*;
+    use ark_ff::{AdditiveGroup, Field};
+    use ark_std::UniformRand;
+    use core::ops::{Add, Mul};
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+
+    #[test]
+    fn test_bls12_381_fq12_add() {
+        let mut prng = ChaCha20Rng::seed_from_u64(0);
+
+        for _ in 0..3 {
+            let a = ark_bls12_381::Fq12::rand(&mut prng);
+            let b = ark_bls12_381::Fq12::rand(&mut prng);
+            let c = a.add(&b);
+
+            let script = script! {
+                { fq12_push(a) }
+                { fq12_push(b) }
+                { Fq12::add(12, 0) }
+                { fq12_push(c) }
+                { Fq12::equalverify() }
+                OP_TRUE
+            };
+            let exec_result = execute_script(script);
+            assert!(exec_result.success);
+        }
+    }
+
+    #[test]
+    fn test_bls12_381_fq12_mul() {
+        println!("Fq12.mul: {} bytes", Fq12::mul(12, 0).len());
+        let mut prng = ChaCha20Rng::seed_from_u64(0);
+
+        for _ in 0..1 {
+            let a = ark_bls12_381::Fq12::rand(&mut prng);
+            let b = ark_bls12_381::Fq12::rand(&mut prng);
+            let c = a.mul(&b);
+
+            let script = script! {
+                { fq12_push(a) }
+                { fq12_push(b) }
+                { Fq12::mul(12, 0) }
+                { fq12_push(c) }
+                { Fq12::equalverify() }
+                OP_TRUE
+            };
+            // the two Fq12 and the products of their halves exceed 1000 stack items
+            let exec_result = execute_script_without_stack_limit(script);
+            assert!(exec_result.success);
+        }
+    }
+
+    // The same tower over the BN254 base field, with beta = 9 + u.
+    #[test]
+    fn test_bn254_generic_fq12_mul_and_frobenius_map() {
+        type Bn254Fq12 = GenericFq12<crate::bn254::fq::Fq>;
+        let mut prng = ChaCha20Rng::seed_from_u64(0);
+
+        let a = ark_bn254::Fq12::rand(&mut prng);
+        let b = ark_bn254::Fq12::rand(&mut prng);
+
+        let script = script! {
+            { crate::bn254::utils::fq12_push(a) }
+            { crate::bn254::utils::fq12_push(b) }
+            { Bn254Fq12::mul(12, 0) }
+            { crate::bn254::utils::fq12_push(a.mul(&b)) }
+            { Bn254Fq12::equalverify() }
+            { crate::bn254::utils::fq12_push(a) }
+            { Bn254Fq12::frobenius_map(1) }
+            { crate::bn254::utils::fq12_push(a.frobenius_map(1)) }
+            { Bn254Fq12::equalverify() }
+            OP_TRUE
+        };
+        let exec_result = execute_script_without_stack_limit(script);
+        assert!(exec_result.success);
+    }
+
+    #[test]
+    fn test_bls12_381_fq12_mul_by_01() {
+        println!("Fq12.mul_by_01: {} bytes", Fq12::mul_by_01().len());
+        let mut prng = ChaCha20Rng::seed_from_u64(0);
+
+        for _ in 0..1 {
+            let a = ark_bls12_381::Fq12::rand(&mut prng);
+            let c0 = ark_bls12_381::Fq2::rand(&mut prng);
+            let c1 = ark_bls12_381::Fq2::rand(&mut prng);
+            let mut b = a;
+            b.mul_by_014(&c0, &c1, &ark_bls12_381::Fq2::ONE);
+
+            let script = script! {
+                { fq12_push(a) }
+                { fq2_push(c0) }
+                { fq2_push(c1) }
+                { Fq12::mul_by_01() }
+                { fq12_push(b) }
+                { Fq12::equalverify() }
+                OP_TRUE
+            };
+            let exec_result = execute_script(script);
+            assert!(exec_result.success);
+        }
+    }
+
+    #[test]
+    fn test_bls12_381_fq12_mul_by_fq6() {
+        println!("Fq12.mul_by_fq6: {} bytes", Fq12::mul_by_fq6().len());
+        let mut prng = ChaCha20Rng::seed_from_u64(0);
+
+        for _ in 0..1 {
+            let a = ark_bls12_381::Fq12::rand(&mut prng);
+            let c = ark_bls12_381::Fq6::rand(&mut prng);
+            let b = a.mul(&ark_bls12_381::Fq12::new(c, ark_bls12_381::Fq6::ZERO));
+
+            let script = script! {
+                { fq12_push(a) }
+                { fq6_push(c) }
+                { Fq12::mul_by_fq6() }
+                { fq12_push(b) }
+                { Fq12::equalverify() }
+                OP_TRUE
+            };
+            let exec_result = execute_script(script);
+            assert!(exec_result.success);
+        }
+    }
+
+    #[test]
+    fn test_bls12_381_fq12_square() {
+        println!("Fq12.square: {} bytes", Fq12::square().len());
+        let mut prng = ChaCha20Rng::seed_from_u64(0);
+
+        for _ in 0..1 {
+            let a = ark_bls12_381::Fq12::rand(&mut prng);
+            let c = a.square();
+
+            let script = script! {
+                { fq12_push(a) }
+                { Fq12::square() }
+                { fq12_push(c) }
+                { Fq12::equalverify() }
+                OP_TRUE
+            };
+            let exec_result = execute_script(script);
+            assert!(exec_result.success);
+        }
+    }
+
+    #[test]
+    fn test_bls12_381_fq12_inv() {
+        println!("Fq12.inv: {} bytes", Fq12::inv().len());
+        let mut prng = ChaCha20Rng::seed_from_u64(0);
+
+        for _ in 0..1 {
+            let a = ark_bls12_381::Fq12::rand(&mut prng);
+            let c = a.inverse().unwrap();
+
+            let script = script! {
+                { fq12_push(a) }
+                { Fq12::inv() }
+                { fq12_push(c) }
+                { Fq12::equalverify() }
+                OP_TRUE
+            };
+            let exec_result = execute_script_without_stack_limit(script);
+            assert!(exec_result.success);
+        }
+    }
+
+    #[test]
+    fn test_bls12_381_fq12_frobenius_map() {
+        let mut prng = ChaCha20Rng::seed_from_u64(0);
+
+        for i in 0..3 {
+            let a = ark_bls12_381::Fq12::rand(&mut prng);
+            let b = a.frobenius_map(i);
+
+            let script = script! {
+                { fq12_push(a) }
+                { Fq12::frobenius_map(i) }
+                { fq12_push(b) }
+                { Fq12::equalverify() }
+                OP_TRUE
+            };
+            let exec_result = execute_script(script);
+            assert!(exec_result.success);
+        }
+    }
+}
@@ -0,0 +1,339 @@
+use crate::bls12_381::base_field::BaseField;
+use crate::bls12_381::fq::Fq;
+use crate::treepp::{script, Script};
+use ark_ff::{Fp2, Fp2Config};
+use std::marker::PhantomData;
+use std::ops::Add;
+
+/// Fq2 = Fq[u] / (u² + 1) over the base field `F`, two elements on the stack, c0 below c1.
+pub struct GenericFq2<F: BaseField>(PhantomData<F>);
+
+pub type Fq2 = GenericFq2<Fq>;
+
+impl<F: BaseField> GenericFq2<F> {
+    pub fn add(mut a: u32, mut b: u32) -> Script {
+        if a < b {
+            (a, b) = (b, a);
+        }
+
+        script! {
+            { F::add(a + 1, b + 1) }
+            { F::add(a, b + 1) }
+        }
+    }
+
+    pub fn sub(a: u32, b: u32) -> Script {
+        if a > b {
+            script! {
+                { F::sub(a + 1, b + 1) }
+                { F::sub(a, b + 1) }
+            }
+        } else {
+            script! {
+                { F::sub(a + 1, b + 1) }
+                { F::sub(a + 1, b) }
+            }
+        }
+    }
+
+    pub fn double(a: u32) -> Script {
+        script! {
+            { F::double(a + 1) }
+            { F::double(a + 1) }
+        }
+    }
+
+    /// Square the top Fq2 element
+    ///
+    /// Optimized by: @Hakkush-07
+    pub fn square() -> Script {
+        script! {
+            { F::copy(1) }
+            { F::copy(1) }
+            { F::copy(1) }
+            { F::copy(1) }
+            { F::mul() }
+            { F::double(0) }
+            { F::sub(2, 1) }
+            { F::add(3, 2) }
+            { F::mul() }
+            { F::roll(1) }
+        }
+    }
+
+    pub fn copy(a: u32) -> Script {
+        script! {
+            { F::copy(a + 1) }
+            { F::copy(a + 1) }
+        }
+    }
+
+    pub fn equalverify() -> Script {
+        script! {
+            { F::equalverify(3, 1) }
+            { F::equalverify(1, 0) }
+        }
+    }
+
+    pub fn roll(a: u32) -> Script {
+        script! {
+            { F::roll(a + 1) }
+            { F::roll(a + 1) }
+        }
+    }
+
+    pub fn mul(mut a: u32, mut b: u32) -> Script {
+        if a < b {
+            (a, b) = (b, a);
+        }
+
+        // The degree-2 extension on Fq is under the polynomial x^2 + 1
+        script! {
+            { F::copy(a + 1) }
+            { F::copy(b + 1 + 1) }
+            { F::mul() }
+            { F::copy(a + 1) }
+            { F::copy(b + 1 + 1) }
+            { F::mul() }
+            { F::add(a + 2, a + 3) }
+            { F::add(b + 3, b + 4) }
+            { F::mul() }
+            { F::copy(2) }
+            { F::copy(2) }
+            { F::sub(1, 0) }
+            { F::add(3, 2) }
+            { F::sub(2, 0) }
+        }
+    }
+
+    pub fn mul_by_fq(mut a: u32, b: u32) -> Script {
+        if a < b {
+            a += 1;
+        }
+
+        script! {
+            { F::copy(b) }
+            { F::roll(a + 2) }
+
+            { F::mul() }
+            { F::roll(b + 1) }
+            { F::roll(a + 1) }
+
+            { F::mul() }
+        }
+    }
+
+    pub fn push_one() -> Script {
+        script! {
+            { F::push_one() }
+            { F::push_zero() }
+        }
+    }
+
+    pub fn push_zero() -> Script {
+        script! {
+            { F::push_zero() }
+            { F::push_zero() }
+        }
+    }
+
+    pub fn neg(a: u32) -> Script {
+        script! {
+            { F::neg(a + 1) }
+            { F::neg(a + 1) }
+        }
+    }
+
+    pub fn inv() -> Script {
+        script! {
+            // copy c1
+            { F::copy(0) }
+
+            // compute v1 = c1^2
+            { F::square() }
+
+            // copy c0
+            { F::copy(2) }
+
+            // compute v0 = c0^2 + v1
+            { F::square() }
+            { F::add(1, 0) }
+
+            // compute inv v0
+            { F::inv() }
+
+            // dup inv v0
+            { F::copy(0) }
+
+            // compute c0
+            { F::roll(3) }
+            { F::mul() }
+
+            // compute c1
+            { F::roll(2) }
+            { F::roll(2) }
+            { F::mul() }
+            { F::neg(0) }
+        }
+    }
+
+    pub fn frobenius_map(i: usize) -> Script {
+        script! {
+            { F::mul_by_constant(&F::Fp2Config::FROBENIUS_COEFF_FP2_C1[i % F::Fp2Config::FROBENIUS_COEFF_FP2_C1.len()]) }
+        }
+    }
+
+    pub fn mul_by_constant(constant: &Fp2<F::Fp2Config>) -> Script {
+        script! {
+            { F::copy(1) }
+            { F::mul_by_constant(&constant.c0) }
+            { F::copy(1) }
+            { F::mul_by_constant(&constant.c1) }
+            { F::add(3, 2) }
+            { F::mul_by_constant(&constant.c0.add(constant.c1)) }
+            { F::copy(2) }
+            { F::copy(2) }
+            { F::add(1, 0) }
+            { F::sub(1, 0) }
+            { F::sub(2, 1) }
+            { F::roll(1) }
+        }
+    }
+
+    pub fn toaltstack() -> Script {
+        script! {
+            { F::toaltstack() }
+            { F::toaltstack() }
+        }
+    }
+
+    pub fn fromaltstack() -> Script {
+        script! {
+            { F::fromaltstack() }
+            { F::fromaltstack() }
+        }
+    }
+
+    pub fn drop() -> Script {
+        script! {
+            { F::drop() }
+            { F::drop() }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::bls12_381::fq2::Fq2;
+    use crate::bls12_381::utils::fq2_push;
+    use crate::treepp::*;
+    use ark_ff::Field;
+    use ark_std::UniformRand;
+    use core::ops::{Add, Mul, Sub};
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+
+    #[test]
+    fn test_bls12_381_fq2_add_sub() {
+        println!("Fq2.add: {} bytes", Fq2::add(2, 0).len());
+        println!("Fq2.sub: {} bytes", Fq2::sub(2, 0).len());
+        let mut prng = ChaCha20Rng::seed_from_u64(0);
+
+        for _ in 0..10 {
+            let a = ark_bls12_381::Fq2::rand(&mut prng);
+            let b = ark_bls12_381::Fq2::rand(&mut prng);
+
+            let script = script! {
+                { fq2_push(a) }
+                { fq2_push(b) }
+                { Fq2::copy(2) }
+                { Fq2::copy(2) }
+                { Fq2::add(2, 0) }
+                { fq2_push(a.add(&b)) }
+                { Fq2::equalverify() }
+                { Fq2::copy(2) }
+                { Fq2::copy(2) }
+                { Fq2::sub(2, 0) }
+                { fq2_push(a.sub(&b)) }
+                { Fq2::equalverify() }
+                { Fq2::sub(0, 2) }
+                { fq2_push(b.sub(&a)) }
+                { Fq2::equalverify() }
+                OP_TRUE
+            };
+            let exec_result = execute_script(script);
+            assert!(exec_result.success);
+        }
+    }
+
+    #[test]
+    fn test_bls12_381_fq2_mul() {
+        println!("Fq2.mul: {} bytes", Fq2::mul(2, 0).len());
+        println!("Fq2.square: {} bytes", Fq2::square().len());
+        let mut prng = ChaCha20Rng::seed_from_u64(0);
+
+        for _ in 0..1 {
+            let a = ark_bls12_381::Fq2::rand(&mut prng);
+            let b = ark_bls12_381::Fq2::rand(&mut prng);
+
+            let script = script! {
+                { fq2_push(a) }
+                { fq2_push(b) }
+                { Fq2::mul(2, 0) }
+                { fq2_push(a.mul(&b)) }
+                { Fq2::equalverify() }
+                { fq2_push(a) }
+                { Fq2::square() }
+                { fq2_push(a.square()) }
+                { Fq2::equalverify() }
+                { fq2_push(a) }
+                { Fq2::mul_by_constant(&b) }
+                { fq2_push(a.mul(&b)) }
+                { Fq2::equalverify() }
+                OP_TRUE
+            };
+            let exec_result = execute_script(script);
+            assert!(exec_result.success);
+        }
+    }
+
+    #[test]
+    fn test_bls12_381_fq2_inv() {
+        println!("Fq2.inv: {} bytes", Fq2::inv().len());
+        let mut prng = ChaCha20Rng::seed_from_u64(0);
+
+        for _ in 0..1 {
+            let a = ark_bls12_381::Fq2::rand(&mut prng);
+
+            let script = script! {
+                { fq2_push(a) }
+                { Fq2::inv() }
+                { fq2_push(a.inverse().unwrap()) }
+                { Fq2::equalverify() }
+                OP_TRUE
+            };
+            let exec_result = execute_script(script);
+            assert!(exec_result.success);
+        }
+    }
+
+    #[test]
+    fn test_bls12_381_fq2_frobenius_map() {
+        let mut prng = ChaCha20Rng::seed_from_u64(0);
+
+        for i in 0..2 {
+            let a = ark_bls12_381::Fq2::rand(&mut prng);
+            let b = a.frobenius_map(i);
+
+            let script = script! {
+                { fq2_push(a) }
+                { Fq2::frobenius_map(i) }
+                { fq2_push(b) }
+                { Fq2::equalverify() }
+                OP_TRUE
+            };
+            let exec_result = execute_script(script);
+            assert!(exec_result.success);
+        }
+    }
+}
@@ -0,0 +1,542 @@
+use crate::bls12_381::base_field::BaseField;
+use crate::bls12_381::fq::Fq;
+use crate::bls12_381::fq2::GenericFq2;
+use crate::treepp::{script, Script};
+use ark_ff::{Field, Fp2, Fp6Config};
+use std::marker::PhantomData;
+
+/// Fq6 = Fq2[v] / (v³ - β) over the base field `F`, three `GenericFq2` on the stack, c0 at the
+/// bottom.
+pub struct GenericFq6<F: BaseField>(PhantomData<F>);
+
+pub type Fq6 = GenericFq6<Fq>;
+
+impl<F: BaseField> GenericFq6<F> {
+    pub fn add(mut a: u32, mut b: u32) -> Script {
+        if a < b {
+            (a, b) = (b, a);
+        }
+        script! {
+            { GenericFq2::<F>::add(a + 4, b + 4) }
+            { GenericFq2::<F>::add(a + 2, b + 4) }
+            { GenericFq2::<F>::add(a, b + 4) }
+        }
+    }
+
+    pub fn sub(a: u32, b: u32) -> Script {
+        if a > b {
+            script! {
+                { GenericFq2::<F>::sub(a + 4, b + 4) }
+                { GenericFq2::<F>::sub(a + 2, b + 4) }
+                { GenericFq2::<F>::sub(a, b + 4) }
+            }
+        } else {
+            script! {
+                { GenericFq2::<F>::sub(a + 4, b + 4) }
+                { GenericFq2::<F>::sub(a + 4, b + 2) }
+                { GenericFq2::<F>::sub(a + 4, b) }
+            }
+        }
+    }
+
+    pub fn double(a: u32) -> Script {
+        script! {
+            { GenericFq2::<F>::double(a + 4) }
+            { GenericFq2::<F>::double(a + 4) }
+            { GenericFq2::<F>::double(a + 4) }
+        }
+    }
+
+    pub fn equalverify() -> Script {
+        script! {
+            for i in 0..6 {
+                { F::equalverify(11 - i * 2, 5 - i) }
+            }
+        }
+    }
+
+    // For beta = 1 + u, as for BLS12-381, (c_0 + c_1 u) beta = (c_0 - c_1) + (c_0 + c_1) u,
+    // otherwise a multiplication by the constant beta.
+    pub fn mul_fq2_by_nonresidue() -> Script {
+        let beta = F::Fp6Config::NONRESIDUE;
+        if beta == Fp2::new(F::Fp::ONE, F::Fp::ONE) {
+            script! {
+                { F::copy(1) }
+                { F::copy(1) }
+                { F::sub(1, 0) }
+                { F::add(2, 1) }
+            }
+        } else {
+            GenericFq2::<F>::mul_by_constant(&beta)
+        }
+    }
+
+    // input:
+    //   p  (6 elements)
+    //   x  (2 elements)
+    pub fn mul_by_fp2() -> Script {
+        script! {
+            // compute p.c0 * c0
+            { GenericFq2::<F>::roll(6) }
+            { GenericFq2::<F>::copy(2) }
+            { GenericFq2::<F>::mul(2, 0) }
+
+            // compute p.c1 * c1
+            { GenericFq2::<F>::roll(6) }
+            { GenericFq2::<F>::copy(4) }
+            { GenericFq2::<F>::mul(2, 0) }
+
+            // compute p.c2 * c2
+            { GenericFq2::<F>::roll(6) }
+            { GenericFq2::<F>::roll(6) }
+            { GenericFq2::<F>::mul(2, 0) }
+        }
+    }
+
+    pub fn mul_by_fp2_constant(constant: &Fp2<F::Fp2Config>) -> Script {
+        script! {
+            // compute p.c0 * c0
+            { GenericFq2::<F>::roll(4) }
+            { GenericFq2::<F>::mul_by_constant(constant) }
+
+            // compute p.c1 * c1
+            { GenericFq2::<F>::roll(4) }
+            { GenericFq2::<F>::mul_by_constant(constant) }
+
+            // compute p.c2 * c2
+            { GenericFq2::<F>::roll(4) }
+            { GenericFq2::<F>::mul_by_constant(constant) }
+        }
+    }
+
+    // input:
+    //    p.c0   (2 elements)
+    //    p.c1   (2 elements)
+    //    p.c2   (2 elements)
+    //    c0  (2 elements)
+    //    c1  (2 elements)
+    pub fn mul_by_01() -> Script {
+        script! {
+            // compute a_a = p.c0 * c0
+            { GenericFq2::<F>::copy(8) }
+            { GenericFq2::<F>::copy(4) }
+            { GenericFq2::<F>::mul(2, 0) }
+
+            // compute b_b = p.c1 * c1
+            { GenericFq2::<F>::copy(8) }
+            { GenericFq2::<F>::copy(4) }
+            { GenericFq2::<F>::mul(2, 0) }
+
+            // compute tmp = p.c1 + p.c2
+            { GenericFq2::<F>::copy(10) }
+            { GenericFq2::<F>::copy(10) }
+            { GenericFq2::<F>::add(2, 0) }
+
+            // t1 = c1 * tmp
+            { GenericFq2::<F>::copy(6) }
+            { GenericFq2::<F>::mul(2, 0) }
+
+            // t1 = t1 - b_b
+            { GenericFq2::<F>::copy(2) }
+            { GenericFq2::<F>::sub(2, 0) }
+
+            // t1 = t1 * nonresidue
+            { Self::mul_fq2_by_nonresidue() }
+
+            // t1 = t1 + a_a
+            { GenericFq2::<F>::copy(4) }
+            { GenericFq2::<F>::add(2, 0) }
+
+            // compute tmp = p.c0 + p.c1
+            { GenericFq2::<F>::copy(14) }
+            { GenericFq2::<F>::roll(14) }
+            { GenericFq2::<F>::add(2, 0) }
+
+            // t2 = c0 + c1
+            { GenericFq2::<F>::copy(10) }
+            { GenericFq2::<F>::roll(10) }
+            { GenericFq2::<F>::add(2, 0) }
+
+            // t2 = t2 * tmp
+            { GenericFq2::<F>::mul(2, 0) }
+
+            // t2 = t2 - a_a
+            { GenericFq2::<F>::copy(6) }
+            { GenericFq2::<F>::sub(2, 0) }
+
+            // t2 = t2 - b_b
+            { GenericFq2::<F>::copy(4) }
+            { GenericFq2::<F>::sub(2, 0) }
+
+            // compute tmp = p.c0 + p.c2
+            { GenericFq2::<F>::add(12, 10) }
+
+            // t3 = c0 * tmp
+            { GenericFq2::<F>::mul(10, 0) }
+
+            // t3 = t3 - a_a
+            { GenericFq2::<F>::sub(0, 8) }
+
+            // t3 = t3 + b_b
+            { GenericFq2::<F>::add(0, 6) }
+        }
+    }
+
+    pub fn push_one() -> Script {
+        script! {
+            { GenericFq2::<F>::push_one() }
+            { GenericFq2::<F>::push_zero() }
+            { GenericFq2::<F>::push_zero() }
+        }
+    }
+
+    pub fn push_zero() -> Script {
+        script! {
+            { GenericFq2::<F>::push_zero() }
+            { GenericFq2::<F>::push_zero() }
+            { GenericFq2::<F>::push_zero() }
+        }
+    }
+
+    pub fn mul(mut a: u32, mut b: u32) -> Script {
+        // The degree-6 extension on Fq2 is under the polynomial y^3 - beta
+        // Karatsuba, with v_i = a_i * b_i:
+        //   c_0 = v_0 + beta ((a_1 + a_2)(b_1 + b_2) - v_1 - v_2)
+        //   c_1 = (a_0 + a_1)(b_0 + b_1) - v_0 - v_1 + beta v_2
+        //   c_2 = (a_0 + a_2)(b_0 + b_2) - v_0 - v_2 + v_1
+        if a < b {
+            (a, b) = (b, a);
+        }
+
+        script! {
+            { Self::roll(a) }
+            { Self::roll(b + 6) }
+
+            // compute v_0, v_1, v_2
+            for _ in 0..3 {
+                { GenericFq2::<F>::copy(10) }
+                { GenericFq2::<F>::copy(6) }
+                { GenericFq2::<F>::mul(2, 0) }
+            }
+
+            // compute c_0
+            { GenericFq2::<F>::copy(14) }
+            { GenericFq2::<F>::copy(14) }
+            { GenericFq2::<F>::add(2, 0) }
+            { GenericFq2::<F>::copy(10) }
+            { GenericFq2::<F>::copy(10) }
+            { GenericFq2::<F>::add(2, 0) }
+            { GenericFq2::<F>::mul(2, 0) }
+            { GenericFq2::<F>::copy(4) }
+            { GenericFq2::<F>::sub(2, 0) }
+            { GenericFq2::<F>::copy(2) }
+            { GenericFq2::<F>::sub(2, 0) }
+            { Self::mul_fq2_by_nonresidue() }
+            { GenericFq2::<F>::copy(6) }
+            { GenericFq2::<F>::add(2, 0) }
+
+            // compute c_1
+            { GenericFq2::<F>::copy(18) }
+            { GenericFq2::<F>::copy(18) }
+            { GenericFq2::<F>::add(2, 0) }
+            { GenericFq2::<F>::copy(14) }
+            { GenericFq2::<F>::copy(14) }
+            { GenericFq2::<F>::add(2, 0) }
+            { GenericFq2::<F>::mul(2, 0) }
+            { GenericFq2::<F>::copy(8) }
+            { GenericFq2::<F>::sub(2, 0) }
+            { GenericFq2::<F>::copy(6) }
+            { GenericFq2::<F>::sub(2, 0) }
+            { GenericFq2::<F>::copy(4) }
+            { Self::mul_fq2_by_nonresidue() }
+            { GenericFq2::<F>::add(2, 0) }
+
+            // compute c_2, consuming a_0, a_2, b_0, b_2 and the v_i
+            { GenericFq2::<F>::roll(20) }
+            { GenericFq2::<F>::roll(18) }
+            { GenericFq2::<F>::add(2, 0) }
+            { GenericFq2::<F>::roll(16) }
+            { GenericFq2::<F>::roll(14) }
+            { GenericFq2::<F>::add(2, 0) }
+            { GenericFq2::<F>::mul(2, 0) }
+            { GenericFq2::<F>::roll(10) }
+            { GenericFq2::<F>::sub(2, 0) }
+            { GenericFq2::<F>::roll(6) }
+            { GenericFq2::<F>::sub(2, 0) }
+            { GenericFq2::<F>::roll(6) }
+            { GenericFq2::<F>::add(2, 0) }
+
+            // drop a_1, b_1
+            { GenericFq2::<F>::roll(6) }
+            { GenericFq2::<F>::drop() }
+            { GenericFq2::<F>::roll(6) }
+            { GenericFq2::<F>::drop() }
+        }
+    }
+
+    pub fn square() -> Script {
+        script! {
+            { Self::copy(0) }
+            { Self::mul(6, 0) }
+        }
+    }
+
+    pub fn copy(a: u32) -> Script {
+        script! {
+            { GenericFq2::<F>::copy(a + 4) }
+            { GenericFq2::<F>::copy(a + 4) }
+            { GenericFq2::<F>::copy(a + 4) }
+        }
+    }
+
+    pub fn roll(a: u32) -> Script {
+        script! {
+            { GenericFq2::<F>::roll(a + 4) }
+            { GenericFq2::<F>::roll(a + 4) }
+            { GenericFq2::<F>::roll(a + 4) }
+        }
+    }
+
+    pub fn neg(a: u32) -> Script {
+        script! {
+            { GenericFq2::<F>::neg(a + 4) }
+            { GenericFq2::<F>::neg(a + 4) }
+            { GenericFq2::<F>::neg(a + 4) }
+        }
+    }
+
+    pub fn inv() -> Script {
+        script! {
+            // compute t0 = c0^2, t1 = c1^2, t2 = c2^2
+            { GenericFq2::<F>::copy(4) }
+            { GenericFq2::<F>::square() }
+            { GenericFq2::<F>::copy(4) }
+            { GenericFq2::<F>::square() }
+            { GenericFq2::<F>::copy(4) }
+            { GenericFq2::<F>::square() }
+
+            // compute t3 = c0 * c1, t4 = c0 * c2, t5 = c1 * c2
+            { GenericFq2::<F>::copy(10) }
+            { GenericFq2::<F>::copy(10) }
+            { GenericFq2::<F>::mul(2, 0) }
+            { GenericFq2::<F>::copy(12) }
+            { GenericFq2::<F>::copy(10) }
+            { GenericFq2::<F>::mul(2, 0) }
+            { GenericFq2::<F>::copy(12) }
+            { GenericFq2::<F>::copy(12) }
+            { GenericFq2::<F>::mul(2, 0) }
+
+            // update t5 = t5 * beta
+            { Self::mul_fq2_by_nonresidue() }
+
+            // compute s0 = t0 - t5
+            { GenericFq2::<F>::sub(10, 0) }
+
+            // compute s1 = t2 * beta - t3
+            { GenericFq2::<F>::roll(6) }
+            { Self::mul_fq2_by_nonresidue() }
+            { GenericFq2::<F>::sub(0, 6) }
+
+            // compute s2 = t1 - t4
+            { GenericFq2::<F>::sub(6, 4) }
+
+            // compute a1 = c2 * s1
+            { GenericFq2::<F>::copy(2) }
+            { GenericFq2::<F>::mul(8, 0) }
+
+            // compute a2 = c1 * s2
+            { GenericFq2::<F>::copy(2) }
+            { GenericFq2::<F>::mul(10, 0) }
+
+            // compute a3 = beta * (a1 + a2)
+            { GenericFq2::<F>::add(2, 0) }
+            { Self::mul_fq2_by_nonresidue() }
+
+            // compute t6 = c0 * s0 + a3
+            { GenericFq2::<F>::copy(6) }
+            { GenericFq2::<F>::mul(10, 0) }
+            { GenericFq2::<F>::add(2, 0) }
+
+            // inverse t6
+            { GenericFq2::<F>::inv() }
+
+            // compute final c0 = s0 * t6
+            { GenericFq2::<F>::copy(0) }
+            { GenericFq2::<F>::mul(8, 0) }
+
+            // compute final c1 = s1 * t6
+            { GenericFq2::<F>::copy(2) }
+            { GenericFq2::<F>::mul(8, 0) }
+
+            // compute final c2 = s2 * t6
+            { GenericFq2::<F>::mul(6, 4) }
+        }
+    }
+
+    pub fn frobenius_map(i: usize) -> Script {
+        script! {
+            { GenericFq2::<F>::roll(4) }
+            { GenericFq2::<F>::frobenius_map(i) }
+            { GenericFq2::<F>::roll(4) }
+            { GenericFq2::<F>::frobenius_map(i) }
+            { GenericFq2::<F>::mul_by_constant(&F::Fp6Config::FROBENIUS_COEFF_FP6_C1[i % F::Fp6Config::FROBENIUS_COEFF_FP6_C1.len()]) }
+            { GenericFq2::<F>::roll(4) }
+            { GenericFq2::<F>::frobenius_map(i) }
+            { GenericFq2::<F>::mul_by_constant(&F::Fp6Config::FROBENIUS_COEFF_FP6_C2[i % F::Fp6Config::FROBENIUS_COEFF_FP6_C2.len()]) }
+        }
+    }
+
+    pub fn toaltstack() -> Script {
+        script! {
+            { GenericFq2::<F>::toaltstack() }
+            { GenericFq2::<F>::toaltstack() }
+            { GenericFq2::<F>::toaltstack() }
+        }
+    }
+
+    pub fn fromaltstack() -> Script {
+        script! {
+            { GenericFq2::<F>::fromaltstack() }
+            { GenericFq2::<F>::fromaltstack() }
+            { GenericFq2::<F>::fromaltstack() }
+        }
+    }
+
+    pub fn drop() -> Script {
+        script! {
+            { GenericFq2::<F>::drop() }
+            { GenericFq2::<F>::drop() }
+            { GenericFq2::<F>::drop() }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::bls12_381::fq6::Fq6;
+    use crate::bls12_381::utils::fq6_push;
+    use crate::treepp::*;
+    use ark_ff::Field;
+    use ark_std::UniformRand;
+    use core::ops::{Mul, Sub};
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+
+    #[test]
+    fn test_bls12_381_fq6_sub() {
+        let mut prng = ChaCha20Rng::seed_from_u64(0);
+
+        for _ in 0..3 {
+            let a = ark_bls12_381::Fq6::rand(&mut prng);
+            let b = ark_bls12_381::Fq6::rand(&mut prng);
+
+            let script = script! {
+                { fq6_push(a) }
+                { fq6_push(b) }
+                { Fq6::copy(6) }
+                { Fq6::copy(6) }
+                { Fq6::sub(6, 0) }
+                { fq6_push(a.sub(&b)) }
+                { Fq6::equalverify() }
+                { Fq6::sub(0, 6) }
+                { fq6_push(b.sub(&a)) }
+                { Fq6::equalverify() }
+                OP_TRUE
+            };
+            let exec_result = execute_script(script);
+            assert!(exec_result.success);
+        }
+    }
+
+    #[test]
+    fn test_bls12_381_fq6_mul() {
+        println!("Fq6.mul: {} bytes", Fq6::mul(6, 0).len());
+        let mut prng = ChaCha20Rng::seed_from_u64(0);
+
+        for _ in 0..1 {
+            let a = ark_bls12_381::Fq6::rand(&mut prng);
+            let b = ark_bls12_381::Fq6::rand(&mut prng);
+            let c = a.mul(&b);
+
+            // the operands in either order, and below another element
+            let script = script! {
+                { fq6_push(a) }
+                { fq6_push(b) }
+                { Fq6::mul(6, 0) }
+                { fq6_push(c) }
+                { Fq6::equalverify() }
+                { fq6_push(a) }
+                { Fq6::push_one() }
+                { fq6_push(b) }
+                { Fq6::mul(0, 12) }
+                { fq6_push(c) }
+                { Fq6::equalverify() }
+                { Fq6::push_one() }
+                { Fq6::equalverify() }
+                OP_TRUE
+            };
+            let exec_result = execute_script(script);
+            assert!(exec_result.success);
+        }
+    }
+
+    #[test]
+    fn test_bls12_381_fq6_square() {
+        println!("Fq6.square: {} bytes", Fq6::square().len());
+        let mut prng = ChaCha20Rng::seed_from_u64(0);
+
+        for _ in 0..1 {
+            let a = ark_bls12_381::Fq6::rand(&mut prng);
+
+            let script = script! {
+                { fq6_push(a) }
+                { Fq6::square() }
+                { fq6_push(a.square()) }
+                { Fq6::equalverify() }
+                OP_TRUE
+            };
+            let exec_result = execute_script(script);
+            assert!(exec_result.success);
+        }
+    }
+
+    #[test]
+    fn test_bls12_381_fq6_inv() {
+        println!("Fq6.inv: {} bytes", Fq6::inv().len());
+        let mut prng = ChaCha20Rng::seed_from_u64(0);
+
+        for _ in 0..1 {
+            let a = ark_bls12_381::Fq6::rand(&mut prng);
+
+            let script = script! {
+                { fq6_push(a) }
+                { Fq6::inv() }
+                { fq6_push(a.inverse().unwrap()) }
+                { Fq6::equalverify() }
+                OP_TRUE
+            };
+            let exec_result = execute_script(script);
+            assert!(exec_result.success);
+        }
+    }
+
+    #[test]
+    fn test_bls12_381_fq6_frobenius_map() {
+        let mut prng = ChaCha20Rng::seed_from_u64(0);
+
+        for i in 0..3 {
+            let a = ark_bls12_381::Fq6::rand(&mut prng);
+            let b = a.frobenius_map(i);
+
+            let script = script! {
+                { fq6_push(a) }
+                { Fq6::frobenius_map(i) }
+                { fq6_push(b) }
+                { Fq6::equalverify() }
+                OP_TRUE
+            };
+            let exec_result = execute_script(script);
+            assert!(exec_result.success);
+        }
+    }
+}
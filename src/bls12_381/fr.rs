@@ -0,0 +1,91 @@
+use crate::bigint::U381;
+use crate::bls12_381::fp381impl::Fp381Impl;
+
+/// The scalar field of BLS12-381, e.g. for the public inputs of a Groth16 proof. It only takes
+/// 255 of the 381 bits of `U381`.
+pub struct Fr;
+
+impl Fp381Impl for Fr {
+    const MODULUS: &'static str =
+        "73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001";
+
+    // 2⁴⁰⁶ mod p
+    const MONTGOMERY_ONE: &'static str =
+        "4e6d253d09c802169116369b81ca6de3db1ea3947249a7bb871748eb4b26bef1";
+
+    const MODULUS_LIMBS: [u32; U381::N_LIMBS as usize] = [
+        0x1, 0x1ffffff8, 0x1f96ffbf, 0x1b4805ff, 0x1d80553b, 0xc0404d0, 0x1520cce7, 0xa6533af,
+        0x73eda7, 0x0, 0x0, 0x0, 0x0, 0x0,
+    ];
+
+    // inv₄₀₆ p  <=>  0x2126a4ef9dd73b4a284c49126d1ba9fe75c03fc2bbc54f2840d7c6e7e4d3e8fffb13f9ac45a4000001a4020000000100000001
+    const MODULUS_INV_406: [u32; U381::N_LIMBS as usize] = [
+        0x1, 0x8, 0x690080, 0xb480000, 0x113f9ac4, 0x9f47ffd, 0x1f1b9f93, 0x9e5081a, 0x1fc2bbc5,
+        0xff3ae01, 0x49b46ea, 0x14509892, 0x19dd73b4, 0x10935277,
+    ];
+
+    type ConstantType = ark_bls12_381::Fr;
+}
+
+#[cfg(test)]
+mod test {
+    use crate::bigint::U381;
+    use crate::bls12_381::fp381impl::Fp381Impl;
+    use crate::bls12_381::fr::Fr;
+    use crate::treepp::*;
+    use ark_ff::PrimeField;
+    use ark_std::UniformRand;
+    use num_bigint::BigUint;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+
+    #[test]
+    fn test_bls12_381_fr_modulus() {
+        assert_eq!(
+            BigUint::parse_bytes(Fr::MODULUS.as_bytes(), 16).unwrap(),
+            BigUint::from(ark_bls12_381::Fr::MODULUS)
+        );
+    }
+
+    #[test]
+    fn test_bls12_381_fr_decode_montgomery() {
+        let mut prng = ChaCha20Rng::seed_from_u64(0);
+        let a = ark_bls12_381::Fr::rand(&mut prng);
+
+        let script = script! {
+            { Fr::push_u32_le(&BigUint::from(a).to_u32_digits()) }
+            { Fr::decode_montgomery() }
+            { U381::push_u32_le(&BigUint::from(a).to_u32_digits()) }
+            { U381::equalverify(1, 0) }
+            OP_TRUE
+        };
+        let exec_result = execute_script(script);
+        assert!(exec_result.success);
+    }
+
+    #[test]
+    fn test_bls12_381_fr_mul_is_field() {
+        let mut prng = ChaCha20Rng::seed_from_u64(0);
+
+        for _ in 0..3 {
+            let a = ark_bls12_381::Fr::rand(&mut prng);
+            let b = ark_bls12_381::Fr::rand(&mut prng);
+
+            let script = script! {
+                { Fr::push_u32_le(&BigUint::from(a).to_u32_digits()) }
+                { Fr::copy(0) }
+                { Fr::is_field() }
+                OP_VERIFY
+                { Fr::push_u32_le(&BigUint::from(b).to_u32_digits()) }
+                { Fr::mul() }
+                { Fr::push_u32_le(&BigUint::from(a * b).to_u32_digits()) }
+                { Fr::equalverify(1, 0) }
+                { U381::push_hex(Fr::MODULUS) }
+                { Fr::is_field() }
+                OP_NOT
+            };
+            let exec_result = execute_script(script);
+            assert!(exec_result.success);
+        }
+    }
+}
@@ -0,0 +1,338 @@
+use crate::bls12_381::curves::{G1Affine, G1Projective, G2Affine};
+use crate::bls12_381::ell_coeffs::G2Prepared;
+use crate::bls12_381::fp381impl::Fp381Impl;
+use crate::bls12_381::fq::Fq;
+use crate::bls12_381::fq12::Fq12;
+use crate::bls12_381::fq2::Fq2;
+use crate::bls12_381::fr::Fr;
+use crate::bls12_381::offchain_checker::compute_c_wi;
+use crate::bls12_381::pairing::Pairing;
+use crate::bls12_381::utils;
+use crate::treepp::{script, Script};
+use ark_bls12_381::{Bls12_381, G1Projective as ArkG1Projective};
+use ark_ec::pairing::Pairing as ark_Pairing;
+use ark_ec::{AffineRepr, CurveGroup, VariableBaseMSM};
+use ark_ff::Field;
+use ark_groth16::{Proof, VerifyingKey};
+use core::ops::Neg;
+use num_bigint::BigUint;
+
+// Number of Fq elements of B, c_inv and wi in the witness.
+const PROOF_TAIL_LEN: u32 = 4 + 12 + 6;
+
+/// Groth16 verifier over BLS12-381, as `groth16::verifier::Verifier` for BN254.
+///
+/// The final exponentiation is checked with the hints c_inv and wi, see
+/// `Pairing::multi_miller_loop_with_c_wi_and_hinted_lines`.
+#[derive(Clone, Copy, Debug)]
+pub struct Verifier;
+
+impl Verifier {
+    /// Script verifying any proof for `vk`, it only depends on the verifying key and can be
+    /// committed to before the proof exists. The proof is provided by `Verifier::witness`.
+    ///
+    /// Input stack: [public_inputs, C, A, B, c_inv, wi, L(B)]
+    pub fn locking_script(vk: &VerifyingKey<Bls12_381>) -> Script {
+        let (q1, q2, q3) = (
+            vk.gamma_g2.into_group().neg().into_affine(),
+            vk.delta_g2.into_group().neg().into_affine(),
+            -vk.beta_g2,
+        );
+        let num_line_elements = 4 * Pairing::num_hinted_lines() as u32;
+
+        script! {
+            { check_witness(vk.gamma_abc_g1.len() as u32 - 1, num_line_elements) }
+
+            // the line coefficients of B are consumed by the miller loop from altstack
+            for _ in 0..num_line_elements {
+                { Fq::toaltstack() }
+            }
+
+            // move B, c_inv and wi out of the way
+            for _ in 0..PROOF_TAIL_LEN {
+                { Fq::toaltstack() }
+            }
+
+            // variants of the proof's G1 points, say -p.x / p.y, 1 / p.y
+            { utils::from_eval_point_in_stack() }
+            { Fq2::toaltstack() }
+            { utils::from_eval_point_in_stack() }
+            { Fq2::toaltstack() }
+            // stack: [public_inputs | L(B), B, c_inv, wi, A', C']
+
+            // P1 from the public inputs
+            { Self::public_inputs_point(vk) }
+            { utils::from_eval_point_in_stack() }
+
+            { Fq2::fromaltstack() }
+            { utils::from_eval_point(vk.alpha_g1) }
+            { Fq2::fromaltstack() }
+            for _ in 0..PROOF_TAIL_LEN {
+                { Fq::fromaltstack() }
+            }
+            // stack: [P1', C', alpha', A', B, c_inv, wi | L(B)]
+
+            // accumulator of B, say T
+            { Fq2::copy(20) }
+            { Fq2::copy(20) }
+            // stack: [P1', C', alpha', A', B, c_inv, wi, T | L(B)]
+
+            { Pairing::quad_miller_loop_with_c_wi_and_hinted_lines(vec![
+                G2Prepared::from_affine(q1),
+                G2Prepared::from_affine(q2),
+                G2Prepared::from_affine(q3),
+            ]) }
+
+            // check c_inv^lambda * f * wi == 1
+            { Fq12::push_one() }
+            { Fq12::equalverify() }
+            OP_TRUE
+        }
+    }
+
+    /// Stack items satisfying `Verifier::locking_script(vk)` for the proof: the public inputs,
+    /// the proof points, the hints of the final exponentiation and the line coefficients of
+    /// proof.b, first item at the bottom.
+    pub fn witness(
+        proof: &Proof<Bls12_381>,
+        public_inputs: &Vec<<Bls12_381 as ark_Pairing>::ScalarField>,
+        vk: &VerifyingKey<Bls12_381>,
+    ) -> Vec<Vec<u8>> {
+        let (c_inv, wi) = Self::hints(proof, public_inputs, vk);
+
+        [
+            public_inputs
+                .iter()
+                .flat_map(|input| Fr::witness_u32_le(&BigUint::from(*input).to_u32_digits()))
+                .collect(),
+            utils::g1_affine_witness(proof.c),
+            utils::g1_affine_witness(proof.a),
+            utils::g2_affine_witness(proof.b),
+            utils::fq12_witness(c_inv),
+            utils::fq6_witness(wi),
+            Pairing::hinted_line_coeffs(proof.b)
+                .into_iter()
+                .flat_map(|(c3, c4)| [utils::fq2_witness(c3), utils::fq2_witness(c4)].concat())
+                .collect(),
+        ]
+        .concat()
+    }
+
+    /// Locking script with the witness pushed in front of it.
+    pub fn verify_proof(
+        public_inputs: &Vec<<Bls12_381 as ark_Pairing>::ScalarField>,
+        proof: &Proof<Bls12_381>,
+        vk: &VerifyingKey<Bls12_381>,
+    ) -> Script {
+        let (c_inv, wi) = Self::hints(proof, public_inputs, vk);
+
+        script! {
+            for input in public_inputs {
+                { Fr::push_u32_le(&BigUint::from(*input).to_u32_digits()) }
+            }
+            { G1Affine::push(proof.c) }
+            { G1Affine::push(proof.a) }
+            { G2Affine::push(proof.b) }
+            { utils::fq12_push(c_inv) }
+            { utils::fq6_push(wi) }
+            for (c3, c4) in Pairing::hinted_line_coeffs(proof.b) {
+                { utils::fq2_push(c3) }
+                { utils::fq2_push(c4) }
+            }
+            { Self::locking_script(vk) }
+        }
+    }
+
+    // Computes P1 = gamma_abc_g1[0] + sum(public_inputs[i] * gamma_abc_g1[i + 1]).
+    //
+    // Input stack: [public_inputs]
+    // Output stack: [x, y]
+    fn public_inputs_point(vk: &VerifyingKey<Bls12_381>) -> Script {
+        let bases = vk
+            .gamma_abc_g1
+            .iter()
+            .map(|base| base.into_group())
+            .collect::<Vec<_>>();
+
+        script! {
+            for _ in 1..bases.len() {
+                { Fr::toaltstack() }
+            }
+            { G1Projective::push(bases[0]) }
+            for base in bases.iter().skip(1) {
+                { Fr::fromaltstack() }
+                { G1Projective::scalar_mul_by_constant_g1(*base) }
+                { G1Projective::add() }
+            }
+            { G1Projective::into_affine() }
+        }
+    }
+
+    // Hints for verifying the final exponentiation: c_inv and wi with c^lambda = f * wi.
+    fn hints(
+        proof: &Proof<Bls12_381>,
+        public_inputs: &[<Bls12_381 as ark_Pairing>::ScalarField],
+        vk: &VerifyingKey<Bls12_381>,
+    ) -> (ark_bls12_381::Fq12, ark_bls12_381::Fq6) {
+        assert_eq!(
+            public_inputs.len() + 1,
+            vk.gamma_abc_g1.len(),
+            "wrong number of public inputs"
+        );
+        let scalars = [
+            vec![<Bls12_381 as ark_Pairing>::ScalarField::ONE],
+            public_inputs.to_vec(),
+        ]
+        .concat();
+        let msm_g1 =
+            ArkG1Projective::msm(&vk.gamma_abc_g1, &scalars).expect("failed to calculate msm");
+
+        // G1/G2 points for pairings
+        let (p1, p2, p3, p4) = (msm_g1.into_affine(), proof.c, vk.alpha_g1, proof.a);
+        let (q1, q2, q3, q4) = (
+            vk.gamma_g2.into_group().neg().into_affine(),
+            vk.delta_g2.into_group().neg().into_affine(),
+            -vk.beta_g2,
+            proof.b,
+        );
+
+        let f = Pairing::multi_miller_loop_affine(&[p1, p2, p3, p4], &[q1, q2, q3, q4]);
+        let (c, wi) = compute_c_wi(f);
+
+        (c.inverse().unwrap(), wi)
+    }
+}
+
+// Checks that the public inputs and the hints of the final exponentiation in the witness are
+// field elements, that A and C are valid points of G1 and B a valid point of G2, namely in the
+// subgroups of order r. The line coefficients of B are checked by the miller loop.
+//
+// Input stack: [public_inputs, C, A, B, c_inv, wi, L(B)]
+// Output stack: [public_inputs, C, A, B, c_inv, wi, L(B)]
+fn check_witness(num_public_inputs: u32, num_line_elements: u32) -> Script {
+    script! {
+        for i in 0..PROOF_TAIL_LEN - 4 {
+            { Fq::copy(num_line_elements + i) }
+            { Fq::is_field() }
+            OP_VERIFY
+        }
+        for i in 0..num_public_inputs {
+            { Fr::copy(num_line_elements + PROOF_TAIL_LEN + 4 + i) }
+            { Fr::is_field() }
+            OP_VERIFY
+        }
+
+        // A and C
+        for i in 0..2 {
+            { Fq2::copy(num_line_elements + PROOF_TAIL_LEN + 2 * i) }
+            { G1Affine::is_valid() }
+            OP_VERIFY
+        }
+
+        // B
+        { Fq2::copy(num_line_elements + 20) }
+        { Fq2::copy(num_line_elements + 20) }
+        { G2Affine::is_valid() }
+        OP_VERIFY
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::bls12_381::groth16::{check_witness, Verifier};
+    use crate::bls12_381::pairing::Pairing;
+    use crate::execute_script_with_witness_without_stack_limit;
+    use crate::treepp::*;
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_crypto_primitives::snark::{CircuitSpecificSetupSNARK, SNARK};
+    use ark_ff::PrimeField;
+    use ark_groth16::Groth16;
+    use ark_relations::lc;
+    use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
+    use ark_std::UniformRand;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+
+    // a * b = c with c public
+    #[derive(Clone, Copy)]
+    struct MulCircuit<F: PrimeField> {
+        a: F,
+        b: F,
+    }
+
+    impl<F: PrimeField> ConstraintSynthesizer<F> for MulCircuit<F> {
+        fn generate_constraints(self, cs: ConstraintSystemRef<F>) -> Result<(), SynthesisError> {
+            let a = cs.new_witness_variable(|| Ok(self.a))?;
+            let b = cs.new_witness_variable(|| Ok(self.b))?;
+            let c = cs.new_input_variable(|| Ok(self.a * self.b))?;
+            cs.enforce_constraint(lc!() + a, lc!() + b, lc!() + c)?;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_groth16_witness() {
+        let mut rng = ChaCha20Rng::seed_from_u64(0);
+        let circuit = MulCircuit {
+            a: Fr::rand(&mut rng),
+            b: Fr::rand(&mut rng),
+        };
+        let (pk, vk) = Groth16::<Bls12_381>::setup(circuit, &mut rng).unwrap();
+        let proof = Groth16::<Bls12_381>::prove(&pk, circuit, &mut rng).unwrap();
+        let public_inputs = vec![circuit.a * circuit.b];
+        assert!(Groth16::<Bls12_381>::verify(&vk, &public_inputs, &proof).unwrap());
+
+        let witness = Verifier::witness(&proof, &public_inputs, &vk);
+        assert_eq!(
+            witness.len(),
+            14 * (1 + 2 + 2 + 4 + 12 + 6 + 4 * Pairing::num_hinted_lines())
+        );
+
+        // the public input, the proof points and the hints pass the checks of the locking script
+        let num_line_elements = 4 * Pairing::num_hinted_lines() as u32;
+        let check = |witness: Vec<Vec<u8>>| {
+            let num_items = witness.len();
+            let script = script! {
+                { check_witness(1, num_line_elements) }
+                for _ in 0..num_items {
+                    OP_DROP
+                }
+                OP_TRUE
+            };
+            execute_script_with_witness_without_stack_limit(script, witness).success
+        };
+        assert!(check(witness.clone()));
+        // C = (A.x, C.y) is not on the curve, the witness starts with the public input (14 items),
+        // C (28 items) and A
+        assert!(!check(
+            [&witness[..14], &witness[42..56], &witness[28..]].concat()
+        ));
+    }
+
+    // Two more fixed pairings than the miller loop of
+    // `pairing::test::test_dual_miller_loop_with_c_wi_and_hinted_lines`, the script takes more
+    // memory than most test runners have: run it with `--ignored`.
+    #[test]
+    #[ignore]
+    fn test_groth16_verifier() {
+        let mut rng = ChaCha20Rng::seed_from_u64(0);
+        let circuit = MulCircuit {
+            a: Fr::rand(&mut rng),
+            b: Fr::rand(&mut rng),
+        };
+        let (pk, vk) = Groth16::<Bls12_381>::setup(circuit, &mut rng).unwrap();
+        let proof = Groth16::<Bls12_381>::prove(&pk, circuit, &mut rng).unwrap();
+        let public_inputs = vec![circuit.a * circuit.b];
+
+        let locking_script = Verifier::locking_script(&vk);
+        println!(
+            "groth16::Verifier::locking_script = {} bytes",
+            locking_script.len()
+        );
+        let exec_result = execute_script_with_witness_without_stack_limit(
+            locking_script,
+            Verifier::witness(&proof, &public_inputs, &vk),
+        );
+        assert!(exec_result.success);
+    }
+}
@@ -0,0 +1,23 @@
+//! Field and curve gadgets for BLS12-381, laid out on the stack like their `bn254` counterparts
+//! but over `U381` in Montgomery form, and a Groth16 verifier built on them.
+//!
+//! The extension tower and the miller loop are generic over the base field, see
+//! `base_field::BaseField`: `Fq2`, `Fq6`, `Fq12` and `Pairing` are their instances over the
+//! BLS12-381 `Fq`. The final exponentiation is left to the hints c and wi of the miller loop, see
+//! `pairing::Pairing::multi_miller_loop_with_c_wi_and_hinted_lines`.
+
+pub mod base_field;
+pub mod fq;
+pub mod fq12;
+pub mod fq2;
+pub mod fq6;
+pub mod fr;
+
+pub mod curves;
+pub mod ell_coeffs;
+pub mod pairing;
+
+pub mod fp381impl;
+pub mod groth16;
+pub mod offchain_checker;
+pub mod utils;
@@ -0,0 +1,58 @@
+use crate::bls12_381::fp381impl::Fp381Impl;
+use crate::bls12_381::fq::Fq;
+use ark_ec::bls12::Bls12Config;
+use ark_ff::{AdditiveGroup, Field, PrimeField};
+use num_bigint::BigUint;
+use num_traits::{Num, One, Zero};
+
+// Primes dividing both p^12 - 1 and the cofactor (x - 1)^2 / 3 of lambda, see `compute_c_wi`.
+const SHARED_PRIMES: [u32; 5] = [3, 11, 10177, 859267, 52437899];
+
+/// lambda = p - x = r * (x - 1)^2 / 3: if f * wi = c^lambda, f is an r-th residue as well as the
+/// product of the pairings f^((p^12 - 1) / r). Unlike BN254 there is no need for a 27-th root of
+/// unity: p - x is the sum of the exponents of the miller loop, |x|, and of one frobenius map.
+pub fn lambda() -> BigUint {
+    BigUint::from_str_radix(Fq::MODULUS, 16).unwrap() + BigUint::from(ark_bls12_381::Config::X[0])
+}
+
+// Finding C, refer from Algorithm 5 of "On Proving Pairings"(https://eprint.iacr.org/2024/640.pdf)
+//
+// The parts of p^12 - 1 made of primes shared with lambda / r can't be inverted, the one of f
+// in them is removed by wi instead. They all divide p^6 - 1, hence wi is in Fq6 and is killed by
+// the final exponentiation: f * wi = c^lambda for a miller loop output f whose pairings product
+// is one.
+pub fn compute_c_wi(f: ark_bls12_381::Fq12) -> (ark_bls12_381::Fq12, ark_bls12_381::Fq6) {
+    let p = BigUint::from_str_radix(Fq::MODULUS, 16).unwrap();
+    let r = BigUint::from(ark_bls12_381::Fr::MODULUS);
+    let exp = p.pow(12_u32) - 1_u32;
+    let lambda = lambda();
+
+    // p^12 - 1 = n_shared * m_coprime
+    let mut n_shared = BigUint::one();
+    let mut m_coprime = exp.clone();
+    for prime in SHARED_PRIMES {
+        while (&m_coprime % prime).is_zero() {
+            m_coprime /= prime;
+            n_shared *= prime;
+        }
+    }
+    assert!(((p.pow(6_u32) - 1_u32) % &n_shared).is_zero());
+
+    // f is an r-th residue
+    assert_eq!(f.pow((&exp / &r).to_u64_digits()), ark_bls12_381::Fq12::ONE);
+
+    // wi removes the part of f of order dividing n_shared: e is 1 modulo n_shared and 0 modulo
+    // m_coprime
+    let e = &m_coprime * m_coprime.modinv(&n_shared).unwrap();
+    let wi = f.pow(e.to_u64_digits()).inverse().unwrap();
+    assert_eq!(wi.c1, ark_bls12_381::Fq6::ZERO);
+    let y = f * wi;
+
+    // y is an r-th residue of order dividing m, where lambda is invertible
+    let m = &m_coprime / &r;
+    assert_eq!(y.pow(m.to_u64_digits()), ark_bls12_381::Fq12::ONE);
+    let c = y.pow(lambda.modinv(&m).unwrap().to_u64_digits());
+    assert_eq!(c.pow(lambda.to_u64_digits()), f * wi);
+
+    (c, wi.c0)
+}
@@ -0,0 +1,403 @@
+use crate::bls12_381::base_field::{BaseField, Bls12BaseField};
+use crate::bls12_381::ell_coeffs::{loop_bits, EllCoeff, G2Prepared};
+use crate::bls12_381::fq::Fq;
+use crate::bls12_381::fq12::GenericFq12;
+use crate::bls12_381::fq2::GenericFq2;
+use crate::bls12_381::fq6::GenericFq6;
+use crate::bls12_381::utils;
+use crate::treepp::*;
+use ark_ec::bls12::{G1Affine, G2Affine};
+use ark_ec::AffineRepr;
+use ark_ff::{AdditiveGroup, Field, Fp12, Fp2, Fp6};
+use std::marker::PhantomData;
+
+/// Coefficients (c3, c4) of a line provided by the prover, see
+/// `GenericPairing::multi_hinted_line_coeffs`.
+pub type HintedLine<F> = (
+    Fp2<<F as BaseField>::Fp2Config>,
+    Fp2<<F as BaseField>::Fp2Config>,
+);
+
+/// Miller loop of a BLS12 curve over the base field `F` and its tower, BLS12-381 for `Pairing`.
+pub struct GenericPairing<F: Bls12BaseField>(PhantomData<F>);
+
+pub type Pairing = GenericPairing<Fq>;
+
+impl<F: Bls12BaseField> GenericPairing<F> {
+    // Same as `bn254::pairing::Pairing::quad_miller_loop_with_c_wi_and_hinted_lines` for
+    // BLS12 curves, for groth16: three pairings on fixed G2 points and one on a G2 point
+    // provided by the prover, with hinted line coefficients.
+    //
+    // input on stack:
+    //     [P1', P2', P3', P4', Q4, c_inv, wi, T4]
+    //
+    // input on altstack:
+    //     [L(Q4)] line coefficients (c3, c4) of Q4 as returned by `Pairing::hinted_line_coeffs`,
+    //     the first line on top
+    //
+    // input of parameters:
+    //     [L(Q1), L(Q2), L(Q3)] (line coefficients in affine mode)
+    pub fn quad_miller_loop_with_c_wi_and_hinted_lines(
+        constants: Vec<G2Prepared<F::Config>>,
+    ) -> Script {
+        assert_eq!(constants.len(), 3);
+        Self::multi_miller_loop_with_c_wi_and_hinted_lines(constants, 1)
+    }
+
+    // Miller loop of n pairings, of which the first m = constants.len() are on fixed G2 points
+    // and the last v = num_variable on G2 points provided by the prover, with hinted line
+    // coefficients, followed by the check of the hints c and wi of the final exponentiation.
+    //
+    // The loop runs over |x| and leaves out the conjugation arkworks applies for the negative x:
+    // with f the product of the miller loops over |x|, the pairings product is one iff
+    // f * wi = c^lambda, see `offchain_checker::compute_c_wi`, namely iff
+    //     c_inv^|x| * f * c_inv^p * wi = 1
+    // where c_inv^|x| * f comes out of the loop started from f = c_inv.
+    //
+    // input on stack:
+    //     [P_0', ..., P_{n-1}', Q_m, ..., Q_{n-1}, c_inv, wi, T_m, ..., T_{n-1}]
+    //     P_i' = (-P_i.x / P_i.y, 1 / P_i.y)
+    //     wi is an element of Fq6, 6 elements
+    //     T_k is the accumulator of Q_k, initially T_k = Q_k
+    //
+    // input on altstack:
+    //     [L(Q_m, ..., Q_{n-1})] line coefficients (c3, c4) as returned by
+    //     `Pairing::multi_hinted_line_coeffs`, the first line on top
+    //
+    // input of parameters:
+    //     [L(Q_0), ..., L(Q_{m-1})] (line coefficients in affine mode)
+    //
+    // output on stack:
+    //     [c_inv^(|x| + p) * f * wi(12)], one for a valid set of pairings and hints
+    pub fn multi_miller_loop_with_c_wi_and_hinted_lines(
+        constants: Vec<G2Prepared<F::Config>>,
+        num_variable: usize,
+    ) -> Script {
+        let num_fixed = constants.len() as u32;
+        let v = num_variable as u32;
+        let n = num_fixed + v;
+
+        let line_coeffs = utils::collect_line_coeffs(constants);
+
+        // [..., T, f | c3, c4] => [..., T, c3, c4, f, P'(2), c3, c4]
+        let pull_line_and_copy_p = |p_index: u32| {
+            script! {
+                { GenericFq2::<F>::fromaltstack() }
+                { GenericFq2::<F>::fromaltstack() }
+                { GenericFq12::<F>::roll(4) }
+                { GenericFq2::<F>::copy(p_index + 4) }
+                { GenericFq2::<F>::copy(16) }
+                { GenericFq2::<F>::copy(16) }
+            }
+        };
+
+        // [..., T_a, T_b, ..., f] => [..., T_b, ..., T_a, f]
+        // Each accumulator is updated on top of the others, after `num_accumulators` rotations
+        // they are back in order.
+        let rotate_accumulators = |num_accumulators: u32| {
+            script! {
+                if num_accumulators > 1 {
+                    { GenericFq2::<F>::roll(10 + 4 * num_accumulators) }
+                    { GenericFq2::<F>::roll(10 + 4 * num_accumulators) }
+                    { GenericFq12::<F>::roll(4) }
+                }
+            }
+        };
+
+        // indices with f on top
+        let wi_index = 12 + 4 * v;
+        let c_inv_index = 18 + 4 * v;
+        // c_inv and wi
+        let num_hint_elements = 18;
+        let p_index = |j: u32| 12 + num_hint_elements + 8 * v + 2 * (n - 1 - j);
+
+        script! {
+            // [P(2n), Q(4v), c_inv(12), wi(6), T(4v)]
+            // 1. f = c_inv
+            { GenericFq12::<F>::copy(6 + 4 * v) }
+            // [P(2n), Q(4v), c_inv(12), wi(6), T(4v), f(12)]
+
+            for (i, bit) in loop_bits::<F::Config>().into_iter().enumerate() {
+                // update f, squaring
+                { GenericFq12::<F>::square() }
+
+                // update f, multiplying
+                // f = f * c_inv, if bit == 1
+                if bit {
+                    { GenericFq12::<F>::copy(c_inv_index) }
+                    { GenericFq12::<F>::mul(12, 0) }
+                }
+
+                // update f with double line evaluation
+                for j in 0..num_fixed {
+                    { GenericFq2::<F>::copy(p_index(j)) }
+                    { utils::ell_by_constant_affine::<F>(&line_coeffs[i][j as usize][0]) }
+                }
+
+                // non-fixed part, update f with the hinted double line of each T_k
+                for k in 0..v {
+                    { rotate_accumulators(v) }
+                    { pull_line_and_copy_p(p_index(num_fixed + k)) }
+                    { utils::ell_affine_in_stack::<F>() }
+                    // [..., Q(4v), c_inv(12), wi(6), T(4v), c3(2), c4(2), f(12)]
+                    { GenericFq12::<F>::toaltstack() }
+
+                    // check the line is tangent to T_k
+                    { GenericFq2::<F>::copy(6) }
+                    { GenericFq2::<F>::copy(6) }
+                    { GenericFq2::<F>::copy(6) }
+                    { GenericFq2::<F>::copy(6) }
+                    { utils::check_tangent_line_in_stack::<F>() }
+
+                    // update T_k, drop T_k.y, leave T_k.x
+                    { GenericFq2::<F>::roll(4) }
+                    { GenericFq2::<F>::drop() }
+                    { utils::affine_double_line_in_stack::<F>() }
+                    { GenericFq12::<F>::fromaltstack() }
+                    // [..., Q(4v), c_inv(12), wi(6), T(4v), f(12)]
+                }
+
+                // update f with add line evaluation
+                if bit {
+                    for j in 0..num_fixed {
+                        { GenericFq2::<F>::copy(p_index(j)) }
+                        { utils::ell_by_constant_affine::<F>(&line_coeffs[i][j as usize][1]) }
+                    }
+
+                    // non-fixed part, update f with the hinted add line of each T_k and Q_k
+                    for k in 0..v {
+                        { rotate_accumulators(v) }
+                        { pull_line_and_copy_p(p_index(num_fixed + k)) }
+                        { utils::ell_affine_in_stack::<F>() }
+                        { GenericFq12::<F>::toaltstack() }
+                        // [..., Q(4v), c_inv(12), wi(6), T(4v), c3(2), c4(2) | f(12)]
+
+                        // check the line is the chord through T_k and Q_k
+                        { GenericFq2::<F>::copy(6) }
+                        { GenericFq2::<F>::copy(6) }
+                        { GenericFq2::<F>::copy(10 + num_hint_elements + 4 * v + 4 * (v - 1 - k)) }
+                        { GenericFq2::<F>::copy(10 + num_hint_elements + 4 * v + 4 * (v - 1 - k)) }
+                        { GenericFq2::<F>::copy(10) }
+                        { GenericFq2::<F>::copy(10) }
+                        { utils::check_chord_line_in_stack::<F>() }
+
+                        // update T_k, drop T_k.y, leave T_k.x
+                        { GenericFq2::<F>::roll(4) }
+                        { GenericFq2::<F>::drop() }
+                        // copy Q_k.x
+                        { GenericFq2::<F>::copy(4 + num_hint_elements + 4 * v + 4 * (v - 1 - k)) }
+                        { GenericFq2::<F>::roll(4) }
+                        { GenericFq2::<F>::roll(4) }
+                        // [..., Q(4v), c_inv(12), wi(6), T(4v - 2), Q_k.x(2), c3(2), c4(2) | f(12)]
+                        { utils::affine_add_line_in_stack::<F>() }
+                        { GenericFq12::<F>::fromaltstack() }
+                    }
+                }
+            }
+
+            // update f with frobenius of c_inv, say f = f * c_inv^p
+            { GenericFq12::<F>::roll(c_inv_index) }
+            { GenericFq12::<F>::frobenius_map(1) }
+            { GenericFq12::<F>::mul(12, 0) }
+
+            // update f with scalar wi, say f = f * wi
+            { GenericFq6::<F>::roll(wi_index) }
+            { GenericFq12::<F>::mul_by_fq6() }
+            // [P(2n), Q(4v), T(4v), f(12)]
+
+            // the points aren't needed anymore
+            { GenericFq12::<F>::toaltstack() }
+            for _ in 0..n + 4 * v {
+                { GenericFq2::<F>::drop() }
+            }
+            { GenericFq12::<F>::fromaltstack() }
+            // [f(12)]
+        }
+    }
+
+    /// Line coefficients (c3, c4) of Q4 in the order `quad_miller_loop_with_c_wi_and_hinted_lines`
+    /// takes them from the altstack.
+    pub fn hinted_line_coeffs(q4: G2Affine<F::Config>) -> Vec<HintedLine<F>> {
+        Self::multi_hinted_line_coeffs(&[q4])
+    }
+
+    /// Line coefficients (c3, c4) of the non-fixed points in the order
+    /// `multi_miller_loop_with_c_wi_and_hinted_lines` takes them from the altstack: at each step
+    /// of the loop, the double lines of all the points, then their add lines.
+    pub fn multi_hinted_line_coeffs(points: &[G2Affine<F::Config>]) -> Vec<HintedLine<F>> {
+        utils::collect_line_coeffs(
+            points
+                .iter()
+                .copied()
+                .map(G2Prepared::<F::Config>::from_affine)
+                .collect(),
+        )
+        .into_iter()
+        .flat_map(|line_coeffs| {
+            let num_phases = line_coeffs.first().map_or(0, |lines| lines.len());
+            (0..num_phases)
+                .flat_map(|phase| line_coeffs.iter().map(move |lines| lines[phase]))
+                .collect::<Vec<_>>()
+        })
+        .map(|(_, c3, c4)| (c3, c4))
+        .collect()
+    }
+
+    /// Number of lines returned by `Pairing::hinted_line_coeffs`, the same for any Q4, and by
+    /// `Pairing::multi_hinted_line_coeffs` for each point.
+    pub fn num_hinted_lines() -> usize {
+        let bits = loop_bits::<F::Config>();
+        bits.len() + bits.iter().filter(|bit| **bit).count()
+    }
+
+    /// The product of the miller loops over |x| the scripts above compute, with the same affine
+    /// lines, in place of the miller loop of arkworks: the lines of arkworks are scaled
+    /// differently, which the final exponentiation hides but the hints c and wi don't. The
+    /// conjugate of the result has the same final exponentiation as the miller loop of arkworks.
+    pub fn multi_miller_loop_affine(
+        p: &[G1Affine<F::Config>],
+        q: &[G2Affine<F::Config>],
+    ) -> Fp12<F::Fp12Config> {
+        assert_eq!(p.len(), q.len());
+        let line_coeffs = utils::collect_line_coeffs(
+            q.iter()
+                .copied()
+                .map(G2Prepared::<F::Config>::from_affine)
+                .collect(),
+        );
+        // P' = (-P.x / P.y, 1 / P.y)
+        let p = p
+            .iter()
+            .map(|p| {
+                let y_inv = p.y().unwrap().inverse().unwrap();
+                (-p.x * y_inv, y_inv)
+            })
+            .collect::<Vec<_>>();
+
+        // (y' * c4) + (x' * c3) * v + v * w, see `utils::ell_by_constant_affine`
+        let line = |(x, y): (F::Fp, F::Fp), coeff: &EllCoeff<F::Config>| {
+            let mut c3 = coeff.1;
+            c3.mul_assign_by_fp(&x);
+            let mut c4 = coeff.2;
+            c4.mul_assign_by_fp(&y);
+            Fp12::new(
+                Fp6::new(c4, c3, Fp2::ZERO),
+                Fp6::new(Fp2::ZERO, Fp2::ONE, Fp2::ZERO),
+            )
+        };
+
+        let mut f = Fp12::<F::Fp12Config>::ONE;
+        for lines in line_coeffs {
+            f.square_in_place();
+            for phase in 0..lines[0].len() {
+                for (j, point_lines) in lines.iter().enumerate() {
+                    f *= line(p[j], &point_lines[phase]);
+                }
+            }
+        }
+        f
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::bls12_381::ell_coeffs::G2Prepared;
+    use crate::bls12_381::fp381impl::Fp381Impl;
+    use crate::bls12_381::fq::Fq;
+    use crate::bls12_381::fq12::Fq12;
+    use crate::bls12_381::offchain_checker::{compute_c_wi, lambda};
+    use crate::bls12_381::pairing::Pairing;
+    use crate::bls12_381::utils::{self, fq12_push, fq2_push, fq6_push};
+    use crate::execute_script_without_stack_limit;
+    use crate::treepp::*;
+    use ark_bls12_381::Bls12_381;
+    use ark_ec::pairing::{MillerLoopOutput, Pairing as ArkPairing};
+    use ark_ec::CurveGroup;
+    use ark_ff::{AdditiveGroup, Field, UniformRand};
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+    use std::ops::Neg;
+
+    #[test]
+    fn test_multi_miller_loop_affine() {
+        let mut prng = ChaCha20Rng::seed_from_u64(0);
+
+        let p = [
+            ark_bls12_381::G1Affine::rand(&mut prng),
+            ark_bls12_381::G1Affine::rand(&mut prng),
+        ];
+        let q = [
+            ark_bls12_381::G2Affine::rand(&mut prng),
+            ark_bls12_381::G2Affine::rand(&mut prng),
+        ];
+
+        let mut f = Pairing::multi_miller_loop_affine(&p, &q);
+        f.conjugate_in_place();
+        assert_eq!(
+            Bls12_381::final_exponentiation(MillerLoopOutput(f)).unwrap(),
+            Bls12_381::multi_pairing(p, q)
+        );
+    }
+
+    #[test]
+    fn test_compute_c_wi() {
+        let mut prng = ChaCha20Rng::seed_from_u64(0);
+
+        // e(P, Q) * e(-s * P, Q / s) = 1
+        let p = ark_bls12_381::G1Affine::rand(&mut prng);
+        let q = ark_bls12_381::G2Affine::rand(&mut prng);
+        let s = ark_bls12_381::Fr::rand(&mut prng);
+        let p2 = (p * s).into_affine().neg();
+        let q2 = (q * s.inverse().unwrap()).into_affine();
+
+        let f = Pairing::multi_miller_loop_affine(&[p, p2], &[q, q2]);
+        let (c, wi) = compute_c_wi(f);
+        let wi = ark_bls12_381::Fq12::new(wi, ark_bls12_381::Fq6::ZERO);
+        assert_eq!(c.pow(lambda().to_u64_digits()), f * wi);
+    }
+
+    // The miller loop of two pairings of product one, a fixed one and a hinted one.
+    #[test]
+    fn test_dual_miller_loop_with_c_wi_and_hinted_lines() {
+        let mut prng = ChaCha20Rng::seed_from_u64(0);
+
+        let p = ark_bls12_381::G1Affine::rand(&mut prng);
+        let q = ark_bls12_381::G2Affine::rand(&mut prng);
+        let s = ark_bls12_381::Fr::rand(&mut prng);
+        let p2 = (p * s).into_affine().neg();
+        let q2 = (q * s.inverse().unwrap()).into_affine();
+
+        let f = Pairing::multi_miller_loop_affine(&[p, p2], &[q, q2]);
+        let (c, wi) = compute_c_wi(f);
+        let c_inv = c.inverse().unwrap();
+
+        let script = script! {
+            { utils::from_eval_point(p) }
+            { utils::from_eval_point(p2) }
+            { fq2_push(q2.x) }
+            { fq2_push(q2.y) }
+            { fq12_push(c_inv) }
+            { fq6_push(wi) }
+            { fq2_push(q2.x) }
+            { fq2_push(q2.y) }
+            for (c3, c4) in Pairing::hinted_line_coeffs(q2).into_iter().rev() {
+                { fq2_push(c4) }
+                { Fq::toaltstack() }
+                { Fq::toaltstack() }
+                { fq2_push(c3) }
+                { Fq::toaltstack() }
+                { Fq::toaltstack() }
+            }
+            { Pairing::multi_miller_loop_with_c_wi_and_hinted_lines(vec![G2Prepared::from_affine(q)], 1) }
+            { Fq12::push_one() }
+            { Fq12::equalverify() }
+            OP_TRUE
+        };
+        println!(
+            "Pairing.multi_miller_loop_with_c_wi_and_hinted_lines: {} bytes",
+            script.len()
+        );
+        let exec_result = execute_script_without_stack_limit(script);
+        assert!(exec_result.success);
+    }
+}
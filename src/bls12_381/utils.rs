@@ -0,0 +1,405 @@
+use crate::bls12_381::base_field::Bls12BaseField;
+use crate::bls12_381::ell_coeffs::{loop_bits, EllCoeff, G2Prepared};
+use crate::bls12_381::fp381impl::Fp381Impl;
+use crate::bls12_381::fq::Fq;
+use crate::bls12_381::fq12::GenericFq12;
+use crate::bls12_381::fq2::GenericFq2;
+use crate::treepp::*;
+use ark_ec::bls12::Bls12Config;
+use ark_ec::AffineRepr;
+use ark_ff::{Field, Fp2};
+use num_bigint::BigUint;
+
+pub fn fq_push(element: ark_bls12_381::Fq) -> Script {
+    script! {
+        { Fq::push_u32_le(&BigUint::from(element).to_u32_digits()) }
+    }
+}
+
+pub fn fq2_push(element: ark_bls12_381::Fq2) -> Script {
+    script! {
+        { Fq::push_u32_le(&BigUint::from(element.c0).to_u32_digits()) }
+        { Fq::push_u32_le(&BigUint::from(element.c1).to_u32_digits()) }
+    }
+}
+
+pub fn fq6_push(element: ark_bls12_381::Fq6) -> Script {
+    script! {
+        for elem in element.to_base_prime_field_elements() {
+            { Fq::push_u32_le(&BigUint::from(elem).to_u32_digits()) }
+        }
+    }
+}
+
+pub fn fq12_push(element: ark_bls12_381::Fq12) -> Script {
+    script! {
+        for elem in element.to_base_prime_field_elements() {
+            { Fq::push_u32_le(&BigUint::from(elem).to_u32_digits()) }
+        }
+    }
+}
+
+// Stack items of the fields and points as pushed by the functions above, for witnesses.
+pub fn fq_witness(element: ark_bls12_381::Fq) -> Vec<Vec<u8>> {
+    Fq::witness_u32_le(&BigUint::from(element).to_u32_digits())
+}
+
+pub fn fq2_witness(element: ark_bls12_381::Fq2) -> Vec<Vec<u8>> {
+    [fq_witness(element.c0), fq_witness(element.c1)].concat()
+}
+
+pub fn fq6_witness(element: ark_bls12_381::Fq6) -> Vec<Vec<u8>> {
+    element
+        .to_base_prime_field_elements()
+        .flat_map(fq_witness)
+        .collect()
+}
+
+pub fn fq12_witness(element: ark_bls12_381::Fq12) -> Vec<Vec<u8>> {
+    element
+        .to_base_prime_field_elements()
+        .flat_map(fq_witness)
+        .collect()
+}
+
+/// Stack items of the affine point, [x, y].
+pub fn g1_affine_witness(point: ark_bls12_381::G1Affine) -> Vec<Vec<u8>> {
+    [fq_witness(point.x), fq_witness(point.y)].concat()
+}
+
+/// Stack items of the affine point, [x.c0, x.c1, y.c0, y.c1].
+pub fn g2_affine_witness(point: ark_bls12_381::G2Affine) -> Vec<Vec<u8>> {
+    [fq2_witness(point.x), fq2_witness(point.y)].concat()
+}
+
+// input:
+//  f            12 elements
+//  x': -p.x / p.y   1 element
+//  y': 1 / p.y      1 element
+// func params:
+//  (c0, c1, c2) where c0 is a trival value ONE in affine mode
+//
+// output:
+//  new f        12 elements
+//
+// The twist is an M-type one: scaled by 1 / p.y, the line evaluated at p is
+// (y' * c2) + (x' * c1) * v + v * w.
+pub fn ell_by_constant_affine<F: Bls12BaseField>(constant: &EllCoeff<F::Config>) -> Script {
+    assert_eq!(constant.0, Fp2::ONE);
+    script! {
+        // [f, x', y']
+        // update c1, c1' = x' * c1
+        { F::copy(1) }
+        { F::mul_by_constant(&constant.1.c0) }
+        // [f, x', y', x' * c1.0]
+        { F::roll(2) }
+        { F::mul_by_constant(&constant.1.c1) }
+        // [f, y', x' * c1.0, x' * c1.1]
+        // [f, y', x' * c1]
+
+        // update c2, c2' = y' * c2
+        { F::copy(2) }
+        { F::mul_by_constant(&constant.2.c0) }
+        // [f, y', x' * c1, y' * c2.0]
+        { F::roll(3) }
+        { F::mul_by_constant(&constant.2.c1) }
+        // [f, x' * c1, y' * c2]
+        { GenericFq2::<F>::roll(2) }
+        // [f, c2', c1']
+
+        { GenericFq12::<F>::mul_by_01() }
+        // [f]
+    }
+}
+
+/// Line coefficients of the points, for each step of the miller loop: for each point, the line
+/// of the doubling step and, if the bit of the step is set, the line of the addition step.
+pub fn collect_line_coeffs<P: Bls12Config>(
+    constants: Vec<G2Prepared<P>>,
+) -> Vec<Vec<Vec<EllCoeff<P>>>> {
+    let mut constant_iters = constants
+        .iter()
+        .map(|item| item.ell_coeffs.iter())
+        .collect::<Vec<_>>();
+    let mut all_line_coeffs = vec![];
+
+    for bit in loop_bits::<P>() {
+        let mut line_coeffs = vec![];
+        for constant_iter in constant_iters.iter_mut() {
+            // double line coeff
+            let mut line_coeff = vec![*constant_iter.next().unwrap()];
+            // add line coeff
+            if bit {
+                line_coeff.push(*constant_iter.next().unwrap());
+            }
+            // line coeff for single point
+            line_coeffs.push(line_coeff);
+        }
+        // line coeffs for all points
+        all_line_coeffs.push(line_coeffs);
+    }
+    for constant_iter in constant_iters.iter_mut() {
+        assert_eq!(constant_iter.next(), None);
+    }
+    all_line_coeffs
+}
+
+/// input of func (params):
+///      p.x, p.y
+/// output on stack:
+///      x' = -p.x / p.y
+///      y' = 1 / p.y
+pub fn from_eval_point(p: ark_bls12_381::G1Affine) -> Script {
+    let py_inv = p.y().unwrap().inverse().unwrap();
+    script! {
+        { fq_push(py_inv) }
+        // [1/y]
+        // -p.x / p.y
+        { Fq::copy(0) }
+        { Fq::mul_by_constant(&-p.x) }
+        // [1/y, -x/y]
+        { Fq::roll(1) }
+        // [-x/y, 1/y]
+    }
+}
+
+/// input of stack:
+///      p.x, p.y (affine space)
+/// output on stack:
+///      x' = -p.x / p.y
+///      y' = 1 / p.y
+pub fn from_eval_point_in_stack() -> Script {
+    script! {
+        // [x, y]
+        { Fq::copy(0) }
+        { Fq::copy(0) }
+        { Fq::inv() }
+        // [x, y, y, 1/y]
+        // check p.y.inv() is valid
+        { Fq::mul() }
+        { Fq::push_one() }
+        { Fq::equalverify(1, 0) }
+        // [x, y]
+        { Fq::inv() }
+        // [x, 1/y]
+
+        // -p.x / p.y
+        { Fq::copy(0) }
+        { Fq::roll(2) }
+        { Fq::neg(0) }
+        { Fq::mul() }
+        // [1/y, -x/y]
+        { Fq::roll(1) }
+        // [-x/y, 1/y]
+    }
+}
+
+// stack input:
+//  f            12 elements
+//  x': -p.x / p.y   1 element
+//  y': 1 / p.y      1 element
+//  c3           2 elements
+//  c4           2 elements
+//
+// output:
+//  new f        12 elements
+//
+// Same as `ell_by_constant_affine`, with the line coefficients (alpha, -bias) on the stack.
+pub fn ell_affine_in_stack<F: Bls12BaseField>() -> Script {
+    script! {
+        // [f, x', y', c3, c4]
+        // update c3, c3' = x' * c3
+        { F::copy(5) }
+        { F::roll(4) }
+        { F::mul() }
+        // [f, x', y', c3.1, c4, x' * c3.0]
+        { F::roll(5) }
+        { F::roll(4) }
+        { F::mul() }
+        // [f, y', c4, x' * c3]
+        { GenericFq2::<F>::toaltstack() }
+
+        // update c4, c4' = y' * c4
+        { F::copy(2) }
+        { F::roll(2) }
+        { F::mul() }
+        // [f, y', c4.1, y' * c4.0]
+        { F::roll(2) }
+        { F::roll(2) }
+        { F::mul() }
+        // [f, y' * c4]
+        { GenericFq2::<F>::fromaltstack() }
+        // [f, c4', c3']
+
+        { GenericFq12::<F>::mul_by_01() }
+        // [f]
+    }
+}
+
+/// same as `bn254::utils::affine_add_line_in_stack`
+///
+/// input on stack:
+///     T.x (2 elements)
+///     Q.x (2 elements)
+///     c3 (2 elements)
+///     c4 (2 elements)
+///
+/// output on stack:
+///     T'.x (2 elements)
+///     T'.y (2 elements)
+pub fn affine_add_line_in_stack<F: Bls12BaseField>() -> Script {
+    script! {
+        // [T.x, Q.x, alpha, -bias]
+        { GenericFq2::<F>::roll(6) }
+        { GenericFq2::<F>::roll(6) }
+        // [alpha, -bias, T.x, Q.x]
+        { GenericFq2::<F>::add(2, 0) }
+        { GenericFq2::<F>::neg(0) }
+        // [alpha, -bias, -T.x - Q.x]
+        { affine_line_in_stack::<F>() }
+        // [x', y']
+    }
+}
+
+/// same as `bn254::utils::affine_double_line_in_stack`
+///
+/// input on stack:
+///     T.x (2 elements)
+///     c3 (2 elements)
+///     c4 (2 elements)
+///
+/// output on stack:
+///     T'.x (2 elements)
+///     T'.y (2 elements)
+pub fn affine_double_line_in_stack<F: Bls12BaseField>() -> Script {
+    script! {
+        // [T.x, alpha, -bias]
+        { GenericFq2::<F>::roll(4) }
+        { GenericFq2::<F>::double(0) }
+        { GenericFq2::<F>::neg(0) }
+        // [alpha, -bias, - 2 * T.x]
+        { affine_line_in_stack::<F>() }
+        // [x', y']
+    }
+}
+
+// input on stack: [alpha, -bias, s], output on stack: [x', y'] where
+//     x' = alpha^2 + s
+//     y' = -bias - alpha * x'
+fn affine_line_in_stack<F: Bls12BaseField>() -> Script {
+    script! {
+        { GenericFq2::<F>::copy(4) }
+        { GenericFq2::<F>::square() }
+        { GenericFq2::<F>::add(2, 0) }
+        // [alpha, -bias, x']
+        { GenericFq2::<F>::copy(0) }
+        { GenericFq2::<F>::roll(6) }
+        { GenericFq2::<F>::mul(2, 0) }
+        { GenericFq2::<F>::neg(0) }
+        // [-bias, x', -alpha * x']
+        { GenericFq2::<F>::roll(4) }
+        { GenericFq2::<F>::add(2, 0) }
+        // [x', y']
+    }
+}
+
+/// check line through one point, that is:
+///     y - alpha * x - bias = 0
+///
+/// input on stack:
+///     x (2 elements)
+///     y (2 elements)
+///     c3 (2 elements)
+///     c4 (2 elements)
+///
+/// output:
+///     true or false (consumed on stack)
+pub fn check_line_through_point_in_stack<F: Bls12BaseField>() -> Script {
+    script! {
+        // [x, y, alpha, -bias]
+        { GenericFq2::<F>::roll(6) }
+        { GenericFq2::<F>::roll(4) }
+        // [y, -bias, x, alpha]
+        { GenericFq2::<F>::mul(2, 0) }
+        { GenericFq2::<F>::neg(0) }
+        // [y, -bias, -alpha * x]
+        { GenericFq2::<F>::add(2, 0) }
+        { GenericFq2::<F>::add(2, 0) }
+        // [y - alpha * x - bias]
+
+        { GenericFq2::<F>::push_zero() }
+        { GenericFq2::<F>::equalverify() }
+    }
+}
+
+/// check whether a tuple coefficient (alpha, -bias) of a tangent line is satisfied with expected
+/// point T (affine)
+///     1. alpha * (2 * T.y) = 3 * T.x^2, make sure the alpha is the right ONE
+///     2. T.y - alpha * T.x - bias = 0, make sure the -bias is the right ONE
+///
+/// input on stack:
+///     T.x (2 elements)
+///     T.y (2 elements)
+///     c3 (2 elements)
+///     c4 (2 elements)
+///
+/// output:
+///     true or false (consumed on stack)
+pub fn check_tangent_line_in_stack<F: Bls12BaseField>() -> Script {
+    script! {
+        // alpha * (2 * T.y) = 3 * T.x^2
+        { GenericFq2::<F>::copy(4) }
+        { GenericFq2::<F>::double(0) }
+        { GenericFq2::<F>::copy(4) }
+        { GenericFq2::<F>::mul(2, 0) }
+        // [T.x, T.y, alpha, -bias, alpha * (2 * T.y)]
+        { GenericFq2::<F>::copy(8) }
+        { GenericFq2::<F>::square() }
+        { GenericFq2::<F>::copy(0) }
+        { GenericFq2::<F>::double(0) }
+        { GenericFq2::<F>::add(2, 0) }
+        // [T.x, T.y, alpha, -bias, alpha * (2 * T.y), 3 * T.x^2]
+        { GenericFq2::<F>::neg(0) }
+        { GenericFq2::<F>::add(2, 0) }
+        { GenericFq2::<F>::push_zero() }
+        { GenericFq2::<F>::equalverify() }
+        // [T.x, T.y, alpha, -bias]
+
+        // check: T.y - alpha * T.x - bias = 0
+        { check_line_through_point_in_stack::<F>() }
+        // []
+    }
+}
+
+/// check whether a tuple coefficient (alpha, -bias) of a chord line is satisfied with expected
+/// points T and Q (both are affine cooordinates)
+///     1. T.y - alpha * T.x - bias = 0
+///     2. Q.y - alpha * Q.x - bias = 0, make sure the alpha/-bias are the right ONEs
+///
+/// input on stack:
+///     T.x (2 elements)
+///     T.y (2 elements)
+///     Q.x (2 elements)
+///     Q.y (2 elements)
+///     c3 (2 elements)
+///     c4 (2 elements)
+///
+/// output:
+///     true or false (consumed on stack)
+pub fn check_chord_line_in_stack<F: Bls12BaseField>() -> Script {
+    script! {
+        // [T.x, T.y, Q.x, Q.y, alpha, -bias]
+        { GenericFq2::<F>::copy(2) }
+        { GenericFq2::<F>::copy(2) }
+        { GenericFq2::<F>::toaltstack() }
+        { GenericFq2::<F>::toaltstack() }
+        // check: Q.y - alpha * Q.x - bias = 0
+        { check_line_through_point_in_stack::<F>() }
+        // [T.x, T.y | alpha, -bias]
+        { GenericFq2::<F>::fromaltstack() }
+        { GenericFq2::<F>::fromaltstack() }
+        // check: T.y - alpha * T.x - bias = 0
+        { check_line_through_point_in_stack::<F>() }
+        // []
+    }
+}
@@ -701,6 +701,89 @@ impl G1Affine {
             OP_ENDIF
         }
     }
+
+    // Inverse of `convert_to_compressed`
+    // Input Stack: [b_31, ..., b_0], the most significant byte b_0 on top
+    // Output Stack: [x,y]
+    // Fails unless x is a field element and the x coordinate of a point on the curve.
+    pub fn decompress() -> Script {
+        script! {
+            // split the flag of y >= (q + 1) / 2 off the most significant byte
+            OP_DUP { 0x80 } OP_GREATERTHANOREQUAL
+            OP_DUP OP_TOALTSTACK
+            OP_IF
+                { 0x80 } OP_SUB
+            OP_ENDIF
+            { Fq::convert_from_be_bytes() }
+            { G1Affine::y_from_x_and_flag() }
+        }
+    }
+
+    // Same as `decompress` for a point packed as [x, flag], in 10 stack items instead of 32 bytes,
+    // with the flag of `G2Affine::decompress`: 1 iff y >= (q + 1) / 2.
+    // Input Stack: [x, flag]
+    // Output Stack: [x,y]
+    // Fails unless x is a canonical field element and the x coordinate of a point on the curve.
+    pub fn decompress_packed() -> Script {
+        script! {
+            OP_DUP 0 2 OP_WITHIN OP_VERIFY
+            OP_TOALTSTACK
+            { Fq::copy(0) }
+            { Fq::is_canonical() }
+            OP_VERIFY
+            { G1Affine::y_from_x_and_flag() }
+        }
+    }
+
+    // Input Stack: [x] | altstack: [flag]
+    // Output Stack: [x,y]
+    fn y_from_x_and_flag() -> Script {
+        script! {
+            // compute x^3 + 3 and its square root
+            { Fq::copy(0) }
+            { Fq::square() }
+            { Fq::copy(1) }
+            { Fq::mul() }
+            { Fq::push_hex("3") }
+            { Fq::add(1, 0) }
+            { Fq::copy(0) }
+            { Fq::sqrt() }
+            { Fq::copy(0) }
+            { Fq::square() }
+            { Fq::roll(2) }
+            { Fq::equalverify(1, 0) }
+
+            // negate y if it doesn't match the flag
+            { Fq::copy(0) }
+            { Fq::decode_montgomery() }
+            { U254::push_hex(Fq::P_PLUS_ONE_DIV2) }
+            { U254::greaterthanorequal(1, 0) }
+            OP_FROMALTSTACK
+            OP_EQUAL
+            OP_NOTIF
+                { Fq::neg(0) }
+            OP_ENDIF
+        }
+    }
+
+    // Checks a point received in a witness, say a proof point: its coordinates are canonical
+    // field elements and it is on the curve, hence in G1. The identity isn't valid.
+    // Input Stack: [x,y]
+    // Output Stack: [is_valid]
+    pub fn is_valid() -> Script {
+        script! {
+            { Fq::copy(1) }
+            { Fq::is_canonical() }
+            OP_TOALTSTACK
+            { Fq::copy(0) }
+            { Fq::is_canonical() }
+            OP_TOALTSTACK
+            { G1Affine::is_on_curve() }
+            OP_FROMALTSTACK OP_BOOLAND
+            OP_FROMALTSTACK OP_BOOLAND
+        }
+    }
+
     // Init stack: [x1,y1,x2,y2)
     pub fn equalverify() -> Script {
         script! {
@@ -717,6 +800,7 @@ impl G1Affine {
 #[cfg(test)]
 mod test {
 
+    use crate::bigint::U254;
     use crate::bn254::curves::{G1Affine, G1Projective};
     use crate::bn254::fq::Fq;
     use crate::treepp::{script, Script};
@@ -729,7 +813,7 @@ mod test {
     use ark_std::{end_timer, start_timer, UniformRand};
     use core::ops::{Add, Mul};
     use num_bigint::BigUint;
-    use num_traits::{One, Zero};
+    use num_traits::{Num, One, Zero};
     // use std::ops::Mul;
 
     use rand::SeedableRng;
@@ -1350,4 +1434,126 @@ mod test {
             assert!(exec_result.success);
         }
     }
+
+    #[test]
+    fn test_decompress() {
+        let decompress_script = G1Affine::decompress();
+        println!("G1.decompress: {} bytes", decompress_script.len());
+
+        let mut prng = ChaCha20Rng::seed_from_u64(0);
+
+        for _ in 0..3 {
+            let p = ark_bn254::G1Affine::rand(&mut prng);
+
+            for p in [p, p.neg()] {
+                let script = script! {
+                    { g1_affine_push(p) }
+                    { G1Affine::convert_to_compressed() }
+                    { decompress_script.clone() }
+                    { g1_affine_push(p) }
+                    { G1Affine::equalverify() }
+                    OP_TRUE
+                };
+                let exec_result = execute_script(script);
+                assert!(exec_result.success);
+            }
+        }
+
+        // x^3 + 3 isn't a square
+        let mut x = ark_bn254::Fq::rand(&mut prng);
+        while (x.square() * x + ark_bn254::Fq::from(3)).legendre().is_qr() {
+            x += ark_bn254::Fq::ONE;
+        }
+        let bytes = x.into_bigint().to_bytes_be();
+        let script = script! {
+            for i in (0..32).rev() {
+                { bytes[i] }
+            }
+            { decompress_script.clone() }
+            { Fq::drop() }
+            { Fq::drop() }
+            OP_TRUE
+        };
+        let exec_result = execute_script(script);
+        assert!(!exec_result.success);
+    }
+
+    #[test]
+    fn test_decompress_packed() {
+        let decompress_script = G1Affine::decompress_packed();
+        println!("G1.decompress_packed: {} bytes", decompress_script.len());
+
+        let mut prng = ChaCha20Rng::seed_from_u64(0);
+
+        for _ in 0..3 {
+            let p = ark_bn254::G1Affine::rand(&mut prng);
+
+            for p in [p, p.neg()] {
+                let script = script! {
+                    { Fq::push_u32_le(&BigUint::from(p.x).to_u32_digits()) }
+                    { (p.y > -p.y) as u32 }
+                    { decompress_script.clone() }
+                    { g1_affine_push(p) }
+                    { G1Affine::equalverify() }
+                    OP_TRUE
+                };
+                let exec_result = execute_script(script);
+                assert!(exec_result.success);
+            }
+        }
+
+        // the flag is a bit
+        let p = ark_bn254::G1Affine::rand(&mut prng);
+        let script = script! {
+            { Fq::push_u32_le(&BigUint::from(p.x).to_u32_digits()) }
+            2
+            { decompress_script.clone() }
+            { Fq::drop() }
+            { Fq::drop() }
+            OP_TRUE
+        };
+        let exec_result = execute_script(script);
+        assert!(!exec_result.success);
+    }
+
+    #[test]
+    fn test_affine_is_valid() {
+        let is_valid_script = G1Affine::is_valid();
+        println!("G1.is_valid: {} bytes", is_valid_script.len());
+
+        let mut prng = ChaCha20Rng::seed_from_u64(0);
+        let p = ark_bn254::G1Affine::rand(&mut prng);
+
+        let script = script! {
+            { g1_affine_push(p) }
+            { is_valid_script.clone() }
+        };
+        let exec_result = execute_script(script);
+        assert!(exec_result.success);
+
+        let script = script! {
+            { g1_affine_push(p) }
+            { Fq::double(0) }
+            { is_valid_script.clone() }
+            OP_NOT
+        };
+        let exec_result = execute_script(script);
+        assert!(exec_result.success);
+
+        // y + q is the same field element, but not a canonical one
+        let q = BigUint::from_str_radix(Fq::MODULUS, 16).unwrap();
+        let r = BigUint::from_str_radix(Fq::MONTGOMERY_ONE, 16).unwrap();
+        let mut p = p;
+        while (BigUint::from(p.y).mul(&r) % &q + &q).bits() > 254 {
+            p = ark_bn254::G1Affine::rand(&mut prng);
+        }
+        let script = script! {
+            { Fq::push_u32_le(&BigUint::from(p.x).to_u32_digits()) }
+            { U254::push_u32_le(&(BigUint::from(p.y).mul(&r) % &q + &q).to_u32_digits()) }
+            { is_valid_script.clone() }
+            OP_NOT
+        };
+        let exec_result = execute_script(script);
+        assert!(exec_result.success);
+    }
 }
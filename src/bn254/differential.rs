@@ -0,0 +1,1345 @@
+// Differential testing of the script gadgets against their arkworks reference.
+//
+// A gadget is run on randomized inputs given as witness stack items, and must leave exactly the
+// stack items of the reference result: all of its inputs consumed, nothing else left behind. The
+// comparison is done in script so that a gadget failing half way, or leaving extra items, is
+// caught as well. Its script size and maximum stack usage are checked against limits
+// kept next to its test.
+use crate::execute_script_with_witness_without_stack_limit;
+use crate::treepp::*;
+use core::fmt;
+use rand::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+
+/// Checked-in bounds on the cost of a gadget, so that a change growing a hand-optimized gadget
+/// fails its differential test instead of going unnoticed.
+#[derive(Clone, Copy, Debug)]
+pub struct Limits {
+    pub script_size: usize,
+    /// Bound on the maximum number of stack items of a run, inputs included.
+    pub max_stack_items: usize,
+}
+
+/// One randomized run of a gadget.
+pub struct Case {
+    /// Stack items the gadget consumes, the first one at the bottom of the stack.
+    pub inputs: Vec<Vec<u8>>,
+    /// Stack items the reference says the gadget leaves, the first one at the bottom of the stack.
+    pub outputs: Vec<Vec<u8>>,
+}
+
+#[derive(Clone, Debug)]
+pub struct GadgetReport {
+    pub name: String,
+    pub runs: usize,
+    pub script_size: usize,
+    /// Maximum number of stack items over all the runs, inputs included.
+    pub max_stack_items: usize,
+}
+
+impl fmt::Display for GadgetReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} bytes, {} max stack items ({} runs)",
+            self.name, self.script_size, self.max_stack_items, self.runs
+        )
+    }
+}
+
+/// Runs `gadget` on `runs` cases drawn by `case` from a PRNG seeded with `seed`, and panics on the
+/// first one where the gadget doesn't leave exactly the reference outputs on the stack, or if the
+/// gadget exceeds `limits`.
+pub fn check_gadget<F>(
+    name: &str,
+    gadget: Script,
+    limits: Limits,
+    seed: u64,
+    runs: usize,
+    case: F,
+) -> GadgetReport
+where
+    F: FnMut(&mut ChaCha20Rng) -> Case,
+{
+    check_gadget_with_normalization(name, gadget, script! {}, limits, seed, runs, case)
+}
+
+/// Same as `check_gadget`, but `normalize` is run on the gadget's outputs before the comparison,
+/// e.g. `G1Projective::into_affine` when the gadget's projective coordinates don't have to match
+/// arkworks' ones. `normalize` isn't counted in the reported script size.
+pub fn check_gadget_with_normalization<F>(
+    name: &str,
+    gadget: Script,
+    normalize: Script,
+    limits: Limits,
+    seed: u64,
+    runs: usize,
+    mut case: F,
+) -> GadgetReport
+where
+    F: FnMut(&mut ChaCha20Rng) -> Case,
+{
+    let mut prng = ChaCha20Rng::seed_from_u64(seed);
+    let mut report = GadgetReport {
+        name: name.to_string(),
+        runs,
+        script_size: gadget.len(),
+        max_stack_items: 0,
+    };
+
+    for run in 0..runs {
+        let Case { inputs, outputs } = case(&mut prng);
+
+        let exec_result =
+            execute_script_with_witness_without_stack_limit(gadget.clone(), inputs.clone());
+        report.max_stack_items = report
+            .max_stack_items
+            .max(exec_result.stats.max_nb_stack_items);
+
+        let script = script! {
+            { gadget.clone() }
+            { normalize.clone() }
+            for output in outputs.iter() {
+                { output.clone() }
+            }
+            { equalverify_items(outputs.len()) }
+            OP_DEPTH
+            OP_NOT
+        };
+        let exec_result = execute_script_with_witness_without_stack_limit(script, inputs);
+        assert!(
+            exec_result.success,
+            "{}: run {} of seed {} doesn't match the reference, expected {} items:\n{}",
+            name,
+            run,
+            seed,
+            outputs.len(),
+            exec_result
+        );
+    }
+
+    assert!(
+        report.script_size <= limits.script_size,
+        "{}: {} bytes exceed the limit of {} bytes",
+        name,
+        report.script_size,
+        limits.script_size
+    );
+    assert!(
+        report.max_stack_items <= limits.max_stack_items,
+        "{}: {} stack items exceed the limit of {} stack items",
+        name,
+        report.max_stack_items,
+        limits.max_stack_items
+    );
+
+    report
+}
+
+// Input Stack: [a_0, ..., a_{n-1}, b_0, ..., b_{n-1}]
+// Output Stack: []
+// Fails unless a_i == b_i for every i.
+fn equalverify_items(n: usize) -> Script {
+    script! {
+        for i in 0..n {
+            { n - i } OP_ROLL OP_EQUALVERIFY
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::bigint::std::script_num;
+    use crate::bn254::curves::{G1Affine, G1Projective};
+    use crate::bn254::ell_coeffs::G2Prepared;
+    use crate::bn254::fp254impl::Fp254Impl;
+    use crate::bn254::fq::Fq;
+    use crate::bn254::fq12::Fq12;
+    use crate::bn254::fq2::Fq2;
+    use crate::bn254::fq6::Fq6;
+    use crate::bn254::fr::Fr;
+    use crate::bn254::pairing::Pairing;
+    use crate::bn254::utils::{
+        ell_by_constant, fq12_witness, fq2_witness, fq6_witness, fq_witness, g1_affine_witness,
+    };
+    use crate::groth16::constants::{LAMBDA, P_POW3};
+    use ark_bn254::Bn254;
+    use ark_ec::pairing::Pairing as ArkPairing;
+    use ark_ec::{AffineRepr, CurveGroup};
+    use ark_ff::{
+        AdditiveGroup, BigInteger, CyclotomicMultSubgroup, Field, Fp12Config, Fp6Config,
+        PrimeField, UniformRand,
+    };
+    use num_bigint::BigUint;
+    use std::ops::Mul;
+    use std::str::FromStr;
+
+    // Measured sizes and stack maxima of the gadgets, to be updated together with a change to a
+    // gadget that changes them.
+    const FQ_ADD_LIMITS: Limits = Limits {
+        script_size: 429,
+        max_stack_items: 22,
+    };
+    const FQ_SUB_LIMITS: Limits = Limits {
+        script_size: 445,
+        max_stack_items: 22,
+    };
+    const FQ_DOUBLE_LIMITS: Limits = Limits {
+        script_size: 389,
+        max_stack_items: 21,
+    };
+    const FQ_NEG_LIMITS: Limits = Limits {
+        script_size: 194,
+        max_stack_items: 12,
+    };
+    const FQ_MUL_LIMITS: Limits = Limits {
+        script_size: 140_650,
+        max_stack_items: 126,
+    };
+    const FQ_SQUARE_LIMITS: Limits = Limits {
+        script_size: 136_747,
+        max_stack_items: 123,
+    };
+    const FQ_INV_LIMITS: Limits = Limits {
+        script_size: 5_414_612,
+        max_stack_items: 265,
+    };
+    const FQ_DIV2_LIMITS: Limits = Limits {
+        script_size: 4_666,
+        max_stack_items: 40,
+    };
+    const FQ_DIV3_LIMITS: Limits = Limits {
+        script_size: 6_126,
+        max_stack_items: 50,
+    };
+    const FQ_MUL_BY_CONSTANT_LIMITS: Limits = Limits {
+        script_size: 105_898,
+        max_stack_items: 106,
+    };
+    const FQ2_ADD_LIMITS: Limits = Limits {
+        script_size: 874,
+        max_stack_items: 40,
+    };
+    const FQ2_SUB_LIMITS: Limits = Limits {
+        script_size: 906,
+        max_stack_items: 40,
+    };
+    const FQ2_DOUBLE_LIMITS: Limits = Limits {
+        script_size: 832,
+        max_stack_items: 30,
+    };
+    const FQ2_TRIPLE_LIMITS: Limits = Limits {
+        script_size: 1_760,
+        max_stack_items: 48,
+    };
+    const FQ2_NEG_LIMITS: Limits = Limits {
+        script_size: 442,
+        max_stack_items: 21,
+    };
+    const FQ2_MUL_LIMITS: Limits = Limits {
+        script_size: 424_313,
+        max_stack_items: 171,
+    };
+    const FQ2_MUL_BY_FQ_LIMITS: Limits = Limits {
+        script_size: 281_399,
+        max_stack_items: 144,
+    };
+    const FQ2_MUL_BY_CONSTANT_LIMITS: Limits = Limits {
+        script_size: 319_741,
+        max_stack_items: 133,
+    };
+    const FQ2_SQUARE_LIMITS: Limits = Limits {
+        script_size: 282_714,
+        max_stack_items: 162,
+    };
+    const FQ2_INV_LIMITS: Limits = Limits {
+        script_size: 5_970_173,
+        max_stack_items: 283,
+    };
+    const FQ2_DIV2_LIMITS: Limits = Limits {
+        script_size: 9_386,
+        max_stack_items: 49,
+    };
+    const FQ2_DIV3_LIMITS: Limits = Limits {
+        script_size: 12_306,
+        max_stack_items: 59,
+    };
+    const FQ2_FROBENIUS_MAP_LIMITS: Limits = Limits {
+        script_size: 105_769,
+        max_stack_items: 115,
+    };
+    const FQ6_ADD_LIMITS: Limits = Limits {
+        script_size: 2_622,
+        max_stack_items: 112,
+    };
+    const FQ6_SUB_LIMITS: Limits = Limits {
+        script_size: 2_718,
+        max_stack_items: 112,
+    };
+    const FQ6_DOUBLE_LIMITS: Limits = Limits {
+        script_size: 2_496,
+        max_stack_items: 66,
+    };
+    const FQ6_NEG_LIMITS: Limits = Limits {
+        script_size: 1_326,
+        max_stack_items: 57,
+    };
+    const FQ6_MUL_LIMITS: Limits = Limits {
+        script_size: 2_235_703,
+        max_stack_items: 333,
+    };
+    const FQ6_MUL_FQ2_BY_NONRESIDUE_LIMITS: Limits = Limits {
+        script_size: 4_344,
+        max_stack_items: 49,
+    };
+    const FQ6_MUL_BY_FP2_LIMITS: Limits = Limits {
+        script_size: 1_273_263,
+        max_stack_items: 225,
+    };
+    const FQ6_MUL_BY_FP2_CONSTANT_LIMITS: Limits = Limits {
+        script_size: 959_385,
+        max_stack_items: 169,
+    };
+    const FQ6_MUL_BY_01_LIMITS: Limits = Limits {
+        script_size: 2_135_609,
+        max_stack_items: 297,
+    };
+    const FQ6_MUL_BY_01_WITH_1_CONSTANT_LIMITS: Limits = Limits {
+        script_size: 1_926_368,
+        max_stack_items: 279,
+    };
+    const FQ6_SQUARE_LIMITS: Limits = Limits {
+        script_size: 1_583_721,
+        max_stack_items: 243,
+    };
+    const FQ6_INV_LIMITS: Limits = Limits {
+        script_size: 10_655_440,
+        max_stack_items: 337,
+    };
+    const FQ6_FROBENIUS_MAP_LIMITS: Limits = Limits {
+        script_size: 958_404,
+        max_stack_items: 169,
+    };
+    const FQ12_ADD_LIMITS: Limits = Limits {
+        script_size: 5_334,
+        max_stack_items: 220,
+    };
+    const FQ12_SUB_LIMITS: Limits = Limits {
+        script_size: 5_526,
+        max_stack_items: 220,
+    };
+    const FQ12_DOUBLE_LIMITS: Limits = Limits {
+        script_size: 4_992,
+        max_stack_items: 120,
+    };
+    const FQ12_MUL_LIMITS: Limits = Limits {
+        script_size: 6_726_171,
+        max_stack_items: 603,
+    };
+    const FQ12_MUL_CPT_LIMITS: Limits = Limits {
+        script_size: 6_726_425,
+        max_stack_items: 549,
+    };
+    const FQ12_MUL_FQ6_BY_NONRESIDUE_LIMITS: Limits = Limits {
+        script_size: 4_452,
+        max_stack_items: 85,
+    };
+    const FQ12_MUL_BY_034_LIMITS: Limits = Limits {
+        script_size: 5_561_541,
+        max_stack_items: 459,
+    };
+    const FQ12_MUL_BY_034_WITH_4_CONSTANT_LIMITS: Limits = Limits {
+        script_size: 5_142_933,
+        max_stack_items: 441,
+    };
+    const FQ12_MUL_BY_34_LIMITS: Limits = Limits {
+        script_size: 4_288_305,
+        max_stack_items: 459,
+    };
+    const FQ12_SQUARE_LIMITS: Limits = Limits {
+        script_size: 4_494_590,
+        max_stack_items: 441,
+    };
+    const FQ12_CYCLOTOMIC_SQUARE_LIMITS: Limits = Limits {
+        script_size: 2_607_646,
+        max_stack_items: 387,
+    };
+    const FQ12_CYCLOTOMIC_INVERSE_LIMITS: Limits = Limits {
+        script_size: 1_326,
+        max_stack_items: 111,
+    };
+    const FQ12_INV_LIMITS: Limits = Limits {
+        script_size: 18_303_396,
+        max_stack_items: 445,
+    };
+    const FQ12_FROBENIUS_MAP_LIMITS: Limits = Limits {
+        script_size: 2_879_118,
+        max_stack_items: 223,
+    };
+    const FQ12_MOVE_TO_CYCLOTOMIC_LIMITS: Limits = Limits {
+        script_size: 34_550_002,
+        max_stack_items: 603,
+    };
+    const FQ12_CYCLOTOMIC_POW_BY_R_LIMITS: Limits = Limits {
+        script_size: 802_301_178,
+        max_stack_items: 819,
+    };
+    const G1_DOUBLE_LIMITS: Limits = Limits {
+        script_size: 971_407,
+        max_stack_items: 196,
+    };
+    const G1_ADD_LIMITS: Limits = Limits {
+        script_size: 2_237_736,
+        max_stack_items: 254,
+    };
+    const G1_NEG_LIMITS: Limits = Limits {
+        script_size: 248,
+        max_stack_items: 30,
+    };
+    const G1_INTO_AFFINE_LIMITS: Limits = Limits {
+        script_size: 5_973_791,
+        max_stack_items: 304,
+    };
+    const G1_SCALAR_MUL_LIMITS: Limits = Limits {
+        script_size: 416_285_342,
+        max_stack_items: 909,
+    };
+    const G1_AFFINE_IS_ON_CURVE_LIMITS: Limits = Limits {
+        script_size: 414_778,
+        max_stack_items: 141,
+    };
+    const G1_AFFINE_INTO_PROJECTIVE_LIMITS: Limits = Limits {
+        script_size: 44,
+        max_stack_items: 27,
+    };
+    const G1_AFFINE_CONVERT_TO_COMPRESSED_LIMITS: Limits = Limits {
+        script_size: 135_740,
+        max_stack_items: 129,
+    };
+    const ELL_BY_CONSTANT_LIMITS: Limits = Limits {
+        script_size: 5_567_753,
+        max_stack_items: 441,
+    };
+    const QUAD_MILLER_LOOP_WITH_C_WI_LIMITS: Limits = Limits {
+        script_size: 2_261_938_572,
+        max_stack_items: 4_257,
+    };
+
+    #[test]
+    fn test_fq_mul() {
+        let report = check_gadget("Fq::mul", Fq::mul(), FQ_MUL_LIMITS, 0, 10, |prng| {
+            let a = ark_bn254::Fq::rand(prng);
+            let b = ark_bn254::Fq::rand(prng);
+            Case {
+                inputs: [fq_witness(a), fq_witness(b)].concat(),
+                outputs: fq_witness(a * b),
+            }
+        });
+        println!("{}", report);
+    }
+
+    // Runs a gadget taking one element and leaving `op` of it
+    fn check_unary<T, W, O>(
+        name: &str,
+        gadget: Script,
+        limits: Limits,
+        runs: usize,
+        witness: W,
+        op: O,
+    ) where
+        T: UniformRand + Copy,
+        W: Fn(T) -> Vec<Vec<u8>>,
+        O: Fn(T) -> T,
+    {
+        let report = check_gadget(name, gadget, limits, 0, runs, |prng| {
+            let a = T::rand(prng);
+            Case {
+                inputs: witness(a),
+                outputs: witness(op(a)),
+            }
+        });
+        println!("{}", report);
+    }
+
+    // Runs a gadget taking two elements and leaving `op` of them
+    fn check_binary<T, W, O>(
+        name: &str,
+        gadget: Script,
+        limits: Limits,
+        runs: usize,
+        witness: W,
+        op: O,
+    ) where
+        T: UniformRand + Copy,
+        W: Fn(T) -> Vec<Vec<u8>>,
+        O: Fn(T, T) -> T,
+    {
+        let report = check_gadget(name, gadget, limits, 0, runs, |prng| {
+            let a = T::rand(prng);
+            let b = T::rand(prng);
+            Case {
+                inputs: [witness(a), witness(b)].concat(),
+                outputs: witness(op(a, b)),
+            }
+        });
+        println!("{}", report);
+    }
+
+    #[test]
+    fn test_fq() {
+        check_binary(
+            "Fq::add",
+            Fq::add(1, 0),
+            FQ_ADD_LIMITS,
+            10,
+            fq_witness,
+            |a, b| a + b,
+        );
+        check_binary(
+            "Fq::sub",
+            Fq::sub(1, 0),
+            FQ_SUB_LIMITS,
+            10,
+            fq_witness,
+            |a, b| a - b,
+        );
+        check_unary(
+            "Fq::double",
+            Fq::double(0),
+            FQ_DOUBLE_LIMITS,
+            10,
+            fq_witness,
+            |a| a.double(),
+        );
+        check_unary("Fq::neg", Fq::neg(0), FQ_NEG_LIMITS, 10, fq_witness, |a| -a);
+        check_unary(
+            "Fq::square",
+            Fq::square(),
+            FQ_SQUARE_LIMITS,
+            10,
+            fq_witness,
+            |a| a.square(),
+        );
+        check_unary("Fq::inv", Fq::inv(), FQ_INV_LIMITS, 10, fq_witness, |a| {
+            a.inverse().unwrap()
+        });
+        check_unary(
+            "Fq::div2",
+            Fq::div2(),
+            FQ_DIV2_LIMITS,
+            10,
+            fq_witness,
+            |a| a / ark_bn254::Fq::from(2),
+        );
+        check_unary(
+            "Fq::div3",
+            Fq::div3(),
+            FQ_DIV3_LIMITS,
+            10,
+            fq_witness,
+            |a| a / ark_bn254::Fq::from(3),
+        );
+
+        let constant = ark_bn254::Fq::rand(&mut ChaCha20Rng::seed_from_u64(1));
+        check_unary(
+            "Fq::mul_by_constant",
+            Fq::mul_by_constant(&constant),
+            FQ_MUL_BY_CONSTANT_LIMITS,
+            10,
+            fq_witness,
+            |a| a * constant,
+        );
+    }
+
+    #[test]
+    fn test_fq2() {
+        check_binary(
+            "Fq2::add",
+            Fq2::add(2, 0),
+            FQ2_ADD_LIMITS,
+            10,
+            fq2_witness,
+            |a, b| a + b,
+        );
+        check_binary(
+            "Fq2::sub",
+            Fq2::sub(2, 0),
+            FQ2_SUB_LIMITS,
+            10,
+            fq2_witness,
+            |a, b| a - b,
+        );
+        check_binary(
+            "Fq2::mul",
+            Fq2::mul(2, 0),
+            FQ2_MUL_LIMITS,
+            10,
+            fq2_witness,
+            |a, b| a * b,
+        );
+        check_unary(
+            "Fq2::double",
+            Fq2::double(0),
+            FQ2_DOUBLE_LIMITS,
+            10,
+            fq2_witness,
+            |a| a.double(),
+        );
+        check_unary(
+            "Fq2::triple",
+            Fq2::triple(0),
+            FQ2_TRIPLE_LIMITS,
+            10,
+            fq2_witness,
+            |a| a.double() + a,
+        );
+        check_unary(
+            "Fq2::neg",
+            Fq2::neg(0),
+            FQ2_NEG_LIMITS,
+            10,
+            fq2_witness,
+            |a| -a,
+        );
+        check_unary(
+            "Fq2::square",
+            Fq2::square(),
+            FQ2_SQUARE_LIMITS,
+            10,
+            fq2_witness,
+            |a| a.square(),
+        );
+        check_unary(
+            "Fq2::inv",
+            Fq2::inv(),
+            FQ2_INV_LIMITS,
+            10,
+            fq2_witness,
+            |a| a.inverse().unwrap(),
+        );
+        check_unary(
+            "Fq2::div2",
+            Fq2::div2(),
+            FQ2_DIV2_LIMITS,
+            10,
+            fq2_witness,
+            |a| a / ark_bn254::Fq2::from(2),
+        );
+        check_unary(
+            "Fq2::div3",
+            Fq2::div3(),
+            FQ2_DIV3_LIMITS,
+            10,
+            fq2_witness,
+            |a| a / ark_bn254::Fq2::from(3),
+        );
+        check_unary(
+            "Fq2::frobenius_map",
+            Fq2::frobenius_map(1),
+            FQ2_FROBENIUS_MAP_LIMITS,
+            10,
+            fq2_witness,
+            |a| a.frobenius_map(1),
+        );
+
+        let constant = ark_bn254::Fq2::rand(&mut ChaCha20Rng::seed_from_u64(1));
+        check_unary(
+            "Fq2::mul_by_constant",
+            Fq2::mul_by_constant(&constant),
+            FQ2_MUL_BY_CONSTANT_LIMITS,
+            10,
+            fq2_witness,
+            |a| a * constant,
+        );
+
+        let report = check_gadget(
+            "Fq2::mul_by_fq",
+            Fq2::mul_by_fq(1, 0),
+            FQ2_MUL_BY_FQ_LIMITS,
+            0,
+            10,
+            |prng| {
+                let a = ark_bn254::Fq2::rand(prng);
+                let b = ark_bn254::Fq::rand(prng);
+                let mut c = a;
+                c.mul_assign_by_fp(&b);
+                Case {
+                    inputs: [fq2_witness(a), fq_witness(b)].concat(),
+                    outputs: fq2_witness(c),
+                }
+            },
+        );
+        println!("{}", report);
+    }
+
+    #[test]
+    fn test_fq6() {
+        check_binary(
+            "Fq6::add",
+            Fq6::add(6, 0),
+            FQ6_ADD_LIMITS,
+            10,
+            fq6_witness,
+            |a, b| a + b,
+        );
+        check_binary(
+            "Fq6::sub",
+            Fq6::sub(6, 0),
+            FQ6_SUB_LIMITS,
+            10,
+            fq6_witness,
+            |a, b| a - b,
+        );
+        check_binary(
+            "Fq6::mul",
+            Fq6::mul(6, 0),
+            FQ6_MUL_LIMITS,
+            3,
+            fq6_witness,
+            |a, b| a * b,
+        );
+        check_unary(
+            "Fq6::double",
+            Fq6::double(0),
+            FQ6_DOUBLE_LIMITS,
+            10,
+            fq6_witness,
+            |a| a.double(),
+        );
+        check_unary(
+            "Fq6::neg",
+            Fq6::neg(0),
+            FQ6_NEG_LIMITS,
+            10,
+            fq6_witness,
+            |a| -a,
+        );
+        check_unary(
+            "Fq6::square",
+            Fq6::square(),
+            FQ6_SQUARE_LIMITS,
+            3,
+            fq6_witness,
+            |a| a.square(),
+        );
+        check_unary(
+            "Fq6::inv",
+            Fq6::inv(),
+            FQ6_INV_LIMITS,
+            3,
+            fq6_witness,
+            |a| a.inverse().unwrap(),
+        );
+        check_unary(
+            "Fq6::frobenius_map",
+            Fq6::frobenius_map(1),
+            FQ6_FROBENIUS_MAP_LIMITS,
+            3,
+            fq6_witness,
+            |a| a.frobenius_map(1),
+        );
+        check_unary(
+            "Fq6::mul_fq2_by_nonresidue",
+            Fq6::mul_fq2_by_nonresidue(),
+            FQ6_MUL_FQ2_BY_NONRESIDUE_LIMITS,
+            10,
+            fq2_witness,
+            ark_bn254::Fq6Config::mul_fp2_by_nonresidue,
+        );
+
+        let constant = ark_bn254::Fq2::rand(&mut ChaCha20Rng::seed_from_u64(1));
+        check_unary(
+            "Fq6::mul_by_fp2_constant",
+            Fq6::mul_by_fp2_constant(&constant),
+            FQ6_MUL_BY_FP2_CONSTANT_LIMITS,
+            3,
+            fq6_witness,
+            |mut a| {
+                a.mul_by_fp2(&constant);
+                a
+            },
+        );
+
+        let report = check_gadget(
+            "Fq6::mul_by_fp2",
+            Fq6::mul_by_fp2(),
+            FQ6_MUL_BY_FP2_LIMITS,
+            0,
+            3,
+            |prng| {
+                let a = ark_bn254::Fq6::rand(prng);
+                let b = ark_bn254::Fq2::rand(prng);
+                let mut c = a;
+                c.mul_by_fp2(&b);
+                Case {
+                    inputs: [fq6_witness(a), fq2_witness(b)].concat(),
+                    outputs: fq6_witness(c),
+                }
+            },
+        );
+        println!("{}", report);
+
+        let report = check_gadget(
+            "Fq6::mul_by_01",
+            Fq6::mul_by_01(),
+            FQ6_MUL_BY_01_LIMITS,
+            0,
+            3,
+            |prng| {
+                let a = ark_bn254::Fq6::rand(prng);
+                let c0 = ark_bn254::Fq2::rand(prng);
+                let c1 = ark_bn254::Fq2::rand(prng);
+                let mut c = a;
+                c.mul_by_01(&c0, &c1);
+                Case {
+                    inputs: [fq6_witness(a), fq2_witness(c0), fq2_witness(c1)].concat(),
+                    outputs: fq6_witness(c),
+                }
+            },
+        );
+        println!("{}", report);
+
+        let report = check_gadget(
+            "Fq6::mul_by_01_with_1_constant",
+            Fq6::mul_by_01_with_1_constant(&constant),
+            FQ6_MUL_BY_01_WITH_1_CONSTANT_LIMITS,
+            0,
+            3,
+            |prng| {
+                let a = ark_bn254::Fq6::rand(prng);
+                let c0 = ark_bn254::Fq2::rand(prng);
+                let mut c = a;
+                c.mul_by_01(&c0, &constant);
+                Case {
+                    inputs: [fq6_witness(a), fq2_witness(c0)].concat(),
+                    outputs: fq6_witness(c),
+                }
+            },
+        );
+        println!("{}", report);
+    }
+
+    #[test]
+    fn test_fq12() {
+        check_binary(
+            "Fq12::add",
+            Fq12::add(12, 0),
+            FQ12_ADD_LIMITS,
+            10,
+            fq12_witness,
+            |a, b| a + b,
+        );
+        check_binary(
+            "Fq12::sub",
+            Fq12::sub(12, 0),
+            FQ12_SUB_LIMITS,
+            10,
+            fq12_witness,
+            |a, b| a - b,
+        );
+        check_binary(
+            "Fq12::mul",
+            Fq12::mul(12, 0),
+            FQ12_MUL_LIMITS,
+            3,
+            fq12_witness,
+            |a, b| a * b,
+        );
+        check_binary(
+            "Fq12::mul_cpt",
+            Fq12::mul_cpt(12, 0),
+            FQ12_MUL_CPT_LIMITS,
+            3,
+            fq12_witness,
+            |a, b| a * b,
+        );
+        check_unary(
+            "Fq12::double",
+            Fq12::double(0),
+            FQ12_DOUBLE_LIMITS,
+            10,
+            fq12_witness,
+            |a| a.double(),
+        );
+        check_unary(
+            "Fq12::square",
+            Fq12::square(),
+            FQ12_SQUARE_LIMITS,
+            3,
+            fq12_witness,
+            |a| a.square(),
+        );
+        check_unary(
+            "Fq12::inv",
+            Fq12::inv(),
+            FQ12_INV_LIMITS,
+            3,
+            fq12_witness,
+            |a| a.inverse().unwrap(),
+        );
+        check_unary(
+            "Fq12::cyclotomic_inverse",
+            Fq12::cyclotomic_inverse(),
+            FQ12_CYCLOTOMIC_INVERSE_LIMITS,
+            10,
+            fq12_witness,
+            |mut a| {
+                a.conjugate_in_place();
+                a
+            },
+        );
+        check_unary(
+            "Fq12::frobenius_map",
+            Fq12::frobenius_map(1),
+            FQ12_FROBENIUS_MAP_LIMITS,
+            3,
+            fq12_witness,
+            |a| a.frobenius_map(1),
+        );
+        check_unary(
+            "Fq12::move_to_cyclotomic",
+            Fq12::move_to_cyclotomic(),
+            FQ12_MOVE_TO_CYCLOTOMIC_LIMITS,
+            1,
+            fq12_witness,
+            |a| {
+                let r = a.cyclotomic_inverse().unwrap() * a.inverse().unwrap();
+                r.frobenius_map(2) * r
+            },
+        );
+        check_unary(
+            "Fq12::mul_fq6_by_nonresidue",
+            Fq12::mul_fq6_by_nonresidue(),
+            FQ12_MUL_FQ6_BY_NONRESIDUE_LIMITS,
+            10,
+            fq6_witness,
+            |mut a| {
+                ark_bn254::Fq12Config::mul_fp6_by_nonresidue_in_place(&mut a);
+                a
+            },
+        );
+
+        let report = check_gadget(
+            "Fq12::cyclotomic_pow_by_r",
+            Fq12::cyclotomic_pow_by_r(),
+            FQ12_CYCLOTOMIC_POW_BY_R_LIMITS,
+            0,
+            1,
+            |prng| {
+                // a^((p^6 - 1)(p^2 + 1)) is in the cyclotomic subgroup, see `test_fq12_cyclotomic_square`
+                let a = ark_bn254::Fq12::rand(prng);
+                let r = a.cyclotomic_inverse().unwrap() * a.inverse().unwrap();
+                let a = r.frobenius_map(2) * r;
+                Case {
+                    inputs: fq12_witness(a),
+                    outputs: fq12_witness(a.cyclotomic_exp(ark_bn254::Fr::MODULUS)),
+                }
+            },
+        );
+        println!("{}", report);
+
+        let report = check_gadget(
+            "Fq12::mul_by_034",
+            Fq12::mul_by_034(),
+            FQ12_MUL_BY_034_LIMITS,
+            0,
+            3,
+            |prng| {
+                let a = ark_bn254::Fq12::rand(prng);
+                let c0 = ark_bn254::Fq2::rand(prng);
+                let c3 = ark_bn254::Fq2::rand(prng);
+                let c4 = ark_bn254::Fq2::rand(prng);
+                let mut b = a;
+                b.mul_by_034(&c0, &c3, &c4);
+                Case {
+                    inputs: [
+                        fq12_witness(a),
+                        fq2_witness(c0),
+                        fq2_witness(c3),
+                        fq2_witness(c4),
+                    ]
+                    .concat(),
+                    outputs: fq12_witness(b),
+                }
+            },
+        );
+        println!("{}", report);
+
+        let c4 = ark_bn254::Fq2::rand(&mut ChaCha20Rng::seed_from_u64(1));
+        let report = check_gadget(
+            "Fq12::mul_by_034_with_4_constant",
+            Fq12::mul_by_034_with_4_constant(&c4),
+            FQ12_MUL_BY_034_WITH_4_CONSTANT_LIMITS,
+            0,
+            3,
+            |prng| {
+                let a = ark_bn254::Fq12::rand(prng);
+                let c0 = ark_bn254::Fq2::rand(prng);
+                let c3 = ark_bn254::Fq2::rand(prng);
+                let mut b = a;
+                b.mul_by_034(&c0, &c3, &c4);
+                Case {
+                    inputs: [fq12_witness(a), fq2_witness(c0), fq2_witness(c3)].concat(),
+                    outputs: fq12_witness(b),
+                }
+            },
+        );
+        println!("{}", report);
+
+        let report = check_gadget(
+            "Fq12::mul_by_34",
+            Fq12::mul_by_34(),
+            FQ12_MUL_BY_34_LIMITS,
+            0,
+            3,
+            |prng| {
+                let a = ark_bn254::Fq12::rand(prng);
+                let c3 = ark_bn254::Fq2::rand(prng);
+                let c4 = ark_bn254::Fq2::rand(prng);
+                let mut b = a;
+                b.mul_by_034(&ark_bn254::Fq2::ONE, &c3, &c4);
+                Case {
+                    inputs: [fq12_witness(a), fq2_witness(c3), fq2_witness(c4)].concat(),
+                    outputs: fq12_witness(b),
+                }
+            },
+        );
+        println!("{}", report);
+    }
+
+    #[test]
+    fn test_fq12_cyclotomic_square() {
+        let report = check_gadget(
+            "Fq12::cyclotomic_square",
+            Fq12::cyclotomic_square(),
+            FQ12_CYCLOTOMIC_SQUARE_LIMITS,
+            0,
+            3,
+            |prng| {
+                let a = ark_bn254::Fq12::rand(prng);
+
+                // the easy part of the final exponentiation, a^((p^6 - 1)(p^2 + 1)), maps a into the
+                // cyclotomic subgroup where cyclotomic_square is a square
+                let a = {
+                    let mut r = a.cyclotomic_inverse().unwrap() * a.inverse().unwrap();
+                    let f = r;
+                    r.frobenius_map_in_place(2);
+                    r * f
+                };
+                assert_eq!(a.cyclotomic_square(), a.square());
+
+                Case {
+                    inputs: fq12_witness(a),
+                    outputs: fq12_witness(a.cyclotomic_square()),
+                }
+            },
+        );
+        println!("{}", report);
+    }
+
+    #[test]
+    fn test_g1_scalar_mul() {
+        let report = check_gadget_with_normalization(
+            "G1Projective::scalar_mul",
+            G1Projective::scalar_mul(),
+            G1Projective::into_affine(),
+            G1_SCALAR_MUL_LIMITS,
+            0,
+            1,
+            |prng| {
+                let p = ark_bn254::G1Projective::rand(prng);
+                let scalar = ark_bn254::Fr::rand(prng);
+                Case {
+                    inputs: [
+                        fq_witness(p.x),
+                        fq_witness(p.y),
+                        fq_witness(p.z),
+                        Fr::witness_u32_le(&BigUint::from(scalar).to_u32_digits()),
+                    ]
+                    .concat(),
+                    outputs: g1_affine_witness(p.mul(scalar).into_affine()),
+                }
+            },
+        );
+        println!("{}", report);
+    }
+
+    #[test]
+    fn test_g1() {
+        let projective_witness = |p: ark_bn254::G1Projective| {
+            [fq_witness(p.x), fq_witness(p.y), fq_witness(p.z)].concat()
+        };
+
+        let report = check_gadget_with_normalization(
+            "G1Projective::double",
+            G1Projective::double(),
+            G1Projective::into_affine(),
+            G1_DOUBLE_LIMITS,
+            0,
+            3,
+            |prng| {
+                let p = ark_bn254::G1Projective::rand(prng);
+                Case {
+                    inputs: projective_witness(p),
+                    outputs: g1_affine_witness(p.double().into_affine()),
+                }
+            },
+        );
+        println!("{}", report);
+
+        let report = check_gadget_with_normalization(
+            "G1Projective::add",
+            G1Projective::add(),
+            G1Projective::into_affine(),
+            G1_ADD_LIMITS,
+            0,
+            3,
+            |prng| {
+                let p = ark_bn254::G1Projective::rand(prng);
+                let q = ark_bn254::G1Projective::rand(prng);
+                Case {
+                    inputs: [projective_witness(p), projective_witness(q)].concat(),
+                    outputs: g1_affine_witness((p + q).into_affine()),
+                }
+            },
+        );
+        println!("{}", report);
+
+        let report = check_gadget(
+            "G1Projective::neg",
+            G1Projective::neg(),
+            G1_NEG_LIMITS,
+            0,
+            10,
+            |prng| {
+                let p = ark_bn254::G1Projective::rand(prng);
+                Case {
+                    inputs: projective_witness(p),
+                    outputs: projective_witness(-p),
+                }
+            },
+        );
+        println!("{}", report);
+
+        let report = check_gadget(
+            "G1Projective::into_affine",
+            G1Projective::into_affine(),
+            G1_INTO_AFFINE_LIMITS,
+            0,
+            3,
+            |prng| {
+                let p = ark_bn254::G1Projective::rand(prng);
+                Case {
+                    inputs: projective_witness(p),
+                    outputs: g1_affine_witness(p.into_affine()),
+                }
+            },
+        );
+        println!("{}", report);
+
+        let report = check_gadget(
+            "G1Affine::into_projective",
+            G1Affine::into_projective(),
+            G1_AFFINE_INTO_PROJECTIVE_LIMITS,
+            0,
+            10,
+            |prng| {
+                let p = ark_bn254::G1Affine::rand(prng);
+                Case {
+                    inputs: g1_affine_witness(p),
+                    outputs: projective_witness(p.into_group()),
+                }
+            },
+        );
+        println!("{}", report);
+
+        let report = check_gadget(
+            "G1Affine::is_on_curve",
+            G1Affine::is_on_curve(),
+            G1_AFFINE_IS_ON_CURVE_LIMITS,
+            0,
+            10,
+            |prng| {
+                // every other point is moved off the curve
+                let p = ark_bn254::G1Affine::rand(prng);
+                let on_curve = bool::rand(prng);
+                let y = if on_curve {
+                    p.y
+                } else {
+                    p.y + ark_bn254::Fq::ONE
+                };
+                Case {
+                    inputs: [fq_witness(p.x), fq_witness(y)].concat(),
+                    outputs: vec![script_num(on_curve as u32)],
+                }
+            },
+        );
+        println!("{}", report);
+
+        let report = check_gadget(
+            "G1Affine::convert_to_compressed",
+            G1Affine::convert_to_compressed(),
+            G1_AFFINE_CONVERT_TO_COMPRESSED_LIMITS,
+            0,
+            10,
+            |prng| {
+                let p = ark_bn254::G1Affine::rand(prng);
+                let mut bytes = p.x.into_bigint().to_bytes_be();
+                if p.y.into_bigint() > ark_bn254::Fq::MODULUS_MINUS_ONE_DIV_TWO {
+                    bytes[0] += 0x80;
+                }
+                Case {
+                    inputs: g1_affine_witness(p),
+                    // the most significant byte on top
+                    outputs: bytes
+                        .iter()
+                        .rev()
+                        .map(|byte| script_num(*byte as u32))
+                        .collect(),
+                }
+            },
+        );
+        println!("{}", report);
+    }
+
+    #[test]
+    fn test_ell_by_constant() {
+        let mut prng = ChaCha20Rng::seed_from_u64(0);
+        let coeffs = G2Prepared::from(ark_bn254::G2Affine::rand(&mut prng));
+        let constant = coeffs.ell_coeffs[0];
+
+        let report = check_gadget(
+            "utils::ell_by_constant",
+            ell_by_constant(&constant),
+            ELL_BY_CONSTANT_LIMITS,
+            0,
+            3,
+            |prng| {
+                let f = ark_bn254::Fq12::rand(prng);
+                let px = ark_bn254::Fq::rand(prng);
+                let py = ark_bn254::Fq::rand(prng);
+
+                let mut c0 = constant.0;
+                c0.mul_assign_by_fp(&py);
+                let mut c1 = constant.1;
+                c1.mul_assign_by_fp(&px);
+                let mut expected = f;
+                expected.mul_by_034(&c0, &c1, &constant.2);
+
+                Case {
+                    inputs: [fq12_witness(f), fq_witness(px), fq_witness(py)].concat(),
+                    outputs: fq12_witness(expected),
+                }
+            },
+        );
+        println!("{}", report);
+    }
+
+    #[test]
+    fn test_quad_miller_loop_with_c_wi() {
+        let mut prng = ChaCha20Rng::seed_from_u64(0);
+        let q1 = ark_bn254::G2Affine::rand(&mut prng);
+        let q2 = ark_bn254::G2Affine::rand(&mut prng);
+        let q3 = ark_bn254::G2Affine::rand(&mut prng);
+
+        // The loop computes f * wi * c_inv^lambda' with lambda' = 6x + 2 + p - p^2 - p^3
+        let (exp, sign) = if LAMBDA.gt(&P_POW3) {
+            (&*LAMBDA - &*P_POW3, true)
+        } else {
+            (&*P_POW3 - &*LAMBDA, false)
+        };
+
+        // Frobenius constants of the loop, see `test_quad_miller_loop_with_c_wi` in pairing.rs
+        let beta = [
+            "21575463638280843010398324269430826099269044274347216827212613867836435027261",
+            "10307601595873709700152284273816112264069230130616436755625194854815875713954",
+            "2821565182194536844548159561693502659359617185244120367078079554186484126554",
+            "3505843767911556378687030309984248845540243509899259641013678093033130930403",
+            "21888242871839275220042445260109153167277707414472061641714758635765020556616",
+            "0",
+        ]
+        .iter()
+        .flat_map(|beta| fq_witness(ark_bn254::Fq::from_str(beta).unwrap()))
+        .collect::<Vec<_>>();
+
+        // Q4 is provided by the prover, only its line coefficients are fixed in the script
+        let q4 = ark_bn254::G2Affine::rand(&mut prng);
+        let gadget = Pairing::quad_miller_loop_with_c_wi(
+            [q1, q2, q3, q4].map(G2Prepared::from_affine).to_vec(),
+        );
+
+        let report = check_gadget(
+            "Pairing::quad_miller_loop_with_c_wi",
+            gadget,
+            QUAD_MILLER_LOOP_WITH_C_WI_LIMITS,
+            0,
+            1,
+            |prng| {
+                let p = [(); 4].map(|_| ark_bn254::G1Affine::rand(prng));
+                let c = ark_bn254::Fq12::rand(prng);
+                let c_inv = c.inverse().unwrap();
+                let wi = ark_bn254::Fq12::rand(prng);
+
+                let f = Bn254::multi_miller_loop_affine(p, [q1, q2, q3, q4]).0;
+                let c_inv_exp = c_inv.pow(exp.to_u64_digits());
+                let expected = if sign {
+                    f * wi * c_inv_exp
+                } else {
+                    f * wi * c_inv_exp.inverse().unwrap()
+                };
+
+                let eval_points = p.iter().flat_map(|p| {
+                    let y_inv = p.y().unwrap().inverse().unwrap();
+                    [fq_witness(-p.x * y_inv), fq_witness(y_inv)].concat()
+                });
+                Case {
+                    inputs: [
+                        beta.clone(),
+                        eval_points.collect(),
+                        fq2_witness(q4.x),
+                        fq2_witness(q4.y),
+                        fq12_witness(c),
+                        fq12_witness(c_inv),
+                        fq12_witness(wi),
+                        fq2_witness(q4.x),
+                        fq2_witness(q4.y),
+                    ]
+                    .concat(),
+                    outputs: fq12_witness(expected),
+                }
+            },
+        );
+        println!("{}", report);
+    }
+
+    #[test]
+    #[should_panic(expected = "doesn't match the reference")]
+    fn test_check_gadget_extra_items() {
+        // Fq::copy leaves its input on the stack
+        check_gadget("Fq::copy", Fq::copy(0), FQ_MUL_LIMITS, 0, 1, |prng| {
+            let a = ark_bn254::Fq::rand(prng);
+            Case {
+                inputs: fq_witness(a),
+                outputs: fq_witness(a),
+            }
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "exceed the limit")]
+    fn test_check_gadget_limits() {
+        let limits = Limits {
+            script_size: Fq::add(1, 0).len() - 1,
+            ..FQ_MUL_LIMITS
+        };
+        check_gadget("Fq::add", Fq::add(1, 0), limits, 0, 1, |prng| {
+            let a = ark_bn254::Fq::rand(prng);
+            let b = ark_bn254::Fq::rand(prng);
+            Case {
+                inputs: [fq_witness(a), fq_witness(b)].concat(),
+                outputs: fq_witness(a + b),
+            }
+        });
+    }
+}
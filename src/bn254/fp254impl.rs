@@ -56,6 +56,13 @@ pub trait Fp254Impl {
         }
     }
 
+    /// The stack items `push_u32_le` leaves on the stack, e.g. to put the element in a witness.
+    fn witness_u32_le(v: &[u32]) -> Vec<Vec<u8>> {
+        let r = BigUint::from_str_radix(Self::MONTGOMERY_ONE, 16).unwrap();
+        let p = BigUint::from_str_radix(Self::MODULUS, 16).unwrap();
+        U254::witness_u32_le(&BigUint::from_slice(v).mul(r).rem(p).to_u32_digits())
+    }
+
     #[inline]
     fn equal(a: u32, b: u32) -> Script { U254::equal(a, b) }
 
@@ -883,6 +890,31 @@ pub trait Fp254Impl {
         }
     }
 
+    // Stricter than `is_field`, for elements received in witnesses: each limb must also fit in
+    // its bits, so that a single number has a single encoding.
+    // Input Stack: [a]
+    // Output Stack: [a is canonical]
+    fn is_canonical() -> Script {
+        let top_limb_size = Self::N_BITS - 29 * (Self::N_LIMBS - 1);
+        script! {
+            for i in 0..Self::N_LIMBS - 1 {
+                { i } OP_PICK
+                0 { 1 << 29 } OP_WITHIN OP_TOALTSTACK
+            }
+            { Self::N_LIMBS - 1 } OP_PICK
+            0 { 1 << top_limb_size } OP_WITHIN
+            for _ in 0..Self::N_LIMBS - 1 {
+                OP_FROMALTSTACK OP_BOOLAND
+            }
+            OP_TOALTSTACK
+
+            { Self::push_modulus() }
+            { U254::lessthan(1, 0) }
+
+            OP_FROMALTSTACK OP_BOOLAND
+        }
+    }
+
     fn square() -> Script {
         script! {
             // a ⋅ a  →  ❨a ⋅ a❩ᵐᵒᵈ2²⁶¹ ⌊2⁻²⁶¹⋅❨a ⋅ a❩⌋
@@ -1380,6 +1412,52 @@ pub trait Fp254Impl {
         }
     }
 
+    // Inverse of `convert_to_be_bytes`
+    // Input Stack: [b_31, ..., b_0], the most significant byte b_0 on top
+    // Output Stack: [a]
+    // Fails unless the bytes are those of a number below the modulus.
+    fn convert_from_be_bytes() -> Script {
+        let r = BigUint::from_str_radix(Self::MONTGOMERY_ONE, 16).unwrap();
+        let p = BigUint::from_str_radix(Self::MODULUS, 16).unwrap();
+        let top_limb_size = Self::N_BITS - 29 * (Self::N_LIMBS - 1);
+        // the top byte holds the 6 most significant bits
+        let byte_size = |i: u32| if i == 0 { 6 } else { 8 };
+        let bit_index = |i: u32, j: u32| if i == 0 { j } else { 6 + 8 * (i - 1) + j };
+        let is_limb_start =
+            |bit: u32| bit == 0 || (bit >= top_limb_size && (bit - top_limb_size) % 29 == 0);
+
+        script! {
+            for i in 0..32 {
+                OP_DUP 0 { 1 << byte_size(i) } OP_WITHIN OP_VERIFY
+                { limb_to_be_bits(byte_size(i)) }
+                // accumulate the bits into the limbs on the altstack, the top limb first
+                for j in 0..byte_size(i) {
+                    if is_limb_start(bit_index(i, j)) {
+                        OP_0 OP_TOALTSTACK
+                    }
+                    OP_FROMALTSTACK
+                    OP_DUP OP_ADD OP_ADD
+                    OP_TOALTSTACK
+                }
+            }
+            for _ in 0..Self::N_LIMBS {
+                OP_FROMALTSTACK
+            }
+            for i in 1..Self::N_LIMBS {
+                { i } OP_ROLL
+            }
+
+            { U254::copy(0) }
+            { Self::push_modulus() }
+            { U254::lessthan(1, 0) }
+            OP_VERIFY
+
+            // into Montgomery form, a ⋅ R² ⋅ R⁻¹
+            { U254::push_u32_le(&r.pow(2).rem(&p).to_u32_digits()) }
+            { Self::mul() }
+        }
+    }
+
     fn convert_to_be_u4() -> Script {
         let build_u8_from_be_bits = |i| {
             script! {
@@ -43,6 +43,27 @@ impl Fq {
     fn modulus_as_bigint() -> BigInt {
         BigInt::from_str_radix(Self::MODULUS, 16).unwrap()
     }
+
+    // a^((p + 1) / 4), a square root of a since p ≡ 3 (mod 4)
+    // Input Stack: [a]
+    // Output Stack: [r]
+    // r² == a iff a is a square, otherwise r² == -a: callers have to check it.
+    pub fn sqrt() -> Script {
+        let exponent: BigInt = (Self::modulus_as_bigint() + 1) / 4;
+        let bits = exponent.to_str_radix(2);
+        script! {
+            { Fq::copy(0) }
+            for bit in bits.chars().skip(1) {
+                { Fq::square() }
+                if bit == '1' {
+                    { Fq::copy(1) }
+                    { Fq::mul() }
+                }
+            }
+            { Fq::roll(1) }
+            { Fq::drop() }
+        }
+    }
 }
 
 macro_rules! fp_lc_mul {
@@ -618,6 +639,29 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_sqrt() {
+        println!("Fq.sqrt: {} bytes", Fq::sqrt().len());
+        let mut prng = ChaCha20Rng::seed_from_u64(0);
+
+        for _ in 0..3 {
+            let a = ark_bn254::Fq::rand(&mut prng);
+            // r^2 == a if a is a square, and r^2 == -a otherwise
+            let b = if a.legendre().is_qr() { a } else { -a };
+
+            let script = script! {
+                { Fq::push_u32_le(&BigUint::from(a).to_u32_digits()) }
+                { Fq::sqrt() }
+                { Fq::square() }
+                { Fq::push_u32_le(&BigUint::from(b).to_u32_digits()) }
+                { Fq::equalverify(1, 0) }
+                OP_TRUE
+            };
+            let exec_result = execute_script(script);
+            assert!(exec_result.success);
+        }
+    }
+
     #[test]
     fn test_div2() {
         println!("Fq.div2: {} bytes", Fq::div2().len());
@@ -784,6 +828,45 @@ mod test {
         assert!(exec_result.success);
     }
 
+    #[test]
+    fn test_is_canonical() {
+        let m = BigUint::from_str_radix(Fq::MODULUS, 16).unwrap();
+        let mut prng = ChaCha20Rng::seed_from_u64(0);
+
+        println!("Fq.is_canonical: {} bytes", Fq::is_canonical().len());
+
+        for _ in 0..10 {
+            let a: BigUint = prng.sample(RandomBits::new(254));
+            let a = a.rem(&m);
+
+            let script = script! {
+                { U254::push_u32_le(&a.to_u32_digits()) }
+                { Fq::is_canonical() }
+            };
+            let exec_result = execute_script(script);
+            assert!(exec_result.success);
+        }
+
+        let script = script! {
+            { Fq::push_modulus() }
+            { Fq::is_canonical() }
+            OP_NOT
+        };
+        let exec_result = execute_script(script);
+        assert!(exec_result.success);
+
+        // 1 + 2^58, encoded with a second limb that doesn't fit in 29 bits
+        let script = script! {
+            { U254::push_u32_le(&[1]) }
+            OP_DROP OP_DROP
+            { 1 << 29 } 1
+            { Fq::is_canonical() }
+            OP_NOT
+        };
+        let exec_result = execute_script(script);
+        assert!(exec_result.success);
+    }
+
     #[test]
     fn test_convert_to_be_bytes() {
         let mut prng = ChaCha20Rng::seed_from_u64(0);
@@ -811,6 +894,46 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_convert_from_be_bytes() {
+        let mut prng = ChaCha20Rng::seed_from_u64(0);
+
+        let convert_from_be_bytes_script = Fq::convert_from_be_bytes();
+        println!(
+            "Fq.convert_from_be_bytes: {} bytes",
+            convert_from_be_bytes_script.len()
+        );
+
+        for _ in 0..10 {
+            let fq = ark_bn254::Fq::rand(&mut prng);
+            let bytes = fq.into_bigint().to_bytes_be();
+
+            let script = script! {
+                for i in (0..32).rev() {
+                    { bytes[i] }
+                }
+                { convert_from_be_bytes_script.clone() }
+                { Fq::push_u32_le(&BigUint::from(fq).to_u32_digits()) }
+                { Fq::equalverify(1, 0) }
+                OP_TRUE
+            };
+            let exec_result = execute_script(script);
+            assert!(exec_result.success);
+        }
+
+        // the modulus isn't a field element
+        let bytes = BigUint::from_str_radix(Fq::MODULUS, 16).unwrap().to_bytes_be();
+        let script = script! {
+            for i in (0..32).rev() {
+                { bytes[i] }
+            }
+            { convert_from_be_bytes_script.clone() }
+            OP_DROP
+        };
+        let exec_result = execute_script(script);
+        assert!(!exec_result.success);
+    }
+
     fn rand_bools<const SIZE: usize>(seed: u64) -> [bool; SIZE] {
         let mut bools = [true; SIZE];
         let mut prng: ChaCha20Rng = ChaCha20Rng::seed_from_u64(seed);
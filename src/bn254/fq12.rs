@@ -4,6 +4,7 @@ use crate::bn254::fq2::Fq2;
 use crate::bn254::fq6::Fq6;
 use crate::bn254::fr::Fr;
 use crate::treepp::{script, Script};
+use ark_ec::bn::BnConfig;
 use ark_ff::Fp12Config;
 use num_bigint::BigUint;
 use num_traits::{Num, Zero};
@@ -51,6 +52,21 @@ impl Fq12 {
         }
     }
 
+    pub fn equal() -> Script {
+        script! {
+            for i in 0..12 {
+                { Fq::equal(23 - i * 2, 11 - i) }
+                OP_TOALTSTACK
+            }
+            for _ in 0..12 {
+                OP_FROMALTSTACK
+            }
+            for _ in 0..11 {
+                OP_BOOLAND
+            }
+        }
+    }
+
     pub fn mul_fq6_by_nonresidue() -> Script {
         script! {
             { Fq6::mul_fq2_by_nonresidue() }
@@ -572,6 +588,48 @@ impl Fq12 {
         }
     }
 
+    // f^{-x} for f in the cyclotomic subgroup, x the BN parameter, as `exp_by_neg_x` of arkworks
+    pub fn cyclotomic_pow_by_neg_x() -> Script {
+        // non-adjacent form of x, the least significant digit first
+        let mut x = ark_bn254::Config::X[0];
+        let mut naf = vec![];
+        while x > 0 {
+            if x & 1 == 1 {
+                let digit = 2 - (x % 4) as i8;
+                naf.push(digit);
+                x = (x as i128 - digit as i128) as u64;
+            } else {
+                naf.push(0);
+            }
+            x >>= 1;
+        }
+        assert!(!ark_bn254::Config::X_IS_NEGATIVE);
+
+        script! {
+            { Fq12::copy(0) }
+            { Fq12::cyclotomic_inverse() }
+            { Fq12::copy(12) }
+            // [f, f^{-1}, res]
+
+            for digit in naf.iter().rev().skip(1) {
+                { Fq12::cyclotomic_square() }
+                if *digit == 1 {
+                    { Fq12::copy(24) }
+                    { Fq12::mul(12, 0) }
+                } else if *digit == -1 {
+                    { Fq12::copy(12) }
+                    { Fq12::mul(12, 0) }
+                }
+            }
+
+            { Fq12::toaltstack() }
+            { Fq12::drop() }
+            { Fq12::drop() }
+            { Fq12::fromaltstack() }
+            { Fq12::cyclotomic_inverse() }
+        }
+    }
+
     pub fn move_to_cyclotomic() -> Script {
         script! {
             // compute f1 = a.cyclotomic_inverse()
@@ -616,6 +674,7 @@ mod test {
     use crate::bn254::fq::Fq;
     use crate::bn254::fq12::Fq12;
     use crate::treepp::*;
+    use ark_ec::bn::BnConfig;
     use ark_ff::AdditiveGroup;
     use ark_ff::{CyclotomicMultSubgroup, Field};
     use ark_std::UniformRand;
@@ -925,6 +984,50 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_bn254_fq12_cyclotomic_pow_by_neg_x() {
+        let mut prng = ChaCha20Rng::seed_from_u64(0);
+
+        let cyclotomic_pow_by_neg_x = Fq12::cyclotomic_pow_by_neg_x();
+        println!(
+            "Fq12.cyclotomic_pow_by_neg_x: {} bytes",
+            cyclotomic_pow_by_neg_x.len()
+        );
+
+        for _ in 0..1 {
+            let a = ark_bn254::fq12::Fq12::rand(&mut prng);
+
+            // move a into the cyclotomic subgroup
+            let a = {
+                let f1 = a.cyclotomic_inverse().unwrap();
+
+                let mut f2 = a.inverse().unwrap();
+                let mut r = f1.mul(&f2);
+                f2 = r;
+
+                r.frobenius_map_in_place(2);
+
+                r *= f2;
+                r
+            };
+
+            let res = a
+                .cyclotomic_exp(ark_bn254::Config::X)
+                .cyclotomic_inverse()
+                .unwrap();
+
+            let script = script! {
+                { fq12_push(a) }
+                { cyclotomic_pow_by_neg_x.clone() }
+                { fq12_push(res) }
+                { Fq12::equalverify() }
+                OP_TRUE
+            };
+            let exec_result = execute_script(script);
+            assert!(exec_result.success);
+        }
+    }
+
     #[test]
     fn test_bn254_fq12_move_to_cyclotomic() {
         let mut prng = ChaCha20Rng::seed_from_u64(0);
@@ -173,6 +173,60 @@ impl Fq2 {
         }
     }
 
+    /// Square root of the top Fq2 element, with the complex method: x = x0 + x1 * u with
+    /// x0² = (a0 ± sqrt(a0² + a1²)) / 2, where the sign is picked so that x0² is a square,
+    /// and x1 = a1 / (2 * x0).
+    ///
+    /// Like `Fq::sqrt`, the result is only a square root if the input is a square: callers have
+    /// to check it. The input must not be zero.
+    pub fn sqrt() -> Script {
+        script! {
+            // compute n = a0^2 + a1^2
+            { Fq::copy(1) }
+            { Fq::square() }
+            { Fq::copy(1) }
+            { Fq::square() }
+            { Fq::add(1, 0) }
+
+            // compute c = (a0 + sqrt(n)) / 2
+            { Fq::sqrt() }
+            { Fq::copy(2) }
+            { Fq::add(1, 0) }
+            { Fq::div2() }
+
+            // c is zero iff a1 is zero and sqrt(n) == -a0, use c = a0 instead
+            { Fq::is_zero_keep_element(0) }
+            OP_IF
+                { Fq::drop() }
+                { Fq::copy(1) }
+            OP_ENDIF
+
+            // compute r = sqrt(c) and whether r^2 == c, otherwise r^2 == -c
+            { Fq::copy(0) }
+            { Fq::sqrt() }
+            { Fq::copy(0) }
+            { Fq::square() }
+            { Fq::roll(2) }
+            { Fq::equal(1, 0) }
+            OP_TOALTSTACK
+
+            // compute t = a1 / (2 * r)
+            { Fq::copy(0) }
+            { Fq::double(0) }
+            { Fq::inv() }
+            { Fq::roll(2) }
+            { Fq::mul() }
+            { Fq::roll(2) }
+            { Fq::drop() }
+
+            // if r^2 == -c, then x0 = t and x1 = r
+            OP_FROMALTSTACK
+            OP_NOTIF
+                { Fq::roll(1) }
+            OP_ENDIF
+        }
+    }
+
     pub fn div2() -> Script {
         script! {
             { Fq::roll(1) }
@@ -436,6 +490,34 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_bn254_fq2_sqrt() {
+        println!("Fq2.sqrt: {} bytes", Fq2::sqrt().len());
+        let mut prng = ChaCha20Rng::seed_from_u64(0);
+
+        for _ in 0..3 {
+            let a = ark_bn254::Fq2::rand(&mut prng).square();
+            // every Fq element is a square in Fq2
+            let b = ark_bn254::Fq2::new(ark_bn254::Fq::rand(&mut prng), ark_bn254::Fq::ZERO);
+
+            let script = script! {
+                { fq2_push(a) }
+                { Fq2::sqrt() }
+                { Fq2::square() }
+                { fq2_push(a) }
+                { Fq2::equalverify() }
+                { fq2_push(b) }
+                { Fq2::sqrt() }
+                { Fq2::square() }
+                { fq2_push(b) }
+                { Fq2::equalverify() }
+                OP_TRUE
+            };
+            let exec_result = execute_script(script);
+            assert!(exec_result.success);
+        }
+    }
+
     #[test]
     fn test_bn254_fq2_div2() {
         println!("Fq2.div2: {} bytes", Fq2::div2().len());
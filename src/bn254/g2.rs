@@ -0,0 +1,1047 @@
+use crate::bigint::U254;
+use crate::bn254::fp254impl::Fp254Impl;
+use crate::bn254::fq::Fq;
+use crate::bn254::fq2::Fq2;
+use crate::bn254::utils::fq2_push;
+use crate::treepp::{script, Script};
+use ark_ec::bn::BnConfig;
+use ark_ec::short_weierstrass::SWCurveConfig;
+use num_bigint::BigUint;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+static G2_DOUBLE_PROJECTIVE: OnceLock<Script> = OnceLock::new();
+static G2_NONZERO_ADD_PROJECTIVE: OnceLock<Script> = OnceLock::new();
+
+// 6x^2 where x is the BN254 curve parameter, see `G2Affine::is_in_subgroup`
+const SIX_X_SQUARED: &str = "147946756881789318990833708069417712966";
+
+/// Points of the twist over Fq2 in Jacobian coordinates, [x, y, z] on the stack.
+pub struct G2Projective;
+
+impl G2Projective {
+    pub fn push(element: ark_bn254::G2Projective) -> Script {
+        script! {
+            { fq2_push(element.x) }
+            { fq2_push(element.y) }
+            { fq2_push(element.z) }
+        }
+    }
+
+    pub fn push_zero() -> Script {
+        script! {
+            { Fq2::push_one() }
+            { Fq2::push_one() }
+            { Fq2::push_zero() }
+        }
+    }
+
+    pub fn is_zero_keep_element(a: u32) -> Script {
+        script! {
+            // Check if the third coordinate(z) is zero
+            { Fq::is_zero_keep_element(a * 6) }
+            OP_TOALTSTACK
+            { Fq::is_zero_keep_element(a * 6 + 1) }
+            OP_FROMALTSTACK
+            OP_BOOLAND
+        }
+    }
+
+    // Input Stack: [x, y, z]
+    // Output Stack: [2P]
+    // The point isn't the identity.
+    pub fn nonzero_double() -> Script {
+        G2_DOUBLE_PROJECTIVE
+            .get_or_init(|| {
+                script! {
+                    // A = x^2
+                    { Fq2::copy(4) }
+                    { Fq2::square() }
+                    // B = y^2
+                    { Fq2::copy(4) }
+                    { Fq2::square() }
+                    // C = B^2
+                    { Fq2::copy(0) }
+                    { Fq2::square() }
+                    // [x, y, z, A, B, C]
+
+                    // D = 2 * ((x + B)^2 - A - C)
+                    { Fq2::roll(2) }
+                    { Fq2::roll(10) }
+                    { Fq2::add(2, 0) }
+                    { Fq2::square() }
+                    { Fq2::copy(4) }
+                    { Fq2::sub(2, 0) }
+                    { Fq2::copy(2) }
+                    { Fq2::sub(2, 0) }
+                    { Fq2::double(0) }
+                    // [y, z, A, C, D]
+
+                    // E = 3 * A, F = E^2
+                    { Fq2::triple(4) }
+                    { Fq2::copy(0) }
+                    { Fq2::square() }
+                    // [y, z, C, D, E, F]
+
+                    // x' = F - 2 * D
+                    { Fq2::copy(4) }
+                    { Fq2::double(0) }
+                    { Fq2::sub(2, 0) }
+                    // [y, z, C, D, E, x']
+
+                    // y' = E * (D - x') - 8 * C
+                    { Fq2::copy(0) }
+                    { Fq2::roll(6) }
+                    { Fq2::sub(0, 2) }
+                    { Fq2::mul(4, 0) }
+                    { Fq2::roll(4) }
+                    { Fq2::double(0) }
+                    { Fq2::double(0) }
+                    { Fq2::double(0) }
+                    { Fq2::sub(2, 0) }
+                    // [y, z, x', y']
+
+                    // z' = 2 * y * z
+                    { Fq2::roll(6) }
+                    { Fq2::roll(6) }
+                    { Fq2::mul(2, 0) }
+                    { Fq2::double(0) }
+                }
+            })
+            .clone()
+    }
+
+    pub fn double() -> Script {
+        script! {
+            { G2Projective::copy(0) }
+            { G2Projective::toaltstack() }
+            // Check if the first point is zero
+            { G2Projective::is_zero_keep_element(0) }
+            OP_TOALTSTACK
+            // Perform a regular doubling
+            { G2Projective::nonzero_double() }
+
+            // Select result
+            OP_FROMALTSTACK
+            OP_IF
+                // Return original point
+                { G2Projective::drop() }
+                { G2Projective::fromaltstack() }
+            OP_ELSE
+                // Return regular doubling result
+                { G2Projective::fromaltstack() }
+                { G2Projective::drop() }
+            OP_ENDIF
+        }
+    }
+
+    // Input Stack: [x1, y1, z1, x2, y2, z2]
+    // Output Stack: [P1 + P2]
+    // Neither point is the identity, and the points are different.
+    pub fn nonzero_add() -> Script {
+        G2_NONZERO_ADD_PROJECTIVE
+            .get_or_init(|| {
+                script! {
+                    // z1z1 = z1^2, z2z2 = z2^2
+                    { Fq2::copy(6) }
+                    { Fq2::square() }
+                    { Fq2::copy(2) }
+                    { Fq2::square() }
+
+                    // u1 = x1 * z2z2
+                    { Fq2::copy(0) }
+                    { Fq2::roll(16) }
+                    { Fq2::mul(2, 0) }
+                    // u2 = x2 * z1z1
+                    { Fq2::copy(4) }
+                    { Fq2::roll(12) }
+                    { Fq2::mul(2, 0) }
+                    // [y1, z1, y2, z2, z1z1, z2z2, u1, u2]
+
+                    // s1 = y1 * z2 * z2z2
+                    { Fq2::copy(4) }
+                    { Fq2::copy(10) }
+                    { Fq2::mul(2, 0) }
+                    { Fq2::roll(16) }
+                    { Fq2::mul(2, 0) }
+                    // s2 = y2 * z1 * z1z1
+                    { Fq2::copy(14) }
+                    { Fq2::copy(10) }
+                    { Fq2::mul(2, 0) }
+                    { Fq2::roll(14) }
+                    { Fq2::mul(2, 0) }
+                    // [z1, z2, z1z1, z2z2, u1, u2, s1, s2]
+
+                    // (z1 + z2)^2 - z1z1 - z2z2
+                    { Fq2::roll(14) }
+                    { Fq2::roll(14) }
+                    { Fq2::add(2, 0) }
+                    { Fq2::square() }
+                    { Fq2::roll(12) }
+                    { Fq2::sub(2, 0) }
+                    { Fq2::roll(10) }
+                    { Fq2::sub(2, 0) }
+                    // [u1, u2, s1, s2, (z1 + z2)^2 - z1z1 - z2z2]
+
+                    // h = u2 - u1
+                    { Fq2::roll(6) }
+                    { Fq2::copy(8) }
+                    { Fq2::sub(2, 0) }
+                    // z' = ((z1 + z2)^2 - z1z1 - z2z2) * h
+                    { Fq2::copy(0) }
+                    { Fq2::roll(4) }
+                    { Fq2::mul(2, 0) }
+                    { Fq2::toaltstack() }
+                    // [u1, s1, s2, h]
+
+                    // i = (2 * h)^2, j = h * i
+                    { Fq2::copy(0) }
+                    { Fq2::double(0) }
+                    { Fq2::square() }
+                    { Fq2::roll(2) }
+                    { Fq2::copy(2) }
+                    { Fq2::mul(2, 0) }
+                    // r = 2 * (s2 - s1)
+                    { Fq2::roll(4) }
+                    { Fq2::copy(6) }
+                    { Fq2::sub(2, 0) }
+                    { Fq2::double(0) }
+                    // v = u1 * i
+                    { Fq2::roll(8) }
+                    { Fq2::roll(6) }
+                    { Fq2::mul(2, 0) }
+                    // [s1, j, r, v]
+
+                    // x' = r^2 - j - 2 * v
+                    { Fq2::copy(2) }
+                    { Fq2::square() }
+                    { Fq2::copy(6) }
+                    { Fq2::sub(2, 0) }
+                    { Fq2::copy(2) }
+                    { Fq2::double(0) }
+                    { Fq2::sub(2, 0) }
+                    // [s1, j, r, v, x']
+
+                    // y' = r * (v - x') - 2 * s1 * j
+                    { Fq2::copy(0) }
+                    { Fq2::roll(4) }
+                    { Fq2::sub(0, 2) }
+                    { Fq2::roll(4) }
+                    { Fq2::mul(2, 0) }
+                    { Fq2::roll(6) }
+                    { Fq2::roll(6) }
+                    { Fq2::mul(2, 0) }
+                    { Fq2::double(0) }
+                    { Fq2::sub(2, 0) }
+                    // [x', y']
+
+                    { Fq2::fromaltstack() }
+                }
+            })
+            .clone()
+    }
+
+    pub fn add() -> Script {
+        script! {
+            { G2Projective::copy(0) }
+            { G2Projective::toaltstack() }
+            { G2Projective::copy(1) }
+            { G2Projective::toaltstack() }
+
+            // Check if the first point is zero
+            { G2Projective::is_zero_keep_element(0) }
+            OP_TOALTSTACK
+            // Check if the second point is zero
+            { G2Projective::is_zero_keep_element(1) }
+            OP_TOALTSTACK
+
+            // Perform a regular addition
+            { G2Projective::nonzero_add() }
+
+            // Select result
+            OP_FROMALTSTACK
+            OP_FROMALTSTACK
+            OP_IF
+                // First point is zero
+                OP_DROP
+                { G2Projective::drop() }
+                { G2Projective::fromaltstack() }
+                { G2Projective::fromaltstack() }
+                { G2Projective::drop() }
+            OP_ELSE
+                OP_IF
+                    // Second point is zero
+                    { G2Projective::drop() }
+                    { G2Projective::fromaltstack() }
+                    { G2Projective::drop() }
+                    { G2Projective::fromaltstack() }
+                OP_ELSE
+                    // Both summands are non-zero
+                    { G2Projective::fromaltstack() }
+                    { G2Projective::fromaltstack() }
+                    { G2Projective::drop() }
+                    { G2Projective::drop() }
+                OP_ENDIF
+            OP_ENDIF
+        }
+    }
+
+    pub fn neg() -> Script {
+        script! {
+            { Fq2::neg(2) }
+            { Fq2::roll(2) }
+        }
+    }
+
+    // Input Stack: [P]
+    // Output Stack: [k * P]
+    // The point isn't the identity, and it has no multiple [m]P = ±P for the prefixes m of k.
+    pub fn scalar_mul_by_constant(k: &BigUint) -> Script {
+        let bits = (0..k.bits()).rev().map(|i| k.bit(i)).collect::<Vec<_>>();
+        script! {
+            { G2Projective::copy(0) }
+            for bit in bits.iter().skip(1) {
+                { G2Projective::nonzero_double() }
+                if *bit {
+                    { G2Projective::copy(1) }
+                    { G2Projective::nonzero_add() }
+                }
+            }
+            { G2Projective::roll(1) }
+            { G2Projective::drop() }
+        }
+    }
+
+    pub fn copy(mut a: u32) -> Script {
+        a *= 6;
+        script! {
+            { Fq2::copy(a + 4) }
+            { Fq2::copy(a + 4) }
+            { Fq2::copy(a + 4) }
+        }
+    }
+
+    pub fn roll(mut a: u32) -> Script {
+        a *= 6;
+        script! {
+            { Fq2::roll(a + 4) }
+            { Fq2::roll(a + 4) }
+            { Fq2::roll(a + 4) }
+        }
+    }
+
+    // Input Stack: [x1, y1, z1, x2, y2, z2]
+    // Output Stack: []
+    // Fails unless x1 * z2^2 == x2 * z1^2 and y1 * z2^3 == y2 * z1^3.
+    pub fn equalverify() -> Script {
+        script! {
+            // z1^2, z2^2
+            { Fq2::copy(6) }
+            { Fq2::square() }
+            { Fq2::copy(2) }
+            { Fq2::square() }
+
+            // x1 * z2^2 == x2 * z1^2
+            { Fq2::roll(14) }
+            { Fq2::copy(2) }
+            { Fq2::mul(2, 0) }
+            { Fq2::roll(10) }
+            { Fq2::copy(6) }
+            { Fq2::mul(2, 0) }
+            { Fq2::equalverify() }
+            // [y1, z1, y2, z2, z1^2, z2^2]
+
+            // y1 * z2^3 == y2 * z1^3
+            { Fq2::roll(4) }
+            { Fq2::mul(2, 0) }
+            { Fq2::roll(8) }
+            { Fq2::mul(2, 0) }
+            { Fq2::roll(2) }
+            { Fq2::roll(6) }
+            { Fq2::mul(2, 0) }
+            { Fq2::roll(4) }
+            { Fq2::mul(2, 0) }
+            { Fq2::equalverify() }
+        }
+    }
+
+    pub fn drop() -> Script {
+        script! {
+            { Fq2::drop() }
+            { Fq2::drop() }
+            { Fq2::drop() }
+        }
+    }
+
+    pub fn toaltstack() -> Script {
+        script! {
+            { Fq2::toaltstack() }
+            { Fq2::toaltstack() }
+            { Fq2::toaltstack() }
+        }
+    }
+
+    pub fn fromaltstack() -> Script {
+        script! {
+            { Fq2::fromaltstack() }
+            { Fq2::fromaltstack() }
+            { Fq2::fromaltstack() }
+        }
+    }
+
+    // Input Stack: [x, y, z]
+    // Output Stack: [x/z^2, y/z^3], [0, 0] for the identity
+    pub fn into_affine() -> Script {
+        script! {
+            { G2Projective::is_zero_keep_element(0) }
+            OP_IF
+                { G2Projective::drop() }
+                { G2Affine::identity() }
+            OP_ELSE
+                { Fq2::inv() }
+                { Fq2::copy(0) }
+                { Fq2::square() }
+                { Fq2::copy(0) }
+                { Fq2::roll(4) }
+                { Fq2::mul(2, 0) }
+                // [x, y, 1/z^2, 1/z^3]
+                { Fq2::roll(4) }
+                { Fq2::mul(2, 0) }
+                { Fq2::toaltstack() }
+                { Fq2::mul(2, 0) }
+                { Fq2::fromaltstack() }
+            OP_ENDIF
+        }
+    }
+}
+
+/// Points of the twist over Fq2 in affine coordinates, [x, y] on the stack. The identity is
+/// [0, 0].
+pub struct G2Affine;
+
+impl G2Affine {
+    pub fn push(element: ark_bn254::G2Affine) -> Script {
+        script! {
+            { fq2_push(element.x) }
+            { fq2_push(element.y) }
+        }
+    }
+
+    pub fn identity() -> Script {
+        script! {
+            { Fq2::push_zero() }
+            { Fq2::push_zero() }
+        }
+    }
+
+    // Input Stack: [x, y]
+    // Output Stack: [y^2 == x^3 + b]
+    pub fn is_on_curve() -> Script {
+        script! {
+            { Fq2::copy(2) }
+            { Fq2::square() }
+            { Fq2::roll(4) }
+            { Fq2::mul(2, 0) }
+            { fq2_push(ark_bn254::g2::Config::COEFF_B) }
+            { Fq2::add(2, 0) }
+            { Fq2::roll(2) }
+            { Fq2::square() }
+            { G2Affine::fq2_equal() }
+        }
+    }
+
+    // Input Stack: [x, y]
+    // Output Stack: [psi(P) == 6x^2 * P]
+    // The point is on the curve and isn't the identity. psi is the untwist-Frobenius-twist
+    // endomorphism, which acts as multiplication by p on the subgroup of order r, see section 4.3
+    // of https://eprint.iacr.org/2022/352.pdf.
+    pub fn is_in_subgroup() -> Script {
+        script! {
+            // psi(P)
+            { G2Affine::copy(0) }
+            { Fq2::roll(2) }
+            { Fq2::frobenius_map(1) }
+            { Fq2::mul_by_constant(&ark_bn254::Config::TWIST_MUL_BY_Q_X) }
+            { Fq2::roll(2) }
+            { Fq2::frobenius_map(1) }
+            { Fq2::mul_by_constant(&ark_bn254::Config::TWIST_MUL_BY_Q_Y) }
+            { G2Affine::toaltstack() }
+
+            // 6x^2 * P, a point of order r has no multiple [m]P = ±P with m < r
+            { G2Affine::into_projective() }
+            { G2Projective::scalar_mul_by_constant(&BigUint::from_str(SIX_X_SQUARED).unwrap()) }
+            { G2Projective::into_affine() }
+
+            { G2Affine::fromaltstack() }
+            { Fq2::roll(4) }
+            { G2Affine::fq2_equal() }
+            OP_TOALTSTACK
+            { G2Affine::fq2_equal() }
+            OP_FROMALTSTACK
+            OP_BOOLAND
+        }
+    }
+
+    // Input Stack: [x, flag]
+    // Output Stack: [x, y]
+    // y is the square root of x^3 + b which is negative iff flag is 1, in the sense of arkworks'
+    // compressed encoding: y > -y, comparing the c1 components first. Fails unless x is made of
+    // canonical field elements and is the x coordinate of a point on the curve.
+    pub fn decompress() -> Script {
+        script! {
+            OP_DUP 0 2 OP_WITHIN OP_VERIFY
+            OP_TOALTSTACK
+            { Fq::copy(1) }
+            { Fq::is_canonical() }
+            OP_VERIFY
+            { Fq::copy(0) }
+            { Fq::is_canonical() }
+            OP_VERIFY
+
+            // compute x^3 + b and its square root
+            { Fq2::copy(0) }
+            { Fq2::square() }
+            { Fq2::copy(2) }
+            { Fq2::mul(2, 0) }
+            { fq2_push(ark_bn254::g2::Config::COEFF_B) }
+            { Fq2::add(2, 0) }
+            { Fq2::copy(0) }
+            { Fq2::sqrt() }
+            { Fq2::copy(0) }
+            { Fq2::square() }
+            { Fq2::roll(4) }
+            { Fq2::equalverify() }
+
+            // y is negative iff y.c1 >= (p + 1) / 2, or y.c0 >= (p + 1) / 2 if y.c1 is zero
+            { Fq::is_zero_keep_element(0) }
+            OP_IF
+                { Fq::copy(1) }
+            OP_ELSE
+                { Fq::copy(0) }
+            OP_ENDIF
+            { Fq::decode_montgomery() }
+            { U254::push_hex(Fq::P_PLUS_ONE_DIV2) }
+            { U254::greaterthanorequal(1, 0) }
+            OP_FROMALTSTACK
+            OP_EQUAL
+            OP_NOTIF
+                { Fq2::neg(0) }
+            OP_ENDIF
+        }
+    }
+
+    // Input Stack: [x, y]
+    // Output Stack: [is_valid]
+    // Checks a point received in a witness, say a proof point: its coordinates are canonical
+    // field elements, it is on the curve and in the subgroup of order r. The identity isn't valid.
+    pub fn is_valid() -> Script {
+        script! {
+            for i in 0..4 {
+                { Fq::copy(i) }
+                { Fq::is_canonical() }
+                OP_TOALTSTACK
+            }
+            { G2Affine::copy(0) }
+            { G2Affine::is_on_curve() }
+            OP_TOALTSTACK
+            // only meaningful on the curve, hence the conjunction with the check above
+            { G2Affine::is_in_subgroup() }
+            for _ in 0..5 {
+                OP_FROMALTSTACK
+                OP_BOOLAND
+            }
+        }
+    }
+
+    // Input Stack: [x1, y1, x2, y2]
+    // Output Stack: [x3, y3]
+    // Neither point is the identity, and x1 != x2.
+    pub fn add() -> Script {
+        script! {
+            // lambda = (y2 - y1) / (x2 - x1)
+            { Fq2::copy(0) }
+            { Fq2::copy(6) }
+            { Fq2::sub(2, 0) }
+            { Fq2::copy(4) }
+            { Fq2::copy(10) }
+            { Fq2::sub(2, 0) }
+            { Fq2::inv() }
+            { Fq2::mul(2, 0) }
+            { Fq2::roll(2) }
+            { Fq2::drop() }
+            // [x1, y1, x2, lambda]
+
+            // x3 = lambda^2 - x1 - x2
+            { Fq2::copy(0) }
+            { Fq2::square() }
+            { Fq2::roll(4) }
+            { Fq2::sub(2, 0) }
+            { Fq2::copy(6) }
+            { Fq2::sub(2, 0) }
+            // [x1, y1, lambda, x3]
+
+            // y3 = lambda * (x1 - x3) - y1
+            { Fq2::copy(0) }
+            { Fq2::roll(8) }
+            { Fq2::sub(0, 2) }
+            { Fq2::roll(4) }
+            { Fq2::mul(2, 0) }
+            { Fq2::roll(4) }
+            { Fq2::sub(2, 0) }
+        }
+    }
+
+    // Input Stack: [x, y]
+    // Output Stack: [x', y']
+    // The point isn't the identity.
+    pub fn double() -> Script {
+        script! {
+            // lambda = 3 * x^2 / (2 * y)
+            { Fq2::copy(2) }
+            { Fq2::square() }
+            { Fq2::triple(0) }
+            { Fq2::copy(2) }
+            { Fq2::double(0) }
+            { Fq2::inv() }
+            { Fq2::mul(2, 0) }
+            // [x, y, lambda]
+
+            // x' = lambda^2 - 2 * x
+            { Fq2::copy(0) }
+            { Fq2::square() }
+            { Fq2::copy(6) }
+            { Fq2::double(0) }
+            { Fq2::sub(2, 0) }
+            // [x, y, lambda, x']
+
+            // y' = lambda * (x - x') - y
+            { Fq2::roll(6) }
+            { Fq2::copy(2) }
+            { Fq2::sub(2, 0) }
+            { Fq2::roll(4) }
+            { Fq2::mul(2, 0) }
+            { Fq2::roll(4) }
+            { Fq2::sub(2, 0) }
+        }
+    }
+
+    pub fn neg() -> Script {
+        script! {
+            { Fq2::neg(0) }
+        }
+    }
+
+    // Input Stack: [x1, y1, x2, y2]
+    // Output Stack: []
+    pub fn equalverify() -> Script {
+        script! {
+            { Fq2::roll(4) }
+            { Fq2::equalverify() }
+            { Fq2::equalverify() }
+        }
+    }
+
+    pub fn copy(mut a: u32) -> Script {
+        a *= 4;
+        script! {
+            { Fq2::copy(a + 2) }
+            { Fq2::copy(a + 2) }
+        }
+    }
+
+    pub fn roll(mut a: u32) -> Script {
+        a *= 4;
+        script! {
+            { Fq2::roll(a + 2) }
+            { Fq2::roll(a + 2) }
+        }
+    }
+
+    pub fn drop() -> Script {
+        script! {
+            { Fq2::drop() }
+            { Fq2::drop() }
+        }
+    }
+
+    pub fn toaltstack() -> Script {
+        script! {
+            { Fq2::toaltstack() }
+            { Fq2::toaltstack() }
+        }
+    }
+
+    pub fn fromaltstack() -> Script {
+        script! {
+            { Fq2::fromaltstack() }
+            { Fq2::fromaltstack() }
+        }
+    }
+
+    // Input Stack: [x, y]
+    // Output Stack: [x, y, z] (z=1)
+    pub fn into_projective() -> Script {
+        script! {
+            { Fq2::push_one() }
+        }
+    }
+
+    // Input Stack: [a, b]
+    // Output Stack: [a == b]
+    fn fq2_equal() -> Script {
+        script! {
+            { Fq::equal(3, 1) }
+            OP_TOALTSTACK
+            { Fq::equal(1, 0) }
+            OP_FROMALTSTACK
+            OP_BOOLAND
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::bn254::differential::{check_gadget, check_gadget_with_normalization, Case, Limits};
+    use crate::bn254::g2::{G2Affine, G2Projective};
+    use crate::bn254::utils::{fq2_witness, g2_affine_witness};
+    use ark_ec::{AffineRepr, CurveGroup};
+    use ark_ff::{Field, UniformRand};
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+
+    // Measured sizes and stack maxima of the gadgets plus a 1% margin on the size and 10 stack
+    // items, see `differential::Limits`
+    const PROJECTIVE_ADD_LIMITS: Limits = Limits {
+        script_size: 6_160_000,
+        max_stack_items: 435,
+    };
+    const PROJECTIVE_DOUBLE_LIMITS: Limits = Limits {
+        script_size: 2_300_000,
+        max_stack_items: 317,
+    };
+    const PROJECTIVE_NEG_LIMITS: Limits = Limits {
+        script_size: 501,
+        max_stack_items: 67,
+    };
+    const PROJECTIVE_EQUALVERIFY_LIMITS: Limits = Limits {
+        script_size: 3_150_000,
+        max_stack_items: 307,
+    };
+    const AFFINE_ADD_LIMITS: Limits = Limits {
+        script_size: 7_180_000,
+        max_stack_items: 383,
+    };
+    const AFFINE_DOUBLE_LIMITS: Limits = Limits {
+        script_size: 7_470_000,
+        max_stack_items: 347,
+    };
+    const AFFINE_NEG_LIMITS: Limits = Limits {
+        script_size: 447,
+        max_stack_items: 49,
+    };
+    const IS_ON_CURVE_LIMITS: Limits = Limits {
+        script_size: 1_010_000,
+        max_stack_items: 208,
+    };
+    const IS_IN_SUBGROUP_LIMITS: Limits = Limits {
+        script_size: 723_000_000,
+        max_stack_items: 415,
+    };
+    const DECOMPRESS_LIMITS: Limits = Limits {
+        script_size: 108_000_000,
+        max_stack_items: 340,
+    };
+    const IS_VALID_LIMITS: Limits = Limits {
+        script_size: 724_000_000,
+        max_stack_items: 420,
+    };
+
+    fn g2_projective_witness(point: ark_bn254::G2Projective) -> Vec<Vec<u8>> {
+        [
+            fq2_witness(point.x),
+            fq2_witness(point.y),
+            fq2_witness(point.z),
+        ]
+        .concat()
+    }
+
+    // A random point of the twist, almost never in the subgroup of order r
+    fn random_point_on_twist(prng: &mut ChaCha20Rng) -> ark_bn254::G2Affine {
+        loop {
+            let x = ark_bn254::Fq2::rand(prng);
+            if let Some(point) = ark_bn254::G2Affine::get_point_from_x_unchecked(x, false) {
+                return point;
+            }
+        }
+    }
+
+    fn bool_witness(value: bool) -> Vec<Vec<u8>> { vec![if value { vec![1] } else { vec![] }] }
+
+    #[test]
+    fn test_projective_add_and_double() {
+        let report = check_gadget_with_normalization(
+            "G2Projective::add",
+            G2Projective::add(),
+            G2Projective::into_affine(),
+            PROJECTIVE_ADD_LIMITS,
+            0,
+            3,
+            |prng| {
+                let a = ark_bn254::G2Projective::rand(prng);
+                let b = ark_bn254::G2Projective::rand(prng);
+                Case {
+                    inputs: [g2_projective_witness(a), g2_projective_witness(b)].concat(),
+                    outputs: g2_affine_witness((a + b).into_affine()),
+                }
+            },
+        );
+        println!("{}", report);
+
+        // the identity on either side
+        let report = check_gadget_with_normalization(
+            "G2Projective::add",
+            G2Projective::add(),
+            G2Projective::into_affine(),
+            PROJECTIVE_ADD_LIMITS,
+            0,
+            2,
+            |prng| {
+                let a = ark_bn254::G2Projective::rand(prng);
+                let zero = ark_bn254::G2Projective::default();
+                let inputs = if bool::rand(prng) {
+                    [a, zero]
+                } else {
+                    [zero, a]
+                };
+                Case {
+                    inputs: inputs.map(g2_projective_witness).concat(),
+                    outputs: g2_affine_witness(a.into_affine()),
+                }
+            },
+        );
+        println!("{}", report);
+
+        let report = check_gadget_with_normalization(
+            "G2Projective::double",
+            G2Projective::double(),
+            G2Projective::into_affine(),
+            PROJECTIVE_DOUBLE_LIMITS,
+            0,
+            3,
+            |prng| {
+                let a = ark_bn254::G2Projective::rand(prng);
+                Case {
+                    inputs: g2_projective_witness(a),
+                    outputs: g2_affine_witness((a + a).into_affine()),
+                }
+            },
+        );
+        println!("{}", report);
+    }
+
+    #[test]
+    fn test_projective_neg_and_equalverify() {
+        let report = check_gadget_with_normalization(
+            "G2Projective::neg",
+            G2Projective::neg(),
+            G2Projective::into_affine(),
+            PROJECTIVE_NEG_LIMITS,
+            0,
+            3,
+            |prng| {
+                let a = ark_bn254::G2Projective::rand(prng);
+                Case {
+                    inputs: g2_projective_witness(a),
+                    outputs: g2_affine_witness((-a).into_affine()),
+                }
+            },
+        );
+        println!("{}", report);
+
+        let report = check_gadget(
+            "G2Projective::equalverify",
+            G2Projective::equalverify(),
+            PROJECTIVE_EQUALVERIFY_LIMITS,
+            0,
+            3,
+            |prng| {
+                let a = ark_bn254::G2Projective::rand(prng);
+                // same point, different coordinates
+                let b = a + a - a;
+                Case {
+                    inputs: [g2_projective_witness(a), g2_projective_witness(b)].concat(),
+                    outputs: vec![],
+                }
+            },
+        );
+        println!("{}", report);
+    }
+
+    #[test]
+    #[should_panic(expected = "doesn't match the reference")]
+    fn test_projective_equalverify_different_points() {
+        check_gadget(
+            "G2Projective::equalverify",
+            G2Projective::equalverify(),
+            PROJECTIVE_EQUALVERIFY_LIMITS,
+            0,
+            1,
+            |prng| {
+                let a = ark_bn254::G2Projective::rand(prng);
+                let b = ark_bn254::G2Projective::rand(prng);
+                Case {
+                    inputs: [g2_projective_witness(a), g2_projective_witness(b)].concat(),
+                    outputs: vec![],
+                }
+            },
+        );
+    }
+
+    #[test]
+    fn test_affine_add_and_double() {
+        let report = check_gadget(
+            "G2Affine::add",
+            G2Affine::add(),
+            AFFINE_ADD_LIMITS,
+            0,
+            3,
+            |prng| {
+                let a = ark_bn254::G2Affine::rand(prng);
+                let b = ark_bn254::G2Affine::rand(prng);
+                Case {
+                    inputs: [g2_affine_witness(a), g2_affine_witness(b)].concat(),
+                    outputs: g2_affine_witness((a + b).into_affine()),
+                }
+            },
+        );
+        println!("{}", report);
+
+        let report = check_gadget(
+            "G2Affine::double",
+            G2Affine::double(),
+            AFFINE_DOUBLE_LIMITS,
+            0,
+            3,
+            |prng| {
+                let a = ark_bn254::G2Affine::rand(prng);
+                Case {
+                    inputs: g2_affine_witness(a),
+                    outputs: g2_affine_witness((a + a).into_affine()),
+                }
+            },
+        );
+        println!("{}", report);
+
+        let report = check_gadget(
+            "G2Affine::neg",
+            G2Affine::neg(),
+            AFFINE_NEG_LIMITS,
+            0,
+            3,
+            |prng| {
+                let a = ark_bn254::G2Affine::rand(prng);
+                Case {
+                    inputs: g2_affine_witness(a),
+                    outputs: g2_affine_witness(-a),
+                }
+            },
+        );
+        println!("{}", report);
+    }
+
+    #[test]
+    fn test_is_on_curve() {
+        let report = check_gadget(
+            "G2Affine::is_on_curve",
+            G2Affine::is_on_curve(),
+            IS_ON_CURVE_LIMITS,
+            0,
+            6,
+            |prng| {
+                let mut a = ark_bn254::G2Affine::rand(prng);
+                if bool::rand(prng) {
+                    a.y += ark_bn254::Fq2::ONE;
+                }
+                Case {
+                    inputs: g2_affine_witness(a),
+                    outputs: bool_witness(a.is_on_curve()),
+                }
+            },
+        );
+        println!("{}", report);
+    }
+
+    #[test]
+    fn test_is_in_subgroup() {
+        let report = check_gadget(
+            "G2Affine::is_in_subgroup",
+            G2Affine::is_in_subgroup(),
+            IS_IN_SUBGROUP_LIMITS,
+            0,
+            2,
+            |prng| {
+                let a = if bool::rand(prng) {
+                    ark_bn254::G2Affine::rand(prng)
+                } else {
+                    random_point_on_twist(prng)
+                };
+                Case {
+                    inputs: g2_affine_witness(a),
+                    outputs: bool_witness(a.is_in_correct_subgroup_assuming_on_curve()),
+                }
+            },
+        );
+        println!("{}", report);
+
+        // both sides of the check
+        let mut prng = ChaCha20Rng::seed_from_u64(0);
+        assert!(ark_bn254::G2Affine::generator().is_in_correct_subgroup_assuming_on_curve());
+        assert!(!random_point_on_twist(&mut prng).is_in_correct_subgroup_assuming_on_curve());
+    }
+
+    #[test]
+    fn test_decompress() {
+        let report = check_gadget(
+            "G2Affine::decompress",
+            G2Affine::decompress(),
+            DECOMPRESS_LIMITS,
+            0,
+            4,
+            |prng| {
+                let a = ark_bn254::G2Affine::rand(prng);
+                let a = if bool::rand(prng) { a } else { -a };
+                Case {
+                    inputs: [fq2_witness(a.x), bool_witness(a.y > -a.y)].concat(),
+                    outputs: g2_affine_witness(a),
+                }
+            },
+        );
+        println!("{}", report);
+    }
+
+    #[test]
+    fn test_is_valid() {
+        let report = check_gadget(
+            "G2Affine::is_valid",
+            G2Affine::is_valid(),
+            IS_VALID_LIMITS,
+            0,
+            3,
+            |prng| {
+                let mut a = ark_bn254::G2Affine::rand(prng);
+                match u8::rand(prng) % 3 {
+                    0 => a.y += ark_bn254::Fq2::ONE,
+                    1 => a = random_point_on_twist(prng),
+                    _ => {}
+                }
+                Case {
+                    inputs: g2_affine_witness(a),
+                    outputs: bool_witness(
+                        a.is_on_curve() && a.is_in_correct_subgroup_assuming_on_curve(),
+                    ),
+                }
+            },
+        );
+        println!("{}", report);
+    }
+}
@@ -2,11 +2,14 @@ pub mod fq;
 pub mod fq12;
 pub mod fq2;
 pub mod fq6;
+pub mod g2;
 pub mod fr;
 
 pub mod curves;
+pub mod differential;
 
 pub mod pairing;
+pub mod pairing_check;
 
 pub mod ell_coeffs;
 
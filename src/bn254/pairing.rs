@@ -5,6 +5,7 @@ use crate::bn254::fq::Fq;
 use crate::bn254::fq12::Fq12;
 use crate::bn254::fq2::Fq2;
 use crate::bn254::utils;
+use crate::groth16::offchain_checker::compute_w;
 use crate::treepp::*;
 use ark_ec::bn::BnConfig;
 
@@ -238,235 +239,507 @@ impl Pairing {
     //
     // input of parameters:
     //     [L(Q1), L(Q2), L(Q3), L(Q4)] (line coefficients in affine mode)
-    pub fn quad_miller_loop_with_c_wi(constants: Vec<G2Prepared>) -> Script {
+    pub fn quad_miller_loop_with_c_wi(mut constants: Vec<G2Prepared>) -> Script {
         assert_eq!(constants.len(), 4);
-        let num_line_groups = constants.len();
-        let num_constant = 3;
+        let q4 = constants.pop().unwrap();
+
+        script! {
+            // L(Q4) as the hints of `quad_miller_loop_with_c_wi_and_hinted_lines`
+            for (_, c3, c4) in q4.ell_coeffs.iter() {
+                { utils::fq2_push(*c3) }
+                { utils::fq2_push(*c4) }
+            }
+            for _ in 0..4 * q4.ell_coeffs.len() {
+                { Fq::toaltstack() }
+            }
+
+            { Self::quad_miller_loop_with_c_wi_and_hinted_lines(constants) }
+        }
+    }
+
+    // Checks the hints of `quad_miller_loop_with_c_wi` and its variants: c_inv is the inverse of c
+    // and wi is 1, w or w^2, see `offchain_checker::compute_c_wi`.
+    //
+    // input on stack:
+    //     [c, c_inv, wi]
+    // output on stack:
+    //     [c, c_inv, wi]
+    pub fn check_c_wi() -> Script {
+        let w = compute_w();
+
+        script! {
+            // wi is 1, w or w^2
+            { Fq12::copy(0) }
+            { Fq12::push_one() }
+            { Fq12::equal() }
+            OP_TOALTSTACK
+            for wi in [w, w * w] {
+                { Fq12::copy(0) }
+                { utils::fq12_push(wi) }
+                { Fq12::equal() }
+                OP_FROMALTSTACK
+                OP_BOOLOR
+                OP_TOALTSTACK
+            }
+            OP_FROMALTSTACK
+            OP_VERIFY
+
+            // c_inv is the inverse of c
+            { Fq12::copy(24) }
+            { Fq12::copy(24) }
+            { Fq12::mul(12, 0) }
+            { Fq12::push_one() }
+            { Fq12::equalverify() }
+        }
+    }
+
+    // Same as `quad_miller_loop_with_c_wi`, except that the line coefficients of Q4 are hints on
+    // the altstack instead of parameters, so that the script does not depend on Q4. Each line
+    // (c3, c4) used on T4 is still checked to be the tangent or chord line through T4.
+    //
+    // input on stack:
+    //     [beta_12, beta_13, beta_22, P1', P2', P3', P4', Q4, c, c_inv, wi, T4]
+    //
+    // input on altstack:
+    //     [L(Q4)] line coefficients (c3, c4) of Q4 as returned by `Pairing::hinted_line_coeffs`,
+    //     the first line on top
+    //
+    // input of parameters:
+    //     [L(Q1), L(Q2), L(Q3)] (line coefficients in affine mode)
+    pub fn quad_miller_loop_with_c_wi_and_hinted_lines(constants: Vec<G2Prepared>) -> Script {
+        assert_eq!(constants.len(), 3);
+        Self::multi_miller_loop_with_c_wi_and_hinted_lines(constants, 1)
+    }
+
+    // Generalization of `quad_miller_loop_with_c_wi_and_hinted_lines` to n pairings, of which the
+    // first m = constants.len() are on fixed G2 points and the last v = num_variable on G2 points
+    // provided by the prover, with hinted line coefficients.
+    //
+    // input on stack:
+    //     [beta_12, beta_13, beta_22, P_0', ..., P_{n-1}', Q_m, ..., Q_{n-1}, c, c_inv, wi, T_m, ..., T_{n-1}]
+    //     P_i' = (-P_i.x / P_i.y, 1 / P_i.y)
+    //     T_k is the accumulator of Q_k, initially T_k = Q_k
+    //
+    // input on altstack:
+    //     [L(Q_m, ..., Q_{n-1})] line coefficients (c3, c4) as returned by
+    //     `Pairing::multi_hinted_line_coeffs`, the first line on top
+    //
+    // input of parameters:
+    //     [L(Q_0), ..., L(Q_{m-1})] (line coefficients in affine mode)
+    pub fn multi_miller_loop_with_c_wi_and_hinted_lines(
+        constants: Vec<G2Prepared>,
+        num_variable: usize,
+    ) -> Script {
+        Self::hinted_multi_miller_loop(constants, num_variable, true)
+    }
+
+    // Same as `multi_miller_loop_with_c_wi_and_hinted_lines` without the hints c, c_inv and wi,
+    // f is the plain miller loop output, to be checked with `Pairing::final_exponentiation`.
+    //
+    // input on stack:
+    //     [beta_12, beta_13, beta_22, P_0', ..., P_{n-1}', Q_m, ..., Q_{n-1}, T_m, ..., T_{n-1}]
+    pub fn multi_miller_loop_with_hinted_lines(
+        constants: Vec<G2Prepared>,
+        num_variable: usize,
+    ) -> Script {
+        Self::hinted_multi_miller_loop(constants, num_variable, false)
+    }
+
+    fn hinted_multi_miller_loop(
+        constants: Vec<G2Prepared>,
+        num_variable: usize,
+        with_c_wi: bool,
+    ) -> Script {
+        let num_fixed = constants.len() as u32;
+        let v = num_variable as u32;
+        let n = num_fixed + v;
 
         let line_coeffs = utils::collect_line_coeffs(constants);
         let num_lines = line_coeffs.len();
 
-        let script = script! {
-            // [beta_12(2), beta_13(2), beta_22(2), P1(2), P2(2), P3(2), P4(2), Q4(4), c(12), c_inv(12), wi(12), T4(4)]
-            // 1. f = c_inv
-            { Fq12::copy(16) }
-            // [beta_12(2), beta_13(2), beta_22(2), P1(2), P2(2), P3(2), P4(2), Q4(4), c(12), c_inv(12), wi(12), T4(4), f(12)]
+        // [..., T, f | c3, c4] => [..., T, c3, c4, f, P'(2), c3, c4]
+        let pull_line_and_copy_p = |p_index: u32| {
+            script! {
+                { Fq2::fromaltstack() }
+                { Fq2::fromaltstack() }
+                { Fq12::roll(4) }
+                { Fq2::copy(p_index + 4) }
+                { Fq2::copy(16) }
+                { Fq2::copy(16) }
+            }
+        };
+
+        // [..., T_a, T_b, ..., f] => [..., T_b, ..., T_a, f]
+        // Each accumulator is updated on top of the others, after `num_accumulators` rotations
+        // they are back in order.
+        let rotate_accumulators = |num_accumulators: u32| {
+            script! {
+                if num_accumulators > 1 {
+                    { Fq2::roll(10 + 4 * num_accumulators) }
+                    { Fq2::roll(10 + 4 * num_accumulators) }
+                    { Fq12::roll(4) }
+                }
+            }
+        };
+
+        // indices with f on top during the loop
+        let c_inv_index = 24 + 4 * v;
+        let c_index = 36 + 4 * v;
+        // c, c_inv and wi
+        let num_hint_elements = if with_c_wi { 36 } else { 0 };
+        let loop_p_index = |j: u32| 12 + num_hint_elements + 8 * v + 2 * (n - 1 - j);
+        // indices with f on top once c, c_inv and wi are consumed
+        let p_index = |j: u32| 12 + 8 * v + 2 * (n - 1 - j);
+
+        script! {
+            // [beta_12(2), beta_13(2), beta_22(2), P(2n), Q(4v), c(12), c_inv(12), wi(12), T(4v)]
+            // 1. f = c_inv, or one without hints
+            if with_c_wi {
+                { Fq12::copy(12 + 4 * v) }
+            } else {
+                { Fq12::push_one() }
+            }
+            // [beta_12(2), beta_13(2), beta_22(2), P(2n), Q(4v), c(12), c_inv(12), wi(12), T(4v), f(12)]
 
-            // ATE_LOOP_COUNT len: 65
             for i in (1..ark_bn254::Config::ATE_LOOP_COUNT.len()).rev() {
-                // update f, squaring
-                { Fq12::square() }
-                // [beta_12(2), beta_13(2), beta_22(2), P1(2), P2(2), P3(2), P4(2), Q4(4), c(12), c_inv(12), wi(12), T4(4), f(12)]
+                // update f, squaring, but for f = 1
+                if with_c_wi || i != ark_bn254::Config::ATE_LOOP_COUNT.len() - 1 {
+                    { Fq12::square() }
+                }
 
                 // update f, multiplying
                 // f = f * c_inv, if digit == 1
                 // f = f * c, if digit == -1
-                if ark_bn254::Config::ATE_LOOP_COUNT[i - 1] == 1 {
-                    // copy c_inv
-                    { Fq12::copy(28) }
-                    // [beta_12(2), beta_13(2), beta_22(2), P1(2), P2(2), P3(2), P4(2), Q4(4), c(12), c_inv(12), wi(12), T4(4), f(12), c_inv(12)]
-                    // f = f * c_inv
+                if with_c_wi && ark_bn254::Config::ATE_LOOP_COUNT[i - 1] == 1 {
+                    { Fq12::copy(c_inv_index) }
                     { Fq12::mul(12, 0) }
-                } else if ark_bn254::Config::ATE_LOOP_COUNT[i - 1] == -1 {
-                    // copy c
-                    { Fq12::copy(40) }
-                    // [beta_12(2), beta_13(2), beta_22(2), P1(2), P2(2), P3(2), P4(2), Q4(4), c(12), c_inv(12), wi(12), T4(4), f(12), c(12)]
-                    // f = f * c
+                } else if with_c_wi && ark_bn254::Config::ATE_LOOP_COUNT[i - 1] == -1 {
+                    { Fq12::copy(c_index) }
                     { Fq12::mul(12, 0) }
                 }
-                // [beta_12(2), beta_13(2), beta_22(2), P1(2), P2(2), P3(2), P4(2), Q4(4), c(12), c_inv(12), wi(12), T4(4), f(12)]
 
                 // update f with double line evaluation
-                for j in 0..num_line_groups {
-                    // copy P_j(p1, p2, p3, p4) to stack
-                    { Fq2::copy((26 + 36 - j * 2) as u32) }
-                    // update f with double line evaluation
-                    { utils::ell_by_constant_affine(&line_coeffs[num_lines - (i + 2)][j][0]) }
-                    // [beta_12(2), beta_13(2), beta_22(2), P1(2), P2(2), P3(2), P4(2), Q4(4), c(12), c_inv(12), wi(12), T4(4), f(12)]
-
-                    // non-fixed part
-                    if j == num_constant {
-                        // check line coeff is satisfied with T4
-                        { Fq12::toaltstack() }
-                        // [beta_12(2), beta_13(2), beta_22(2), P1(2), P2(2), P3(2), P4(2), Q4(4), c(12), c_inv(12), wi(12), T4(4) | f(12)]
-                        { Fq2::copy(2) }
-                        { Fq2::copy(2) }
-                        // [beta_12(2), beta_13(2), beta_22(2), P1(2), P2(2), P3(2), P4(2), Q4(4), c(12), c_inv(12), wi(12), T4(4), T4(4) | f(12)]
-                        { utils::check_tangent_line(line_coeffs[num_lines - (i + 2)][j][0].1, line_coeffs[num_lines - (i + 2)][j][0].2) }
-                        // [beta_12(2), beta_13(2), beta_22(2), P1(2), P2(2), P3(2), P4(2), Q4(4), c(12), c_inv(12), wi(12), T4(4) | f(12)]
-
-                        // update T4
-                        // drop T4.y, leave T4.x
-                        { Fq2::drop() }
-                        // [beta_12(2), beta_13(2), beta_22(2), P1(2), P2(2), P3(2), P4(2), Q4(4), c(12), c_inv(12), wi(12), T4.x(2) | f(12)]
-                        { utils::affine_double_line(line_coeffs[num_lines - (i + 2)][j][0].1, line_coeffs[num_lines - (i + 2)][j][0].2) }
-                        // [beta_12(2), beta_13(2), beta_22(2), P1(2), P2(2), P3(2), P4(2), Q4(4), c(12), c_inv(12), wi(12), T4(4) | f(12)]
-                        { Fq12::fromaltstack() }
-                        // [beta_12(2), beta_13(2), beta_22(2), P1(2), P2(2), P3(2), P4(2), Q4(4), c(12), c_inv(12), wi(12), T4(4), f(12)]
-                    }
+                for j in 0..num_fixed {
+                    { Fq2::copy(loop_p_index(j)) }
+                    { utils::ell_by_constant_affine(&line_coeffs[num_lines - (i + 2)][j as usize][0]) }
                 }
 
-                // update f with add line evaluation
-                if ark_bn254::Config::ATE_LOOP_COUNT[i - 1] == 1 || ark_bn254::Config::ATE_LOOP_COUNT[i - 1] == -1 {
-                    for j in 0..num_line_groups {
-                        // copy P_j(p1, p2, p3, p4) to stack
-                        { Fq2::copy((26 + 36 - j * 2) as u32) }
-                        // update f with adding line evaluation
-                        { utils::ell_by_constant_affine(&line_coeffs[num_lines - (i + 2)][j][1]) }
-                        // [beta_12(2), beta_13(2), beta_22(2), P1(2), P2(2), P3(2), P4(2), Q4(4), c(12), c_inv(12), wi(12), T4(4), f(12)]
-
-                        // non-fixed part
-                        if j == num_constant {
-                            { Fq12::toaltstack() }
-                            // [beta_12(2), beta_13(2), beta_22(2), P1(2), P2(2), P3(2), P4(2), Q4(4), c(12), c_inv(12), wi(12), T4(4) | f(12)]
-
-                            // copy T4
-                            { Fq2::copy(2) }
-                            { Fq2::copy(2) }
-                            // [beta_12(2), beta_13(2), beta_22(2), P1(2), P2(2), P3(2), P4(2), Q4(4), c(12), c_inv(12), wi(12), T4(4), T4(4) | f(12)]
-
-                            // copy Q4
-                            { Fq2::copy(10 + 36) }
-                            { Fq2::copy(10 + 36) }
-                            // [beta_12(2), beta_13(2), beta_22(2), P1(2), P2(2), P3(2), P4(2), Q4(4), c(12), c_inv(12), wi(12), T4(4), T4(4), Q4(4) | f(12)]
-                            if ark_bn254::Config::ATE_LOOP_COUNT[i - 1] == -1 {
-                                { Fq2::neg(0) }
-                            }
-                            { utils::check_chord_line(line_coeffs[num_lines - (i + 2)][j][1].1, line_coeffs[num_lines - (i + 2)][j][1].2) }
-                            // [beta_12(2), beta_13(2), beta_22(2), P1(2), P2(2), P3(2), P4(2), Q4(4), c(12), c_inv(12), wi(12), T4(4) | f(12)]
-
-                            // update T4
-                            // drop T4.y, leave T4.x
-                            { Fq2::drop() }
-                            // [beta_12(2), beta_13(2), beta_22(2), P1(2), P2(2), P3(2), P4(2), Q4(4), c(12), c_inv(12), wi(12), T4.x(2) | f(12)]
-                            // copy Q4.x
-                            { Fq2::copy(4 + 36) }
-                            // [beta_12(2), beta_13(2), beta_22(2), P1(2), P2(2), P3(2), P4(2), Q4(4), c(12), c_inv(12), wi(12), T4.x(2), Q4.x(2) | f(12)]
-                            { utils::affine_add_line(line_coeffs[num_lines - (i + 2)][j][1].1, line_coeffs[num_lines - (i + 2)][j][1].2) }
-                            // [beta_12(2), beta_13(2), beta_22(2), P1(2), P2(2), P3(2), P4(2), Q4(4), c(12), c_inv(12), wi(12), T4(4) | f(12)]
-                            { Fq12::fromaltstack() }
-                            // [beta_12(2), beta_13(2), beta_22(2), P1(2), P2(2), P3(2), P4(2), Q4(4), c(12), c_inv(12), wi(12), T4(4), f(12)]
-                        }
-                    }
-                }
-            }
-
-            // update f with frobenius of c, say f = f * c_inv^p * c^{p^2}
-            { Fq12::roll(28) }
-            // [beta_12(2), beta_13(2), beta_22(2), P1(2), P2(2), P3(2), P4(2), Q4(4), c(12), wi(12), T4(4), f(12), c_inv(12)]
-            { Fq12::frobenius_map(1) }
-            // [beta_12(2), beta_13(2), beta_22(2), P1(2), P2(2), P3(2), P4(2), Q4(4), c(12), wi(12), T4(4), f(12), c_inv^p(12)]
-            { Fq12::mul(12, 0) }
-            // [beta_12(2), beta_13(2), beta_22(2), P1(2), P2(2), P3(2), P4(2), Q4(4), c(12), wi(12), T4(4), f(12)]
-            { Fq12::roll(28) }
-            // [beta_12(2), beta_13(2), beta_22(2), P1(2), P2(2), P3(2), P4(2), Q4(4), wi(12), T4(4), f(12), c(12)]
-            { Fq12::frobenius_map(2) }
-            // [beta_12(2), beta_13(2), beta_22(2), P1(2), P2(2), P3(2), P4(2), Q4(4), wi(12), T4(4), f(12), c^{p^2}(12)]
-            { Fq12::mul(12, 0) }
-            // [beta_12(2), beta_13(2), beta_22(2), P1(2), P2(2), P3(2), P4(2), Q4(4), wi(12), T4(4), f(12)]
-
-            // update f with scalar wi, say f = f * wi
-            { Fq12::roll(16) }
-            // [beta_12(2), beta_13(2), beta_22(2), P1(2), P2(2), P3(2), P4(2), Q4(4), T4(4), f(12), wi(12)]
-            { Fq12::mul(12, 0) }
-            // [beta_12(2), beta_13(2), beta_22(2), P1(2), P2(2), P3(2), P4(2), Q4(4), T4(4), f(12)]
-
-            // update f with add line evaluation of one-time of frobenius map on Q4
-            for j in 0..num_line_groups {
-                // copy P_j(p1, p2, p3, p4) to stack
-                { Fq2::copy((26 - j * 2) as u32) }
-                // [beta_12(2), beta_13(2), beta_22(2), P1(2), P2(2), P3(2), P4(2), Q4(4), T4(4), f(12), P_j(2)]
-                { utils::ell_by_constant_affine(&line_coeffs[num_lines - 2][j][0]) }
-                // [beta_12(2), beta_13(2), beta_22(2), P1(2), P2(2), P3(2), P4(2), Q4(4), T4(4), f(12)]
-
-                // non-fixed part
-                if j == num_constant {
+                // non-fixed part, update f with the hinted double line of each T_k
+                for k in 0..v {
+                    { rotate_accumulators(v) }
+                    { pull_line_and_copy_p(loop_p_index(num_fixed + k)) }
+                    { utils::ell_affine_in_stack() }
+                    // [..., Q(4v), c(12), c_inv(12), wi(12), T(4v), c3(2), c4(2), f(12)]
                     { Fq12::toaltstack() }
-                    // [beta_12(2), beta_13(2), beta_22(2), P1(2), P2(2), P3(2), P4(2), Q4(4), T4(4) | f(12)]
 
-                    // Qx' = Qx.conjugate * beta^{2 * (p - 1) / 6}
-                    { Fq2::copy(6) }
-                    // [beta_12(2), beta_13(2), beta_22(2), P1(2), P2(2), P3(2), P4(2), Q4(4), T4(4), Q4.x(2) | f(12)]
-                    { Fq::neg(0) }
-                    // [beta_12(2), beta_13(2), beta_22(2), P1(2), P2(2), P3(2), P4(2), Q4(4), T4(4), -Q4.x(2) | f(12)]
-                    { Fq2::roll(22) }
-                    // [beta_13(2), beta_22(2), P1(2), P2(2), P3(2), P4(2), Q4(4), T4(4), -Q4.x(2), beta_12(2) | f(12)]
-                    { Fq2::mul(2, 0) }
-                    // Q4.x' = -Q4.x * beta_12 (2)
-                    // [beta_13(2), beta_22(2), P1(2), P2(2), P3(2), P4(2), Q4(4), T4(4), Q4.x'(2) | f(12)]
-
-                    // Qy' = Qy.conjugate * beta^{3 * (p - 1) / 6}
-                    { Fq2::copy(6) }
-                    // [beta_13(2), beta_22(2), P1(2), P2(2), P3(2), P4(2), Q4(4), T4(4), Q4.x'(2), Q4.y(2) | f(12)]
-                    { Fq::neg(0) }
-                    // [beta_13(2), beta_22(2), P1(2), P2(2), P3(2), P4(2), Q4(4), T4(4), Q4.x'(2), -Q4.y(2) | f(12)]
-                    { Fq2::roll(22) }
-                    // [beta_22(2), P1(2), P2(2), P3(2), P4(2), Q4(4), T4(4), Q4.x'(2), -Q4.y(2), beta_13(2) | f(12)]
-                    { Fq2::mul(2, 0) }
-                    // Q4.y' = -Q4.y * beta_13 (2)
-                    // [beta_22(2), P1(2), P2(2), P3(2), P4(2), Q4(4), T4(4), Q4.x'(2), Q4.y'(2) | f(12)]
-                    // phi(Q4) = (Q4.x', Q4.y')
-                    // [beta_22(2), P1(2), P2(2), P3(2), P4(2), Q4(4), T4(4), phi(Q4)(4) | f(12)]
-
-                    // check chord line
+                    // check the line is tangent to T_k
                     { Fq2::copy(6) }
                     { Fq2::copy(6) }
-                    // [beta_22(2), P1(2), P2(2), P3(2), P4(2), Q4(4), T4(4), phi(Q4)(4), T4(4) | f(12)]
                     { Fq2::copy(6) }
                     { Fq2::copy(6) }
-                    // [beta_22(2), P1(2), P2(2), P3(2), P4(2), Q4(4), T4(4), phi(Q4)(4), T4(4), phi(Q4)(4) | f(12)]
-                    { utils::check_chord_line(line_coeffs[num_lines - 2][j][0].1, line_coeffs[num_lines - 2][j][0].2) }
-                    // [beta_22(2), P1(2), P2(2), P3(2), P4(2), Q4(4), T4(4), phi(Q4)(4) | f(12)]
+                    { utils::check_tangent_line_in_stack() }
 
-                    // update T4
+                    // update T_k, drop T_k.y, leave T_k.x
+                    { Fq2::roll(4) }
                     { Fq2::drop() }
-                    // [beta_22(2), P1(2), P2(2), P3(2), P4(2), Q4(4), T4(4), phi(Q4).x(2) | f(12)]
-                    { Fq2::toaltstack() }
-                    // [beta_22(2), P1(2), P2(2), P3(2), P4(2), Q4(4), T4(4) | phi(Q4).x(2), f(12)]
-                    { Fq2::drop() }
-                    // [beta_22(2), P1(2), P2(2), P3(2), P4(2), Q4(4), T4.x(2) | phi(Q4).x(2), f(12)]
-                    { Fq2::fromaltstack() }
-                    // [beta_22(2), P1(2), P2(2), P3(2), P4(2), Q4(4), T4.x(2), phi(Q4).x(2) | f(12)]
-                    { utils::affine_add_line(line_coeffs[num_lines - 2][j][0].1, line_coeffs[num_lines - 2][j][0].2) }
-                    // [beta_22(2), P1(2), P2(2), P3(2), P4(2), Q4(4), T4(4) | f(12)]
+                    { utils::affine_double_line_in_stack() }
                     { Fq12::fromaltstack() }
-                    // [beta_22(2), P1(2), P2(2), P3(2), P4(2), Q4(4), T4(4), f(12)]
+                    // [..., Q(4v), c(12), c_inv(12), wi(12), T(4v), f(12)]
+                }
+
+                // update f with add line evaluation
+                if ark_bn254::Config::ATE_LOOP_COUNT[i - 1] == 1 || ark_bn254::Config::ATE_LOOP_COUNT[i - 1] == -1 {
+                    for j in 0..num_fixed {
+                        { Fq2::copy(loop_p_index(j)) }
+                        { utils::ell_by_constant_affine(&line_coeffs[num_lines - (i + 2)][j as usize][1]) }
+                    }
+
+                    // non-fixed part, update f with the hinted add line of each T_k and Q_k
+                    for k in 0..v {
+                        { rotate_accumulators(v) }
+                        { pull_line_and_copy_p(loop_p_index(num_fixed + k)) }
+                        { utils::ell_affine_in_stack() }
+                        { Fq12::toaltstack() }
+                        // [..., Q(4v), c(12), c_inv(12), wi(12), T(4v), c3(2), c4(2) | f(12)]
+
+                        // check the line is the chord through T_k and Q_k (or -Q_k)
+                        { Fq2::copy(6) }
+                        { Fq2::copy(6) }
+                        { Fq2::copy(10 + num_hint_elements + 4 * v + 4 * (v - 1 - k)) }
+                        { Fq2::copy(10 + num_hint_elements + 4 * v + 4 * (v - 1 - k)) }
+                        if ark_bn254::Config::ATE_LOOP_COUNT[i - 1] == -1 {
+                            { Fq2::neg(0) }
+                        }
+                        { Fq2::copy(10) }
+                        { Fq2::copy(10) }
+                        { utils::check_chord_line_in_stack() }
+
+                        // update T_k, drop T_k.y, leave T_k.x
+                        { Fq2::roll(4) }
+                        { Fq2::drop() }
+                        // copy Q_k.x
+                        { Fq2::copy(4 + num_hint_elements + 4 * v + 4 * (v - 1 - k)) }
+                        { Fq2::roll(4) }
+                        { Fq2::roll(4) }
+                        // [..., Q(4v), c(12), c_inv(12), wi(12), T(4v - 2), Q_k.x(2), c3(2), c4(2) | f(12)]
+                        { utils::affine_add_line_in_stack() }
+                        { Fq12::fromaltstack() }
+                    }
                 }
             }
 
-            // update f with add line evaluation of two-times of frobenius map on Q4
-            for j in 0..num_line_groups {
-                // update f with adding line evaluation by rolling each Pi(2) element to the right(stack top)
-                { Fq2::roll((26 - j * 2) as u32) }
-                { utils::ell_by_constant_affine(&line_coeffs[num_lines - 1][j][0]) }
-                // [beta_22(2), Q4(4), T4(4), f(12)]
+            if with_c_wi {
+                // update f with frobenius of c, say f = f * c_inv^p * c^{p^2}
+                { Fq12::roll(c_inv_index) }
+                { Fq12::frobenius_map(1) }
+                { Fq12::mul(12, 0) }
+                { Fq12::roll(c_inv_index) }
+                { Fq12::frobenius_map(2) }
+                { Fq12::mul(12, 0) }
+
+                // update f with scalar wi, say f = f * wi
+                { Fq12::roll(12 + 4 * v) }
+                { Fq12::mul(12, 0) }
+            }
+            // [beta_12(2), beta_13(2), beta_22(2), P(2n), Q(4v), T(4v), f(12)]
 
-                // non-fixed part(Q4)
-                if j == num_constant {
-                    { Fq12::toaltstack() }
-                    // [beta_22(2), Q4(4), T4(4) | f(12)]
-                    { Fq2::roll(8) }
-                    // [Q4(4), T4(4), beta_22(2) | f(12)]
-                    { Fq2::roll(8) }
-                    // [Q4.y(2), T4(4), beta_22(2), Q4.x(2) | f(12)]
-                    { Fq2::mul(2, 0) }
-                    // [Q4.y(2), T4(4), beta_22(2) * Q4.x(2) | f(12)]
-                    // Q4.x' = Q4.x * beta^{2 * (p^2 - 1) / 6}
-                    // [Q4.y(2), T4(4), Q4.x'(2) | f(12)]
-                    { Fq2::roll(6) }
-                    // [T4(4), Q4.x'(2), Q4.y(2) | f(12)]
-                    // phi(Q4)^2 = (Q4.x', Qy)
-                    // [T4(4), phi(Q4)^2(4) | f(12)]
-
-                    // check whether the chord line through T4 and phi(Q4)^2
-                    { utils::check_chord_line(line_coeffs[num_lines - 1][j][0].1, line_coeffs[num_lines - 1][j][0].2) }
-                    // [ | f(12)]
-                    { Fq12::fromaltstack() }
-                    // [f(12)]
+            // update f with add line evaluation of one-time of frobenius map on Q
+            for j in 0..num_fixed {
+                { Fq2::copy(p_index(j)) }
+                { utils::ell_by_constant_affine(&line_coeffs[num_lines - 2][j as usize][0]) }
+            }
+
+            // non-fixed part, the betas are consumed by the last Q_k
+            for k in 0..v {
+                { rotate_accumulators(v) }
+                { pull_line_and_copy_p(p_index(num_fixed + k)) }
+                { utils::ell_affine_in_stack() }
+                { Fq12::toaltstack() }
+                { Fq2::toaltstack() }
+                { Fq2::toaltstack() }
+                // [beta_12(2), beta_13(2), beta_22(2), P(2n), Q(4v), T(4v) | f(12), c4(2), c3(2)]
+
+                // Qx' = Qx.conjugate * beta^{2 * (p - 1) / 6}
+                { Fq2::copy(2 + 4 * v + 4 * (v - 1 - k)) }
+                { Fq::neg(0) }
+                if k == v - 1 {
+                    { Fq2::roll(2 * n + 8 * v + 6) }
+                } else {
+                    { Fq2::copy(2 * n + 8 * v + 6) }
+                }
+                { Fq2::mul(2, 0) }
+                // Qy' = Qy.conjugate * beta^{3 * (p - 1) / 6}
+                { Fq2::copy(2 + 4 * v + 4 * (v - 1 - k)) }
+                { Fq::neg(0) }
+                if k == v - 1 {
+                    { Fq2::roll(2 * n + 8 * v + 6) }
+                } else {
+                    { Fq2::copy(2 * n + 8 * v + 6) }
                 }
+                { Fq2::mul(2, 0) }
+                // [..., P(2n), Q(4v), T(4v), phi(Q_k)(4) | f(12), c4(2), c3(2)]
+
+                // check chord line
+                { Fq2::copy(6) }
+                { Fq2::copy(6) }
+                { Fq2::copy(6) }
+                { Fq2::copy(6) }
+                { Fq2::fromaltstack() }
+                { Fq2::fromaltstack() }
+                { Fq2::copy(2) }
+                { Fq2::copy(2) }
+                { Fq2::toaltstack() }
+                { Fq2::toaltstack() }
+                { utils::check_chord_line_in_stack() }
+
+                // update T_k
+                { Fq2::drop() }
+                { Fq2::toaltstack() }
+                { Fq2::drop() }
+                { Fq2::fromaltstack() }
+                // [..., P(2n), Q(4v), T(4v - 2), T_k.x(2), phi(Q_k).x(2) | f(12), c4(2), c3(2)]
+                { Fq2::fromaltstack() }
+                { Fq2::fromaltstack() }
+                { utils::affine_add_line_in_stack() }
+                { Fq12::fromaltstack() }
+                // [..., P(2n), Q(4v), T(4v), f(12)]
             }
-        };
-        script
+
+            // update f with add line evaluation of two-times of frobenius map on Q
+            for j in 0..num_fixed {
+                // update f with adding line evaluation by rolling each P_j(2) element to the right(stack top)
+                { Fq2::roll(p_index(j)) }
+                { utils::ell_by_constant_affine(&line_coeffs[num_lines - 1][j as usize][0]) }
+            }
+            // [beta_22(2), P_m, ..., P_{n-1}(2v), Q(4v), T(4v), f(12)]
+
+            // non-fixed part, P_k, Q_k and T_k are consumed, beta_22 by the last one
+            for k in 0..v {
+                // r = v - k points left, P_k, Q_k and T_k are the deepest ones
+                { rotate_accumulators(v - k) }
+                { Fq2::fromaltstack() }
+                { Fq2::fromaltstack() }
+                { Fq12::roll(4) }
+                { Fq2::roll(16 + 8 * (v - k) + 2 * (v - k - 1)) }
+                { Fq2::copy(16) }
+                { Fq2::copy(16) }
+                { utils::ell_affine_in_stack() }
+                { Fq12::toaltstack() }
+                { Fq2::toaltstack() }
+                { Fq2::toaltstack() }
+                // [beta_22(2), P(2r - 2), Q(4r), T(4r) | f(12), c4(2), c3(2)]
+
+                // Q_k.x' = Q_k.x * beta^{2 * (p^2 - 1) / 6}
+                if k == v - 1 {
+                    { Fq2::roll(10 * (v - k) - 2) }
+                } else {
+                    { Fq2::copy(10 * (v - k) - 2) }
+                }
+                { Fq2::roll(8 * (v - k)) }
+                { Fq2::mul(2, 0) }
+                { Fq2::roll(8 * (v - k) - 2) }
+                // phi(Q_k)^2 = (Q_k.x', Q_k.y)
+                // [..., T_k(4), phi(Q_k)^2(4) | f(12), c4(2), c3(2)]
+
+                // check whether the chord line through T_k and phi(Q_k)^2
+                { Fq2::fromaltstack() }
+                { Fq2::fromaltstack() }
+                { utils::check_chord_line_in_stack() }
+                { Fq12::fromaltstack() }
+            }
+
+            // without non-fixed points, the betas are left
+            if v == 0 {
+                { Fq12::toaltstack() }
+                for _ in 0..3 {
+                    { Fq2::drop() }
+                }
+                { Fq12::fromaltstack() }
+            }
+            // [f(12)]
+        }
+    }
+
+    /// Line coefficients (c3, c4) of Q4 in the order `quad_miller_loop_with_c_wi_and_hinted_lines`
+    /// takes them from the altstack.
+    pub fn hinted_line_coeffs(q4: ark_bn254::G2Affine) -> Vec<(ark_bn254::Fq2, ark_bn254::Fq2)> {
+        Self::multi_hinted_line_coeffs(&[q4])
+    }
+
+    /// Line coefficients (c3, c4) of the non-fixed points in the order
+    /// `multi_miller_loop_with_c_wi_and_hinted_lines` takes them from the altstack: at each step
+    /// of the loop, the double lines of all the points, then their add lines.
+    pub fn multi_hinted_line_coeffs(
+        points: &[ark_bn254::G2Affine],
+    ) -> Vec<(ark_bn254::Fq2, ark_bn254::Fq2)> {
+        utils::collect_line_coeffs(
+            points
+                .iter()
+                .copied()
+                .map(G2Prepared::from_affine)
+                .collect(),
+        )
+        .into_iter()
+        .flat_map(|line_coeffs| {
+            let num_phases = line_coeffs.first().map_or(0, |lines| lines.len());
+            (0..num_phases)
+                .flat_map(|phase| line_coeffs.iter().map(move |lines| lines[phase]))
+                .collect::<Vec<_>>()
+        })
+        .map(|(_, c3, c4)| (c3, c4))
+        .collect()
+    }
+
+    /// Number of lines returned by `Pairing::hinted_line_coeffs`, the same for any Q4, and by
+    /// `Pairing::multi_hinted_line_coeffs` for each point.
+    pub fn num_hinted_lines() -> usize {
+        let count = ark_bn254::Config::ATE_LOOP_COUNT;
+        let num_additions = count[..count.len() - 1]
+            .iter()
+            .filter(|digit| **digit != 0)
+            .count();
+        count.len() - 1 + num_additions + 2
+    }
+
+    // Final exponentiation f^((p^12 - 1) / r) in script, as `final_exponentiation` of arkworks,
+    // an alternative to the hints c and wi.
+    //
+    // input on stack:
+    //     [f(12)] miller loop output
+    // output on stack:
+    //     [f^((p^12 - 1) / r)(12)]
+    pub fn final_exponentiation() -> Script {
+        script! {
+            // easy part, r = f^((p^6 - 1) * (p^2 + 1))
+            { Fq12::move_to_cyclotomic() }
+
+            // hard part, https://eprint.iacr.org/2015/192.pdf
+            // r^(2x * (6x^2 + 3x + 1) * (p^4 - p^2 + 1) / r)
+            { Fq12::copy(0) }
+            { Fq12::cyclotomic_pow_by_neg_x() }
+            // y1 = y0^2, y0 = r^{-x}
+            { Fq12::cyclotomic_square() }
+            // y3 = y2 * y1, y2 = y1^2
+            { Fq12::copy(0) }
+            { Fq12::cyclotomic_square() }
+            { Fq12::copy(12) }
+            { Fq12::mul(12, 0) }
+            // [r, y1, y3]
+
+            // y4 = y3^{-x}
+            { Fq12::copy(0) }
+            { Fq12::cyclotomic_pow_by_neg_x() }
+            // y6 = y5^{-x}, y5 = y4^2
+            { Fq12::copy(0) }
+            { Fq12::cyclotomic_square() }
+            { Fq12::cyclotomic_pow_by_neg_x() }
+            // [r, y1, y3, y4, y6]
+
+            // y3 = y3^{-1}
+            { Fq12::roll(24) }
+            { Fq12::cyclotomic_inverse() }
+            // y6 = y6^{-1}
+            { Fq12::roll(12) }
+            { Fq12::cyclotomic_inverse() }
+            // y7 = y6 * y4
+            { Fq12::copy(24) }
+            { Fq12::mul(12, 0) }
+            // y8 = y7 * y3
+            { Fq12::mul(12, 0) }
+            // [r, y1, y4, y8]
+
+            // y9 = y8 * y1
+            { Fq12::copy(0) }
+            { Fq12::roll(36) }
+            { Fq12::mul(12, 0) }
+            // y10 = y8 * y4
+            { Fq12::copy(12) }
+            { Fq12::roll(36) }
+            { Fq12::mul(12, 0) }
+            // y11 = y10 * r
+            { Fq12::copy(36) }
+            { Fq12::mul(12, 0) }
+            // [r, y8, y9, y11]
+
+            // y13 = y12 * y11, y12 = y9^p
+            { Fq12::copy(12) }
+            { Fq12::frobenius_map(1) }
+            { Fq12::mul(12, 0) }
+            // y14 = y8^{p^2} * y13
+            { Fq12::roll(24) }
+            { Fq12::frobenius_map(2) }
+            { Fq12::mul(12, 0) }
+            // [r, y9, y14]
+
+            // y15 = (r^{-1} * y9)^{p^3}
+            { Fq12::roll(24) }
+            { Fq12::cyclotomic_inverse() }
+            { Fq12::roll(24) }
+            { Fq12::mul(12, 0) }
+            { Fq12::frobenius_map(3) }
+            // y16 = y15 * y14
+            { Fq12::mul(12, 0) }
+        }
     }
 }
 
@@ -479,11 +752,12 @@ mod test {
     use crate::bn254::fq2::Fq2;
     use crate::bn254::pairing::Pairing;
     use crate::bn254::utils::{self, fq12_push, fq2_push};
+    use crate::groth16::offchain_checker::compute_w;
     use crate::{execute_script_without_stack_limit, treepp::*};
     use ark_bn254::g2::G2Affine;
     use ark_bn254::Bn254;
 
-    use ark_ec::pairing::Pairing as _;
+    use ark_ec::pairing::{MillerLoopOutput, Pairing as _};
     use ark_ec::AffineRepr;
     use ark_ff::{AdditiveGroup, Field};
     use ark_std::{test_rng, UniformRand};
@@ -702,6 +976,59 @@ mod test {
         assert!(exec_result.success);
     }
 
+    #[test]
+    fn test_check_c_wi() {
+        let mut prng = ChaCha20Rng::seed_from_u64(0);
+
+        let c = ark_bn254::Fq12::rand(&mut prng);
+        let c_inv = c.inverse().unwrap();
+        let w = compute_w();
+
+        let check_c_wi = |c: ark_bn254::Fq12, c_inv: ark_bn254::Fq12, wi: ark_bn254::Fq12| {
+            let script = script! {
+                { fq12_push(c) }
+                { fq12_push(c_inv) }
+                { fq12_push(wi) }
+                { Pairing::check_c_wi() }
+                for _ in 0..3 {
+                    { Fq12::drop() }
+                }
+                OP_TRUE
+            };
+            execute_script_without_stack_limit(script).success
+        };
+
+        for wi in [ark_bn254::Fq12::ONE, w, w * w] {
+            assert!(check_c_wi(c, c_inv, wi));
+        }
+        assert!(!check_c_wi(c, c_inv, w * w * w));
+        assert!(!check_c_wi(c, c, w));
+    }
+
+    #[test]
+    fn test_final_exponentiation() {
+        let mut prng = ChaCha20Rng::seed_from_u64(0);
+
+        let final_exponentiation = Pairing::final_exponentiation();
+        println!(
+            "Pairing.final_exponentiation: {} bytes",
+            final_exponentiation.len()
+        );
+
+        let f = ark_bn254::Fq12::rand(&mut prng);
+        let res = Bn254::final_exponentiation(MillerLoopOutput(f)).unwrap().0;
+
+        let script = script! {
+            { fq12_push(f) }
+            { final_exponentiation.clone() }
+            { fq12_push(res) }
+            { Fq12::equalverify() }
+            OP_TRUE
+        };
+        let exec_result = execute_script_without_stack_limit(script);
+        assert!(exec_result.success);
+    }
+
     #[test]
     fn test_mul_by_char() {
         let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
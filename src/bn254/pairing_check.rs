@@ -0,0 +1,375 @@
+// Check of e(P_0, Q_0) * ... * e(P_{n-1}, Q_{n-1}) == 1 in script, the core of pairing-based
+// verifiers: Groth16, KZG openings, PLONK-style verifiers or batches of them.
+//
+// The first m G2 points are fixed, their line coefficients are embedded in the script. The last
+// n - m ones are provided by the prover along with their line coefficients, which the script
+// checks against them. The final exponentiation is replaced by the hints c and wi of algorithm 9
+// of https://eprint.iacr.org/2024/640.pdf, with f * wi == c^lambda, unless the check computes the
+// final exponentiation itself with `FinalExponentiation::Full`.
+use crate::bn254::ell_coeffs::G2Prepared;
+use crate::bn254::fp254impl::Fp254Impl;
+use crate::bn254::fq::Fq;
+use crate::bn254::fq12::Fq12;
+use crate::bn254::fq2::Fq2;
+use crate::bn254::pairing::Pairing;
+use crate::bn254::utils;
+use crate::groth16::offchain_checker::compute_c_wi;
+use crate::treepp::{script, Script};
+use ark_bn254::Bn254;
+use ark_ec::pairing::Pairing as ArkPairing;
+use ark_ff::Field;
+
+/// How `PairingCheck::script` checks the miller loop output f.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FinalExponentiation {
+    /// f * wi == c^lambda with the hints c and wi, the smaller script.
+    Hinted,
+    /// f^((p^12 - 1) / r) == 1 computed in script, without hints to trust.
+    Full,
+}
+
+impl FinalExponentiation {
+    /// Number of Fq elements of the hints c, c_inv and wi in the witness.
+    pub fn num_hint_elements(&self) -> u32 {
+        match self {
+            FinalExponentiation::Hinted => 36,
+            FinalExponentiation::Full => 0,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct PairingCheck {
+    fixed: Vec<ark_bn254::G2Affine>,
+    num_variable: usize,
+    final_exponentiation: FinalExponentiation,
+}
+
+impl PairingCheck {
+    /// Check of `fixed.len() + num_variable` pairings, the G2 points of the first ones being
+    /// `fixed`.
+    pub fn new(fixed: Vec<ark_bn254::G2Affine>, num_variable: usize) -> Self {
+        assert!(!fixed.is_empty() || num_variable > 0, "no pairing to check");
+        Self {
+            fixed,
+            num_variable,
+            final_exponentiation: FinalExponentiation::Hinted,
+        }
+    }
+
+    pub fn with_final_exponentiation(mut self, final_exponentiation: FinalExponentiation) -> Self {
+        self.final_exponentiation = final_exponentiation;
+        self
+    }
+
+    pub fn num_pairings(&self) -> usize { self.fixed.len() + self.num_variable }
+
+    /// Number of Fq elements above the G1 points in the input of `PairingCheck::script`, for
+    /// callers computing the G1 points in script and moving the hints out of the way.
+    pub fn num_hint_elements(&self) -> u32 {
+        self.num_variable as u32 * (4 + 4 * Pairing::num_hinted_lines() as u32)
+            + self.final_exponentiation.num_hint_elements()
+    }
+
+    /// Script failing unless the product of the pairings is one.
+    ///
+    /// Input stack: [P_0, ..., P_{n-1}, Q_m, ..., Q_{n-1}, c, c_inv, wi, L(Q_m, ..., Q_{n-1})]
+    /// The P_i are affine G1 points other than the identity, Q_m, ..., Q_{n-1} the non-fixed
+    /// affine G2 points and L(...) their line coefficients, see `PairingCheck::hints_witness`.
+    /// c, c_inv and wi are left out with `FinalExponentiation::Full`.
+    /// Output stack: []
+    pub fn script(&self) -> Script {
+        let n = self.num_pairings() as u32;
+        let v = self.num_variable as u32;
+        let num_line_elements = 4 * Pairing::num_hinted_lines() as u32 * v;
+        let hinted = self.final_exponentiation == FinalExponentiation::Hinted;
+        let num_exp_hints = self.final_exponentiation.num_hint_elements();
+        // Q_m, ..., Q_{n-1} and the hints of the final exponentiation
+        let num_tail_elements = 4 * v + num_exp_hints;
+        let fixed = self
+            .fixed
+            .iter()
+            .copied()
+            .map(G2Prepared::from_affine)
+            .collect::<Vec<_>>();
+
+        script! {
+            if hinted {
+                // expected final_f, say c^{p^3}
+                { Fq12::copy(num_line_elements + 24) }
+                { Fq12::frobenius_map(3) }
+                { Fq12::toaltstack() }
+            }
+
+            // the line coefficients are consumed by the miller loop from altstack
+            for _ in 0..num_line_elements {
+                { Fq::toaltstack() }
+            }
+            for _ in 0..num_tail_elements {
+                { Fq::toaltstack() }
+            }
+
+            // variants of the G1 points, say -p.x / p.y, 1 / p.y
+            for _ in 0..n {
+                { utils::from_eval_point_in_stack() }
+                { Fq2::toaltstack() }
+            }
+            { betas() }
+            for _ in 0..n {
+                { Fq2::fromaltstack() }
+            }
+            for _ in 0..num_tail_elements {
+                { Fq::fromaltstack() }
+            }
+            // stack: [beta_12, beta_13, beta_22, P', Q, c, c_inv, wi | c^{p^3}, L(Q)]
+
+            if hinted {
+                // c_inv is the inverse of c and wi is 1, w or w^2
+                { Pairing::check_c_wi() }
+            }
+
+            // accumulators of the non-fixed points, initially T = Q
+            for _ in 0..2 * v {
+                { Fq2::copy(num_exp_hints + 4 * v - 2) }
+            }
+            // stack: [beta_12, beta_13, beta_22, P', Q, c, c_inv, wi, T | c^{p^3}, L(Q)]
+
+            if hinted {
+                { Pairing::multi_miller_loop_with_c_wi_and_hinted_lines(fixed, self.num_variable) }
+
+                // check final_f == c^{p^3}
+                { Fq12::fromaltstack() }
+                { Fq12::equalverify() }
+            } else {
+                { Pairing::multi_miller_loop_with_hinted_lines(fixed, self.num_variable) }
+
+                // check f^((p^12 - 1) / r) == 1
+                { Pairing::final_exponentiation() }
+                { Fq12::push_one() }
+                { Fq12::equalverify() }
+            }
+        }
+    }
+
+    /// Hints c, c_inv and wi of `FinalExponentiation::Hinted`. Fails unless the product of the
+    /// pairings is one.
+    pub fn hints(
+        &self,
+        p: &[ark_bn254::G1Affine],
+        variable: &[ark_bn254::G2Affine],
+    ) -> Result<(ark_bn254::Fq12, ark_bn254::Fq12, ark_bn254::Fq12), String> {
+        self.check_num_points(p, variable)?;
+
+        let q = [self.fixed.clone(), variable.to_vec()].concat();
+        let f = Bn254::multi_miller_loop_affine(p.to_vec(), q).0;
+        let (c, wi) = compute_c_wi(f)?;
+        Ok((c, c.inverse().unwrap(), wi))
+    }
+
+    fn check_num_points(
+        &self,
+        p: &[ark_bn254::G1Affine],
+        variable: &[ark_bn254::G2Affine],
+    ) -> Result<(), String> {
+        if p.len() != self.num_pairings() {
+            return Err(format!(
+                "Expected {} G1 points, got {}",
+                self.num_pairings(),
+                p.len()
+            ));
+        }
+        if variable.len() != self.num_variable {
+            return Err(format!(
+                "Expected {} G2 points, got {}",
+                self.num_variable,
+                variable.len()
+            ));
+        }
+        Ok(())
+    }
+
+    /// Stack items above the G1 points in the input of `PairingCheck::script`: the non-fixed G2
+    /// points, c, c_inv, wi if hinted and the line coefficients of the non-fixed G2 points.
+    pub fn hints_witness(
+        &self,
+        p: &[ark_bn254::G1Affine],
+        variable: &[ark_bn254::G2Affine],
+    ) -> Result<Vec<Vec<u8>>, String> {
+        let exp_hints = match self.final_exponentiation {
+            FinalExponentiation::Hinted => {
+                let (c, c_inv, wi) = self.hints(p, variable)?;
+                [
+                    utils::fq12_witness(c),
+                    utils::fq12_witness(c_inv),
+                    utils::fq12_witness(wi),
+                ]
+                .concat()
+            }
+            FinalExponentiation::Full => {
+                self.check_num_points(p, variable)?;
+                vec![]
+            }
+        };
+
+        Ok([
+            variable
+                .iter()
+                .flat_map(|q| utils::g2_affine_witness(*q))
+                .collect(),
+            exp_hints,
+            Pairing::multi_hinted_line_coeffs(variable)
+                .into_iter()
+                .flat_map(|(c3, c4)| [utils::fq2_witness(c3), utils::fq2_witness(c4)].concat())
+                .collect(),
+        ]
+        .concat())
+    }
+
+    /// Stack items satisfying `PairingCheck::script`, first item at the bottom.
+    pub fn witness(
+        &self,
+        p: &[ark_bn254::G1Affine],
+        variable: &[ark_bn254::G2Affine],
+    ) -> Result<Vec<Vec<u8>>, String> {
+        Ok([
+            p.iter()
+                .flat_map(|p| utils::g1_affine_witness(*p))
+                .collect(),
+            self.hints_witness(p, variable)?,
+        ]
+        .concat())
+    }
+}
+
+// Push the Frobenius constants of the miller loop to stack
+// Return Stack: [beta_12, beta_13, beta_22]
+fn betas() -> Script {
+    script! {
+        // beta_12
+        { Fq::push_dec("21575463638280843010398324269430826099269044274347216827212613867836435027261") }
+        { Fq::push_dec("10307601595873709700152284273816112264069230130616436755625194854815875713954") }
+
+        // beta_13
+        { Fq::push_dec("2821565182194536844548159561693502659359617185244120367078079554186484126554") }
+        { Fq::push_dec("3505843767911556378687030309984248845540243509899259641013678093033130930403") }
+
+        // beta_22
+        { Fq::push_dec("21888242871839275220042445260109153167277707414472061641714758635765020556616") }
+        { Fq::push_zero() }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::execute_script_with_witness_without_stack_limit;
+    use ark_ec::{AffineRepr, CurveGroup};
+    use ark_std::UniformRand;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+
+    // G1 and G2 points whose pairings multiply to one: P_i = s_i * G1 and Q_i = t_i * G2 with
+    // sum(s_i * t_i) == 0
+    fn random_relation(
+        prng: &mut ChaCha20Rng,
+        n: usize,
+    ) -> (Vec<ark_bn254::G1Affine>, Vec<ark_bn254::G2Affine>) {
+        let t = (0..n)
+            .map(|_| ark_bn254::Fr::rand(prng))
+            .collect::<Vec<_>>();
+        let mut s = (0..n - 1)
+            .map(|_| ark_bn254::Fr::rand(prng))
+            .collect::<Vec<_>>();
+        let sum = s
+            .iter()
+            .zip(t.iter())
+            .map(|(s, t)| *s * t)
+            .sum::<ark_bn254::Fr>();
+        s.push(-sum / t[n - 1]);
+
+        (
+            s.iter()
+                .map(|s| (ark_bn254::G1Affine::generator() * s).into_affine())
+                .collect(),
+            t.iter()
+                .map(|t| (ark_bn254::G2Affine::generator() * t).into_affine())
+                .collect(),
+        )
+    }
+
+    fn check(num_fixed: usize, num_variable: usize, final_exponentiation: FinalExponentiation) {
+        let mut prng = ChaCha20Rng::seed_from_u64(0);
+        let (p, q) = random_relation(&mut prng, num_fixed + num_variable);
+        let pairing_check = PairingCheck::new(q[..num_fixed].to_vec(), num_variable)
+            .with_final_exponentiation(final_exponentiation);
+
+        let script = pairing_check.script();
+        println!(
+            "PairingCheck({} fixed, {} variable, {:?}): {} bytes",
+            num_fixed,
+            num_variable,
+            final_exponentiation,
+            script.len()
+        );
+
+        let witness = pairing_check.witness(&p, &q[num_fixed..]).unwrap();
+        assert_eq!(
+            witness.len(),
+            pairing_check.num_pairings() * 18 + pairing_check.num_hint_elements() as usize * 9
+        );
+        let exec_result = execute_script_with_witness_without_stack_limit(
+            script! {
+                { script.clone() }
+                OP_TRUE
+            },
+            witness.clone(),
+        );
+        assert!(exec_result.success);
+
+        // the same witness doesn't satisfy the script for another G1 point
+        let mut witness = witness;
+        let other = utils::g1_affine_witness(ark_bn254::G1Affine::rand(&mut prng));
+        witness.splice(0..18, other);
+        let exec_result = execute_script_with_witness_without_stack_limit(
+            script! {
+                { script }
+                OP_TRUE
+            },
+            witness,
+        );
+        assert!(!exec_result.success);
+    }
+
+    #[test]
+    fn test_pairing_check_with_variable_points() { check(2, 2, FinalExponentiation::Hinted); }
+
+    #[test]
+    fn test_pairing_check_with_one_variable_point() { check(2, 1, FinalExponentiation::Hinted); }
+
+    #[test]
+    fn test_pairing_check_with_fixed_points() { check(2, 0, FinalExponentiation::Hinted); }
+
+    #[test]
+    fn test_pairing_check_without_fixed_points() { check(0, 2, FinalExponentiation::Hinted); }
+
+    #[test]
+    fn test_pairing_check_witness_errors() {
+        let mut prng = ChaCha20Rng::seed_from_u64(0);
+        let (p, q) = random_relation(&mut prng, 3);
+        let pairing_check = PairingCheck::new(q[..2].to_vec(), 1);
+
+        assert!(pairing_check.witness(&p[..2], &q[2..]).is_err());
+        assert!(pairing_check.witness(&p, &q[1..]).is_err());
+        // the product of the pairings isn't one
+        let other = ark_bn254::G1Affine::rand(&mut prng);
+        assert!(pairing_check
+            .witness(&[p[0], p[1], other], &q[2..])
+            .is_err());
+    }
+
+    #[test]
+    fn test_pairing_check_with_full_final_exponentiation() {
+        check(2, 1, FinalExponentiation::Full);
+        check(2, 0, FinalExponentiation::Full);
+    }
+}
@@ -4,7 +4,7 @@ use crate::bn254::ell_coeffs::G2Prepared;
 use crate::bn254::{fq12::Fq12, fq2::Fq2};
 use ark_ec::{bn::BnConfig, AffineRepr};
 use ark_ff::BigInt;
-use ark_ff::Field;
+use ark_ff::{BigInteger, Field, PrimeField};
 use num_bigint::BigUint;
 
 use crate::{
@@ -262,6 +262,53 @@ pub fn fq12_push(element: ark_bn254::Fq12) -> Script {
     }
 }
 
+// Stack items of the fields and points as pushed by the functions above, for witnesses.
+pub fn fq_witness(element: ark_bn254::Fq) -> Vec<Vec<u8>> {
+    Fq::witness_u32_le(&BigUint::from(element).to_u32_digits())
+}
+
+pub fn fq2_witness(element: ark_bn254::Fq2) -> Vec<Vec<u8>> {
+    [fq_witness(element.c0), fq_witness(element.c1)].concat()
+}
+
+pub fn fq6_witness(element: ark_bn254::Fq6) -> Vec<Vec<u8>> {
+    element
+        .to_base_prime_field_elements()
+        .flat_map(fq_witness)
+        .collect()
+}
+
+pub fn fq12_witness(element: ark_bn254::Fq12) -> Vec<Vec<u8>> {
+    element
+        .to_base_prime_field_elements()
+        .flat_map(fq_witness)
+        .collect()
+}
+
+/// Stack items of the affine point, [x, y].
+pub fn g1_affine_witness(point: ark_bn254::G1Affine) -> Vec<Vec<u8>> {
+    [fq_witness(point.x), fq_witness(point.y)].concat()
+}
+
+/// Stack items of the affine point, [x.c0, x.c1, y.c0, y.c1].
+pub fn g2_affine_witness(point: ark_bn254::G2Affine) -> Vec<Vec<u8>> {
+    [fq2_witness(point.x), fq2_witness(point.y)].concat()
+}
+
+/// Stack items of the affine point packed as [x, flag], see `G1Affine::decompress_packed`.
+pub fn g1_affine_packed_witness(point: ark_bn254::G1Affine) -> Vec<Vec<u8>> {
+    [fq_witness(point.x), flag_witness(point.y > -point.y)].concat()
+}
+
+/// Stack items of the affine point packed as [x.c0, x.c1, flag], see `G2Affine::decompress`.
+pub fn g2_affine_packed_witness(point: ark_bn254::G2Affine) -> Vec<Vec<u8>> {
+    [fq2_witness(point.x), flag_witness(point.y > -point.y)].concat()
+}
+
+fn flag_witness(flag: bool) -> Vec<Vec<u8>> {
+    vec![if flag { vec![1] } else { vec![] }]
+}
+
 pub fn fq_to_bits(fq: BigInt<4>, limb_size: usize) -> Vec<u32> {
     let mut bits: Vec<bool> = ark_ff::BitIteratorBE::new(fq.as_ref()).skip(2).collect();
     bits.reverse();
@@ -463,6 +510,209 @@ pub fn check_chord_line(c3: ark_bn254::Fq2, c4: ark_bn254::Fq2) -> Script {
     }
 }
 
+// stack input:
+//  f            12 elements
+//  x': -p.x / p.y   1 element
+//  y': 1 / p.y      1 element
+//  c3           2 elements
+//  c4           2 elements
+//
+// output:
+//  new f        12 elements
+pub fn ell_affine_in_stack() -> Script {
+    script! {
+        // [f, x', y', c3, c4]
+        // update c3, c3' = x' * c3
+        { Fq::copy(5) }
+        { Fq::roll(4) }
+        { Fq::mul() }
+        // [f, x', y', c3.1, c4, x' * c3.0]
+        { Fq::roll(5) }
+        { Fq::roll(4) }
+        { Fq::mul() }
+        // [f, y', c4, x' * c3]
+        { Fq2::toaltstack() }
+
+        // update c4, c4' = y' * c4
+        { Fq::copy(2) }
+        { Fq::roll(2) }
+        { Fq::mul() }
+        // [f, y', c4.1, y' * c4.0]
+        { Fq::roll(2) }
+        { Fq::roll(2) }
+        { Fq::mul() }
+        // [f, y' * c4]
+        { Fq2::fromaltstack() }
+        { Fq2::roll(2) }
+        // [f, c3', c4']
+
+        // compute the new f with c3' and c4'
+        { Fq12::mul_by_34() }
+        // [f]
+    }
+}
+
+/// same as `affine_add_line`, except that c3 and c4 are taken from stack
+///
+/// input on stack:
+///     T.x (2 elements)
+///     Q.x (2 elements)
+///     c3 (2 elements)
+///     c4 (2 elements)
+///
+/// output on stack:
+///     T'.x (2 elements)
+///     T'.y (2 elements)
+pub fn affine_add_line_in_stack() -> Script {
+    script! {
+        // [T.x, Q.x, alpha, -bias]
+        { Fq2::roll(6) }
+        { Fq2::roll(6) }
+        // [alpha, -bias, T.x, Q.x]
+        { Fq2::add(2, 0) }
+        { Fq2::neg(0) }
+        // [alpha, -bias, -T.x - Q.x]
+        { affine_line_in_stack() }
+        // [x', y']
+    }
+}
+
+/// same as `affine_double_line`, except that c3 and c4 are taken from stack
+///
+/// input on stack:
+///     T.x (2 elements)
+///     c3 (2 elements)
+///     c4 (2 elements)
+///
+/// output on stack:
+///     T'.x (2 elements)
+///     T'.y (2 elements)
+pub fn affine_double_line_in_stack() -> Script {
+    script! {
+        // [T.x, alpha, -bias]
+        { Fq2::roll(4) }
+        { Fq2::double(0) }
+        { Fq2::neg(0) }
+        // [alpha, -bias, - 2 * T.x]
+        { affine_line_in_stack() }
+        // [x', y']
+    }
+}
+
+// input on stack: [alpha, -bias, s], output on stack: [x', y'] where
+//     x' = alpha^2 + s
+//     y' = -bias - alpha * x'
+fn affine_line_in_stack() -> Script {
+    script! {
+        { Fq2::copy(4) }
+        { Fq2::square() }
+        { Fq2::add(2, 0) }
+        // [alpha, -bias, x']
+        { Fq2::copy(0) }
+        { Fq2::roll(6) }
+        { Fq2::mul(2, 0) }
+        { Fq2::neg(0) }
+        // [-bias, x', -alpha * x']
+        { Fq2::roll(4) }
+        { Fq2::add(2, 0) }
+        // [x', y']
+    }
+}
+
+/// same as `check_line_through_point`, except that c3 and c4 are taken from stack
+///
+/// input on stack:
+///     x (2 elements)
+///     y (2 elements)
+///     c3 (2 elements)
+///     c4 (2 elements)
+///
+/// output:
+///     true or false (consumed on stack)
+pub fn check_line_through_point_in_stack() -> Script {
+    script! {
+        // [x, y, alpha, -bias]
+        { Fq2::roll(6) }
+        { Fq2::roll(4) }
+        // [y, -bias, x, alpha]
+        { Fq2::mul(2, 0) }
+        { Fq2::neg(0) }
+        // [y, -bias, -alpha * x]
+        { Fq2::add(2, 0) }
+        { Fq2::add(2, 0) }
+        // [y - alpha * x - bias]
+
+        { Fq2::push_zero() }
+        { Fq2::equalverify() }
+    }
+}
+
+/// same as `check_tangent_line`, except that c3 and c4 are taken from stack
+///
+/// input on stack:
+///     T.x (2 elements)
+///     T.y (2 elements)
+///     c3 (2 elements)
+///     c4 (2 elements)
+///
+/// output:
+///     true or false (consumed on stack)
+pub fn check_tangent_line_in_stack() -> Script {
+    script! {
+        // alpha * (2 * T.y) = 3 * T.x^2
+        { Fq2::copy(4) }
+        { Fq2::double(0) }
+        { Fq2::copy(4) }
+        { Fq2::mul(2, 0) }
+        // [T.x, T.y, alpha, -bias, alpha * (2 * T.y)]
+        { Fq2::copy(8) }
+        { Fq2::square() }
+        { Fq2::copy(0) }
+        { Fq2::double(0) }
+        { Fq2::add(2, 0) }
+        // [T.x, T.y, alpha, -bias, alpha * (2 * T.y), 3 * T.x^2]
+        { Fq2::neg(0) }
+        { Fq2::add(2, 0) }
+        { Fq2::push_zero() }
+        { Fq2::equalverify() }
+        // [T.x, T.y, alpha, -bias]
+
+        // check: T.y - alpha * T.x - bias = 0
+        { check_line_through_point_in_stack() }
+        // []
+    }
+}
+
+/// same as `check_chord_line`, except that c3 and c4 are taken from stack
+///
+/// input on stack:
+///     T.x (2 elements)
+///     T.y (2 elements)
+///     Q.x (2 elements)
+///     Q.y (2 elements)
+///     c3 (2 elements)
+///     c4 (2 elements)
+///
+/// output:
+///     true or false (consumed on stack)
+pub fn check_chord_line_in_stack() -> Script {
+    script! {
+        // [T.x, T.y, Q.x, Q.y, alpha, -bias]
+        { Fq2::copy(2) }
+        { Fq2::copy(2) }
+        { Fq2::toaltstack() }
+        { Fq2::toaltstack() }
+        // check: Q.y - alpha * Q.x - bias = 0
+        { check_line_through_point_in_stack() }
+        // [T.x, T.y | alpha, -bias]
+        { Fq2::fromaltstack() }
+        { Fq2::fromaltstack() }
+        // check: T.y - alpha * T.x - bias = 0
+        { check_line_through_point_in_stack() }
+        // []
+    }
+}
+
 // stack data: beta^{2 * (p - 1) / 6}, beta^{3 * (p - 1) / 6}, beta^{2 * (p^2 - 1) / 6}, 1/2, B,
 // P1, P2, P3, P4, Q4, c, c', wi, f, Px, Py, Tx, Ty, Tz, Qx, Qy
 // [..., Fq12, Fq12, Fq12, Fq12, Fq, Fq, (Fq, Fq), (Fq, Fq), (Fq, Fq), (Fq, Fq), (Fq, Fq)]
@@ -812,10 +1062,34 @@ pub fn double_line() -> Script {
     }
 }
 
+// Native counterparts of `Fr::convert_to_be_bytes`, `Fq::convert_to_be_bytes`,
+// `G1Affine::convert_to_compressed` and `Fr::from_hash` after blake3, for computing the
+// transcripts of the verifiers off-chain.
+pub fn fr_be_bytes(element: ark_bn254::Fr) -> Vec<u8> { element.into_bigint().to_bytes_be() }
+
+pub fn fq_be_bytes(element: ark_bn254::Fq) -> Vec<u8> { element.into_bigint().to_bytes_be() }
+
+/// Big-endian bytes of x, with the top bit set when y is the larger of y and -y.
+pub fn g1_compressed_bytes(point: ark_bn254::G1Affine) -> Vec<u8> {
+    let mut bytes = point.x.into_bigint().to_bytes_be();
+    if point.y > -point.y {
+        bytes[0] |= 0x80;
+    }
+    bytes
+}
+
+pub fn hash_to_fr(message: &[u8]) -> ark_bn254::Fr {
+    ark_bn254::Fr::from_be_bytes_mod_order(blake3::hash(message).as_bytes())
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::bn254::curves::G1Affine;
     use crate::bn254::fq2::Fq2;
+    use crate::bn254::fr::Fr;
+    use crate::hash::blake3::blake3_var_length;
+    use ark_ec::CurveGroup;
     use ark_ff::AdditiveGroup;
     use ark_std::UniformRand;
     use num_traits::One;
@@ -1085,4 +1359,41 @@ mod test {
         let exec_result = execute_script(script);
         assert!(exec_result.success);
     }
+
+    #[test]
+    fn test_native_transcript() {
+        let mut prng = ChaCha20Rng::seed_from_u64(0);
+
+        for _ in 0..4 {
+            let scalar = ark_bn254::Fr::rand(&mut prng);
+            let element = ark_bn254::Fq::rand(&mut prng);
+            let point = ark_bn254::G1Projective::rand(&mut prng).into_affine();
+            let hash = hash_to_fr(
+                &[
+                    g1_compressed_bytes(point),
+                    fq_be_bytes(element),
+                    fr_be_bytes(scalar),
+                ]
+                .concat(),
+            );
+
+            // the topmost bytes come first in the message
+            let script = script! {
+                { Fr::push_u32_le(&BigUint::from(scalar).to_u32_digits()) }
+                { Fr::convert_to_be_bytes() }
+                { Fq::push_u32_le(&BigUint::from(element).to_u32_digits()) }
+                { Fq::convert_to_be_bytes() }
+                { Fq::push_u32_le(&BigUint::from(point.x).to_u32_digits()) }
+                { Fq::push_u32_le(&BigUint::from(point.y).to_u32_digits()) }
+                { G1Affine::convert_to_compressed() }
+                { blake3_var_length(96) }
+                { Fr::from_hash() }
+                { Fr::push_u32_le(&BigUint::from(hash).to_u32_digits()) }
+                { Fr::equalverify(1, 0) }
+                OP_TRUE
+            };
+            let exec_result = execute_script(script);
+            assert!(exec_result.success);
+        }
+    }
 }
@@ -9,4 +9,8 @@ pub trait ChainAdaptor {
     async fn get_peg_out_init_event(&self) -> Result<Vec<PegOutEvent>, String>;
     async fn get_peg_out_burnt_event(&self) -> Result<Vec<PegOutBurntEvent>, String>;
     async fn get_peg_in_minted_event(&self) -> Result<Vec<PegInEvent>, String>;
+
+    /// Height of the chain tip, from which the lag of the adaptor behind the chain is measured.
+    /// Adaptors that can't report it return None and are polled without lag metrics.
+    async fn get_block_height(&self) -> Result<Option<u64>, String> { Ok(None) }
 }
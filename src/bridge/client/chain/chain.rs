@@ -1,6 +1,8 @@
 use bitcoin::{Amount, OutPoint, PubkeyHash, PublicKey};
+use std::{future::Future, time::Instant};
 
 use super::{
+    super::metrics::{metrics, CHAIN_ADAPTOR_REQUEST_DURATION},
     base::ChainAdaptor,
     ethereum::{EthereumAdaptor, EthereumInitConfig},
 };
@@ -50,17 +52,14 @@ impl Chain {
 
     pub async fn get_peg_out_init(&self) -> Result<Vec<PegOutEvent>, String> {
         match self.get_driver() {
-            Ok(driver) => match driver.get_peg_out_init_event().await {
-                Ok(events) => Ok(events),
-                Err(err) => Err(err.to_string()),
-            },
+            Ok(driver) => poll("peg_out_init", driver, driver.get_peg_out_init_event()).await,
             Err(err) => Err(err.to_string()),
         }
     }
 
     pub async fn get_peg_in_minted(&self) -> Result<Vec<PegInEvent>, String> {
         match self.get_driver() {
-            Ok(driver) => driver.get_peg_in_minted_event().await,
+            Ok(driver) => poll("peg_in_minted", driver, driver.get_peg_in_minted_event()).await,
             Err(err) => Err(err.to_string()),
         }
     }
@@ -73,3 +72,34 @@ impl Chain {
         }
     }
 }
+
+// Records the duration of the request in the metrics. The chain tip is fetched before the request,
+// so that once the request succeeds the events reach at least that block, from which the lag of
+// the adaptor behind the chain is measured.
+async fn poll<T, R>(event: &str, driver: &dyn ChainAdaptor, request: R) -> Result<T, String>
+where
+    R: Future<Output = Result<T, String>>,
+{
+    let started = Instant::now();
+    let result = match driver.get_block_height().await {
+        Ok(tip_height) => {
+            if let Some(tip_height) = tip_height {
+                metrics().record_chain_tip_height(tip_height);
+            }
+            request.await.map(|events| (events, tip_height))
+        }
+        Err(err) => Err(err),
+    };
+    metrics().observe_duration(
+        CHAIN_ADAPTOR_REQUEST_DURATION,
+        &[("event", event)],
+        started.elapsed(),
+    );
+
+    let (events, synced_height) = result?;
+    if let Some(synced_height) = synced_height {
+        metrics().record_chain_adaptor_poll(event, synced_height);
+    }
+
+    Ok(events)
+}
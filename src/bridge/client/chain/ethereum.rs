@@ -182,6 +182,14 @@ impl ChainAdaptor for EthereumAdaptor {
 
         Ok(peg_in_minted_events)
     }
+
+    async fn get_block_height(&self) -> Result<Option<u64>, String> {
+        self.provider
+            .get_block_number()
+            .await
+            .map(Some)
+            .map_err(|err| err.to_string())
+    }
 }
 
 impl EthereumAdaptor {
@@ -0,0 +1,1029 @@
+use bitcoin::{
+    address::NetworkUnchecked,
+    bip32::{DerivationPath, Fingerprint, KeySource},
+    psbt::Psbt,
+    Address, Amount, OutPoint, PublicKey, ScriptBuf,
+};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, fs, net::SocketAddr, path::PathBuf, str::FromStr, time::Duration};
+use tokio::time::sleep;
+
+use super::{
+    super::{
+        super::{
+            graphs::base::BaseGraph,
+            scripts::generate_pay_to_pubkey_script,
+            signing_session::batch::BatchProgress,
+            transactions::base::{Input, InputWithScript},
+        },
+        chain::chain::Chain,
+        client::BitVMClient,
+        daemon::server::{Daemon, DEFAULT_LISTEN_ADDRESS},
+        operator_registry::SelectionPolicy,
+        refund_watch::{RefundWatcher, DEFAULT_REFUND_WARNING_BLOCKS},
+        wallet::descriptor::WalletDescriptor,
+    },
+    config::{BridgeConfig, ClientRole},
+};
+
+pub const DEFAULT_CONFIG_PATH: &str = "bitvm-bridge.json";
+pub const DEFAULT_FEE_RATE: u64 = 2;
+
+#[derive(Parser, Debug)]
+#[command(name = "bitvm-bridge", version, about = "BitVM bridge client")]
+pub struct Cli {
+    /// Path to the JSON config file with the network, keys and endpoints to use
+    #[arg(short, long, default_value = DEFAULT_CONFIG_PATH)]
+    pub config: PathBuf,
+
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Print the status of every graph for the roles present in the config
+    Status,
+    /// Fetch the latest bridge data from the data store and list the graphs it contains
+    Sync,
+    /// Peg-in graph creation, deposit and refund
+    #[command(subcommand)]
+    Depositor(DepositorCommand),
+    /// Peg-out graph creation and the operator's transactions
+    #[command(subcommand)]
+    Operator(OperatorCommand),
+    /// Nonces, pre-signatures and the verifier's transactions
+    #[command(subcommand)]
+    Verifier(VerifierCommand),
+    /// Peg-out requests to operators
+    #[command(subcommand)]
+    Withdrawer(WithdrawerCommand),
+    /// Export transactions for signing outside of the client and import their signatures
+    #[command(subcommand)]
+    Psbt(PsbtCommand),
+    /// Keep syncing in the background and serve the client's operations over local JSON-RPC
+    Daemon {
+        #[arg(long, default_value = DEFAULT_LISTEN_ADDRESS)]
+        listen: SocketAddr,
+        /// Seconds between two syncs with the data store
+        #[arg(long, default_value_t = 60)]
+        sync_interval: u64,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum PsbtCommand {
+    /// Print a graph transaction as a base64 PSBT
+    Export {
+        graph_id: String,
+        /// Transaction name as used by the broadcast commands, e.g. peg_in_refund or kick_off_1
+        transaction: String,
+        /// Write the PSBT to this file instead of printing it
+        #[arg(long)]
+        output: Option<PathBuf>,
+        /// Origin of a key of the signer as [<fingerprint>/<path>]<key>, e.g.
+        /// [d34db33f/84'/1'/0'/0/7]02..., keys without one are exported as their own master key
+        #[arg(long = "key-origin", value_parser = parse_key_origin)]
+        key_origins: Vec<(PublicKey, KeySource)>,
+    },
+    /// Add the signatures of a signed base64 PSBT file to a graph transaction
+    Import {
+        graph_id: String,
+        transaction: String,
+        file: PathBuf,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum DepositorCommand {
+    /// Create a peg-in graph spending the given deposit output, or funding it from the
+    /// depositor's wallet when no outpoint is given
+    CreatePegIn {
+        /// Deposit output as <txid>:<vout>
+        #[arg(long)]
+        outpoint: Option<OutPoint>,
+        /// Value of the deposit output in satoshis
+        #[arg(long)]
+        amount: u64,
+        /// Address that receives the wrapped BTC on the destination network
+        #[arg(long)]
+        evm_address: String,
+        #[command(flatten)]
+        funding: FundingArgs,
+    },
+    /// Broadcast the peg-in deposit transaction
+    Deposit { graph_id: String },
+    /// Broadcast the peg-in refund transaction once its timelock expired
+    Refund { graph_id: String },
+    /// Track the deposit and confirm transactions of the depositor's peg-in graphs, warn before
+    /// the refund unlocks and broadcast the refund if the peg-in is not confirmed by then. Exits
+    /// once every watched graph is pegged in or refunded
+    Watch {
+        /// Only watch this peg-in graph
+        graph_id: Option<String>,
+        /// Seconds between two checks of the chain
+        #[arg(long, default_value_t = 60)]
+        poll_interval: u64,
+        /// Warn this many blocks before the refund unlocks
+        #[arg(long, default_value_t = DEFAULT_REFUND_WARNING_BLOCKS)]
+        warning_blocks: u32,
+        /// Only warn when the refund unlocks instead of broadcasting it
+        #[arg(long)]
+        no_auto_refund: bool,
+    },
+}
+
+/// Wallet used to fund a graph input when no outpoint is given.
+#[derive(Args, Debug)]
+pub struct FundingArgs {
+    /// Descriptor of the funding wallet, wpkh(KEY), wsh(pk(KEY)) or tr(KEY) of the role's key.
+    /// Defaults to wsh(pk(KEY)), the output type of the graph input itself
+    #[arg(long)]
+    pub descriptor: Option<String>,
+    /// Fee rate of the funding transaction in sat/vB
+    #[arg(long, default_value_t = DEFAULT_FEE_RATE)]
+    pub fee_rate: u64,
+    /// Also fund from UTXOs of unconfirmed transactions
+    #[arg(long)]
+    pub include_unconfirmed: bool,
+}
+
+impl FundingArgs {
+    fn descriptor(&self) -> Result<Option<WalletDescriptor>, String> {
+        self.descriptor
+            .as_deref()
+            .map(WalletDescriptor::from_str)
+            .transpose()
+    }
+}
+
+#[derive(Subcommand, Debug)]
+pub enum OperatorCommand {
+    /// Create the operator's peg-out graph for a peg-in graph, funding the kick-off input from
+    /// the operator's wallet when no outpoint is given
+    CreatePegOut {
+        #[arg(long)]
+        peg_in_graph_id: String,
+        /// Kick-off funding output as <txid>:<vout>
+        #[arg(long)]
+        outpoint: Option<OutPoint>,
+        /// Value of the kick-off funding output in satoshis
+        #[arg(long)]
+        amount: u64,
+        #[command(flatten)]
+        funding: FundingArgs,
+    },
+    /// Publish the total value this operator is willing to front for peg-outs
+    DeclareLiquidity {
+        /// Liquidity in satoshis
+        #[arg(long)]
+        amount: u64,
+    },
+    /// Broadcast the peg-in confirm transaction once the n-of-n signed it
+    PegInConfirm { graph_id: String },
+    /// Pay out the peg-out requested on a peg-out graph to the withdrawer
+    PegOut {
+        graph_id: String,
+        /// Operator output funding the peg-out as <txid>:<vout>:<amount in satoshis>, paying the
+        /// withdrawer's burnt amount after the fee
+        #[arg(long, value_parser = parse_funding_input)]
+        input: (OutPoint, Amount),
+    },
+    /// Broadcast the kick-off 1 transaction
+    KickOff1 { graph_id: String },
+    /// Broadcast the start time transaction
+    StartTime { graph_id: String },
+    /// Broadcast the kick-off 2 transaction
+    KickOff2 { graph_id: String },
+    /// Broadcast the assert transaction
+    Assert { graph_id: String },
+    /// Broadcast the take 1 transaction
+    Take1 { graph_id: String },
+    /// Broadcast the take 2 transaction
+    Take2 { graph_id: String },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum VerifierCommand {
+    /// Generate and publish this verifier's MuSig2 nonces for a graph
+    PushNonces {
+        #[arg(value_enum)]
+        graph_type: GraphType,
+        graph_id: String,
+    },
+    /// Validate a graph and publish this verifier's partial signatures for it
+    PreSign {
+        #[arg(value_enum)]
+        graph_type: GraphType,
+        graph_id: String,
+    },
+    /// Publish this verifier's MuSig2 nonces for all graphs that do not have them yet
+    PushPendingNonces,
+    /// Publish this verifier's partial signatures for all graphs that have the nonces of every
+    /// verifier
+    PreSignPending,
+    /// Broadcast the challenge transaction, crowdfunded from outputs paying to the verifier key
+    Challenge {
+        graph_id: String,
+        /// Crowdfunding input as <txid>:<vout>:<amount in satoshis>, can be repeated
+        #[arg(long = "input", required = true, value_parser = parse_funding_input)]
+        inputs: Vec<(OutPoint, Amount)>,
+        /// Address receiving the change of the crowdfunding inputs
+        #[arg(long)]
+        refund_address: Address<NetworkUnchecked>,
+    },
+    /// Broadcast the kick-off timeout transaction
+    KickOffTimeout {
+        graph_id: String,
+        #[arg(long)]
+        reward_address: Address<NetworkUnchecked>,
+    },
+    /// Broadcast the start time timeout transaction
+    StartTimeTimeout {
+        graph_id: String,
+        #[arg(long)]
+        reward_address: Address<NetworkUnchecked>,
+    },
+    /// Broadcast the disprove chain transaction
+    DisproveChain {
+        graph_id: String,
+        #[arg(long)]
+        reward_address: Address<NetworkUnchecked>,
+    },
+    /// Broadcast the disprove transaction spending the given connector C leaf
+    Disprove {
+        graph_id: String,
+        /// Index of the connector C leaf whose script fails for the asserted values
+        #[arg(long)]
+        script_index: u32,
+        #[arg(long)]
+        reward_address: Address<NetworkUnchecked>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum WithdrawerCommand {
+    /// List the operators whose presigned peg-out graph for a peg-in graph can be requested,
+    /// with their available liquidity
+    Operators { peg_in_graph_id: String },
+    /// Request a peg-out from the peg-out graph of the chosen operator, or of the operator
+    /// selected by the policy when none is given
+    RequestPegOut {
+        peg_in_graph_id: String,
+        /// Public key of the operator fronting the peg-out
+        #[arg(long)]
+        operator: Option<PublicKey>,
+        /// How the operator is selected when none is given
+        #[arg(long, value_enum, default_value_t = SelectionPolicy::default())]
+        policy: SelectionPolicy,
+        /// Address that burnt the wrapped BTC on the destination network
+        #[arg(long)]
+        evm_address: String,
+    },
+}
+
+#[derive(ValueEnum, Serialize, Deserialize, Clone, Copy, Eq, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum GraphType {
+    PegIn,
+    PegOut,
+}
+
+pub async fn run(cli: Cli) -> Result<(), String> {
+    let config = BridgeConfig::load(&cli.config)?;
+
+    match cli.command {
+        Command::Status => {
+            let client = synced_client(&config, &config.configured_roles()).await?;
+            client.status().await;
+        }
+        Command::Sync => {
+            let client = synced_client(&config, &[]).await?;
+            let data = client.get_data();
+            println!("Peg-in graphs: {}", data.peg_in_graphs.len());
+            for peg_in_graph in data.peg_in_graphs.iter() {
+                println!("  {}", peg_in_graph.id());
+            }
+            println!("Peg-out graphs: {}", data.peg_out_graphs.len());
+            for peg_out_graph in data.peg_out_graphs.iter() {
+                println!(
+                    "  {} (peg-in graph: {})",
+                    peg_out_graph.id(),
+                    peg_out_graph.peg_in_graph_id
+                );
+            }
+        }
+        Command::Depositor(command) => run_depositor_command(&config, command).await?,
+        Command::Operator(command) => run_operator_command(&config, command).await?,
+        Command::Verifier(command) => run_verifier_command(&config, command).await?,
+        Command::Withdrawer(command) => run_withdrawer_command(&config, command).await?,
+        Command::Psbt(command) => run_psbt_command(&config, command).await?,
+        Command::Daemon {
+            listen,
+            sync_interval,
+        } => {
+            let mut daemon = Daemon::new(config).await?;
+            daemon
+                .run(listen, Duration::from_secs(sync_interval))
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn run_psbt_command(config: &BridgeConfig, command: PsbtCommand) -> Result<(), String> {
+    let mut client = synced_client(config, &[]).await?;
+
+    match command {
+        PsbtCommand::Export {
+            graph_id,
+            transaction,
+            output,
+            key_origins,
+        } => {
+            let psbt = client
+                .export_psbt(&graph_id, &transaction, &HashMap::from_iter(key_origins))
+                .await?
+                .to_string();
+            match output {
+                Some(output) => fs::write(&output, psbt).map_err(|err| {
+                    format!("Could not write {}: {}", output.to_string_lossy(), err)
+                })?,
+                None => println!("{}", psbt),
+            }
+        }
+        PsbtCommand::Import {
+            graph_id,
+            transaction,
+            file,
+        } => {
+            let data = fs::read_to_string(&file)
+                .map_err(|err| format!("Could not read {}: {}", file.to_string_lossy(), err))?;
+            let psbt = Psbt::from_str(data.trim())
+                .map_err(|err| format!("Invalid PSBT in {}: {}", file.to_string_lossy(), err))?;
+            let signed_inputs = client.import_psbt(&graph_id, &transaction, &psbt)?;
+            client.flush().await;
+            println!("Imported signatures of {} inputs", signed_inputs);
+        }
+    }
+
+    Ok(())
+}
+
+async fn run_depositor_command(
+    config: &BridgeConfig,
+    command: DepositorCommand,
+) -> Result<(), String> {
+    let mut client = synced_client(config, &[ClientRole::Depositor]).await?;
+
+    match command {
+        DepositorCommand::CreatePegIn {
+            outpoint,
+            amount,
+            evm_address,
+            funding,
+        } => {
+            let peg_in_graph_id = match outpoint {
+                Some(outpoint) => {
+                    client
+                        .create_peg_in_graph(
+                            Input {
+                                outpoint,
+                                amount: Amount::from_sat(amount),
+                            },
+                            &evm_address,
+                            &config.timelock_config(),
+                        )
+                        .await
+                }
+                None => {
+                    client
+                        .create_peg_in_graph_with_funding(
+                            Amount::from_sat(amount),
+                            &evm_address,
+                            &config.timelock_config(),
+                            funding.descriptor()?,
+                            funding.fee_rate,
+                            funding.include_unconfirmed,
+                        )
+                        .await?
+                }
+            };
+            client.flush().await;
+            println!("Created peg-in graph: {}", peg_in_graph_id);
+        }
+        DepositorCommand::Deposit { graph_id } => client.broadcast_peg_in_deposit(&graph_id).await,
+        DepositorCommand::Refund { graph_id } => client.broadcast_peg_in_refund(&graph_id).await,
+        DepositorCommand::Watch {
+            graph_id,
+            poll_interval,
+            warning_blocks,
+            no_auto_refund,
+        } => {
+            let mut watcher = RefundWatcher::new(warning_blocks, !no_auto_refund);
+            loop {
+                if let Err(err) = client
+                    .watch_peg_in_refunds(&mut watcher, graph_id.as_deref())
+                    .await
+                {
+                    eprintln!("Watch failed: {}", err);
+                }
+                if watcher.is_finished() {
+                    break;
+                }
+                sleep(Duration::from_secs(poll_interval)).await;
+                client.sync().await;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn run_operator_command(
+    config: &BridgeConfig,
+    command: OperatorCommand,
+) -> Result<(), String> {
+    let mut client = synced_client(config, &[ClientRole::Operator]).await?;
+
+    match command {
+        OperatorCommand::CreatePegOut {
+            peg_in_graph_id,
+            outpoint,
+            amount,
+            funding,
+        } => {
+            let peg_out_graph_id = match outpoint {
+                Some(outpoint) => {
+                    client
+                        .create_peg_out_graph(
+                            &peg_in_graph_id,
+                            Input {
+                                outpoint,
+                                amount: Amount::from_sat(amount),
+                            },
+                        )
+                        .await
+                }
+                None => {
+                    client
+                        .create_peg_out_graph_with_funding(
+                            &peg_in_graph_id,
+                            Amount::from_sat(amount),
+                            funding.descriptor()?,
+                            funding.fee_rate,
+                            funding.include_unconfirmed,
+                        )
+                        .await?
+                }
+            };
+            client.flush().await;
+            println!("Created peg-out graph: {}", peg_out_graph_id);
+        }
+        OperatorCommand::DeclareLiquidity { amount } => {
+            client.declare_operator_liquidity(Amount::from_sat(amount));
+            client.flush().await;
+            println!("Declared liquidity: {}", Amount::from_sat(amount));
+        }
+        OperatorCommand::PegInConfirm { graph_id } => {
+            client.broadcast_peg_in_confirm(&graph_id).await
+        }
+        OperatorCommand::PegOut {
+            graph_id,
+            input: (outpoint, amount),
+        } => {
+            client
+                .broadcast_peg_out(&graph_id, Input { outpoint, amount }, &Chain::new())
+                .await;
+            client.flush().await;
+        }
+        OperatorCommand::KickOff1 { graph_id } => client.broadcast_kick_off_1(&graph_id).await,
+        OperatorCommand::StartTime { graph_id } => client.broadcast_start_time(&graph_id).await,
+        OperatorCommand::KickOff2 { graph_id } => client.broadcast_kick_off_2(&graph_id).await,
+        OperatorCommand::Assert { graph_id } => client.broadcast_assert(&graph_id).await,
+        OperatorCommand::Take1 { graph_id } => client.broadcast_take_1(&graph_id).await,
+        OperatorCommand::Take2 { graph_id } => client.broadcast_take_2(&graph_id).await,
+    }
+
+    Ok(())
+}
+
+async fn run_verifier_command(
+    config: &BridgeConfig,
+    command: VerifierCommand,
+) -> Result<(), String> {
+    let mut client = synced_client(config, &[ClientRole::Verifier]).await?;
+
+    match command {
+        VerifierCommand::PushNonces {
+            graph_type,
+            graph_id,
+        } => {
+            match graph_type {
+                GraphType::PegIn => client.push_peg_in_nonces(&graph_id),
+                GraphType::PegOut => client.push_peg_out_nonces(&graph_id),
+            }
+            client.flush().await;
+        }
+        VerifierCommand::PreSign {
+            graph_type,
+            graph_id,
+        } => {
+            match graph_type {
+                GraphType::PegIn => client.pre_sign_peg_in(&graph_id),
+                GraphType::PegOut => client.pre_sign_peg_out(&graph_id),
+            }
+            client.flush().await;
+        }
+        VerifierCommand::PushPendingNonces => {
+            let graph_ids = client.push_pending_nonces(&print_batch_progress);
+            client.flush().await;
+            println!("Pushed nonces for {} graphs", graph_ids.len());
+        }
+        VerifierCommand::PreSignPending => {
+            let graph_ids = client.pre_sign_pending_graphs(&print_batch_progress);
+            client.flush().await;
+            println!("Pre-signed {} graphs", graph_ids.len());
+        }
+        VerifierCommand::Challenge {
+            graph_id,
+            inputs,
+            refund_address,
+        } => {
+            // The crowdfunding outputs must pay to the verifier's key, which signs them.
+            let script = generate_pay_to_pubkey_script(&config.public_key(ClientRole::Verifier)?);
+            let crowdfunding_inputs: Vec<InputWithScript> = inputs
+                .iter()
+                .map(|(outpoint, amount)| InputWithScript {
+                    outpoint: *outpoint,
+                    amount: *amount,
+                    script: &script,
+                })
+                .collect();
+            client
+                .broadcast_challenge(
+                    &graph_id,
+                    &crowdfunding_inputs,
+                    script_pubkey(config, refund_address)?,
+                )
+                .await;
+        }
+        VerifierCommand::KickOffTimeout {
+            graph_id,
+            reward_address,
+        } => {
+            client
+                .broadcast_kick_off_timeout(&graph_id, script_pubkey(config, reward_address)?)
+                .await
+        }
+        VerifierCommand::StartTimeTimeout {
+            graph_id,
+            reward_address,
+        } => {
+            client
+                .broadcast_start_time_timeout(&graph_id, script_pubkey(config, reward_address)?)
+                .await
+        }
+        VerifierCommand::DisproveChain {
+            graph_id,
+            reward_address,
+        } => {
+            client
+                .broadcast_disprove_chain(&graph_id, script_pubkey(config, reward_address)?)
+                .await
+        }
+        VerifierCommand::Disprove {
+            graph_id,
+            script_index,
+            reward_address,
+        } => {
+            client
+                .broadcast_disprove(
+                    &graph_id,
+                    script_index,
+                    script_pubkey(config, reward_address)?,
+                )
+                .await
+        }
+    }
+
+    Ok(())
+}
+
+async fn run_withdrawer_command(
+    config: &BridgeConfig,
+    command: WithdrawerCommand,
+) -> Result<(), String> {
+    let mut client = synced_client(config, &[ClientRole::Withdrawer]).await?;
+
+    match command {
+        WithdrawerCommand::Operators { peg_in_graph_id } => {
+            let registry = client.get_operator_registry().await;
+            for operator_public_key in client.get_available_peg_out_operators(&peg_in_graph_id) {
+                let available_liquidity = registry
+                    .operator(&operator_public_key)
+                    .map(|record| record.available_liquidity())
+                    .unwrap_or(Amount::ZERO);
+                println!(
+                    "{} (available liquidity: {})",
+                    operator_public_key, available_liquidity
+                );
+            }
+        }
+        WithdrawerCommand::RequestPegOut {
+            peg_in_graph_id,
+            operator,
+            policy,
+            evm_address,
+        } => {
+            let peg_out_graph_id = match operator {
+                Some(operator) => {
+                    client.request_peg_out(&peg_in_graph_id, &operator, &evm_address)?
+                }
+                None => {
+                    client
+                        .request_peg_out_from_selected_operator(
+                            &peg_in_graph_id,
+                            &evm_address,
+                            policy,
+                        )
+                        .await?
+                }
+            };
+            client.flush().await;
+            println!("Requested peg-out on peg-out graph: {}", peg_out_graph_id);
+        }
+    }
+
+    Ok(())
+}
+
+async fn synced_client(config: &BridgeConfig, roles: &[ClientRole]) -> Result<BitVMClient, String> {
+    let mut client = config.client(roles).await?;
+    client.sync().await;
+
+    Ok(client)
+}
+
+fn print_batch_progress(progress: &BatchProgress) {
+    println!(
+        "[{}/{}] Graph {}",
+        progress.completed, progress.total, progress.graph_id
+    );
+}
+
+fn script_pubkey(
+    config: &BridgeConfig,
+    address: Address<NetworkUnchecked>,
+) -> Result<ScriptBuf, String> {
+    address
+        .require_network(config.source_network)
+        .map(|address| address.script_pubkey())
+        .map_err(|err| format!("Invalid address: {}", err))
+}
+
+fn parse_funding_input(input: &str) -> Result<(OutPoint, Amount), String> {
+    let (outpoint, amount) = input
+        .rsplit_once(':')
+        .ok_or_else(|| format!("Expected <txid>:<vout>:<amount>, got {}", input))?;
+    let outpoint =
+        OutPoint::from_str(outpoint).map_err(|err| format!("Invalid outpoint: {}", err))?;
+    let amount = amount
+        .parse::<u64>()
+        .map_err(|err| format!("Invalid amount: {}", err))?;
+
+    Ok((outpoint, Amount::from_sat(amount)))
+}
+
+fn parse_key_origin(key_origin: &str) -> Result<(PublicKey, KeySource), String> {
+    let (origin, public_key) = key_origin
+        .strip_prefix('[')
+        .and_then(|key_origin| key_origin.split_once(']'))
+        .ok_or_else(|| format!("Expected [<fingerprint>/<path>]<key>, got {}", key_origin))?;
+    let (fingerprint, path) = origin.split_once('/').unwrap_or((origin, ""));
+    let fingerprint = Fingerprint::from_str(fingerprint)
+        .map_err(|err| format!("Invalid fingerprint: {}", err))?;
+    let derivation_path = DerivationPath::from_str(&format!("m/{}", path).trim_end_matches('/'))
+        .map_err(|err| format!("Invalid derivation path: {}", err))?;
+    let public_key =
+        PublicKey::from_str(public_key).map_err(|err| format!("Invalid public key: {}", err))?;
+
+    Ok((public_key, (fingerprint, derivation_path)))
+}
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::{
+        bip32::{DerivationPath, Fingerprint},
+        Amount,
+    };
+    use clap::{CommandFactory, Parser};
+    use std::str::FromStr;
+
+    use super::{
+        parse_funding_input, parse_key_origin, Cli, Command, DepositorCommand, GraphType,
+        OperatorCommand, PsbtCommand, SelectionPolicy, VerifierCommand, WithdrawerCommand,
+        DEFAULT_FEE_RATE, DEFAULT_REFUND_WARNING_BLOCKS,
+    };
+
+    const TXID: &str = "0e6719ac074b0e3cac76d057643506faa1c266b322aa9cf4c6f635fe63b14327";
+    const PUBLIC_KEY: &str = "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+
+    #[test]
+    fn test_cli_definition() { Cli::command().debug_assert(); }
+
+    #[test]
+    fn test_parse_verifier_commands() {
+        let cli = Cli::try_parse_from(["bitvm-bridge", "verifier", "pre-sign", "peg-out", "ABCD"])
+            .unwrap();
+        assert!(matches!(
+            cli.command,
+            Command::Verifier(VerifierCommand::PreSign {
+                graph_type: GraphType::PegOut,
+                ..
+            })
+        ));
+
+        let cli = Cli::try_parse_from(["bitvm-bridge", "verifier", "pre-sign-pending"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Command::Verifier(VerifierCommand::PreSignPending)
+        ));
+
+        let input = format!("{}:1:5000", TXID);
+        let cli = Cli::try_parse_from([
+            "bitvm-bridge",
+            "--config",
+            "verifier.json",
+            "verifier",
+            "challenge",
+            "ABCD",
+            "--input",
+            &input,
+            "--input",
+            &input,
+            "--refund-address",
+            "tb1qw508d6qejxtdg4y5r3zarvary0c5xw7kxpjzsx",
+        ])
+        .unwrap();
+        assert_eq!(cli.config.to_str(), Some("verifier.json"));
+        match cli.command {
+            Command::Verifier(VerifierCommand::Challenge { inputs, .. }) => {
+                assert_eq!(inputs.len(), 2);
+                assert_eq!(inputs[0].0.vout, 1);
+                assert_eq!(inputs[0].1, Amount::from_sat(5000));
+            }
+            _ => panic!("Expected a challenge command"),
+        }
+    }
+
+    #[test]
+    fn test_parse_peg_out_commands() {
+        let operator = "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+        let cli = Cli::try_parse_from([
+            "bitvm-bridge",
+            "withdrawer",
+            "request-peg-out",
+            "ABCD",
+            "--operator",
+            operator,
+            "--evm-address",
+            "0x0000000000000000000000000000000000000000",
+        ])
+        .unwrap();
+        match cli.command {
+            Command::Withdrawer(WithdrawerCommand::RequestPegOut {
+                peg_in_graph_id,
+                operator: Some(operator_public_key),
+                policy: SelectionPolicy::MostAvailableLiquidity,
+                ..
+            }) => {
+                assert_eq!(peg_in_graph_id, "ABCD");
+                assert_eq!(operator_public_key.to_string(), operator);
+            }
+            _ => panic!("Expected a request peg-out command"),
+        }
+
+        let cli = Cli::try_parse_from([
+            "bitvm-bridge",
+            "withdrawer",
+            "request-peg-out",
+            "ABCD",
+            "--policy",
+            "fewest-open-peg-outs",
+            "--evm-address",
+            "0x0000000000000000000000000000000000000000",
+        ])
+        .unwrap();
+        assert!(matches!(
+            cli.command,
+            Command::Withdrawer(WithdrawerCommand::RequestPegOut {
+                operator: None,
+                policy: SelectionPolicy::FewestOpenPegOuts,
+                ..
+            })
+        ));
+
+        let input = format!("{}:0:131072", TXID);
+        let cli = Cli::try_parse_from([
+            "bitvm-bridge",
+            "operator",
+            "peg-out",
+            "EFGH",
+            "--input",
+            &input,
+        ])
+        .unwrap();
+        match cli.command {
+            Command::Operator(OperatorCommand::PegOut {
+                input: (_, amount), ..
+            }) => {
+                assert_eq!(amount, Amount::from_sat(131072));
+            }
+            _ => panic!("Expected a peg-out command"),
+        }
+    }
+
+    #[test]
+    fn test_parse_depositor_watch_command() {
+        let cli = Cli::try_parse_from(["bitvm-bridge", "depositor", "watch"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Command::Depositor(DepositorCommand::Watch {
+                graph_id: None,
+                poll_interval: 60,
+                warning_blocks: DEFAULT_REFUND_WARNING_BLOCKS,
+                no_auto_refund: false,
+            })
+        ));
+
+        let cli = Cli::try_parse_from([
+            "bitvm-bridge",
+            "depositor",
+            "watch",
+            "ABCD",
+            "--warning-blocks",
+            "10",
+            "--no-auto-refund",
+        ])
+        .unwrap();
+        match cli.command {
+            Command::Depositor(DepositorCommand::Watch {
+                graph_id,
+                warning_blocks,
+                no_auto_refund,
+                ..
+            }) => {
+                assert_eq!(graph_id.as_deref(), Some("ABCD"));
+                assert_eq!(warning_blocks, 10);
+                assert!(no_auto_refund);
+            }
+            _ => panic!("Expected a depositor watch command"),
+        }
+    }
+
+    #[test]
+    fn test_parse_funding_arguments() {
+        let cli = Cli::try_parse_from([
+            "bitvm-bridge",
+            "depositor",
+            "create-peg-in",
+            "--amount",
+            "100000",
+            "--evm-address",
+            "0x0000000000000000000000000000000000000000",
+            "--fee-rate",
+            "5",
+            "--include-unconfirmed",
+        ])
+        .unwrap();
+        match cli.command {
+            Command::Depositor(DepositorCommand::CreatePegIn {
+                outpoint, funding, ..
+            }) => {
+                assert_eq!(outpoint, None);
+                assert_eq!(funding.fee_rate, 5);
+                assert_eq!(funding.descriptor(), Ok(None));
+                assert!(funding.include_unconfirmed);
+            }
+            _ => panic!("Expected a create peg-in command"),
+        }
+
+        let outpoint = format!("{}:0", TXID);
+        let cli = Cli::try_parse_from([
+            "bitvm-bridge",
+            "operator",
+            "create-peg-out",
+            "--peg-in-graph-id",
+            "ABCD",
+            "--outpoint",
+            &outpoint,
+            "--amount",
+            "100000",
+            "--descriptor",
+            "wpkh(02deadbeef)",
+        ])
+        .unwrap();
+        match cli.command {
+            Command::Operator(OperatorCommand::CreatePegOut {
+                outpoint, funding, ..
+            }) => {
+                assert!(outpoint.is_some());
+                assert_eq!(funding.fee_rate, DEFAULT_FEE_RATE);
+                assert!(funding.descriptor().is_err());
+                assert!(!funding.include_unconfirmed);
+            }
+            _ => panic!("Expected a create peg-out command"),
+        }
+    }
+
+    #[test]
+    fn test_parse_psbt_commands() {
+        let cli =
+            Cli::try_parse_from(["bitvm-bridge", "psbt", "export", "ABCD", "kick_off_1"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Command::Psbt(PsbtCommand::Export { output: None, .. })
+        ));
+
+        let key_origin = format!("[d34db33f/84'/1'/0'/0/7]{}", PUBLIC_KEY);
+        let cli = Cli::try_parse_from([
+            "bitvm-bridge",
+            "psbt",
+            "export",
+            "ABCD",
+            "kick_off_1",
+            "--key-origin",
+            &key_origin,
+        ])
+        .unwrap();
+        match cli.command {
+            Command::Psbt(PsbtCommand::Export { key_origins, .. }) => {
+                assert_eq!(key_origins.len(), 1)
+            }
+            _ => panic!("Expected a PSBT export command"),
+        }
+
+        let cli = Cli::try_parse_from([
+            "bitvm-bridge",
+            "psbt",
+            "import",
+            "ABCD",
+            "take_1",
+            "take_1.psbt",
+        ])
+        .unwrap();
+        match cli.command {
+            Command::Psbt(PsbtCommand::Import {
+                transaction, file, ..
+            }) => {
+                assert_eq!(transaction, "take_1");
+                assert_eq!(file.to_str(), Some("take_1.psbt"));
+            }
+            _ => panic!("Expected a PSBT import command"),
+        }
+    }
+
+    #[test]
+    fn test_parse_daemon_command() {
+        let cli = Cli::try_parse_from(["bitvm-bridge", "daemon", "--sync-interval", "30"]).unwrap();
+        match cli.command {
+            Command::Daemon {
+                listen,
+                sync_interval,
+            } => {
+                assert!(listen.ip().is_loopback());
+                assert_eq!(sync_interval, 30);
+            }
+            _ => panic!("Expected a daemon command"),
+        }
+    }
+
+    #[test]
+    fn test_parse_funding_input() {
+        assert!(parse_funding_input(&format!("{}:0:1000", TXID)).is_ok());
+        assert!(parse_funding_input(&format!("{}:0", TXID)).is_err());
+        assert!(parse_funding_input("not an input").is_err());
+    }
+
+    #[test]
+    fn test_parse_key_origin() {
+        let (public_key, (fingerprint, derivation_path)) =
+            parse_key_origin(&format!("[d34db33f/84'/1'/0'/0/7]{}", PUBLIC_KEY)).unwrap();
+        assert_eq!(public_key.to_string(), PUBLIC_KEY);
+        assert_eq!(fingerprint, Fingerprint::from([0xd3, 0x4d, 0xb3, 0x3f]));
+        assert_eq!(
+            derivation_path,
+            DerivationPath::from_str("m/84'/1'/0'/0/7").unwrap()
+        );
+
+        let (_, (_, derivation_path)) =
+            parse_key_origin(&format!("[d34db33f]{}", PUBLIC_KEY)).unwrap();
+        assert!(derivation_path.is_master());
+
+        assert!(parse_key_origin(PUBLIC_KEY).is_err());
+        assert!(parse_key_origin(&format!("[d34db33f/x]{}", PUBLIC_KEY)).is_err());
+    }
+}
@@ -0,0 +1,208 @@
+use bitcoin::{Network, PublicKey};
+use esplora_client::Builder;
+use serde::{Deserialize, Serialize};
+use std::{fmt, fs, path::Path};
+
+use super::super::{
+    super::{
+        constants::DestinationNetwork, contexts::base::generate_keys_from_secret,
+        serialization::try_deserialize, timelocks::TimelockConfig,
+    },
+    client::BitVMClient,
+};
+
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub enum ClientRole {
+    Depositor,
+    Operator,
+    Verifier,
+    Withdrawer,
+}
+
+impl fmt::Display for ClientRole {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ClientRole::Depositor => write!(f, "depositor"),
+            ClientRole::Operator => write!(f, "operator"),
+            ClientRole::Verifier => write!(f, "verifier"),
+            ClientRole::Withdrawer => write!(f, "withdrawer"),
+        }
+    }
+}
+
+/// Keys and endpoints used by the `bitvm-bridge` binary. Only the secrets of the roles that are
+/// actually run on this machine need to be present.
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Debug)]
+pub struct BridgeConfig {
+    pub source_network: Network,
+    pub destination_network: DestinationNetwork,
+    #[serde(default)]
+    pub esplora_url: Option<String>,
+    pub n_of_n_public_keys: Vec<PublicKey>,
+    #[serde(default)]
+    pub depositor_secret: Option<String>,
+    #[serde(default)]
+    pub operator_secret: Option<String>,
+    #[serde(default)]
+    pub verifier_secret: Option<String>,
+    #[serde(default)]
+    pub withdrawer_secret: Option<String>,
+    /// Timelocks for new peg-in graphs, defaults to the preset of the source network.
+    #[serde(default)]
+    pub timelock_config: Option<TimelockConfig>,
+}
+
+impl BridgeConfig {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let data = fs::read_to_string(path).map_err(|err| {
+            format!(
+                "Could not read config file {}: {}",
+                path.to_string_lossy(),
+                err
+            )
+        })?;
+        let config = try_deserialize::<BridgeConfig>(&data)?;
+        config.validate()?;
+
+        Ok(config)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.n_of_n_public_keys.is_empty() {
+            return Err(String::from(
+                "Config must list at least one n-of-n public key",
+            ));
+        }
+        self.timelock_config().validate()
+    }
+
+    pub fn timelock_config(&self) -> TimelockConfig {
+        self.timelock_config
+            .unwrap_or(TimelockConfig::for_network(self.source_network))
+    }
+
+    pub fn secret(&self, role: ClientRole) -> Option<&str> {
+        match role {
+            ClientRole::Depositor => self.depositor_secret.as_deref(),
+            ClientRole::Operator => self.operator_secret.as_deref(),
+            ClientRole::Verifier => self.verifier_secret.as_deref(),
+            ClientRole::Withdrawer => self.withdrawer_secret.as_deref(),
+        }
+    }
+
+    pub fn public_key(&self, role: ClientRole) -> Result<PublicKey, String> {
+        let secret = self.required_secret(role)?;
+        let (_, _, public_key) = generate_keys_from_secret(self.source_network, secret);
+
+        Ok(public_key)
+    }
+
+    /// Creates a client acting only as the given roles, so that commands sign with the key of the
+    /// role they belong to even when the config holds the secrets of several roles.
+    pub async fn client(&self, roles: &[ClientRole]) -> Result<BitVMClient, String> {
+        let mut secrets = [None; 4];
+        for (index, role) in [
+            ClientRole::Depositor,
+            ClientRole::Operator,
+            ClientRole::Verifier,
+            ClientRole::Withdrawer,
+        ]
+        .iter()
+        .enumerate()
+        {
+            if roles.contains(role) {
+                secrets[index] = Some(self.required_secret(*role)?);
+            }
+        }
+
+        let mut client = BitVMClient::new(
+            self.source_network,
+            self.destination_network,
+            &self.n_of_n_public_keys,
+            secrets[0],
+            secrets[1],
+            secrets[2],
+            secrets[3],
+        )
+        .await;
+        if let Some(esplora_url) = &self.esplora_url {
+            client.esplora = Builder::new(esplora_url)
+                .build_async()
+                .map_err(|err| format!("Could not build esplora client: {}", err))?;
+        }
+
+        Ok(client)
+    }
+
+    /// Roles whose secret is present in the config.
+    pub fn configured_roles(&self) -> Vec<ClientRole> {
+        [
+            ClientRole::Depositor,
+            ClientRole::Operator,
+            ClientRole::Verifier,
+            ClientRole::Withdrawer,
+        ]
+        .into_iter()
+        .filter(|role| self.secret(*role).is_some())
+        .collect()
+    }
+
+    fn required_secret(&self, role: ClientRole) -> Result<&str, String> {
+        self.secret(role)
+            .ok_or_else(|| format!("Config is missing the {} secret", role))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::Network;
+
+    use super::{
+        super::super::super::{
+            constants::DestinationNetwork, serialization::try_deserialize,
+            timelocks::TimelockConfig,
+        },
+        BridgeConfig, ClientRole,
+    };
+
+    const CONFIG: &str = r#"{
+        "source_network": "testnet",
+        "destination_network": "ethereum_sepolia",
+        "n_of_n_public_keys": [
+            "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798"
+        ],
+        "operator_secret": "3076ca1dfc1e383be26d5dd3c0c427340f96139fa8c2520862cf551ec2d670ac"
+    }"#;
+
+    #[test]
+    fn test_parse_config() {
+        let config = try_deserialize::<BridgeConfig>(CONFIG).unwrap();
+
+        assert!(config.validate().is_ok());
+        assert_eq!(config.source_network, Network::Testnet);
+        assert!(config.destination_network == DestinationNetwork::EthereumSepolia);
+        assert_eq!(config.esplora_url, None);
+        assert_eq!(
+            config.timelock_config(),
+            TimelockConfig::for_network(Network::Testnet)
+        );
+        assert_eq!(config.configured_roles(), vec![ClientRole::Operator]);
+        assert!(config.public_key(ClientRole::Operator).is_ok());
+        assert!(config
+            .public_key(ClientRole::Verifier)
+            .is_err_and(|err| err.contains("missing the verifier secret")));
+    }
+
+    #[test]
+    fn test_reject_invalid_config() {
+        let mut config = try_deserialize::<BridgeConfig>(CONFIG).unwrap();
+        config.n_of_n_public_keys.clear();
+        assert!(config.validate().is_err());
+
+        let mut config = try_deserialize::<BridgeConfig>(CONFIG).unwrap();
+        let mut timelock_config = TimelockConfig::regtest();
+        timelock_config.num_blocks_take_1 = 0;
+        config.timelock_config = Some(timelock_config);
+        assert!(config.validate().is_err());
+    }
+}
@@ -1,31 +1,62 @@
 use musig2::SecNonce;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     fs::{self},
     path::Path,
 };
 
-use bitcoin::{absolute::Height, Address, Amount, Network, OutPoint, PublicKey, ScriptBuf, Txid};
+use bitcoin::{
+    absolute::Height, bip32::KeySource, key::Keypair, psbt::Psbt, Address, Amount, Network,
+    OutPoint, PublicKey, ScriptBuf, Transaction, Txid,
+};
 use esplora_client::{AsyncClient, Builder, Utxo};
+use tracing::{debug, error, info, instrument, warn};
 
 use crate::bridge::{constants::DestinationNetwork, contexts::base::generate_n_of_n_public_key};
 
 use super::{
     super::{
         contexts::{
-            depositor::DepositorContext, operator::OperatorContext, verifier::VerifierContext,
-            withdrawer::WithdrawerContext,
+            base::BaseContext, depositor::DepositorContext, operator::OperatorContext,
+            verifier::VerifierContext, withdrawer::WithdrawerContext,
         },
         graphs::{
             base::BaseGraph,
+            migration::migrate_client_data,
             peg_in::{generate_id as peg_in_generate_id, PegInGraph},
-            peg_out::{generate_id as peg_out_generate_id, PegOutGraph},
+            peg_out::{
+                generate_id as peg_out_generate_id, PegOutGraph, PegOutOperatorStatus,
+                PegOutWithdrawerStatus,
+            },
+        },
+        scripts::generate_pay_to_pubkey_script_address,
+        serialization::{
+            binary, serialize, try_deserialize, try_deserialize_value, SerializationFormat,
+        },
+        signing_session::batch::{
+            count_pending_signatures, is_ready_to_pre_sign, needs_nonces, pre_sign_batch,
+            push_nonces_batch, BatchProgress,
+        },
+        timelocks::TimelockConfig,
+        transactions::{
+            base::{BaseTransaction, Input, InputWithScript},
+            pre_signed::PreSignedTransaction,
         },
-        serialization::{serialize, try_deserialize},
-        transactions::base::{Input, InputWithScript},
     },
+    chain::chain::Chain,
     data_store::data_store::DataStore,
+    metrics::{metrics, to_labels, BROADCAST_FAILURES, PENDING_SIGNATURES},
+    operator_registry::{
+        merge_operator_liquidity, retain_valid_operator_liquidity, OperatorLiquidity,
+        OperatorRegistry, SelectionPolicy,
+    },
+    refund_watch::{PegInChainState, RefundWatchEvent, RefundWatcher},
+    wallet::{
+        descriptor::WalletDescriptor,
+        wallet::{FundingWallet, UtxoFilter},
+    },
 };
 
 const ESPLORA_URL: &str = "https://mutinynet.com/api";
@@ -36,8 +67,12 @@ pub type UtxoSet = HashMap<OutPoint, Height>;
 #[derive(Serialize, Deserialize, Eq, PartialEq)]
 pub struct BitVMClientPublicData {
     pub version: u32,
+    #[serde(default)]
     pub peg_in_graphs: Vec<PegInGraph>,
+    #[serde(default)]
     pub peg_out_graphs: Vec<PegOutGraph>,
+    #[serde(default)]
+    pub operator_liquidity: Vec<OperatorLiquidity>,
 }
 
 #[derive(Serialize, Deserialize, Eq, PartialEq)]
@@ -54,6 +89,7 @@ pub struct BitVMClient {
     operator_context: Option<OperatorContext>,
     verifier_context: Option<VerifierContext>,
     withdrawer_context: Option<WithdrawerContext>,
+    n_of_n_public_keys: Vec<PublicKey>,
 
     data_store: DataStore,
     data: BitVMClientPublicData,
@@ -120,6 +156,7 @@ impl BitVMClient {
             version: 1,
             peg_in_graphs: vec![],
             peg_out_graphs: vec![],
+            operator_liquidity: vec![],
         };
 
         let data_store = DataStore::new();
@@ -135,6 +172,7 @@ impl BitVMClient {
             operator_context,
             verifier_context,
             withdrawer_context,
+            n_of_n_public_keys: n_of_n_public_keys.clone(),
 
             data_store,
             data,
@@ -147,6 +185,40 @@ impl BitVMClient {
 
     pub fn get_data(&self) -> &BitVMClientPublicData { return &self.data; }
 
+    /// Updates the pending signatures gauge with the nonces and partial signatures the n-of-n
+    /// signers still owe to the graphs in the data.
+    pub fn update_pending_signature_metrics(&self) {
+        let pending = [
+            (
+                "peg_in",
+                count_pending_signatures(&self.data.peg_in_graphs, &self.n_of_n_public_keys),
+            ),
+            (
+                "peg_out",
+                count_pending_signatures(&self.data.peg_out_graphs, &self.n_of_n_public_keys),
+            ),
+        ];
+
+        metrics().set_gauge_values(
+            PENDING_SIGNATURES,
+            pending
+                .iter()
+                .flat_map(|(graph_type, pending)| {
+                    [
+                        ("nonces", pending.nonces),
+                        ("partial_signatures", pending.partial_signatures),
+                    ]
+                    .map(|(stage, count)| {
+                        (
+                            to_labels(&[("graph_type", graph_type), ("stage", stage)]),
+                            count as f64,
+                        )
+                    })
+                })
+                .collect(),
+        );
+    }
+
     pub async fn sync(&mut self) { self.read().await; }
 
     pub async fn flush(&mut self) { self.save().await; }
@@ -180,10 +252,14 @@ impl BitVMClient {
                 )
                 .await;
                 if latest_file.is_some() && latest_file_name.is_some() {
+                    let serialization_format = DataStore::get_file_serialization_format(
+                        latest_file_name.as_ref().unwrap(),
+                    )
+                    .unwrap_or(SerializationFormat::Json);
                     Self::save_local_public_file(
                         &self.file_path,
                         latest_file_name.as_ref().unwrap(),
-                        &serialize(&latest_file.as_ref().unwrap()),
+                        &Self::serialize_data(latest_file.as_ref().unwrap(), serialization_format),
                     );
                     self.fetched_file_name = latest_file_name;
 
@@ -192,17 +268,20 @@ impl BitVMClient {
                         Self::process_files_by_timestamp(self, latest_file_names, TEN_MINUTES)
                             .await;
                     match result {
-                        Ok(_) => (), // println!("Ok"),
-                        Err(err) => println!("Error: {}", err),
+                        Ok(_) => (),
+                        Err(err) => error!("Failed to process data files: {}", err),
                     }
 
                     self.merge_data(latest_file.unwrap()); // merge the latest data at the end
                 }
             } else {
-                println!("Up to date. No need to read data from the server.");
+                info!("Up to date. No need to read data from the server.");
             }
         } else {
-            println!("Error: {}", latest_file_names_result.unwrap_err());
+            error!(
+                "Failed to list data files: {}",
+                latest_file_names_result.unwrap_err()
+            );
         }
     }
 
@@ -304,18 +383,20 @@ impl BitVMClient {
                     .fetch_data_by_key(file_name, Some(&self.file_path))
                     .await; // TODO: use `fetch_by_key()` function
                 if result.is_ok() && result.as_ref().unwrap().is_some() {
-                    let data =
-                        try_deserialize::<BitVMClientPublicData>(&(result.unwrap()).unwrap());
+                    let data = Self::try_deserialize_data(file_name, &(result.unwrap()).unwrap());
                     if data.is_ok() && Self::validate_data(&data.as_ref().unwrap()) {
                         // merge the file if the data is valid
-                        println!("Merging {} data...", { file_name });
+                        info!(file_name, "Merging data");
                         self.merge_data(data.unwrap());
                         if latest_valid_file_name.is_none() {
                             latest_valid_file_name = Some(file_name.clone());
                         }
+                    } else if let Err(err) = data {
+                        // skip the file if the data can not be read, e.g. written by a newer client
+                        warn!(file_name, error = %err, "Unreadable file, skipping");
                     } else {
                         // skip the file if the data is invalid
-                        println!("Invalid file {}, Skipping...", file_name);
+                        warn!(file_name, "Invalid file, skipping");
                     }
                 }
             }
@@ -340,15 +421,12 @@ impl BitVMClient {
                     Self::fetch_by_key(data_store, &file_name, file_path).await;
                 if latest_data.is_some() && Self::validate_data(&latest_data.as_ref().unwrap()) {
                     // data is valid
-                    println!(
-                        "Fetched valid file: {} (size: {})",
-                        file_name, latest_data_len
-                    );
+                    info!(file_name, size = latest_data_len, "Fetched valid file");
                     latest_valid_file = latest_data;
                     latest_valid_file_name = Some(file_name);
                     break;
                 } else {
-                    println!("Invalid file: {}", file_name); // TODO: can be removed
+                    warn!(file_name, "Invalid file");
                 }
                 // for invalid data try another file
             }
@@ -364,10 +442,10 @@ impl BitVMClient {
     ) -> (Option<BitVMClientPublicData>, usize) {
         let result = data_store.fetch_data_by_key(key, file_path).await;
         if result.is_ok() {
-            if let Some(json) = result.unwrap() {
-                let data = try_deserialize::<BitVMClientPublicData>(&json);
-                if data.is_ok() {
-                    return (Some(data.unwrap()), json.len());
+            if let Some(contents) = result.unwrap() {
+                match Self::try_deserialize_data(key, &contents) {
+                    Ok(data) => return (Some(data), contents.len()),
+                    Err(err) => warn!(file_name = key, error = %err, "Unreadable file"),
                 }
             }
         }
@@ -394,35 +472,85 @@ impl BitVMClient {
         // push data
         self.data.version += 1;
 
-        let json = serialize(&self.data);
+        let contents = Self::serialize_data(&self.data, self.data_store.serialization_format());
         let result = self
             .data_store
-            .write_data(json.clone(), Some(&self.file_path))
+            .write_data(contents.clone(), Some(&self.file_path))
             .await;
         match result {
             Ok(key) => {
-                println!("Pushed new file: {} (size: {})", key, json.len());
-                Self::save_local_public_file(&self.file_path, &key, &json);
+                info!(key, size = contents.len(), "Pushed new file");
+                Self::save_local_public_file(&self.file_path, &key, &contents);
             }
-            Err(err) => println!("Failed to push: {}", err),
+            Err(err) => error!("Failed to push: {}", err),
+        }
+    }
+
+    fn serialize_data(
+        data: &BitVMClientPublicData,
+        serialization_format: SerializationFormat,
+    ) -> Vec<u8> {
+        match serialization_format {
+            SerializationFormat::Json => serialize(data).into_bytes(),
+            SerializationFormat::Binary => binary::serialize(data),
+        }
+    }
+
+    /// Deserializes public data in the format of the file it was read from, migrating graphs
+    /// serialized by older versions of the client.
+    fn try_deserialize_data(
+        file_name: &String,
+        contents: &[u8],
+    ) -> Result<BitVMClientPublicData, String> {
+        let mut data = match DataStore::get_file_serialization_format(file_name)? {
+            SerializationFormat::Binary => binary::try_deserialize_value(contents)?,
+            SerializationFormat::Json => match std::str::from_utf8(contents) {
+                Ok(json) => try_deserialize::<Value>(json)?,
+                Err(err) => return Err(format!("Failed to parse json: {}", err)),
+            },
+        };
+        Self::check_data_version(&data)?;
+        migrate_client_data(&mut data)?;
+
+        let mut data = try_deserialize_value::<BitVMClientPublicData>(data)?;
+        retain_valid_operator_liquidity(&mut data.operator_liquidity);
+
+        Ok(data)
+    }
+
+    // Every saved file has been written with a version of at least 1, see `save()`.
+    fn check_data_version(data: &Value) -> Result<(), String> {
+        match data.get("version").and_then(Value::as_u64) {
+            Some(version) if version > 0 && version <= u32::MAX as u64 => Ok(()),
+            Some(version) => Err(format!("Invalid data version: {}", version)),
+            None => Err(String::from("Missing or invalid data version")),
         }
     }
 
     pub fn validate_data(data: &BitVMClientPublicData) -> bool {
         for peg_in_graph in data.peg_in_graphs.iter() {
             if !peg_in_graph.validate() {
-                println!(
-                    "Encountered invalid peg in graph (Graph id: {})",
-                    peg_in_graph.id()
-                );
+                warn!(graph_id = %peg_in_graph.id(), "Encountered invalid peg in graph");
                 return false;
             }
         }
         for peg_out_graph in data.peg_out_graphs.iter() {
             if !peg_out_graph.validate() {
-                println!(
-                    "Encountered invalid peg out graph (Graph id: {})",
-                    peg_out_graph.id()
+                warn!(graph_id = %peg_out_graph.id(), "Encountered invalid peg out graph");
+                return false;
+            }
+
+            let timelocks_differ = data
+                .peg_in_graphs
+                .iter()
+                .find(|&peg_in_graph| peg_in_graph.id().eq(&peg_out_graph.peg_in_graph_id))
+                .map(|peg_in_graph| {
+                    peg_in_graph.timelock_config() != peg_out_graph.timelock_config()
+                });
+            if timelocks_differ == Some(true) {
+                warn!(
+                    graph_id = %peg_out_graph.id(),
+                    "Encountered peg out graph with timelocks differing from its peg in graph"
                 );
                 return false;
             }
@@ -478,6 +606,9 @@ impl BitVMClient {
         for graph in peg_out_graphs_to_add.into_iter() {
             self.data.peg_out_graphs.push(graph.clone());
         }
+
+        // operator liquidity
+        merge_operator_liquidity(&mut self.data.operator_liquidity, &data.operator_liquidity);
     }
 
     // fn process(&self) {
@@ -515,6 +646,9 @@ impl BitVMClient {
         if self.verifier_context.is_some() {
             self.verifier_status().await;
         }
+        if self.withdrawer_context.is_some() {
+            self.withdrawer_status().await;
+        }
     }
 
     async fn depositor_status(&self) {
@@ -530,7 +664,7 @@ impl BitVMClient {
         for peg_in_graph in self.data.peg_in_graphs.iter() {
             if peg_in_graph.depositor_public_key.eq(depositor_public_key) {
                 let status = peg_in_graph.depositor_status(&self.esplora).await;
-                println!("Graph id: {} status: {}\n", peg_in_graph.id(), status);
+                info!(graph_id = %peg_in_graph.id(), %status, "Depositor status");
             }
         }
     }
@@ -549,15 +683,12 @@ impl BitVMClient {
         for peg_in_graph in self.data.peg_in_graphs.iter() {
             let peg_out_graph_id = peg_out_generate_id(peg_in_graph, operator_public_key);
             if !peg_out_graphs_by_id.contains_key(&peg_out_graph_id) {
-                println!(
-                    "Graph id: {} status: {}\n",
-                    peg_in_graph.id(),
-                    "Missing peg out graph" // TODO update this to ask the operator to create a new peg out graph
-                );
+                // TODO update this to ask the operator to create a new peg out graph
+                warn!(graph_id = %peg_in_graph.id(), "Missing peg out graph");
             } else {
                 let peg_out_graph = peg_out_graphs_by_id.get(&peg_out_graph_id).unwrap();
                 let status = peg_out_graph.operator_status(&self.esplora).await;
-                println!("Graph id: {} status: {}\n", peg_out_graph.id(), status);
+                info!(graph_id = %peg_out_graph.id(), %status, "Operator status");
             }
         }
     }
@@ -569,17 +700,167 @@ impl BitVMClient {
 
         for peg_out_graph in self.data.peg_out_graphs.iter() {
             let status = peg_out_graph.verifier_status(&self.esplora).await;
-            println!("Graph id: {} status: {}\n", peg_out_graph.id(), status);
+            info!(graph_id = %peg_out_graph.id(), %status, "Verifier status");
+        }
+    }
+
+    async fn withdrawer_status(&self) {
+        for (peg_out_graph_id, status) in self.get_withdrawer_peg_out_statuses().await {
+            info!(graph_id = peg_out_graph_id, %status, "Withdrawer status");
+        }
+    }
+
+    /// Status of every peg-out requested by this withdrawer, by peg-out graph id.
+    pub async fn get_withdrawer_peg_out_statuses(&self) -> Vec<(String, PegOutWithdrawerStatus)> {
+        if self.withdrawer_context.is_none() {
+            panic!("Withdrawer context must be initialized");
+        }
+
+        let withdrawer_public_key = &self
+            .withdrawer_context
+            .as_ref()
+            .unwrap()
+            .withdrawer_public_key;
+        let mut statuses = vec![];
+        for peg_out_graph in self.data.peg_out_graphs.iter() {
+            if peg_out_graph.withdrawer_public_key() == Some(withdrawer_public_key) {
+                let status = peg_out_graph.withdrawer_status(&self.esplora).await;
+                statuses.push((peg_out_graph.id().clone(), status));
+            }
+        }
+
+        statuses
+    }
+
+    /// Operators whose peg-out graph for the peg-in graph is presigned and can still be
+    /// requested.
+    pub fn get_available_peg_out_operators(&self, peg_in_graph_id: &str) -> Vec<PublicKey> {
+        self.data
+            .peg_out_graphs
+            .iter()
+            .filter(|peg_out_graph| {
+                peg_out_graph.peg_in_graph_id == peg_in_graph_id
+                    && peg_out_graph.is_presigned()
+                    && peg_out_graph.withdrawer_public_key().is_none()
+            })
+            .map(|peg_out_graph| *peg_out_graph.operator_public_key())
+            .collect()
+    }
+
+    /// Publishes the total value this operator is willing to front for peg-outs, replacing its
+    /// previous declaration.
+    pub fn declare_operator_liquidity(&mut self, liquidity: Amount) {
+        if self.operator_context.is_none() {
+            panic!("Operator context must be initialized");
+        }
+
+        let context = self.operator_context.as_ref().unwrap();
+        let sequence = self
+            .data
+            .operator_liquidity
+            .iter()
+            .filter(|declaration| declaration.operator_public_key == context.operator_public_key)
+            .map(|declaration| declaration.sequence + 1)
+            .max()
+            .unwrap_or(0);
+        let declaration = OperatorLiquidity::new(context, liquidity, sequence);
+        merge_operator_liquidity(&mut self.data.operator_liquidity, &[declaration]);
+    }
+
+    /// Builds the registry of all operators with their graphs and liquidity. Requested peg-outs
+    /// count as committed liquidity until their operator's take 1 or take 2 transaction, or a
+    /// transaction ending the operator's claim, is confirmed.
+    pub async fn get_operator_registry(&self) -> OperatorRegistry {
+        let mut settled_graph_ids = HashSet::new();
+        for peg_out_graph in self.data.peg_out_graphs.iter() {
+            if peg_out_graph.withdrawer_public_key().is_some() {
+                let status = peg_out_graph.operator_status(&self.esplora).await;
+                if matches!(
+                    status,
+                    PegOutOperatorStatus::PegOutComplete | PegOutOperatorStatus::PegOutFailed
+                ) {
+                    settled_graph_ids.insert(peg_out_graph.id().clone());
+                }
+            }
         }
+
+        OperatorRegistry::new(
+            &self.data.peg_in_graphs,
+            &self.data.peg_out_graphs,
+            &self.data.operator_liquidity,
+            &settled_graph_ids,
+        )
+    }
+
+    /// Requests a peg-out from the operator chosen by `policy` among those with enough available
+    /// liquidity. Returns the id of the peg-out graph.
+    pub async fn request_peg_out_from_selected_operator(
+        &mut self,
+        peg_in_graph_id: &str,
+        evm_address: &str,
+        policy: SelectionPolicy,
+    ) -> Result<String, String> {
+        let registry = self.get_operator_registry().await;
+        let (operator, _) = registry.select_operator(peg_in_graph_id, policy)?;
+        let operator_public_key = operator.operator_public_key;
+
+        self.request_peg_out(peg_in_graph_id, &operator_public_key, evm_address)
     }
 
-    pub async fn create_peg_in_graph(&mut self, input: Input, evm_address: &str) -> String {
+    /// Requests a peg-out to this withdrawer from the peg-out graph of `operator_public_key` for
+    /// the peg-in graph. Returns the id of the peg-out graph.
+    pub fn request_peg_out(
+        &mut self,
+        peg_in_graph_id: &str,
+        operator_public_key: &PublicKey,
+        evm_address: &str,
+    ) -> Result<String, String> {
+        let context = self
+            .withdrawer_context
+            .as_ref()
+            .ok_or("Withdrawer context must be initialized")?;
+
+        let peg_in_graph = self
+            .data
+            .peg_in_graphs
+            .iter()
+            .find(|peg_in_graph| peg_in_graph.id().eq(peg_in_graph_id))
+            .ok_or(format!("Invalid graph id: {}", peg_in_graph_id))?;
+        let peg_out_graph_id = peg_out_generate_id(peg_in_graph, operator_public_key);
+
+        let peg_out_graph = self
+            .data
+            .peg_out_graphs
+            .iter_mut()
+            .find(|peg_out_graph| peg_out_graph.id().eq(&peg_out_graph_id))
+            .ok_or(format!(
+                "Operator {} has no peg-out graph for peg-in graph {}",
+                operator_public_key, peg_in_graph_id
+            ))?;
+        peg_out_graph.request_peg_out(context, evm_address)?;
+
+        Ok(peg_out_graph_id)
+    }
+
+    pub async fn create_peg_in_graph(
+        &mut self,
+        input: Input,
+        evm_address: &str,
+        timelock_config: &TimelockConfig,
+    ) -> String {
         if self.depositor_context.is_none() {
             panic!("Depositor context must be initialized");
         }
+        if let Err(err) = timelock_config.validate() {
+            panic!("Invalid timelock config: {}", err);
+        }
 
-        let peg_in_graph =
-            PegInGraph::new(self.depositor_context.as_ref().unwrap(), input, evm_address);
+        let peg_in_graph = PegInGraph::new(
+            self.depositor_context.as_ref().unwrap(),
+            input,
+            evm_address,
+            timelock_config,
+        );
 
         let peg_in_graph_id = peg_in_generate_id(&peg_in_graph.peg_in_deposit_transaction);
 
@@ -597,6 +878,41 @@ impl BitVMClient {
         peg_in_graph_id
     }
 
+    /// Creates a peg-in graph for a deposit of `amount`, funding the deposit input from the
+    /// depositor's wallet. The wallet defaults to `wsh(pk(KEY))` of the depositor key, whose UTXO
+    /// is used directly if it already holds exactly `amount`. Otherwise the funding transaction
+    /// is broadcast before the graph is created. UTXOs already spent by graphs in the client data
+    /// are never used, unconfirmed ones only with `include_unconfirmed`.
+    pub async fn create_peg_in_graph_with_funding(
+        &mut self,
+        amount: Amount,
+        evm_address: &str,
+        timelock_config: &TimelockConfig,
+        descriptor: Option<WalletDescriptor>,
+        fee_rate_sat_per_vb: u64,
+        include_unconfirmed: bool,
+    ) -> Result<String, String> {
+        let context = self
+            .depositor_context
+            .as_ref()
+            .ok_or("Depositor context must be initialized")?;
+        let input = self
+            .fund_graph_input(
+                context,
+                &context.depositor_keypair,
+                &context.depositor_public_key,
+                descriptor,
+                amount,
+                fee_rate_sat_per_vb,
+                include_unconfirmed,
+            )
+            .await?;
+
+        Ok(self
+            .create_peg_in_graph(input, evm_address, timelock_config)
+            .await)
+    }
+
     pub async fn broadcast_peg_in_deposit(&mut self, peg_in_graph_id: &str) {
         let peg_in_graph = self
             .data
@@ -623,6 +939,107 @@ impl BitVMClient {
         peg_in_graph.unwrap().refund(&self.esplora).await
     }
 
+    /// Checks the chain once for the depositor's peg-in graphs, or only for `peg_in_graph_id`,
+    /// and logs the events of `watcher`, unlock warnings at warn level. Refunds requested by the
+    /// watcher are broadcast right away; a failed broadcast is retried on the next call.
+    pub async fn watch_peg_in_refunds(
+        &self,
+        watcher: &mut RefundWatcher,
+        peg_in_graph_id: Option<&str>,
+    ) -> Result<(), String> {
+        let context = self
+            .depositor_context
+            .as_ref()
+            .ok_or("Depositor context must be initialized")?;
+        let tip_height = self
+            .esplora
+            .get_height()
+            .await
+            .map_err(|err| format!("Failed to fetch blockchain height: {}", err))?;
+
+        for peg_in_graph in self.data.peg_in_graphs.iter().filter(|peg_in_graph| {
+            peg_in_graph.depositor_public_key == context.depositor_public_key
+                && peg_in_graph_id.map_or(true, |graph_id| peg_in_graph.id().eq(graph_id))
+        }) {
+            let state = self.peg_in_chain_state(peg_in_graph, tip_height).await?;
+            for event in watcher.observe(peg_in_graph, &state) {
+                match event {
+                    RefundWatchEvent::UnlockApproaching {
+                        unlock_height,
+                        blocks_left,
+                        presigned,
+                    } => warn!(
+                        graph_id = %peg_in_graph.id(),
+                        unlock_height,
+                        blocks_left,
+                        presigned,
+                        "{}",
+                        event
+                    ),
+                    _ => info!(graph_id = %peg_in_graph.id(), "{}", event),
+                }
+                if event != RefundWatchEvent::BroadcastRefund {
+                    continue;
+                }
+
+                let refund_tx = peg_in_graph.peg_in_refund_transaction_ref().finalize();
+                match self.esplora.broadcast(&refund_tx).await {
+                    Ok(_) => {
+                        watcher.refund_broadcast(peg_in_graph.id());
+                        info!(
+                            graph_id = %peg_in_graph.id(),
+                            txid = %refund_tx.compute_txid(),
+                            "Broadcast refund transaction"
+                        );
+                    }
+                    Err(err) => {
+                        metrics().increment_counter(
+                            BROADCAST_FAILURES,
+                            &[("transaction", "peg_in_refund")],
+                        );
+                        error!(graph_id = %peg_in_graph.id(), "Failed to broadcast refund: {}", err)
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn peg_in_chain_state(
+        &self,
+        peg_in_graph: &PegInGraph,
+        tip_height: u32,
+    ) -> Result<PegInChainState, String> {
+        let txids = [
+            peg_in_graph.peg_in_deposit_transaction.tx().compute_txid(),
+            peg_in_graph
+                .peg_in_confirm_transaction_ref()
+                .tx()
+                .compute_txid(),
+            peg_in_graph
+                .peg_in_refund_transaction_ref()
+                .tx()
+                .compute_txid(),
+        ];
+        let mut statuses = vec![];
+        for txid in txids {
+            statuses.push(
+                self.esplora
+                    .get_tx_status(&txid)
+                    .await
+                    .map_err(|err| format!("Failed to get status of tx {}: {}", txid, err))?,
+            );
+        }
+
+        Ok(PegInChainState {
+            deposit_block_height: statuses[0].block_height.filter(|_| statuses[0].confirmed),
+            confirm_confirmed: statuses[1].confirmed,
+            refund_confirmed: statuses[2].confirmed,
+            tip_height,
+        })
+    }
+
     pub async fn broadcast_peg_in_confirm(&mut self, peg_in_graph_id: &str) {
         let peg_in_graph = self
             .data
@@ -676,6 +1093,116 @@ impl BitVMClient {
         peg_out_graph_id
     }
 
+    /// Creates the operator's peg-out graph, funding the kick-off input of `amount` from the
+    /// operator's wallet like `create_peg_in_graph_with_funding`.
+    pub async fn create_peg_out_graph_with_funding(
+        &mut self,
+        peg_in_graph_id: &str,
+        amount: Amount,
+        descriptor: Option<WalletDescriptor>,
+        fee_rate_sat_per_vb: u64,
+        include_unconfirmed: bool,
+    ) -> Result<String, String> {
+        let context = self
+            .operator_context
+            .as_ref()
+            .ok_or("Operator context must be initialized")?;
+
+        // Checked before funding, so that no funding transaction is broadcast for a graph that
+        // `create_peg_out_graph` would refuse to create
+        let peg_in_graph = self
+            .data
+            .peg_in_graphs
+            .iter()
+            .find(|&peg_in_graph| peg_in_graph.id().eq(peg_in_graph_id))
+            .ok_or("Invalid graph id")?;
+        let peg_out_graph_id = peg_out_generate_id(peg_in_graph, &context.operator_public_key);
+        if self
+            .data
+            .peg_out_graphs
+            .iter()
+            .any(|peg_out_graph| peg_out_graph.id().eq(&peg_out_graph_id))
+        {
+            return Err(String::from("Peg out graph already exists"));
+        }
+
+        let input = self
+            .fund_graph_input(
+                context,
+                &context.operator_keypair,
+                &context.operator_public_key,
+                descriptor,
+                amount,
+                fee_rate_sat_per_vb,
+                include_unconfirmed,
+            )
+            .await?;
+
+        Ok(self.create_peg_out_graph(peg_in_graph_id, input).await)
+    }
+
+    // Provides the pay-to-pubkey output of `public_key` the depositor and operator graph inputs
+    // spend, broadcasting the funding transaction if one is needed.
+    async fn fund_graph_input(
+        &self,
+        context: &dyn BaseContext,
+        keypair: &Keypair,
+        public_key: &PublicKey,
+        descriptor: Option<WalletDescriptor>,
+        amount: Amount,
+        fee_rate_sat_per_vb: u64,
+        include_unconfirmed: bool,
+    ) -> Result<Input, String> {
+        let filter = UtxoFilter {
+            reserved_outpoints: self.graph_external_outpoints(),
+            include_unconfirmed,
+        };
+        let wallet = FundingWallet::new(
+            descriptor.unwrap_or(WalletDescriptor::for_graph_inputs(public_key)),
+            keypair,
+        )?;
+        let script_pubkey =
+            generate_pay_to_pubkey_script_address(context.network(), public_key).script_pubkey();
+        let funded_input = wallet
+            .fund_input(
+                context,
+                &self.esplora,
+                &filter,
+                &script_pubkey,
+                amount,
+                fee_rate_sat_per_vb,
+            )
+            .await?;
+
+        if let Some(funding_transaction) = funded_input.funding_transaction {
+            let txid = funding_transaction.compute_txid();
+            self.esplora
+                .broadcast(&funding_transaction)
+                .await
+                .map_err(|err| {
+                    format!("Failed to broadcast funding transaction {}: {}", txid, err)
+                })?;
+            info!(%txid, "Broadcast funding transaction");
+        }
+
+        Ok(funded_input.input)
+    }
+
+    // Outpoints spent by the graphs in the client data that the graphs do not create themselves.
+    fn graph_external_outpoints(&self) -> HashSet<OutPoint> {
+        self.data
+            .peg_in_graphs
+            .iter()
+            .flat_map(PegInGraph::external_outpoints)
+            .chain(
+                self.data
+                    .peg_out_graphs
+                    .iter()
+                    .flat_map(PegOutGraph::external_outpoints),
+            )
+            .collect()
+    }
+
     pub async fn broadcast_kick_off_1(&mut self, peg_out_graph_id: &str) {
         let peg_out_graph = self
             .data
@@ -897,8 +1424,45 @@ impl BitVMClient {
         peg_out_graph.unwrap().take_2(&self.esplora).await;
     }
 
+    /// Pays out the peg-out requested on the graph from the operator's `input`, once the
+    /// withdrawer's burn shows up in the peg-out events of `chain`.
+    pub async fn broadcast_peg_out(&mut self, peg_out_graph_id: &str, input: Input, chain: &Chain) {
+        if self.operator_context.is_none() {
+            panic!("Operator context must be initialized");
+        }
+        let peg_out_events = match chain.get_peg_out_init().await {
+            Ok(peg_out_events) => peg_out_events,
+            Err(err) => panic!("Failed to fetch peg-out events: {}", err),
+        };
+
+        let peg_out_graph = self
+            .data
+            .peg_out_graphs
+            .iter_mut()
+            .find(|peg_out_graph| peg_out_graph.id().eq(peg_out_graph_id));
+        if peg_out_graph.is_none() {
+            panic!("Invalid graph id");
+        }
+
+        peg_out_graph
+            .unwrap()
+            .peg_out(
+                &self.esplora,
+                self.operator_context.as_ref().unwrap(),
+                input,
+                &peg_out_events,
+            )
+            .await;
+    }
+
     pub async fn get_initial_utxo(&self, address: Address, amount: Amount) -> Option<Utxo> {
-        let utxos = self.esplora.get_address_utxo(address).await.unwrap();
+        let utxos = match self.esplora.get_address_utxo(address.clone()).await {
+            Ok(utxos) => utxos,
+            Err(err) => {
+                warn!(%address, "Could not fetch UTXOs: {}", err);
+                return None;
+            }
+        };
         let possible_utxos = utxos
             .into_iter()
             .filter(|utxo| utxo.value == amount)
@@ -911,7 +1475,13 @@ impl BitVMClient {
     }
 
     pub async fn get_initial_utxos(&self, address: Address, amount: Amount) -> Option<Vec<Utxo>> {
-        let utxos = self.esplora.get_address_utxo(address).await.unwrap();
+        let utxos = match self.esplora.get_address_utxo(address.clone()).await {
+            Ok(utxos) => utxos,
+            Err(err) => {
+                warn!(%address, "Could not fetch UTXOs: {}", err);
+                return None;
+            }
+        };
         let possible_utxos = utxos
             .into_iter()
             .filter(|utxo| utxo.value == amount)
@@ -923,6 +1493,101 @@ impl BitVMClient {
         }
     }
 
+    /// Exports a graph transaction as a PSBT, fetching the previous transactions of its segwit v0
+    /// inputs. Keys of the spent scripts are exported with their origin in `key_origins`, or as
+    /// their own master key.
+    pub async fn export_psbt(
+        &self,
+        graph_id: &str,
+        transaction_name: &str,
+        key_origins: &HashMap<PublicKey, KeySource>,
+    ) -> Result<Psbt, String> {
+        if let Some(peg_in_graph) = self
+            .data
+            .peg_in_graphs
+            .iter()
+            .find(|peg_in_graph| peg_in_graph.id().eq(graph_id))
+        {
+            let previous_transactions = self
+                .fetch_transactions(&peg_in_graph.psbt_previous_txids(transaction_name)?)
+                .await?;
+            return peg_in_graph.export_psbt(transaction_name, &previous_transactions, key_origins);
+        }
+        if let Some(peg_out_graph) = self
+            .data
+            .peg_out_graphs
+            .iter()
+            .find(|peg_out_graph| peg_out_graph.id().eq(graph_id))
+        {
+            let previous_transactions = self
+                .fetch_transactions(&peg_out_graph.psbt_previous_txids(transaction_name)?)
+                .await?;
+            return peg_out_graph.export_psbt(
+                transaction_name,
+                &previous_transactions,
+                key_origins,
+            );
+        }
+
+        Err(format!("Invalid graph id: {}", graph_id))
+    }
+
+    async fn fetch_transactions(&self, txids: &[Txid]) -> Result<Vec<Transaction>, String> {
+        let mut transactions = vec![];
+        for txid in txids {
+            match self.esplora.get_tx(txid).await {
+                Ok(Some(transaction)) => transactions.push(transaction),
+                Ok(None) => return Err(format!("Transaction {} not found", txid)),
+                Err(err) => return Err(format!("Could not fetch transaction {}: {}", txid, err)),
+            }
+        }
+
+        Ok(transactions)
+    }
+
+    /// Adds the signatures of an externally signed PSBT to a graph transaction. The graph is left
+    /// unchanged if any witness fails script validation afterwards.
+    pub fn import_psbt(
+        &mut self,
+        graph_id: &str,
+        transaction_name: &str,
+        psbt: &Psbt,
+    ) -> Result<usize, String> {
+        if let Some(peg_in_graph) = self
+            .data
+            .peg_in_graphs
+            .iter_mut()
+            .find(|peg_in_graph| peg_in_graph.id().eq(graph_id))
+        {
+            let mut signed_graph = peg_in_graph.clone();
+            let signed_inputs = signed_graph.import_psbt(transaction_name, psbt)?;
+            let report = signed_graph.validate_scripts();
+            if !report.is_valid() {
+                return Err(format!("Rejecting invalid signatures\n{}", report));
+            }
+            *peg_in_graph = signed_graph;
+            return Ok(signed_inputs);
+        }
+        if let Some(peg_out_graph) = self
+            .data
+            .peg_out_graphs
+            .iter_mut()
+            .find(|peg_out_graph| peg_out_graph.id().eq(graph_id))
+        {
+            let mut signed_graph = peg_out_graph.clone();
+            let signed_inputs = signed_graph.import_psbt(transaction_name, psbt)?;
+            let report = signed_graph.validate_scripts();
+            if !report.is_valid() {
+                return Err(format!("Rejecting invalid signatures\n{}", report));
+            }
+            *peg_out_graph = signed_graph;
+            return Ok(signed_inputs);
+        }
+
+        Err(format!("Invalid graph id: {}", graph_id))
+    }
+
+    #[instrument(name = "graph", skip_all, fields(graph_id = peg_in_graph_id))]
     pub fn push_peg_in_nonces(&mut self, peg_in_graph_id: &str) {
         if self.verifier_context.is_none() {
             panic!("Can only be called by a verifier!");
@@ -950,6 +1615,7 @@ impl BitVMClient {
         Self::save_local_private_file(&self.file_path, &json);
     }
 
+    #[instrument(name = "graph", skip_all, fields(graph_id = peg_out_graph_id))]
     pub fn push_peg_out_nonces(&mut self, peg_out_graph_id: &str) {
         if self.verifier_context.is_none() {
             panic!("Can only be called by a verifier!");
@@ -1016,6 +1682,7 @@ impl BitVMClient {
             .extend(secret_nonces);
     }
 
+    #[instrument(name = "graph", skip_all, fields(graph_id = peg_in_graph_id))]
     pub fn pre_sign_peg_in(&mut self, peg_in_graph_id: &str) {
         if self.operator_context.is_none() && self.verifier_context.is_none() {
             panic!("Can only be called by an operator or a verifier!");
@@ -1030,13 +1697,22 @@ impl BitVMClient {
             panic!("Invalid graph id");
         }
 
-        peg_in_graph.unwrap().pre_sign(
+        let peg_in_graph = peg_in_graph.unwrap();
+        if !peg_in_graph.validate() {
+            panic!(
+                "Refusing to pre-sign invalid peg-in graph (Graph id: {})",
+                peg_in_graph_id
+            );
+        }
+
+        peg_in_graph.pre_sign(
             &self.verifier_context.as_ref().unwrap(),
             &self.private_data.secret_nonces
                 [&self.verifier_context.as_ref().unwrap().verifier_public_key][peg_in_graph_id],
         );
     }
 
+    #[instrument(name = "graph", skip_all, fields(graph_id = peg_out_graph_id))]
     pub fn pre_sign_peg_out(&mut self, peg_out_graph_id: &str) {
         if self.operator_context.is_none() && self.verifier_context.is_none() {
             panic!("Can only be called by an operator or a verifier!");
@@ -1051,19 +1727,127 @@ impl BitVMClient {
             panic!("Invalid graph id");
         }
 
-        peg_out_graph.unwrap().pre_sign(
+        let peg_out_graph = peg_out_graph.unwrap();
+        if !peg_out_graph.validate() {
+            panic!(
+                "Refusing to pre-sign invalid peg-out graph (Graph id: {})",
+                peg_out_graph_id
+            );
+        }
+
+        peg_out_graph.pre_sign(
             &self.verifier_context.as_ref().unwrap(),
             &self.private_data.secret_nonces
                 [&self.verifier_context.as_ref().unwrap().verifier_public_key][peg_out_graph_id],
         );
     }
 
+    /// Pushes this verifier's nonces for every valid graph that does not have them yet. Graphs
+    /// are processed in parallel, `progress` is called after each graph. Returns the ids of the
+    /// graphs.
+    pub fn push_pending_nonces(
+        &mut self,
+        progress: &(dyn Fn(&BatchProgress) + Sync),
+    ) -> Vec<String> {
+        if self.verifier_context.is_none() {
+            panic!("Can only be called by a verifier!");
+        }
+        let context = self.verifier_context.as_ref().unwrap();
+
+        let mut peg_in_graphs = self
+            .data
+            .peg_in_graphs
+            .iter_mut()
+            .filter(|graph| needs_nonces(*graph, &context.verifier_public_key))
+            .filter(|graph| Self::is_valid_for_signing(graph.id(), graph.validate()))
+            .collect::<Vec<_>>();
+        let mut peg_out_graphs = self
+            .data
+            .peg_out_graphs
+            .iter_mut()
+            .filter(|graph| needs_nonces(*graph, &context.verifier_public_key))
+            .filter(|graph| Self::is_valid_for_signing(graph.id(), graph.validate()))
+            .collect::<Vec<_>>();
+
+        let total = peg_in_graphs.len() + peg_out_graphs.len();
+        let mut secret_nonces =
+            push_nonces_batch(&mut peg_in_graphs, context, &|p| progress(&p.of(0, total)));
+        let offset = peg_in_graphs.len();
+        secret_nonces.extend(push_nonces_batch(&mut peg_out_graphs, context, &|p| {
+            progress(&p.of(offset, total))
+        }));
+
+        let graph_ids = secret_nonces.keys().cloned().collect::<Vec<_>>();
+        for (graph_id, graph_secret_nonces) in secret_nonces {
+            self.merge_secret_nonces(&graph_id, graph_secret_nonces);
+        }
+        let json = serialize(&self.private_data);
+        Self::save_local_private_file(&self.file_path, &json);
+
+        graph_ids
+    }
+
+    /// Pre-signs every valid graph that has the nonces of all verifiers but not yet this
+    /// verifier's partial signatures. Graphs are processed in parallel, `progress` is called after
+    /// each graph. Returns the ids of the pre-signed graphs.
+    pub fn pre_sign_pending_graphs(
+        &mut self,
+        progress: &(dyn Fn(&BatchProgress) + Sync),
+    ) -> Vec<String> {
+        if self.verifier_context.is_none() {
+            panic!("Can only be called by a verifier!");
+        }
+        let context = self.verifier_context.as_ref().unwrap();
+        let empty_secret_nonces = HashMap::new();
+        let secret_nonces = self
+            .private_data
+            .secret_nonces
+            .get(&context.verifier_public_key)
+            .unwrap_or(&empty_secret_nonces);
+
+        let mut peg_in_graphs = self
+            .data
+            .peg_in_graphs
+            .iter_mut()
+            .filter(|graph| is_ready_to_pre_sign(*graph, context))
+            .filter(|graph| Self::is_valid_for_signing(graph.id(), graph.validate()))
+            .collect::<Vec<_>>();
+        let mut peg_out_graphs = self
+            .data
+            .peg_out_graphs
+            .iter_mut()
+            .filter(|graph| is_ready_to_pre_sign(*graph, context))
+            .filter(|graph| Self::is_valid_for_signing(graph.id(), graph.validate()))
+            .collect::<Vec<_>>();
+
+        let total = peg_in_graphs.len() + peg_out_graphs.len();
+        let mut graph_ids = pre_sign_batch(&mut peg_in_graphs, context, secret_nonces, &|p| {
+            progress(&p.of(0, total))
+        });
+        let offset = peg_in_graphs.len();
+        graph_ids.extend(pre_sign_batch(
+            &mut peg_out_graphs,
+            context,
+            secret_nonces,
+            &|p| progress(&p.of(offset, total)),
+        ));
+
+        graph_ids
+    }
+
+    fn is_valid_for_signing(graph_id: &str, is_valid: bool) -> bool {
+        if !is_valid {
+            warn!(graph_id, "Skipping invalid graph");
+        }
+        is_valid
+    }
+
     fn get_private_data(file_path: &String) -> BitVMClientPrivateData {
         match Self::read_local_private_file(file_path) {
             Some(data) => try_deserialize::<BitVMClientPrivateData>(&data)
                 .expect("Could not deserialize private data"),
             None => {
-                println!("New private data will be generated.");
+                info!("New private data will be generated.");
                 BitVMClientPrivateData {
                     secret_nonces: HashMap::new(),
                 }
@@ -1071,25 +1855,25 @@ impl BitVMClient {
         }
     }
 
-    fn save_local_public_file(file_path: &String, key: &String, json: &String) {
+    fn save_local_public_file(file_path: &String, key: &String, contents: &[u8]) {
         Self::create_directories_if_non_existent(file_path);
-        println!("Saving public data in local file: {}...", key);
-        fs::write(format!("{file_path}/public/{key}"), json).expect("Unable to write a file");
+        debug!(key, "Saving public data in local file");
+        fs::write(format!("{file_path}/public/{key}"), contents).expect("Unable to write a file");
     }
 
     fn save_local_private_file(file_path: &String, json: &String) {
         Self::create_directories_if_non_existent(file_path);
-        println!("Saving private data in local file...");
+        debug!("Saving private data in local file");
         fs::write(format!("{file_path}/private/private_nonces.json"), json)
             .expect("Unable to write a file");
     }
 
     fn read_local_private_file(file_path: &String) -> Option<String> {
-        println!("Reading private data from local file...");
+        debug!("Reading private data from local file");
         match fs::read_to_string(format!("{file_path}/private/private_nonces.json")) {
             Ok(content) => Some(content),
             Err(e) => {
-                eprintln!("Could not read file {file_path} due to error: {e}");
+                warn!("Could not read file {file_path} due to error: {e}");
                 None
             }
         }
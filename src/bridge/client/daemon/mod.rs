@@ -0,0 +1,2 @@
+pub mod rpc;
+pub mod server;
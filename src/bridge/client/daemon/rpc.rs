@@ -0,0 +1,259 @@
+use bitcoin::OutPoint;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::super::{
+    super::graphs::{
+        peg_in::{PegInDepositorStatus, PegInOperatorStatus, PegInVerifierStatus},
+        peg_out::{PegOutDepositorStatus, PegOutOperatorStatus, PegOutVerifierStatus},
+    },
+    cli::commands::GraphType,
+};
+
+pub const JSON_RPC_VERSION: &str = "2.0";
+
+pub const PARSE_ERROR: i64 = -32700;
+pub const INVALID_REQUEST: i64 = -32600;
+pub const METHOD_NOT_FOUND: i64 = -32601;
+pub const INVALID_PARAMS: i64 = -32602;
+// Application errors, e.g. an unknown graph id or a failed broadcast
+pub const OPERATION_FAILED: i64 = -32000;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct RpcRequest {
+    pub jsonrpc: String,
+    #[serde(default)]
+    pub id: Value,
+    pub method: String,
+    #[serde(default)]
+    pub params: Value,
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Debug)]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+}
+
+impl RpcError {
+    pub fn new(code: i64, message: impl Into<String>) -> Self {
+        RpcError {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct RpcResponse {
+    pub jsonrpc: String,
+    pub id: Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<RpcError>,
+}
+
+impl RpcResponse {
+    pub fn result(id: Value, result: Value) -> Self {
+        RpcResponse {
+            jsonrpc: JSON_RPC_VERSION.to_string(),
+            id,
+            result: Some(result),
+            error: None,
+        }
+    }
+
+    pub fn error(id: Value, error: RpcError) -> Self {
+        RpcResponse {
+            jsonrpc: JSON_RPC_VERSION.to_string(),
+            id,
+            result: None,
+            error: Some(error),
+        }
+    }
+}
+
+/// Methods served by the daemon, in the `method` field of a request.
+pub mod methods {
+    pub const LIST_GRAPHS: &str = "list_graphs";
+    pub const GET_STATUS: &str = "get_status";
+    pub const SYNC: &str = "sync";
+    pub const PUSH_NONCES: &str = "push_nonces";
+    pub const PRE_SIGN: &str = "pre_sign";
+    pub const PUSH_PENDING_NONCES: &str = "push_pending_nonces";
+    pub const PRE_SIGN_PENDING: &str = "pre_sign_pending";
+    pub const BROADCAST: &str = "broadcast";
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Debug)]
+pub struct GraphParams {
+    pub graph_type: GraphType,
+    pub graph_id: String,
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Debug, Default)]
+pub struct StatusParams {
+    /// Only report the status of this graph, all graphs when omitted.
+    #[serde(default)]
+    pub graph_id: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Copy, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum BroadcastTransaction {
+    PegInDeposit,
+    PegInRefund,
+    PegInConfirm,
+    KickOff1,
+    StartTime,
+    StartTimeTimeout,
+    KickOff2,
+    KickOffTimeout,
+    Challenge,
+    Assert,
+    DisproveChain,
+    Disprove,
+    Take1,
+    Take2,
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Copy, Debug)]
+pub struct FundingInput {
+    pub outpoint: OutPoint,
+    /// Value of the output in satoshis.
+    pub amount: u64,
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Debug)]
+pub struct BroadcastParams {
+    pub graph_id: String,
+    pub transaction: BroadcastTransaction,
+    /// Address receiving the reward of timeout and disprove transactions, or the change of the
+    /// challenge crowdfunding inputs.
+    #[serde(default)]
+    pub reward_address: Option<String>,
+    /// Connector C leaf spent by the disprove transaction.
+    #[serde(default)]
+    pub script_index: Option<u32>,
+    /// Outputs crowdfunding the challenge transaction, paying to the daemon's key.
+    #[serde(default)]
+    pub crowdfunding_inputs: Vec<FundingInput>,
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Debug)]
+pub struct PegInGraphSummary {
+    pub id: String,
+    pub depositor_public_key: String,
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Debug)]
+pub struct PegOutGraphSummary {
+    pub id: String,
+    pub peg_in_graph_id: String,
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Debug)]
+pub struct ListGraphsResponse {
+    pub peg_in_graphs: Vec<PegInGraphSummary>,
+    pub peg_out_graphs: Vec<PegOutGraphSummary>,
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Copy, Debug)]
+#[serde(tag = "role", content = "status", rename_all = "snake_case")]
+pub enum GraphStatus {
+    PegInDepositor(PegInDepositorStatus),
+    PegInOperator(PegInOperatorStatus),
+    PegInVerifier(PegInVerifierStatus),
+    PegOutDepositor(PegOutDepositorStatus),
+    PegOutOperator(PegOutOperatorStatus),
+    PegOutVerifier(PegOutVerifierStatus),
+}
+
+impl GraphStatus {
+    pub fn description(&self) -> String {
+        match self {
+            GraphStatus::PegInDepositor(status) => status.to_string(),
+            GraphStatus::PegInOperator(status) => status.to_string(),
+            GraphStatus::PegInVerifier(status) => status.to_string(),
+            GraphStatus::PegOutDepositor(status) => status.to_string(),
+            GraphStatus::PegOutOperator(status) => status.to_string(),
+            GraphStatus::PegOutVerifier(status) => status.to_string(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Debug)]
+pub struct GraphStatusEntry {
+    pub graph_id: String,
+    #[serde(flatten)]
+    pub status: GraphStatus,
+    /// Human readable status, as printed by the `status` command.
+    pub description: String,
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Debug)]
+pub struct SyncResponse {
+    pub peg_in_graphs: usize,
+    pub peg_out_graphs: usize,
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Debug)]
+pub struct OperationResponse {
+    pub graph_id: String,
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Debug)]
+pub struct BatchOperationResponse {
+    pub graph_ids: Vec<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{
+        super::super::super::graphs::peg_out::PegOutVerifierStatus, BroadcastParams,
+        BroadcastTransaction, GraphStatus, GraphStatusEntry, RpcRequest,
+    };
+
+    #[test]
+    fn test_parse_broadcast_request() {
+        let request: RpcRequest = serde_json::from_value(json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "broadcast",
+            "params": {
+                "graph_id": "ABCD",
+                "transaction": "kick_off_timeout",
+                "reward_address": "tb1qw508d6qejxtdg4y5r3zarvary0c5xw7kxpjzsx"
+            }
+        }))
+        .unwrap();
+        let params: BroadcastParams = serde_json::from_value(request.params).unwrap();
+
+        assert_eq!(params.transaction, BroadcastTransaction::KickOffTimeout);
+        assert_eq!(params.script_index, None);
+        assert!(params.crowdfunding_inputs.is_empty());
+    }
+
+    #[test]
+    fn test_serialize_status_entry() {
+        let status = GraphStatus::PegOutVerifier(PegOutVerifierStatus::PegOutPresign);
+        let entry = GraphStatusEntry {
+            graph_id: String::from("ABCD"),
+            status,
+            description: status.description(),
+        };
+
+        assert_eq!(
+            serde_json::to_value(&entry).unwrap(),
+            json!({
+                "graph_id": "ABCD",
+                "role": "peg_out_verifier",
+                "status": "PegOutPresign",
+                "description": "Signatures required. Presign peg-out transactions?"
+            })
+        );
+    }
+}
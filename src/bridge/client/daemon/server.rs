@@ -0,0 +1,780 @@
+use bitcoin::{address::NetworkUnchecked, Address, Amount, ScriptBuf};
+use futures::FutureExt;
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+use std::{
+    any::Any, collections::BTreeMap, future::Future, net::SocketAddr, panic::AssertUnwindSafe,
+    time::Duration,
+};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+    time::{interval, timeout, MissedTickBehavior},
+};
+use tracing::{error, info};
+
+use super::{
+    super::{
+        super::{
+            graphs::{base::BaseGraph, peg_out::generate_id as peg_out_generate_id},
+            scripts::generate_pay_to_pubkey_script,
+            transactions::base::InputWithScript,
+        },
+        cli::{
+            commands::GraphType,
+            config::{BridgeConfig, ClientRole},
+        },
+        client::BitVMClient,
+        metrics::{metrics, to_labels, Labels, GRAPHS},
+    },
+    rpc::*,
+};
+
+pub const DEFAULT_LISTEN_ADDRESS: &str = "127.0.0.1:8331";
+
+const MAX_REQUEST_SIZE: usize = 1 << 20;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+const JSON_CONTENT_TYPE: &str = "application/json";
+const TEXT_CONTENT_TYPE: &str = "text/plain";
+const METRICS_CONTENT_TYPE: &str = "text/plain; version=0.0.4";
+
+enum HttpRequest {
+    /// `GET /metrics`, answered with the metrics in the Prometheus text format.
+    Metrics,
+    /// Body of a POST request, a JSON-RPC request.
+    JsonRpc(Vec<u8>),
+}
+
+/// Owns a `BitVMClient` and serves its operations over JSON-RPC 2.0 on HTTP POST requests, and
+/// its metrics on `GET /metrics`. Requests are handled one at a time, interleaved with the
+/// periodic sync, so that the client never has to be shared between tasks.
+pub struct Daemon {
+    config: BridgeConfig,
+    client: BitVMClient,
+    // Set when the client could not be rebuilt after a panic, see `recover`.
+    reload_error: Option<String>,
+}
+
+impl Daemon {
+    pub async fn new(config: BridgeConfig) -> Result<Self, String> {
+        let mut client = config.client(&config.configured_roles()).await?;
+        client.sync().await;
+
+        Ok(Daemon {
+            config,
+            client,
+            reload_error: None,
+        })
+    }
+
+    /// Serves requests until the client state can no longer be trusted, i.e. a panicked operation
+    /// left it in an unknown state and reloading it failed.
+    pub async fn run(
+        &mut self,
+        listen_address: SocketAddr,
+        sync_interval: Duration,
+    ) -> Result<(), String> {
+        let listener = TcpListener::bind(listen_address)
+            .await
+            .map_err(|err| format!("Could not listen on {}: {}", listen_address, err))?;
+        info!(%listen_address, "Bridge daemon listening");
+
+        let mut sync_timer = interval(sync_interval);
+        sync_timer.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            if let Some(err) = &self.reload_error {
+                return Err(format!("Failed to reload client state: {}", err));
+            }
+            tokio::select! {
+                _ = sync_timer.tick() => {
+                    match self.sync().await {
+                        Ok(_) => self.update_metrics().await,
+                        Err(err) => error!("Sync failed: {}", err.message),
+                    }
+                }
+                accepted = listener.accept() => match accepted {
+                    Ok((stream, _)) => {
+                        if let Err(err) = self.handle_connection(stream).await {
+                            error!("Failed to handle request: {}", err);
+                        }
+                    }
+                    Err(err) => error!("Failed to accept connection: {}", err),
+                },
+            }
+        }
+    }
+
+    async fn handle_connection(&mut self, mut stream: TcpStream) -> Result<(), String> {
+        let body = match timeout(REQUEST_TIMEOUT, read_http_request(&mut stream)).await {
+            Ok(Ok(HttpRequest::JsonRpc(body))) => body,
+            Ok(Ok(HttpRequest::Metrics)) => {
+                let response = metrics().render();
+                return write_http_response(
+                    &mut stream,
+                    "200 OK",
+                    METRICS_CONTENT_TYPE,
+                    response.as_bytes(),
+                )
+                .await;
+            }
+            Ok(Err(err)) => {
+                return write_http_response(
+                    &mut stream,
+                    "400 Bad Request",
+                    TEXT_CONTENT_TYPE,
+                    err.as_bytes(),
+                )
+                .await
+            }
+            Err(_) => return Err(String::from("Timed out reading request")),
+        };
+
+        let response = self.handle_request(&body).await;
+        let response = serde_json::to_vec(&response)
+            .map_err(|err| format!("Failed to serialize response: {}", err))?;
+        write_http_response(&mut stream, "200 OK", JSON_CONTENT_TYPE, &response).await
+    }
+
+    // Refreshes the gauges derived from the graphs, the other metrics are recorded as they occur.
+    async fn update_metrics(&self) {
+        let mut graph_counts: BTreeMap<Labels, f64> = BTreeMap::new();
+        for entry in self.get_status(None).await {
+            *graph_counts
+                .entry(graph_status_labels(&entry.status))
+                .or_default() += 1.0;
+        }
+        metrics().set_gauge_values(GRAPHS, graph_counts.into_iter().collect());
+        self.client.update_pending_signature_metrics();
+    }
+
+    pub async fn handle_request(&mut self, body: &[u8]) -> RpcResponse {
+        let request = match serde_json::from_slice::<Value>(body) {
+            Ok(request) => request,
+            Err(err) => {
+                return RpcResponse::error(Value::Null, RpcError::new(PARSE_ERROR, err.to_string()))
+            }
+        };
+        let id = request.get("id").cloned().unwrap_or(Value::Null);
+        let request = match serde_json::from_value::<RpcRequest>(request) {
+            Ok(request) if request.jsonrpc == JSON_RPC_VERSION => request,
+            Ok(_) => {
+                return RpcResponse::error(
+                    id,
+                    RpcError::new(INVALID_REQUEST, "Only JSON-RPC 2.0 is supported"),
+                )
+            }
+            Err(err) => {
+                return RpcResponse::error(id, RpcError::new(INVALID_REQUEST, err.to_string()))
+            }
+        };
+
+        if let Some(err) = &self.reload_error {
+            return RpcResponse::error(
+                request.id,
+                RpcError::new(
+                    OPERATION_FAILED,
+                    format!("Failed to reload client state: {}", err),
+                ),
+            );
+        }
+
+        match self.dispatch(&request.method, request.params).await {
+            Ok(result) => RpcResponse::result(request.id, result),
+            Err(err) => RpcResponse::error(request.id, err),
+        }
+    }
+
+    async fn dispatch(&mut self, method: &str, params: Value) -> Result<Value, RpcError> {
+        match method {
+            methods::LIST_GRAPHS => to_result(self.list_graphs()),
+            methods::GET_STATUS => {
+                let params = match params {
+                    Value::Null => StatusParams::default(),
+                    params => parse_params::<StatusParams>(params)?,
+                };
+                to_result(self.get_status(params.graph_id.as_deref()).await)
+            }
+            methods::SYNC => to_result(self.sync().await?),
+            methods::PUSH_NONCES => {
+                let params = parse_params::<GraphParams>(params)?;
+                let client = &mut self.client;
+                let result = catch_panic(async {
+                    match params.graph_type {
+                        GraphType::PegIn => client.push_peg_in_nonces(&params.graph_id),
+                        GraphType::PegOut => client.push_peg_out_nonces(&params.graph_id),
+                    }
+                    client.flush().await;
+                })
+                .await;
+                self.recover(result).await?;
+                to_result(OperationResponse {
+                    graph_id: params.graph_id,
+                })
+            }
+            methods::PRE_SIGN => {
+                let params = parse_params::<GraphParams>(params)?;
+                let client = &mut self.client;
+                let result = catch_panic(async {
+                    match params.graph_type {
+                        GraphType::PegIn => client.pre_sign_peg_in(&params.graph_id),
+                        GraphType::PegOut => client.pre_sign_peg_out(&params.graph_id),
+                    }
+                    client.flush().await;
+                })
+                .await;
+                self.recover(result).await?;
+                to_result(OperationResponse {
+                    graph_id: params.graph_id,
+                })
+            }
+            methods::PUSH_PENDING_NONCES => {
+                let client = &mut self.client;
+                let result = catch_panic(async {
+                    let graph_ids = client.push_pending_nonces(&|_| {});
+                    client.flush().await;
+                    graph_ids
+                })
+                .await;
+                let graph_ids = self.recover(result).await?;
+                to_result(BatchOperationResponse { graph_ids })
+            }
+            methods::PRE_SIGN_PENDING => {
+                let client = &mut self.client;
+                let result = catch_panic(async {
+                    let graph_ids = client.pre_sign_pending_graphs(&|_| {});
+                    client.flush().await;
+                    graph_ids
+                })
+                .await;
+                let graph_ids = self.recover(result).await?;
+                to_result(BatchOperationResponse { graph_ids })
+            }
+            methods::BROADCAST => {
+                let params = parse_params::<BroadcastParams>(params)?;
+                self.broadcast(&params).await?;
+                to_result(OperationResponse {
+                    graph_id: params.graph_id,
+                })
+            }
+            _ => Err(RpcError::new(
+                METHOD_NOT_FOUND,
+                format!("Unknown method: {}", method),
+            )),
+        }
+    }
+
+    async fn sync(&mut self) -> Result<SyncResponse, RpcError> {
+        let client = &mut self.client;
+        let result = catch_panic(client.sync()).await;
+        self.recover(result).await?;
+
+        let data = self.client.get_data();
+        Ok(SyncResponse {
+            peg_in_graphs: data.peg_in_graphs.len(),
+            peg_out_graphs: data.peg_out_graphs.len(),
+        })
+    }
+
+    fn list_graphs(&self) -> ListGraphsResponse {
+        let data = self.client.get_data();
+        ListGraphsResponse {
+            peg_in_graphs: data
+                .peg_in_graphs
+                .iter()
+                .map(|peg_in_graph| PegInGraphSummary {
+                    id: peg_in_graph.id().clone(),
+                    depositor_public_key: peg_in_graph.depositor_public_key.to_string(),
+                })
+                .collect(),
+            peg_out_graphs: data
+                .peg_out_graphs
+                .iter()
+                .map(|peg_out_graph| PegOutGraphSummary {
+                    id: peg_out_graph.id().clone(),
+                    peg_in_graph_id: peg_out_graph.peg_in_graph_id.clone(),
+                })
+                .collect(),
+        }
+    }
+
+    // Reports the same statuses as `BitVMClient::status` for each role present in the config.
+    async fn get_status(&self, graph_id: Option<&str>) -> Vec<GraphStatusEntry> {
+        let esplora = &self.client.esplora;
+        let data = self.client.get_data();
+        let roles = self.config.configured_roles();
+        let mut statuses = vec![];
+
+        let depositor_public_key = self.config.public_key(ClientRole::Depositor).ok();
+        let operator_public_key = self.config.public_key(ClientRole::Operator).ok();
+        for peg_in_graph in data.peg_in_graphs.iter() {
+            if graph_id.is_some_and(|graph_id| !peg_in_graph.id().eq(graph_id)) {
+                continue;
+            }
+            if depositor_public_key.is_some_and(|key| peg_in_graph.depositor_public_key.eq(&key)) {
+                statuses.push((
+                    peg_in_graph.id(),
+                    GraphStatus::PegInDepositor(peg_in_graph.depositor_status(esplora).await),
+                ));
+            }
+            if roles.contains(&ClientRole::Operator) {
+                statuses.push((
+                    peg_in_graph.id(),
+                    GraphStatus::PegInOperator(peg_in_graph.operator_status(esplora).await),
+                ));
+            }
+            if roles.contains(&ClientRole::Verifier) {
+                statuses.push((
+                    peg_in_graph.id(),
+                    GraphStatus::PegInVerifier(peg_in_graph.verifier_status(esplora).await),
+                ));
+            }
+        }
+
+        // The operator only follows its own peg-out graphs
+        let operator_peg_out_graph_ids: Vec<String> = match operator_public_key {
+            Some(operator_public_key) => data
+                .peg_in_graphs
+                .iter()
+                .map(|peg_in_graph| peg_out_generate_id(peg_in_graph, &operator_public_key))
+                .collect(),
+            None => vec![],
+        };
+        for peg_out_graph in data.peg_out_graphs.iter() {
+            if graph_id.is_some_and(|graph_id| !peg_out_graph.id().eq(graph_id)) {
+                continue;
+            }
+            if roles.contains(&ClientRole::Depositor) || roles.contains(&ClientRole::Withdrawer) {
+                statuses.push((
+                    peg_out_graph.id(),
+                    GraphStatus::PegOutDepositor(peg_out_graph.depositor_status(esplora).await),
+                ));
+            }
+            if operator_peg_out_graph_ids.contains(peg_out_graph.id()) {
+                statuses.push((
+                    peg_out_graph.id(),
+                    GraphStatus::PegOutOperator(peg_out_graph.operator_status(esplora).await),
+                ));
+            }
+            if roles.contains(&ClientRole::Verifier) {
+                statuses.push((
+                    peg_out_graph.id(),
+                    GraphStatus::PegOutVerifier(peg_out_graph.verifier_status(esplora).await),
+                ));
+            }
+        }
+
+        statuses
+            .into_iter()
+            .map(|(graph_id, status)| GraphStatusEntry {
+                graph_id: graph_id.clone(),
+                status,
+                description: status.description(),
+            })
+            .collect()
+    }
+
+    async fn broadcast(&mut self, params: &BroadcastParams) -> Result<(), RpcError> {
+        let graph_id = params.graph_id.as_str();
+        let reward_script_pubkey = match &params.reward_address {
+            Some(address) => Some(self.script_pubkey(address)?),
+            None => None,
+        };
+        let require_reward_script_pubkey = || {
+            reward_script_pubkey.clone().ok_or(RpcError::new(
+                INVALID_PARAMS,
+                format!("{:?} requires a reward_address", params.transaction),
+            ))
+        };
+
+        let client = &mut self.client;
+        let result = match params.transaction {
+            BroadcastTransaction::PegInDeposit => {
+                catch_panic(client.broadcast_peg_in_deposit(graph_id)).await
+            }
+            BroadcastTransaction::PegInRefund => {
+                catch_panic(client.broadcast_peg_in_refund(graph_id)).await
+            }
+            BroadcastTransaction::PegInConfirm => {
+                catch_panic(client.broadcast_peg_in_confirm(graph_id)).await
+            }
+            BroadcastTransaction::KickOff1 => {
+                catch_panic(client.broadcast_kick_off_1(graph_id)).await
+            }
+            BroadcastTransaction::StartTime => {
+                catch_panic(client.broadcast_start_time(graph_id)).await
+            }
+            BroadcastTransaction::StartTimeTimeout => {
+                let script_pubkey = require_reward_script_pubkey()?;
+                catch_panic(client.broadcast_start_time_timeout(graph_id, script_pubkey)).await
+            }
+            BroadcastTransaction::KickOff2 => {
+                catch_panic(client.broadcast_kick_off_2(graph_id)).await
+            }
+            BroadcastTransaction::KickOffTimeout => {
+                let script_pubkey = require_reward_script_pubkey()?;
+                catch_panic(client.broadcast_kick_off_timeout(graph_id, script_pubkey)).await
+            }
+            BroadcastTransaction::Challenge => {
+                let script_pubkey = require_reward_script_pubkey()?;
+                if params.crowdfunding_inputs.is_empty() {
+                    return Err(RpcError::new(
+                        INVALID_PARAMS,
+                        "Challenge requires crowdfunding_inputs",
+                    ));
+                }
+                // The client signs the crowdfunding inputs with the key of its first role
+                let signing_role = self.config.configured_roles().first().copied();
+                let public_key = match signing_role {
+                    Some(role) => self.config.public_key(role),
+                    None => Err(String::from("No secret configured to sign the challenge")),
+                }
+                .map_err(|err| RpcError::new(OPERATION_FAILED, err))?;
+                let script = generate_pay_to_pubkey_script(&public_key);
+                let crowdfunding_inputs: Vec<InputWithScript> = params
+                    .crowdfunding_inputs
+                    .iter()
+                    .map(|input| InputWithScript {
+                        outpoint: input.outpoint,
+                        amount: Amount::from_sat(input.amount),
+                        script: &script,
+                    })
+                    .collect();
+                catch_panic(client.broadcast_challenge(
+                    graph_id,
+                    &crowdfunding_inputs,
+                    script_pubkey,
+                ))
+                .await
+            }
+            BroadcastTransaction::Assert => catch_panic(client.broadcast_assert(graph_id)).await,
+            BroadcastTransaction::DisproveChain => {
+                let script_pubkey = require_reward_script_pubkey()?;
+                catch_panic(client.broadcast_disprove_chain(graph_id, script_pubkey)).await
+            }
+            BroadcastTransaction::Disprove => {
+                let script_pubkey = require_reward_script_pubkey()?;
+                let script_index = params.script_index.ok_or(RpcError::new(
+                    INVALID_PARAMS,
+                    "Disprove requires a script_index",
+                ))?;
+                catch_panic(client.broadcast_disprove(graph_id, script_index, script_pubkey)).await
+            }
+            BroadcastTransaction::Take1 => catch_panic(client.broadcast_take_1(graph_id)).await,
+            BroadcastTransaction::Take2 => catch_panic(client.broadcast_take_2(graph_id)).await,
+        };
+        self.recover(result).await
+    }
+
+    // A panic can leave the client half way through an operation, e.g. with only some of the
+    // inputs of a graph signed, so the client is rebuilt from the files on disk before it is used
+    // again. If that fails, no further requests are served and `run` returns the error, rather
+    // than serving requests from unknown state.
+    async fn recover<T>(&mut self, result: Result<T, RpcError>) -> Result<T, RpcError> {
+        if let Err(err) = &result {
+            error!(
+                "Operation panicked, reloading client state: {}",
+                err.message
+            );
+            let config = &self.config;
+            let client = catch_panic(async {
+                let mut client = config.client(&config.configured_roles()).await?;
+                client.sync().await;
+                Ok::<_, String>(client)
+            })
+            .await;
+            match client {
+                Ok(Ok(client)) => self.client = client,
+                Ok(Err(err)) | Err(RpcError { message: err, .. }) => {
+                    error!("Failed to reload client state: {}", err);
+                    self.reload_error = Some(err);
+                }
+            }
+        }
+
+        result
+    }
+
+    fn script_pubkey(&self, address: &str) -> Result<ScriptBuf, RpcError> {
+        address
+            .parse::<Address<NetworkUnchecked>>()
+            .map_err(|err| err.to_string())
+            .and_then(|address| {
+                address
+                    .require_network(self.config.source_network)
+                    .map_err(|err| err.to_string())
+            })
+            .map(|address| address.script_pubkey())
+            .map_err(|err| RpcError::new(INVALID_PARAMS, format!("Invalid address: {}", err)))
+    }
+}
+
+// Client operations panic on invalid graph ids and failed broadcasts, which must not stop the
+// daemon. Callers pass the result to `Daemon::recover`, which discards the client on a panic.
+async fn catch_panic<T>(future: impl Future<Output = T>) -> Result<T, RpcError> {
+    AssertUnwindSafe(future)
+        .catch_unwind()
+        .await
+        .map_err(|panic| RpcError::new(OPERATION_FAILED, panic_message(panic)))
+}
+
+fn panic_message(panic: Box<dyn Any + Send>) -> String {
+    if let Some(message) = panic.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = panic.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        String::from("Operation failed")
+    }
+}
+
+fn parse_params<T: DeserializeOwned>(params: Value) -> Result<T, RpcError> {
+    serde_json::from_value::<T>(params)
+        .map_err(|err| RpcError::new(INVALID_PARAMS, err.to_string()))
+}
+
+fn to_result(result: impl Serialize) -> Result<Value, RpcError> {
+    serde_json::to_value(result).map_err(|err| RpcError::new(OPERATION_FAILED, err.to_string()))
+}
+
+// Role and status of a graph status as serialized in the RPC responses, e.g. `peg_out_verifier`
+// and `PegOutPresign`.
+fn graph_status_labels(status: &GraphStatus) -> Labels {
+    let value = serde_json::to_value(status).unwrap_or(Value::Null);
+    let role = value["role"].as_str().unwrap_or_default();
+    let status = match &value["status"] {
+        Value::String(status) => status.clone(),
+        Value::Object(status) => status.keys().next().cloned().unwrap_or_default(),
+        status => status.to_string(),
+    };
+
+    to_labels(&[("role", role), ("status", &status)])
+}
+
+async fn read_http_request(stream: &mut TcpStream) -> Result<HttpRequest, String> {
+    let mut buffer = vec![];
+    let mut chunk = [0u8; 4096];
+    let header_end = loop {
+        if let Some(position) = find_header_end(&buffer) {
+            break position;
+        }
+        if buffer.len() > MAX_REQUEST_SIZE {
+            return Err(String::from("Request too large"));
+        }
+        let read = stream
+            .read(&mut chunk)
+            .await
+            .map_err(|err| err.to_string())?;
+        if read == 0 {
+            return Err(String::from(
+                "Connection closed before the end of the headers",
+            ));
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+    };
+
+    let headers = String::from_utf8_lossy(&buffer[..header_end]).to_string();
+    if is_metrics_request(&headers) {
+        return Ok(HttpRequest::Metrics);
+    }
+    if !headers.starts_with("POST ") {
+        return Err(String::from(
+            "Only POST requests and GET /metrics are supported",
+        ));
+    }
+    let content_length = headers
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case("content-length"))
+        .and_then(|(_, value)| value.trim().parse::<usize>().ok())
+        .ok_or(String::from("Missing Content-Length header"))?;
+    if content_length > MAX_REQUEST_SIZE {
+        return Err(String::from("Request too large"));
+    }
+
+    let mut body = buffer.split_off(header_end + 4);
+    while body.len() < content_length {
+        let read = stream
+            .read(&mut chunk)
+            .await
+            .map_err(|err| err.to_string())?;
+        if read == 0 {
+            return Err(String::from("Connection closed before the end of the body"));
+        }
+        body.extend_from_slice(&chunk[..read]);
+    }
+    body.truncate(content_length);
+
+    Ok(HttpRequest::JsonRpc(body))
+}
+
+fn find_header_end(buffer: &[u8]) -> Option<usize> {
+    buffer.windows(4).position(|window| window == b"\r\n\r\n")
+}
+
+fn is_metrics_request(headers: &str) -> bool {
+    let mut request_line = headers.lines().next().unwrap_or_default().split(' ');
+    request_line.next() == Some("GET") && request_line.next() == Some("/metrics")
+}
+
+async fn write_http_response(
+    stream: &mut TcpStream,
+    status: &str,
+    content_type: &str,
+    body: &[u8],
+) -> Result<(), String> {
+    let header = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        status,
+        content_type,
+        body.len()
+    );
+    stream
+        .write_all(header.as_bytes())
+        .await
+        .map_err(|err| err.to_string())?;
+    stream
+        .write_all(body)
+        .await
+        .map_err(|err| err.to_string())?;
+    stream.flush().await.map_err(|err| err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::Network;
+    use serde_json::{json, Value};
+    use std::time::Duration;
+
+    use super::{
+        super::super::{
+            super::{
+                constants::DestinationNetwork,
+                contexts::base::generate_keys_from_secret,
+                graphs::{
+                    base::{VERIFIER_0_SECRET, VERIFIER_1_SECRET},
+                    peg_in::PegInVerifierStatus,
+                },
+            },
+            cli::config::BridgeConfig,
+        },
+        find_header_end, graph_status_labels, is_metrics_request, methods, to_labels, Daemon,
+        GraphStatus, RpcResponse, INVALID_PARAMS, INVALID_REQUEST, JSON_RPC_VERSION,
+        METHOD_NOT_FOUND, OPERATION_FAILED, PARSE_ERROR,
+    };
+
+    async fn setup_daemon() -> Daemon {
+        let source_network = Network::Testnet;
+        let n_of_n_public_keys = [VERIFIER_0_SECRET, VERIFIER_1_SECRET]
+            .iter()
+            .map(|secret| generate_keys_from_secret(source_network, secret).2)
+            .collect();
+
+        Daemon::new(BridgeConfig {
+            source_network,
+            destination_network: DestinationNetwork::EthereumSepolia,
+            esplora_url: None,
+            n_of_n_public_keys,
+            depositor_secret: None,
+            operator_secret: None,
+            verifier_secret: Some(VERIFIER_0_SECRET.to_string()),
+            withdrawer_secret: None,
+            timelock_config: None,
+        })
+        .await
+        .unwrap()
+    }
+
+    async fn request(daemon: &mut Daemon, method: &str, params: Value) -> RpcResponse {
+        let request = json!({
+            "jsonrpc": JSON_RPC_VERSION,
+            "id": 1,
+            "method": method,
+            "params": params,
+        });
+        daemon
+            .handle_request(&serde_json::to_vec(&request).unwrap())
+            .await
+    }
+
+    fn error_code(response: &RpcResponse) -> Option<i64> {
+        response.error.as_ref().map(|error| error.code)
+    }
+
+    #[tokio::test]
+    async fn test_handle_request_dispatch() {
+        let mut daemon = setup_daemon().await;
+
+        let response = daemon.handle_request(b"{").await;
+        assert_eq!(error_code(&response), Some(PARSE_ERROR));
+
+        let response = daemon
+            .handle_request(br#"{"jsonrpc": "1.0", "id": 1, "method": "list_graphs"}"#)
+            .await;
+        assert_eq!(response.id, json!(1));
+        assert_eq!(error_code(&response), Some(INVALID_REQUEST));
+
+        let response = request(&mut daemon, "unknown_method", Value::Null).await;
+        assert_eq!(error_code(&response), Some(METHOD_NOT_FOUND));
+
+        let response = request(&mut daemon, methods::PUSH_NONCES, json!({})).await;
+        assert_eq!(error_code(&response), Some(INVALID_PARAMS));
+
+        let response = request(&mut daemon, methods::LIST_GRAPHS, Value::Null).await;
+        assert_eq!(response.id, json!(1));
+        assert!(response.error.is_none());
+        let result = response.result.unwrap();
+        assert!(result["peg_in_graphs"].is_array());
+        assert!(result["peg_out_graphs"].is_array());
+    }
+
+    #[tokio::test]
+    async fn test_recover_after_panicked_operation() {
+        let mut daemon = setup_daemon().await;
+        let params = json!({ "graph_type": "peg_in", "graph_id": "unknown" });
+
+        // The client panics on unknown graph ids and is reloaded
+        let response = request(&mut daemon, methods::PUSH_NONCES, params.clone()).await;
+        assert_eq!(error_code(&response), Some(OPERATION_FAILED));
+        assert!(daemon.reload_error.is_none());
+        let response = request(&mut daemon, methods::LIST_GRAPHS, Value::Null).await;
+        assert!(response.error.is_none());
+
+        // A client that can't be reloaded stops the daemon instead of the process
+        daemon.config.verifier_secret = Some(String::from("invalid secret"));
+        let response = request(&mut daemon, methods::PUSH_NONCES, params).await;
+        assert_eq!(error_code(&response), Some(OPERATION_FAILED));
+        assert!(daemon.reload_error.is_some());
+        let response = request(&mut daemon, methods::LIST_GRAPHS, Value::Null).await;
+        assert_eq!(error_code(&response), Some(OPERATION_FAILED));
+        let result = daemon
+            .run("127.0.0.1:0".parse().unwrap(), Duration::from_secs(60))
+            .await;
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_find_header_end() {
+        let request = b"POST / HTTP/1.1\r\nContent-Length: 2\r\n\r\n{}";
+        assert_eq!(find_header_end(request), Some(34));
+        assert_eq!(find_header_end(b"POST / HTTP/1.1\r\n"), None);
+    }
+
+    #[test]
+    fn test_is_metrics_request() {
+        assert!(is_metrics_request(
+            "GET /metrics HTTP/1.1\r\nHost: localhost"
+        ));
+        assert!(!is_metrics_request("GET / HTTP/1.1"));
+        assert!(!is_metrics_request("POST /metrics HTTP/1.1"));
+    }
+
+    #[test]
+    fn test_graph_status_labels() {
+        let status = GraphStatus::PegInVerifier(PegInVerifierStatus::PegInPresign);
+        assert_eq!(
+            graph_status_labels(&status),
+            to_labels(&[("role", "peg_in_verifier"), ("status", "PegInPresign")])
+        );
+    }
+}
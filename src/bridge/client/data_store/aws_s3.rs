@@ -8,6 +8,7 @@ use aws_sdk_s3::{
     Client, Config,
 };
 use dotenv;
+use tracing::warn;
 
 // To use this data store, create a .env file in the base directory with the following values:
 // export BRIDGE_AWS_ACCESS_KEY_ID="..."
@@ -125,7 +126,7 @@ impl DataStoreDriver for AwsS3 {
                     }
                 }
                 Err(err) => {
-                    eprintln!("{err:?}");
+                    warn!(error = ?err, "Unable to list objects");
                     return Err("Unable to list objects".to_string());
                 }
             }
@@ -165,4 +166,26 @@ impl DataStoreDriver for AwsS3 {
             Err(err) => Err(format!("Failed to save json file: {}", err)),
         }
     }
+
+    async fn fetch_bytes(&self, key: &str, file_path: Option<&str>) -> Result<Vec<u8>, String> {
+        match self.get_object(key, file_path).await {
+            Ok(buffer) => Ok(buffer),
+            Err(err) => Err(format!("Failed to get file: {}", err.to_string())),
+        }
+    }
+
+    async fn upload_bytes(
+        &self,
+        key: &str,
+        data: Vec<u8>,
+        file_path: Option<&str>,
+    ) -> Result<usize, String> {
+        let size = data.len();
+        let byte_stream = ByteStream::from(data);
+
+        match self.upload_object(&key, byte_stream, file_path).await {
+            Ok(_) => Ok(size),
+            Err(err) => Err(format!("Failed to save file: {}", err)),
+        }
+    }
 }
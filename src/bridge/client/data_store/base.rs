@@ -10,4 +10,11 @@ pub trait DataStoreDriver {
         json: String,
         file_path: Option<&str>,
     ) -> Result<usize, String>;
+    async fn fetch_bytes(&self, key: &str, file_path: Option<&str>) -> Result<Vec<u8>, String>;
+    async fn upload_bytes(
+        &self,
+        key: &str,
+        data: Vec<u8>,
+        file_path: Option<&str>,
+    ) -> Result<usize, String>;
 }
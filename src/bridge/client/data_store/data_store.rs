@@ -1,8 +1,12 @@
 use once_cell::sync::Lazy;
 use regex::Regex;
 use std::cmp::Ordering;
-use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::future::Future;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tracing::{debug, warn};
 
+use super::super::super::serialization::SerializationFormat;
+use super::super::metrics::{metrics, DATA_STORE_REQUEST_DURATION};
 use super::base::DataStoreDriver;
 use super::{
     aws_s3::AwsS3,
@@ -13,24 +17,55 @@ use super::{
 static CLIENT_MISSING_CREDENTIALS_ERROR: &str =
     "Bridge client is missing AWS S3, FTP, FTPS, or SFTP credentials";
 
-static CLIENT_DATA_SUFFIX: &str = "-bridge-client-data-musig2.json";
+static CLIENT_DATA_SUFFIX: &str = "-bridge-client-data-musig2";
 static CLIENT_DATA_REGEX: Lazy<Regex> =
-    Lazy::new(|| Regex::new(&format!(r"(\d{{13}}){}", CLIENT_DATA_SUFFIX)).unwrap());
+    Lazy::new(|| Regex::new(&format!(r"(\d{{13}}){}\.(json|bin)$", CLIENT_DATA_SUFFIX)).unwrap());
+
+// To store data in the compact binary format instead of json, add to the .env file:
+// export BRIDGE_DATA_STORE_FORMAT="binary"
+static DATA_STORE_FORMAT_VARIABLE: &str = "BRIDGE_DATA_STORE_FORMAT";
 
 pub struct DataStore {
     aws_s3: Option<AwsS3>,
     ftp: Option<Ftp>,
     ftps: Option<Ftps>,
     sftp: Option<Sftp>,
+    serialization_format: SerializationFormat,
 }
 
 impl DataStore {
     pub fn new() -> Self {
+        dotenv::dotenv().ok();
+        let serialization_format = match dotenv::var(DATA_STORE_FORMAT_VARIABLE) {
+            Ok(format) if format.eq_ignore_ascii_case("binary") => SerializationFormat::Binary,
+            _ => SerializationFormat::Json,
+        };
+
         Self {
             aws_s3: AwsS3::new(),
             ftp: None,  // Ftp::new(),
             ftps: None, // Ftps::new(),
             sftp: None, // Sftp::new(),
+            serialization_format,
+        }
+    }
+
+    /// Format used for the files written by this data store. Files in either format can be read.
+    pub fn serialization_format(&self) -> SerializationFormat { self.serialization_format }
+
+    pub fn set_serialization_format(&mut self, serialization_format: SerializationFormat) {
+        self.serialization_format = serialization_format;
+    }
+
+    pub fn get_file_serialization_format(
+        file_name: &String,
+    ) -> Result<SerializationFormat, String> {
+        match CLIENT_DATA_REGEX.captures(file_name) {
+            Some(captures) => match SerializationFormat::from_file_extension(&captures[2]) {
+                Some(format) => Ok(format),
+                None => Err(String::from("Unknown file extension")),
+            },
+            None => Err(String::from("Incorrect file name")),
         }
     }
 
@@ -50,7 +85,7 @@ impl DataStore {
 
     pub async fn get_file_names(&self, file_path: Option<&str>) -> Result<Vec<String>, String> {
         match self.get_driver() {
-            Ok(driver) => match driver.list_objects(file_path).await {
+            Ok(driver) => match timed("list", driver.list_objects(file_path)).await {
                 Ok(keys) => {
                     let mut data_keys: Vec<String> = keys
                         .iter()
@@ -81,16 +116,16 @@ impl DataStore {
         &self,
         key: &String,
         file_path: Option<&str>,
-    ) -> Result<Option<String>, String> {
+    ) -> Result<Option<Vec<u8>>, String> {
         match self.get_driver() {
             Ok(driver) => {
-                let json = driver.fetch_json(key, file_path).await;
-                if json.is_ok() {
-                    // println!("Fetched data file: {}", key);
-                    return Ok(Some(json.unwrap()));
+                let data = timed("fetch", driver.fetch_bytes(key, file_path)).await;
+                if data.is_ok() {
+                    debug!(key, "Fetched data file");
+                    return Ok(Some(data.unwrap()));
                 }
 
-                println!("No data file {} found", key);
+                warn!(key, "No data file found");
                 Ok(None)
             }
             Err(err) => Err(err.to_string()),
@@ -99,7 +134,7 @@ impl DataStore {
 
     pub async fn write_data(
         &self,
-        json: String,
+        data: Vec<u8>,
         file_path: Option<&str>,
     ) -> Result<String, String> {
         match self.get_driver() {
@@ -108,8 +143,8 @@ impl DataStore {
                     .duration_since(UNIX_EPOCH)
                     .unwrap()
                     .as_millis();
-                let key = Self::create_file_name(time);
-                let response = driver.upload_json(&key, json, file_path).await;
+                let key = Self::create_file_name(time, self.serialization_format);
+                let response = timed("write", driver.upload_bytes(&key, data, file_path)).await;
 
                 match response {
                     Ok(_) => Ok(key),
@@ -123,13 +158,20 @@ impl DataStore {
     pub fn get_past_max_file_name_by_timestamp(latest_timestamp: u64, period: u64) -> String {
         let past_max_timestamp =
             (Duration::from_millis(latest_timestamp) - Duration::from_secs(period)).as_millis();
-        let past_max_file_name = Self::create_file_name(past_max_timestamp);
+        // Only the timestamp matters when comparing file names
+        let past_max_file_name =
+            Self::create_file_name(past_max_timestamp, SerializationFormat::Binary);
 
         return past_max_file_name;
     }
 
-    fn create_file_name(timestamp: u128) -> String {
-        return format!("{}{}", timestamp, CLIENT_DATA_SUFFIX);
+    fn create_file_name(timestamp: u128, serialization_format: SerializationFormat) -> String {
+        return format!(
+            "{}{}.{}",
+            timestamp,
+            CLIENT_DATA_SUFFIX,
+            serialization_format.file_extension()
+        );
     }
 
     fn get_driver(&self) -> Result<&dyn DataStoreDriver, &str> {
@@ -146,3 +188,16 @@ impl DataStore {
         }
     }
 }
+
+// Records the duration of a data store request in the metrics.
+async fn timed<T>(operation: &str, request: impl Future<Output = T>) -> T {
+    let started = Instant::now();
+    let result = request.await;
+    metrics().observe_duration(
+        DATA_STORE_REQUEST_DURATION,
+        &[("operation", operation)],
+        started.elapsed(),
+    );
+
+    result
+}
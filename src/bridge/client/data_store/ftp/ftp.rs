@@ -4,6 +4,7 @@ use super::{
 };
 use async_trait::async_trait;
 use dotenv;
+use tracing::warn;
 
 // To use this data store, create a .env file in the base directory with the following values:
 // export BRIDGE_FTP_HOST="..."
@@ -46,7 +47,7 @@ impl Ftp {
         match lib::test_connection(&credentials) {
             Ok(_) => Some(Self { credentials }),
             Err(err) => {
-                eprintln!("{err:?}");
+                warn!(error = %err, "FTP data store unavailable");
                 None
             }
         }
@@ -71,4 +72,17 @@ impl DataStoreDriver for Ftp {
     ) -> Result<usize, String> {
         lib::upload_json(&self.credentials, key, json, file_path).await
     }
+
+    async fn fetch_bytes(&self, key: &str, file_path: Option<&str>) -> Result<Vec<u8>, String> {
+        lib::fetch_bytes(&self.credentials, key, file_path).await
+    }
+
+    async fn upload_bytes(
+        &self,
+        key: &str,
+        data: Vec<u8>,
+        file_path: Option<&str>,
+    ) -> Result<usize, String> {
+        lib::upload_bytes(&self.credentials, key, data, file_path).await
+    }
 }
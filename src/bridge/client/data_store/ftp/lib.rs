@@ -3,6 +3,7 @@ use suppaftp::{
     async_native_tls::TlsConnector, AsyncFtpStream, AsyncNativeTlsConnector,
     AsyncNativeTlsFtpStream,
 };
+use tracing::{debug, warn};
 
 pub struct FtpCredentials {
     pub is_secure: bool,
@@ -95,7 +96,7 @@ pub async fn upload_json(
     let bytes = json.as_bytes().to_vec();
     let size = bytes.len();
 
-    println!("Writing data file to {} (size: {})", key, size);
+    debug!(key, size, "Writing data file");
 
     match upload_object(credentials, &key, &bytes, file_path).await {
         Ok(_) => Ok(size),
@@ -103,6 +104,31 @@ pub async fn upload_json(
     }
 }
 
+pub async fn fetch_bytes(
+    credentials: &FtpCredentials,
+    key: &str,
+    file_path: Option<&str>,
+) -> Result<Vec<u8>, String> {
+    match get_object(credentials, key, file_path).await {
+        Ok(buffer) => Ok(buffer),
+        Err(err) => Err(format!("Failed to get file: {}", err.to_string())),
+    }
+}
+
+pub async fn upload_bytes(
+    credentials: &FtpCredentials,
+    key: &str,
+    data: Vec<u8>,
+    file_path: Option<&str>,
+) -> Result<usize, String> {
+    let size = data.len();
+
+    match upload_object(credentials, &key, &data, file_path).await {
+        Ok(_) => Ok(size),
+        Err(err) => Err(format!("Failed to save file: {}", err)),
+    }
+}
+
 async fn get_object(
     credentials: &FtpCredentials,
     key: &str,
@@ -350,12 +376,12 @@ async fn disconnect(
     if insecure_ftp_stream.is_some() {
         match insecure_ftp_stream.unwrap().quit().await {
             Ok(_) => {}
-            Err(err) => eprintln!("Unable to close FTP connection: {}", err),
+            Err(err) => warn!(error = %err, "Unable to close FTP connection"),
         }
     } else if secure_ftp_stream.is_some() {
         match secure_ftp_stream.unwrap().quit().await {
             Ok(_) => {}
-            Err(err) => eprintln!("Unable to close FTPS connection: {}", err),
+            Err(err) => warn!(error = %err, "Unable to close FTPS connection"),
         }
     }
 }
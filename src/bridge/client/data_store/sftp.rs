@@ -8,6 +8,7 @@ use openssh_sftp_client::{
     Sftp as _Sftp,
 };
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tracing::{debug, warn};
 
 // To use this data store, create a .env file in the base directory with the following values:
 // export BRIDGE_SFTP_HOST="..."
@@ -49,8 +50,6 @@ impl Sftp {
             return None;
         }
 
-        println!("SFTP 46");
-
         let credentials = SftpCredentials {
             host: host.unwrap(),
             port: port.unwrap(),
@@ -59,12 +58,10 @@ impl Sftp {
             base_path: base_path.unwrap(),
         };
 
-        println!("SFTP 55");
-
         match test_connection(&credentials) {
             Ok(_) => Some(Self { credentials }),
             Err(err) => {
-                eprintln!("{err:?}");
+                warn!(error = %err, "SFTP data store unavailable");
                 None
             }
         }
@@ -193,20 +190,41 @@ impl DataStoreDriver for Sftp {
         let bytes = json.as_bytes().to_vec();
         let size = bytes.len();
 
-        println!("Writing data file to {} (size: {})", key, size);
+        debug!(key, size, "Writing data file");
 
         match self.upload_object(&key, &bytes, file_path).await {
             Ok(_) => Ok(size),
             Err(err) => Err(format!("Failed to save json file: {}", err)),
         }
     }
+
+    async fn fetch_bytes(&self, key: &str, file_path: Option<&str>) -> Result<Vec<u8>, String> {
+        match self.get_object(key, file_path).await {
+            Ok(buffer) => Ok(buffer),
+            Err(err) => Err(format!("Failed to get file: {}", err.to_string())),
+        }
+    }
+
+    async fn upload_bytes(
+        &self,
+        key: &str,
+        data: Vec<u8>,
+        file_path: Option<&str>,
+    ) -> Result<usize, String> {
+        let size = data.len();
+
+        debug!(key, size, "Writing data file");
+
+        match self.upload_object(&key, &data, file_path).await {
+            Ok(_) => Ok(size),
+            Err(err) => Err(format!("Failed to save file: {}", err)),
+        }
+    }
 }
 
 fn test_connection(credentials: &SftpCredentials) -> Result<(), String> {
-    println!("SFTP 190");
     match executor::block_on(connect(credentials)) {
         Ok(sftp) => {
-            println!("SFTP 192");
             executor::block_on(disconnect(sftp));
             Ok(())
         }
@@ -261,5 +279,5 @@ async fn disconnect(sftp: _Sftp) {
         return;
     }
 
-    eprintln!("Unable to close connection");
+    warn!("Unable to close SFTP connection");
 }
@@ -0,0 +1,333 @@
+use once_cell::sync::Lazy;
+use std::{
+    collections::BTreeMap,
+    fmt::Write,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+pub const GRAPHS: &str = "bridge_graphs";
+pub const PENDING_SIGNATURES: &str = "bridge_pending_signatures";
+pub const BROADCAST_FAILURES: &str = "bridge_broadcast_failures_total";
+pub const DATA_STORE_REQUEST_DURATION: &str = "bridge_data_store_request_duration_seconds";
+pub const CHAIN_ADAPTOR_REQUEST_DURATION: &str = "bridge_chain_adaptor_request_duration_seconds";
+pub const CHAIN_ADAPTOR_LAST_POLL_AGE: &str = "bridge_chain_adaptor_seconds_since_last_poll";
+pub const CHAIN_ADAPTOR_LAG: &str = "bridge_chain_adaptor_lag_blocks";
+
+const DESCRIPTIONS: [(&str, &str); 7] = [
+    (GRAPHS, "Graphs by type, role and status"),
+    (
+        PENDING_SIGNATURES,
+        "MuSig2 partial signatures still missing from n-of-n signers, by graph type",
+    ),
+    (
+        BROADCAST_FAILURES,
+        "Transaction broadcasts that failed, by transaction",
+    ),
+    (
+        DATA_STORE_REQUEST_DURATION,
+        "Duration of data store requests, by operation",
+    ),
+    (
+        CHAIN_ADAPTOR_REQUEST_DURATION,
+        "Duration of chain adaptor requests, by event",
+    ),
+    (
+        CHAIN_ADAPTOR_LAST_POLL_AGE,
+        "Seconds since the last successful chain adaptor request, by event",
+    ),
+    (
+        CHAIN_ADAPTOR_LAG,
+        "Blocks between the chain tip and the last block the chain adaptor returned events up to, by event",
+    ),
+];
+
+// Upper bounds of the duration histogram buckets in seconds, the last bucket is +Inf.
+const DURATION_BUCKETS: [f64; 10] = [0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
+static METRICS: Lazy<Metrics> = Lazy::new(Metrics::default);
+
+/// Metrics of the bridge client. All clients of a process share one instance, see `metrics()`.
+pub fn metrics() -> &'static Metrics { &METRICS }
+
+pub type Labels = Vec<(String, String)>;
+
+#[derive(Clone, Default)]
+struct Histogram {
+    bucket_counts: [u64; DURATION_BUCKETS.len()],
+    count: u64,
+    sum: f64,
+}
+
+#[derive(Default)]
+struct Families {
+    counters: BTreeMap<&'static str, BTreeMap<Labels, u64>>,
+    gauges: BTreeMap<&'static str, BTreeMap<Labels, f64>>,
+    histograms: BTreeMap<&'static str, BTreeMap<Labels, Histogram>>,
+    // Time of the last successful poll and the block its events reach, by event.
+    chain_adaptor_polls: BTreeMap<String, (Instant, u64)>,
+    chain_tip_height: Option<u64>,
+}
+
+#[derive(Default)]
+pub struct Metrics {
+    families: Mutex<Families>,
+}
+
+impl Metrics {
+    pub fn increment_counter(&self, name: &'static str, labels: &[(&str, &str)]) {
+        let mut families = self.families.lock().unwrap();
+        *families
+            .counters
+            .entry(name)
+            .or_default()
+            .entry(to_labels(labels))
+            .or_default() += 1;
+    }
+
+    /// Replaces all values of the gauge, so that label sets that are gone are not reported
+    /// anymore.
+    pub fn set_gauge_values(&self, name: &'static str, values: Vec<(Labels, f64)>) {
+        let mut families = self.families.lock().unwrap();
+        families.gauges.insert(name, values.into_iter().collect());
+    }
+
+    pub fn observe_duration(
+        &self,
+        name: &'static str,
+        labels: &[(&str, &str)],
+        duration: Duration,
+    ) {
+        let seconds = duration.as_secs_f64();
+        let mut families = self.families.lock().unwrap();
+        let histogram = families
+            .histograms
+            .entry(name)
+            .or_default()
+            .entry(to_labels(labels))
+            .or_default();
+        for (i, upper_bound) in DURATION_BUCKETS.iter().enumerate() {
+            if seconds <= *upper_bound {
+                histogram.bucket_counts[i] += 1;
+            }
+        }
+        histogram.count += 1;
+        histogram.sum += seconds;
+    }
+
+    /// Records a successful chain adaptor request for `event` that returned the events up to
+    /// `synced_height`, from which the age of the last poll and the lag are measured.
+    pub fn record_chain_adaptor_poll(&self, event: &str, synced_height: u64) {
+        let mut families = self.families.lock().unwrap();
+        families
+            .chain_adaptor_polls
+            .insert(event.to_string(), (Instant::now(), synced_height));
+    }
+
+    /// Records the latest chain tip seen by the chain adaptor. Polls that fail after fetching the
+    /// tip still update it, so the lag keeps growing while the adaptor falls behind.
+    pub fn record_chain_tip_height(&self, height: u64) {
+        let mut families = self.families.lock().unwrap();
+        families.chain_tip_height = Some(height.max(families.chain_tip_height.unwrap_or(0)));
+    }
+
+    /// Renders all metrics in the Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        let families = self.families.lock().unwrap();
+        let mut output = String::new();
+
+        for (name, values) in families.counters.iter() {
+            write_header(&mut output, name, "counter");
+            for (labels, value) in values {
+                writeln!(output, "{}{} {}", name, format_labels(labels, None), value).unwrap();
+            }
+        }
+
+        let mut gauges = families.gauges.clone();
+        if !families.chain_adaptor_polls.is_empty() {
+            gauges.insert(
+                CHAIN_ADAPTOR_LAST_POLL_AGE,
+                families
+                    .chain_adaptor_polls
+                    .iter()
+                    .map(|(event, (polled_at, _))| {
+                        (
+                            vec![(String::from("event"), event.clone())],
+                            polled_at.elapsed().as_secs_f64(),
+                        )
+                    })
+                    .collect(),
+            );
+        }
+        if let Some(chain_tip_height) = families.chain_tip_height {
+            gauges.insert(
+                CHAIN_ADAPTOR_LAG,
+                families
+                    .chain_adaptor_polls
+                    .iter()
+                    .map(|(event, (_, synced_height))| {
+                        (
+                            vec![(String::from("event"), event.clone())],
+                            chain_tip_height.saturating_sub(*synced_height) as f64,
+                        )
+                    })
+                    .collect(),
+            );
+        }
+        for (name, values) in gauges.iter() {
+            write_header(&mut output, name, "gauge");
+            for (labels, value) in values {
+                writeln!(output, "{}{} {}", name, format_labels(labels, None), value).unwrap();
+            }
+        }
+
+        for (name, values) in families.histograms.iter() {
+            write_header(&mut output, name, "histogram");
+            for (labels, histogram) in values {
+                for (upper_bound, bucket_count) in
+                    DURATION_BUCKETS.iter().zip(histogram.bucket_counts)
+                {
+                    let le = upper_bound.to_string();
+                    writeln!(
+                        output,
+                        "{}_bucket{} {}",
+                        name,
+                        format_labels(labels, Some(&le)),
+                        bucket_count
+                    )
+                    .unwrap();
+                }
+                writeln!(
+                    output,
+                    "{}_bucket{} {}",
+                    name,
+                    format_labels(labels, Some("+Inf")),
+                    histogram.count
+                )
+                .unwrap();
+                let labels = format_labels(labels, None);
+                writeln!(output, "{}_sum{} {}", name, labels, histogram.sum).unwrap();
+                writeln!(output, "{}_count{} {}", name, labels, histogram.count).unwrap();
+            }
+        }
+
+        output
+    }
+}
+
+pub fn to_labels(labels: &[(&str, &str)]) -> Labels {
+    labels
+        .iter()
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect()
+}
+
+fn write_header(output: &mut String, name: &str, metric_type: &str) {
+    if let Some((_, description)) = DESCRIPTIONS.iter().find(|(n, _)| *n == name) {
+        writeln!(output, "# HELP {} {}", name, description).unwrap();
+    }
+    writeln!(output, "# TYPE {} {}", name, metric_type).unwrap();
+}
+
+fn format_labels(labels: &Labels, le: Option<&str>) -> String {
+    let mut pairs = labels
+        .iter()
+        .map(|(name, value)| format!("{}=\"{}\"", name, escape_label_value(value)))
+        .collect::<Vec<_>>();
+    if let Some(le) = le {
+        pairs.push(format!("le=\"{}\"", le));
+    }
+
+    match pairs.is_empty() {
+        true => String::new(),
+        false => format!("{{{}}}", pairs.join(",")),
+    }
+}
+
+fn escape_label_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{to_labels, Metrics, BROADCAST_FAILURES, DATA_STORE_REQUEST_DURATION, GRAPHS};
+
+    #[test]
+    fn test_render_prometheus_text() {
+        let metrics = Metrics::default();
+        metrics.increment_counter(BROADCAST_FAILURES, &[("transaction", "kick_off_1")]);
+        metrics.increment_counter(BROADCAST_FAILURES, &[("transaction", "kick_off_1")]);
+        metrics.set_gauge_values(
+            GRAPHS,
+            vec![(
+                to_labels(&[("role", "peg_in_verifier"), ("status", "Say \"hi\"")]),
+                3.0,
+            )],
+        );
+        metrics.observe_duration(
+            DATA_STORE_REQUEST_DURATION,
+            &[("operation", "fetch")],
+            Duration::from_millis(200),
+        );
+
+        let output = metrics.render();
+        assert!(output.contains("# TYPE bridge_broadcast_failures_total counter\n"));
+        assert!(output.contains("bridge_broadcast_failures_total{transaction=\"kick_off_1\"} 2\n"));
+        assert!(output
+            .contains("bridge_graphs{role=\"peg_in_verifier\",status=\"Say \\\"hi\\\"\"} 3\n"));
+        assert!(output.contains(
+            "bridge_data_store_request_duration_seconds_bucket{operation=\"fetch\",le=\"0.1\"} 0\n"
+        ));
+        assert!(output.contains(
+            "bridge_data_store_request_duration_seconds_bucket{operation=\"fetch\",le=\"0.25\"} 1\n"
+        ));
+        assert!(output.contains(
+            "bridge_data_store_request_duration_seconds_bucket{operation=\"fetch\",le=\"+Inf\"} 1\n"
+        ));
+        assert!(output
+            .contains("bridge_data_store_request_duration_seconds_count{operation=\"fetch\"} 1\n"));
+    }
+
+    #[test]
+    fn test_gauge_values_are_replaced() {
+        let metrics = Metrics::default();
+        metrics.set_gauge_values(GRAPHS, vec![(to_labels(&[("status", "a")]), 1.0)]);
+        metrics.set_gauge_values(GRAPHS, vec![(to_labels(&[("status", "b")]), 2.0)]);
+
+        let output = metrics.render();
+        assert!(!output.contains("status=\"a\""));
+        assert!(output.contains("bridge_graphs{status=\"b\"} 2\n"));
+    }
+
+    #[test]
+    fn test_render_seconds_since_last_poll() {
+        let metrics = Metrics::default();
+        metrics.record_chain_adaptor_poll("peg_out_init", 100);
+
+        let output = metrics.render();
+        assert!(output.contains("# TYPE bridge_chain_adaptor_seconds_since_last_poll gauge\n"));
+        assert!(output
+            .contains("bridge_chain_adaptor_seconds_since_last_poll{event=\"peg_out_init\"} "));
+    }
+
+    #[test]
+    fn test_render_chain_adaptor_lag() {
+        let metrics = Metrics::default();
+        metrics.record_chain_tip_height(100);
+        metrics.record_chain_adaptor_poll("peg_out_init", 100);
+        metrics.record_chain_adaptor_poll("peg_in_minted", 100);
+        // The next peg-in minted poll fails after fetching the new tip
+        metrics.record_chain_tip_height(107);
+        metrics.record_chain_adaptor_poll("peg_out_init", 107);
+
+        let output = metrics.render();
+        assert!(output.contains("# TYPE bridge_chain_adaptor_lag_blocks gauge\n"));
+        assert!(output.contains("bridge_chain_adaptor_lag_blocks{event=\"peg_out_init\"} 0\n"));
+        assert!(output.contains("bridge_chain_adaptor_lag_blocks{event=\"peg_in_minted\"} 7\n"));
+    }
+}
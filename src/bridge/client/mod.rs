@@ -1,3 +1,9 @@
 pub mod chain;
+pub mod cli;
 pub mod client;
+pub mod daemon;
 pub mod data_store;
+pub mod metrics;
+pub mod operator_registry;
+pub mod refund_watch;
+pub mod wallet;
@@ -0,0 +1,310 @@
+use bitcoin::{hashes::sha256, key::Secp256k1, Amount, PublicKey, XOnlyPublicKey};
+use clap::ValueEnum;
+use musig2::secp256k1::{schnorr::Signature, Message};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use tracing::warn;
+
+use super::super::{
+    contexts::operator::OperatorContext,
+    graphs::{base::BaseGraph, peg_in::PegInGraph, peg_out::PegOutGraph},
+    transactions::pre_signed::PreSignedTransaction,
+};
+
+const OPERATOR_LIQUIDITY_TAG: &[u8] = b"bitvm-bridge/operator-liquidity";
+
+/// Value an operator is willing to front for peg-outs in total, published in the bridge data and
+/// signed by the operator. Only the declaration with the highest `sequence` is in effect.
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Debug)]
+pub struct OperatorLiquidity {
+    pub operator_public_key: PublicKey,
+    /// Liquidity in satoshis.
+    pub liquidity: u64,
+    pub sequence: u64,
+    pub signature: Signature,
+}
+
+impl OperatorLiquidity {
+    pub fn new(context: &OperatorContext, liquidity: Amount, sequence: u64) -> Self {
+        let message = liquidity_message(&context.operator_public_key, liquidity.to_sat(), sequence);
+        OperatorLiquidity {
+            operator_public_key: context.operator_public_key,
+            liquidity: liquidity.to_sat(),
+            sequence,
+            signature: context
+                .secp
+                .sign_schnorr(&message, &context.operator_keypair),
+        }
+    }
+
+    pub fn verify(&self) -> bool {
+        let message = liquidity_message(&self.operator_public_key, self.liquidity, self.sequence);
+        Secp256k1::verification_only()
+            .verify_schnorr(
+                &self.signature,
+                &message,
+                &XOnlyPublicKey::from(self.operator_public_key),
+            )
+            .is_ok()
+    }
+}
+
+fn liquidity_message(operator_public_key: &PublicKey, liquidity: u64, sequence: u64) -> Message {
+    let mut data = OPERATOR_LIQUIDITY_TAG.to_vec();
+    data.extend(operator_public_key.to_bytes());
+    data.extend(liquidity.to_be_bytes());
+    data.extend(sequence.to_be_bytes());
+    Message::from_hashed_data::<sha256::Hash>(&data)
+}
+
+/// Drops the declarations whose signature does not verify. A forged declaration in a data file
+/// only invalidates itself, the rest of the file is still merged.
+pub fn retain_valid_operator_liquidity(declarations: &mut Vec<OperatorLiquidity>) {
+    declarations.retain(|declaration| {
+        let is_valid = declaration.verify();
+        if !is_valid {
+            warn!(
+                operator = %declaration.operator_public_key,
+                sequence = declaration.sequence,
+                "Dropping invalid liquidity declaration"
+            );
+        }
+        is_valid
+    });
+}
+
+/// Keeps the declaration with the highest sequence of every operator, assuming all are valid.
+pub fn merge_operator_liquidity(
+    declarations: &mut Vec<OperatorLiquidity>,
+    other_declarations: &[OperatorLiquidity],
+) {
+    for declaration in other_declarations {
+        match declarations
+            .iter_mut()
+            .find(|d| d.operator_public_key == declaration.operator_public_key)
+        {
+            Some(existing) if existing.sequence < declaration.sequence => {
+                *existing = declaration.clone()
+            }
+            Some(_) => {}
+            None => declarations.push(declaration.clone()),
+        }
+    }
+}
+
+/// How an operator is chosen for a peg-out request.
+#[derive(ValueEnum, Serialize, Deserialize, Clone, Copy, Eq, PartialEq, Debug, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SelectionPolicy {
+    /// The operator with the most liquidity left after the request.
+    #[default]
+    MostAvailableLiquidity,
+    /// The operator fronting the fewest peg-outs not reimbursed yet, spreading requests evenly.
+    FewestOpenPegOuts,
+}
+
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub struct OperatorRecord {
+    pub operator_public_key: PublicKey,
+    /// Declared liquidity, zero if the operator never declared any.
+    pub liquidity: Amount,
+    /// Ids of all peg-out graphs of the operator.
+    pub graph_ids: Vec<String>,
+    /// Presigned peg-out graphs without a peg-out request, by peg-in graph id.
+    pub requestable_graphs: HashMap<String, String>,
+    /// Ids of the requested peg-out graphs the operator has not been reimbursed for yet.
+    pub open_peg_outs: Vec<String>,
+    /// Value of the open peg-outs.
+    pub committed: Amount,
+}
+
+impl OperatorRecord {
+    fn new(operator_public_key: &PublicKey) -> Self {
+        OperatorRecord {
+            operator_public_key: *operator_public_key,
+            liquidity: Amount::ZERO,
+            graph_ids: vec![],
+            requestable_graphs: HashMap::new(),
+            open_peg_outs: vec![],
+            committed: Amount::ZERO,
+        }
+    }
+
+    pub fn available_liquidity(&self) -> Amount {
+        self.liquidity
+            .checked_sub(self.committed)
+            .unwrap_or(Amount::ZERO)
+    }
+}
+
+/// Operators of a multi-operator federation with their peg-out graphs and liquidity.
+pub struct OperatorRegistry {
+    operators: HashMap<PublicKey, OperatorRecord>,
+    peg_in_amounts: HashMap<String, Amount>,
+}
+
+impl OperatorRegistry {
+    /// Builds the registry from the bridge data. `settled_graph_ids` are the requested peg-out
+    /// graphs whose operator was reimbursed or lost the claim, their value is not committed
+    /// anymore. Invalid liquidity declarations are ignored.
+    pub fn new(
+        peg_in_graphs: &[PegInGraph],
+        peg_out_graphs: &[PegOutGraph],
+        operator_liquidity: &[OperatorLiquidity],
+        settled_graph_ids: &HashSet<String>,
+    ) -> Self {
+        let peg_in_amounts: HashMap<String, Amount> = peg_in_graphs
+            .iter()
+            .map(|peg_in_graph| (peg_in_graph.id().clone(), peg_in_amount(peg_in_graph)))
+            .collect();
+
+        let mut operators: HashMap<PublicKey, OperatorRecord> = HashMap::new();
+        for peg_out_graph in peg_out_graphs {
+            let operator_public_key = peg_out_graph.operator_public_key();
+            let record = operators
+                .entry(*operator_public_key)
+                .or_insert_with(|| OperatorRecord::new(operator_public_key));
+            record.graph_ids.push(peg_out_graph.id().clone());
+
+            if peg_out_graph.withdrawer_public_key().is_some() {
+                if !settled_graph_ids.contains(peg_out_graph.id()) {
+                    record.open_peg_outs.push(peg_out_graph.id().clone());
+                    record.committed += peg_in_amounts
+                        .get(&peg_out_graph.peg_in_graph_id)
+                        .copied()
+                        .unwrap_or(Amount::ZERO);
+                }
+            } else if peg_out_graph.is_presigned() {
+                record.requestable_graphs.insert(
+                    peg_out_graph.peg_in_graph_id.clone(),
+                    peg_out_graph.id().clone(),
+                );
+            }
+        }
+
+        let mut declarations = vec![];
+        let valid_declarations = operator_liquidity
+            .iter()
+            .filter(|declaration| declaration.verify())
+            .cloned()
+            .collect::<Vec<_>>();
+        merge_operator_liquidity(&mut declarations, &valid_declarations);
+        for declaration in declarations {
+            operators
+                .entry(declaration.operator_public_key)
+                .or_insert_with(|| OperatorRecord::new(&declaration.operator_public_key))
+                .liquidity = Amount::from_sat(declaration.liquidity);
+        }
+
+        OperatorRegistry {
+            operators,
+            peg_in_amounts,
+        }
+    }
+
+    pub fn operator(&self, operator_public_key: &PublicKey) -> Option<&OperatorRecord> {
+        self.operators.get(operator_public_key)
+    }
+
+    pub fn operators(&self) -> impl Iterator<Item = &OperatorRecord> { self.operators.values() }
+
+    /// Chooses the operator fronting a peg-out of the peg-in graph. Only operators with a
+    /// presigned, unrequested peg-out graph for it and enough available liquidity qualify.
+    /// Returns the operator's record and the id of its peg-out graph.
+    pub fn select_operator(
+        &self,
+        peg_in_graph_id: &str,
+        policy: SelectionPolicy,
+    ) -> Result<(&OperatorRecord, &String), String> {
+        let amount = self
+            .peg_in_amounts
+            .get(peg_in_graph_id)
+            .ok_or(format!("Invalid graph id: {}", peg_in_graph_id))?;
+
+        let mut candidates = self
+            .operators
+            .values()
+            .filter(|record| record.available_liquidity() >= *amount)
+            .filter_map(|record| {
+                record
+                    .requestable_graphs
+                    .get(peg_in_graph_id)
+                    .map(|peg_out_graph_id| (record, peg_out_graph_id))
+            })
+            .collect::<Vec<_>>();
+        // Ties are broken by public key so that every client selects the same operator.
+        candidates.sort_by_key(|(record, _)| record.operator_public_key.to_bytes());
+
+        let selected = match policy {
+            SelectionPolicy::MostAvailableLiquidity => candidates
+                .into_iter()
+                .rev()
+                .max_by_key(|(record, _)| record.available_liquidity()),
+            SelectionPolicy::FewestOpenPegOuts => candidates
+                .into_iter()
+                .min_by_key(|(record, _)| record.open_peg_outs.len()),
+        };
+
+        selected.ok_or(format!(
+            "No operator with enough liquidity for {} can front a peg-out of peg-in graph {}",
+            amount, peg_in_graph_id
+        ))
+    }
+}
+
+// Value of the peg-in, which the operator fronts to the withdrawer and is reimbursed with.
+fn peg_in_amount(peg_in_graph: &PegInGraph) -> Amount {
+    peg_in_graph
+        .peg_in_confirm_transaction_ref()
+        .tx()
+        .output
+        .iter()
+        .map(|output| output.value)
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::{Amount, Network, PublicKey};
+
+    use super::{
+        super::super::{
+            contexts::{base::generate_keys_from_secret, operator::OperatorContext},
+            graphs::base::{OPERATOR_SECRET, VERIFIER_0_SECRET, VERIFIER_1_SECRET},
+        },
+        merge_operator_liquidity, OperatorLiquidity,
+    };
+
+    fn context() -> OperatorContext {
+        let n_of_n_public_keys: Vec<PublicKey> = [VERIFIER_0_SECRET, VERIFIER_1_SECRET]
+            .iter()
+            .map(|secret| generate_keys_from_secret(Network::Regtest, secret).2)
+            .collect();
+        OperatorContext::new(Network::Regtest, OPERATOR_SECRET, &n_of_n_public_keys)
+    }
+
+    #[test]
+    fn test_verify_operator_liquidity() {
+        let declaration = OperatorLiquidity::new(&context(), Amount::from_sat(1_000_000), 1);
+        assert!(declaration.verify());
+
+        let mut forged = declaration.clone();
+        forged.liquidity = 2_000_000;
+        assert!(!forged.verify());
+    }
+
+    #[test]
+    fn test_merge_keeps_latest_declaration() {
+        let context = context();
+        let first = OperatorLiquidity::new(&context, Amount::from_sat(1_000), 1);
+        let second = OperatorLiquidity::new(&context, Amount::from_sat(2_000), 2);
+
+        let mut declarations = vec![second.clone()];
+        merge_operator_liquidity(&mut declarations, &[first.clone()]);
+        assert_eq!(declarations, vec![second.clone()]);
+
+        let mut declarations = vec![first];
+        merge_operator_liquidity(&mut declarations, &[second.clone()]);
+        assert_eq!(declarations, vec![second]);
+    }
+}
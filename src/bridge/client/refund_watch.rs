@@ -0,0 +1,365 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::{Display, Formatter, Result as FmtResult},
+    mem::discriminant,
+};
+
+use super::super::{
+    constants::NUM_BLOCKS_PER_6_HOURS,
+    graphs::{base::BaseGraph, peg_in::PegInGraph},
+};
+
+/// Blocks before the refund unlocks from which the depositor is warned by default.
+pub const DEFAULT_REFUND_WARNING_BLOCKS: u32 = NUM_BLOCKS_PER_6_HOURS;
+
+/// Chain state of the transactions of a peg-in graph.
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub struct PegInChainState {
+    /// Height of the block that mined the peg-in deposit transaction.
+    pub deposit_block_height: Option<u32>,
+    pub confirm_confirmed: bool,
+    pub refund_confirmed: bool,
+    pub tip_height: u32,
+}
+
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub enum RefundWatchStatus {
+    DepositUnconfirmed,
+    /// The peg-in confirm transaction is not confirmed and the refund is still timelocked.
+    /// `blocks_left` blocks have to be mined before the refund can be broadcast for the block at
+    /// `unlock_height`.
+    RefundLocked {
+        unlock_height: u32,
+        blocks_left: u32,
+    },
+    /// The refund can be mined in the next block, the peg-in confirm transaction still competes
+    /// for the deposit.
+    RefundAvailable {
+        unlock_height: u32,
+    },
+    PegInComplete,
+    RefundComplete,
+}
+
+impl RefundWatchStatus {
+    pub fn of(peg_in_graph: &PegInGraph, state: &PegInChainState) -> Self {
+        let deposit_block_height = match state.deposit_block_height {
+            Some(deposit_block_height) => deposit_block_height,
+            None => return RefundWatchStatus::DepositUnconfirmed,
+        };
+        if state.confirm_confirmed {
+            return RefundWatchStatus::PegInComplete;
+        }
+        if state.refund_confirmed {
+            return RefundWatchStatus::RefundComplete;
+        }
+
+        let unlock_height = peg_in_graph.refund_unlock_height(deposit_block_height);
+        let next_block_height = state.tip_height + 1;
+        if next_block_height < unlock_height {
+            RefundWatchStatus::RefundLocked {
+                unlock_height,
+                blocks_left: unlock_height - next_block_height,
+            }
+        } else {
+            RefundWatchStatus::RefundAvailable { unlock_height }
+        }
+    }
+
+    pub fn is_final(&self) -> bool {
+        matches!(
+            self,
+            RefundWatchStatus::PegInComplete | RefundWatchStatus::RefundComplete
+        )
+    }
+}
+
+impl Display for RefundWatchStatus {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self {
+            RefundWatchStatus::DepositUnconfirmed => {
+                write!(f, "Peg-in deposit transaction not confirmed yet. Wait...")
+            }
+            RefundWatchStatus::RefundLocked {
+                unlock_height,
+                blocks_left,
+            } => write!(
+                f,
+                "Peg-in confirm transaction not confirmed yet, refund unlocks at height {} ({} blocks left). Wait...",
+                unlock_height, blocks_left
+            ),
+            RefundWatchStatus::RefundAvailable { unlock_height } => write!(
+                f,
+                "Peg-in timed out, refund unlocked at height {}. Broadcast refund transaction?",
+                unlock_height
+            ),
+            RefundWatchStatus::PegInComplete => write!(f, "Peg-in complete. Done."),
+            RefundWatchStatus::RefundComplete => {
+                write!(f, "Peg-in refund complete, funds reclaimed. Done.")
+            }
+        }
+    }
+}
+
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub enum RefundWatchEvent {
+    StatusChanged(RefundWatchStatus),
+    /// The refund unlocks within the warning window while the peg-in is not confirmed. Once it
+    /// unlocks, the deposit can be claimed by whichever of the refund and the peg-in confirm
+    /// transaction is mined first. `presigned` tells whether the federation presigned the peg-in
+    /// confirm transaction.
+    UnlockApproaching {
+        unlock_height: u32,
+        blocks_left: u32,
+        presigned: bool,
+    },
+    /// The refund should be broadcast now.
+    BroadcastRefund,
+}
+
+impl Display for RefundWatchEvent {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self {
+            RefundWatchEvent::StatusChanged(status) => write!(f, "{}", status),
+            RefundWatchEvent::UnlockApproaching {
+                unlock_height,
+                blocks_left,
+                presigned,
+            } => write!(
+                f,
+                "Refund unlocks at height {} in {} blocks and the peg-in is not confirmed ({}). From then on the deposit can be spent by either the refund or the peg-in confirm transaction.",
+                unlock_height,
+                blocks_left,
+                match presigned {
+                    true => "peg-in confirm presigned by the federation",
+                    false => "federation has not presigned the peg-in confirm",
+                }
+            ),
+            RefundWatchEvent::BroadcastRefund => write!(f, "Broadcasting refund transaction"),
+        }
+    }
+}
+
+/// Tracks the depositor's peg-in graphs across polls of the chain, reporting every status
+/// change once, warning once when a refund is about to unlock and requesting the refund
+/// broadcast when `auto_refund` is set.
+pub struct RefundWatcher {
+    pub warning_blocks: u32,
+    pub auto_refund: bool,
+    statuses: HashMap<String, RefundWatchStatus>,
+    warned_graph_ids: HashSet<String>,
+    refunded_graph_ids: HashSet<String>,
+}
+
+impl RefundWatcher {
+    pub fn new(warning_blocks: u32, auto_refund: bool) -> Self {
+        RefundWatcher {
+            warning_blocks,
+            auto_refund,
+            statuses: HashMap::new(),
+            warned_graph_ids: HashSet::new(),
+            refunded_graph_ids: HashSet::new(),
+        }
+    }
+
+    pub fn observe(
+        &mut self,
+        peg_in_graph: &PegInGraph,
+        state: &PegInChainState,
+    ) -> Vec<RefundWatchEvent> {
+        let graph_id = peg_in_graph.id();
+        let status = RefundWatchStatus::of(peg_in_graph, state);
+        let mut events = vec![];
+
+        // The remaining blocks of a locked refund are not reported on every new block.
+        let previous_status = self.statuses.insert(graph_id.clone(), status);
+        if previous_status.map_or(true, |previous| {
+            discriminant(&previous) != discriminant(&status)
+        }) {
+            events.push(RefundWatchEvent::StatusChanged(status));
+        }
+
+        match status {
+            RefundWatchStatus::RefundLocked {
+                unlock_height,
+                blocks_left,
+            } if blocks_left <= self.warning_blocks
+                && self.warned_graph_ids.insert(graph_id.clone()) =>
+            {
+                events.push(RefundWatchEvent::UnlockApproaching {
+                    unlock_height,
+                    blocks_left,
+                    presigned: peg_in_graph.is_presigned(),
+                })
+            }
+            RefundWatchStatus::RefundAvailable { .. }
+                if self.auto_refund && !self.refunded_graph_ids.contains(graph_id) =>
+            {
+                events.push(RefundWatchEvent::BroadcastRefund)
+            }
+            _ => {}
+        }
+
+        events
+    }
+
+    /// Stops requesting the refund broadcast for the graph, e.g. once it was accepted.
+    pub fn refund_broadcast(&mut self, graph_id: &str) {
+        self.refunded_graph_ids.insert(graph_id.to_string());
+    }
+
+    /// Whether every observed graph was either pegged in or refunded.
+    pub fn is_finished(&self) -> bool {
+        !self.statuses.is_empty() && self.statuses.values().all(RefundWatchStatus::is_final)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::{hashes::Hash, Amount, Network, OutPoint, PublicKey, Txid};
+
+    use super::{
+        super::super::{
+            contexts::{base::generate_keys_from_secret, depositor::DepositorContext},
+            graphs::{
+                base::{
+                    BaseGraph, DEPOSITOR_EVM_ADDRESS, DEPOSITOR_SECRET, INITIAL_AMOUNT,
+                    VERIFIER_0_SECRET, VERIFIER_1_SECRET,
+                },
+                peg_in::PegInGraph,
+            },
+            timelocks::TimelockConfig,
+            transactions::base::Input,
+        },
+        PegInChainState, RefundWatchEvent, RefundWatchStatus, RefundWatcher,
+    };
+
+    const NUM_BLOCKS_PEG_IN_REFUND: u32 = 10;
+
+    fn peg_in_graph() -> PegInGraph {
+        let network = Network::Regtest;
+        let n_of_n_public_keys: Vec<PublicKey> = [VERIFIER_0_SECRET, VERIFIER_1_SECRET]
+            .iter()
+            .map(|secret| generate_keys_from_secret(network, secret).2)
+            .collect();
+        let depositor_context =
+            DepositorContext::new(network, DEPOSITOR_SECRET, &n_of_n_public_keys);
+        let timelock_config = TimelockConfig {
+            num_blocks_peg_in_refund: NUM_BLOCKS_PEG_IN_REFUND,
+            ..TimelockConfig::regtest()
+        };
+
+        PegInGraph::new(
+            &depositor_context,
+            Input {
+                outpoint: OutPoint {
+                    txid: Txid::all_zeros(),
+                    vout: 0,
+                },
+                amount: Amount::from_sat(INITIAL_AMOUNT),
+            },
+            DEPOSITOR_EVM_ADDRESS,
+            &timelock_config,
+        )
+    }
+
+    fn deposited_at(deposit_block_height: u32, tip_height: u32) -> PegInChainState {
+        PegInChainState {
+            deposit_block_height: Some(deposit_block_height),
+            confirm_confirmed: false,
+            refund_confirmed: false,
+            tip_height,
+        }
+    }
+
+    #[test]
+    fn test_refund_unlock_height() {
+        let graph = peg_in_graph();
+        assert_eq!(
+            graph.refund_unlock_height(100),
+            100 + NUM_BLOCKS_PEG_IN_REFUND
+        );
+
+        assert_eq!(
+            RefundWatchStatus::of(&graph, &deposited_at(100, 108)),
+            RefundWatchStatus::RefundLocked {
+                unlock_height: 110,
+                blocks_left: 1
+            }
+        );
+        // The refund can be broadcast as soon as the next block may mine it.
+        assert_eq!(
+            RefundWatchStatus::of(&graph, &deposited_at(100, 109)),
+            RefundWatchStatus::RefundAvailable { unlock_height: 110 }
+        );
+
+        let state = PegInChainState {
+            confirm_confirmed: true,
+            ..deposited_at(100, 200)
+        };
+        assert_eq!(
+            RefundWatchStatus::of(&graph, &state),
+            RefundWatchStatus::PegInComplete
+        );
+        let state = PegInChainState {
+            deposit_block_height: None,
+            ..deposited_at(0, 200)
+        };
+        assert_eq!(
+            RefundWatchStatus::of(&graph, &state),
+            RefundWatchStatus::DepositUnconfirmed
+        );
+    }
+
+    #[test]
+    fn test_refund_watcher_warns_once_and_refunds() {
+        let graph = peg_in_graph();
+        let mut watcher = RefundWatcher::new(3, true);
+
+        let events = watcher.observe(&graph, &deposited_at(100, 100));
+        assert_eq!(
+            events,
+            vec![RefundWatchEvent::StatusChanged(
+                RefundWatchStatus::RefundLocked {
+                    unlock_height: 110,
+                    blocks_left: 9
+                }
+            )]
+        );
+        assert!(watcher.observe(&graph, &deposited_at(100, 100)).is_empty());
+
+        assert!(watcher.observe(&graph, &deposited_at(100, 101)).is_empty());
+
+        let events = watcher.observe(&graph, &deposited_at(100, 106));
+        assert_eq!(
+            events,
+            vec![RefundWatchEvent::UnlockApproaching {
+                unlock_height: 110,
+                blocks_left: 3,
+                presigned: false
+            }]
+        );
+        assert!(watcher.observe(&graph, &deposited_at(100, 107)).is_empty());
+
+        // The refund is requested on every poll until its broadcast succeeded.
+        for _ in 0..2 {
+            let events = watcher.observe(&graph, &deposited_at(100, 109));
+            assert_eq!(events.last(), Some(&RefundWatchEvent::BroadcastRefund));
+        }
+        watcher.refund_broadcast(graph.id());
+        assert!(watcher.observe(&graph, &deposited_at(100, 110)).is_empty());
+        assert!(!watcher.is_finished());
+
+        let state = PegInChainState {
+            refund_confirmed: true,
+            ..deposited_at(100, 111)
+        };
+        assert_eq!(
+            watcher.observe(&graph, &state),
+            vec![RefundWatchEvent::StatusChanged(
+                RefundWatchStatus::RefundComplete
+            )]
+        );
+        assert!(watcher.is_finished());
+    }
+}
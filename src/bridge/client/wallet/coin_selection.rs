@@ -0,0 +1,205 @@
+use bitcoin::{Amount, OutPoint};
+
+// Version, locktime, input and output counts and the segwit marker and flag.
+const TRANSACTION_OVERHEAD_VBYTES: u64 = 11;
+// Value and script length prefix of an output, without the script pubkey.
+const OUTPUT_OVERHEAD_VBYTES: u64 = 9;
+
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub struct WalletUtxo {
+    pub outpoint: OutPoint,
+    pub value: Amount,
+}
+
+/// Spending costs of the transaction being funded, all sizes in virtual bytes.
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub struct CoinSelectionParams {
+    /// Value of the payment output.
+    pub target: Amount,
+    pub fee_rate_sat_per_vb: u64,
+    /// Size of the transaction without inputs and without change output.
+    pub base_vbytes: u64,
+    /// Size of one wallet input.
+    pub input_vbytes: u64,
+    pub change_output_vbytes: u64,
+    /// Smallest change output that is not dust, smaller change is added to the fee.
+    pub change_dust_limit: Amount,
+}
+
+impl CoinSelectionParams {
+    pub fn new(
+        target: Amount,
+        fee_rate_sat_per_vb: u64,
+        payment_script_pubkey_len: usize,
+        input_vbytes: u64,
+        change_script_pubkey_len: usize,
+        change_dust_limit: Amount,
+    ) -> Self {
+        CoinSelectionParams {
+            target,
+            fee_rate_sat_per_vb,
+            base_vbytes: TRANSACTION_OVERHEAD_VBYTES + output_vbytes(payment_script_pubkey_len),
+            input_vbytes,
+            change_output_vbytes: output_vbytes(change_script_pubkey_len),
+            change_dust_limit,
+        }
+    }
+
+    fn fee(&self, vbytes: u64) -> Amount { Amount::from_sat(vbytes * self.fee_rate_sat_per_vb) }
+
+    fn fee_without_change(&self, input_count: usize) -> Amount {
+        self.fee(self.base_vbytes + input_count as u64 * self.input_vbytes)
+    }
+
+    fn fee_with_change(&self, input_count: usize) -> Amount {
+        self.fee_without_change(input_count) + self.fee(self.change_output_vbytes)
+    }
+}
+
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub struct CoinSelection {
+    pub inputs: Vec<WalletUtxo>,
+    pub fee: Amount,
+    pub change: Option<Amount>,
+}
+
+pub fn output_vbytes(script_pubkey_len: usize) -> u64 {
+    OUTPUT_OVERHEAD_VBYTES + script_pubkey_len as u64
+}
+
+/// Selects the wallet UTXOs funding `params.target`.
+///
+/// A single UTXO that covers the target without leaving more than the cost of a change output
+/// is preferred, so that no change is created. Otherwise UTXOs are added largest first until the
+/// target, fees and change are covered. Change below the dust limit is added to the fee. UTXOs
+/// worth less than the fee of spending them are never selected.
+pub fn select_coins(
+    utxos: &[WalletUtxo],
+    params: &CoinSelectionParams,
+) -> Result<CoinSelection, String> {
+    let input_fee = params.fee(params.input_vbytes);
+    let mut candidates = utxos
+        .iter()
+        .filter(|utxo| utxo.value > input_fee)
+        .copied()
+        .collect::<Vec<_>>();
+    candidates.sort_by(|a, b| b.value.cmp(&a.value));
+
+    let changeless_target = params.target + params.fee_without_change(1);
+    let cost_of_change = params.fee(params.change_output_vbytes) + params.change_dust_limit;
+    if let Some(utxo) = candidates.iter().rev().find(|utxo| {
+        utxo.value >= changeless_target && utxo.value - changeless_target < cost_of_change
+    }) {
+        return Ok(CoinSelection {
+            inputs: vec![*utxo],
+            fee: utxo.value - params.target,
+            change: None,
+        });
+    }
+
+    let mut selected = vec![];
+    let mut total = Amount::ZERO;
+    for utxo in candidates {
+        selected.push(utxo);
+        total += utxo.value;
+
+        let fee_with_change = params.fee_with_change(selected.len());
+        if total >= params.target + fee_with_change + params.change_dust_limit {
+            let change = total - params.target - fee_with_change;
+            return Ok(CoinSelection {
+                inputs: selected,
+                fee: fee_with_change,
+                change: Some(change),
+            });
+        }
+        if total >= params.target + params.fee_without_change(selected.len()) {
+            return Ok(CoinSelection {
+                inputs: selected,
+                fee: total - params.target,
+                change: None,
+            });
+        }
+    }
+
+    Err(format!(
+        "Insufficient funds: {} required plus fees, {} spendable",
+        params.target, total
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::{hashes::Hash, Amount, OutPoint, Txid};
+
+    use super::{select_coins, CoinSelectionParams, WalletUtxo};
+
+    fn utxo(vout: u32, value: u64) -> WalletUtxo {
+        WalletUtxo {
+            outpoint: OutPoint {
+                txid: Txid::all_zeros(),
+                vout,
+            },
+            value: Amount::from_sat(value),
+        }
+    }
+
+    // 1 sat/vB, p2wsh payment and change outputs: 54 vbytes base, 69 vbytes per input, 43
+    // vbytes change output, 330 sats dust limit.
+    fn params(target: u64) -> CoinSelectionParams {
+        CoinSelectionParams::new(
+            Amount::from_sat(target),
+            1,
+            34,
+            69,
+            34,
+            Amount::from_sat(330),
+        )
+    }
+
+    #[test]
+    fn test_select_changeless_utxo() {
+        let utxos = [utxo(0, 200_000), utxo(1, 100_200), utxo(2, 100_150)];
+        let selection = select_coins(&utxos, &params(100_000)).unwrap();
+
+        assert_eq!(selection.inputs, vec![utxos[2]]);
+        assert_eq!(selection.fee, Amount::from_sat(150));
+        assert_eq!(selection.change, None);
+    }
+
+    #[test]
+    fn test_select_multiple_utxos_with_change() {
+        let utxos = [utxo(0, 30_000), utxo(1, 60_000), utxo(2, 50_000)];
+        let selection = select_coins(&utxos, &params(100_000)).unwrap();
+
+        assert_eq!(selection.inputs, vec![utxos[1], utxos[2]]);
+        // 54 + 2 * 69 + 43 vbytes
+        assert_eq!(selection.fee, Amount::from_sat(235));
+        assert_eq!(selection.change, Some(Amount::from_sat(9_765)));
+    }
+
+    #[test]
+    fn test_dust_change_is_added_to_fee() {
+        let utxos = [utxo(0, 60_000), utxo(1, 40_300)];
+        let selection = select_coins(&utxos, &params(100_000)).unwrap();
+
+        assert_eq!(selection.inputs.len(), 2);
+        assert_eq!(selection.fee, Amount::from_sat(300));
+        assert_eq!(selection.change, None);
+    }
+
+    #[test]
+    fn test_skip_uneconomical_utxos() {
+        let utxos = [utxo(0, 100_123), utxo(1, 69)];
+        let selection = select_coins(&utxos, &params(100_000)).unwrap();
+
+        assert_eq!(selection.inputs, vec![utxos[0]]);
+    }
+
+    #[test]
+    fn test_insufficient_funds() {
+        let utxos = [utxo(0, 60_000), utxo(1, 40_000)];
+        assert!(select_coins(&utxos, &params(100_000))
+            .is_err_and(|err| err.contains("Insufficient funds")));
+        assert!(select_coins(&[], &params(1_000)).is_err());
+    }
+}
@@ -0,0 +1,257 @@
+use bitcoin::{
+    key::Secp256k1, Address, CompressedPublicKey, Network, PublicKey, ScriptBuf, XOnlyPublicKey,
+};
+use core::fmt;
+use std::str::FromStr;
+
+use super::super::super::scripts::{
+    generate_pay_to_pubkey_script, generate_pay_to_pubkey_script_address,
+};
+
+// Character set and generator of the descriptor checksum, see BIP 380.
+const INPUT_CHARSET: &str = "0123456789()[],'/*abcdefgh@:$%{}IJKLMNOPQRSTUVWXYZ&+-.;<=>?!^_|~ijklmnopqrstuvwxyzABCDEFGH`#\"\\ ";
+const CHECKSUM_CHARSET: &[u8] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+const CHECKSUM_GENERATOR: [u64; 5] = [
+    0xf5dee51989,
+    0xa9fdca3312,
+    0x1bab10e32d,
+    0x3706b1677a,
+    0x644d626ffd,
+];
+
+// Virtual size of an input spending each descriptor type, signed with a 72 byte DER signature
+// for ECDSA and a 64 byte default sighash signature for schnorr.
+const P2WPKH_INPUT_VBYTES: u64 = 68;
+const P2WSH_PAY_TO_PUBKEY_INPUT_VBYTES: u64 = 69;
+const P2TR_KEY_SPEND_INPUT_VBYTES: u64 = 58;
+
+/// Single key output descriptors the funding wallet can spend from.
+///
+/// `wsh(pk(KEY))` pays to the same address as the depositor and operator graph inputs, so coins
+/// received there can be used directly when their value matches.
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub enum WalletDescriptor {
+    Wpkh(PublicKey),
+    WshPk(PublicKey),
+    Tr(XOnlyPublicKey),
+}
+
+impl WalletDescriptor {
+    /// Descriptor of the output type the graph inputs of `public_key` are locked to.
+    pub fn for_graph_inputs(public_key: &PublicKey) -> Self { WalletDescriptor::WshPk(*public_key) }
+
+    pub fn address(&self, network: Network) -> Address {
+        match self {
+            WalletDescriptor::Wpkh(public_key) => Address::p2wpkh(
+                &CompressedPublicKey::try_from(*public_key).expect("Could not compress public key"),
+                network,
+            ),
+            WalletDescriptor::WshPk(public_key) => {
+                generate_pay_to_pubkey_script_address(network, public_key)
+            }
+            WalletDescriptor::Tr(internal_key) => Address::p2tr(
+                &Secp256k1::verification_only(),
+                *internal_key,
+                None,
+                network,
+            ),
+        }
+    }
+
+    pub fn script_pubkey(&self) -> ScriptBuf {
+        // The script pubkey does not depend on the network.
+        self.address(Network::Bitcoin).script_pubkey()
+    }
+
+    /// Witness script of `wsh` descriptors.
+    pub fn witness_script(&self) -> Option<ScriptBuf> {
+        match self {
+            WalletDescriptor::WshPk(public_key) => Some(generate_pay_to_pubkey_script(public_key)),
+            _ => None,
+        }
+    }
+
+    /// Virtual size of a signed input spending an output of this descriptor.
+    pub fn input_vbytes(&self) -> u64 {
+        match self {
+            WalletDescriptor::Wpkh(_) => P2WPKH_INPUT_VBYTES,
+            WalletDescriptor::WshPk(_) => P2WSH_PAY_TO_PUBKEY_INPUT_VBYTES,
+            WalletDescriptor::Tr(_) => P2TR_KEY_SPEND_INPUT_VBYTES,
+        }
+    }
+
+    /// Whether the descriptor's key is the one of `public_key`.
+    pub fn is_owned_by(&self, public_key: &PublicKey) -> bool {
+        match self {
+            WalletDescriptor::Wpkh(key) | WalletDescriptor::WshPk(key) => key == public_key,
+            WalletDescriptor::Tr(key) => *key == XOnlyPublicKey::from(public_key.inner),
+        }
+    }
+
+    fn descriptor_string(&self) -> String {
+        match self {
+            WalletDescriptor::Wpkh(public_key) => format!("wpkh({})", public_key),
+            WalletDescriptor::WshPk(public_key) => format!("wsh(pk({}))", public_key),
+            WalletDescriptor::Tr(internal_key) => format!("tr({})", internal_key),
+        }
+    }
+}
+
+impl fmt::Display for WalletDescriptor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let descriptor = self.descriptor_string();
+        let checksum = descriptor_checksum(&descriptor).unwrap();
+        write!(f, "{}#{}", descriptor, checksum)
+    }
+}
+
+impl FromStr for WalletDescriptor {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let descriptor = match s.split_once('#') {
+            Some((descriptor, checksum)) => {
+                if descriptor_checksum(descriptor)? != checksum {
+                    return Err(format!("Invalid descriptor checksum: {}", s));
+                }
+                descriptor
+            }
+            None => s,
+        };
+
+        if let Some(key) = unwrap_function(descriptor, "wpkh") {
+            let public_key = parse_compressed_key(key)?;
+            return Ok(WalletDescriptor::Wpkh(public_key));
+        }
+        if let Some(key) =
+            unwrap_function(descriptor, "wsh").and_then(|inner| unwrap_function(inner, "pk"))
+        {
+            let public_key = parse_compressed_key(key)?;
+            return Ok(WalletDescriptor::WshPk(public_key));
+        }
+        if let Some(key) = unwrap_function(descriptor, "tr") {
+            let internal_key = match XOnlyPublicKey::from_str(key) {
+                Ok(internal_key) => internal_key,
+                Err(_) => XOnlyPublicKey::from(parse_compressed_key(key)?.inner),
+            };
+            return Ok(WalletDescriptor::Tr(internal_key));
+        }
+
+        Err(format!(
+            "Unsupported descriptor, expected wpkh(KEY), wsh(pk(KEY)) or tr(KEY): {}",
+            s
+        ))
+    }
+}
+
+fn unwrap_function<'a>(s: &'a str, name: &str) -> Option<&'a str> {
+    s.strip_prefix(name)?.strip_prefix('(')?.strip_suffix(')')
+}
+
+fn parse_compressed_key(key: &str) -> Result<PublicKey, String> {
+    let public_key = PublicKey::from_str(key)
+        .map_err(|err| format!("Invalid descriptor key {}: {}", key, err))?;
+    if !public_key.compressed {
+        return Err(format!("Descriptor key must be compressed: {}", key));
+    }
+    Ok(public_key)
+}
+
+fn checksum_polymod(symbols: &[u64]) -> u64 {
+    let mut checksum = 1;
+    for value in symbols {
+        let top = checksum >> 35;
+        checksum = ((checksum & 0x7ffffffff) << 5) ^ value;
+        for (i, generator) in CHECKSUM_GENERATOR.iter().enumerate() {
+            if (top >> i) & 1 == 1 {
+                checksum ^= generator;
+            }
+        }
+    }
+    checksum
+}
+
+/// Computes the BIP 380 checksum of a descriptor without its `#checksum` suffix.
+pub fn descriptor_checksum(descriptor: &str) -> Result<String, String> {
+    let mut symbols = vec![];
+    let mut groups = vec![];
+    for c in descriptor.chars() {
+        let value = INPUT_CHARSET
+            .find(c)
+            .ok_or_else(|| format!("Invalid character in descriptor: {:?}", c))?
+            as u64;
+        symbols.push(value & 31);
+        groups.push(value >> 5);
+        if groups.len() == 3 {
+            symbols.push(groups[0] * 9 + groups[1] * 3 + groups[2]);
+            groups.clear();
+        }
+    }
+    match groups.len() {
+        1 => symbols.push(groups[0]),
+        2 => symbols.push(groups[0] * 3 + groups[1]),
+        _ => {}
+    }
+    symbols.extend([0; 8]);
+
+    let checksum = checksum_polymod(&symbols) ^ 1;
+    Ok((0..8)
+        .map(|i| CHECKSUM_CHARSET[((checksum >> (5 * (7 - i))) & 31) as usize] as char)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::{Network, PublicKey};
+    use std::str::FromStr;
+
+    use super::{
+        super::super::super::scripts::generate_pay_to_pubkey_script_address, descriptor_checksum,
+        WalletDescriptor,
+    };
+
+    const PUBLIC_KEY: &str = "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+
+    #[test]
+    fn test_descriptor_checksum() {
+        // Test vector from Bitcoin Core's descriptor tests
+        assert_eq!(descriptor_checksum("raw(deadbeef)").unwrap(), "89f8spxm");
+        assert!(descriptor_checksum("wpkh(é)").is_err());
+    }
+
+    #[test]
+    fn test_parse_descriptors() {
+        let public_key = PublicKey::from_str(PUBLIC_KEY).unwrap();
+
+        let descriptor = WalletDescriptor::from_str(&format!("wpkh({})", PUBLIC_KEY)).unwrap();
+        assert_eq!(descriptor, WalletDescriptor::Wpkh(public_key));
+        assert_eq!(
+            descriptor.to_string(),
+            format!("wpkh({})#ucxz0gak", PUBLIC_KEY)
+        );
+
+        let descriptor =
+            WalletDescriptor::from_str(&format!("wsh(pk({}))#fdpqq2al", PUBLIC_KEY)).unwrap();
+        assert_eq!(descriptor, WalletDescriptor::for_graph_inputs(&public_key));
+        assert_eq!(
+            descriptor.address(Network::Regtest),
+            generate_pay_to_pubkey_script_address(Network::Regtest, &public_key)
+        );
+
+        let descriptor =
+            WalletDescriptor::from_str(&format!("tr({})#gxjkeue2", &PUBLIC_KEY[2..])).unwrap();
+        assert!(descriptor.is_owned_by(&public_key));
+        assert!(descriptor.script_pubkey().is_p2tr());
+        assert_eq!(
+            WalletDescriptor::from_str(&format!("tr({})", PUBLIC_KEY)).unwrap(),
+            descriptor
+        );
+    }
+
+    #[test]
+    fn test_reject_invalid_descriptors() {
+        assert!(WalletDescriptor::from_str(&format!("wpkh({})#ucxz0gal", PUBLIC_KEY)).is_err());
+        assert!(WalletDescriptor::from_str(&format!("pkh({})", PUBLIC_KEY)).is_err());
+        assert!(WalletDescriptor::from_str("wpkh(02deadbeef)").is_err());
+    }
+}
@@ -0,0 +1,3 @@
+pub mod coin_selection;
+pub mod descriptor;
+pub mod wallet;
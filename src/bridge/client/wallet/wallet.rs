@@ -0,0 +1,367 @@
+use bitcoin::{
+    absolute, key::Keypair, transaction::Version, Amount, EcdsaSighashType, OutPoint, PublicKey,
+    ScriptBuf, Sequence, TapSighashType, Transaction, TxIn, TxOut, Witness,
+};
+use esplora_client::AsyncClient;
+use std::collections::HashSet;
+
+use super::{
+    super::super::{
+        contexts::base::BaseContext,
+        transactions::{
+            base::Input,
+            signing::{
+                populate_p2wpkh_witness, populate_p2wsh_witness, populate_taproot_key_spend_witness,
+            },
+        },
+    },
+    coin_selection::{select_coins, CoinSelectionParams, WalletUtxo},
+    descriptor::WalletDescriptor,
+};
+
+/// Graph input together with the transaction creating it, if it did not exist yet.
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub struct FundedInput {
+    pub input: Input,
+    pub funding_transaction: Option<Transaction>,
+}
+
+/// UTXOs of the wallet that coin selection may use.
+#[derive(Eq, PartialEq, Clone, Debug, Default)]
+pub struct UtxoFilter {
+    /// Outpoints already spent by graphs in the client data, which are not broadcast yet.
+    pub reserved_outpoints: HashSet<OutPoint>,
+    /// Also use UTXOs of unconfirmed transactions, which may still be replaced or dropped.
+    pub include_unconfirmed: bool,
+}
+
+impl UtxoFilter {
+    pub fn allows(&self, outpoint: &OutPoint, confirmed: bool) -> bool {
+        (confirmed || self.include_unconfirmed) && !self.reserved_outpoints.contains(outpoint)
+    }
+}
+
+/// Single key wallet funding the depositor and operator inputs of the graphs.
+pub struct FundingWallet {
+    descriptor: WalletDescriptor,
+    keypair: Keypair,
+    public_key: PublicKey,
+}
+
+impl FundingWallet {
+    pub fn new(descriptor: WalletDescriptor, keypair: &Keypair) -> Result<Self, String> {
+        let public_key = PublicKey::new(keypair.public_key());
+        if !descriptor.is_owned_by(&public_key) {
+            return Err(format!(
+                "Descriptor {} does not belong to key {}",
+                descriptor, public_key
+            ));
+        }
+
+        Ok(FundingWallet {
+            descriptor,
+            keypair: *keypair,
+            public_key,
+        })
+    }
+
+    pub fn descriptor(&self) -> &WalletDescriptor { &self.descriptor }
+
+    pub async fn list_utxos(
+        &self,
+        context: &dyn BaseContext,
+        esplora: &AsyncClient,
+        filter: &UtxoFilter,
+    ) -> Result<Vec<WalletUtxo>, String> {
+        let address = self.descriptor.address(context.network());
+        let utxos = esplora
+            .get_address_utxo(address.clone())
+            .await
+            .map_err(|err| format!("Could not fetch UTXOs of {}: {}", address, err))?;
+
+        Ok(utxos
+            .into_iter()
+            .filter(|utxo| {
+                filter.allows(
+                    &OutPoint {
+                        txid: utxo.txid,
+                        vout: utxo.vout,
+                    },
+                    utxo.status.confirmed,
+                )
+            })
+            .map(|utxo| WalletUtxo {
+                outpoint: OutPoint {
+                    txid: utxo.txid,
+                    vout: utxo.vout,
+                },
+                value: utxo.value,
+            })
+            .collect())
+    }
+
+    /// Provides an output of exactly `amount` locked to `script_pubkey`. An existing wallet UTXO
+    /// is used as is when it already is such an output, otherwise a signed funding transaction
+    /// paying it in output 0 is created from the wallet's UTXOs. Only UTXOs allowed by `filter`
+    /// are considered. The caller broadcasts the funding transaction.
+    pub async fn fund_input(
+        &self,
+        context: &dyn BaseContext,
+        esplora: &AsyncClient,
+        filter: &UtxoFilter,
+        script_pubkey: &ScriptBuf,
+        amount: Amount,
+        fee_rate_sat_per_vb: u64,
+    ) -> Result<FundedInput, String> {
+        let utxos = self.list_utxos(context, esplora, filter).await?;
+        if self.descriptor.script_pubkey() == *script_pubkey {
+            if let Some(utxo) = utxos.iter().find(|utxo| utxo.value == amount) {
+                return Ok(FundedInput {
+                    input: Input {
+                        outpoint: utxo.outpoint,
+                        amount,
+                    },
+                    funding_transaction: None,
+                });
+            }
+        }
+
+        let funding_transaction = self.create_funding_transaction(
+            context,
+            &utxos,
+            script_pubkey,
+            amount,
+            fee_rate_sat_per_vb,
+        )?;
+
+        Ok(FundedInput {
+            input: Input {
+                outpoint: OutPoint {
+                    txid: funding_transaction.compute_txid(),
+                    vout: 0,
+                },
+                amount,
+            },
+            funding_transaction: Some(funding_transaction),
+        })
+    }
+
+    /// Creates and signs a transaction paying `amount` to `script_pubkey` in output 0, with the
+    /// change returning to the wallet in output 1.
+    pub fn create_funding_transaction(
+        &self,
+        context: &dyn BaseContext,
+        utxos: &[WalletUtxo],
+        script_pubkey: &ScriptBuf,
+        amount: Amount,
+        fee_rate_sat_per_vb: u64,
+    ) -> Result<Transaction, String> {
+        if amount < script_pubkey.minimal_non_dust() {
+            return Err(format!("Cannot fund a dust output of {}", amount));
+        }
+
+        let change_script_pubkey = self.descriptor.script_pubkey();
+        let selection = select_coins(
+            utxos,
+            &CoinSelectionParams::new(
+                amount,
+                fee_rate_sat_per_vb,
+                script_pubkey.len(),
+                self.descriptor.input_vbytes(),
+                change_script_pubkey.len(),
+                change_script_pubkey.minimal_non_dust(),
+            ),
+        )?;
+
+        let mut output = vec![TxOut {
+            value: amount,
+            script_pubkey: script_pubkey.clone(),
+        }];
+        if let Some(change) = selection.change {
+            output.push(TxOut {
+                value: change,
+                script_pubkey: change_script_pubkey.clone(),
+            });
+        }
+
+        let mut tx = Transaction {
+            version: Version(2),
+            lock_time: absolute::LockTime::ZERO,
+            input: selection
+                .inputs
+                .iter()
+                .map(|utxo| TxIn {
+                    previous_output: utxo.outpoint,
+                    script_sig: ScriptBuf::new(),
+                    sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+                    witness: Witness::default(),
+                })
+                .collect(),
+            output,
+        };
+
+        let prevouts = selection
+            .inputs
+            .iter()
+            .map(|utxo| TxOut {
+                value: utxo.value,
+                script_pubkey: change_script_pubkey.clone(),
+            })
+            .collect::<Vec<_>>();
+        for (input_index, prevout) in prevouts.iter().enumerate() {
+            self.sign_input(context, &mut tx, &prevouts, input_index, prevout.value);
+        }
+
+        Ok(tx)
+    }
+
+    fn sign_input(
+        &self,
+        context: &dyn BaseContext,
+        tx: &mut Transaction,
+        prevouts: &Vec<TxOut>,
+        input_index: usize,
+        value: Amount,
+    ) {
+        match self.descriptor {
+            WalletDescriptor::Wpkh(_) => populate_p2wpkh_witness(
+                context,
+                tx,
+                input_index,
+                EcdsaSighashType::All,
+                value,
+                &self.public_key,
+                &self.keypair,
+            ),
+            WalletDescriptor::WshPk(_) => populate_p2wsh_witness(
+                context,
+                tx,
+                input_index,
+                EcdsaSighashType::All,
+                &self.descriptor.witness_script().unwrap(),
+                value,
+                &vec![&self.keypair],
+            ),
+            WalletDescriptor::Tr(_) => populate_taproot_key_spend_witness(
+                context,
+                tx,
+                prevouts,
+                input_index,
+                TapSighashType::Default,
+                &self.keypair,
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::{hashes::Hash, Amount, Network, OutPoint, PublicKey, Txid};
+    use std::collections::HashSet;
+
+    use super::{
+        super::{
+            super::super::{
+                contexts::{base::generate_keys_from_secret, depositor::DepositorContext},
+                scripts::generate_pay_to_pubkey_script_address,
+            },
+            coin_selection::WalletUtxo,
+            descriptor::WalletDescriptor,
+        },
+        FundingWallet, UtxoFilter,
+    };
+
+    const SECRET: &str = "b8f17ea979be24199e7c3fec71ee88914d92fd4ca508443f765d56ce024ef1d7";
+
+    fn context() -> DepositorContext {
+        let (_, _, public_key) = generate_keys_from_secret(Network::Regtest, SECRET);
+        DepositorContext::new(Network::Regtest, SECRET, &vec![public_key])
+    }
+
+    fn utxos() -> Vec<WalletUtxo> {
+        [70_000, 50_000]
+            .iter()
+            .enumerate()
+            .map(|(vout, value)| WalletUtxo {
+                outpoint: OutPoint {
+                    txid: Txid::all_zeros(),
+                    vout: vout as u32,
+                },
+                value: Amount::from_sat(*value),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_create_funding_transaction() {
+        let context = context();
+        let public_key = context.depositor_public_key;
+        let script_pubkey =
+            generate_pay_to_pubkey_script_address(Network::Regtest, &public_key).script_pubkey();
+
+        for descriptor in [
+            WalletDescriptor::Wpkh(public_key),
+            WalletDescriptor::for_graph_inputs(&public_key),
+            WalletDescriptor::Tr(public_key.inner.x_only_public_key().0),
+        ] {
+            let wallet = FundingWallet::new(descriptor, &context.depositor_keypair).unwrap();
+            let tx = wallet
+                .create_funding_transaction(
+                    &context,
+                    &utxos(),
+                    &script_pubkey,
+                    Amount::from_sat(100_000),
+                    2,
+                )
+                .unwrap();
+
+            assert_eq!(tx.input.len(), 2);
+            assert!(tx.input.iter().all(|input| !input.witness.is_empty()));
+            assert_eq!(tx.output[0].value, Amount::from_sat(100_000));
+            assert_eq!(tx.output[0].script_pubkey, script_pubkey);
+            assert_eq!(tx.output[1].script_pubkey, descriptor.script_pubkey());
+
+            let fee = Amount::from_sat(120_000) - tx.output[0].value - tx.output[1].value;
+            // Input sizes are estimated, ECDSA signatures vary by a byte.
+            assert!(fee.to_sat() >= 2 * (tx.vsize() as u64 - 1));
+            assert!(fee.to_sat() <= 2 * (tx.vsize() as u64 + 2));
+        }
+    }
+
+    #[test]
+    fn test_utxo_filter() {
+        let [reserved, free] = [0, 1].map(|vout| OutPoint {
+            txid: Txid::all_zeros(),
+            vout,
+        });
+        let mut filter = UtxoFilter {
+            reserved_outpoints: HashSet::from([reserved]),
+            include_unconfirmed: false,
+        };
+
+        assert!(filter.allows(&free, true));
+        assert!(!filter.allows(&free, false));
+        assert!(!filter.allows(&reserved, true));
+
+        filter.include_unconfirmed = true;
+        assert!(filter.allows(&free, false));
+        assert!(!filter.allows(&reserved, false));
+    }
+
+    #[test]
+    fn test_reject_foreign_descriptor() {
+        let context = context();
+        let other_key = PublicKey::from_slice(&[
+            0x02, 0x79, 0xbe, 0x66, 0x7e, 0xf9, 0xdc, 0xbb, 0xac, 0x55, 0xa0, 0x62, 0x95, 0xce,
+            0x87, 0x0b, 0x07, 0x02, 0x9b, 0xfc, 0xdb, 0x2d, 0xce, 0x28, 0xd9, 0x59, 0xf2, 0x81,
+            0x5b, 0x16, 0xf8, 0x17, 0x98,
+        ])
+        .unwrap();
+
+        assert!(FundingWallet::new(
+            WalletDescriptor::Wpkh(other_key),
+            &context.depositor_keypair
+        )
+        .is_err());
+    }
+}
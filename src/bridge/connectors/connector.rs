@@ -1,4 +1,5 @@
 use bitcoin::{taproot::TaprootSpendInfo, Address, ScriptBuf, Sequence, TxIn, Witness};
+use once_cell::sync::OnceCell;
 
 use super::super::transactions::base::Input;
 
@@ -34,3 +35,20 @@ pub trait TaprootConnector {
 
     fn generate_taproot_address(&self) -> Address;
 }
+
+// The taproot tree of a connector only depends on its parameters. It is built the first time it is
+// needed, and neither serialized nor compared.
+#[derive(Clone, Default)]
+pub struct TaprootSpendInfoCache(OnceCell<TaprootSpendInfo>);
+
+impl TaprootSpendInfoCache {
+    pub fn get_or_init(&self, init: impl FnOnce() -> TaprootSpendInfo) -> TaprootSpendInfo {
+        self.0.get_or_init(init).clone()
+    }
+}
+
+impl PartialEq for TaprootSpendInfoCache {
+    fn eq(&self, _: &Self) -> bool { true }
+}
+
+impl Eq for TaprootSpendInfoCache {}
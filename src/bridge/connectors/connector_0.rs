@@ -14,6 +14,8 @@ use super::{
 pub struct Connector0 {
     pub network: Network,
     pub n_of_n_taproot_public_key: XOnlyPublicKey,
+    #[serde(skip)]
+    taproot_spend_info: TaprootSpendInfoCache,
 }
 
 impl Connector0 {
@@ -21,6 +23,7 @@ impl Connector0 {
         Connector0 {
             network,
             n_of_n_taproot_public_key: n_of_n_taproot_public_key.clone(),
+            taproot_spend_info: TaprootSpendInfoCache::default(),
         }
     }
 
@@ -55,13 +58,15 @@ impl TaprootConnector for Connector0 {
     }
 
     fn generate_taproot_spend_info(&self) -> TaprootSpendInfo {
-        TaprootBuilder::new()
-            .add_leaf(1, self.generate_taproot_leaf_0_script())
-            .expect("Unable to add leaf 0")
-            .add_leaf(1, self.generate_taproot_leaf_1_script())
-            .expect("Unable to add leaf 1")
-            .finalize(&Secp256k1::new(), self.n_of_n_taproot_public_key)
-            .expect("Unable to finalize taproot")
+        self.taproot_spend_info.get_or_init(|| {
+            TaprootBuilder::new()
+                .add_leaf(1, self.generate_taproot_leaf_0_script())
+                .expect("Unable to add leaf 0")
+                .add_leaf(1, self.generate_taproot_leaf_1_script())
+                .expect("Unable to add leaf 1")
+                .finalize(&Secp256k1::new(), self.n_of_n_taproot_public_key)
+                .expect("Unable to finalize taproot")
+        })
     }
 
     fn generate_taproot_address(&self) -> Address {
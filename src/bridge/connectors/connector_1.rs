@@ -6,12 +6,7 @@ use bitcoin::{
 use serde::{Deserialize, Serialize};
 
 use super::{
-    super::{
-        constants::{NUM_BLOCKS_PER_2_WEEKS, NUM_BLOCKS_PER_6_HOURS, NUM_BLOCKS_PER_DAY},
-        scripts::*,
-        transactions::base::Input,
-        utils::num_blocks_per_network,
-    },
+    super::{scripts::*, timelocks::TimelockConfig, transactions::base::Input},
     connector::*,
 };
 
@@ -23,6 +18,8 @@ pub struct Connector1 {
     pub num_blocks_timelock_0: u32,
     pub num_blocks_timelock_1: u32,
     pub num_blocks_timelock_2: u32,
+    #[serde(skip)]
+    taproot_spend_info: TaprootSpendInfoCache,
 }
 
 impl Connector1 {
@@ -30,17 +27,16 @@ impl Connector1 {
         network: Network,
         operator_taproot_public_key: &XOnlyPublicKey,
         n_of_n_taproot_public_key: &XOnlyPublicKey,
+        timelock_config: &TimelockConfig,
     ) -> Self {
         Connector1 {
             network,
             operator_taproot_public_key: operator_taproot_public_key.clone(),
             n_of_n_taproot_public_key: n_of_n_taproot_public_key.clone(),
-            num_blocks_timelock_0: num_blocks_per_network(network, NUM_BLOCKS_PER_2_WEEKS),
-            num_blocks_timelock_1: num_blocks_per_network(
-                network,
-                NUM_BLOCKS_PER_2_WEEKS + NUM_BLOCKS_PER_DAY,
-            ),
-            num_blocks_timelock_2: num_blocks_per_network(network, NUM_BLOCKS_PER_6_HOURS),
+            num_blocks_timelock_0: timelock_config.num_blocks_kick_off_2,
+            num_blocks_timelock_1: timelock_config.num_blocks_kick_off_timeout,
+            num_blocks_timelock_2: timelock_config.num_blocks_start_time_timeout,
+            taproot_spend_info: TaprootSpendInfoCache::default(),
         }
     }
 
@@ -98,15 +94,17 @@ impl TaprootConnector for Connector1 {
     }
 
     fn generate_taproot_spend_info(&self) -> TaprootSpendInfo {
-        TaprootBuilder::new()
-            .add_leaf(2, self.generate_taproot_leaf_0_script())
-            .expect("Unable to add leaf 0")
-            .add_leaf(2, self.generate_taproot_leaf_1_script())
-            .expect("Unable to add leaf 1")
-            .add_leaf(1, self.generate_taproot_leaf_2_script())
-            .expect("Unable to add leaf 2")
-            .finalize(&Secp256k1::new(), self.n_of_n_taproot_public_key)
-            .expect("Unable to finalize taproot")
+        self.taproot_spend_info.get_or_init(|| {
+            TaprootBuilder::new()
+                .add_leaf(2, self.generate_taproot_leaf_0_script())
+                .expect("Unable to add leaf 0")
+                .add_leaf(2, self.generate_taproot_leaf_1_script())
+                .expect("Unable to add leaf 1")
+                .add_leaf(1, self.generate_taproot_leaf_2_script())
+                .expect("Unable to add leaf 2")
+                .finalize(&Secp256k1::new(), self.n_of_n_taproot_public_key)
+                .expect("Unable to finalize taproot")
+        })
     }
 
     fn generate_taproot_address(&self) -> Address {
@@ -15,6 +15,8 @@ pub struct Connector2 {
     pub network: Network,
     pub operator_taproot_public_key: XOnlyPublicKey,
     pub n_of_n_taproot_public_key: XOnlyPublicKey,
+    #[serde(skip)]
+    taproot_spend_info: TaprootSpendInfoCache,
 }
 
 impl Connector2 {
@@ -27,6 +29,7 @@ impl Connector2 {
             network,
             operator_taproot_public_key: operator_taproot_public_key.clone(),
             n_of_n_taproot_public_key: n_of_n_taproot_public_key.clone(),
+            taproot_spend_info: TaprootSpendInfoCache::default(),
         }
     }
 
@@ -62,13 +65,15 @@ impl TaprootConnector for Connector2 {
     }
 
     fn generate_taproot_spend_info(&self) -> TaprootSpendInfo {
-        TaprootBuilder::new()
-            .add_leaf(1, self.generate_taproot_leaf_0_script())
-            .expect("Unable to add leaf 0")
-            .add_leaf(1, self.generate_taproot_leaf_1_script())
-            .expect("Unable to add leaf 1")
-            .finalize(&Secp256k1::new(), self.n_of_n_taproot_public_key)
-            .expect("Unable to finalize taproot")
+        self.taproot_spend_info.get_or_init(|| {
+            TaprootBuilder::new()
+                .add_leaf(1, self.generate_taproot_leaf_0_script())
+                .expect("Unable to add leaf 0")
+                .add_leaf(1, self.generate_taproot_leaf_1_script())
+                .expect("Unable to add leaf 1")
+                .finalize(&Secp256k1::new(), self.n_of_n_taproot_public_key)
+                .expect("Unable to finalize taproot")
+        })
     }
 
     fn generate_taproot_address(&self) -> Address {
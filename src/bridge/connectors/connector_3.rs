@@ -3,8 +3,8 @@ use serde::{Deserialize, Serialize};
 
 use super::{
     super::{
-        super::bridge::{constants::NUM_BLOCKS_PER_3_DAYS, utils::num_blocks_per_network},
         scripts::*,
+        timelocks::TimelockConfig,
         transactions::base::Input,
     },
     connector::*,
@@ -18,11 +18,15 @@ pub struct Connector3 {
 }
 
 impl Connector3 {
-    pub fn new(network: Network, operator_public_key: &PublicKey) -> Self {
+    pub fn new(
+        network: Network,
+        operator_public_key: &PublicKey,
+        timelock_config: &TimelockConfig,
+    ) -> Self {
         Connector3 {
             network,
             operator_public_key: operator_public_key.clone(),
-            num_blocks_timelock: num_blocks_per_network(network, NUM_BLOCKS_PER_3_DAYS),
+            num_blocks_timelock: timelock_config.num_blocks_take_1,
         }
     }
 }
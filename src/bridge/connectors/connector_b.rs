@@ -6,10 +6,7 @@ use bitcoin::{
 use serde::{Deserialize, Serialize};
 
 use super::{
-    super::{
-        constants::NUM_BLOCKS_PER_3_DAYS, scripts::*, transactions::base::Input,
-        utils::num_blocks_per_network,
-    },
+    super::{scripts::*, timelocks::TimelockConfig, transactions::base::Input},
     connector::*,
 };
 
@@ -18,14 +15,21 @@ pub struct ConnectorB {
     pub network: Network,
     pub n_of_n_taproot_public_key: XOnlyPublicKey,
     pub num_blocks_timelock_1: u32,
+    #[serde(skip)]
+    taproot_spend_info: TaprootSpendInfoCache,
 }
 
 impl ConnectorB {
-    pub fn new(network: Network, n_of_n_taproot_public_key: &XOnlyPublicKey) -> Self {
+    pub fn new(
+        network: Network,
+        n_of_n_taproot_public_key: &XOnlyPublicKey,
+        timelock_config: &TimelockConfig,
+    ) -> Self {
         ConnectorB {
             network,
             n_of_n_taproot_public_key: n_of_n_taproot_public_key.clone(),
-            num_blocks_timelock_1: num_blocks_per_network(network, NUM_BLOCKS_PER_3_DAYS),
+            num_blocks_timelock_1: timelock_config.num_blocks_assert,
+            taproot_spend_info: TaprootSpendInfoCache::default(),
         }
     }
 
@@ -74,15 +78,17 @@ impl TaprootConnector for ConnectorB {
     }
 
     fn generate_taproot_spend_info(&self) -> TaprootSpendInfo {
-        TaprootBuilder::new()
-            .add_leaf(2, self.generate_taproot_leaf_0_script())
-            .expect("Unable to add leaf 0")
-            .add_leaf(2, self.generate_taproot_leaf_1_script())
-            .expect("Unable to add leaf 1")
-            .add_leaf(1, self.generate_taproot_leaf_2_script())
-            .expect("Unable to add leaf 2")
-            .finalize(&Secp256k1::new(), self.n_of_n_taproot_public_key)
-            .expect("Unable to finalize taproot")
+        self.taproot_spend_info.get_or_init(|| {
+            TaprootBuilder::new()
+                .add_leaf(2, self.generate_taproot_leaf_0_script())
+                .expect("Unable to add leaf 0")
+                .add_leaf(2, self.generate_taproot_leaf_1_script())
+                .expect("Unable to add leaf 1")
+                .add_leaf(1, self.generate_taproot_leaf_2_script())
+                .expect("Unable to add leaf 2")
+                .finalize(&Secp256k1::new(), self.n_of_n_taproot_public_key)
+                .expect("Unable to finalize taproot")
+        })
     }
 
     fn generate_taproot_address(&self) -> Address {
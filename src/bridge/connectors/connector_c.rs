@@ -6,6 +6,7 @@ use bitcoin::{
     Address, Network, ScriptBuf, TxIn, XOnlyPublicKey,
 };
 use num_traits::ToPrimitive;
+use once_cell::sync::OnceCell;
 use serde::{Deserialize, Serialize};
 
 use super::{super::transactions::base::Input, connector::*};
@@ -20,59 +21,114 @@ pub struct DisproveLeaf {
     pub unlock: UnlockWitness,
 }
 
-#[derive(Serialize, Deserialize, Eq, PartialEq, Clone)]
+// Only the parameters and the committed output key are serialized. The leaves and the taproot
+// tree are rebuilt from the parameters the first time they are needed and cached afterwards.
+#[derive(Serialize, Deserialize, Clone)]
 pub struct ConnectorC {
     pub network: Network,
     pub operator_taproot_public_key: XOnlyPublicKey,
-    lock_scripts: Vec<ScriptBuf>,
-    unlock_witnesses: Vec<UnlockWitnessData>,
+    taproot_output_key: XOnlyPublicKey,
+    #[serde(skip)]
+    leaves: OnceCell<(Vec<ScriptBuf>, Vec<UnlockWitnessData>)>,
+    #[serde(skip)]
+    taproot_spend_info: OnceCell<TaprootSpendInfo>,
 }
 
+impl PartialEq for ConnectorC {
+    fn eq(&self, other: &Self) -> bool {
+        self.network == other.network
+            && self.operator_taproot_public_key == other.operator_taproot_public_key
+            && self.taproot_output_key == other.taproot_output_key
+    }
+}
+
+impl Eq for ConnectorC {}
+
 impl ConnectorC {
     pub fn new(network: Network, operator_taproot_public_key: &XOnlyPublicKey) -> Self {
         let leaves = generate_assert_leaves();
+        let taproot_spend_info =
+            generate_assert_taproot_spend_info(&leaves.0, operator_taproot_public_key)
+                .expect("Unable to build assert transaction connector c taproot");
 
         ConnectorC {
             network,
             operator_taproot_public_key: operator_taproot_public_key.clone(),
-            lock_scripts: leaves.0,
-            unlock_witnesses: leaves.1,
+            taproot_output_key: taproot_spend_info.output_key().to_inner(),
+            leaves: OnceCell::with_value(leaves),
+            taproot_spend_info: OnceCell::with_value(taproot_spend_info),
         }
     }
 
+    pub fn taproot_output_key(&self) -> XOnlyPublicKey { self.taproot_output_key }
+
+    /// Rebuilds the taproot tree if it is not cached yet and checks that it commits to the
+    /// output key the connector was created with.
+    pub fn verify_taproot_output_key(&self) -> Result<(), String> {
+        self.get_or_try_init_taproot_spend_info().map(|_| ())
+    }
+
+    /// Same as `generate_taproot_spend_info`, but returns an error instead of panicking when the
+    /// connector was deserialized from data whose output key the leaves don't commit to.
+    pub fn try_generate_taproot_spend_info(&self) -> Result<TaprootSpendInfo, String> {
+        self.get_or_try_init_taproot_spend_info().cloned()
+    }
+
     pub fn generate_taproot_leaf_script_witness(&self, leaf_index: u32) -> UnlockWitnessData {
         let index = leaf_index.to_usize().unwrap();
-        if index >= self.unlock_witnesses.len() {
+        let unlock_witnesses = &self.leaves().1;
+        if index >= unlock_witnesses.len() {
             panic!("Invalid leaf index.")
         }
-        self.unlock_witnesses[index].clone()
+        unlock_witnesses[index].clone()
+    }
+
+    fn leaves(&self) -> &(Vec<ScriptBuf>, Vec<UnlockWitnessData>) {
+        self.leaves.get_or_init(generate_assert_leaves)
+    }
+
+    fn get_or_try_init_taproot_spend_info(&self) -> Result<&TaprootSpendInfo, String> {
+        self.taproot_spend_info.get_or_try_init(|| {
+            let taproot_spend_info = generate_assert_taproot_spend_info(
+                &self.leaves().0,
+                &self.operator_taproot_public_key,
+            )?;
+            if taproot_spend_info.output_key().to_inner() != self.taproot_output_key {
+                return Err(String::from(
+                    "Rebuilt connector c taproot output key does not match the committed address",
+                ));
+            }
+
+            Ok(taproot_spend_info)
+        })
     }
 }
 
 impl TaprootConnector for ConnectorC {
     fn generate_taproot_leaf_script(&self, leaf_index: u32) -> ScriptBuf {
         let index = leaf_index.to_usize().unwrap();
-        if index >= self.lock_scripts.len() {
+        let lock_scripts = &self.leaves().0;
+        if index >= lock_scripts.len() {
             panic!("Invalid leaf index.")
         }
-        self.lock_scripts[index].clone()
+        lock_scripts[index].clone()
     }
 
     fn generate_taproot_leaf_tx_in(&self, leaf_index: u32, input: &Input) -> TxIn {
         let index = leaf_index.to_usize().unwrap();
-        if index >= self.lock_scripts.len() {
+        if index >= self.leaves().0.len() {
             panic!("Invalid leaf index.")
         }
         generate_default_tx_in(input)
     }
 
+    // Graphs read from untrusted data only pass `PegOutGraph::validate` if their connector c output
+    // key matches the rebuilt leaves, use `try_generate_taproot_spend_info` before validation.
     fn generate_taproot_spend_info(&self) -> TaprootSpendInfo {
-        let script_weights = self.lock_scripts.iter().map(|script| (1, script.clone()));
-
-        TaprootBuilder::with_huffman_tree(script_weights)
-            .expect("Unable to add assert leaves")
-            .finalize(&Secp256k1::new(), self.operator_taproot_public_key)
-            .expect("Unable to finalize assert transaction connector c taproot")
+        match self.try_generate_taproot_spend_info() {
+            Ok(taproot_spend_info) => taproot_spend_info,
+            Err(err) => panic!("{}", err),
+        }
     }
 
     fn generate_taproot_address(&self) -> Address {
@@ -100,6 +156,33 @@ fn disprove_leaf() -> DisproveLeaf {
     }
 }
 
+/// Output key of a connector c committing to the given assert leaves, e.g. leaves that were
+/// serialized by older versions of the client.
+pub fn generate_assert_taproot_output_key(
+    lock_scripts: &[ScriptBuf],
+    operator_taproot_public_key: &XOnlyPublicKey,
+) -> Result<XOnlyPublicKey, String> {
+    generate_assert_taproot_spend_info(lock_scripts, operator_taproot_public_key)
+        .map(|taproot_spend_info| taproot_spend_info.output_key().to_inner())
+}
+
+fn generate_assert_taproot_spend_info(
+    lock_scripts: &[ScriptBuf],
+    operator_taproot_public_key: &XOnlyPublicKey,
+) -> Result<TaprootSpendInfo, String> {
+    let script_weights = lock_scripts.iter().map(|script| (1, script.clone()));
+
+    match TaprootBuilder::with_huffman_tree(script_weights) {
+        Ok(builder) => match builder.finalize(&Secp256k1::new(), *operator_taproot_public_key) {
+            Ok(taproot_spend_info) => Ok(taproot_spend_info),
+            Err(_) => Err(String::from(
+                "Unable to finalize assert transaction connector c taproot",
+            )),
+        },
+        Err(err) => Err(format!("Unable to add assert leaves: {}", err)),
+    }
+}
+
 fn generate_assert_leaves() -> (Vec<ScriptBuf>, Vec<UnlockWitnessData>) {
     // TODO: Scripts with n_of_n_public_key and one of the commitments disprove leaves in each leaf (Winternitz signatures)
     let mut locks = Vec::with_capacity(1000);
@@ -112,3 +195,62 @@ fn generate_assert_leaves() -> (Vec<ScriptBuf>, Vec<UnlockWitnessData>) {
     }
     (locks, unlocks)
 }
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::{Network, XOnlyPublicKey};
+    use std::str::FromStr;
+
+    use super::{
+        super::{super::serialization::serialize, connector::TaprootConnector},
+        ConnectorC,
+    };
+
+    const OPERATOR_TAPROOT_PUBLIC_KEY: &str =
+        "79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+
+    #[test]
+    fn test_connector_c_rebuilds_leaves_after_deserialization() {
+        let operator_taproot_public_key =
+            XOnlyPublicKey::from_str(OPERATOR_TAPROOT_PUBLIC_KEY).unwrap();
+        let connector_c = ConnectorC::new(Network::Testnet, &operator_taproot_public_key);
+
+        let json = serialize(&connector_c);
+        assert!(!json.contains("lock_scripts"));
+        let deserialized_connector_c = serde_json::from_str::<ConnectorC>(&json).unwrap();
+
+        assert!(connector_c == deserialized_connector_c);
+        assert!(deserialized_connector_c.verify_taproot_output_key().is_ok());
+        assert_eq!(
+            connector_c.generate_taproot_address(),
+            deserialized_connector_c.generate_taproot_address()
+        );
+        assert_eq!(
+            connector_c.generate_taproot_leaf_script(999),
+            deserialized_connector_c.generate_taproot_leaf_script(999)
+        );
+        assert_eq!(
+            connector_c.generate_taproot_leaf_script_witness(999),
+            deserialized_connector_c.generate_taproot_leaf_script_witness(999)
+        );
+    }
+
+    #[test]
+    fn test_connector_c_rejects_mismatched_output_key() {
+        let operator_taproot_public_key =
+            XOnlyPublicKey::from_str(OPERATOR_TAPROOT_PUBLIC_KEY).unwrap();
+        let connector_c = ConnectorC::new(Network::Testnet, &operator_taproot_public_key);
+
+        // Commit to the untweaked key instead of the output key of the taproot tree
+        let json = serialize(&connector_c).replace(
+            &connector_c.taproot_output_key().to_string(),
+            OPERATOR_TAPROOT_PUBLIC_KEY,
+        );
+        let tampered_connector_c = serde_json::from_str::<ConnectorC>(&json).unwrap();
+
+        assert!(tampered_connector_c.verify_taproot_output_key().is_err());
+        assert!(tampered_connector_c
+            .try_generate_taproot_spend_info()
+            .is_err());
+    }
+}
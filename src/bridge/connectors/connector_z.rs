@@ -1,7 +1,4 @@
-use crate::{
-    bridge::{constants::NUM_BLOCKS_PER_2_WEEKS, utils::num_blocks_per_network},
-    treepp::*,
-};
+use crate::treepp::*;
 use bitcoin::{
     key::Secp256k1,
     taproot::{TaprootBuilder, TaprootSpendInfo},
@@ -10,7 +7,7 @@ use bitcoin::{
 use serde::{Deserialize, Serialize};
 
 use super::{
-    super::{scripts::*, transactions::base::Input},
+    super::{scripts::*, timelocks::TimelockConfig, transactions::base::Input},
     connector::*,
 };
 
@@ -21,6 +18,8 @@ pub struct ConnectorZ {
     pub n_of_n_taproot_public_key: XOnlyPublicKey,
     pub evm_address: String,
     pub num_blocks_timelock_0: u32,
+    #[serde(skip)]
+    taproot_spend_info: TaprootSpendInfoCache,
 }
 
 impl ConnectorZ {
@@ -29,13 +28,15 @@ impl ConnectorZ {
         evm_address: &str,
         depositor_taproot_public_key: &XOnlyPublicKey,
         n_of_n_taproot_public_key: &XOnlyPublicKey,
+        timelock_config: &TimelockConfig,
     ) -> Self {
         ConnectorZ {
             network,
             depositor_taproot_public_key: depositor_taproot_public_key.clone(),
             n_of_n_taproot_public_key: n_of_n_taproot_public_key.clone(),
             evm_address: evm_address.to_string(),
-            num_blocks_timelock_0: num_blocks_per_network(network, NUM_BLOCKS_PER_2_WEEKS),
+            num_blocks_timelock_0: timelock_config.num_blocks_peg_in_refund,
+            taproot_spend_info: TaprootSpendInfoCache::default(),
         }
     }
 
@@ -87,13 +88,15 @@ impl TaprootConnector for ConnectorZ {
     }
 
     fn generate_taproot_spend_info(&self) -> TaprootSpendInfo {
-        TaprootBuilder::new()
-            .add_leaf(1, self.generate_taproot_leaf_0_script())
-            .expect("Unable to add leaf 0")
-            .add_leaf(1, self.generate_taproot_leaf_1_script())
-            .expect("Unable to add leaf 1")
-            .finalize(&Secp256k1::new(), self.depositor_taproot_public_key) // TODO: should this be depositor or n-of-n
-            .expect("Unable to finalize ttaproot")
+        self.taproot_spend_info.get_or_init(|| {
+            TaprootBuilder::new()
+                .add_leaf(1, self.generate_taproot_leaf_0_script())
+                .expect("Unable to add leaf 0")
+                .add_leaf(1, self.generate_taproot_leaf_1_script())
+                .expect("Unable to add leaf 1")
+                .finalize(&Secp256k1::new(), self.depositor_taproot_public_key) // TODO: should this be depositor or n-of-n
+                .expect("Unable to finalize ttaproot")
+        })
     }
 
     fn generate_taproot_address(&self) -> Address {
@@ -1,7 +1,10 @@
 use bitcoin::{Network, Txid};
 use esplora_client::{AsyncClient, Error};
+use tracing::{error, info};
 
-pub const GRAPH_VERSION: &str = "0.1";
+use super::super::client::metrics::{metrics, BROADCAST_FAILURES};
+
+pub const GRAPH_VERSION: &str = "0.3";
 
 pub const INITIAL_AMOUNT: u64 = 2 << 16; // 131072
 pub const FEE_AMOUNT: u64 = 1_000;
@@ -57,10 +60,15 @@ pub async fn verify_if_not_mined(client: &AsyncClient, txid: Txid) {
     }
 }
 
-pub fn verify_tx_result(tx_result: &Result<(), Error>) {
+pub fn verify_tx_result(tx_result: &Result<(), Error>, transaction_name: &str) {
     if tx_result.is_ok() {
-        println!("Tx mined successfully.");
+        info!(transaction = transaction_name, "Tx mined successfully.");
     } else {
+        metrics().increment_counter(BROADCAST_FAILURES, &[("transaction", transaction_name)]);
+        error!(
+            transaction = transaction_name,
+            "Broadcast failed: {:?}", tx_result
+        );
         panic!("Error occurred {:?}", tx_result);
     }
 }
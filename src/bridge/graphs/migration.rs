@@ -0,0 +1,586 @@
+use bitcoin::{consensus, key::TweakedPublicKey, Network, ScriptBuf, Transaction, XOnlyPublicKey};
+use serde::Deserialize;
+use serde_json::Value;
+
+use super::{
+    super::{
+        connectors::connector_c::generate_assert_taproot_output_key,
+        serialization::{try_deserialize, try_deserialize_value},
+        timelocks::TimelockConfig,
+    },
+    base::GRAPH_VERSION,
+    peg_in::PegInGraph,
+    peg_out::PegOutGraph,
+};
+
+// Connector C locks the third output of the assert transaction.
+const ASSERT_CONNECTOR_C_OUTPUT_INDEX: usize = 2;
+
+// Transactions are serialized consensus encoded, as hex
+#[derive(Deserialize)]
+struct ConsensusTransaction(
+    #[serde(with = "consensus::serde::With::<consensus::serde::Hex>")] Transaction,
+);
+
+// Migrations receive the graph data and, for peg-out graphs, the data of the peg-in graph it was
+// created for, already migrated to `GRAPH_VERSION`.
+type Migration = fn(&mut Value, Option<&Value>) -> Result<(), String>;
+
+// Each entry upgrades graph data serialized with the first version to the second version.
+// Whenever the serialized layout of a graph changes, bump `GRAPH_VERSION` and append a migration.
+const PEG_IN_GRAPH_MIGRATIONS: [(&str, &str, Migration); 2] = [
+    ("0.1", "0.2", migrate_peg_in_graph_0_1_to_0_2),
+    ("0.2", "0.3", migrate_unchanged_graph),
+];
+const PEG_OUT_GRAPH_MIGRATIONS: [(&str, &str, Migration); 2] = [
+    ("0.1", "0.2", migrate_peg_out_graph_0_1_to_0_2),
+    ("0.2", "0.3", migrate_peg_out_graph_0_2_to_0_3),
+];
+
+pub fn try_deserialize_peg_in_graph(data: &str) -> Result<PegInGraph, String> {
+    let mut graph = try_deserialize::<Value>(data)?;
+    migrate_peg_in_graph(&mut graph)?;
+    try_deserialize_value::<PegInGraph>(graph)
+}
+
+pub fn try_deserialize_peg_out_graph(data: &str) -> Result<PegOutGraph, String> {
+    let mut graph = try_deserialize::<Value>(data)?;
+    migrate_peg_out_graph(&mut graph)?;
+    try_deserialize_value::<PegOutGraph>(graph)
+}
+
+/// Upgrades serialized peg-in graph data in place to `GRAPH_VERSION`.
+pub fn migrate_peg_in_graph(graph: &mut Value) -> Result<(), String> {
+    migrate(graph, None, "peg-in", &PEG_IN_GRAPH_MIGRATIONS)
+}
+
+/// Upgrades serialized peg-out graph data in place to `GRAPH_VERSION`. Values that older versions
+/// only stored on the peg-in graph fall back to the defaults of the network, use
+/// `migrate_graphs` to take them from the linked peg-in graph instead.
+pub fn migrate_peg_out_graph(graph: &mut Value) -> Result<(), String> {
+    migrate(graph, None, "peg-out", &PEG_OUT_GRAPH_MIGRATIONS)
+}
+
+/// Upgrades the serialized peg-in graphs and then the peg-out graphs of client data in place to
+/// `GRAPH_VERSION`. Each peg-out graph is migrated with the peg-in graph it links to.
+pub fn migrate_graphs(
+    peg_in_graphs: &mut [Value],
+    peg_out_graphs: &mut [Value],
+) -> Result<(), String> {
+    for peg_in_graph in peg_in_graphs.iter_mut() {
+        migrate_peg_in_graph(peg_in_graph)?;
+    }
+    for peg_out_graph in peg_out_graphs.iter_mut() {
+        let peg_in_graph = peg_out_graph
+            .get("peg_in_graph_id")
+            .and_then(|peg_in_graph_id| {
+                peg_in_graphs
+                    .iter()
+                    .find(|&peg_in_graph| peg_in_graph.get("id") == Some(peg_in_graph_id))
+            });
+        migrate(
+            peg_out_graph,
+            peg_in_graph,
+            "peg-out",
+            &PEG_OUT_GRAPH_MIGRATIONS,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Upgrades the graphs of serialized client data in place to `GRAPH_VERSION`. Data without one of
+/// the graph arrays keeps its shape, the graphs of the other array are still migrated.
+pub fn migrate_client_data(data: &mut Value) -> Result<(), String> {
+    let mut peg_in_graphs = match data.get_mut("peg_in_graphs") {
+        Some(Value::Array(peg_in_graphs)) => Some(std::mem::take(peg_in_graphs)),
+        _ => None,
+    };
+    let result = match data.get_mut("peg_out_graphs") {
+        Some(Value::Array(peg_out_graphs)) => migrate_graphs(
+            peg_in_graphs.as_deref_mut().unwrap_or_default(),
+            peg_out_graphs,
+        ),
+        _ => migrate_graphs(peg_in_graphs.as_deref_mut().unwrap_or_default(), &mut []),
+    };
+    if let Some(peg_in_graphs) = peg_in_graphs {
+        data["peg_in_graphs"] = Value::from(peg_in_graphs);
+    }
+
+    result
+}
+
+fn migrate(
+    graph: &mut Value,
+    peg_in_graph: Option<&Value>,
+    graph_type: &str,
+    migrations: &[(&str, &str, Migration)],
+) -> Result<(), String> {
+    loop {
+        let version = match graph.get("version").and_then(Value::as_str) {
+            Some(version) => version.to_string(),
+            None => return Err(format!("Missing version in {} graph data", graph_type)),
+        };
+        if version == GRAPH_VERSION {
+            return Ok(());
+        }
+
+        if parse_version(&version)? > parse_version(GRAPH_VERSION)? {
+            return Err(format!(
+                "Unsupported {} graph version {}. The latest version supported by this client is {}, please upgrade the client",
+                graph_type, version, GRAPH_VERSION
+            ));
+        }
+
+        match migrations.iter().find(|(from, _, _)| version.eq(from)) {
+            Some((_, to, migration)) => {
+                migration(graph, peg_in_graph)?;
+                graph["version"] = Value::from(*to);
+            }
+            None => {
+                return Err(format!(
+                    "No migration from {} graph version {} to {}",
+                    graph_type, version, GRAPH_VERSION
+                ))
+            }
+        }
+    }
+}
+
+fn parse_version(version: &str) -> Result<(u32, u32), String> {
+    let parts: Vec<&str> = version.split('.').collect();
+    if let [major, minor] = parts.as_slice() {
+        if let (Ok(major), Ok(minor)) = (major.parse::<u32>(), minor.parse::<u32>()) {
+            return Ok((major, minor));
+        }
+    }
+
+    Err(format!("Invalid graph version: {}", version))
+}
+
+// 0.2 stores the timelock config on the graph. Graphs serialized before that keep the timelocks
+// their connectors were created with; anything not stored on a connector falls back to the
+// timelocks that were hardcoded for the network at the time.
+fn migrate_peg_in_graph_0_1_to_0_2(graph: &mut Value, _: Option<&Value>) -> Result<(), String> {
+    let mut timelock_config = legacy_timelock_config(graph)?;
+    if let Some(num_blocks) = read_num_blocks(
+        graph,
+        "/peg_in_refund_transaction/connector_z/num_blocks_timelock_0",
+    ) {
+        timelock_config.num_blocks_peg_in_refund = num_blocks;
+    }
+
+    insert_timelock_config(graph, &timelock_config)
+}
+
+// The peg-in refund timelock is not stored on any peg-out connector. Peg-out graphs must share the
+// timelocks of their peg-in graph, so it is taken from the linked one when available.
+fn migrate_peg_out_graph_0_1_to_0_2(
+    graph: &mut Value,
+    peg_in_graph: Option<&Value>,
+) -> Result<(), String> {
+    let mut timelock_config = legacy_timelock_config(graph)?;
+    if let Some(num_blocks) = peg_in_graph.and_then(|peg_in_graph| {
+        read_num_blocks(peg_in_graph, "/timelock_config/num_blocks_peg_in_refund")
+    }) {
+        timelock_config.num_blocks_peg_in_refund = num_blocks;
+    }
+    let timelocks: [(&str, &mut u32); 6] = [
+        (
+            "/kick_off_2_transaction/connector_1/num_blocks_timelock_0",
+            &mut timelock_config.num_blocks_kick_off_2,
+        ),
+        (
+            "/kick_off_2_transaction/connector_1/num_blocks_timelock_1",
+            &mut timelock_config.num_blocks_kick_off_timeout,
+        ),
+        (
+            "/kick_off_2_transaction/connector_1/num_blocks_timelock_2",
+            &mut timelock_config.num_blocks_start_time_timeout,
+        ),
+        (
+            "/assert_transaction/connector_b/num_blocks_timelock_1",
+            &mut timelock_config.num_blocks_assert,
+        ),
+        (
+            "/take_1_transaction/connector_3/num_blocks_timelock",
+            &mut timelock_config.num_blocks_take_1,
+        ),
+        (
+            "/take_2_transaction/connector_4/num_blocks_timelock",
+            &mut timelock_config.num_blocks_take_2,
+        ),
+    ];
+    for (pointer, num_blocks) in timelocks {
+        if let Some(value) = read_num_blocks(graph, pointer) {
+            *num_blocks = value;
+        }
+    }
+
+    insert_timelock_config(graph, &timelock_config)
+}
+
+// 0.3 stops serializing the leaves of connector C and commits to its taproot output key instead.
+// The key is derived from the leaves the graph was created with, and must be the key the assert
+// transaction pays to.
+fn migrate_peg_out_graph_0_2_to_0_3(graph: &mut Value, _: Option<&Value>) -> Result<(), String> {
+    let mut taproot_output_keys = Vec::new();
+    for pointer in [
+        "/disprove_transaction/connector_c",
+        "/take_2_transaction/connector_c",
+    ] {
+        if let Some(connector_c) = graph.pointer_mut(pointer).and_then(Value::as_object_mut) {
+            let lock_scripts = match connector_c.remove("lock_scripts") {
+                Some(lock_scripts) => try_deserialize_value::<Vec<ScriptBuf>>(lock_scripts)?,
+                None => return Err(format!("Missing connector c leaves at {}", pointer)),
+            };
+            connector_c.remove("unlock_witnesses");
+
+            let operator_taproot_public_key = match connector_c.get("operator_taproot_public_key") {
+                Some(operator_taproot_public_key) => {
+                    try_deserialize_value::<XOnlyPublicKey>(operator_taproot_public_key.clone())?
+                }
+                None => return Err(format!("Missing connector c parameters at {}", pointer)),
+            };
+            let taproot_output_key =
+                generate_assert_taproot_output_key(&lock_scripts, &operator_taproot_public_key)?;
+            connector_c.insert(
+                String::from("taproot_output_key"),
+                Value::from(taproot_output_key.to_string()),
+            );
+            taproot_output_keys.push(taproot_output_key);
+        }
+    }
+
+    if let Some(assert_tx) = graph.pointer("/assert_transaction/tx") {
+        let ConsensusTransaction(assert_tx) =
+            try_deserialize_value::<ConsensusTransaction>(assert_tx.clone())?;
+        for taproot_output_key in taproot_output_keys {
+            let script_pubkey = ScriptBuf::new_p2tr_tweaked(
+                TweakedPublicKey::dangerous_assume_tweaked(taproot_output_key),
+            );
+            if assert_tx
+                .output
+                .get(ASSERT_CONNECTOR_C_OUTPUT_INDEX)
+                .map(|output| &output.script_pubkey)
+                != Some(&script_pubkey)
+            {
+                return Err(String::from(
+                    "Connector c leaves do not commit to the assert transaction output",
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn migrate_unchanged_graph(_graph: &mut Value, _: Option<&Value>) -> Result<(), String> { Ok(()) }
+
+fn legacy_timelock_config(graph: &Value) -> Result<TimelockConfig, String> {
+    let network = match graph.get("network") {
+        Some(network) => try_deserialize_value::<Network>(network.clone())?,
+        None => return Err(String::from("Missing network in graph data")),
+    };
+
+    match network {
+        Network::Bitcoin => Ok(TimelockConfig::mainnet()),
+        _ => Ok(TimelockConfig::regtest()),
+    }
+}
+
+fn read_num_blocks(graph: &Value, pointer: &str) -> Option<u32> {
+    graph
+        .pointer(pointer)
+        .and_then(Value::as_u64)
+        .and_then(|num_blocks| u32::try_from(num_blocks).ok())
+}
+
+fn insert_timelock_config(
+    graph: &mut Value,
+    timelock_config: &TimelockConfig,
+) -> Result<(), String> {
+    match serde_json::to_value(timelock_config) {
+        Ok(value) => {
+            graph["timelock_config"] = value;
+            Ok(())
+        }
+        Err(err) => Err(format!("Failed to serialize timelock config: {}", err)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::{
+        absolute::LockTime, consensus::encode::serialize_hex, key::TweakedPublicKey,
+        transaction::Version, Amount, Network, ScriptBuf, Transaction, TxIn, TxOut, XOnlyPublicKey,
+    };
+    use serde_json::{json, Value};
+    use std::str::FromStr;
+
+    use super::{
+        super::super::{
+            connectors::{
+                connector::TaprootConnector,
+                connector_c::{generate_assert_taproot_output_key, ConnectorC},
+            },
+            timelocks::TimelockConfig,
+        },
+        migrate_client_data, migrate_graphs, migrate_peg_in_graph, migrate_peg_out_graph,
+        GRAPH_VERSION,
+    };
+
+    const OPERATOR_TAPROOT_PUBLIC_KEY: &str =
+        "79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+
+    // Client data written by a 0.1 client, with the graph transactions trimmed to the connectors
+    // the migrations read.
+    const CLIENT_DATA_0_1: &str = include_str!("fixtures/client_data_0_1.json");
+
+    #[test]
+    fn test_migrate_peg_in_graph_from_0_1() {
+        let mut graph = json!({
+            "version": "0.1",
+            "network": "bitcoin",
+            "peg_in_refund_transaction": {
+                "connector_z": { "num_blocks_timelock_0": 1000 }
+            }
+        });
+
+        assert!(migrate_peg_in_graph(&mut graph).is_ok());
+        assert_eq!(graph["version"], GRAPH_VERSION);
+
+        let mut expected_timelock_config = TimelockConfig::mainnet();
+        expected_timelock_config.num_blocks_peg_in_refund = 1000;
+        assert_eq!(
+            graph["timelock_config"],
+            serde_json::to_value(expected_timelock_config).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_migrate_peg_out_graph_from_0_1() {
+        let mut graph = json!({
+            "version": "0.1",
+            "network": "testnet",
+            "kick_off_2_transaction": {
+                "connector_1": {
+                    "num_blocks_timelock_0": 2,
+                    "num_blocks_timelock_1": 3,
+                    "num_blocks_timelock_2": 4
+                }
+            },
+            "assert_transaction": { "connector_b": { "num_blocks_timelock_1": 5 } },
+            "take_1_transaction": { "connector_3": { "num_blocks_timelock": 6 } },
+            "take_2_transaction": { "connector_4": { "num_blocks_timelock": 7 } }
+        });
+
+        assert!(migrate_peg_out_graph(&mut graph).is_ok());
+        assert_eq!(graph["version"], GRAPH_VERSION);
+        assert_eq!(
+            graph["timelock_config"],
+            serde_json::to_value(TimelockConfig {
+                num_blocks_peg_in_refund: 1,
+                num_blocks_kick_off_2: 2,
+                num_blocks_kick_off_timeout: 3,
+                num_blocks_start_time_timeout: 4,
+                num_blocks_assert: 5,
+                num_blocks_take_1: 6,
+                num_blocks_take_2: 7,
+            })
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_migrate_peg_out_graph_from_0_1_takes_peg_in_refund_timelock_from_peg_in_graph() {
+        let mut peg_in_graphs = vec![json!({
+            "version": "0.1",
+            "network": "testnet",
+            "id": "peg_in_graph",
+            "peg_in_refund_transaction": {
+                "connector_z": { "num_blocks_timelock_0": 6 }
+            }
+        })];
+        let mut peg_out_graphs = vec![json!({
+            "version": "0.1",
+            "network": "testnet",
+            "id": "peg_out_graph",
+            "peg_in_graph_id": "peg_in_graph"
+        })];
+
+        assert!(migrate_graphs(&mut peg_in_graphs, &mut peg_out_graphs).is_ok());
+        assert_eq!(
+            peg_out_graphs[0]["timelock_config"]["num_blocks_peg_in_refund"],
+            6
+        );
+        assert_eq!(
+            peg_in_graphs[0]["timelock_config"],
+            peg_out_graphs[0]["timelock_config"]
+        );
+    }
+
+    #[test]
+    fn test_migrate_graphs_from_0_1_client_data() {
+        let data = serde_json::from_str::<Value>(CLIENT_DATA_0_1).unwrap();
+        let mut peg_in_graphs = data["peg_in_graphs"].as_array().unwrap().clone();
+        let mut peg_out_graphs = data["peg_out_graphs"].as_array().unwrap().clone();
+
+        assert!(migrate_graphs(&mut peg_in_graphs, &mut peg_out_graphs).is_ok());
+        for graph in peg_in_graphs.iter().chain(peg_out_graphs.iter()) {
+            assert_eq!(graph["version"], GRAPH_VERSION);
+        }
+        assert_eq!(
+            peg_in_graphs[0]["timelock_config"],
+            peg_out_graphs[0]["timelock_config"]
+        );
+        for pointer in [
+            "/disprove_transaction/connector_c",
+            "/take_2_transaction/connector_c",
+        ] {
+            let connector_c = peg_out_graphs[0].pointer(pointer).unwrap();
+            assert!(connector_c.get("lock_scripts").is_none());
+            assert!(connector_c.get("taproot_output_key").is_some());
+        }
+    }
+
+    #[test]
+    fn test_migrate_client_data_with_a_single_graph_array() {
+        let mut data = serde_json::from_str::<Value>(CLIENT_DATA_0_1).unwrap();
+        data.as_object_mut().unwrap().remove("peg_out_graphs");
+        assert!(migrate_client_data(&mut data).is_ok());
+        assert!(data.get("peg_out_graphs").is_none());
+        let peg_in_graphs = data["peg_in_graphs"].as_array().unwrap();
+        assert_eq!(peg_in_graphs.len(), 1);
+        assert_eq!(peg_in_graphs[0]["version"], GRAPH_VERSION);
+
+        let mut data = serde_json::from_str::<Value>(CLIENT_DATA_0_1).unwrap();
+        data.as_object_mut().unwrap().remove("peg_in_graphs");
+        assert!(migrate_client_data(&mut data).is_ok());
+        assert!(data.get("peg_in_graphs").is_none());
+        let peg_out_graphs = data["peg_out_graphs"].as_array().unwrap();
+        assert_eq!(peg_out_graphs.len(), 1);
+        assert_eq!(peg_out_graphs[0]["version"], GRAPH_VERSION);
+
+        let mut data = json!({ "version": 1 });
+        assert!(migrate_client_data(&mut data).is_ok());
+        assert_eq!(data, json!({ "version": 1 }));
+    }
+
+    fn connector_c_0_2(lock_scripts: &[ScriptBuf]) -> Value {
+        json!({
+            "network": "testnet",
+            "operator_taproot_public_key": OPERATOR_TAPROOT_PUBLIC_KEY,
+            "lock_scripts": lock_scripts,
+            "unlock_witnesses": lock_scripts.iter().map(|_| vec![1u8]).collect::<Vec<_>>()
+        })
+    }
+
+    fn peg_out_graph_0_2(connector_c: Value) -> Value {
+        json!({
+            "version": "0.2",
+            "network": "testnet",
+            "disprove_transaction": { "connector_c": connector_c.clone() },
+            "take_2_transaction": { "connector_c": connector_c }
+        })
+    }
+
+    fn assert_tx_paying_to(taproot_output_key: XOnlyPublicKey) -> Value {
+        let mut output = vec![
+            TxOut {
+                value: Amount::ZERO,
+                script_pubkey: ScriptBuf::new(),
+            };
+            3
+        ];
+        output[2].script_pubkey = ScriptBuf::new_p2tr_tweaked(
+            TweakedPublicKey::dangerous_assume_tweaked(taproot_output_key),
+        );
+
+        Value::from(serialize_hex(&Transaction {
+            version: Version(2),
+            lock_time: LockTime::ZERO,
+            input: vec![TxIn::default()],
+            output,
+        }))
+    }
+
+    #[test]
+    fn test_migrate_peg_out_graph_from_0_2() {
+        let operator_taproot_public_key =
+            XOnlyPublicKey::from_str(OPERATOR_TAPROOT_PUBLIC_KEY).unwrap();
+        let expected_connector_c = ConnectorC::new(Network::Testnet, &operator_taproot_public_key);
+        let lock_scripts: Vec<ScriptBuf> = (0..1000)
+            .map(|index| expected_connector_c.generate_taproot_leaf_script(index))
+            .collect();
+        let mut graph = peg_out_graph_0_2(connector_c_0_2(&lock_scripts));
+        graph["assert_transaction"] =
+            json!({ "tx": assert_tx_paying_to(expected_connector_c.taproot_output_key()) });
+
+        assert!(migrate_peg_out_graph(&mut graph).is_ok());
+        assert_eq!(graph["version"], GRAPH_VERSION);
+
+        for pointer in [
+            "/disprove_transaction/connector_c",
+            "/take_2_transaction/connector_c",
+        ] {
+            let connector_c = graph.pointer(pointer).unwrap();
+            assert!(connector_c.get("lock_scripts").is_none());
+            assert!(connector_c.get("unlock_witnesses").is_none());
+            let connector_c = serde_json::from_value::<ConnectorC>(connector_c.clone()).unwrap();
+            assert!(connector_c == expected_connector_c);
+            assert!(connector_c.verify_taproot_output_key().is_ok());
+        }
+    }
+
+    #[test]
+    fn test_migrate_peg_out_graph_from_0_2_commits_to_stored_leaves() {
+        let operator_taproot_public_key =
+            XOnlyPublicKey::from_str(OPERATOR_TAPROOT_PUBLIC_KEY).unwrap();
+        let lock_scripts = vec![ScriptBuf::from_hex("51").unwrap()];
+        let stored_taproot_output_key =
+            generate_assert_taproot_output_key(&lock_scripts, &operator_taproot_public_key)
+                .unwrap();
+
+        let mut graph = peg_out_graph_0_2(connector_c_0_2(&lock_scripts));
+        assert!(migrate_peg_out_graph(&mut graph).is_ok());
+        let connector_c = serde_json::from_value::<ConnectorC>(
+            graph["disprove_transaction"]["connector_c"].clone(),
+        )
+        .unwrap();
+        assert_eq!(connector_c.taproot_output_key(), stored_taproot_output_key);
+        // The leaves of the current client differ from the stored ones
+        assert!(connector_c.verify_taproot_output_key().is_err());
+        assert!(connector_c.try_generate_taproot_spend_info().is_err());
+
+        // Leaves that the assert transaction does not pay to are rejected
+        let mut graph = peg_out_graph_0_2(connector_c_0_2(&lock_scripts));
+        graph["assert_transaction"] =
+            json!({ "tx": assert_tx_paying_to(operator_taproot_public_key) });
+        assert!(migrate_peg_out_graph(&mut graph).is_err());
+
+        let mut graph = peg_out_graph_0_2(connector_c_0_2(&lock_scripts));
+        graph["assert_transaction"] =
+            json!({ "tx": assert_tx_paying_to(stored_taproot_output_key) });
+        assert!(migrate_peg_out_graph(&mut graph).is_ok());
+
+        let mut graph = peg_out_graph_0_2(json!({
+            "network": "testnet",
+            "operator_taproot_public_key": OPERATOR_TAPROOT_PUBLIC_KEY
+        }));
+        assert!(migrate_peg_out_graph(&mut graph).is_err());
+    }
+
+    #[test]
+    fn test_migrate_graph_rejects_unknown_versions() {
+        let mut graph = json!({ "version": "99.0", "network": "testnet" });
+        let result = migrate_peg_in_graph(&mut graph);
+        assert!(result.is_err_and(|err| err.contains("Unsupported peg-in graph version 99.0")));
+
+        let mut graph = json!({ "version": "0.0", "network": "testnet" });
+        assert!(migrate_peg_out_graph(&mut graph).is_err());
+
+        let mut graph = json!({ "network": "testnet" });
+        assert!(migrate_peg_out_graph(&mut graph).is_err());
+    }
+}
@@ -1,3 +1,4 @@
 pub mod base;
+pub mod migration;
 pub mod peg_in;
 pub mod peg_out;
@@ -1,6 +1,8 @@
 use bitcoin::{
+    bip32::KeySource,
     hex::{Case::Upper, DisplayHex},
-    Network, OutPoint, PublicKey, Txid, XOnlyPublicKey,
+    psbt::Psbt,
+    Network, OutPoint, PublicKey, Transaction, Txid, XOnlyPublicKey,
 };
 use esplora_client::{AsyncClient, Error, TxStatus};
 use musig2::SecNonce;
@@ -11,22 +13,28 @@ use std::{
     collections::HashMap,
     fmt::{Display, Formatter, Result as FmtResult},
 };
+use tracing::{instrument, warn};
 
 use super::{
     super::{
         contexts::{depositor::DepositorContext, verifier::VerifierContext},
         graphs::base::get_block_height,
+        timelocks::TimelockConfig,
         transactions::{
             base::{validate_transaction, verify_public_nonces_for_tx, BaseTransaction, Input},
             peg_in_confirm::PegInConfirmTransaction,
             peg_in_deposit::PegInDepositTransaction,
             peg_in_refund::PegInRefundTransaction,
             pre_signed::PreSignedTransaction,
+            pre_signed_musig2::Musig2Transaction,
+            psbt::PsbtTransaction,
+            script_validation::ScriptValidationReport,
         },
     },
     base::{verify_if_not_mined, verify_tx_result, BaseGraph, GRAPH_VERSION},
 };
 
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Copy, Debug)]
 pub enum PegInDepositorStatus {
     PegInDepositWait,     // peg-in deposit not yet confirmed
     PegInConfirmWait, // peg-in confirm not yet confirmed, wait for operator to complete peg-in, refund not available yet
@@ -57,6 +65,7 @@ impl Display for PegInDepositorStatus {
     }
 }
 
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Copy, Debug)]
 pub enum PegInVerifierStatus {
     PegInWait,     // no action required, wait
     PegInPresign,  // should presign peg-in confirm
@@ -75,6 +84,7 @@ impl Display for PegInVerifierStatus {
     }
 }
 
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Copy, Debug)]
 pub enum PegInOperatorStatus {
     PegInWait,             // peg-in not yet complete, no action required yet, wait
     PegInConfirmAvailable, // should execute peg-in confirm
@@ -115,6 +125,8 @@ pub struct PegInGraph {
     pub depositor_public_key: PublicKey,
     depositor_taproot_public_key: XOnlyPublicKey,
     depositor_evm_address: String,
+
+    timelock_config: TimelockConfig,
 }
 
 impl BaseGraph for PegInGraph {
@@ -124,9 +136,14 @@ impl BaseGraph for PegInGraph {
 }
 
 impl PegInGraph {
-    pub fn new(context: &DepositorContext, deposit_input: Input, evm_address: &str) -> Self {
+    pub fn new(
+        context: &DepositorContext,
+        deposit_input: Input,
+        evm_address: &str,
+        timelock_config: &TimelockConfig,
+    ) -> Self {
         let peg_in_deposit_transaction =
-            PegInDepositTransaction::new(context, evm_address, deposit_input);
+            PegInDepositTransaction::new(context, evm_address, deposit_input, timelock_config);
         let peg_in_deposit_txid = peg_in_deposit_transaction.tx().compute_txid();
 
         let peg_in_refund_vout_0: usize = 0;
@@ -140,6 +157,7 @@ impl PegInGraph {
                 },
                 amount: peg_in_deposit_transaction.tx().output[peg_in_refund_vout_0].value,
             },
+            timelock_config,
         );
 
         let peg_in_confirm_vout_0: usize = 0;
@@ -153,6 +171,7 @@ impl PegInGraph {
                 },
                 amount: peg_in_deposit_transaction.tx().output[peg_in_confirm_vout_0].value,
             },
+            timelock_config,
         );
 
         PegInGraph {
@@ -168,10 +187,12 @@ impl PegInGraph {
             depositor_public_key: context.depositor_public_key,
             depositor_taproot_public_key: context.depositor_taproot_public_key,
             depositor_evm_address: evm_address.to_string(),
+            timelock_config: *timelock_config,
         }
     }
 
     pub fn new_for_validation(&self) -> Self {
+        let timelock_config = &self.timelock_config;
         let peg_in_deposit_transaction = PegInDepositTransaction::new_for_validation(
             self.network,
             &self.depositor_public_key,
@@ -182,6 +203,7 @@ impl PegInGraph {
                 outpoint: self.peg_in_deposit_transaction.tx().input[0].previous_output, // Self-referencing
                 amount: self.peg_in_deposit_transaction.prev_outs()[0].value, // Self-referencing
             },
+            timelock_config,
         );
         let peg_in_deposit_txid = peg_in_deposit_transaction.tx().compute_txid();
 
@@ -199,6 +221,7 @@ impl PegInGraph {
                 },
                 amount: peg_in_deposit_transaction.tx().output[peg_in_refund_vout_0].value,
             },
+            timelock_config,
         );
 
         let peg_in_confirm_vout_0: usize = 0;
@@ -214,6 +237,7 @@ impl PegInGraph {
                 },
                 amount: peg_in_deposit_transaction.tx().output[peg_in_confirm_vout_0].value,
             },
+            timelock_config,
         );
 
         PegInGraph {
@@ -229,6 +253,7 @@ impl PegInGraph {
             depositor_public_key: self.depositor_public_key,
             depositor_taproot_public_key: self.depositor_taproot_public_key,
             depositor_evm_address: self.depositor_evm_address.clone(),
+            timelock_config: self.timelock_config,
         }
     }
 
@@ -246,6 +271,25 @@ impl PegInGraph {
         secret_nonces
     }
 
+    /// Outpoints spent by the graph that it does not create itself, i.e. the depositor's input.
+    pub fn external_outpoints(&self) -> Vec<OutPoint> {
+        self.peg_in_deposit_transaction
+            .tx()
+            .input
+            .iter()
+            .map(|input| input.previous_output)
+            .collect()
+    }
+
+    /// Transactions with n-of-n inputs signed through MuSig2.
+    pub fn musig2_transactions(&self) -> Vec<&dyn Musig2Transaction> {
+        vec![&self.peg_in_confirm_transaction]
+    }
+
+    pub fn musig2_transactions_mut(&mut self) -> Vec<&mut dyn Musig2Transaction> {
+        vec![&mut self.peg_in_confirm_transaction]
+    }
+
     pub fn pre_sign(
         &mut self,
         context: &VerifierContext,
@@ -259,10 +303,25 @@ impl PegInGraph {
         self.n_of_n_presigned = true; // TODO: set to true after collecting all n of n signatures
     }
 
+    pub fn timelock_config(&self) -> &TimelockConfig { &self.timelock_config }
+
     pub fn peg_in_confirm_transaction_ref(&self) -> &PegInConfirmTransaction {
         &self.peg_in_confirm_transaction
     }
 
+    pub fn peg_in_refund_transaction_ref(&self) -> &PegInRefundTransaction {
+        &self.peg_in_refund_transaction
+    }
+
+    pub fn is_presigned(&self) -> bool { self.n_of_n_presigned }
+
+    /// First block height at which the peg-in refund transaction can be mined, given the height
+    /// at which the peg-in deposit transaction was mined. From then on the deposit can be spent
+    /// by both the peg-in refund and the peg-in confirm transaction.
+    pub fn refund_unlock_height(&self, deposit_block_height: u32) -> u32 {
+        deposit_block_height + self.peg_in_refund_transaction.num_blocks_timelock_0()
+    }
+
     pub async fn verifier_status(&self, client: &AsyncClient) -> PegInVerifierStatus {
         let (peg_in_deposit_status, peg_in_confirm_status, _) =
             Self::get_peg_in_statuses(self, client).await;
@@ -327,8 +386,8 @@ impl PegInGraph {
                     .unwrap()
                     .block_height
                     .is_some_and(|block_height| {
-                        block_height + self.peg_in_refund_transaction.num_blocks_timelock_0()
-                            <= blockchain_height
+                        // the refund can be broadcast once it can be mined in the next block
+                        self.refund_unlock_height(block_height) <= blockchain_height + 1
                     })
                 {
                     if peg_in_refund_status.is_ok_and(|status| status.confirmed) {
@@ -349,6 +408,7 @@ impl PegInGraph {
         }
     }
 
+    #[instrument(name = "transaction", skip_all, fields(graph_id = %self.id, transaction = "peg_in_deposit"))]
     pub async fn deposit(&self, client: &AsyncClient) {
         verify_if_not_mined(client, self.peg_in_deposit_transaction.tx().compute_txid()).await;
 
@@ -359,9 +419,10 @@ impl PegInGraph {
         let deposit_result = client.broadcast(&deposit_tx).await;
 
         // verify deposit result
-        verify_tx_result(&deposit_result);
+        verify_tx_result(&deposit_result, "peg_in_deposit");
     }
 
+    #[instrument(name = "transaction", skip_all, fields(graph_id = %self.id, transaction = "peg_in_confirm"))]
     pub async fn confirm(&self, client: &AsyncClient) {
         verify_if_not_mined(client, self.peg_in_confirm_transaction.tx().compute_txid()).await;
 
@@ -377,12 +438,13 @@ impl PegInGraph {
             let confirm_result = client.broadcast(&confirm_tx).await;
 
             // verify confirm result
-            verify_tx_result(&confirm_result);
+            verify_tx_result(&confirm_result, "peg_in_confirm");
         } else {
             panic!("Deposit tx has not been confirmed!");
         }
     }
 
+    #[instrument(name = "transaction", skip_all, fields(graph_id = %self.id, transaction = "peg_in_refund"))]
     pub async fn refund(&self, client: &AsyncClient) {
         verify_if_not_mined(client, self.peg_in_refund_transaction.tx().compute_txid()).await;
 
@@ -398,7 +460,7 @@ impl PegInGraph {
             let refund_result = client.broadcast(&refund_tx).await;
 
             // verify refund result
-            verify_tx_result(&refund_result);
+            verify_tx_result(&refund_result, "peg_in_refund");
         } else {
             panic!("Deposit tx has not been confirmed!");
         }
@@ -433,6 +495,11 @@ impl PegInGraph {
 
     pub fn validate(&self) -> bool {
         let mut ret_val = true;
+        if let Err(err) = self.timelock_config.validate() {
+            warn!(graph_id = %self.id, "Invalid timelock config on peg-in graph: {}", err);
+            ret_val = false;
+        }
+
         let peg_in_graph = self.new_for_validation();
         if !validate_transaction(
             self.peg_in_deposit_transaction.tx(),
@@ -457,9 +524,73 @@ impl PegInGraph {
             ret_val = false;
         }
 
+        let report = self.validate_scripts();
+        if !report.is_valid() {
+            warn!(graph_id = %self.id, "Script validation failed on peg-in graph\n{}", report);
+            ret_val = false;
+        }
+
         ret_val
     }
 
+    /// Executes the witnesses of every signed input in the graph against the outputs it spends.
+    pub fn validate_scripts(&self) -> ScriptValidationReport {
+        let mut report = ScriptValidationReport::new();
+        report.validate_psbt_transaction("peg-in deposit", &self.peg_in_deposit_transaction);
+        report.validate_psbt_transaction("peg-in refund", &self.peg_in_refund_transaction);
+        report.validate_psbt_transaction("peg-in confirm", &self.peg_in_confirm_transaction);
+
+        report
+    }
+
+    /// Exports a transaction of the graph as a PSBT to be signed outside of the client. The
+    /// transaction is named like the broadcast commands, e.g. `peg_in_refund`. The transactions listed
+    /// by `psbt_previous_txids` must be passed in `previous_transactions`.
+    pub fn export_psbt(
+        &self,
+        transaction_name: &str,
+        previous_transactions: &[Transaction],
+        key_origins: &HashMap<PublicKey, KeySource>,
+    ) -> Result<Psbt, String> {
+        self.psbt_transaction(transaction_name)?
+            .export_psbt(previous_transactions, key_origins)
+    }
+
+    /// Transactions created outside of the graph that `export_psbt` needs for `transaction_name`.
+    pub fn psbt_previous_txids(&self, transaction_name: &str) -> Result<Vec<Txid>, String> {
+        Ok(self
+            .psbt_transaction(transaction_name)?
+            .segwit_v0_previous_txids())
+    }
+
+    /// Adds the signatures of an externally signed PSBT, see `export_psbt`. Returns the number of
+    /// inputs that were signed by it.
+    pub fn import_psbt(&mut self, transaction_name: &str, psbt: &Psbt) -> Result<usize, String> {
+        self.psbt_transaction_mut(transaction_name)?
+            .import_psbt(psbt)
+    }
+
+    fn psbt_transaction(&self, transaction_name: &str) -> Result<&dyn PsbtTransaction, String> {
+        match transaction_name {
+            "peg_in_deposit" => Ok(&self.peg_in_deposit_transaction),
+            "peg_in_refund" => Ok(&self.peg_in_refund_transaction),
+            "peg_in_confirm" => Ok(&self.peg_in_confirm_transaction),
+            _ => Err(format!("Unknown peg-in transaction: {}", transaction_name)),
+        }
+    }
+
+    fn psbt_transaction_mut(
+        &mut self,
+        transaction_name: &str,
+    ) -> Result<&mut dyn PsbtTransaction, String> {
+        match transaction_name {
+            "peg_in_deposit" => Ok(&mut self.peg_in_deposit_transaction),
+            "peg_in_refund" => Ok(&mut self.peg_in_refund_transaction),
+            "peg_in_confirm" => Ok(&mut self.peg_in_confirm_transaction),
+            _ => Err(format!("Unknown peg-in transaction: {}", transaction_name)),
+        }
+    }
+
     pub fn merge(&mut self, source_peg_in_graph: &PegInGraph) {
         self.peg_in_confirm_transaction
             .merge(&source_peg_in_graph.peg_in_confirm_transaction);
@@ -1,7 +1,11 @@
 use bitcoin::{
+    bip32::KeySource,
+    hashes::sha256,
     hex::{Case::Upper, DisplayHex},
-    key::Keypair,
-    Amount, Network, OutPoint, PublicKey, ScriptBuf, Txid, XOnlyPublicKey,
+    key::{Keypair, Secp256k1},
+    psbt::Psbt,
+    secp256k1::{schnorr::Signature, Message},
+    Amount, Network, OutPoint, PublicKey, ScriptBuf, Transaction, Txid, XOnlyPublicKey,
 };
 use esplora_client::{AsyncClient, Error, TxStatus};
 use musig2::SecNonce;
@@ -12,10 +16,16 @@ use std::{
     collections::HashMap,
     fmt::{Display, Formatter, Result as FmtResult},
 };
+use tracing::{instrument, warn};
 
 use super::{
     super::{
-        contexts::{base::BaseContext, operator::OperatorContext, verifier::VerifierContext},
+        client::chain::chain::PegOutEvent,
+        contexts::{
+            base::BaseContext, operator::OperatorContext, verifier::VerifierContext,
+            withdrawer::WithdrawerContext,
+        },
+        timelocks::TimelockConfig,
         transactions::{
             assert::AssertTransaction,
             base::{
@@ -30,16 +40,25 @@ use super::{
             kick_off_timeout::KickOffTimeoutTransaction,
             peg_out::PegOutTransaction,
             pre_signed::PreSignedTransaction,
+            pre_signed_musig2::Musig2Transaction,
+            psbt::PsbtTransaction,
+            script_validation::ScriptValidationReport,
             start_time::StartTimeTransaction,
             start_time_timeout::StartTimeTimeoutTransaction,
             take_1::Take1Transaction,
             take_2::Take2Transaction,
         },
     },
-    base::{get_block_height, verify_if_not_mined, verify_tx_result, BaseGraph, GRAPH_VERSION},
+    base::{
+        get_block_height, verify_if_not_mined, verify_tx_result, BaseGraph, FEE_AMOUNT,
+        GRAPH_VERSION,
+    },
     peg_in::PegInGraph,
 };
 
+const PEG_OUT_REQUEST_TAG: &[u8] = b"bitvm-bridge/peg-out-request";
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Copy, Debug)]
 pub enum PegOutDepositorStatus {
     PegOutNotStarted, // peg-out transaction not created yet
     PegOutWait,       // peg-out not confirmed yet, wait
@@ -58,6 +77,30 @@ impl Display for PegOutDepositorStatus {
     }
 }
 
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Copy, Debug)]
+pub enum PegOutWithdrawerStatus {
+    PegOutNotRequested, // no peg-out requested on this graph yet
+    PegOutRequested,    // peg-out requested, wait for the operator to send it
+    PegOutWait,         // peg-out not confirmed yet, wait
+    PegOutComplete,     // peg-out complete
+}
+
+impl Display for PegOutWithdrawerStatus {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self {
+            PegOutWithdrawerStatus::PegOutNotRequested => {
+                write!(f, "Peg-out available. Request peg-out?")
+            }
+            PegOutWithdrawerStatus::PegOutRequested => {
+                write!(f, "Peg-out requested. Wait for the operator...")
+            }
+            PegOutWithdrawerStatus::PegOutWait => write!(f, "No action available. Wait..."),
+            PegOutWithdrawerStatus::PegOutComplete => write!(f, "Peg-out complete. Done."),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Copy, Debug)]
 pub enum PegOutVerifierStatus {
     PegOutPresign,            // should presign peg-out graph
     PegOutComplete,           // peg-out complete
@@ -111,6 +154,7 @@ impl Display for PegOutVerifierStatus {
     }
 }
 
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Copy, Debug)]
 pub enum PegOutOperatorStatus {
     PegOutWait,
     PegOutComplete,    // peg-out complete
@@ -194,8 +238,12 @@ pub struct PegOutGraph {
     withdrawer_public_key: Option<PublicKey>,
     withdrawer_taproot_public_key: Option<XOnlyPublicKey>,
     withdrawer_evm_address: Option<String>,
+    // Withdrawer's signature over the graph id, its key and `withdrawer_evm_address`.
+    withdrawer_request_signature: Option<Signature>,
 
     peg_out_transaction: Option<PegOutTransaction>,
+
+    timelock_config: TimelockConfig,
 }
 
 impl BaseGraph for PegOutGraph {
@@ -208,8 +256,10 @@ impl PegOutGraph {
     pub fn new(context: &OperatorContext, peg_in_graph: &PegInGraph, kickoff_input: Input) -> Self {
         let peg_in_confirm_transaction = peg_in_graph.peg_in_confirm_transaction_ref();
         let peg_in_confirm_txid = peg_in_confirm_transaction.tx().compute_txid();
+        let timelock_config = peg_in_graph.timelock_config();
 
-        let kick_off_1_transaction = KickOff1Transaction::new(context, kickoff_input);
+        let kick_off_1_transaction =
+            KickOff1Transaction::new(context, kickoff_input, timelock_config);
         let kick_off_1_txid = kick_off_1_transaction.tx().compute_txid();
 
         let start_time_vout_0 = 2;
@@ -242,6 +292,7 @@ impl PegOutGraph {
                 },
                 amount: kick_off_1_transaction.tx().output[start_time_timeout_vout_1].value,
             },
+            timelock_config,
         );
 
         let kick_off_2_vout_0 = 1;
@@ -254,6 +305,7 @@ impl PegOutGraph {
                 },
                 amount: kick_off_1_transaction.tx().output[kick_off_2_vout_0].value,
             },
+            timelock_config,
         );
         let kick_off_2_txid = kick_off_2_transaction.tx().compute_txid();
 
@@ -267,6 +319,7 @@ impl PegOutGraph {
                 },
                 amount: kick_off_1_transaction.tx().output[kick_off_timeout_vout_0].value,
             },
+            timelock_config,
         );
 
         let input_amount_crowdfunding = Amount::from_btc(1.0).unwrap(); // TODO replace placeholder
@@ -317,6 +370,7 @@ impl PegOutGraph {
                 },
                 amount: kick_off_2_transaction.tx().output[take_1_vout_3].value,
             },
+            timelock_config,
         );
 
         let assert_vout_0 = 1;
@@ -329,6 +383,7 @@ impl PegOutGraph {
                 },
                 amount: kick_off_2_transaction.tx().output[assert_vout_0].value,
             },
+            timelock_config,
         );
         let assert_txid = assert_transaction.tx().compute_txid();
 
@@ -366,6 +421,7 @@ impl PegOutGraph {
                 },
                 amount: assert_transaction.tx().output[take_2_vout_3].value,
             },
+            timelock_config,
         );
 
         let script_index = 1; // TODO replace placeholder
@@ -400,6 +456,7 @@ impl PegOutGraph {
                 },
                 amount: kick_off_2_transaction.tx().output[disprove_chain_vout_0].value,
             },
+            timelock_config,
         );
 
         PegOutGraph {
@@ -427,12 +484,15 @@ impl PegOutGraph {
             withdrawer_public_key: None,
             withdrawer_taproot_public_key: None,
             withdrawer_evm_address: None,
+            withdrawer_request_signature: None,
             peg_out_transaction: None,
+            timelock_config: *timelock_config,
         }
     }
 
     pub fn new_for_validation(&self) -> Self {
         let peg_in_confirm_txid = self.take_1_transaction.tx().input[0].previous_output.txid; // Self-referencing
+        let timelock_config = &self.timelock_config;
 
         let kick_off_1_vout_0 = 0;
         let kick_off_1_transaction = KickOff1Transaction::new_for_validation(
@@ -444,6 +504,7 @@ impl PegOutGraph {
                 outpoint: self.kick_off_1_transaction.tx().input[kick_off_1_vout_0].previous_output, // Self-referencing
                 amount: self.kick_off_1_transaction.prev_outs()[kick_off_1_vout_0].value, // Self-referencing
             },
+            timelock_config,
         );
         let kick_off_1_txid = kick_off_1_transaction.tx().compute_txid();
 
@@ -482,6 +543,7 @@ impl PegOutGraph {
                 },
                 amount: kick_off_1_transaction.tx().output[start_time_timeout_vout_1].value,
             },
+            timelock_config,
         );
 
         let kick_off_2_vout_0 = 1;
@@ -497,6 +559,7 @@ impl PegOutGraph {
                 },
                 amount: kick_off_1_transaction.tx().output[kick_off_2_vout_0].value,
             },
+            timelock_config,
         );
         let kick_off_2_txid = kick_off_2_transaction.tx().compute_txid();
 
@@ -512,6 +575,7 @@ impl PegOutGraph {
                 },
                 amount: kick_off_1_transaction.tx().output[kick_off_timeout_vout_0].value,
             },
+            timelock_config,
         );
 
         let input_amount_crowdfunding = Amount::from_btc(1.0).unwrap(); // TODO replace placeholder
@@ -568,6 +632,7 @@ impl PegOutGraph {
                 },
                 amount: kick_off_2_transaction.tx().output[take_1_vout_3].value,
             },
+            timelock_config,
         );
 
         let assert_vout_0 = 1;
@@ -583,6 +648,7 @@ impl PegOutGraph {
                 },
                 amount: kick_off_2_transaction.tx().output[assert_vout_0].value,
             },
+            timelock_config,
         );
         let assert_txid = assert_transaction.tx().compute_txid();
 
@@ -623,6 +689,7 @@ impl PegOutGraph {
                 },
                 amount: assert_transaction.tx().output[take_2_vout_3].value,
             },
+            timelock_config,
         );
 
         let script_index = 1; // TODO replace placeholder
@@ -660,6 +727,7 @@ impl PegOutGraph {
                 },
                 amount: kick_off_2_transaction.tx().output[disprove_chain_vout_0].value,
             },
+            timelock_config,
         );
 
         PegOutGraph {
@@ -687,7 +755,9 @@ impl PegOutGraph {
             withdrawer_public_key: None,
             withdrawer_taproot_public_key: None,
             withdrawer_evm_address: None,
+            withdrawer_request_signature: None,
             peg_out_transaction: None,
+            timelock_config: self.timelock_config,
         }
     }
 
@@ -729,6 +799,54 @@ impl PegOutGraph {
         secret_nonces
     }
 
+    /// Outpoints spent by the graph that it does not create itself, i.e. the operator's inputs
+    /// of the kick-off 1 and peg-out transactions.
+    pub fn external_outpoints(&self) -> Vec<OutPoint> {
+        let mut outpoints: Vec<OutPoint> = self
+            .kick_off_1_transaction
+            .tx()
+            .input
+            .iter()
+            .map(|input| input.previous_output)
+            .collect();
+        if let Some(peg_out_transaction) = &self.peg_out_transaction {
+            outpoints.extend(
+                peg_out_transaction
+                    .tx()
+                    .input
+                    .iter()
+                    .map(|input| input.previous_output),
+            );
+        }
+
+        outpoints
+    }
+
+    /// Transactions with n-of-n inputs signed through MuSig2.
+    pub fn musig2_transactions(&self) -> Vec<&dyn Musig2Transaction> {
+        vec![
+            &self.assert_transaction,
+            &self.disprove_chain_transaction,
+            &self.disprove_transaction,
+            &self.kick_off_timeout_transaction,
+            &self.start_time_timeout_transaction,
+            &self.take_1_transaction,
+            &self.take_2_transaction,
+        ]
+    }
+
+    pub fn musig2_transactions_mut(&mut self) -> Vec<&mut dyn Musig2Transaction> {
+        vec![
+            &mut self.assert_transaction,
+            &mut self.disprove_chain_transaction,
+            &mut self.disprove_transaction,
+            &mut self.kick_off_timeout_transaction,
+            &mut self.start_time_timeout_transaction,
+            &mut self.take_1_transaction,
+            &mut self.take_2_transaction,
+        ]
+    }
+
     pub fn pre_sign(
         &mut self,
         context: &VerifierContext,
@@ -1001,6 +1119,170 @@ impl PegOutGraph {
         }
     }
 
+    pub async fn withdrawer_status(&self, client: &AsyncClient) -> PegOutWithdrawerStatus {
+        match &self.peg_out_transaction {
+            Some(peg_out_transaction) => {
+                let peg_out_status = client
+                    .get_tx_status(&peg_out_transaction.tx().compute_txid())
+                    .await;
+                if peg_out_status.is_ok_and(|status| status.confirmed) {
+                    PegOutWithdrawerStatus::PegOutComplete
+                } else {
+                    PegOutWithdrawerStatus::PegOutWait
+                }
+            }
+            None if self.withdrawer_public_key.is_some() => PegOutWithdrawerStatus::PegOutRequested,
+            None => PegOutWithdrawerStatus::PegOutNotRequested,
+        }
+    }
+
+    pub fn is_presigned(&self) -> bool { self.n_of_n_presigned }
+
+    pub fn operator_public_key(&self) -> &PublicKey { &self.operator_public_key }
+
+    pub fn withdrawer_public_key(&self) -> Option<&PublicKey> {
+        self.withdrawer_public_key.as_ref()
+    }
+
+    pub fn withdrawer_evm_address(&self) -> Option<&String> { self.withdrawer_evm_address.as_ref() }
+
+    pub fn peg_out_transaction_ref(&self) -> Option<&PegOutTransaction> {
+        self.peg_out_transaction.as_ref()
+    }
+
+    /// Records the withdrawer's request to be paid out by this graph's operator, after burning
+    /// the wrapped BTC from `evm_address` on the destination network.
+    pub fn request_peg_out(
+        &mut self,
+        context: &WithdrawerContext,
+        evm_address: &str,
+    ) -> Result<(), String> {
+        if !self.n_of_n_presigned {
+            return Err(format!(
+                "Peg-out graph is not presigned yet (Graph id: {})",
+                self.id
+            ));
+        }
+        if self.peg_out_transaction.is_some() {
+            return Err(format!("Peg-out already sent (Graph id: {})", self.id));
+        }
+        if self
+            .withdrawer_public_key
+            .is_some_and(|public_key| public_key != context.withdrawer_public_key)
+        {
+            return Err(format!(
+                "Peg-out already requested by another withdrawer (Graph id: {})",
+                self.id
+            ));
+        }
+
+        self.withdrawer_public_key = Some(context.withdrawer_public_key);
+        self.withdrawer_taproot_public_key = Some(context.withdrawer_taproot_public_key);
+        self.withdrawer_evm_address = Some(evm_address.to_string());
+        self.withdrawer_request_signature = Some(context.secp.sign_schnorr(
+            &peg_out_request_message(&self.id, &context.withdrawer_public_key, evm_address),
+            &context.withdrawer_keypair,
+        ));
+
+        Ok(())
+    }
+
+    /// Checks that the recorded peg-out request is signed by the withdrawer it pays out to.
+    pub fn verify_peg_out_request(&self) -> Result<(), String> {
+        let (withdrawer_public_key, evm_address, signature) = match (
+            &self.withdrawer_public_key,
+            &self.withdrawer_evm_address,
+            &self.withdrawer_request_signature,
+        ) {
+            (Some(public_key), Some(evm_address), Some(signature)) => {
+                (public_key, evm_address, signature)
+            }
+            _ => return Err(format!("No peg-out requested (Graph id: {})", self.id)),
+        };
+
+        Secp256k1::verification_only()
+            .verify_schnorr(
+                signature,
+                &peg_out_request_message(&self.id, withdrawer_public_key, evm_address),
+                &XOnlyPublicKey::from(*withdrawer_public_key),
+            )
+            .map_err(|err| {
+                format!(
+                    "Invalid withdrawer signature on the peg-out request (Graph id: {}): {}",
+                    self.id, err
+                )
+            })
+    }
+
+    /// Finds the peg-out initiated on the destination network for the recorded request: the
+    /// wrapped BTC burnt from the withdrawer's EVM address for this graph's operator and peg-in,
+    /// paying `amount` to the withdrawer's key.
+    pub fn find_peg_out_event<'a>(
+        &self,
+        peg_out_events: &'a [PegOutEvent],
+        amount: Amount,
+    ) -> Option<&'a PegOutEvent> {
+        let withdrawer_public_key = self.withdrawer_public_key.as_ref()?;
+        let evm_address = self.withdrawer_evm_address.as_ref()?;
+
+        peg_out_events.iter().find(|event| {
+            event
+                .withdrawer_chain_address
+                .eq_ignore_ascii_case(evm_address)
+                && event.withdrawer_public_key_hash == withdrawer_public_key.pubkey_hash()
+                && event.operator_public_key == self.operator_public_key
+                && event.source_outpoint.txid == self.peg_in_confirm_txid
+                && event.amount == amount
+        })
+    }
+
+    /// Sends the requested peg-out to the withdrawer from the operator's `input`, once the
+    /// request is signed by the withdrawer and matched by one of `peg_out_events` for the amount
+    /// paid out.
+    #[instrument(name = "transaction", skip_all, fields(graph_id = %self.id, transaction = "peg_out"))]
+    pub async fn peg_out(
+        &mut self,
+        client: &AsyncClient,
+        context: &OperatorContext,
+        input: Input,
+        peg_out_events: &[PegOutEvent],
+    ) {
+        if context.operator_public_key != self.operator_public_key {
+            panic!("Peg-out can only be sent by the graph's operator!");
+        }
+        if self.peg_out_transaction.is_some() {
+            panic!("Peg-out already sent!");
+        }
+        if let Err(err) = self.verify_peg_out_request() {
+            panic!("{}", err);
+        }
+        let amount = input
+            .amount
+            .checked_sub(Amount::from_sat(FEE_AMOUNT))
+            .expect("Peg-out input does not cover the fee!");
+        let peg_out_event = match self.find_peg_out_event(peg_out_events, amount) {
+            Some(peg_out_event) => peg_out_event,
+            None => panic!("No matching peg-out burn found on the destination network!"),
+        };
+
+        let peg_out_transaction = PegOutTransaction::new(
+            context,
+            self.withdrawer_public_key.as_ref().unwrap(),
+            self.withdrawer_evm_address.as_ref().unwrap(),
+            peg_out_event.timestamp,
+            input,
+        );
+
+        // broadcast peg-out tx
+        let peg_out_result = client.broadcast(&peg_out_transaction.finalize()).await;
+
+        // verify peg-out tx result
+        verify_tx_result(&peg_out_result, "peg_out");
+
+        self.peg_out_transaction = Some(peg_out_transaction);
+    }
+
+    #[instrument(name = "transaction", skip_all, fields(graph_id = %self.id, transaction = "kick_off_1"))]
     pub async fn kick_off_1(&mut self, client: &AsyncClient) {
         verify_if_not_mined(&client, self.kick_off_1_transaction.tx().compute_txid()).await;
 
@@ -1011,9 +1293,10 @@ impl PegOutGraph {
         let kick_off_1_result = client.broadcast(&kick_off_1_tx).await;
 
         // verify kick-off 1 tx result
-        verify_tx_result(&kick_off_1_result);
+        verify_tx_result(&kick_off_1_result, "kick_off_1");
     }
 
+    #[instrument(name = "transaction", skip_all, fields(graph_id = %self.id, transaction = "challenge"))]
     pub async fn challenge(
         &mut self,
         client: &AsyncClient,
@@ -1041,12 +1324,13 @@ impl PegOutGraph {
             let challenge_result = client.broadcast(&challenge_tx).await;
 
             // verify challenge tx result
-            verify_tx_result(&challenge_result);
+            verify_tx_result(&challenge_result, "challenge");
         } else {
             panic!("Kick-off 1 tx has not been confirmed!");
         }
     }
 
+    #[instrument(name = "transaction", skip_all, fields(graph_id = %self.id, transaction = "start_time"))]
     pub async fn start_time(&mut self, client: &AsyncClient) {
         verify_if_not_mined(client, self.start_time_transaction.tx().compute_txid()).await;
 
@@ -1061,12 +1345,13 @@ impl PegOutGraph {
             let start_time_result = client.broadcast(&start_time_tx).await;
 
             // verify start time tx result
-            verify_tx_result(&start_time_result);
+            verify_tx_result(&start_time_result, "start_time");
         } else {
             panic!("Kick-off 1 tx has not been confirmed!");
         }
     }
 
+    #[instrument(name = "transaction", skip_all, fields(graph_id = %self.id, transaction = "start_time_timeout"))]
     pub async fn start_time_timeout(
         &mut self,
         client: &AsyncClient,
@@ -1105,7 +1390,7 @@ impl PegOutGraph {
                 let start_time_timeout_result = client.broadcast(&start_time_timeout_tx).await;
 
                 // verify start time timeout tx result
-                verify_tx_result(&start_time_timeout_result);
+                verify_tx_result(&start_time_timeout_result, "start_time_timeout");
             } else {
                 panic!("Kick-off 1 timelock has not elapsed!");
             }
@@ -1114,6 +1399,7 @@ impl PegOutGraph {
         }
     }
 
+    #[instrument(name = "transaction", skip_all, fields(graph_id = %self.id, transaction = "kick_off_2"))]
     pub async fn kick_off_2(&mut self, client: &AsyncClient) {
         verify_if_not_mined(client, self.kick_off_2_transaction.tx().compute_txid()).await;
 
@@ -1142,7 +1428,7 @@ impl PegOutGraph {
                 let kick_off_2_result = client.broadcast(&kick_off_2_tx).await;
 
                 // verify kick-off 2 tx result
-                verify_tx_result(&kick_off_2_result);
+                verify_tx_result(&kick_off_2_result, "kick_off_2");
             } else {
                 panic!("Kick-off 1 timelock has not elapsed!");
             }
@@ -1151,6 +1437,7 @@ impl PegOutGraph {
         }
     }
 
+    #[instrument(name = "transaction", skip_all, fields(graph_id = %self.id, transaction = "kick_off_timeout"))]
     pub async fn kick_off_timeout(
         &mut self,
         client: &AsyncClient,
@@ -1189,7 +1476,7 @@ impl PegOutGraph {
                 let kick_off_timeout_result = client.broadcast(&kick_off_timeout_tx).await;
 
                 // verify kick-off timeout tx result
-                verify_tx_result(&kick_off_timeout_result);
+                verify_tx_result(&kick_off_timeout_result, "kick_off_timeout");
             } else {
                 panic!("Kick-off 1 timelock has not elapsed!");
             }
@@ -1198,6 +1485,7 @@ impl PegOutGraph {
         }
     }
 
+    #[instrument(name = "transaction", skip_all, fields(graph_id = %self.id, transaction = "assert"))]
     pub async fn assert(&mut self, client: &AsyncClient) {
         verify_if_not_mined(client, self.assert_transaction.tx().compute_txid()).await;
 
@@ -1226,7 +1514,7 @@ impl PegOutGraph {
                 let assert_result = client.broadcast(&assert_tx).await;
 
                 // verify assert tx result
-                verify_tx_result(&assert_result);
+                verify_tx_result(&assert_result, "assert");
             } else {
                 panic!("Kick-off 2 timelock has not elapsed!");
             }
@@ -1235,6 +1523,7 @@ impl PegOutGraph {
         }
     }
 
+    #[instrument(name = "transaction", skip_all, fields(graph_id = %self.id, transaction = "disprove"))]
     pub async fn disprove(
         &mut self,
         client: &AsyncClient,
@@ -1256,12 +1545,13 @@ impl PegOutGraph {
             let disprove_result = client.broadcast(&disprove_tx).await;
 
             // verify disprove tx result
-            verify_tx_result(&disprove_result);
+            verify_tx_result(&disprove_result, "disprove");
         } else {
             panic!("Assert tx has not been confirmed!");
         }
     }
 
+    #[instrument(name = "transaction", skip_all, fields(graph_id = %self.id, transaction = "disprove_chain"))]
     pub async fn disprove_chain(&mut self, client: &AsyncClient, output_script_pubkey: ScriptBuf) {
         verify_if_not_mined(client, self.disprove_chain_transaction.tx().compute_txid()).await;
 
@@ -1278,12 +1568,13 @@ impl PegOutGraph {
             let disprove_chain_result = client.broadcast(&disprove_chain_tx).await;
 
             // verify disprove chain tx result
-            verify_tx_result(&disprove_chain_result);
+            verify_tx_result(&disprove_chain_result, "disprove_chain");
         } else {
             panic!("Kick-off 2 tx has not been confirmed!");
         }
     }
 
+    #[instrument(name = "transaction", skip_all, fields(graph_id = %self.id, transaction = "take_1"))]
     pub async fn take_1(&mut self, client: &AsyncClient) {
         verify_if_not_mined(&client, self.take_1_transaction.tx().compute_txid()).await;
         verify_if_not_mined(&client, self.challenge_transaction.tx().compute_txid()).await;
@@ -1323,7 +1614,7 @@ impl PegOutGraph {
                 let take_1_result = client.broadcast(&take_1_tx).await;
 
                 // verify take 1 tx result
-                verify_tx_result(&take_1_result);
+                verify_tx_result(&take_1_result, "take_1");
             } else {
                 panic!("Kick-off 2 tx timelock has not elapsed!");
             }
@@ -1332,6 +1623,7 @@ impl PegOutGraph {
         }
     }
 
+    #[instrument(name = "transaction", skip_all, fields(graph_id = %self.id, transaction = "take_2"))]
     pub async fn take_2(&mut self, client: &AsyncClient) {
         verify_if_not_mined(&client, self.take_2_transaction.tx().compute_txid()).await;
         verify_if_not_mined(&client, self.take_1_transaction.tx().compute_txid()).await;
@@ -1362,7 +1654,7 @@ impl PegOutGraph {
                 let take_2_result = client.broadcast(&take_2_tx).await;
 
                 // verify take 2 tx result
-                verify_tx_result(&take_2_result);
+                verify_tx_result(&take_2_result, "take_2");
             } else {
                 panic!("Assert tx timelock has not elapsed!");
             }
@@ -1464,8 +1756,15 @@ impl PegOutGraph {
         );
     }
 
+    pub fn timelock_config(&self) -> &TimelockConfig { &self.timelock_config }
+
     pub fn validate(&self) -> bool {
         let mut ret_val = true;
+        if let Err(err) = self.timelock_config.validate() {
+            warn!(graph_id = %self.id, "Invalid timelock config on peg-out graph: {}", err);
+            ret_val = false;
+        }
+
         let peg_out_graph = self.new_for_validation();
         if !validate_transaction(
             self.assert_transaction.tx(),
@@ -1534,6 +1833,26 @@ impl PegOutGraph {
             ret_val = false;
         }
 
+        // Connector C only serializes its parameters and the output key it commits to
+        if self.disprove_transaction.connector_c()
+            != peg_out_graph.disprove_transaction.connector_c()
+            || self.take_2_transaction.connector_c()
+                != peg_out_graph.take_2_transaction.connector_c()
+        {
+            warn!(graph_id = %self.id, "Connector C mismatch on peg-out graph");
+            ret_val = false;
+        }
+
+        if self.withdrawer_public_key.map(XOnlyPublicKey::from)
+            != self.withdrawer_taproot_public_key
+            || self.withdrawer_public_key.is_some() != self.withdrawer_evm_address.is_some()
+            || (self.withdrawer_public_key.is_some() && self.verify_peg_out_request().is_err())
+            || (self.peg_out_transaction.is_some() && self.withdrawer_public_key.is_none())
+        {
+            warn!(graph_id = %self.id, "Invalid peg-out request on peg-out graph");
+            ret_val = false;
+        }
+
         if !verify_public_nonces_for_tx(&self.assert_transaction) {
             ret_val = false;
         }
@@ -1559,9 +1878,109 @@ impl PegOutGraph {
             ret_val = false;
         }
 
+        let report = self.validate_scripts();
+        if !report.is_valid() {
+            warn!(graph_id = %self.id, "Script validation failed on peg-out graph\n{}", report);
+            ret_val = false;
+        }
+
         ret_val
     }
 
+    /// Executes the witnesses of every signed input in the graph against the outputs it spends.
+    pub fn validate_scripts(&self) -> ScriptValidationReport {
+        let mut report = ScriptValidationReport::new();
+        report.validate_psbt_transaction("assert", &self.assert_transaction);
+        report.validate_psbt_transaction("challenge", &self.challenge_transaction);
+        report.validate_psbt_transaction("disprove chain", &self.disprove_chain_transaction);
+        report.validate_psbt_transaction("disprove", &self.disprove_transaction);
+        report.validate_psbt_transaction("kick-off 1", &self.kick_off_1_transaction);
+        report.validate_psbt_transaction("kick-off 2", &self.kick_off_2_transaction);
+        report.validate_psbt_transaction("kick-off timeout", &self.kick_off_timeout_transaction);
+        report.validate_psbt_transaction("start time", &self.start_time_transaction);
+        report
+            .validate_psbt_transaction("start time timeout", &self.start_time_timeout_transaction);
+        report.validate_psbt_transaction("take 1", &self.take_1_transaction);
+        report.validate_psbt_transaction("take 2", &self.take_2_transaction);
+        if let Some(peg_out_transaction) = &self.peg_out_transaction {
+            report.validate_psbt_transaction("peg-out", peg_out_transaction);
+        }
+
+        report
+    }
+
+    /// Exports a transaction of the graph as a PSBT to be signed outside of the client. The
+    /// transaction is named like the broadcast commands, e.g. `kick_off_1`. The transactions listed
+    /// by `psbt_previous_txids` must be passed in `previous_transactions`.
+    pub fn export_psbt(
+        &self,
+        transaction_name: &str,
+        previous_transactions: &[Transaction],
+        key_origins: &HashMap<PublicKey, KeySource>,
+    ) -> Result<Psbt, String> {
+        self.psbt_transaction(transaction_name)?
+            .export_psbt(previous_transactions, key_origins)
+    }
+
+    /// Transactions created outside of the graph that `export_psbt` needs for `transaction_name`.
+    pub fn psbt_previous_txids(&self, transaction_name: &str) -> Result<Vec<Txid>, String> {
+        Ok(self
+            .psbt_transaction(transaction_name)?
+            .segwit_v0_previous_txids())
+    }
+
+    /// Adds the signatures of an externally signed PSBT, see `export_psbt`. Returns the number of
+    /// inputs that were signed by it.
+    pub fn import_psbt(&mut self, transaction_name: &str, psbt: &Psbt) -> Result<usize, String> {
+        self.psbt_transaction_mut(transaction_name)?
+            .import_psbt(psbt)
+    }
+
+    fn psbt_transaction(&self, transaction_name: &str) -> Result<&dyn PsbtTransaction, String> {
+        match transaction_name {
+            "assert" => Ok(&self.assert_transaction),
+            "challenge" => Ok(&self.challenge_transaction),
+            "disprove_chain" => Ok(&self.disprove_chain_transaction),
+            "disprove" => Ok(&self.disprove_transaction),
+            "kick_off_1" => Ok(&self.kick_off_1_transaction),
+            "kick_off_2" => Ok(&self.kick_off_2_transaction),
+            "kick_off_timeout" => Ok(&self.kick_off_timeout_transaction),
+            "start_time" => Ok(&self.start_time_transaction),
+            "start_time_timeout" => Ok(&self.start_time_timeout_transaction),
+            "take_1" => Ok(&self.take_1_transaction),
+            "take_2" => Ok(&self.take_2_transaction),
+            "peg_out" => match &self.peg_out_transaction {
+                Some(peg_out_transaction) => Ok(peg_out_transaction),
+                None => Err(String::from("Peg-out transaction not created yet")),
+            },
+            _ => Err(format!("Unknown peg-out transaction: {}", transaction_name)),
+        }
+    }
+
+    fn psbt_transaction_mut(
+        &mut self,
+        transaction_name: &str,
+    ) -> Result<&mut dyn PsbtTransaction, String> {
+        match transaction_name {
+            "assert" => Ok(&mut self.assert_transaction),
+            "challenge" => Ok(&mut self.challenge_transaction),
+            "disprove_chain" => Ok(&mut self.disprove_chain_transaction),
+            "disprove" => Ok(&mut self.disprove_transaction),
+            "kick_off_1" => Ok(&mut self.kick_off_1_transaction),
+            "kick_off_2" => Ok(&mut self.kick_off_2_transaction),
+            "kick_off_timeout" => Ok(&mut self.kick_off_timeout_transaction),
+            "start_time" => Ok(&mut self.start_time_transaction),
+            "start_time_timeout" => Ok(&mut self.start_time_timeout_transaction),
+            "take_1" => Ok(&mut self.take_1_transaction),
+            "take_2" => Ok(&mut self.take_2_transaction),
+            "peg_out" => match &mut self.peg_out_transaction {
+                Some(peg_out_transaction) => Ok(peg_out_transaction),
+                None => Err(String::from("Peg-out transaction not created yet")),
+            },
+            _ => Err(format!("Unknown peg-out transaction: {}", transaction_name)),
+        }
+    }
+
     pub fn merge(&mut self, source_peg_out_graph: &PegOutGraph) {
         self.assert_transaction
             .merge(&source_peg_out_graph.assert_transaction);
@@ -1589,6 +2008,16 @@ impl PegOutGraph {
 
         self.take_2_transaction
             .merge(&source_peg_out_graph.take_2_transaction);
+
+        if self.withdrawer_public_key.is_none() {
+            self.withdrawer_public_key = source_peg_out_graph.withdrawer_public_key;
+            self.withdrawer_taproot_public_key = source_peg_out_graph.withdrawer_taproot_public_key;
+            self.withdrawer_evm_address = source_peg_out_graph.withdrawer_evm_address.clone();
+            self.withdrawer_request_signature = source_peg_out_graph.withdrawer_request_signature;
+        }
+        if self.peg_out_transaction.is_none() {
+            self.peg_out_transaction = source_peg_out_graph.peg_out_transaction.clone();
+        }
     }
 }
 
@@ -1599,3 +2028,15 @@ pub fn generate_id(peg_in_graph: &PegInGraph, operator_public_key: &PublicKey) -
 
     hasher.finalize().to_hex_string(Upper)
 }
+
+fn peg_out_request_message(
+    peg_out_graph_id: &str,
+    withdrawer_public_key: &PublicKey,
+    evm_address: &str,
+) -> Message {
+    let mut data = PEG_OUT_REQUEST_TAG.to_vec();
+    data.extend(peg_out_graph_id.as_bytes());
+    data.extend(withdrawer_public_key.to_bytes());
+    data.extend(evm_address.as_bytes());
+    Message::from_hashed_data::<sha256::Hash>(&data)
+}
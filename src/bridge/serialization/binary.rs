@@ -0,0 +1,676 @@
+//! Compact and deterministic binary encoding for bridge data.
+//!
+//! Data is encoded through its JSON data model, so binary files carry the same field names and
+//! graph versions as JSON files and older layouts are migrated the same way. Every value starts
+//! with a type tag and integers and lengths are LEB128 encoded. Strings of lowercase hex are stored
+//! as the bytes they encode: transactions consensus encoded, public keys in their compressed or
+//! x-only encoding, both tagged as such and validated when decoded, and any other hex, such as
+//! scripts, as plain bytes. Byte strings that occur more than once (mostly field names, keys and
+//! scripts shared by several transactions of a graph) are stored once in a table at the start of
+//! the data and referenced by index. Object keys are sorted, so the same value always produces
+//! the same bytes.
+
+use bitcoin::{consensus, secp256k1::PublicKey, Transaction, XOnlyPublicKey};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{Map, Number, Value};
+use std::collections::HashMap;
+
+const MAGIC: &[u8; 4] = b"BVMB";
+const FORMAT_VERSION: u8 = 1;
+
+// Shorter byte strings are always stored inline, a table reference would not save any space.
+const MIN_SHARED_BYTES_LEN: usize = 4;
+
+// Same nesting limit as serde_json, so decoding untrusted data can not overflow the stack.
+const MAX_DEPTH: usize = 128;
+
+const TAG_NULL: u8 = 0;
+const TAG_FALSE: u8 = 1;
+const TAG_TRUE: u8 = 2;
+const TAG_U64: u8 = 3;
+const TAG_I64: u8 = 4;
+const TAG_F64: u8 = 5;
+const TAG_STRING: u8 = 6;
+const TAG_HEX_STRING: u8 = 7;
+const TAG_ARRAY: u8 = 8;
+const TAG_OBJECT: u8 = 9;
+const TAG_PUBLIC_KEY: u8 = 10;
+const TAG_X_ONLY_PUBLIC_KEY: u8 = 11;
+const TAG_TRANSACTION: u8 = 12;
+
+pub fn serialize(object: &impl Serialize) -> Vec<u8> { try_serialize(object).unwrap() }
+
+pub fn try_serialize(object: &impl Serialize) -> Result<Vec<u8>, String> {
+    match serde_json::to_value(object) {
+        Ok(value) => Ok(serialize_value(&value)),
+        Err(err) => Err(format!("Failed to serialize binary data: {}", err)),
+    }
+}
+
+pub fn serialize_value(value: &Value) -> Vec<u8> {
+    // The first pass only finds the byte strings that are repeated, the second pass encodes the
+    // data against the table of those byte strings.
+    let mut bytes_count = HashMap::new();
+    count_bytes(value, &mut bytes_count);
+    let mut shared_bytes: Vec<Vec<u8>> = bytes_count
+        .into_iter()
+        .filter(|(_, count)| *count > 1)
+        .map(|(bytes, _)| bytes)
+        .collect();
+    shared_bytes.sort();
+
+    let mut encoder = Encoder {
+        output: MAGIC.to_vec(),
+        shared_bytes_indexes: shared_bytes
+            .iter()
+            .enumerate()
+            .map(|(index, bytes)| (bytes.clone(), index))
+            .collect(),
+    };
+    encoder.output.push(FORMAT_VERSION);
+    write_varint(&mut encoder.output, shared_bytes.len() as u64);
+    for bytes in shared_bytes.iter() {
+        write_varint(&mut encoder.output, bytes.len() as u64);
+        encoder.output.extend_from_slice(bytes);
+    }
+    encoder.value(value);
+
+    encoder.output
+}
+
+pub fn deserialize<T>(data: &[u8]) -> T
+where
+    T: DeserializeOwned,
+{
+    try_deserialize(data).unwrap()
+}
+
+pub fn try_deserialize<T>(data: &[u8]) -> Result<T, String>
+where
+    T: DeserializeOwned,
+{
+    match serde_json::from_value(try_deserialize_value(data)?) {
+        Ok(object) => Ok(object),
+        Err(err) => Err(format!("Failed to parse binary data: {}", err)),
+    }
+}
+
+/// Decodes binary data into its JSON data model, e.g. to migrate it before deserializing.
+pub fn try_deserialize_value(data: &[u8]) -> Result<Value, String> {
+    match Decoder::new(data).and_then(|mut decoder| {
+        let value = decoder.value(0)?;
+        if !decoder.input.is_empty() {
+            return Err(String::from("Trailing bytes after binary data"));
+        }
+        Ok(value)
+    }) {
+        Ok(value) => Ok(value),
+        Err(err) => Err(format!("Failed to parse binary data: {}", err)),
+    }
+}
+
+fn write_varint(output: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        output.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    output.push(value as u8);
+}
+
+fn zigzag_encode(value: i64) -> u64 { ((value << 1) ^ (value >> 63)) as u64 }
+
+fn zigzag_decode(value: u64) -> i64 { ((value >> 1) as i64) ^ -((value & 1) as i64) }
+
+fn hex_string_bytes(string: &str) -> Option<Vec<u8>> {
+    if string.is_empty()
+        || string.len() % 2 != 0
+        || !string
+            .bytes()
+            .all(|c| matches!(c, b'0'..=b'9' | b'a'..=b'f'))
+    {
+        return None;
+    }
+
+    Some(
+        (0..string.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&string[i..i + 2], 16).unwrap())
+            .collect(),
+    )
+}
+
+// Hex strings are tagged with the type their bytes decode as. Only encodings that decode back to
+// the same bytes are tagged, so every hex string round trips unchanged.
+fn hex_bytes_tag(bytes: &[u8]) -> u8 {
+    if bytes.len() == 33 && PublicKey::from_slice(bytes).is_ok() {
+        TAG_PUBLIC_KEY
+    } else if bytes.len() == 32 && XOnlyPublicKey::from_slice(bytes).is_ok() {
+        TAG_X_ONLY_PUBLIC_KEY
+    } else if consensus::deserialize::<Transaction>(bytes)
+        .is_ok_and(|tx| consensus::serialize(&tx) == bytes)
+    {
+        TAG_TRANSACTION
+    } else {
+        TAG_HEX_STRING
+    }
+}
+
+fn count_bytes(value: &Value, bytes_count: &mut HashMap<Vec<u8>, usize>) {
+    match value {
+        Value::String(string) => count_shared_bytes(
+            hex_string_bytes(string).unwrap_or_else(|| string.as_bytes().to_vec()),
+            bytes_count,
+        ),
+        Value::Array(values) => {
+            for value in values.iter() {
+                count_bytes(value, bytes_count);
+            }
+        }
+        Value::Object(map) => {
+            for (key, value) in map.iter() {
+                count_shared_bytes(key.as_bytes().to_vec(), bytes_count);
+                count_bytes(value, bytes_count);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn count_shared_bytes(bytes: Vec<u8>, bytes_count: &mut HashMap<Vec<u8>, usize>) {
+    if bytes.len() >= MIN_SHARED_BYTES_LEN {
+        *bytes_count.entry(bytes).or_insert(0) += 1;
+    }
+}
+
+fn hex_string(bytes: &[u8]) -> Value {
+    Value::String(bytes.iter().map(|byte| format!("{:02x}", byte)).collect())
+}
+
+struct Encoder {
+    output: Vec<u8>,
+    shared_bytes_indexes: HashMap<Vec<u8>, usize>,
+}
+
+impl Encoder {
+    // Byte strings are prefixed with `len << 1`, or `index << 1 | 1` if they are in the table.
+    fn bytes(&mut self, bytes: &[u8]) {
+        match self.shared_bytes_indexes.get(bytes) {
+            Some(index) => write_varint(&mut self.output, ((*index as u64) << 1) | 1),
+            None => {
+                write_varint(&mut self.output, (bytes.len() as u64) << 1);
+                self.output.extend_from_slice(bytes);
+            }
+        }
+    }
+
+    fn value(&mut self, value: &Value) {
+        match value {
+            Value::Null => self.output.push(TAG_NULL),
+            Value::Bool(false) => self.output.push(TAG_FALSE),
+            Value::Bool(true) => self.output.push(TAG_TRUE),
+            Value::Number(number) => {
+                if let Some(value) = number.as_u64() {
+                    self.output.push(TAG_U64);
+                    write_varint(&mut self.output, value);
+                } else if let Some(value) = number.as_i64() {
+                    self.output.push(TAG_I64);
+                    write_varint(&mut self.output, zigzag_encode(value));
+                } else {
+                    self.output.push(TAG_F64);
+                    let value = number.as_f64().unwrap_or(f64::NAN);
+                    self.output.extend_from_slice(&value.to_le_bytes());
+                }
+            }
+            Value::String(string) => match hex_string_bytes(string) {
+                Some(bytes) => {
+                    self.output.push(hex_bytes_tag(&bytes));
+                    self.bytes(&bytes);
+                }
+                None => {
+                    self.output.push(TAG_STRING);
+                    self.bytes(string.as_bytes());
+                }
+            },
+            Value::Array(values) => {
+                self.output.push(TAG_ARRAY);
+                write_varint(&mut self.output, values.len() as u64);
+                for value in values.iter() {
+                    self.value(value);
+                }
+            }
+            Value::Object(map) => {
+                // Sorted explicitly, serde_json keeps insertion order with `preserve_order`
+                let mut entries: Vec<(&String, &Value)> = map.iter().collect();
+                entries.sort_by(|a, b| a.0.cmp(b.0));
+
+                self.output.push(TAG_OBJECT);
+                write_varint(&mut self.output, entries.len() as u64);
+                for (key, value) in entries {
+                    self.bytes(key.as_bytes());
+                    self.value(value);
+                }
+            }
+        }
+    }
+}
+
+struct Decoder<'de> {
+    input: &'de [u8],
+    shared_bytes: Vec<&'de [u8]>,
+}
+
+impl<'de> Decoder<'de> {
+    fn new(data: &'de [u8]) -> Result<Self, String> {
+        if !data.starts_with(MAGIC) {
+            return Err(String::from("Missing binary data header"));
+        }
+        let mut decoder = Decoder {
+            input: &data[MAGIC.len()..],
+            shared_bytes: Vec::new(),
+        };
+
+        let format_version = decoder.read_u8()?;
+        if format_version != FORMAT_VERSION {
+            return Err(format!(
+                "Unsupported binary format version {}, the latest version supported by this client is {}",
+                format_version, FORMAT_VERSION
+            ));
+        }
+
+        let shared_bytes_len = decoder.read_len()?;
+        for _ in 0..shared_bytes_len {
+            let len = decoder.read_len()?;
+            let bytes = decoder.read_slice(len)?;
+            decoder.shared_bytes.push(bytes);
+        }
+
+        Ok(decoder)
+    }
+
+    fn read_slice(&mut self, len: usize) -> Result<&'de [u8], String> {
+        if self.input.len() < len {
+            return Err(String::from("Unexpected end of binary data"));
+        }
+        let (slice, rest) = self.input.split_at(len);
+        self.input = rest;
+        Ok(slice)
+    }
+
+    fn read_u8(&mut self) -> Result<u8, String> { Ok(self.read_slice(1)?[0]) }
+
+    fn read_varint(&mut self) -> Result<u64, String> {
+        let mut value: u64 = 0;
+        let mut shift = 0;
+        loop {
+            let byte = self.read_u8()?;
+            if shift >= 64 || (shift == 63 && byte > 1) {
+                return Err(String::from("Varint overflows 64 bits"));
+            }
+            value |= ((byte & 0x7f) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+            shift += 7;
+        }
+    }
+
+    // Every element takes at least one byte, so no length can exceed the remaining input.
+    fn read_len(&mut self) -> Result<usize, String> {
+        let len = self.read_varint()?;
+        if len > self.input.len() as u64 {
+            return Err(String::from("Unexpected end of binary data"));
+        }
+        Ok(len as usize)
+    }
+
+    fn read_bytes(&mut self) -> Result<&'de [u8], String> {
+        let prefix = self.read_varint()?;
+        if prefix & 1 == 1 {
+            return match self.shared_bytes.get((prefix >> 1) as usize) {
+                Some(bytes) => Ok(*bytes),
+                None => Err(format!("Invalid shared bytes index {}", prefix >> 1)),
+            };
+        }
+        if prefix >> 1 > self.input.len() as u64 {
+            return Err(String::from("Unexpected end of binary data"));
+        }
+        self.read_slice((prefix >> 1) as usize)
+    }
+
+    fn read_string(&mut self) -> Result<String, String> {
+        match std::str::from_utf8(self.read_bytes()?) {
+            Ok(string) => Ok(string.to_string()),
+            Err(err) => Err(format!("Invalid string in binary data: {}", err)),
+        }
+    }
+
+    fn value(&mut self, depth: usize) -> Result<Value, String> {
+        if depth > MAX_DEPTH {
+            return Err(String::from("Binary data is nested too deeply"));
+        }
+
+        match self.read_u8()? {
+            TAG_NULL => Ok(Value::Null),
+            TAG_FALSE => Ok(Value::Bool(false)),
+            TAG_TRUE => Ok(Value::Bool(true)),
+            TAG_U64 => Ok(Value::from(self.read_varint()?)),
+            TAG_I64 => Ok(Value::from(zigzag_decode(self.read_varint()?))),
+            TAG_F64 => {
+                let mut bytes = [0u8; 8];
+                bytes.copy_from_slice(self.read_slice(8)?);
+                match Number::from_f64(f64::from_le_bytes(bytes)) {
+                    Some(number) => Ok(Value::Number(number)),
+                    None => Err(String::from("Invalid float in binary data")),
+                }
+            }
+            TAG_STRING => Ok(Value::String(self.read_string()?)),
+            TAG_HEX_STRING => Ok(hex_string(self.read_bytes()?)),
+            TAG_PUBLIC_KEY => {
+                let bytes = self.read_bytes()?;
+                match PublicKey::from_slice(bytes) {
+                    Ok(_) if bytes.len() == 33 => Ok(hex_string(bytes)),
+                    _ => Err(String::from("Invalid public key in binary data")),
+                }
+            }
+            TAG_X_ONLY_PUBLIC_KEY => {
+                let bytes = self.read_bytes()?;
+                match XOnlyPublicKey::from_slice(bytes) {
+                    Ok(_) => Ok(hex_string(bytes)),
+                    Err(err) => Err(format!("Invalid x-only public key in binary data: {}", err)),
+                }
+            }
+            TAG_TRANSACTION => {
+                let bytes = self.read_bytes()?;
+                match consensus::deserialize::<Transaction>(bytes) {
+                    Ok(tx) if consensus::serialize(&tx) == bytes => Ok(hex_string(bytes)),
+                    Ok(_) => Err(String::from(
+                        "Non-canonical transaction encoding in binary data",
+                    )),
+                    Err(err) => Err(format!("Invalid transaction in binary data: {}", err)),
+                }
+            }
+            TAG_ARRAY => {
+                let len = self.read_len()?;
+                let mut values = Vec::with_capacity(len);
+                for _ in 0..len {
+                    values.push(self.value(depth + 1)?);
+                }
+                Ok(Value::Array(values))
+            }
+            TAG_OBJECT => {
+                let len = self.read_len()?;
+                let mut map = Map::new();
+                for _ in 0..len {
+                    let key = self.read_string()?;
+                    let value = self.value(depth + 1)?;
+                    if map.insert(key, value).is_some() {
+                        return Err(String::from("Duplicate key in binary data"));
+                    }
+                }
+                Ok(Value::Object(map))
+            }
+            tag => Err(format!("Invalid value tag {} in binary data", tag)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::{
+        absolute::LockTime, consensus, hashes::Hash, transaction::Version, Amount, Network,
+        OutPoint, PublicKey, ScriptBuf, Sequence, Transaction, TxIn, TxOut, Txid, Witness,
+        XOnlyPublicKey,
+    };
+    use serde::{Deserialize, Serialize};
+    use serde_json::json;
+    use std::{collections::HashMap, str::FromStr};
+
+    use super::{
+        serialize, serialize_value, try_deserialize, try_deserialize_value, TAG_PUBLIC_KEY,
+        TAG_TRANSACTION, TAG_X_ONLY_PUBLIC_KEY,
+    };
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    enum Kind {
+        Unit,
+        Newtype(u32),
+        Struct { value: i64 },
+    }
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Sample {
+        name: String,
+        amount: u64,
+        delta: i32,
+        flag: bool,
+        kinds: Vec<Kind>,
+        scripts: Vec<ScriptBuf>,
+        witness: Vec<u8>,
+        map: HashMap<u32, String>,
+        tuple: (u8, u16),
+        optional: Option<Box<Sample>>,
+    }
+
+    fn sample(map: HashMap<u32, String>) -> Sample {
+        let script = ScriptBuf::from_bytes((0..100).collect());
+        Sample {
+            name: String::from("graph"),
+            amount: 131_072,
+            delta: -42,
+            flag: true,
+            kinds: vec![Kind::Unit, Kind::Newtype(7), Kind::Struct { value: -1 }],
+            scripts: vec![script.clone(), script.clone(), script],
+            witness: b"SECRET_1".to_vec(),
+            map,
+            tuple: (1, 300),
+            optional: Some(Box::new(Sample {
+                name: String::new(),
+                amount: 0,
+                delta: 0,
+                flag: false,
+                kinds: vec![],
+                scripts: vec![],
+                witness: vec![],
+                map: HashMap::new(),
+                tuple: (0, 0),
+                optional: None,
+            })),
+        }
+    }
+
+    // Laid out like the graph data: keys, and transactions and their previous outputs consensus
+    // encoded as hex.
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct SampleGraph {
+        network: Network,
+        n_of_n_public_key: PublicKey,
+        n_of_n_taproot_public_key: XOnlyPublicKey,
+        #[serde(with = "consensus::serde::With::<consensus::serde::Hex>")]
+        tx: Transaction,
+        #[serde(with = "consensus::serde::With::<consensus::serde::Hex>")]
+        prev_outs: Vec<TxOut>,
+    }
+
+    fn sample_graph() -> SampleGraph {
+        let n_of_n_public_key = PublicKey::from_str(
+            "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798",
+        )
+        .unwrap();
+        let n_of_n_taproot_public_key = XOnlyPublicKey::from(n_of_n_public_key.inner);
+        let script_pubkey = ScriptBuf::new_p2tr_tweaked(
+            bitcoin::key::TweakedPublicKey::dangerous_assume_tweaked(n_of_n_taproot_public_key),
+        );
+
+        SampleGraph {
+            network: Network::Bitcoin,
+            n_of_n_public_key,
+            n_of_n_taproot_public_key,
+            tx: Transaction {
+                version: Version(2),
+                lock_time: LockTime::ZERO,
+                input: vec![TxIn {
+                    previous_output: OutPoint {
+                        txid: Txid::from_byte_array([0x11; 32]),
+                        vout: 0,
+                    },
+                    script_sig: ScriptBuf::new(),
+                    sequence: Sequence::MAX,
+                    witness: Witness::new(),
+                }],
+                output: vec![TxOut {
+                    value: Amount::from_sat(100_000),
+                    script_pubkey: script_pubkey.clone(),
+                }],
+            },
+            prev_outs: vec![TxOut {
+                value: Amount::from_sat(110_000),
+                script_pubkey,
+            }],
+        }
+    }
+
+    #[test]
+    fn test_binary_graph_round_trip() {
+        let graph = sample_graph();
+        let data = serialize(&graph);
+
+        // Header 6 and object header 2 bytes, then each field name, tag, length prefix and bytes:
+        // public key 53, x-only public key 60, network 17, previous outputs 56 and transaction 100
+        assert_eq!(data.len(), 294);
+        assert_eq!(try_deserialize::<SampleGraph>(&data).unwrap(), graph);
+    }
+
+    #[test]
+    fn test_binary_tags_and_validates_transactions_and_keys() {
+        let graph = sample_graph();
+        let data = serialize(&graph);
+
+        let tx = consensus::serialize(&graph.tx);
+        let tx_start = data
+            .windows(tx.len())
+            .position(|bytes| bytes == tx)
+            .unwrap();
+        // Each is preceded by its tag and its length prefix, two bytes long for the transaction
+        assert_eq!(data[tx_start - 3], TAG_TRANSACTION);
+        let public_key = graph.n_of_n_public_key.to_bytes();
+        let public_key_start = data
+            .windows(public_key.len())
+            .position(|bytes| bytes == public_key)
+            .unwrap();
+        assert_eq!(data[public_key_start - 2], TAG_PUBLIC_KEY);
+        // The x-only key follows the compressed key, which ends with the same bytes
+        let x_only_public_key = graph.n_of_n_taproot_public_key.serialize();
+        let x_only_public_key_start = public_key_start
+            + public_key.len()
+            + data[public_key_start + public_key.len()..]
+                .windows(x_only_public_key.len())
+                .position(|bytes| bytes == x_only_public_key)
+                .unwrap();
+        assert_eq!(data[x_only_public_key_start - 2], TAG_X_ONLY_PUBLIC_KEY);
+
+        // Claims an input count that the transaction does not have
+        let mut invalid_tx_data = data.clone();
+        invalid_tx_data[tx_start + 4] = 2;
+        assert!(try_deserialize_value(&invalid_tx_data)
+            .is_err_and(|err| err.contains("Invalid transaction")));
+
+        // Not a valid key prefix
+        let mut invalid_public_key_data = data.clone();
+        invalid_public_key_data[public_key_start] = 0x05;
+        assert!(try_deserialize_value(&invalid_public_key_data)
+            .is_err_and(|err| err.contains("Invalid public key")));
+    }
+
+    #[test]
+    fn test_binary_round_trip() {
+        let map = HashMap::from([(1, String::from("a")), (2, String::from("b"))]);
+        let value = sample(map);
+
+        let data = serialize(&value);
+        assert_eq!(try_deserialize::<Sample>(&data).unwrap(), value);
+    }
+
+    #[test]
+    fn test_binary_stores_repeated_bytes_once() {
+        let mut value = sample(HashMap::new());
+        let data = serialize(&value);
+
+        // The two other copies of the 100 byte script only add table references
+        value.scripts.truncate(1);
+        assert!(data.len() < serialize(&value).len() + 8);
+    }
+
+    #[test]
+    fn test_binary_stores_hex_strings_as_bytes() {
+        let value = json!({
+            "script": "00".repeat(100),
+            "uppercase": "ABCD",
+            "odd": "abc",
+            "text": "testnet",
+        });
+
+        let data = serialize_value(&value);
+        assert!(data.len() < 100 + 64);
+        assert_eq!(try_deserialize_value(&data).unwrap(), value);
+    }
+
+    #[test]
+    fn test_binary_is_deterministic() {
+        let mut map_0 = HashMap::new();
+        let mut map_1 = HashMap::new();
+        for i in 0..100 {
+            map_0.insert(i, i.to_string());
+            map_1.insert(99 - i, (99 - i).to_string());
+        }
+
+        assert_eq!(serialize(&sample(map_0)), serialize(&sample(map_1)));
+    }
+
+    #[test]
+    fn test_binary_rejects_invalid_data() {
+        let data = serialize(&sample(HashMap::new()));
+
+        assert!(try_deserialize::<Sample>(&data[1..]).is_err());
+        assert!(try_deserialize::<Sample>(&data[..data.len() - 1]).is_err());
+
+        let mut trailing_data = data.clone();
+        trailing_data.push(0);
+        assert!(try_deserialize::<Sample>(&trailing_data).is_err());
+
+        let mut future_data = data.clone();
+        future_data[4] = 2;
+        assert!(try_deserialize::<Sample>(&future_data)
+            .is_err_and(|err| err.contains("Unsupported binary format version 2")));
+    }
+
+    #[test]
+    fn test_binary_defaults_appended_fields() {
+        #[derive(Serialize, Deserialize, PartialEq, Debug)]
+        struct Original {
+            name: String,
+        }
+        #[derive(Serialize, Deserialize, PartialEq, Debug)]
+        struct Extended {
+            name: String,
+            #[serde(default)]
+            appended: Vec<u32>,
+        }
+        #[derive(Serialize, Deserialize, PartialEq, Debug)]
+        struct Required {
+            name: String,
+            appended: Vec<u32>,
+        }
+
+        let data = serialize(&Original {
+            name: String::from("graph"),
+        });
+        assert_eq!(
+            try_deserialize::<Extended>(&data).unwrap(),
+            Extended {
+                name: String::from("graph"),
+                appended: vec![],
+            }
+        );
+        assert!(try_deserialize::<Required>(&data).is_err());
+    }
+}
@@ -0,0 +1,56 @@
+pub mod binary;
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub enum SerializationFormat {
+    Json,
+    Binary,
+}
+
+impl SerializationFormat {
+    pub fn file_extension(&self) -> &str {
+        match self {
+            SerializationFormat::Json => "json",
+            SerializationFormat::Binary => "bin",
+        }
+    }
+
+    pub fn from_file_extension(extension: &str) -> Option<Self> {
+        match extension {
+            "json" => Some(SerializationFormat::Json),
+            "bin" => Some(SerializationFormat::Binary),
+            _ => None,
+        }
+    }
+}
+
+pub fn serialize(object: &impl Serialize) -> String { serde_json::to_string(object).unwrap() }
+
+pub fn deserialize<'a, T>(data: &'a str) -> T
+where
+    T: Deserialize<'a>,
+{
+    serde_json::from_str::<T>(data).unwrap()
+}
+
+pub fn try_deserialize<'a, T>(data: &'a str) -> Result<T, String>
+where
+    T: Deserialize<'a>,
+{
+    match serde_json::from_str::<T>(data) {
+        Ok(x) => Ok(x),
+        Err(err) => Err(format!("Failed to parse json: {}", err.to_string())),
+    }
+}
+
+pub fn try_deserialize_value<T>(value: Value) -> Result<T, String>
+where
+    T: DeserializeOwned,
+{
+    match serde_json::from_value::<T>(value) {
+        Ok(x) => Ok(x),
+        Err(err) => Err(format!("Failed to parse json: {}", err.to_string())),
+    }
+}
@@ -0,0 +1,322 @@
+use bitcoin::{PublicKey, Txid};
+use musig2::SecNonce;
+use std::{
+    collections::HashMap,
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+};
+use tracing::info_span;
+
+use super::{
+    super::{
+        contexts::verifier::VerifierContext,
+        transactions::pre_signed_musig2::PreSignedMusig2Transaction,
+    },
+    session::Musig2Graph,
+};
+
+/// Secret nonces of one verifier by graph id.
+pub type GraphSecretNonces = HashMap<String, HashMap<Txid, HashMap<usize, SecNonce>>>;
+
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub struct BatchProgress {
+    /// Graph that was just processed.
+    pub graph_id: String,
+    pub completed: usize,
+    pub total: usize,
+}
+
+impl BatchProgress {
+    /// Progress within a larger batch of `total` graphs, in which this batch starts after
+    /// `offset` graphs.
+    pub fn of(&self, offset: usize, total: usize) -> Self {
+        BatchProgress {
+            graph_id: self.graph_id.clone(),
+            completed: offset + self.completed,
+            total,
+        }
+    }
+}
+
+/// Whether `verifier` still has to push nonces for some MuSig2 input of `graph`.
+pub fn needs_nonces<G: Musig2Graph>(graph: &G, verifier: &PublicKey) -> bool {
+    graph.musig2_transactions().iter().any(|tx| {
+        tx.musig2_nonces().is_empty()
+            || tx
+                .musig2_nonces()
+                .values()
+                .any(|nonces| !nonces.contains_key(verifier))
+    })
+}
+
+/// Whether `verifier` has not pre-signed all MuSig2 inputs of `graph` that have nonces.
+pub fn needs_signatures<G: Musig2Graph>(graph: &G, verifier: &PublicKey) -> bool {
+    graph.musig2_transactions().iter().any(|tx| {
+        tx.musig2_nonces().keys().any(|input_index| {
+            !tx.musig2_signatures()
+                .get(input_index)
+                .is_some_and(|signatures| signatures.contains_key(verifier))
+        })
+    })
+}
+
+/// Whether all n-of-n signers pushed their nonces for `graph` and `verifier` has not pre-signed
+/// all of its MuSig2 inputs yet.
+pub fn is_ready_to_pre_sign<G: Musig2Graph>(graph: &G, context: &VerifierContext) -> bool {
+    let has_all_nonces = context
+        .n_of_n_public_keys
+        .iter()
+        .all(|public_key| !needs_nonces(graph, public_key));
+
+    has_all_nonces && needs_signatures(graph, &context.verifier_public_key)
+}
+
+/// Contributions the n-of-n signers still owe to a set of graphs, counted once per graph and
+/// signer.
+#[derive(Eq, PartialEq, Clone, Copy, Debug, Default)]
+pub struct PendingSignatures {
+    /// Signers that did not push their nonces yet.
+    pub nonces: usize,
+    /// Signers that pushed their nonces but did not pre-sign yet.
+    pub partial_signatures: usize,
+}
+
+pub fn count_pending_signatures<G: Musig2Graph>(
+    graphs: &[G],
+    n_of_n_public_keys: &[PublicKey],
+) -> PendingSignatures {
+    let mut pending = PendingSignatures::default();
+    for graph in graphs {
+        for public_key in n_of_n_public_keys {
+            if needs_nonces(graph, public_key) {
+                pending.nonces += 1;
+            } else if needs_signatures(graph, public_key) {
+                pending.partial_signatures += 1;
+            }
+        }
+    }
+
+    pending
+}
+
+fn worker_count(jobs: usize) -> usize {
+    let cores = thread::available_parallelism().map_or(1, |cores| cores.get());
+    cores.min(jobs).max(1)
+}
+
+// Runs `job` on every graph, spread over one thread per core.
+fn for_each_parallel<G, R, F>(
+    graphs: &mut [&mut G],
+    job: F,
+    progress: &(dyn Fn(&BatchProgress) + Sync),
+) -> Vec<(String, R)>
+where
+    G: Musig2Graph + Send,
+    R: Send,
+    F: Fn(&mut G) -> R + Sync,
+{
+    let total = graphs.len();
+    if total == 0 {
+        return vec![];
+    }
+    let chunk_size = total.div_ceil(worker_count(total));
+    let completed = AtomicUsize::new(0);
+
+    thread::scope(|scope| {
+        let workers = graphs
+            .chunks_mut(chunk_size)
+            .map(|chunk| {
+                let (job, completed) = (&job, &completed);
+                scope.spawn(move || {
+                    chunk
+                        .iter_mut()
+                        .map(|graph| {
+                            let graph_id = graph.graph_id();
+                            let result = info_span!("graph", graph_id = %graph_id)
+                                .in_scope(|| job(&mut **graph));
+                            progress(&BatchProgress {
+                                graph_id: graph_id.clone(),
+                                completed: completed.fetch_add(1, Ordering::SeqCst) + 1,
+                                total,
+                            });
+                            (graph_id, result)
+                        })
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect::<Vec<_>>();
+
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().expect("Batch worker panicked"))
+            .collect()
+    })
+}
+
+/// Pushes the verifier's nonces for every MuSig2 input of all `graphs` and returns the secret
+/// nonces by graph id. Graphs are processed in parallel, `progress` is called after each graph.
+pub fn push_nonces_batch<G: Musig2Graph + Send>(
+    graphs: &mut [&mut G],
+    context: &VerifierContext,
+    progress: &(dyn Fn(&BatchProgress) + Sync),
+) -> GraphSecretNonces {
+    for_each_parallel(graphs, |graph| graph.push_nonces(context), progress)
+        .into_iter()
+        .collect()
+}
+
+/// Pre-signs every MuSig2 input of all `graphs` with the secret nonces pushed earlier. Graphs
+/// without secret nonces are skipped, the ids of the pre-signed graphs are returned.
+pub fn pre_sign_batch<G: Musig2Graph + Send>(
+    graphs: &mut [&mut G],
+    context: &VerifierContext,
+    secret_nonces: &GraphSecretNonces,
+    progress: &(dyn Fn(&BatchProgress) + Sync),
+) -> Vec<String> {
+    for_each_parallel(
+        graphs,
+        |graph| match secret_nonces.get(&graph.graph_id()) {
+            Some(graph_secret_nonces) => {
+                graph.pre_sign(context, graph_secret_nonces);
+                true
+            }
+            None => false,
+        },
+        progress,
+    )
+    .into_iter()
+    .filter_map(|(graph_id, signed)| signed.then_some(graph_id))
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::{hashes::Hash, Amount, Network, OutPoint, PublicKey, Txid};
+    use std::sync::Mutex;
+
+    use super::{
+        super::super::{
+            contexts::{
+                base::generate_keys_from_secret, depositor::DepositorContext,
+                verifier::VerifierContext,
+            },
+            graphs::{
+                base::{
+                    DEPOSITOR_EVM_ADDRESS, DEPOSITOR_SECRET, INITIAL_AMOUNT, VERIFIER_0_SECRET,
+                    VERIFIER_1_SECRET,
+                },
+                peg_in::PegInGraph,
+            },
+            timelocks::TimelockConfig,
+            transactions::base::Input,
+        },
+        count_pending_signatures, is_ready_to_pre_sign, needs_nonces, pre_sign_batch,
+        push_nonces_batch, BatchProgress, Musig2Graph, PendingSignatures,
+    };
+
+    fn setup(graph_count: u32) -> (Vec<PegInGraph>, Vec<VerifierContext>) {
+        let network = Network::Regtest;
+        let n_of_n_public_keys: Vec<PublicKey> = [VERIFIER_0_SECRET, VERIFIER_1_SECRET]
+            .iter()
+            .map(|secret| generate_keys_from_secret(network, secret).2)
+            .collect();
+        let depositor_context =
+            DepositorContext::new(network, DEPOSITOR_SECRET, &n_of_n_public_keys);
+        let graphs = (0..graph_count)
+            .map(|vout| {
+                PegInGraph::new(
+                    &depositor_context,
+                    Input {
+                        outpoint: OutPoint {
+                            txid: Txid::all_zeros(),
+                            vout,
+                        },
+                        amount: Amount::from_sat(INITIAL_AMOUNT),
+                    },
+                    DEPOSITOR_EVM_ADDRESS,
+                    &TimelockConfig::regtest(),
+                )
+            })
+            .collect();
+        let contexts = [VERIFIER_0_SECRET, VERIFIER_1_SECRET]
+            .iter()
+            .map(|secret| VerifierContext::new(network, secret, &n_of_n_public_keys))
+            .collect();
+
+        (graphs, contexts)
+    }
+
+    #[test]
+    fn test_batch_pre_sign_graphs() {
+        let (mut graphs, contexts) = setup(5);
+        let progress = Mutex::new(vec![]);
+        let report = |p: &BatchProgress| progress.lock().unwrap().push(p.completed);
+
+        let n_of_n_public_keys = &contexts[0].n_of_n_public_keys;
+        assert_eq!(
+            count_pending_signatures(&graphs, n_of_n_public_keys),
+            PendingSignatures {
+                nonces: 10,
+                partial_signatures: 0
+            }
+        );
+
+        let mut secret_nonces = vec![];
+        for context in &contexts {
+            assert!(graphs
+                .iter()
+                .all(|graph| needs_nonces(graph, &context.verifier_public_key)));
+            let mut pending = graphs.iter_mut().collect::<Vec<_>>();
+            secret_nonces.push(push_nonces_batch(&mut pending, context, &report));
+            assert!(graphs
+                .iter()
+                .all(|graph| !needs_nonces(graph, &context.verifier_public_key)));
+        }
+        assert_eq!(secret_nonces[0].len(), 5);
+        let mut completed = progress.lock().unwrap().clone();
+        completed.sort();
+        assert_eq!(completed, [1, 1, 2, 2, 3, 3, 4, 4, 5, 5]);
+
+        assert_eq!(
+            count_pending_signatures(&graphs, n_of_n_public_keys),
+            PendingSignatures {
+                nonces: 0,
+                partial_signatures: 10
+            }
+        );
+
+        for (context, secret_nonces) in contexts.iter().zip(&secret_nonces) {
+            assert!(graphs
+                .iter()
+                .all(|graph| is_ready_to_pre_sign(graph, context)));
+            let mut pending = graphs.iter_mut().collect::<Vec<_>>();
+            let signed = pre_sign_batch(&mut pending, context, secret_nonces, &|_| {});
+            assert_eq!(signed.len(), 5);
+            assert!(!graphs
+                .iter()
+                .any(|graph| is_ready_to_pre_sign(graph, context)));
+        }
+
+        assert_eq!(
+            count_pending_signatures(&graphs, n_of_n_public_keys),
+            PendingSignatures::default()
+        );
+        for graph in &graphs {
+            let report = Musig2Graph::validate_scripts(graph);
+            assert!(report.is_valid(), "{}", report);
+            assert_eq!(report.checked_inputs, 1);
+        }
+    }
+
+    #[test]
+    fn test_batch_skips_graphs_without_secret_nonces() {
+        let (mut graphs, contexts) = setup(2);
+        let mut pending = graphs.iter_mut().collect::<Vec<_>>();
+        let mut secret_nonces = push_nonces_batch(&mut pending[..1], &contexts[0], &|_| {});
+        push_nonces_batch(&mut pending[1..], &contexts[1], &|_| {});
+        secret_nonces.clear();
+
+        assert!(pre_sign_batch(&mut pending, &contexts[0], &secret_nonces, &|_| {}).is_empty());
+        assert!(push_nonces_batch::<PegInGraph>(&mut [], &contexts[0], &|_| {}).is_empty());
+    }
+}
@@ -0,0 +1,120 @@
+use bitcoin::{hashes::sha256, key::Secp256k1, PublicKey, Txid, XOnlyPublicKey};
+use musig2::{
+    secp256k1::{schnorr::Signature, Message},
+    PartialSignature, PubNonce,
+};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Debug)]
+pub struct InputNonce {
+    pub txid: Txid,
+    pub input_index: usize,
+    pub public_nonce: PubNonce,
+    /// Signature of the public nonce by the signer's key, as stored in the graph.
+    pub nonce_signature: Signature,
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Debug)]
+pub struct InputPartialSignature {
+    pub txid: Txid,
+    pub input_index: usize,
+    pub partial_signature: PartialSignature,
+}
+
+/// Reason a session was aborted. `signer` is the participant at fault, if one could be
+/// identified, e.g. by an invalid nonce signature or partial signature.
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Debug)]
+pub struct Blame {
+    pub signer: Option<PublicKey>,
+    pub reason: String,
+}
+
+impl Blame {
+    pub fn signer(signer: &PublicKey, reason: impl Into<String>) -> Self {
+        Blame {
+            signer: Some(*signer),
+            reason: reason.into(),
+        }
+    }
+
+    pub fn unattributed(reason: impl Into<String>) -> Self {
+        Blame {
+            signer: None,
+            reason: reason.into(),
+        }
+    }
+}
+
+impl fmt::Display for Blame {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.signer {
+            Some(signer) => write!(f, "{} (signer {})", self.reason, signer),
+            None => write!(f, "{}", self.reason),
+        }
+    }
+}
+
+/// Messages broadcast between the n-of-n signers of a graph. Round 1 exchanges the nonces of
+/// every MuSig2 input of the graph, round 2 the partial signatures over them.
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SessionMessage {
+    Nonces {
+        session_id: String,
+        signer: PublicKey,
+        nonces: Vec<InputNonce>,
+    },
+    PartialSignatures {
+        session_id: String,
+        signer: PublicKey,
+        partial_signatures: Vec<InputPartialSignature>,
+    },
+    /// Signed by the sender, see `get_abort_message`, so that only signers of the session can
+    /// abort it.
+    Abort {
+        session_id: String,
+        sender: PublicKey,
+        blame: Blame,
+        signature: Signature,
+    },
+}
+
+impl SessionMessage {
+    pub fn session_id(&self) -> &str {
+        match self {
+            SessionMessage::Nonces { session_id, .. }
+            | SessionMessage::PartialSignatures { session_id, .. }
+            | SessionMessage::Abort { session_id, .. } => session_id,
+        }
+    }
+
+    pub fn sender(&self) -> &PublicKey {
+        match self {
+            SessionMessage::Nonces { signer, .. }
+            | SessionMessage::PartialSignatures { signer, .. } => signer,
+            SessionMessage::Abort { sender, .. } => sender,
+        }
+    }
+}
+
+/// Message the sender of an `Abort` signs, committing to the session and the blame.
+pub fn get_abort_message(session_id: &str, blame: &Blame) -> Message {
+    let data = serde_json::to_vec(&(session_id, blame)).unwrap();
+    Message::from_hashed_data::<sha256::Hash>(&data)
+}
+
+pub fn verify_abort_signature(
+    signature: &Signature,
+    session_id: &str,
+    blame: &Blame,
+    sender: &PublicKey,
+) -> bool {
+    Secp256k1::verification_only()
+        .verify_schnorr(
+            signature,
+            &get_abort_message(session_id, blame),
+            &XOnlyPublicKey::from(*sender),
+        )
+        .is_ok()
+}
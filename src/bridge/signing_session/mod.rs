@@ -0,0 +1,4 @@
+pub mod batch;
+pub mod messages;
+pub mod session;
+pub mod transport;
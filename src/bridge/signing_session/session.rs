@@ -0,0 +1,675 @@
+use bitcoin::{
+    hex::{Case::Lower, DisplayHex},
+    PublicKey, TapSighashType, Txid, XOnlyPublicKey,
+};
+use musig2::SecNonce;
+use rand::RngCore;
+use std::collections::{HashMap, HashSet};
+
+use super::{
+    super::{
+        contexts::verifier::VerifierContext,
+        graphs::{base::BaseGraph, peg_in::PegInGraph, peg_out::PegOutGraph},
+        transactions::{
+            pre_signed::PreSignedTransaction,
+            pre_signed_musig2::{
+                verify_public_nonce, Musig2Transaction, PreSignedMusig2Transaction,
+            },
+            psbt::PsbtTransaction,
+            script_validation::ScriptValidationReport,
+            signing_musig2::{generate_aggregated_nonce, verify_taproot_partial_signature},
+        },
+    },
+    messages::{
+        get_abort_message, verify_abort_signature, Blame, InputNonce, InputPartialSignature,
+        SessionMessage,
+    },
+};
+
+/// Graph whose n-of-n inputs can be presigned in a signing session.
+pub trait Musig2Graph: Clone {
+    fn graph_id(&self) -> String;
+    fn push_nonces(&mut self, context: &VerifierContext)
+        -> HashMap<Txid, HashMap<usize, SecNonce>>;
+    fn pre_sign(
+        &mut self,
+        context: &VerifierContext,
+        secret_nonces: &HashMap<Txid, HashMap<usize, SecNonce>>,
+    );
+    fn musig2_transactions(&self) -> Vec<&dyn Musig2Transaction>;
+    fn musig2_transactions_mut(&mut self) -> Vec<&mut dyn Musig2Transaction>;
+    fn validate_scripts(&self) -> ScriptValidationReport;
+}
+
+impl Musig2Graph for PegInGraph {
+    fn graph_id(&self) -> String { self.id().clone() }
+
+    fn push_nonces(
+        &mut self,
+        context: &VerifierContext,
+    ) -> HashMap<Txid, HashMap<usize, SecNonce>> {
+        PegInGraph::push_nonces(self, context)
+    }
+
+    fn pre_sign(
+        &mut self,
+        context: &VerifierContext,
+        secret_nonces: &HashMap<Txid, HashMap<usize, SecNonce>>,
+    ) {
+        PegInGraph::pre_sign(self, context, secret_nonces)
+    }
+
+    fn musig2_transactions(&self) -> Vec<&dyn Musig2Transaction> {
+        PegInGraph::musig2_transactions(self)
+    }
+
+    fn musig2_transactions_mut(&mut self) -> Vec<&mut dyn Musig2Transaction> {
+        PegInGraph::musig2_transactions_mut(self)
+    }
+
+    fn validate_scripts(&self) -> ScriptValidationReport { PegInGraph::validate_scripts(self) }
+}
+
+impl Musig2Graph for PegOutGraph {
+    fn graph_id(&self) -> String { self.id().clone() }
+
+    fn push_nonces(
+        &mut self,
+        context: &VerifierContext,
+    ) -> HashMap<Txid, HashMap<usize, SecNonce>> {
+        PegOutGraph::push_nonces(self, context)
+    }
+
+    fn pre_sign(
+        &mut self,
+        context: &VerifierContext,
+        secret_nonces: &HashMap<Txid, HashMap<usize, SecNonce>>,
+    ) {
+        PegOutGraph::pre_sign(self, context, secret_nonces)
+    }
+
+    fn musig2_transactions(&self) -> Vec<&dyn Musig2Transaction> {
+        PegOutGraph::musig2_transactions(self)
+    }
+
+    fn musig2_transactions_mut(&mut self) -> Vec<&mut dyn Musig2Transaction> {
+        PegOutGraph::musig2_transactions_mut(self)
+    }
+
+    fn validate_scripts(&self) -> ScriptValidationReport { PegOutGraph::validate_scripts(self) }
+}
+
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub enum SessionState {
+    /// Round 1, waiting for the nonces of the other signers.
+    CollectingNonces,
+    /// Round 2, waiting for the partial signatures of the other signers.
+    CollectingPartialSignatures,
+    /// Every MuSig2 input of the graph is signed.
+    Complete,
+    Aborted(Blame),
+}
+
+pub fn generate_session_id() -> String {
+    let mut bytes = [0u8; 16];
+    rand::rngs::OsRng.fill_bytes(&mut bytes);
+    bytes.to_hex_string(Lower)
+}
+
+/// One signer's side of a session presigning every MuSig2 input of a graph with the other
+/// n-of-n signers.
+///
+/// The session works on its own copy of the graph, starting from fresh nonces. Messages returned
+/// by `start` and `handle_message` must be broadcast to all other signers. Partial signatures
+/// arriving before all nonces are buffered. Any invalid message aborts the session, blaming its
+/// sender.
+pub struct SigningSession<G: Musig2Graph> {
+    session_id: String,
+    signer: PublicKey,
+    signers: Vec<PublicKey>,
+    graph: G,
+    secret_nonces: HashMap<Txid, HashMap<usize, SecNonce>>,
+    nonces_received: HashSet<PublicKey>,
+    partial_signatures_received: HashSet<PublicKey>,
+    pending_partial_signatures: Vec<(PublicKey, Vec<InputPartialSignature>)>,
+    state: SessionState,
+}
+
+impl<G: Musig2Graph> SigningSession<G> {
+    /// Creates the session and the round 1 message with this signer's nonces.
+    pub fn start(session_id: &str, graph: &G, context: &VerifierContext) -> (Self, SessionMessage) {
+        let mut graph = graph.clone();
+        for tx in graph.musig2_transactions_mut() {
+            tx.musig2_nonces_mut().clear();
+            tx.musig2_nonce_signatures_mut().clear();
+            tx.musig2_signatures_mut().clear();
+        }
+        let secret_nonces = graph.push_nonces(context);
+
+        let signer = context.verifier_public_key;
+        let mut nonces = vec![];
+        for tx in graph.musig2_transactions() {
+            let txid = tx.tx().compute_txid();
+            for (input_index, input_nonces) in tx.musig2_nonces() {
+                nonces.push(InputNonce {
+                    txid,
+                    input_index: *input_index,
+                    public_nonce: input_nonces[&signer].clone(),
+                    nonce_signature: tx.musig2_nonce_signatures()[input_index][&signer],
+                });
+            }
+        }
+
+        let session = SigningSession {
+            session_id: session_id.to_string(),
+            signer,
+            signers: context.n_of_n_public_keys.clone(),
+            graph,
+            secret_nonces,
+            nonces_received: HashSet::from([signer]),
+            partial_signatures_received: HashSet::new(),
+            pending_partial_signatures: vec![],
+            state: SessionState::CollectingNonces,
+        };
+        let message = SessionMessage::Nonces {
+            session_id: session_id.to_string(),
+            signer,
+            nonces,
+        };
+
+        (session, message)
+    }
+
+    pub fn session_id(&self) -> &str { &self.session_id }
+
+    pub fn state(&self) -> &SessionState { &self.state }
+
+    pub fn is_finished(&self) -> bool {
+        matches!(
+            self.state,
+            SessionState::Complete | SessionState::Aborted(_)
+        )
+    }
+
+    /// The session's copy of the graph, holding every n-of-n signature once complete.
+    pub fn graph(&self) -> &G { &self.graph }
+
+    pub fn into_graph(self) -> G { self.graph }
+
+    /// Processes a message from another signer and returns the messages to broadcast in reply.
+    pub fn handle_message(
+        &mut self,
+        context: &VerifierContext,
+        message: &SessionMessage,
+    ) -> Vec<SessionMessage> {
+        if self.is_finished() || message.session_id() != self.session_id {
+            return vec![];
+        }
+
+        let result = match message {
+            SessionMessage::Nonces { signer, nonces, .. } => {
+                self.receive_nonces(context, signer, nonces)
+            }
+            SessionMessage::PartialSignatures {
+                signer,
+                partial_signatures,
+                ..
+            } => {
+                if self.state == SessionState::CollectingNonces {
+                    self.pending_partial_signatures
+                        .push((*signer, partial_signatures.clone()));
+                    Ok(vec![])
+                } else {
+                    self.receive_partial_signatures(context, signer, partial_signatures)
+                }
+            }
+            SessionMessage::Abort {
+                sender,
+                blame,
+                signature,
+                ..
+            } => {
+                // Aborts that are not signed by a signer of the session are ignored, otherwise
+                // anyone could abort it.
+                if self.signers.contains(sender)
+                    && verify_abort_signature(signature, &self.session_id, blame, sender)
+                {
+                    self.state = SessionState::Aborted(blame.clone());
+                }
+                return vec![];
+            }
+        };
+
+        match result {
+            Ok(replies) => replies,
+            Err(blame) => {
+                self.state = SessionState::Aborted(blame.clone());
+                vec![self.abort_message(context, blame)]
+            }
+        }
+    }
+
+    fn abort_message(&self, context: &VerifierContext, blame: Blame) -> SessionMessage {
+        let signature = context.secp.sign_schnorr(
+            &get_abort_message(&self.session_id, &blame),
+            &context.verifier_keypair,
+        );
+        SessionMessage::Abort {
+            session_id: self.session_id.clone(),
+            sender: self.signer,
+            blame,
+            signature,
+        }
+    }
+
+    fn check_sender(&self, signer: &PublicKey, received: &HashSet<PublicKey>) -> Result<(), Blame> {
+        if !self.signers.contains(signer) {
+            return Err(Blame::signer(signer, "Sender is not an n-of-n signer"));
+        }
+        if received.contains(signer) {
+            return Err(Blame::signer(signer, "Sender already sent this round"));
+        }
+        Ok(())
+    }
+
+    // Every signer must provide exactly one value for each MuSig2 input of the graph.
+    fn check_inputs(
+        &self,
+        signer: &PublicKey,
+        inputs: impl Iterator<Item = (Txid, usize)>,
+    ) -> Result<(), Blame> {
+        let mut expected = self
+            .secret_nonces
+            .iter()
+            .flat_map(|(txid, nonces)| nonces.keys().map(move |input_index| (*txid, *input_index)))
+            .collect::<HashSet<_>>();
+        for input in inputs {
+            if !expected.remove(&input) {
+                return Err(Blame::signer(
+                    signer,
+                    format!("Unexpected or duplicate input {}:{}", input.0, input.1),
+                ));
+            }
+        }
+        if !expected.is_empty() {
+            return Err(Blame::signer(signer, "Missing inputs"));
+        }
+        Ok(())
+    }
+
+    fn receive_nonces(
+        &mut self,
+        context: &VerifierContext,
+        signer: &PublicKey,
+        nonces: &[InputNonce],
+    ) -> Result<Vec<SessionMessage>, Blame> {
+        if self.state != SessionState::CollectingNonces {
+            return Err(Blame::signer(signer, "Nonces received after round 1"));
+        }
+        self.check_sender(signer, &self.nonces_received)?;
+        self.check_inputs(signer, nonces.iter().map(|n| (n.txid, n.input_index)))?;
+        for nonce in nonces {
+            if !verify_public_nonce(
+                &nonce.nonce_signature,
+                &nonce.public_nonce,
+                &XOnlyPublicKey::from(*signer),
+            ) {
+                return Err(Blame::signer(
+                    signer,
+                    format!(
+                        "Invalid nonce signature for input {}:{}",
+                        nonce.txid, nonce.input_index
+                    ),
+                ));
+            }
+        }
+
+        for tx in self.graph.musig2_transactions_mut() {
+            let txid = tx.tx().compute_txid();
+            for nonce in nonces.iter().filter(|nonce| nonce.txid == txid) {
+                tx.musig2_nonces_mut()
+                    .entry(nonce.input_index)
+                    .or_default()
+                    .insert(*signer, nonce.public_nonce.clone());
+                tx.musig2_nonce_signatures_mut()
+                    .entry(nonce.input_index)
+                    .or_default()
+                    .insert(*signer, nonce.nonce_signature);
+            }
+        }
+        self.nonces_received.insert(*signer);
+        if self.nonces_received.len() < self.signers.len() {
+            return Ok(vec![]);
+        }
+
+        // Round 2: sign a copy, the own partial signatures are only added to the session's graph
+        // together with the others' so that the inputs get finalized.
+        let mut signed_graph = self.graph.clone();
+        signed_graph.pre_sign(context, &self.secret_nonces);
+        let mut partial_signatures = vec![];
+        for tx in signed_graph.musig2_transactions() {
+            let txid = tx.tx().compute_txid();
+            for (input_index, signatures) in tx.musig2_signatures() {
+                partial_signatures.push(InputPartialSignature {
+                    txid,
+                    input_index: *input_index,
+                    partial_signature: signatures[&self.signer],
+                });
+            }
+        }
+        self.partial_signatures_received.insert(self.signer);
+        self.state = SessionState::CollectingPartialSignatures;
+
+        let mut replies = vec![SessionMessage::PartialSignatures {
+            session_id: self.session_id.clone(),
+            signer: self.signer,
+            partial_signatures,
+        }];
+        for (signer, partial_signatures) in std::mem::take(&mut self.pending_partial_signatures) {
+            replies.extend(self.receive_partial_signatures(
+                context,
+                &signer,
+                &partial_signatures,
+            )?);
+        }
+
+        Ok(replies)
+    }
+
+    fn receive_partial_signatures(
+        &mut self,
+        context: &VerifierContext,
+        signer: &PublicKey,
+        partial_signatures: &[InputPartialSignature],
+    ) -> Result<Vec<SessionMessage>, Blame> {
+        self.check_sender(signer, &self.partial_signatures_received)?;
+        self.check_inputs(
+            signer,
+            partial_signatures.iter().map(|s| (s.txid, s.input_index)),
+        )?;
+
+        for tx in self.graph.musig2_transactions() {
+            let txid = tx.tx().compute_txid();
+            for signature in partial_signatures.iter().filter(|s| s.txid == txid) {
+                let input_index = signature.input_index;
+                let sighash_type = tx
+                    .sighash_type(input_index)
+                    .and_then(|sighash_type| sighash_type.taproot_hash_ty().ok())
+                    .unwrap_or(TapSighashType::Default);
+                let nonces = &tx.musig2_nonces()[&input_index];
+                let aggregated_nonce =
+                    generate_aggregated_nonce(&nonces.values().cloned().collect());
+
+                if verify_taproot_partial_signature(
+                    context,
+                    tx.tx(),
+                    signature.partial_signature,
+                    &aggregated_nonce,
+                    signer,
+                    &nonces[signer],
+                    input_index,
+                    tx.prev_outs(),
+                    &tx.prev_scripts()[input_index],
+                    sighash_type,
+                )
+                .is_err()
+                {
+                    return Err(Blame::signer(
+                        signer,
+                        format!(
+                            "Invalid partial signature for input {}:{}",
+                            txid, input_index
+                        ),
+                    ));
+                }
+            }
+        }
+
+        for tx in self.graph.musig2_transactions_mut() {
+            let txid = tx.tx().compute_txid();
+            for signature in partial_signatures.iter().filter(|s| s.txid == txid) {
+                tx.musig2_signatures_mut()
+                    .entry(signature.input_index)
+                    .or_default()
+                    .insert(*signer, signature.partial_signature);
+            }
+        }
+        self.partial_signatures_received.insert(*signer);
+        if self.partial_signatures_received.len() < self.signers.len() {
+            return Ok(vec![]);
+        }
+
+        // Adding the own partial signatures last finalizes every input.
+        self.graph.pre_sign(context, &self.secret_nonces);
+        let report = self.graph.validate_scripts();
+        if !report.is_valid() {
+            return Err(Blame::unattributed(format!(
+                "Aggregated signatures are invalid\n{}",
+                report
+            )));
+        }
+        self.state = SessionState::Complete;
+
+        Ok(vec![])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::{hashes::Hash, Amount, Network, OutPoint, PublicKey, Txid};
+
+    use super::{
+        super::{
+            super::{
+                contexts::{
+                    base::generate_keys_from_secret, depositor::DepositorContext,
+                    operator::OperatorContext, verifier::VerifierContext,
+                },
+                graphs::{
+                    base::{
+                        DEPOSITOR_EVM_ADDRESS, DEPOSITOR_SECRET, INITIAL_AMOUNT, OPERATOR_SECRET,
+                        VERIFIER_0_SECRET, VERIFIER_1_SECRET,
+                    },
+                    peg_in::PegInGraph,
+                    peg_out::PegOutGraph,
+                },
+                timelocks::TimelockConfig,
+                transactions::{
+                    base::Input, pre_signed::PreSignedTransaction,
+                    pre_signed_musig2::PreSignedMusig2Transaction,
+                },
+            },
+            messages::{get_abort_message, Blame, SessionMessage},
+            transport::run_local_session,
+        },
+        Musig2Graph, SessionState, SigningSession,
+    };
+
+    fn setup() -> (PegInGraph, Vec<VerifierContext>) {
+        let network = Network::Regtest;
+        let n_of_n_public_keys: Vec<PublicKey> = [VERIFIER_0_SECRET, VERIFIER_1_SECRET]
+            .iter()
+            .map(|secret| generate_keys_from_secret(network, secret).2)
+            .collect();
+        let depositor_context =
+            DepositorContext::new(network, DEPOSITOR_SECRET, &n_of_n_public_keys);
+        let graph = PegInGraph::new(
+            &depositor_context,
+            Input {
+                outpoint: OutPoint {
+                    txid: Txid::all_zeros(),
+                    vout: 0,
+                },
+                amount: Amount::from_sat(INITIAL_AMOUNT),
+            },
+            DEPOSITOR_EVM_ADDRESS,
+            &TimelockConfig::regtest(),
+        );
+        let contexts = [VERIFIER_0_SECRET, VERIFIER_1_SECRET]
+            .iter()
+            .map(|secret| VerifierContext::new(network, secret, &n_of_n_public_keys))
+            .collect();
+
+        (graph, contexts)
+    }
+
+    #[test]
+    fn test_local_session_presigns_graph() {
+        let (graph, contexts) = setup();
+
+        let signed_graph = run_local_session(&graph, &contexts).unwrap();
+
+        let peg_in_confirm = signed_graph.peg_in_confirm_transaction_ref();
+        assert!(!peg_in_confirm.tx().input[0].witness.is_empty());
+        let report = signed_graph.validate_scripts();
+        assert!(report.is_valid(), "{}", report);
+        assert_eq!(report.checked_inputs, 1);
+    }
+
+    #[test]
+    fn test_local_session_presigns_peg_out_graph() {
+        let (peg_in_graph, contexts) = setup();
+        let operator_context = OperatorContext::new(
+            Network::Regtest,
+            OPERATOR_SECRET,
+            &contexts[0].n_of_n_public_keys,
+        );
+        let graph = PegOutGraph::new(
+            &operator_context,
+            &peg_in_graph,
+            Input {
+                outpoint: OutPoint {
+                    txid: Txid::all_zeros(),
+                    vout: 1,
+                },
+                amount: Amount::from_sat(INITIAL_AMOUNT),
+            },
+        );
+        let musig2_transactions = Musig2Graph::musig2_transactions(&graph).len();
+
+        let signed_graph = run_local_session(&graph, &contexts).unwrap();
+
+        let report = Musig2Graph::validate_scripts(&signed_graph);
+        assert!(report.is_valid(), "{}", report);
+        assert!(report.checked_inputs >= musig2_transactions);
+        for tx in Musig2Graph::musig2_transactions(&signed_graph) {
+            for input_index in tx.musig2_signatures().keys() {
+                assert!(!tx.tx().input[*input_index].witness.is_empty());
+            }
+        }
+    }
+
+    #[test]
+    fn test_abort_blames_invalid_nonce_signature() {
+        let (graph, contexts) = setup();
+        let (mut session_0, message_0) = SigningSession::start("session", &graph, &contexts[0]);
+        let (_, mut message_1) = SigningSession::start("session", &graph, &contexts[1]);
+
+        if let SessionMessage::Nonces { nonces, .. } = &mut message_1 {
+            let SessionMessage::Nonces {
+                nonces: other_nonces,
+                ..
+            } = &message_0
+            else {
+                unreachable!()
+            };
+            nonces[0].nonce_signature = other_nonces[0].nonce_signature;
+        }
+
+        let replies = session_0.handle_message(&contexts[0], &message_1);
+        assert!(matches!(replies[..], [SessionMessage::Abort { .. }]));
+        let SessionState::Aborted(blame) = session_0.state() else {
+            panic!("Session not aborted")
+        };
+        assert_eq!(blame.signer, Some(contexts[1].verifier_public_key));
+    }
+
+    #[test]
+    fn test_abort_blames_invalid_partial_signature() {
+        let (graph, contexts) = setup();
+        let (mut session_0, _) = SigningSession::start("session", &graph, &contexts[0]);
+        let (mut session_1, message_1) = SigningSession::start("session", &graph, &contexts[1]);
+
+        // Ignored, the session id does not match.
+        let mut other_session_message = message_1.clone();
+        if let SessionMessage::Nonces { session_id, .. } = &mut other_session_message {
+            *session_id = "other".to_string();
+        }
+        assert!(session_0
+            .handle_message(&contexts[0], &other_session_message)
+            .is_empty());
+
+        let replies_0 = session_0.handle_message(&contexts[0], &message_1);
+        assert_eq!(
+            *session_0.state(),
+            SessionState::CollectingPartialSignatures
+        );
+        let SessionMessage::PartialSignatures {
+            partial_signatures, ..
+        } = &replies_0[0]
+        else {
+            panic!("Expected partial signatures")
+        };
+
+        // Verifier 1 replays verifier 0's partial signatures as its own.
+        let forged = SessionMessage::PartialSignatures {
+            session_id: "session".to_string(),
+            signer: contexts[1].verifier_public_key,
+            partial_signatures: partial_signatures.clone(),
+        };
+        session_0.handle_message(&contexts[0], &forged);
+        let SessionState::Aborted(blame) = session_0.state() else {
+            panic!("Session not aborted")
+        };
+        assert_eq!(blame.signer, Some(contexts[1].verifier_public_key));
+
+        // The abort is adopted by the other signers.
+        let blame = blame.clone();
+        let abort = session_0.abort_message(&contexts[0], blame.clone());
+        session_1.handle_message(&contexts[1], &abort);
+        assert_eq!(*session_1.state(), SessionState::Aborted(blame));
+    }
+
+    #[test]
+    fn test_abort_requires_signature_of_a_signer() {
+        let (graph, contexts) = setup();
+        let (session_0, _) = SigningSession::start("session", &graph, &contexts[0]);
+        let (mut session_1, _) = SigningSession::start("session", &graph, &contexts[1]);
+        let blame = Blame::unattributed("Aborted by an outsider");
+        let sign = |context: &VerifierContext, session_id: &str| {
+            context.secp.sign_schnorr(
+                &get_abort_message(session_id, &blame),
+                &context.verifier_keypair,
+            )
+        };
+
+        // Claims to be sent by verifier 0, but signed for another session.
+        let replayed = SessionMessage::Abort {
+            session_id: "session".to_string(),
+            sender: contexts[0].verifier_public_key,
+            blame: blame.clone(),
+            signature: sign(&contexts[0], "other"),
+        };
+        assert!(session_1.handle_message(&contexts[1], &replayed).is_empty());
+        assert_eq!(*session_1.state(), SessionState::CollectingNonces);
+
+        // Signed by a key that is not an n-of-n signer.
+        let outsider_context = VerifierContext::new(
+            Network::Regtest,
+            OPERATOR_SECRET,
+            &contexts[0].n_of_n_public_keys,
+        );
+        let outsider_abort = SessionMessage::Abort {
+            session_id: "session".to_string(),
+            sender: outsider_context.verifier_public_key,
+            blame: blame.clone(),
+            signature: sign(&outsider_context, "session"),
+        };
+        session_1.handle_message(&contexts[1], &outsider_abort);
+        assert_eq!(*session_1.state(), SessionState::CollectingNonces);
+
+        let abort = session_0.abort_message(&contexts[0], blame.clone());
+        session_1.handle_message(&contexts[1], &abort);
+        assert_eq!(*session_1.state(), SessionState::Aborted(blame));
+    }
+}
@@ -0,0 +1,124 @@
+use bitcoin::PublicKey;
+use std::{
+    collections::VecDeque,
+    sync::mpsc::{channel, Receiver, Sender, TryRecvError},
+};
+
+use super::{
+    super::contexts::verifier::VerifierContext,
+    messages::{Blame, SessionMessage},
+    session::{generate_session_id, Musig2Graph, SessionState, SigningSession},
+};
+
+/// Broadcast channel between the signers of a session.
+pub trait SessionTransport {
+    /// Sends `message` to every other signer.
+    fn broadcast(&mut self, message: &SessionMessage) -> Result<(), String>;
+
+    /// Returns the next message from another signer, or `None` if there is none yet.
+    fn receive(&mut self) -> Result<Option<SessionMessage>, String>;
+}
+
+/// In-process transport endpoint of one signer, connected to the endpoints of all other signers.
+pub struct LocalEndpoint {
+    signer: PublicKey,
+    peers: Vec<Sender<SessionMessage>>,
+    inbox: Receiver<SessionMessage>,
+}
+
+impl LocalEndpoint {
+    pub fn signer(&self) -> &PublicKey { &self.signer }
+}
+
+impl SessionTransport for LocalEndpoint {
+    fn broadcast(&mut self, message: &SessionMessage) -> Result<(), String> {
+        for peer in &self.peers {
+            peer.send(message.clone())
+                .map_err(|err| format!("Failed to send session message: {}", err))?;
+        }
+        Ok(())
+    }
+
+    fn receive(&mut self) -> Result<Option<SessionMessage>, String> {
+        match self.inbox.try_recv() {
+            Ok(message) => Ok(Some(message)),
+            Err(TryRecvError::Empty) => Ok(None),
+            Err(TryRecvError::Disconnected) => Err("All signers disconnected".to_string()),
+        }
+    }
+}
+
+/// Creates one connected endpoint per signer, in the order of `signers`.
+pub fn local_transport(signers: &[PublicKey]) -> Vec<LocalEndpoint> {
+    let (senders, receivers): (Vec<_>, Vec<_>) = signers.iter().map(|_| channel()).unzip();
+
+    signers
+        .iter()
+        .zip(receivers)
+        .enumerate()
+        .map(|(i, (signer, inbox))| LocalEndpoint {
+            signer: *signer,
+            peers: senders
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| *j != i)
+                .map(|(_, sender)| sender.clone())
+                .collect(),
+            inbox,
+        })
+        .collect()
+}
+
+/// Presigns `graph` in one session between all `contexts`, one per n-of-n signer, over a local
+/// transport. Returns the graph with every MuSig2 input signed, or the blame of the abort.
+pub fn run_local_session<G: Musig2Graph>(
+    graph: &G,
+    contexts: &[VerifierContext],
+) -> Result<G, Blame> {
+    let session_id = generate_session_id();
+    let signers = contexts
+        .iter()
+        .map(|context| context.verifier_public_key)
+        .collect::<Vec<_>>();
+    let mut endpoints = local_transport(&signers);
+
+    let mut sessions = vec![];
+    let mut outgoing = vec![];
+    for context in contexts {
+        let (session, message) = SigningSession::start(&session_id, graph, context);
+        sessions.push(session);
+        outgoing.push(VecDeque::from([message]));
+    }
+
+    let transport_error = |err: String| Blame::unattributed(err);
+    loop {
+        let mut progressed = false;
+        for i in 0..sessions.len() {
+            while let Some(message) = outgoing[i].pop_front() {
+                endpoints[i].broadcast(&message).map_err(transport_error)?;
+                progressed = true;
+            }
+            while let Some(message) = endpoints[i].receive().map_err(transport_error)? {
+                outgoing[i].extend(sessions[i].handle_message(&contexts[i], &message));
+                progressed = true;
+            }
+        }
+
+        for session in &sessions {
+            if let SessionState::Aborted(blame) = session.state() {
+                return Err(blame.clone());
+            }
+        }
+        if sessions
+            .iter()
+            .all(|session| *session.state() == SessionState::Complete)
+        {
+            break;
+        }
+        if !progressed {
+            return Err(Blame::unattributed("Session stalled"));
+        }
+    }
+
+    Ok(sessions.swap_remove(0).into_graph())
+}
@@ -0,0 +1,174 @@
+use bitcoin::Network;
+use serde::{Deserialize, Serialize};
+
+use super::constants::{
+    NUM_BLOCKS_PER_2_WEEKS, NUM_BLOCKS_PER_3_DAYS, NUM_BLOCKS_PER_6_HOURS, NUM_BLOCKS_PER_DAY,
+    NUM_BLOCKS_PER_HOUR,
+};
+
+// BIP68 encodes block based relative timelocks in the low 16 bits of nSequence.
+pub const MAX_RELATIVE_TIMELOCK_BLOCKS: u32 = 0xFFFF;
+
+/// Relative timelocks (in blocks) used by the connectors of a peg-in graph and all of the
+/// peg-out graphs created for it.
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Copy, Debug)]
+pub struct TimelockConfig {
+    /// Connector Z leaf 0: depositor can reclaim the deposit with the peg-in refund tx.
+    pub num_blocks_peg_in_refund: u32,
+    /// Connector 1 leaf 0: operator can broadcast kick-off 2 after kick-off 1.
+    pub num_blocks_kick_off_2: u32,
+    /// Connector 1 leaf 1: n-of-n can broadcast kick-off timeout after kick-off 1.
+    pub num_blocks_kick_off_timeout: u32,
+    /// Connector 1 leaf 2: n-of-n can broadcast start time timeout after kick-off 1.
+    pub num_blocks_start_time_timeout: u32,
+    /// Connector B leaf 1: assert can be broadcast after kick-off 2.
+    pub num_blocks_assert: u32,
+    /// Connector 3: operator can broadcast take 1 after kick-off 2.
+    pub num_blocks_take_1: u32,
+    /// Connector 4: operator can broadcast take 2 after assert.
+    pub num_blocks_take_2: u32,
+}
+
+impl TimelockConfig {
+    pub fn mainnet() -> Self {
+        TimelockConfig {
+            num_blocks_peg_in_refund: NUM_BLOCKS_PER_2_WEEKS,
+            num_blocks_kick_off_2: NUM_BLOCKS_PER_2_WEEKS,
+            num_blocks_kick_off_timeout: NUM_BLOCKS_PER_2_WEEKS + NUM_BLOCKS_PER_DAY,
+            num_blocks_start_time_timeout: NUM_BLOCKS_PER_6_HOURS,
+            num_blocks_assert: NUM_BLOCKS_PER_3_DAYS,
+            num_blocks_take_1: NUM_BLOCKS_PER_3_DAYS,
+            num_blocks_take_2: NUM_BLOCKS_PER_2_WEEKS,
+        }
+    }
+
+    /// Shorter but still realistic windows for signet and mutinynet deployments.
+    pub fn signet() -> Self {
+        TimelockConfig {
+            num_blocks_peg_in_refund: NUM_BLOCKS_PER_DAY,
+            num_blocks_kick_off_2: NUM_BLOCKS_PER_DAY,
+            num_blocks_kick_off_timeout: NUM_BLOCKS_PER_DAY + NUM_BLOCKS_PER_6_HOURS,
+            num_blocks_start_time_timeout: NUM_BLOCKS_PER_HOUR,
+            num_blocks_assert: NUM_BLOCKS_PER_6_HOURS,
+            num_blocks_take_1: NUM_BLOCKS_PER_6_HOURS,
+            num_blocks_take_2: NUM_BLOCKS_PER_DAY,
+        }
+    }
+
+    /// Testnet deployments use the signet windows rather than the single block regtest ones.
+    pub fn testnet() -> Self { Self::signet() }
+
+    /// Single block windows so that every path of a graph can be exercised quickly.
+    pub fn regtest() -> Self {
+        TimelockConfig {
+            num_blocks_peg_in_refund: 1,
+            num_blocks_kick_off_2: 1,
+            num_blocks_kick_off_timeout: 1,
+            num_blocks_start_time_timeout: 1,
+            num_blocks_assert: 1,
+            num_blocks_take_1: 1,
+            num_blocks_take_2: 1,
+        }
+    }
+
+    pub fn for_network(network: Network) -> Self {
+        match network {
+            Network::Bitcoin => Self::mainnet(),
+            Network::Testnet => Self::testnet(),
+            Network::Signet => Self::signet(),
+            _ => Self::regtest(),
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        let timelocks = [
+            ("peg-in refund", self.num_blocks_peg_in_refund),
+            ("kick-off 2", self.num_blocks_kick_off_2),
+            ("kick-off timeout", self.num_blocks_kick_off_timeout),
+            ("start time timeout", self.num_blocks_start_time_timeout),
+            ("assert", self.num_blocks_assert),
+            ("take 1", self.num_blocks_take_1),
+            ("take 2", self.num_blocks_take_2),
+        ];
+        for (name, num_blocks) in timelocks {
+            if num_blocks == 0 {
+                return Err(format!("The {} timelock must be at least 1 block", name));
+            }
+            if num_blocks > MAX_RELATIVE_TIMELOCK_BLOCKS {
+                return Err(format!(
+                    "The {} timelock of {} blocks exceeds the maximum relative timelock of {} blocks",
+                    name, num_blocks, MAX_RELATIVE_TIMELOCK_BLOCKS
+                ));
+            }
+        }
+
+        // The operator must get a chance to broadcast kick-off 2 before verifiers can time it out.
+        if self.num_blocks_kick_off_timeout < self.num_blocks_kick_off_2 {
+            return Err(format!(
+                "The kick-off timeout timelock ({} blocks) must not be shorter than the kick-off 2 timelock ({} blocks)",
+                self.num_blocks_kick_off_timeout, self.num_blocks_kick_off_2
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::Network;
+
+    use super::{TimelockConfig, MAX_RELATIVE_TIMELOCK_BLOCKS, NUM_BLOCKS_PER_6_HOURS};
+
+    #[test]
+    fn test_timelock_config_presets_are_valid() {
+        assert!(TimelockConfig::mainnet().validate().is_ok());
+        assert!(TimelockConfig::signet().validate().is_ok());
+        assert!(TimelockConfig::testnet().validate().is_ok());
+        assert!(TimelockConfig::regtest().validate().is_ok());
+    }
+
+    #[test]
+    fn test_timelock_config_for_network() {
+        assert_eq!(
+            TimelockConfig::for_network(Network::Bitcoin),
+            TimelockConfig::mainnet()
+        );
+        assert_eq!(
+            TimelockConfig::for_network(Network::Signet),
+            TimelockConfig::signet()
+        );
+        assert_eq!(
+            TimelockConfig::for_network(Network::Testnet),
+            TimelockConfig::testnet()
+        );
+        assert_eq!(
+            TimelockConfig::for_network(Network::Regtest),
+            TimelockConfig::regtest()
+        );
+
+        // Only regtest may use single block windows.
+        for network in [Network::Bitcoin, Network::Testnet, Network::Signet] {
+            assert!(TimelockConfig::for_network(network).num_blocks_peg_in_refund > 1);
+        }
+        assert_eq!(
+            TimelockConfig::for_network(Network::Testnet).num_blocks_take_1,
+            NUM_BLOCKS_PER_6_HOURS
+        );
+    }
+
+    #[test]
+    fn test_timelock_config_rejects_invalid_values() {
+        let mut timelock_config = TimelockConfig::mainnet();
+        timelock_config.num_blocks_assert = 0;
+        assert!(timelock_config.validate().is_err());
+
+        let mut timelock_config = TimelockConfig::mainnet();
+        timelock_config.num_blocks_take_2 = MAX_RELATIVE_TIMELOCK_BLOCKS + 1;
+        assert!(timelock_config.validate().is_err());
+
+        let mut timelock_config = TimelockConfig::mainnet();
+        timelock_config.num_blocks_kick_off_timeout = timelock_config.num_blocks_kick_off_2 - 1;
+        assert!(timelock_config.validate().is_err());
+    }
+}
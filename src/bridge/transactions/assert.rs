@@ -1,6 +1,6 @@
 use bitcoin::{
-    absolute, consensus, Amount, Network, PublicKey, ScriptBuf, TapSighashType, Transaction, TxOut,
-    XOnlyPublicKey,
+    absolute, consensus, psbt::PsbtSighashType, taproot::TaprootSpendInfo, Amount, Network,
+    PublicKey, ScriptBuf, TapSighashType, Transaction, TxOut, XOnlyPublicKey,
 };
 use musig2::{secp256k1::schnorr::Signature, PartialSignature, PubNonce, SecNonce};
 use serde::{Deserialize, Serialize};
@@ -14,10 +14,12 @@ use super::{
         },
         contexts::{base::BaseContext, operator::OperatorContext, verifier::VerifierContext},
         graphs::base::{DUST_AMOUNT, FEE_AMOUNT},
+        timelocks::TimelockConfig,
     },
     base::*,
     pre_signed::*,
     pre_signed_musig2::*,
+    psbt::*,
 };
 
 #[derive(Serialize, Deserialize, Eq, PartialEq, Clone)]
@@ -44,6 +46,22 @@ impl PreSignedTransaction for AssertTransaction {
     fn prev_scripts(&self) -> &Vec<ScriptBuf> { &self.prev_scripts }
 }
 
+impl PsbtTransaction for AssertTransaction {
+    fn taproot_spend_info(&self, input_index: usize) -> Option<TaprootSpendInfo> {
+        match input_index {
+            0 => Some(self.connector_b.generate_taproot_spend_info()),
+            _ => None,
+        }
+    }
+
+    fn sighash_type(&self, input_index: usize) -> Option<PsbtSighashType> {
+        match input_index {
+            0 => Some(TapSighashType::All.into()),
+            _ => None,
+        }
+    }
+}
+
 impl PreSignedMusig2Transaction for AssertTransaction {
     fn musig2_nonces(&self) -> &HashMap<usize, HashMap<PublicKey, PubNonce>> { &self.musig2_nonces }
     fn musig2_nonces_mut(&mut self) -> &mut HashMap<usize, HashMap<PublicKey, PubNonce>> {
@@ -68,13 +86,18 @@ impl PreSignedMusig2Transaction for AssertTransaction {
 }
 
 impl AssertTransaction {
-    pub fn new(context: &OperatorContext, input_0: Input) -> Self {
+    pub fn new(
+        context: &OperatorContext,
+        input_0: Input,
+        timelock_config: &TimelockConfig,
+    ) -> Self {
         Self::new_for_validation(
             context.network,
             &context.operator_public_key,
             &context.operator_taproot_public_key,
             &context.n_of_n_taproot_public_key,
             input_0,
+            timelock_config,
         )
     }
 
@@ -84,10 +107,11 @@ impl AssertTransaction {
         operator_taproot_public_key: &XOnlyPublicKey,
         n_of_n_taproot_public_key: &XOnlyPublicKey,
         input_0: Input,
+        timelock_config: &TimelockConfig,
     ) -> Self {
-        let connector_4 = Connector4::new(network, operator_public_key);
+        let connector_4 = Connector4::new(network, operator_public_key, timelock_config);
         let connector_5 = Connector5::new(network, n_of_n_taproot_public_key);
-        let connector_b = ConnectorB::new(network, n_of_n_taproot_public_key);
+        let connector_b = ConnectorB::new(network, n_of_n_taproot_public_key, timelock_config);
         let connector_c = ConnectorC::new(network, operator_taproot_public_key);
 
         let input_0_leaf = 1;
@@ -2,6 +2,7 @@ use bitcoin::{Amount, OutPoint, PublicKey, Script, Transaction, Txid, XOnlyPubli
 use core::cmp;
 use musig2::{secp256k1::schnorr::Signature, PubNonce};
 use std::collections::HashMap;
+use tracing::warn;
 
 use super::{
     pre_signed::PreSignedTransaction,
@@ -84,10 +85,10 @@ pub fn validate_transaction(
             || transaction.input[i].script_sig != comparison_transaction.input[i].script_sig
             || transaction.input[i].sequence != comparison_transaction.input[i].sequence
         {
-            println!(
-                "Input mismatch on transaction: {} input index: {}",
-                transaction.compute_txid(),
-                i
+            warn!(
+                txid = %transaction.compute_txid(),
+                input_index = i,
+                "Input mismatch on transaction"
             );
             return false;
         }
@@ -97,10 +98,10 @@ pub fn validate_transaction(
         if transaction.output[i].value != comparison_transaction.output[i].value
             || transaction.output[i].script_pubkey != comparison_transaction.output[i].script_pubkey
         {
-            println!(
-                "Output mismatch on transaction: {} output index: {}",
-                transaction.compute_txid(),
-                i
+            warn!(
+                txid = %transaction.compute_txid(),
+                output_index = i,
+                "Output mismatch on transaction"
             );
             return false;
         }
@@ -119,8 +120,11 @@ fn verify_public_nonces(
     for (i, nonces) in all_nonces {
         for (pubkey, nonce) in nonces {
             if !verify_public_nonce(&all_sigs[i][pubkey], nonce, &XOnlyPublicKey::from(*pubkey)) {
-                eprintln!(
-                    "Failed to verify public nonce for pubkey {pubkey} on tx:input {txid}:{i}."
+                warn!(
+                    %pubkey,
+                    %txid,
+                    input_index = i,
+                    "Failed to verify public nonce"
                 );
                 ret_val = false;
             }
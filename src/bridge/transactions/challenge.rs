@@ -1,6 +1,7 @@
 use bitcoin::{
-    absolute, consensus, key::Keypair, Amount, Network, PublicKey, ScriptBuf, Sequence,
-    TapSighashType, Transaction, TxIn, TxOut, Witness, XOnlyPublicKey,
+    absolute, consensus, key::Keypair, psbt::PsbtSighashType, taproot::TaprootSpendInfo, Amount,
+    Network, PublicKey, ScriptBuf, Sequence, TapSighashType, Transaction, TxIn, TxOut, Witness,
+    XOnlyPublicKey,
 };
 use serde::{Deserialize, Serialize};
 
@@ -13,6 +14,7 @@ use super::{
     },
     base::*,
     pre_signed::*,
+    psbt::*,
     signing::populate_p2wsh_witness,
 };
 
@@ -37,6 +39,22 @@ impl PreSignedTransaction for ChallengeTransaction {
     fn prev_scripts(&self) -> &Vec<ScriptBuf> { &self.prev_scripts }
 }
 
+impl PsbtTransaction for ChallengeTransaction {
+    fn taproot_spend_info(&self, input_index: usize) -> Option<TaprootSpendInfo> {
+        match input_index {
+            0 => Some(self.connector_a.generate_taproot_spend_info()),
+            _ => None,
+        }
+    }
+
+    fn sighash_type(&self, input_index: usize) -> Option<PsbtSighashType> {
+        match input_index {
+            0 => Some(TapSighashType::SinglePlusAnyoneCanPay.into()),
+            _ => None,
+        }
+    }
+}
+
 impl ChallengeTransaction {
     pub fn new(
         context: &OperatorContext,
@@ -1,6 +1,6 @@
 use bitcoin::{
-    absolute, consensus, Amount, Network, PublicKey, ScriptBuf, TapSighashType, Transaction, TxOut,
-    XOnlyPublicKey,
+    absolute, consensus, psbt::PsbtSighashType, taproot::TaprootSpendInfo, Amount, Network,
+    PublicKey, ScriptBuf, TapSighashType, Transaction, TxOut, XOnlyPublicKey,
 };
 use musig2::{secp256k1::schnorr::Signature, PartialSignature, PubNonce, SecNonce};
 use serde::{Deserialize, Serialize};
@@ -16,6 +16,7 @@ use super::{
     base::*,
     pre_signed::*,
     pre_signed_musig2::*,
+    psbt::*,
     signing::push_taproot_leaf_script_and_control_block_to_witness,
 };
 
@@ -45,6 +46,23 @@ impl PreSignedTransaction for DisproveTransaction {
     fn prev_scripts(&self) -> &Vec<ScriptBuf> { &self.prev_scripts }
 }
 
+impl PsbtTransaction for DisproveTransaction {
+    fn taproot_spend_info(&self, input_index: usize) -> Option<TaprootSpendInfo> {
+        match input_index {
+            0 => Some(self.connector_5.generate_taproot_spend_info()),
+            1 => self.connector_c.try_generate_taproot_spend_info().ok(),
+            _ => None,
+        }
+    }
+
+    fn sighash_type(&self, input_index: usize) -> Option<PsbtSighashType> {
+        match input_index {
+            0 => Some(TapSighashType::Single.into()),
+            _ => None,
+        }
+    }
+}
+
 impl PreSignedMusig2Transaction for DisproveTransaction {
     fn musig2_nonces(&self) -> &HashMap<usize, HashMap<PublicKey, PubNonce>> { &self.musig2_nonces }
     fn musig2_nonces_mut(&mut self) -> &mut HashMap<usize, HashMap<PublicKey, PubNonce>> {
@@ -217,6 +235,8 @@ impl DisproveTransaction {
         );
     }
 
+    pub fn connector_c(&self) -> &ConnectorC { &self.connector_c }
+
     pub fn merge(&mut self, disprove: &DisproveTransaction) {
         merge_transactions(&mut self.tx, &disprove.tx);
         merge_musig2_nonces_and_signatures(self, disprove);
@@ -1,6 +1,6 @@
 use bitcoin::{
-    absolute, consensus, Amount, Network, PublicKey, ScriptBuf, TapSighashType, Transaction, TxOut,
-    XOnlyPublicKey,
+    absolute, consensus, psbt::PsbtSighashType, taproot::TaprootSpendInfo, Amount, Network,
+    PublicKey, ScriptBuf, TapSighashType, Transaction, TxOut, XOnlyPublicKey,
 };
 use musig2::{secp256k1::schnorr::Signature, PartialSignature, PubNonce, SecNonce};
 use serde::{Deserialize, Serialize};
@@ -12,10 +12,12 @@ use super::{
         contexts::{base::BaseContext, operator::OperatorContext, verifier::VerifierContext},
         graphs::base::FEE_AMOUNT,
         scripts::*,
+        timelocks::TimelockConfig,
     },
     base::*,
     pre_signed::*,
     pre_signed_musig2::*,
+    psbt::*,
 };
 
 #[derive(Serialize, Deserialize, Eq, PartialEq, Clone)]
@@ -43,6 +45,22 @@ impl PreSignedTransaction for DisproveChainTransaction {
     fn prev_scripts(&self) -> &Vec<ScriptBuf> { &self.prev_scripts }
 }
 
+impl PsbtTransaction for DisproveChainTransaction {
+    fn taproot_spend_info(&self, input_index: usize) -> Option<TaprootSpendInfo> {
+        match input_index {
+            0 => Some(self.connector_b.generate_taproot_spend_info()),
+            _ => None,
+        }
+    }
+
+    fn sighash_type(&self, input_index: usize) -> Option<PsbtSighashType> {
+        match input_index {
+            0 => Some(TapSighashType::Single.into()),
+            _ => None,
+        }
+    }
+}
+
 impl PreSignedMusig2Transaction for DisproveChainTransaction {
     fn musig2_nonces(&self) -> &HashMap<usize, HashMap<PublicKey, PubNonce>> { &self.musig2_nonces }
     fn musig2_nonces_mut(&mut self) -> &mut HashMap<usize, HashMap<PublicKey, PubNonce>> {
@@ -67,16 +85,26 @@ impl PreSignedMusig2Transaction for DisproveChainTransaction {
 }
 
 impl DisproveChainTransaction {
-    pub fn new(context: &OperatorContext, input_0: Input) -> Self {
-        Self::new_for_validation(context.network, &context.n_of_n_taproot_public_key, input_0)
+    pub fn new(
+        context: &OperatorContext,
+        input_0: Input,
+        timelock_config: &TimelockConfig,
+    ) -> Self {
+        Self::new_for_validation(
+            context.network,
+            &context.n_of_n_taproot_public_key,
+            input_0,
+            timelock_config,
+        )
     }
 
     pub fn new_for_validation(
         network: Network,
         n_of_n_taproot_public_key: &XOnlyPublicKey,
         input_0: Input,
+        timelock_config: &TimelockConfig,
     ) -> Self {
-        let connector_b = ConnectorB::new(network, &n_of_n_taproot_public_key);
+        let connector_b = ConnectorB::new(network, &n_of_n_taproot_public_key, timelock_config);
 
         let input_0_leaf = 2;
         let _input_0 = connector_b.generate_taproot_leaf_tx_in(input_0_leaf, &input_0);
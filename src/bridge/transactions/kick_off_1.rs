@@ -1,6 +1,7 @@
 use bitcoin::{
-    absolute, consensus, Amount, EcdsaSighashType, Network, PublicKey, ScriptBuf, Sequence,
-    Transaction, TxIn, TxOut, Witness, XOnlyPublicKey,
+    absolute, consensus, psbt::PsbtSighashType, taproot::TaprootSpendInfo, Amount,
+    EcdsaSighashType, Network, PublicKey, ScriptBuf, Sequence, Transaction, TxIn, TxOut, Witness,
+    XOnlyPublicKey,
 };
 use serde::{Deserialize, Serialize};
 
@@ -12,9 +13,11 @@ use super::{
         contexts::operator::OperatorContext,
         graphs::base::{DUST_AMOUNT, FEE_AMOUNT},
         scripts::*,
+        timelocks::TimelockConfig,
     },
     base::*,
     pre_signed::*,
+    psbt::*,
 };
 
 #[derive(Serialize, Deserialize, Eq, PartialEq, Clone)]
@@ -36,14 +39,30 @@ impl PreSignedTransaction for KickOff1Transaction {
     fn prev_scripts(&self) -> &Vec<ScriptBuf> { &self.prev_scripts }
 }
 
+impl PsbtTransaction for KickOff1Transaction {
+    fn taproot_spend_info(&self, _: usize) -> Option<TaprootSpendInfo> { None }
+
+    fn sighash_type(&self, input_index: usize) -> Option<PsbtSighashType> {
+        match input_index {
+            0 => Some(EcdsaSighashType::All.into()),
+            _ => None,
+        }
+    }
+}
+
 impl KickOff1Transaction {
-    pub fn new(context: &OperatorContext, operator_input: Input) -> Self {
+    pub fn new(
+        context: &OperatorContext,
+        operator_input: Input,
+        timelock_config: &TimelockConfig,
+    ) -> Self {
         let mut this = Self::new_for_validation(
             context.network,
             &context.operator_public_key,
             &context.operator_taproot_public_key,
             &context.n_of_n_taproot_public_key,
             operator_input,
+            timelock_config,
         );
 
         this.sign_input_0(context);
@@ -57,11 +76,13 @@ impl KickOff1Transaction {
         operator_taproot_public_key: &XOnlyPublicKey,
         n_of_n_taproot_public_key: &XOnlyPublicKey,
         operator_input: Input,
+        timelock_config: &TimelockConfig,
     ) -> Self {
         let connector_1 = Connector1::new(
             network,
             operator_taproot_public_key,
             n_of_n_taproot_public_key,
+            timelock_config,
         );
         let connector_a = ConnectorA::new(
             network,
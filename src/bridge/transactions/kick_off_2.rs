@@ -1,6 +1,6 @@
 use bitcoin::{
-    absolute, consensus, Amount, Network, PublicKey, ScriptBuf, TapSighashType, Transaction, TxOut,
-    XOnlyPublicKey,
+    absolute, consensus, psbt::PsbtSighashType, taproot::TaprootSpendInfo, Amount, Network,
+    PublicKey, ScriptBuf, TapSighashType, Transaction, TxOut, XOnlyPublicKey,
 };
 use serde::{Deserialize, Serialize};
 
@@ -11,9 +11,11 @@ use super::{
         },
         contexts::operator::OperatorContext,
         graphs::base::{DUST_AMOUNT, FEE_AMOUNT},
+        timelocks::TimelockConfig,
     },
     base::*,
     pre_signed::*,
+    psbt::*,
 };
 
 #[derive(Serialize, Deserialize, Eq, PartialEq, Clone)]
@@ -36,14 +38,35 @@ impl PreSignedTransaction for KickOff2Transaction {
     fn prev_scripts(&self) -> &Vec<ScriptBuf> { &self.prev_scripts }
 }
 
+impl PsbtTransaction for KickOff2Transaction {
+    fn taproot_spend_info(&self, input_index: usize) -> Option<TaprootSpendInfo> {
+        match input_index {
+            0 => Some(self.connector_1.generate_taproot_spend_info()),
+            _ => None,
+        }
+    }
+
+    fn sighash_type(&self, input_index: usize) -> Option<PsbtSighashType> {
+        match input_index {
+            0 => Some(TapSighashType::All.into()),
+            _ => None,
+        }
+    }
+}
+
 impl KickOff2Transaction {
-    pub fn new(context: &OperatorContext, input_0: Input) -> Self {
+    pub fn new(
+        context: &OperatorContext,
+        input_0: Input,
+        timelock_config: &TimelockConfig,
+    ) -> Self {
         let mut this = Self::new_for_validation(
             context.network,
             &context.operator_public_key,
             &context.operator_taproot_public_key,
             &context.n_of_n_taproot_public_key,
             input_0,
+            timelock_config,
         );
 
         this.sign_input_0(context);
@@ -57,14 +80,16 @@ impl KickOff2Transaction {
         operator_taproot_public_key: &XOnlyPublicKey,
         n_of_n_taproot_public_key: &XOnlyPublicKey,
         input_0: Input,
+        timelock_config: &TimelockConfig,
     ) -> Self {
         let connector_1 = Connector1::new(
             network,
             operator_taproot_public_key,
             n_of_n_taproot_public_key,
+            timelock_config,
         );
-        let connector_3 = Connector3::new(network, operator_public_key);
-        let connector_b = ConnectorB::new(network, n_of_n_taproot_public_key);
+        let connector_3 = Connector3::new(network, operator_public_key, timelock_config);
+        let connector_b = ConnectorB::new(network, n_of_n_taproot_public_key, timelock_config);
 
         let input_0_leaf = 0;
         let _input_0 = connector_1.generate_taproot_leaf_tx_in(input_0_leaf, &input_0);
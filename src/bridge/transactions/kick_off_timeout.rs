@@ -1,6 +1,6 @@
 use bitcoin::{
-    absolute, consensus, Amount, Network, PublicKey, ScriptBuf, TapSighashType, Transaction, TxOut,
-    XOnlyPublicKey,
+    absolute, consensus, psbt::PsbtSighashType, taproot::TaprootSpendInfo, Amount, Network,
+    PublicKey, ScriptBuf, TapSighashType, Transaction, TxOut, XOnlyPublicKey,
 };
 use musig2::{secp256k1::schnorr::Signature, PartialSignature, PubNonce, SecNonce};
 use serde::{Deserialize, Serialize};
@@ -12,10 +12,12 @@ use super::{
         contexts::{base::BaseContext, operator::OperatorContext, verifier::VerifierContext},
         graphs::base::FEE_AMOUNT,
         scripts::*,
+        timelocks::TimelockConfig,
     },
     base::*,
     pre_signed::*,
     pre_signed_musig2::*,
+    psbt::*,
 };
 
 #[derive(Serialize, Deserialize, Eq, PartialEq, Clone)]
@@ -43,6 +45,22 @@ impl PreSignedTransaction for KickOffTimeoutTransaction {
     fn prev_scripts(&self) -> &Vec<ScriptBuf> { &self.prev_scripts }
 }
 
+impl PsbtTransaction for KickOffTimeoutTransaction {
+    fn taproot_spend_info(&self, input_index: usize) -> Option<TaprootSpendInfo> {
+        match input_index {
+            0 => Some(self.connector_1.generate_taproot_spend_info()),
+            _ => None,
+        }
+    }
+
+    fn sighash_type(&self, input_index: usize) -> Option<PsbtSighashType> {
+        match input_index {
+            0 => Some(TapSighashType::Single.into()),
+            _ => None,
+        }
+    }
+}
+
 impl PreSignedMusig2Transaction for KickOffTimeoutTransaction {
     fn musig2_nonces(&self) -> &HashMap<usize, HashMap<PublicKey, PubNonce>> { &self.musig2_nonces }
     fn musig2_nonces_mut(&mut self) -> &mut HashMap<usize, HashMap<PublicKey, PubNonce>> {
@@ -67,12 +85,17 @@ impl PreSignedMusig2Transaction for KickOffTimeoutTransaction {
 }
 
 impl KickOffTimeoutTransaction {
-    pub fn new(context: &OperatorContext, input_0: Input) -> Self {
+    pub fn new(
+        context: &OperatorContext,
+        input_0: Input,
+        timelock_config: &TimelockConfig,
+    ) -> Self {
         Self::new_for_validation(
             context.network,
             &context.operator_taproot_public_key,
             &context.n_of_n_taproot_public_key,
             input_0,
+            timelock_config,
         )
     }
 
@@ -81,11 +104,13 @@ impl KickOffTimeoutTransaction {
         operator_taproot_public_key: &XOnlyPublicKey,
         n_of_n_taproot_public_key: &XOnlyPublicKey,
         input_0: Input,
+        timelock_config: &TimelockConfig,
     ) -> Self {
         let connector_1 = Connector1::new(
             network,
             &operator_taproot_public_key,
             &n_of_n_taproot_public_key,
+            timelock_config,
         );
 
         let input_0_leaf = 1;
@@ -12,6 +12,8 @@ pub mod peg_in_refund;
 pub mod peg_out;
 pub mod pre_signed;
 pub mod pre_signed_musig2;
+pub mod psbt;
+pub mod script_validation;
 pub mod signing;
 pub mod signing_musig2;
 pub mod start_time;
@@ -1,6 +1,6 @@
 use bitcoin::{
-    absolute, consensus, Amount, Network, PublicKey, ScriptBuf, TapSighashType, Transaction, TxOut,
-    XOnlyPublicKey,
+    absolute, consensus, psbt::PsbtSighashType, taproot::TaprootSpendInfo, Amount, Network,
+    PublicKey, ScriptBuf, TapSighashType, Transaction, TxOut, XOnlyPublicKey,
 };
 use musig2::{secp256k1::schnorr::Signature, PartialSignature, PubNonce, SecNonce};
 use serde::{Deserialize, Serialize};
@@ -11,10 +11,12 @@ use super::{
         connectors::{connector::*, connector_0::Connector0, connector_z::ConnectorZ},
         contexts::{base::BaseContext, depositor::DepositorContext, verifier::VerifierContext},
         graphs::base::FEE_AMOUNT,
+        timelocks::TimelockConfig,
     },
     base::*,
     pre_signed::*,
     pre_signed_musig2::*,
+    psbt::*,
     signing::*,
 };
 
@@ -42,6 +44,22 @@ impl PreSignedTransaction for PegInConfirmTransaction {
     fn prev_scripts(&self) -> &Vec<ScriptBuf> { &self.prev_scripts }
 }
 
+impl PsbtTransaction for PegInConfirmTransaction {
+    fn taproot_spend_info(&self, input_index: usize) -> Option<TaprootSpendInfo> {
+        match input_index {
+            0 => Some(self.connector_z.generate_taproot_spend_info()),
+            _ => None,
+        }
+    }
+
+    fn sighash_type(&self, input_index: usize) -> Option<PsbtSighashType> {
+        match input_index {
+            0 => Some(TapSighashType::All.into()),
+            _ => None,
+        }
+    }
+}
+
 impl PreSignedMusig2Transaction for PegInConfirmTransaction {
     fn musig2_nonces(&self) -> &HashMap<usize, HashMap<PublicKey, PubNonce>> { &self.musig2_nonces }
     fn musig2_nonces_mut(&mut self) -> &mut HashMap<usize, HashMap<PublicKey, PubNonce>> {
@@ -66,13 +84,19 @@ impl PreSignedMusig2Transaction for PegInConfirmTransaction {
 }
 
 impl PegInConfirmTransaction {
-    pub fn new(context: &DepositorContext, evm_address: &str, input_0: Input) -> Self {
+    pub fn new(
+        context: &DepositorContext,
+        evm_address: &str,
+        input_0: Input,
+        timelock_config: &TimelockConfig,
+    ) -> Self {
         let mut this = Self::new_for_validation(
             context.network,
             &context.depositor_taproot_public_key,
             &context.n_of_n_taproot_public_key,
             evm_address,
             input_0,
+            timelock_config,
         );
 
         this.push_depositor_signature_input_0(context);
@@ -86,6 +110,7 @@ impl PegInConfirmTransaction {
         n_of_n_taproot_public_key: &XOnlyPublicKey,
         evm_address: &str,
         input_0: Input,
+        timelock_config: &TimelockConfig,
     ) -> Self {
         let connector_0 = Connector0::new(network, n_of_n_taproot_public_key);
         let connector_z = ConnectorZ::new(
@@ -93,6 +118,7 @@ impl PegInConfirmTransaction {
             evm_address,
             depositor_taproot_public_key,
             n_of_n_taproot_public_key,
+            timelock_config,
         );
 
         let input_0_leaf = 1;
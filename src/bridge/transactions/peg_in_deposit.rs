@@ -1,6 +1,6 @@
 use bitcoin::{
-    absolute, consensus, Amount, EcdsaSighashType, Network, PublicKey, ScriptBuf, Transaction,
-    TxOut, XOnlyPublicKey,
+    absolute, consensus, psbt::PsbtSighashType, taproot::TaprootSpendInfo, Amount,
+    EcdsaSighashType, Network, PublicKey, ScriptBuf, Transaction, TxOut, XOnlyPublicKey,
 };
 use serde::{Deserialize, Serialize};
 
@@ -10,9 +10,11 @@ use super::{
         contexts::depositor::DepositorContext,
         graphs::base::FEE_AMOUNT,
         scripts::*,
+        timelocks::TimelockConfig,
     },
     base::*,
     pre_signed::*,
+    psbt::*,
 };
 
 #[derive(Serialize, Deserialize, Eq, PartialEq, Clone)]
@@ -34,8 +36,24 @@ impl PreSignedTransaction for PegInDepositTransaction {
     fn prev_scripts(&self) -> &Vec<ScriptBuf> { &self.prev_scripts }
 }
 
+impl PsbtTransaction for PegInDepositTransaction {
+    fn taproot_spend_info(&self, _: usize) -> Option<TaprootSpendInfo> { None }
+
+    fn sighash_type(&self, input_index: usize) -> Option<PsbtSighashType> {
+        match input_index {
+            0 => Some(EcdsaSighashType::All.into()),
+            _ => None,
+        }
+    }
+}
+
 impl PegInDepositTransaction {
-    pub fn new(context: &DepositorContext, evm_address: &str, input_0: Input) -> Self {
+    pub fn new(
+        context: &DepositorContext,
+        evm_address: &str,
+        input_0: Input,
+        timelock_config: &TimelockConfig,
+    ) -> Self {
         let mut this = Self::new_for_validation(
             context.network,
             &context.depositor_public_key,
@@ -43,6 +61,7 @@ impl PegInDepositTransaction {
             &context.n_of_n_taproot_public_key,
             evm_address,
             input_0,
+            timelock_config,
         );
 
         this.sign_input_0(context);
@@ -57,12 +76,14 @@ impl PegInDepositTransaction {
         n_of_n_taproot_public_key: &XOnlyPublicKey,
         evm_address: &str,
         input_0: Input,
+        timelock_config: &TimelockConfig,
     ) -> Self {
         let connector_z = ConnectorZ::new(
             network,
             evm_address,
             depositor_taproot_public_key,
             n_of_n_taproot_public_key,
+            timelock_config,
         );
 
         let _input_0 = generate_default_tx_in(&input_0);
@@ -1,6 +1,6 @@
 use bitcoin::{
-    absolute, consensus, Amount, Network, PublicKey, ScriptBuf, TapSighashType, Transaction, TxOut,
-    XOnlyPublicKey,
+    absolute, consensus, psbt::PsbtSighashType, taproot::TaprootSpendInfo, Amount, Network,
+    PublicKey, ScriptBuf, TapSighashType, Transaction, TxOut, XOnlyPublicKey,
 };
 use serde::{Deserialize, Serialize};
 
@@ -10,9 +10,11 @@ use super::{
         contexts::depositor::DepositorContext,
         graphs::base::FEE_AMOUNT,
         scripts::*,
+        timelocks::TimelockConfig,
     },
     base::*,
     pre_signed::*,
+    psbt::*,
 };
 
 #[derive(Serialize, Deserialize, Eq, PartialEq, Clone)]
@@ -35,8 +37,29 @@ impl PreSignedTransaction for PegInRefundTransaction {
     fn prev_scripts(&self) -> &Vec<ScriptBuf> { &self.prev_scripts }
 }
 
+impl PsbtTransaction for PegInRefundTransaction {
+    fn taproot_spend_info(&self, input_index: usize) -> Option<TaprootSpendInfo> {
+        match input_index {
+            0 => Some(self.connector_z.generate_taproot_spend_info()),
+            _ => None,
+        }
+    }
+
+    fn sighash_type(&self, input_index: usize) -> Option<PsbtSighashType> {
+        match input_index {
+            0 => Some(TapSighashType::All.into()),
+            _ => None,
+        }
+    }
+}
+
 impl PegInRefundTransaction {
-    pub fn new(context: &DepositorContext, evm_address: &str, input_0: Input) -> Self {
+    pub fn new(
+        context: &DepositorContext,
+        evm_address: &str,
+        input_0: Input,
+        timelock_config: &TimelockConfig,
+    ) -> Self {
         let mut this = Self::new_for_validation(
             context.network,
             &context.depositor_public_key,
@@ -44,6 +67,7 @@ impl PegInRefundTransaction {
             &context.n_of_n_taproot_public_key,
             evm_address,
             input_0,
+            timelock_config,
         );
 
         this.sign_input_0(context);
@@ -58,12 +82,14 @@ impl PegInRefundTransaction {
         n_of_n_taproot_public_key: &XOnlyPublicKey,
         evm_address: &str,
         input_0: Input,
+        timelock_config: &TimelockConfig,
     ) -> Self {
         let connector_z = ConnectorZ::new(
             network,
             evm_address,
             depositor_taproot_public_key,
             n_of_n_taproot_public_key,
+            timelock_config,
         );
 
         let input_0_leaf = 0;
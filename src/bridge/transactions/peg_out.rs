@@ -1,6 +1,6 @@
 use bitcoin::{
-    absolute, consensus, Amount, EcdsaSighashType, Network, PublicKey, ScriptBuf, Sequence,
-    Transaction, TxIn, TxOut, Witness,
+    absolute, consensus, psbt::PsbtSighashType, taproot::TaprootSpendInfo, Amount,
+    EcdsaSighashType, Network, PublicKey, ScriptBuf, Sequence, Transaction, TxIn, TxOut, Witness,
 };
 use serde::{Deserialize, Serialize};
 
@@ -8,6 +8,7 @@ use super::{
     super::{contexts::operator::OperatorContext, graphs::base::FEE_AMOUNT, scripts::*},
     base::*,
     pre_signed::*,
+    psbt::*,
 };
 
 #[derive(Serialize, Deserialize, Eq, PartialEq, Clone)]
@@ -29,6 +30,17 @@ impl PreSignedTransaction for PegOutTransaction {
     fn prev_scripts(&self) -> &Vec<ScriptBuf> { &self.prev_scripts }
 }
 
+impl PsbtTransaction for PegOutTransaction {
+    fn taproot_spend_info(&self, _: usize) -> Option<TaprootSpendInfo> { None }
+
+    fn sighash_type(&self, input_index: usize) -> Option<PsbtSighashType> {
+        match input_index {
+            0 => Some(EcdsaSighashType::All.into()),
+            _ => None,
+        }
+    }
+}
+
 impl PegOutTransaction {
     pub fn new(
         context: &OperatorContext,
@@ -7,10 +7,12 @@ use musig2::{
     BinaryEncoding, PartialSignature, PubNonce, SecNonce,
 };
 use std::collections::HashMap;
+use tracing::warn;
 
 use super::{
     super::contexts::{base::BaseContext, verifier::VerifierContext},
     pre_signed::PreSignedTransaction,
+    psbt::PsbtTransaction,
     signing::push_taproot_leaf_script_and_control_block_to_witness,
     signing_musig2::{
         generate_aggregated_nonce, generate_nonce, generate_taproot_aggregated_signature,
@@ -30,6 +32,12 @@ pub trait PreSignedMusig2Transaction {
     ) -> &mut HashMap<usize, HashMap<PublicKey, PartialSignature>>;
 }
 
+/// Transaction with inputs signed by the n-of-n through MuSig2, whose nonces and partial
+/// signatures are exchanged in a signing session.
+pub trait Musig2Transaction: PsbtTransaction + PreSignedMusig2Transaction {}
+
+impl<T: PsbtTransaction + PreSignedMusig2Transaction> Musig2Transaction for T {}
+
 pub fn push_nonce<T: PreSignedTransaction + PreSignedMusig2Transaction>(
     tx: &mut T,
     context: &VerifierContext,
@@ -74,7 +82,7 @@ fn verify_schnorr_signature(sig: &Signature, msg: &Message, pubkey: &XOnlyPublic
     match Secp256k1::new().verify_schnorr(sig, msg, pubkey) {
         Ok(()) => true,
         Err(e) => {
-            eprintln!("verify_schnorr() failed with: {e}");
+            warn!("verify_schnorr() failed with: {e}");
             false
         }
     }
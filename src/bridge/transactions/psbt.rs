@@ -0,0 +1,796 @@
+use bitcoin::{
+    bip32::{DerivationPath, Fingerprint, KeySource},
+    ecdsa,
+    hashes::Hash,
+    key::{Parity, Secp256k1},
+    opcodes::all::{OP_CHECKSIG, OP_CHECKSIGVERIFY},
+    psbt::{self, Psbt, PsbtSighashType},
+    script::{Builder, Instruction},
+    secp256k1::Message,
+    sighash::SighashCache,
+    taproot::{LeafVersion, TaprootSpendInfo},
+    PublicKey, ScriptBuf, TapLeafHash, TapSighashType, Transaction, TxOut, Txid, Witness,
+    XOnlyPublicKey,
+};
+use std::collections::HashMap;
+
+use super::{pre_signed::PreSignedTransaction, script_validation::ScriptValidationReport};
+
+/// BIP 174/371 PSBT export and import of the inputs a transaction spends from the graph, so they
+/// can be signed outside of the client, e.g. with a hardware wallet.
+///
+/// Exported inputs carry their prevout, sighash type and either the witness script or the
+/// internal key, merkle root and the leaf script with its control block. Segwit v0 inputs also
+/// carry their previous transaction. Every key in the spent script is listed with its origin, so
+/// signers can recognize their keys. Inputs already signed are exported with their final witness.
+/// On import, final witnesses are copied and signatures of signature-only inputs are assembled
+/// into witnesses; inputs that need more than signatures, e.g. the connector C leaves, must be
+/// finalized before importing. Imported taproot witnesses must pass script validation, imported
+/// segwit v0 witnesses must spend a p2wpkh output or a p2wsh script that only checks signatures,
+/// like every segwit v0 input of the graph, and carry a valid signature for each of its keys.
+pub trait PsbtTransaction: PreSignedTransaction {
+    /// Spend info of the connector spent by a taproot input, None for segwit v0 inputs.
+    fn taproot_spend_info(&self, input_index: usize) -> Option<TaprootSpendInfo>;
+
+    /// Sighash type the input is signed with, None for inputs that are not signed.
+    fn sighash_type(&self, input_index: usize) -> Option<PsbtSighashType>;
+
+    /// Inputs spending taproot outputs that are signed, with their taproot sighash type.
+    fn taproot_sighash_types(&self) -> Vec<(usize, TapSighashType)> {
+        (0..self.tx().input.len())
+            .filter_map(|input_index| Some((input_index, taproot_sighash_type(self, input_index)?)))
+            .collect()
+    }
+
+    /// Transactions whose outputs are spent by segwit v0 inputs, which `export_psbt` needs.
+    fn segwit_v0_previous_txids(&self) -> Vec<Txid> {
+        self.prev_outs()
+            .iter()
+            .zip(&self.tx().input)
+            .filter(|(prev_out, _)| is_segwit_v0(prev_out))
+            .map(|(_, input)| input.previous_output.txid)
+            .collect()
+    }
+
+    /// Exports the transaction with the `previous_transactions` of its segwit v0 inputs. Keys
+    /// without an entry in `key_origins` are exported as their own master key.
+    fn export_psbt(
+        &self,
+        previous_transactions: &[Transaction],
+        key_origins: &HashMap<PublicKey, KeySource>,
+    ) -> Result<Psbt, String> {
+        let mut unsigned_tx = self.tx().clone();
+        for input in unsigned_tx.input.iter_mut() {
+            input.script_sig = ScriptBuf::new();
+            input.witness = Witness::default();
+        }
+        let mut psbt = Psbt::from_unsigned_tx(unsigned_tx)
+            .map_err(|err| format!("Could not create PSBT: {}", err))?;
+
+        for (input_index, psbt_input) in psbt.inputs.iter_mut().enumerate() {
+            let witness = &self.tx().input[input_index].witness;
+            if !witness.is_empty() {
+                psbt_input.final_script_witness = Some(witness.clone());
+            }
+
+            // Inputs added outside of the graph, e.g. challenge crowdfunding, have no prev out.
+            let (prev_out, script) = match (
+                self.prev_outs().get(input_index),
+                self.prev_scripts().get(input_index),
+            ) {
+                (Some(prev_out), Some(script)) => (prev_out, script),
+                _ => continue,
+            };
+            psbt_input.witness_utxo = Some(prev_out.clone());
+            psbt_input.sighash_type = self.sighash_type(input_index);
+
+            // Segwit v0 signatures do not commit to the spent amount, signers check it against
+            // the previous transaction.
+            if is_segwit_v0(prev_out) {
+                let previous_output = self.tx().input[input_index].previous_output;
+                let previous_transaction = previous_transactions
+                    .iter()
+                    .find(|tx| tx.compute_txid() == previous_output.txid)
+                    .ok_or_else(|| {
+                        format!(
+                            "Previous transaction {} of input {} is missing",
+                            previous_output.txid, input_index
+                        )
+                    })?;
+                if previous_transaction
+                    .output
+                    .get(previous_output.vout as usize)
+                    != Some(prev_out)
+                {
+                    return Err(format!(
+                        "Previous transaction {} does not create the output spent by input {}",
+                        previous_output.txid, input_index
+                    ));
+                }
+                psbt_input.non_witness_utxo = Some(previous_transaction.clone());
+            }
+
+            if prev_out.script_pubkey.is_p2wsh() {
+                psbt_input.witness_script = Some(script.clone());
+                for public_key in script_public_keys(script) {
+                    psbt_input
+                        .bip32_derivation
+                        .insert(public_key.inner, key_source(key_origins, &public_key));
+                }
+            } else if let Some(taproot_spend_info) = self.taproot_spend_info(input_index) {
+                let leaf = (script.clone(), LeafVersion::TapScript);
+                let control_block = taproot_spend_info.control_block(&leaf).ok_or_else(|| {
+                    format!(
+                        "Leaf script of input {} is not in the taproot tree it spends",
+                        input_index
+                    )
+                })?;
+                psbt_input.tap_internal_key = Some(taproot_spend_info.internal_key());
+                psbt_input.tap_merkle_root = taproot_spend_info.merkle_root();
+                psbt_input.tap_scripts.insert(control_block, leaf);
+
+                let leaf_hash = TapLeafHash::from_script(script, LeafVersion::TapScript);
+                for public_key in script_x_only_public_keys(script) {
+                    psbt_input.tap_key_origins.insert(
+                        public_key,
+                        (vec![leaf_hash], x_only_key_source(key_origins, &public_key)),
+                    );
+                }
+            }
+        }
+
+        Ok(psbt)
+    }
+
+    /// Copies the witnesses of inputs signed in `psbt` that are still unsigned here, returning
+    /// the number of inputs that were completed. Nothing is imported if any of the new witnesses
+    /// fails script validation or, for segwit v0 inputs, signature validation.
+    fn import_psbt(&mut self, psbt: &Psbt) -> Result<usize, String> {
+        let txid = self.tx().compute_txid();
+        if psbt.unsigned_tx.compute_txid() != txid {
+            return Err(format!(
+                "PSBT spends transaction {}, expected {}",
+                psbt.unsigned_tx.compute_txid(),
+                txid
+            ));
+        }
+
+        let mut imported_inputs = vec![];
+        for (input_index, psbt_input) in psbt.inputs.iter().enumerate() {
+            if !self.tx().input[input_index].witness.is_empty() {
+                continue;
+            }
+
+            let witness = match &psbt_input.final_script_witness {
+                Some(witness) => Some(witness.clone()),
+                None => match (
+                    self.prev_outs().get(input_index),
+                    self.prev_scripts().get(input_index),
+                ) {
+                    (Some(prev_out), Some(script)) => generate_witness_from_signatures(
+                        prev_out,
+                        script,
+                        self.taproot_spend_info(input_index),
+                        psbt_input,
+                    )
+                    .map_err(|err| format!("Input {}: {}", input_index, err))?,
+                    _ => None,
+                },
+            };
+
+            if let Some(witness) = witness {
+                self.tx_mut().input[input_index].witness = witness;
+                imported_inputs.push(input_index);
+            }
+        }
+
+        let signed_inputs = imported_inputs
+            .iter()
+            .filter_map(|&input_index| {
+                Some((input_index, taproot_sighash_type(self, input_index)?))
+            })
+            .collect::<Vec<_>>();
+        let mut report = ScriptValidationReport::new();
+        report.validate_transaction("imported", self, &signed_inputs);
+        // Script validation skips segwit v0 inputs, their signatures are checked here instead
+        let segwit_v0_failures = imported_inputs
+            .iter()
+            .filter_map(|&input_index| {
+                let prev_out = self.prev_outs().get(input_index)?;
+                if !is_segwit_v0(prev_out) {
+                    return None;
+                }
+                validate_segwit_v0_witness(self.tx(), input_index, prev_out)
+                    .err()
+                    .map(|err| format!("  segwit v0 input {}: {}\n", input_index, err))
+            })
+            .collect::<String>();
+        if !segwit_v0_failures.is_empty()
+            || report
+                .failures
+                .iter()
+                .any(|failure| imported_inputs.contains(&failure.input_index))
+        {
+            for input_index in imported_inputs {
+                self.tx_mut().input[input_index].witness = Witness::default();
+            }
+            return Err(format!(
+                "Rejecting invalid witnesses\n{}{}",
+                report, segwit_v0_failures
+            ));
+        }
+
+        Ok(imported_inputs.len())
+    }
+}
+
+// Sighash type of a signed input spending a taproot output, which script validation checks.
+fn taproot_sighash_type<T: PsbtTransaction + ?Sized>(
+    transaction: &T,
+    input_index: usize,
+) -> Option<TapSighashType> {
+    if !transaction
+        .prev_outs()
+        .get(input_index)?
+        .script_pubkey
+        .is_p2tr()
+    {
+        return None;
+    }
+    transaction
+        .sighash_type(input_index)?
+        .taproot_hash_ty()
+        .ok()
+}
+
+fn is_segwit_v0(prev_out: &TxOut) -> bool {
+    prev_out.script_pubkey.is_p2wsh() || prev_out.script_pubkey.is_p2wpkh()
+}
+
+fn script_public_keys(script: &ScriptBuf) -> Vec<PublicKey> {
+    script
+        .instructions()
+        .filter_map(|instruction| match instruction {
+            Ok(Instruction::PushBytes(bytes)) => PublicKey::from_slice(bytes.as_bytes()).ok(),
+            _ => None,
+        })
+        .collect()
+}
+
+fn script_x_only_public_keys(script: &ScriptBuf) -> Vec<XOnlyPublicKey> {
+    script
+        .instructions()
+        .filter_map(|instruction| match instruction {
+            Ok(Instruction::PushBytes(bytes)) => XOnlyPublicKey::from_slice(bytes.as_bytes()).ok(),
+            _ => None,
+        })
+        .collect()
+}
+
+// Keys of the graph are not derived from an extended key, by default they are exported as their
+// own master key: the fingerprint of the key itself with an empty derivation path.
+fn key_source(key_origins: &HashMap<PublicKey, KeySource>, public_key: &PublicKey) -> KeySource {
+    key_origins.get(public_key).cloned().unwrap_or_else(|| {
+        let hash = public_key.pubkey_hash();
+        let [b0, b1, b2, b3, ..] = *hash.as_byte_array();
+        (
+            Fingerprint::from([b0, b1, b2, b3]),
+            DerivationPath::master(),
+        )
+    })
+}
+
+fn x_only_key_source(
+    key_origins: &HashMap<PublicKey, KeySource>,
+    public_key: &XOnlyPublicKey,
+) -> KeySource {
+    match key_origins
+        .iter()
+        .find(|(key, _)| key.inner.x_only_public_key().0 == *public_key)
+    {
+        Some((_, key_source)) => key_source.clone(),
+        None => key_source(
+            key_origins,
+            &PublicKey::new(public_key.public_key(Parity::Even)),
+        ),
+    }
+}
+
+// Builds the witness of an input whose script only checks signatures. Signatures are pushed in
+// the reverse order of their keys in the script, as `<key 1> OP_CHECKSIGVERIFY <key 2>
+// OP_CHECKSIG` consumes the signature of key 1 first.
+fn generate_witness_from_signatures(
+    prev_out: &TxOut,
+    script: &ScriptBuf,
+    taproot_spend_info: Option<TaprootSpendInfo>,
+    psbt_input: &psbt::Input,
+) -> Result<Option<Witness>, String> {
+    let mut witness = Witness::new();
+
+    if prev_out.script_pubkey.is_p2wpkh() {
+        let (public_key, signature) = match psbt_input.partial_sigs.iter().next() {
+            Some(partial_sig) => partial_sig,
+            None => return Ok(None),
+        };
+        witness.push_ecdsa_signature(signature);
+        witness.push(public_key.to_bytes());
+    } else if prev_out.script_pubkey.is_p2wsh() {
+        if psbt_input.partial_sigs.is_empty() {
+            return Ok(None);
+        }
+        let mut signatures = vec![];
+        for (public_key, signature) in &psbt_input.partial_sigs {
+            signatures.push((key_position(script, &public_key.to_bytes())?, signature));
+        }
+        signatures.sort_by(|a, b| b.0.cmp(&a.0));
+        for (_, signature) in signatures {
+            witness.push_ecdsa_signature(signature);
+        }
+        witness.push(script);
+    } else if let Some(taproot_spend_info) = taproot_spend_info {
+        let leaf_hash = TapLeafHash::from_script(script, LeafVersion::TapScript);
+        let mut signatures = vec![];
+        for ((public_key, signature_leaf_hash), signature) in &psbt_input.tap_script_sigs {
+            if *signature_leaf_hash == leaf_hash {
+                signatures.push((key_position(script, &public_key.serialize())?, signature));
+            }
+        }
+        if signatures.is_empty() {
+            return Ok(None);
+        }
+        signatures.sort_by(|a, b| b.0.cmp(&a.0));
+        for (_, signature) in signatures {
+            witness.push(signature.to_vec());
+        }
+
+        let leaf = (script.clone(), LeafVersion::TapScript);
+        let control_block = taproot_spend_info
+            .control_block(&leaf)
+            .ok_or("Leaf script is not in the taproot tree it spends")?;
+        witness.push(script.to_bytes());
+        witness.push(control_block.serialize());
+    } else {
+        return Ok(None);
+    }
+
+    Ok(Some(witness))
+}
+
+// Checks the signatures of the witness of a segwit v0 input against the keys of the spent p2wpkh
+// output or p2wsh script. Signatures are expected in the order `generate_witness_from_signatures`
+// pushes them, the reverse order of their keys in the script.
+fn validate_segwit_v0_witness(
+    tx: &Transaction,
+    input_index: usize,
+    prev_out: &TxOut,
+) -> Result<(), String> {
+    let mut stack = tx.input[input_index].witness.to_vec();
+    let script = ScriptBuf::from_bytes(stack.pop().ok_or("Empty witness")?);
+    let public_keys = if prev_out.script_pubkey.is_p2wpkh() {
+        let public_key = PublicKey::from_slice(script.as_bytes())
+            .map_err(|err| format!("Invalid public key in witness: {}", err))?;
+        let wpubkey_hash = public_key
+            .wpubkey_hash()
+            .map_err(|err| format!("Invalid public key in witness: {}", err))?;
+        if ScriptBuf::new_p2wpkh(&wpubkey_hash) != prev_out.script_pubkey {
+            return Err(String::from(
+                "Public key in witness does not match the spent output",
+            ));
+        }
+        vec![public_key]
+    } else {
+        if ScriptBuf::new_p2wsh(&script.wscript_hash()) != prev_out.script_pubkey {
+            return Err(String::from(
+                "Witness script does not match the spent output",
+            ));
+        }
+        let public_keys = script_public_keys(&script);
+        if public_keys.is_empty() || generate_signature_only_script(&public_keys) != script {
+            return Err(String::from(
+                "Witness script does not only check signatures",
+            ));
+        }
+        public_keys
+    };
+    if stack.len() != public_keys.len() {
+        return Err(format!(
+            "Expected {} signatures, found {}",
+            public_keys.len(),
+            stack.len()
+        ));
+    }
+
+    let secp = Secp256k1::verification_only();
+    let mut sighash_cache = SighashCache::new(tx);
+    for (signature, public_key) in stack.iter().zip(public_keys.iter().rev()) {
+        let signature = ecdsa::Signature::from_slice(signature)
+            .map_err(|err| format!("Invalid signature: {}", err))?;
+        let sighash = if prev_out.script_pubkey.is_p2wpkh() {
+            sighash_cache
+                .p2wpkh_signature_hash(
+                    input_index,
+                    &prev_out.script_pubkey,
+                    prev_out.value,
+                    signature.sighash_type,
+                )
+                .map_err(|err| err.to_string())?
+        } else {
+            sighash_cache
+                .p2wsh_signature_hash(input_index, &script, prev_out.value, signature.sighash_type)
+                .map_err(|err| err.to_string())?
+        };
+        secp.verify_ecdsa(
+            &Message::from(sighash),
+            &signature.signature,
+            &public_key.inner,
+        )
+        .map_err(|_| format!("Invalid signature of key {}", public_key))?;
+    }
+
+    Ok(())
+}
+
+// `<key 1> OP_CHECKSIGVERIFY … <key n> OP_CHECKSIG`
+fn generate_signature_only_script(public_keys: &[PublicKey]) -> ScriptBuf {
+    let mut builder = Builder::new();
+    for (index, public_key) in public_keys.iter().enumerate() {
+        builder = builder.push_key(public_key);
+        builder = match index + 1 < public_keys.len() {
+            true => builder.push_opcode(OP_CHECKSIGVERIFY),
+            false => builder.push_opcode(OP_CHECKSIG),
+        };
+    }
+    builder.into_script()
+}
+
+fn key_position(script: &ScriptBuf, key: &[u8]) -> Result<usize, String> {
+    script
+        .as_bytes()
+        .windows(key.len())
+        .position(|window| window == key)
+        .ok_or_else(|| String::from("Signature key does not appear in the spent script"))
+}
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::{
+        absolute,
+        bip32::{DerivationPath, Fingerprint},
+        hashes::Hash,
+        psbt::PsbtSighashType,
+        sighash::Prevouts,
+        taproot::{LeafVersion, TaprootBuilder, TaprootSpendInfo},
+        Amount, EcdsaSighashType, Network, OutPoint, ScriptBuf, Sequence, TapLeafHash,
+        TapSighashType, Transaction, TxIn, TxOut, Witness,
+    };
+    use std::{collections::HashMap, str::FromStr};
+
+    use super::{
+        super::{
+            super::{
+                contexts::{
+                    base::{generate_keys_from_secret, BaseContext},
+                    verifier::VerifierContext,
+                },
+                scripts::{
+                    generate_pay_to_pubkey_script, generate_pay_to_pubkey_script_address,
+                    generate_pay_to_pubkey_taproot_script,
+                },
+            },
+            pre_signed::PreSignedTransaction,
+            script_validation::ScriptValidationReport,
+            signing::{generate_p2wsh_signature, generate_taproot_leaf_signature},
+        },
+        PsbtTransaction,
+    };
+
+    const SECRET: &str = "ee0817eac0c13aa8ee2dd3256304041f09f0499d1089b56495310ae8093583e2";
+
+    struct TestTransaction {
+        tx: Transaction,
+        prev_outs: Vec<TxOut>,
+        prev_scripts: Vec<ScriptBuf>,
+        taproot_spend_info: Option<TaprootSpendInfo>,
+        sighash_type: PsbtSighashType,
+    }
+
+    impl PreSignedTransaction for TestTransaction {
+        fn tx(&self) -> &Transaction { &self.tx }
+
+        fn tx_mut(&mut self) -> &mut Transaction { &mut self.tx }
+
+        fn prev_outs(&self) -> &Vec<TxOut> { &self.prev_outs }
+
+        fn prev_scripts(&self) -> &Vec<ScriptBuf> { &self.prev_scripts }
+    }
+
+    impl PsbtTransaction for TestTransaction {
+        fn taproot_spend_info(&self, _: usize) -> Option<TaprootSpendInfo> {
+            self.taproot_spend_info.clone()
+        }
+
+        fn sighash_type(&self, _: usize) -> Option<PsbtSighashType> { Some(self.sighash_type) }
+    }
+
+    fn context() -> VerifierContext {
+        let (_, _, public_key) = generate_keys_from_secret(Network::Regtest, SECRET);
+        VerifierContext::new(Network::Regtest, SECRET, &vec![public_key])
+    }
+
+    fn unsigned_transaction(context: &VerifierContext) -> TestTransaction {
+        let taproot_public_key = context.verifier_keypair.x_only_public_key().0;
+        let script = generate_pay_to_pubkey_taproot_script(&taproot_public_key);
+        let taproot_spend_info = TaprootBuilder::new()
+            .add_leaf(0, script.clone())
+            .unwrap()
+            .finalize(context.secp(), taproot_public_key)
+            .unwrap();
+
+        TestTransaction {
+            tx: Transaction {
+                version: bitcoin::transaction::Version(2),
+                lock_time: absolute::LockTime::ZERO,
+                input: vec![TxIn {
+                    previous_output: OutPoint::null(),
+                    script_sig: ScriptBuf::new(),
+                    sequence: Sequence::MAX,
+                    witness: Witness::default(),
+                }],
+                output: vec![TxOut {
+                    value: Amount::from_sat(90_000),
+                    script_pubkey: ScriptBuf::new(),
+                }],
+            },
+            prev_outs: vec![TxOut {
+                value: Amount::from_sat(100_000),
+                script_pubkey: ScriptBuf::new_p2tr_tweaked(taproot_spend_info.output_key()),
+            }],
+            prev_scripts: vec![script],
+            taproot_spend_info: Some(taproot_spend_info),
+            sighash_type: TapSighashType::All.into(),
+        }
+    }
+
+    // Spends output 1 of `previous_transaction`, a `wsh(pk(KEY))` output of the verifier key.
+    fn unsigned_segwit_v0_transaction(context: &VerifierContext) -> (TestTransaction, Transaction) {
+        let script = generate_pay_to_pubkey_script(&context.verifier_public_key);
+        let prev_out = TxOut {
+            value: Amount::from_sat(100_000),
+            script_pubkey: generate_pay_to_pubkey_script_address(
+                context.network(),
+                &context.verifier_public_key,
+            )
+            .script_pubkey(),
+        };
+        let mut previous_transaction = unsigned_transaction(context).tx;
+        previous_transaction.output.push(prev_out.clone());
+
+        let mut transaction = unsigned_transaction(context);
+        transaction.tx.input[0].previous_output = OutPoint {
+            txid: previous_transaction.compute_txid(),
+            vout: 1,
+        };
+        transaction.prev_outs = vec![prev_out];
+        transaction.prev_scripts = vec![script];
+        transaction.taproot_spend_info = None;
+        transaction.sighash_type = EcdsaSighashType::All.into();
+
+        (transaction, previous_transaction)
+    }
+
+    #[test]
+    fn test_export_taproot_input() {
+        let context = context();
+        let transaction = unsigned_transaction(&context);
+        assert_eq!(
+            transaction.taproot_sighash_types(),
+            vec![(0, TapSighashType::All)]
+        );
+        let psbt = transaction.export_psbt(&[], &HashMap::new()).unwrap();
+
+        let input = &psbt.inputs[0];
+        assert_eq!(input.witness_utxo, Some(transaction.prev_outs[0].clone()));
+        assert_eq!(input.sighash_type, Some(TapSighashType::All.into()));
+        assert_eq!(
+            input.tap_internal_key,
+            transaction
+                .taproot_spend_info
+                .as_ref()
+                .map(|taproot_spend_info| taproot_spend_info.internal_key())
+        );
+        assert_eq!(input.tap_scripts.len(), 1);
+        assert_eq!(
+            input.tap_scripts.values().next().unwrap().0,
+            transaction.prev_scripts[0]
+        );
+        assert!(input.final_script_witness.is_none());
+        assert!(input.non_witness_utxo.is_none());
+
+        let (leaf_hashes, (fingerprint, derivation_path)) =
+            &input.tap_key_origins[&context.verifier_keypair.x_only_public_key().0];
+        assert_eq!(
+            leaf_hashes,
+            &vec![TapLeafHash::from_script(
+                &transaction.prev_scripts[0],
+                LeafVersion::TapScript
+            )]
+        );
+        assert_eq!(
+            fingerprint.as_bytes()[..],
+            context.verifier_public_key.pubkey_hash().as_byte_array()[..4]
+        );
+        assert!(derivation_path.is_master());
+    }
+
+    #[test]
+    fn test_export_segwit_v0_input() {
+        let context = context();
+        let (transaction, previous_transaction) = unsigned_segwit_v0_transaction(&context);
+        assert_eq!(
+            transaction.segwit_v0_previous_txids(),
+            vec![previous_transaction.compute_txid()]
+        );
+        assert!(transaction.taproot_sighash_types().is_empty());
+        assert!(transaction
+            .export_psbt(&[], &HashMap::new())
+            .is_err_and(|err| err.contains("missing")));
+
+        let key_source = (
+            Fingerprint::from([0xd3, 0x4d, 0xb3, 0x3f]),
+            DerivationPath::from_str("m/84'/1'/0'/0/7").unwrap(),
+        );
+        let psbt = transaction
+            .export_psbt(
+                &[previous_transaction.clone()],
+                &HashMap::from([(context.verifier_public_key, key_source.clone())]),
+            )
+            .unwrap();
+
+        let input = &psbt.inputs[0];
+        assert_eq!(input.non_witness_utxo, Some(previous_transaction));
+        assert_eq!(
+            input.witness_script,
+            Some(transaction.prev_scripts[0].clone())
+        );
+        assert_eq!(
+            input
+                .bip32_derivation
+                .get(&context.verifier_public_key.inner),
+            Some(&key_source)
+        );
+        assert!(input.tap_key_origins.is_empty());
+    }
+
+    #[test]
+    fn test_import_taproot_signature() {
+        let context = context();
+        let mut transaction = unsigned_transaction(&context);
+        let mut psbt = transaction.export_psbt(&[], &HashMap::new()).unwrap();
+
+        // Sign as an external signer would, from the PSBT alone.
+        let (script, _) = psbt.inputs[0].tap_scripts.values().next().unwrap().clone();
+        let leaf_hash = TapLeafHash::from_script(&script, LeafVersion::TapScript);
+        let prev_outs = vec![psbt.inputs[0].witness_utxo.clone().unwrap()];
+        let signature = generate_taproot_leaf_signature(
+            &context,
+            &mut psbt.unsigned_tx.clone(),
+            &Prevouts::All(&prev_outs),
+            0,
+            TapSighashType::All,
+            &script,
+            &context.verifier_keypair,
+        );
+        psbt.inputs[0].tap_script_sigs.insert(
+            (context.verifier_keypair.x_only_public_key().0, leaf_hash),
+            signature,
+        );
+
+        assert_eq!(transaction.import_psbt(&psbt), Ok(1));
+        let mut report = ScriptValidationReport::new();
+        report.validate_psbt_transaction("test", &transaction);
+        assert!(report.is_valid(), "{}", report);
+        assert_eq!(report.checked_inputs, 1);
+
+        // Signed inputs are exported final and not imported again.
+        let psbt = transaction.export_psbt(&[], &HashMap::new()).unwrap();
+        assert_eq!(
+            psbt.inputs[0].final_script_witness.as_ref(),
+            Some(&transaction.tx.input[0].witness)
+        );
+        assert_eq!(transaction.import_psbt(&psbt), Ok(0));
+    }
+
+    #[test]
+    fn test_import_segwit_v0_signature() {
+        let context = context();
+        let (mut transaction, previous_transaction) = unsigned_segwit_v0_transaction(&context);
+        let export = |transaction: &TestTransaction| {
+            transaction
+                .export_psbt(&[previous_transaction.clone()], &HashMap::new())
+                .unwrap()
+        };
+
+        // A signature over another transaction is rejected
+        let mut psbt = export(&transaction);
+        let mut other_tx = psbt.unsigned_tx.clone();
+        other_tx.output[0].value = Amount::from_sat(80_000);
+        let (script, value) = (
+            transaction.prev_scripts[0].clone(),
+            transaction.prev_outs[0].value,
+        );
+        let sign = |tx: &mut Transaction| {
+            generate_p2wsh_signature(
+                &context,
+                tx,
+                0,
+                EcdsaSighashType::All,
+                &script,
+                value,
+                &context.verifier_keypair,
+            )
+        };
+        let signature = sign(&mut other_tx);
+        psbt.inputs[0]
+            .partial_sigs
+            .insert(context.verifier_public_key, signature);
+        assert!(transaction
+            .import_psbt(&psbt)
+            .is_err_and(|err| err.contains("segwit v0 input 0")));
+        assert!(transaction.tx.input[0].witness.is_empty());
+
+        let mut psbt = export(&transaction);
+        let signature = sign(&mut psbt.unsigned_tx.clone());
+        psbt.inputs[0]
+            .partial_sigs
+            .insert(context.verifier_public_key, signature);
+        assert_eq!(transaction.import_psbt(&psbt), Ok(1));
+        assert_eq!(transaction.tx.input[0].witness.len(), 2);
+    }
+
+    #[test]
+    fn test_reject_invalid_final_witness() {
+        let context = context();
+        let mut transaction = unsigned_transaction(&context);
+        let mut psbt = transaction.export_psbt(&[], &HashMap::new()).unwrap();
+
+        // A signature over another transaction, finalized by the signer.
+        let (script, control_block) = {
+            let (control_block, (script, _)) = psbt.inputs[0].tap_scripts.iter().next().unwrap();
+            (script.clone(), control_block.clone())
+        };
+        let mut other_tx = psbt.unsigned_tx.clone();
+        other_tx.output[0].value = Amount::from_sat(80_000);
+        let prev_outs = vec![psbt.inputs[0].witness_utxo.clone().unwrap()];
+        let signature = generate_taproot_leaf_signature(
+            &context,
+            &mut other_tx,
+            &Prevouts::All(&prev_outs),
+            0,
+            TapSighashType::All,
+            &script,
+            &context.verifier_keypair,
+        );
+        let mut witness = Witness::new();
+        witness.push(signature.to_vec());
+        witness.push(script.to_bytes());
+        witness.push(control_block.serialize());
+        psbt.inputs[0].final_script_witness = Some(witness);
+
+        assert!(transaction
+            .import_psbt(&psbt)
+            .is_err_and(|err| err.contains("Rejecting invalid witnesses")));
+        assert!(transaction.tx.input[0].witness.is_empty());
+    }
+
+    #[test]
+    fn test_reject_psbt_of_other_transaction() {
+        let context = context();
+        let mut transaction = unsigned_transaction(&context);
+        let mut psbt = transaction.export_psbt(&[], &HashMap::new()).unwrap();
+        psbt.unsigned_tx.output[0].value = Amount::from_sat(80_000);
+
+        assert!(transaction
+            .import_psbt(&psbt)
+            .is_err_and(|err| err.contains("expected")));
+    }
+}
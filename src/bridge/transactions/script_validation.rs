@@ -0,0 +1,375 @@
+use bitcoin::{
+    key::Secp256k1,
+    taproot::{ControlBlock, LeafVersion, Signature, TAPROOT_ANNEX_PREFIX},
+    ScriptBuf, TapLeafHash, TapSighashType, Transaction, TxOut, Txid, XOnlyPublicKey,
+};
+use bitcoin_scriptexec::{Exec, ExecCtx, Options, TxTemplate};
+use core::fmt;
+
+use super::{pre_signed::PreSignedTransaction, psbt::PsbtTransaction};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InputValidationFailure {
+    pub transaction_name: String,
+    pub txid: Txid,
+    pub input_index: usize,
+    pub reason: String,
+}
+
+/// Outcome of executing the witnesses of a graph's transactions against the outputs they spend.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScriptValidationReport {
+    /// Inputs whose witness was executed successfully.
+    pub checked_inputs: usize,
+    /// Inputs without a witness yet, e.g. n-of-n inputs that are still being pre-signed.
+    pub unsigned_inputs: usize,
+    /// Signed inputs that cannot be executed here: non-taproot spends and inputs added outside
+    /// of the graph, which have no prev out to check against.
+    pub skipped_inputs: usize,
+    pub failures: Vec<InputValidationFailure>,
+}
+
+impl ScriptValidationReport {
+    pub fn new() -> Self { Self::default() }
+
+    pub fn is_valid(&self) -> bool { self.failures.is_empty() }
+
+    /// Executes every signed input of `transaction`. `signed_inputs` lists the inputs the graph
+    /// participants sign together with the sighash type they must be signed with; the leaf script
+    /// spent by those inputs must also be the one recorded in the transaction's `prev_scripts`.
+    pub fn validate_transaction<T: PreSignedTransaction + ?Sized>(
+        &mut self,
+        transaction_name: &str,
+        transaction: &T,
+        signed_inputs: &[(usize, TapSighashType)],
+    ) {
+        let tx = transaction.tx();
+        for input_index in 0..tx.input.len() {
+            if tx.input[input_index].witness.is_empty() {
+                self.unsigned_inputs += 1;
+                continue;
+            }
+            if input_index >= transaction.prev_outs().len() {
+                self.skipped_inputs += 1;
+                continue;
+            }
+
+            let expected_sighash_type = signed_inputs
+                .iter()
+                .find(|(index, _)| *index == input_index)
+                .map(|(_, sighash_type)| *sighash_type);
+            let expected_script = match expected_sighash_type {
+                Some(_) => transaction.prev_scripts().get(input_index),
+                None => None,
+            };
+
+            match validate_input(
+                tx,
+                transaction.prev_outs(),
+                input_index,
+                expected_script,
+                expected_sighash_type,
+            ) {
+                Ok(true) => self.checked_inputs += 1,
+                Ok(false) => self.skipped_inputs += 1,
+                Err(reason) => self.failures.push(InputValidationFailure {
+                    transaction_name: transaction_name.to_string(),
+                    txid: tx.compute_txid(),
+                    input_index,
+                    reason,
+                }),
+            }
+        }
+    }
+
+    /// Executes every signed input of `transaction`, expecting the signed inputs and sighash types
+    /// that `PsbtTransaction::sighash_type` lists for it.
+    pub fn validate_psbt_transaction<T: PsbtTransaction + ?Sized>(
+        &mut self,
+        transaction_name: &str,
+        transaction: &T,
+    ) {
+        self.validate_transaction(
+            transaction_name,
+            transaction,
+            &transaction.taproot_sighash_types(),
+        );
+    }
+}
+
+impl fmt::Display for ScriptValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Script validation: {} inputs executed, {} unsigned, {} skipped, {} failed",
+            self.checked_inputs,
+            self.unsigned_inputs,
+            self.skipped_inputs,
+            self.failures.len()
+        )?;
+        for failure in &self.failures {
+            writeln!(
+                f,
+                "  {} transaction {} input {}: {}",
+                failure.transaction_name, failure.txid, failure.input_index, failure.reason
+            )?;
+        }
+        Ok(())
+    }
+}
+
+// Returns whether the witness was executed. Only taproot script path spends are executed, which
+// covers every input the graph participants sign.
+fn validate_input(
+    tx: &Transaction,
+    prev_outs: &[TxOut],
+    input_index: usize,
+    expected_script: Option<&ScriptBuf>,
+    expected_sighash_type: Option<TapSighashType>,
+) -> Result<bool, String> {
+    let script_pubkey = &prev_outs[input_index].script_pubkey;
+    if !script_pubkey.is_p2tr() {
+        if expected_sighash_type.is_some() {
+            return Err(String::from("Signed input does not spend a taproot output"));
+        }
+        return Ok(false);
+    }
+    let output_key = XOnlyPublicKey::from_slice(&script_pubkey.as_bytes()[2..34])
+        .map_err(|err| format!("Invalid taproot output key: {}", err))?;
+
+    let mut stack = tx.input[input_index].witness.to_vec();
+    if stack.len() >= 2 && stack.last().unwrap().first() == Some(&TAPROOT_ANNEX_PREFIX) {
+        return Err(String::from("Unexpected taproot annex in witness"));
+    }
+    if stack.len() < 2 {
+        if expected_sighash_type.is_some() {
+            return Err(String::from(
+                "Signed input must spend a script leaf, found a key path spend",
+            ));
+        }
+        return Ok(false);
+    }
+
+    let control_block = ControlBlock::decode(&stack.pop().unwrap())
+        .map_err(|err| format!("Invalid control block: {}", err))?;
+    let leaf_script = ScriptBuf::from_bytes(stack.pop().unwrap());
+    if control_block.leaf_version != LeafVersion::TapScript {
+        return Err(format!(
+            "Unsupported leaf version: {}",
+            control_block.leaf_version
+        ));
+    }
+    if let Some(expected_script) = expected_script {
+        if leaf_script != *expected_script {
+            return Err(String::from(
+                "Leaf script in witness does not match the expected leaf script",
+            ));
+        }
+    }
+    if !control_block.verify_taproot_commitment(
+        &Secp256k1::verification_only(),
+        output_key,
+        &leaf_script,
+    ) {
+        return Err(String::from(
+            "Control block does not commit the leaf script to the spent output key",
+        ));
+    }
+
+    if let Some(expected_sighash_type) = expected_sighash_type {
+        let signature = match stack.first() {
+            Some(signature) => Signature::from_slice(signature)
+                .map_err(|err| format!("Invalid signature: {}", err))?,
+            None => return Err(String::from("Missing signature in witness")),
+        };
+        if !is_expected_sighash_type(signature.sighash_type, expected_sighash_type) {
+            return Err(format!(
+                "Signature sighash type {:?} does not match the expected sighash type {:?}",
+                signature.sighash_type, expected_sighash_type
+            ));
+        }
+    }
+
+    let leaf_hash = TapLeafHash::from_script(&leaf_script, LeafVersion::TapScript);
+    let mut exec = Exec::new(
+        ExecCtx::Tapscript,
+        Options::default(),
+        TxTemplate {
+            tx: tx.clone(),
+            prevouts: prev_outs.to_vec(),
+            input_idx: input_index,
+            taproot_annex_scriptleaf: Some((leaf_hash, None)),
+        },
+        leaf_script,
+        stack,
+    )
+    .map_err(|err| format!("Failed to set up script execution: {:?}", err))?;
+
+    loop {
+        if exec.exec_next().is_err() {
+            break;
+        }
+    }
+    let res = exec.result().unwrap();
+    if !res.success {
+        return Err(format!(
+            "Script execution failed at {:?}: {:?}",
+            res.opcode, res.error
+        ));
+    }
+
+    Ok(true)
+}
+
+// A 64 byte signature commits to the default sighash type, which signs the same data as ALL.
+fn is_expected_sighash_type(sighash_type: TapSighashType, expected: TapSighashType) -> bool {
+    sighash_type == expected
+        || (expected == TapSighashType::All && sighash_type == TapSighashType::Default)
+}
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::{
+        absolute, taproot::TaprootBuilder, Amount, Network, OutPoint, ScriptBuf, Sequence,
+        TapSighashType, Transaction, TxIn, TxOut, Witness,
+    };
+
+    use super::{
+        super::{
+            super::{
+                contexts::{
+                    base::{generate_keys_from_secret, BaseContext},
+                    verifier::VerifierContext,
+                },
+                scripts::generate_pay_to_pubkey_taproot_script,
+            },
+            signing::populate_taproot_input_witness,
+        },
+        PreSignedTransaction, ScriptValidationReport,
+    };
+
+    const SECRET: &str = "ee0817eac0c13aa8ee2dd3256304041f09f0499d1089b56495310ae8093583e2";
+
+    struct TestTransaction {
+        tx: Transaction,
+        prev_outs: Vec<TxOut>,
+        prev_scripts: Vec<ScriptBuf>,
+    }
+
+    impl PreSignedTransaction for TestTransaction {
+        fn tx(&self) -> &Transaction { &self.tx }
+
+        fn tx_mut(&mut self) -> &mut Transaction { &mut self.tx }
+
+        fn prev_outs(&self) -> &Vec<TxOut> { &self.prev_outs }
+
+        fn prev_scripts(&self) -> &Vec<ScriptBuf> { &self.prev_scripts }
+    }
+
+    fn signed_transaction(sighash_type: TapSighashType) -> TestTransaction {
+        let (_, _, public_key) = generate_keys_from_secret(Network::Regtest, SECRET);
+        let context = VerifierContext::new(Network::Regtest, SECRET, &vec![public_key]);
+        let taproot_public_key = context.verifier_keypair.x_only_public_key().0;
+
+        let script = generate_pay_to_pubkey_taproot_script(&taproot_public_key);
+        let taproot_spend_info = TaprootBuilder::new()
+            .add_leaf(0, script.clone())
+            .unwrap()
+            .finalize(context.secp(), taproot_public_key)
+            .unwrap();
+        let prev_out = TxOut {
+            value: Amount::from_sat(100_000),
+            script_pubkey: ScriptBuf::new_p2tr_tweaked(taproot_spend_info.output_key()),
+        };
+
+        let mut tx = Transaction {
+            version: bitcoin::transaction::Version(2),
+            lock_time: absolute::LockTime::ZERO,
+            input: vec![TxIn {
+                previous_output: OutPoint::null(),
+                script_sig: ScriptBuf::new(),
+                sequence: Sequence::MAX,
+                witness: Witness::default(),
+            }],
+            output: vec![TxOut {
+                value: Amount::from_sat(90_000),
+                script_pubkey: ScriptBuf::new(),
+            }],
+        };
+        populate_taproot_input_witness(
+            &context,
+            &mut tx,
+            &vec![prev_out.clone()],
+            0,
+            sighash_type,
+            &taproot_spend_info,
+            &script,
+            &vec![&context.verifier_keypair],
+        );
+
+        TestTransaction {
+            tx,
+            prev_outs: vec![prev_out],
+            prev_scripts: vec![script],
+        }
+    }
+
+    #[test]
+    fn test_valid_presigned_input() {
+        let transaction = signed_transaction(TapSighashType::All);
+        let mut report = ScriptValidationReport::new();
+        report.validate_transaction("test", &transaction, &[(0, TapSighashType::All)]);
+
+        assert!(report.is_valid(), "{}", report);
+        assert_eq!(report.checked_inputs, 1);
+    }
+
+    #[test]
+    fn test_unexpected_sighash_type() {
+        let transaction = signed_transaction(TapSighashType::SinglePlusAnyoneCanPay);
+        let mut report = ScriptValidationReport::new();
+        report.validate_transaction("test", &transaction, &[(0, TapSighashType::All)]);
+
+        assert!(!report.is_valid());
+        assert_eq!(report.failures[0].input_index, 0);
+        assert!(report.failures[0].reason.contains("sighash type"));
+    }
+
+    #[test]
+    fn test_invalid_signature() {
+        let mut transaction = signed_transaction(TapSighashType::All);
+        // Changing an output invalidates the signature over ALL.
+        transaction.tx.output[0].value = Amount::from_sat(80_000);
+        let mut report = ScriptValidationReport::new();
+        report.validate_transaction("test", &transaction, &[(0, TapSighashType::All)]);
+
+        assert!(!report.is_valid());
+        assert!(report.failures[0]
+            .reason
+            .contains("Script execution failed"));
+    }
+
+    #[test]
+    fn test_tampered_control_block() {
+        let mut transaction = signed_transaction(TapSighashType::All);
+        let mut witness = transaction.tx.input[0].witness.to_vec();
+        let control_block = witness.last_mut().unwrap();
+        control_block[1] ^= 1;
+        transaction.tx.input[0].witness = Witness::from_slice(&witness);
+        let mut report = ScriptValidationReport::new();
+        report.validate_transaction("test", &transaction, &[(0, TapSighashType::All)]);
+
+        assert!(!report.is_valid());
+    }
+
+    #[test]
+    fn test_unsigned_input() {
+        let mut transaction = signed_transaction(TapSighashType::All);
+        transaction.tx.input[0].witness = Witness::default();
+        let mut report = ScriptValidationReport::new();
+        report.validate_transaction("test", &transaction, &[(0, TapSighashType::All)]);
+
+        assert!(report.is_valid());
+        assert_eq!(report.unsigned_inputs, 1);
+    }
+}
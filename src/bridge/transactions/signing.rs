@@ -1,5 +1,5 @@
 use bitcoin::{
-    key::Keypair,
+    key::{Keypair, TapTweak},
     secp256k1::Message,
     sighash::{Prevouts, SighashCache},
     taproot::{LeafVersion, TaprootSpendInfo},
@@ -282,3 +282,31 @@ pub fn populate_taproot_input_witness(
         script,
     );
 }
+
+// Signs a key path spend of a taproot output without script tree, as created for `tr(KEY)`
+// descriptors.
+pub fn populate_taproot_key_spend_witness(
+    context: &dyn BaseContext,
+    tx: &mut Transaction,
+    prevouts: &Vec<TxOut>,
+    input_index: usize,
+    sighash_type: TapSighashType,
+    keypair: &Keypair,
+) {
+    let sighash = SighashCache::new(&*tx)
+        .taproot_key_spend_signature_hash(input_index, &Prevouts::All(prevouts), sighash_type)
+        .expect("Failed to construct sighash");
+
+    let tweaked_keypair = keypair.tap_tweak(context.secp(), None);
+    let signature = context
+        .secp()
+        .sign_schnorr_no_aux_rand(&Message::from(sighash), &tweaked_keypair.to_inner());
+
+    tx.input[input_index].witness.push(
+        bitcoin::taproot::Signature {
+            signature,
+            sighash_type,
+        }
+        .to_vec(),
+    );
+}
@@ -1,6 +1,6 @@
 use bitcoin::{
-    absolute, consensus, Amount, Network, PublicKey, ScriptBuf, TapSighashType, Transaction, TxOut,
-    XOnlyPublicKey,
+    absolute, consensus, psbt::PsbtSighashType, taproot::TaprootSpendInfo, Amount, Network,
+    PublicKey, ScriptBuf, TapSighashType, Transaction, TxOut, XOnlyPublicKey,
 };
 use musig2::{secp256k1::schnorr::Signature, PartialSignature, PubNonce, SecNonce};
 use serde::{Deserialize, Serialize};
@@ -12,10 +12,12 @@ use super::{
         contexts::{base::BaseContext, operator::OperatorContext, verifier::VerifierContext},
         graphs::base::FEE_AMOUNT,
         scripts::*,
+        timelocks::TimelockConfig,
     },
     base::*,
     pre_signed::*,
     pre_signed_musig2::*,
+    psbt::*,
 };
 
 #[derive(Serialize, Deserialize, Eq, PartialEq, Clone)]
@@ -44,6 +46,24 @@ impl PreSignedTransaction for StartTimeTimeoutTransaction {
     fn prev_scripts(&self) -> &Vec<ScriptBuf> { &self.prev_scripts }
 }
 
+impl PsbtTransaction for StartTimeTimeoutTransaction {
+    fn taproot_spend_info(&self, input_index: usize) -> Option<TaprootSpendInfo> {
+        match input_index {
+            0 => Some(self.connector_2.generate_taproot_spend_info()),
+            1 => Some(self.connector_1.generate_taproot_spend_info()),
+            _ => None,
+        }
+    }
+
+    fn sighash_type(&self, input_index: usize) -> Option<PsbtSighashType> {
+        match input_index {
+            0 => Some(TapSighashType::Single.into()),
+            1 => Some(TapSighashType::None.into()),
+            _ => None,
+        }
+    }
+}
+
 impl PreSignedMusig2Transaction for StartTimeTimeoutTransaction {
     fn musig2_nonces(&self) -> &HashMap<usize, HashMap<PublicKey, PubNonce>> { &self.musig2_nonces }
     fn musig2_nonces_mut(&mut self) -> &mut HashMap<usize, HashMap<PublicKey, PubNonce>> {
@@ -68,13 +88,19 @@ impl PreSignedMusig2Transaction for StartTimeTimeoutTransaction {
 }
 
 impl StartTimeTimeoutTransaction {
-    pub fn new(context: &OperatorContext, input_0: Input, input_1: Input) -> Self {
+    pub fn new(
+        context: &OperatorContext,
+        input_0: Input,
+        input_1: Input,
+        timelock_config: &TimelockConfig,
+    ) -> Self {
         Self::new_for_validation(
             context.network,
             &context.operator_taproot_public_key,
             &context.n_of_n_taproot_public_key,
             input_0,
             input_1,
+            timelock_config,
         )
     }
 
@@ -84,11 +110,13 @@ impl StartTimeTimeoutTransaction {
         n_of_n_taproot_public_key: &XOnlyPublicKey,
         input_0: Input,
         input_1: Input,
+        timelock_config: &TimelockConfig,
     ) -> Self {
         let connector_1 = Connector1::new(
             network,
             operator_taproot_public_key,
             n_of_n_taproot_public_key,
+            timelock_config,
         );
         let connector_2 = Connector2::new(
             network,
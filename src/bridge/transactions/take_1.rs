@@ -1,6 +1,7 @@
 use bitcoin::{
-    absolute, consensus, Amount, EcdsaSighashType, Network, PublicKey, ScriptBuf, TapSighashType,
-    Transaction, TxOut, XOnlyPublicKey,
+    absolute, consensus, psbt::PsbtSighashType, taproot::TaprootSpendInfo, Amount,
+    EcdsaSighashType, Network, PublicKey, ScriptBuf, TapSighashType, Transaction, TxOut,
+    XOnlyPublicKey,
 };
 use musig2::{secp256k1::schnorr::Signature, PartialSignature, PubNonce, SecNonce};
 use serde::{Deserialize, Serialize};
@@ -15,10 +16,12 @@ use super::{
         contexts::{base::BaseContext, operator::OperatorContext, verifier::VerifierContext},
         graphs::base::FEE_AMOUNT,
         scripts::*,
+        timelocks::TimelockConfig,
     },
     base::*,
     pre_signed::*,
     pre_signed_musig2::*,
+    psbt::*,
 };
 
 #[derive(Serialize, Deserialize, Eq, PartialEq, Clone)]
@@ -48,6 +51,25 @@ impl PreSignedTransaction for Take1Transaction {
     fn prev_scripts(&self) -> &Vec<ScriptBuf> { &self.prev_scripts }
 }
 
+impl PsbtTransaction for Take1Transaction {
+    fn taproot_spend_info(&self, input_index: usize) -> Option<TaprootSpendInfo> {
+        match input_index {
+            0 => Some(self.connector_0.generate_taproot_spend_info()),
+            1 => Some(self.connector_a.generate_taproot_spend_info()),
+            3 => Some(self.connector_b.generate_taproot_spend_info()),
+            _ => None,
+        }
+    }
+
+    fn sighash_type(&self, input_index: usize) -> Option<PsbtSighashType> {
+        match input_index {
+            0 | 1 | 3 => Some(TapSighashType::All.into()),
+            2 => Some(EcdsaSighashType::All.into()),
+            _ => None,
+        }
+    }
+}
+
 impl PreSignedMusig2Transaction for Take1Transaction {
     fn musig2_nonces(&self) -> &HashMap<usize, HashMap<PublicKey, PubNonce>> { &self.musig2_nonces }
     fn musig2_nonces_mut(&mut self) -> &mut HashMap<usize, HashMap<PublicKey, PubNonce>> {
@@ -78,6 +100,7 @@ impl Take1Transaction {
         input_1: Input,
         input_2: Input,
         input_3: Input,
+        timelock_config: &TimelockConfig,
     ) -> Self {
         let mut this = Self::new_for_validation(
             context.network,
@@ -88,6 +111,7 @@ impl Take1Transaction {
             input_1,
             input_2,
             input_3,
+            timelock_config,
         );
 
         this.sign_input_1(context);
@@ -105,15 +129,16 @@ impl Take1Transaction {
         input_1: Input,
         input_2: Input,
         input_3: Input,
+        timelock_config: &TimelockConfig,
     ) -> Self {
         let connector_0 = Connector0::new(network, n_of_n_taproot_public_key);
-        let connector_3 = Connector3::new(network, operator_public_key);
+        let connector_3 = Connector3::new(network, operator_public_key, timelock_config);
         let connector_a = ConnectorA::new(
             network,
             operator_taproot_public_key,
             n_of_n_taproot_public_key,
         );
-        let connector_b = ConnectorB::new(network, n_of_n_taproot_public_key);
+        let connector_b = ConnectorB::new(network, n_of_n_taproot_public_key, timelock_config);
 
         let input_0_leaf = 0;
         let _input_0 = connector_0.generate_taproot_leaf_tx_in(input_0_leaf, &input_0);
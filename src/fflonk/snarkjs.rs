@@ -0,0 +1,146 @@
+// Loaders for the JSON files snarkjs writes for fflonk circuits, see `circom_ref/` for examples.
+use crate::fflonk::verifier::{Proof, VerifyingKey};
+pub use crate::groth16::snarkjs::load_public_inputs;
+use crate::groth16::snarkjs::{check_header, parse_field, parse_g1, parse_g2};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+#[derive(Deserialize)]
+struct SnarkjsVerifyingKey {
+    protocol: String,
+    curve: String,
+    #[serde(rename = "nPublic")]
+    n_public: usize,
+    power: u32,
+    k1: String,
+    k2: String,
+    w: String,
+    w3: String,
+    w4: String,
+    w8: String,
+    wr: String,
+    #[serde(rename = "X_2")]
+    x_2: Vec<Vec<String>>,
+    #[serde(rename = "C0")]
+    c0: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct SnarkjsProof {
+    protocol: String,
+    curve: String,
+    polynomials: HashMap<String, Vec<String>>,
+    evaluations: HashMap<String, String>,
+}
+
+/// Loads an fflonk `verification_key.json` written by snarkjs.
+pub fn load_verifying_key(json: &str) -> Result<VerifyingKey, String> {
+    let vk: SnarkjsVerifyingKey = serde_json::from_str(json)
+        .map_err(|err| format!("Invalid snarkjs verifying key: {}", err))?;
+    check_header(&vk.protocol, &vk.curve, "fflonk")?;
+
+    Ok(VerifyingKey {
+        n_public: vk.n_public,
+        power: vk.power,
+        k1: parse_field(&vk.k1)?,
+        k2: parse_field(&vk.k2)?,
+        w: parse_field(&vk.w)?,
+        w3: parse_field(&vk.w3)?,
+        w4: parse_field(&vk.w4)?,
+        w8: parse_field(&vk.w8)?,
+        wr: parse_field(&vk.wr)?,
+        x_2: parse_g2(&vk.x_2)?,
+        c0: parse_g1(&vk.c0)?,
+    })
+}
+
+/// Loads an fflonk `proof.json` written by snarkjs.
+pub fn load_proof(json: &str) -> Result<Proof, String> {
+    let proof: SnarkjsProof =
+        serde_json::from_str(json).map_err(|err| format!("Invalid snarkjs proof: {}", err))?;
+    check_header(&proof.protocol, &proof.curve, "fflonk")?;
+
+    let polynomial = |name: &str| match proof.polynomials.get(name) {
+        Some(point) => parse_g1(point),
+        None => Err(format!("Missing polynomial commitment {}", name)),
+    };
+    let evaluation = |name: &str| match proof.evaluations.get(name) {
+        Some(value) => parse_field(value),
+        None => Err(format!("Missing evaluation {}", name)),
+    };
+
+    Ok(Proof {
+        c1: polynomial("C1")?,
+        c2: polynomial("C2")?,
+        w1: polynomial("W1")?,
+        w2: polynomial("W2")?,
+        ql: evaluation("ql")?,
+        qr: evaluation("qr")?,
+        qm: evaluation("qm")?,
+        qo: evaluation("qo")?,
+        qc: evaluation("qc")?,
+        s1: evaluation("s1")?,
+        s2: evaluation("s2")?,
+        s3: evaluation("s3")?,
+        a: evaluation("a")?,
+        b: evaluation("b")?,
+        c: evaluation("c")?,
+        z: evaluation("z")?,
+        zw: evaluation("zw")?,
+        t1w: evaluation("t1w")?,
+        t2w: evaluation("t2w")?,
+        inv: evaluation("inv")?,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_load_circom_ref() {
+        let fr = |s: &str| ark_bn254::Fr::from_str(s).unwrap();
+
+        let vk = load_verifying_key(include_str!("circom_ref/verification_key.json")).unwrap();
+        assert_eq!(vk.n_public, 2);
+        assert_eq!(vk.power, 18);
+        assert_eq!(vk.k1, fr("2"));
+        assert_eq!(vk.k2, fr("3"));
+        assert_eq!(
+            vk.w,
+            fr("11699596668367776675346610687704220591435078791727316319397053191800576917728")
+        );
+        assert_eq!(
+            vk.c0.x.to_string(),
+            "303039279492065453055049758769758984569666029850327527958551993331680103359"
+        );
+        assert_eq!(
+            vk.x_2.y.c1.to_string(),
+            "11507326595632554467052522095592665270651932854513688777769618397986436103170"
+        );
+
+        let proof = load_proof(include_str!("circom_ref/proof.json")).unwrap();
+        assert_eq!(
+            proof.w2.y.to_string(),
+            "8930092616903485317239646434389939466400752538134075201209141980838088395614"
+        );
+        assert_eq!(
+            proof.inv,
+            fr("21247383512588455895834686692756529012394058115069710447132959660051940541361")
+        );
+
+        let public_inputs = load_public_inputs(include_str!("circom_ref/public.json")).unwrap();
+        assert_eq!(public_inputs.len(), vk.n_public);
+
+        // Groth16 files aren't fflonk ones
+        assert!(
+            load_proof(&include_str!("circom_ref/proof.json").replace("fflonk", "groth16"))
+                .is_err()
+        );
+        assert!(
+            load_proof(&include_str!("circom_ref/proof.json").replace("\"inv\"", "\"int\""))
+                .is_err()
+        );
+    }
+}
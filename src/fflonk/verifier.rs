@@ -1,3 +1,2462 @@
+use crate::bn254::curves::{G1Affine, G1Projective};
+use crate::bn254::ell_coeffs::G2Prepared;
+use crate::bn254::fp254impl::Fp254Impl;
+use crate::bn254::fq::Fq;
+use crate::bn254::fq12::Fq12;
+use crate::bn254::fq2::Fq2;
+use crate::bn254::fr::Fr;
+use crate::bn254::pairing::Pairing;
+use crate::bn254::utils;
+use crate::groth16::offchain_checker::compute_c_wi;
+use crate::hash::blake3::blake3_var_length;
+use crate::treepp::*;
+use ark_bn254::Bn254;
+use ark_ec::pairing::Pairing as ArkPairing;
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::{AdditiveGroup, Field};
+use num_bigint::BigUint;
+use num_traits::Zero;
+use std::str::FromStr;
+
+/// Verifying key of a snarkjs fflonk circuit, as in `circom_ref/verification_key.json`.
+#[derive(Clone, Debug)]
+pub struct VerifyingKey {
+    pub n_public: usize,
+    pub power: u32,
+    pub k1: ark_bn254::Fr,
+    pub k2: ark_bn254::Fr,
+    pub w: ark_bn254::Fr,
+    pub w3: ark_bn254::Fr,
+    pub w4: ark_bn254::Fr,
+    pub w8: ark_bn254::Fr,
+    pub wr: ark_bn254::Fr,
+    pub x_2: ark_bn254::G2Affine,
+    pub c0: ark_bn254::G1Affine,
+}
+
+/// snarkjs fflonk proof, as in `circom_ref/proof.json`.
+#[derive(Clone, Debug)]
+pub struct Proof {
+    pub c1: ark_bn254::G1Affine,
+    pub c2: ark_bn254::G1Affine,
+    pub w1: ark_bn254::G1Affine,
+    pub w2: ark_bn254::G1Affine,
+    pub ql: ark_bn254::Fr,
+    pub qr: ark_bn254::Fr,
+    pub qm: ark_bn254::Fr,
+    pub qo: ark_bn254::Fr,
+    pub qc: ark_bn254::Fr,
+    pub s1: ark_bn254::Fr,
+    pub s2: ark_bn254::Fr,
+    pub s3: ark_bn254::Fr,
+    pub a: ark_bn254::Fr,
+    pub b: ark_bn254::Fr,
+    pub c: ark_bn254::Fr,
+    pub z: ark_bn254::Fr,
+    pub zw: ark_bn254::Fr,
+    pub t1w: ark_bn254::Fr,
+    pub t2w: ark_bn254::Fr,
+    pub inv: ark_bn254::Fr,
+}
+
+impl Proof {
+    // The evaluations in the order of the transcript and of the witness.
+    fn evaluations(&self) -> [ark_bn254::Fr; 15] {
+        [
+            self.ql, self.qr, self.qm, self.qo, self.qc, self.s1, self.s2, self.s3, self.a, self.b,
+            self.c, self.z, self.zw, self.t1w, self.t2w,
+        ]
+    }
+}
+
+// Number of field elements of C1, C2, W1, W2, the evaluations, inv and the xiseed hint in the
+// witness.
+const PROOF_LEN: u32 = 8 + 15 + 2;
+
+// Number of Fq elements of c, c_inv and wi in the witness.
+const HINTS_LEN: u32 = 12 * 3;
+
+#[derive(Clone, Copy, Debug)]
+pub struct Verifier;
+
+impl Verifier {
+    /// Largest number of public inputs. The transcript of beta, C0, C1 and the public inputs, is
+    /// hashed in script by `blake3_var_length` which takes at most 512 bytes, so
+    /// 64 + 32 * n_public <= 512.
+    pub const MAX_PUBLIC: usize = 14;
+
+    /// Script verifying any proof for `vk`, it only depends on the verifying key and can be
+    /// committed to before the proof exists. The challenges are derived in script with blake3,
+    /// the final exponentiation of the pairing check is replaced by hints. The public inputs, the
+    /// proof and the hints are provided by `Verifier::witness`.
+    ///
+    /// Fails for verifying keys with more than `Verifier::MAX_PUBLIC` public inputs.
+    ///
+    /// Input stack: [public_inputs, C1, C2, W1, W2, evaluations, inv, xiseed, c, c_inv, wi]
+    pub fn locking_script(vk: &VerifyingKey) -> Result<Script, String> {
+        Self::check_verifying_key(vk)?;
+        let n_public = vk.n_public as u32;
+        Ok(script! {
+            { check_witness(n_public) }
+
+            // move c, c_inv and wi out of the way
+            { Fq12::toaltstack() }
+            { Fq12::toaltstack() }
+            { Fq12::toaltstack() }
+
+            { Self::compute_a1(vk) }
+            // [public_inputs, proof, A1.x, A1.y]
+            { utils::from_eval_point_in_stack() }
+            { witness_element(n_public + 6) }
+            { witness_element(n_public + 7) }
+            { utils::from_eval_point_in_stack() }
+
+            // the public inputs and the proof aren't needed anymore
+            { Fq2::toaltstack() }
+            { Fq2::toaltstack() }
+            for _ in 0..n_public + PROOF_LEN {
+                { Fq::drop() }
+            }
+            { Fq2::fromaltstack() }
+            { Fq2::fromaltstack() }
+            { Fq12::fromaltstack() }
+            { Fq12::fromaltstack() }
+            { Fq12::fromaltstack() }
+            // [A1.x', A1.y', w2.x', w2.y', c, c_inv, wi]
+
+            // c_inv is the inverse of c and wi is 1, w or w^2
+            { Pairing::check_c_wi() }
+
+            // expected final_f, say c^{p^3}
+            { Fq12::copy(24) }
+            { Fq12::frobenius_map(3) }
+            { Fq12::toaltstack() }
+
+            // e(A1, [1]_2) * e(W2, -[x]_2) = 1
+            { Pairing::dual_miller_loop_with_c_wi(
+                &G2Prepared::from_affine(ark_bn254::G2Affine::generator()),
+                &G2Prepared::from_affine(-vk.x_2),
+                true,
+            ) }
+
+            // check final_f == c^{p^3}
+            { Fq12::fromaltstack() }
+            { Fq12::equalverify() }
+            OP_TRUE
+        })
+    }
+
+    /// Stack items satisfying `Verifier::locking_script(vk)` for the proof: the public inputs,
+    /// the proof, the xiseed of its transcript and the hints of the final exponentiation, first
+    /// item at the bottom.
+    pub fn witness(
+        vk: &VerifyingKey,
+        proof: &Proof,
+        public_inputs: &[ark_bn254::Fr],
+    ) -> Result<Vec<Vec<u8>>, String> {
+        let (xiseed, c, c_inv, wi) = Self::hints(vk, proof, public_inputs)?;
+        let fr_witness =
+            |element: ark_bn254::Fr| Fr::witness_u32_le(&BigUint::from(element).to_u32_digits());
+
+        Ok([
+            public_inputs
+                .iter()
+                .flat_map(|input| fr_witness(*input))
+                .collect(),
+            utils::g1_affine_witness(proof.c1),
+            utils::g1_affine_witness(proof.c2),
+            utils::g1_affine_witness(proof.w1),
+            utils::g1_affine_witness(proof.w2),
+            proof
+                .evaluations()
+                .into_iter()
+                .flat_map(fr_witness)
+                .collect(),
+            fr_witness(proof.inv),
+            fr_witness(xiseed),
+            utils::fq12_witness(c),
+            utils::fq12_witness(c_inv),
+            utils::fq12_witness(wi),
+        ]
+        .concat())
+    }
+
+    /// Locking script with the witness pushed in front of it.
+    pub fn verify_proof(
+        vk: &VerifyingKey,
+        proof: &Proof,
+        public_inputs: &[ark_bn254::Fr],
+    ) -> Result<Script, String> {
+        let (xiseed, c, c_inv, wi) = Self::hints(vk, proof, public_inputs)?;
+        let scalars = [&proof.evaluations()[..], &[proof.inv, xiseed]].concat();
+        let locking_script = Self::locking_script(vk)?;
+
+        Ok(script! {
+            for input in public_inputs {
+                { Fr::push_u32_le(&BigUint::from(*input).to_u32_digits()) }
+            }
+            for point in [proof.c1, proof.c2, proof.w1, proof.w2] {
+                { Fq::push_u32_le(&BigUint::from(point.x).to_u32_digits()) }
+                { Fq::push_u32_le(&BigUint::from(point.y).to_u32_digits()) }
+            }
+            for scalar in scalars {
+                { Fr::push_u32_le(&BigUint::from(scalar).to_u32_digits()) }
+            }
+            { utils::fq12_push(c) }
+            { utils::fq12_push(c_inv) }
+            { utils::fq12_push(wi) }
+            { locking_script }
+        })
+    }
+
+    // Computes A1 = F - E - J + y * W2 with the challenges of the proof in the witness.
+    //
+    // Input stack: [public_inputs, C1, C2, W1, W2, evaluations, inv, xiseed]
+    // Output stack: [public_inputs, C1, C2, W1, W2, evaluations, inv, xiseed, A1.x, A1.y]
+    fn compute_a1(vk: &VerifyingKey) -> Script {
+        let n_public = vk.n_public as u32;
+        let inputs = (0..n_public).map(witness_element).collect::<Vec<_>>();
+        let point = |i: u32| {
+            script! {
+                { witness_element(n_public + 2 * i) }
+                { witness_element(n_public + 2 * i + 1) }
+            }
+        };
+        let (c1, c2, w1, w2) = (point(0), point(1), point(2), point(3));
+        let [ql, qr, qm, qo, qc, s1, s2, s3, a, b, c, z, zw, t1w, t2w, inv, xiseed] =
+            std::array::from_fn(|i| witness_element(n_public + 8 + i as u32));
+
+        let (c0_x, c0_y) = (dec(vk.c0.x), dec(vk.c0.y));
+        let w8 = (1..8)
+            .map(|i| dec(vk.w8.pow([i as u64])))
+            .collect::<Vec<_>>();
+        let (w3, w3_2) = (dec(vk.w3), dec(vk.w3.square()));
+        let (w4, w4_2, w4_3) = (dec(vk.w4), dec(vk.w4.square()), dec(vk.w4.pow([3_u64])));
+        let (wr, w, k1, k2) = (dec(vk.wr), dec(vk.w), dec(vk.k1), dec(vk.k2));
+
+        let (hash_beta, hash_32, hash_64, hash_512) = (
+            blake3_var_length(64 + 32 * vk.n_public),
+            blake3_var_length(32),
+            blake3_var_length(64),
+            blake3_var_length(512),
+        );
+
+        script! {
+            { compute_challenges_beta(&hash_beta, &c0_x, &c0_y, &c1, &inputs) }
+            { compute_challenges_gamma(&hash_32) }
+            { compute_challenges_alpha(
+                &hash_512, &xiseed, &ql, &qr, &qm, &qo, &qc, &s1, &s2, &s3, &a, &b, &c, &z, &zw,
+                &t1w, &t2w,
+            ) }
+            { compute_challenges_y(&hash_64, &w1) }
+            { compute_challenges_xiseed(&hash_64, &c2) }
+            // [beta, gamma, alpha, y, xiseed]
+
+            // alpha is derived from the xiseed of the witness, which must be the one of the
+            // transcript
+            { Fr::copy(0) }
+            { xiseed }
+            { Fr::equalverify(1, 0) }
+
+            { compute_challenges_xin(
+                &w8[0], &w8[1], &w8[2], &w8[3], &w8[4], &w8[5], &w8[6], &w3, &w3_2, &w4, &w4_2,
+                &w4_3, &wr, vk.power,
+            ) }
+            { compute_inversions(&w, &inv, vk.power, vk.n_public) }
+            { compute_lagranges(&w, vk.n_public) }
+            { compute_pi(&inputs) }
+            { compute_r0(&ql, &qr, &qo, &qm, &qc, &s1, &s2, &s3) }
+            { compute_r1(&ql, &qr, &qm, &qo, &qc, &a, &b, &c) }
+            { compute_r2(&a, &b, &c, &z, &zw, &s1, &s2, &s3, &t1w, &t2w, &w, &k1, &k2) }
+            { compute_fej() }
+            { compute_f_opt(&c0_x, &c0_y, &c1, &c2) }
+
+            // save f
+            { Fq::toaltstack() }
+            { Fq::toaltstack() }
+            { Fq::toaltstack() }
+
+            // push the scalar
+            { Fr::copy(1) }
+            { Fr::toaltstack() } // [ | e_scalar]
+
+            // push g1
+            { Fq::push_dec("1") }
+            { Fq::push_dec("2") }
+            { Fq::push_dec("1") } // [-g1 | e_scalar]
+            { G1Projective::neg() }
+            { G1Projective::toaltstack() } // [ | -g1 e_scalar]
+
+            // push the scalar
+            { Fr::toaltstack() }
+            // push W1
+            { w1 }
+            { Fq::push_one() }
+            { G1Projective::neg() }
+            { G1Projective::toaltstack() } // [| -w1, w1_scalar, -g1, e_scalar]
+
+            { Fr::roll(3) }
+            { Fr::toaltstack() }
+
+            { w2 }
+            { Fq::push_one() }
+            { Fr::fromaltstack() } // [w2, w2_scalar(y) | -w1, w1_scalar, -g1, e_scalar ]
+
+            { Fr::fromaltstack() }
+            { G1Projective::fromaltstack() }
+            { Fr::fromaltstack() }
+            { G1Projective::fromaltstack() } // [w2, w2_scalar(y) -w1, w1_scalar, -g1, e_scalar ]
+
+            { G1Projective::batched_scalar_mul::<3>() } // W2 * y - (j + e)] | [ f ]
+            { G1Projective::fromaltstack() }
+            { G1Projective::add() } // A1 = w2 * y + f - (e + j)
+
+            // clear stack
+            { G1Projective::toaltstack() }
+            { Fr::drop() }
+            { Fr::drop() }
+            { Fr::drop() }
+            { G1Projective::fromaltstack() }
+
+            { G1Projective::into_affine() }
+        }
+    }
+
+    fn check_verifying_key(vk: &VerifyingKey) -> Result<(), String> {
+        if vk.n_public > Self::MAX_PUBLIC {
+            return Err(format!(
+                "Expected at most {} public inputs, got {}",
+                Self::MAX_PUBLIC,
+                vk.n_public
+            ));
+        }
+        if vk.power >= 32 {
+            return Err(format!("Unsupported domain size 2^{}", vk.power));
+        }
+        Ok(())
+    }
+
+    // The xiseed of the transcript and the hints for verifying the final exponentiation: c,
+    // c_inv and wi with c^lambda = f * wi.
+    fn hints(
+        vk: &VerifyingKey,
+        proof: &Proof,
+        public_inputs: &[ark_bn254::Fr],
+    ) -> Result<
+        (
+            ark_bn254::Fr,
+            ark_bn254::Fq12,
+            ark_bn254::Fq12,
+            ark_bn254::Fq12,
+        ),
+        String,
+    > {
+        Self::check_verifying_key(vk)?;
+        if public_inputs.len() != vk.n_public {
+            return Err(format!(
+                "Expected {} public inputs, got {}",
+                vk.n_public,
+                public_inputs.len()
+            ));
+        }
+
+        let challenges = Challenges::new(vk, proof, public_inputs);
+        let a1 = compute_a1_native(vk, proof, public_inputs, &challenges)?;
+
+        let (p, q) = ([a1, proof.w2], [ark_bn254::G2Affine::generator(), -vk.x_2]);
+        if !Bn254::multi_pairing(p, q).is_zero() {
+            return Err("fflonk proof isn't valid".to_string());
+        }
+
+        let f = Bn254::multi_miller_loop_affine(p, q).0;
+        let (c, wi) = compute_c_wi(f)?;
+
+        Ok((challenges.xiseed, c, c.inverse().unwrap(), wi))
+    }
+}
+
+// Challenges of the transcript, as derived by `compute_challenges_*` in script.
+struct Challenges {
+    beta: ark_bn254::Fr,
+    gamma: ark_bn254::Fr,
+    xiseed: ark_bn254::Fr,
+    alpha: ark_bn254::Fr,
+    y: ark_bn254::Fr,
+}
+
+impl Challenges {
+    fn new(vk: &VerifyingKey, proof: &Proof, public_inputs: &[ark_bn254::Fr]) -> Self {
+        let beta = utils::hash_to_fr(
+            &[
+                utils::g1_compressed_bytes(vk.c0),
+                public_inputs
+                    .iter()
+                    .flat_map(|input| utils::fr_be_bytes(*input))
+                    .collect(),
+                utils::g1_compressed_bytes(proof.c1),
+            ]
+            .concat(),
+        );
+        let gamma = utils::hash_to_fr(&utils::fr_be_bytes(beta));
+        let xiseed = utils::hash_to_fr(
+            &[
+                utils::fr_be_bytes(gamma),
+                utils::g1_compressed_bytes(proof.c2),
+            ]
+            .concat(),
+        );
+        let alpha = utils::hash_to_fr(
+            &[xiseed]
+                .into_iter()
+                .chain(proof.evaluations())
+                .flat_map(utils::fr_be_bytes)
+                .collect::<Vec<_>>(),
+        );
+        let y = utils::hash_to_fr(
+            &[
+                utils::fr_be_bytes(alpha),
+                utils::g1_compressed_bytes(proof.w1),
+            ]
+            .concat(),
+        );
+
+        Self {
+            beta,
+            gamma,
+            xiseed,
+            alpha,
+            y,
+        }
+    }
+}
+
+// A1 = F - E - J + y * W2 as computed by `Verifier::compute_a1`, following the verifier of
+// snarkjs. Fails if `proof.inv` isn't the inverse of the denominators the script inverts.
+fn compute_a1_native(
+    vk: &VerifyingKey,
+    proof: &Proof,
+    public_inputs: &[ark_bn254::Fr],
+    challenges: &Challenges,
+) -> Result<ark_bn254::G1Affine, String> {
+    let Challenges {
+        beta,
+        gamma,
+        xiseed,
+        alpha,
+        y,
+    } = *challenges;
+    let roots = |first: ark_bn254::Fr, root: ark_bn254::Fr, len: usize| {
+        (0..len)
+            .map(|i| first * root.pow([i as u64]))
+            .collect::<Vec<_>>()
+    };
+    let h0 = roots(xiseed.pow([3_u64]), vk.w8, 8);
+    let h1 = roots(xiseed.pow([6_u64]), vk.w4, 4);
+    let h2 = roots(xiseed.pow([8_u64]), vk.w3, 3);
+    let h3 = roots(xiseed.pow([8_u64]) * vk.wr, vk.w3, 3);
+    let xi = xiseed.pow([24_u64]);
+    let xiw = xi * vk.w;
+    let n = ark_bn254::Fr::from(1_u64 << vk.power);
+    let zh = xi.pow([1_u64 << vk.power]) - ark_bn254::Fr::ONE;
+
+    let mul_h0 = h0.iter().map(|h| y - h).product::<ark_bn254::Fr>();
+    let mul_h1 = h1.iter().map(|h| y - h).product::<ark_bn254::Fr>();
+    let mul_h2 = h2
+        .iter()
+        .chain(&h3)
+        .map(|h| y - h)
+        .product::<ark_bn254::Fr>();
+
+    // denominators of the Lagrange polynomials at y over the roots, computeLiS0 and computeLiS1
+    // of snarkjs for h0 and h1, computeLiS2 for h2 and h3
+    let lis_dens = |roots: &[ark_bn254::Fr], factor: ark_bn254::Fr| {
+        let len = roots.len();
+        (0..len)
+            .map(|i| factor * roots[(len - 1) * i % len] * (y - roots[i]))
+            .collect::<Vec<_>>()
+    };
+    let lis0_dens = lis_dens(&h0, ark_bn254::Fr::from(8_u64) * h0[0].pow([6_u64]));
+    let lis1_dens = lis_dens(&h1, ark_bn254::Fr::from(4_u64) * h1[0].square());
+    let lis2_dens = [
+        lis_dens(&h2, ark_bn254::Fr::from(3_u64) * h2[0] * (xi - xiw)),
+        lis_dens(&h3, ark_bn254::Fr::from(3_u64) * h3[0] * (xiw - xi)),
+    ]
+    .concat();
+    let w_powers = roots(ark_bn254::Fr::ONE, vk.w, public_inputs.len().max(1));
+    let li_dens = w_powers
+        .iter()
+        .map(|w_i| n * (xi - w_i))
+        .collect::<Vec<_>>();
+
+    let denominators = [zh, mul_h1, mul_h2]
+        .iter()
+        .chain(&lis0_dens)
+        .chain(&lis1_dens)
+        .chain(&lis2_dens)
+        .chain(&li_dens)
+        .product::<ark_bn254::Fr>();
+    if denominators * proof.inv != ark_bn254::Fr::ONE {
+        return Err("The inverse of the proof doesn't match its evaluations".to_string());
+    }
+    // none of the denominators is zero from here on
+    let zh_inv = zh.inverse().unwrap();
+
+    // L_i = w^(i - 1) * zh / (n * (xi - w^(i - 1))) and PI = -sum(L_i * input_i)
+    let lagranges = w_powers
+        .iter()
+        .zip(&li_dens)
+        .map(|(w_i, den)| *w_i * zh / den)
+        .collect::<Vec<_>>();
+    let pi = -public_inputs
+        .iter()
+        .zip(&lagranges)
+        .map(|(input, l)| *input * l)
+        .sum::<ark_bn254::Fr>();
+
+    let r0 = {
+        let num = y.pow([8_u64]) - xi;
+        let coefficients = [
+            proof.ql, proof.qr, proof.qo, proof.qm, proof.qc, proof.s1, proof.s2, proof.s3,
+        ];
+        h0.iter()
+            .zip(&lis0_dens)
+            .map(|(x, den)| {
+                let value = coefficients
+                    .iter()
+                    .rev()
+                    .fold(ark_bn254::Fr::ZERO, |value, coefficient| {
+                        value * x + coefficient
+                    });
+                value * num / den
+            })
+            .sum::<ark_bn254::Fr>()
+    };
+
+    let r1 = {
+        let num = y.pow([4_u64]) - xi;
+        let t0 = (proof.ql * proof.a
+            + proof.qr * proof.b
+            + proof.qm * proof.a * proof.b
+            + proof.qo * proof.c
+            + proof.qc
+            + pi)
+            * zh_inv;
+        let coefficients = [proof.a, proof.b, proof.c, t0];
+        h1.iter()
+            .zip(&lis1_dens)
+            .map(|(x, den)| {
+                let value = coefficients
+                    .iter()
+                    .rev()
+                    .fold(ark_bn254::Fr::ZERO, |value, coefficient| {
+                        value * x + coefficient
+                    });
+                value * num / den
+            })
+            .sum::<ark_bn254::Fr>()
+    };
+
+    let r2 = {
+        let num = y.pow([6_u64]) - (xi + xiw) * y.pow([3_u64]) + xi * xiw;
+        let betaxi = beta * xi;
+        let t2 = ((proof.a + betaxi + gamma)
+            * (proof.b + betaxi * vk.k1 + gamma)
+            * (proof.c + betaxi * vk.k2 + gamma)
+            * proof.z
+            - (proof.a + beta * proof.s1 + gamma)
+                * (proof.b + beta * proof.s2 + gamma)
+                * (proof.c + beta * proof.s3 + gamma)
+                * proof.zw)
+            * zh_inv;
+        let t1 = (proof.z - ark_bn254::Fr::ONE) * lagranges[0] * zh_inv;
+        let coefficients = [[proof.z, t1, t2], [proof.zw, proof.t1w, proof.t2w]];
+        h2.iter()
+            .map(|x| (x, &coefficients[0]))
+            .chain(h3.iter().map(|x| (x, &coefficients[1])))
+            .zip(&lis2_dens)
+            .map(|((x, [c0, c1, c2]), den)| (*c0 + *c1 * x + *c2 * x.square()) * num / den)
+            .sum::<ark_bn254::Fr>()
+    };
+
+    let quotient1 = alpha * mul_h0 / mul_h1;
+    let quotient2 = alpha.square() * mul_h0 / mul_h2;
+    let f = vk.c0.into_group() + proof.c1 * quotient1 + proof.c2 * quotient2;
+    let e = ark_bn254::G1Affine::generator() * (r0 + quotient1 * r1 + quotient2 * r2);
+    let j = proof.w1 * mul_h0;
+
+    Ok((f - e - j + proof.w2 * y).into_affine())
+}
+
+// Checks that the public inputs, the proof and the hints of the final exponentiation in the
+// witness are field elements and that C1, C2, W1 and W2 are on G1.
+//
+// Input stack: [public_inputs, C1, C2, W1, W2, evaluations, inv, xiseed, c, c_inv, wi]
+// Output stack: [public_inputs, C1, C2, W1, W2, evaluations, inv, xiseed, c, c_inv, wi]
+fn check_witness(n_public: u32) -> Script {
+    script! {
+        for i in 0..HINTS_LEN {
+            { Fq::copy(i) }
+            { Fq::is_field() }
+            OP_VERIFY
+        }
+        // xiseed, inv and the evaluations
+        for i in HINTS_LEN..HINTS_LEN + 17 {
+            { Fr::copy(i) }
+            { Fr::is_field() }
+            OP_VERIFY
+        }
+        for i in HINTS_LEN + 17..HINTS_LEN + PROOF_LEN {
+            { Fq::copy(i) }
+            { Fq::is_field() }
+            OP_VERIFY
+        }
+        for i in 0..n_public {
+            { Fr::copy(HINTS_LEN + PROOF_LEN + i) }
+            { Fr::is_field() }
+            OP_VERIFY
+        }
+
+        // W2, W1, C2 and C1
+        for i in 0..4 {
+            { Fq2::copy(HINTS_LEN + 17 + 2 * i) }
+            { G1Affine::is_on_curve() }
+            OP_VERIFY
+        }
+    }
+}
+
+// Pushes a copy of the `index`-th field element of the witness, counted from the bottom of the
+// stack, so that the proof can be read wherever the transcript needs it.
+fn witness_element(index: u32) -> Script {
+    script! {
+        for limb in 0..Fq::N_LIMBS {
+            OP_DEPTH { index * Fq::N_LIMBS + limb + 1 } OP_SUB OP_PICK
+        }
+    }
+}
+
+fn dec<F>(element: F) -> String
+where
+    BigUint: From<F>,
+{
+    BigUint::from(element).to_string()
+}
+
+// w^0, ..., w^(n - 1) for the Lagrange polynomials of the public inputs.
+fn powers(w: &str, n: usize) -> Vec<String> {
+    let w = ark_bn254::Fr::from_str(w).unwrap();
+    (0..n).map(|i| dec(w.pow([i as u64]))).collect()
+}
+
+/// compute challenges
+// beta = blake3(C0, public_inputs, C1), `c1` pushes [C1.x, C1.y] and each of `inputs` a public
+// input.
+fn compute_challenges_beta(
+    hash: &Script,
+    c0_x: &str,
+    c0_y: &str,
+    c1: &Script,
+    inputs: &[Script],
+) -> Script {
+    script! {
+        // the topmost bytes come first in the transcript, convert C1 into bytes
+        { c1.clone() }
+        { G1Affine::convert_to_compressed() }
+
+        // convert the public inputs into bytes
+        for input in inputs.iter().rev() {
+            { input.clone() }
+            { Fr::convert_to_be_bytes() }
+        }
+
+        // convert C0 into bytes
+        { Fq::push_dec(c0_x) }
+        { Fq::push_dec(c0_y) }
+        { G1Affine::convert_to_compressed() }
+
+        // compute the hash
+        { hash.clone() }
+        { Fr::from_hash() }
+    }
+}
+
+fn compute_challenges_gamma(hash: &Script) -> Script {
+    script! {
+     { Fr::copy(0) }
+     { Fr::convert_to_be_bytes() }
+     { hash.clone() }
+     { Fr::from_hash() }
+    }
+}
+
+fn compute_challenges_alpha(
+    hash: &Script,
+    xi: &Script,
+    ql: &Script,
+    qr: &Script,
+    qm: &Script,
+    qo: &Script,
+    qc: &Script,
+    s1: &Script,
+    s2: &Script,
+    s3: &Script,
+    a: &Script,
+    b: &Script,
+    c: &Script,
+    z: &Script,
+    zw: &Script,
+    t1w: &Script,
+    t2w: &Script,
+) -> Script {
+    script! {
+        // push xi seed
+        { xi.clone() }
+
+        // push the polynomial evaluations
+
+        // ql
+        { ql.clone() }
+
+        // qr
+        { qr.clone() }
+
+        // qm
+        { qm.clone() }
+
+        // qo
+        { qo.clone() }
+
+        // qc
+        { qc.clone() }
+
+        // s1
+        { s1.clone() }
+
+        // s2
+        { s2.clone() }
+
+        // s3
+        { s3.clone() }
+
+        // a
+        { a.clone() }
+
+        // b
+        { b.clone() }
+
+        // c
+        { c.clone() }
+
+        // z
+        { z.clone() }
+
+        // zw
+        { zw.clone() }
+
+        // t1w
+        { t1w.clone() }
+
+        // t2w
+        { t2w.clone() }
+
+        for i in 1..16 {
+            { Fr::roll(16 - i) } { Fr::toaltstack() }
+        }
+
+        { Fr::convert_to_be_bytes() }
+
+        for _ in 0..15 {
+            { Fr::fromaltstack() } { Fr::convert_to_be_bytes() }
+        }
+
+        {hash.clone()}
+        { Fr::from_hash() }
+    }
+}
+
+// [beta, gamma, alpha]
+fn compute_challenges_y(hash: &Script, w1: &Script) -> Script {
+    script! {
+        // alpha
+        { Fr::copy(0) }
+        // W1
+        { w1.clone() }
+
+        { Fr::roll(2) }
+        { Fr::toaltstack() }
+
+        { G1Affine::convert_to_compressed() }
+        { Fr::fromaltstack() }
+        { Fr::convert_to_be_bytes() }
+
+        {hash.clone()}
+        { Fr::from_hash() }
+    }
+}
+
+// [beta, gamma, alpha, y]
+fn compute_challenges_xiseed(hash: &Script, c2: &Script) -> Script {
+    script! {
+        { Fr::copy(2) }
+        // C2
+        { c2.clone() }
+
+        { Fr::roll(2) }
+        { Fr::toaltstack() }
+
+        { G1Affine::convert_to_compressed() }
+        { Fr::fromaltstack() }
+        { Fr::convert_to_be_bytes() }
+
+        {hash.clone()}
+        { Fr::from_hash() }
+    }
+}
+
+// [beta, gamma, alpha, y, xiseed]
+fn compute_challenges_xin(
+    w8_1: &str,
+    w8_2: &str,
+    w8_3: &str,
+    w8_4: &str,
+    w8_5: &str,
+    w8_6: &str,
+    w8_7: &str,
+    w3: &str,
+    w3_2: &str,
+    w4: &str,
+    w4_2: &str,
+    w4_3: &str,
+    wr: &str,
+    power: u32,
+) -> Script {
+    script! {
+        // push xiseed
+        // { Fr::copy(0) }
+        // compute xiseed^2
+        { Fr::copy(0) }
+        { Fr::square() }
+        { Fr::copy(0) }
+        { Fr::toaltstack() }
+
+        // pH0w8_0 = xiseed^3
+        { Fr::mul() }
+        // [beta, gamma, alpha, y, pH0w8_0]
+
+        // pH0w8_1
+        { Fr::copy(0) }
+        // push constant w8_1
+        { Fr::push_dec(w8_1) }
+        { Fr::mul() }
+        // [beta, gamma, alpha, y, pH0w8_0, pH0w8_1]
+
+        // pH0w8_2
+        // { Fr::copy(0) }
+        { Fr::copy(1) }
+        // push constant w8_2
+        { Fr::push_dec(w8_2) }
+        { Fr::mul() }
+        // [beta, gamma, alpha, y, pH0w8_0, pH0w8_1, pH0w8_2]
+
+        // pH0w8_3
+        // { Fr::copy(0) }
+        { Fr::copy(2) }
+        // push constant w8_3
+        { Fr::push_dec(w8_3) }
+        { Fr::mul() }
+        // [beta, gamma, alpha, y, pH0w8_0, pH0w8_1, pH0w8_2, pH0w8_3]
+
+        // pH0w8_4
+        { Fr::copy(3) }
+        // push constant w8_4
+        { Fr::push_dec(w8_4) }
+        { Fr::mul() }
+        // [beta, gamma, alpha, y, pH0w8_0, pH0w8_1, pH0w8_2, pH0w8_3, pH0w8_4]
+
+        // pH0w8_5
+        { Fr::copy(4) }
+        // push constant w8_5
+        { Fr::push_dec(w8_5) }
+        { Fr::mul() }
+        // [beta, gamma, alpha, y, pH0w8_0, pH0w8_1, pH0w8_2, pH0w8_3, pH0w8_4, pH0w8_5]
+
+        // pH0w8_6
+        { Fr::copy(5) }
+        // push constant w8_6
+        { Fr::push_dec(w8_6) }
+        { Fr::mul() }
+        // [beta, gamma, alpha, y, pH0w8_0, pH0w8_1, pH0w8_2, pH0w8_3, pH0w8_4, pH0w8_5, pH0w8_6]
+
+        // pH0w8_7
+        { Fr::copy(6) }
+        // push constant w8_7
+        { Fr::push_dec(w8_7) }
+        { Fr::mul() }
+        // [beta, gamma, alpha, y, pH0w8_0, pH0w8_1, pH0w8_2, pH0w8_3, pH0w8_4, pH0w8_5, pH0w8_6, pH0w8_7]
+
+        // pH1w4_0 = xiseed^6
+        { Fr::copy(7) }
+        { Fr::square() }
+        // [beta, gamma, alpha, y, pH0w8_0, pH0w8_1, pH0w8_2, pH0w8_3, pH0w8_4, pH0w8_5, pH0w8_6, pH0w8_7,
+        // pH1w4_0, ]
+
+        // pH1w4_1
+        { Fr::copy(0) }
+        // push constant w4
+        { Fr::push_dec(w4) }
+        { Fr::mul() }
+        // [beta, gamma, alpha, y, pH0w8_0, pH0w8_1, pH0w8_2, pH0w8_3, pH0w8_4, pH0w8_5, pH0w8_6, pH0w8_7,
+        // pH1w4_0, pH1w4_1]
+
+        // pH1w4_2
+        { Fr::copy(1) }
+        // push constant w4_1
+        { Fr::push_dec(w4_2) }
+        { Fr::mul() }
+        // [beta, gamma, alpha, y, pH0w8_0, pH0w8_1, pH0w8_2, pH0w8_3, pH0w8_4, pH0w8_5, pH0w8_6, pH0w8_7,
+        // pH1w4_0, pH1w4_1, pH1w4_2]
+
+        // pH1w4_3
+        { Fr::copy(2) }
+        // push constant w4_2
+        { Fr::push_dec(w4_3) }
+        { Fr::mul() }
+        // [beta, gamma, alpha, y, pH0w8_0, pH0w8_1, pH0w8_2, pH0w8_3, pH0w8_4, pH0w8_5, pH0w8_6, pH0w8_7,
+        // pH1w4_0, pH1w4_1, pH1w4_2, pH1w4_3]
+
+        // pH2w3_0 = xiseed^8
+        { Fr::copy(3) }
+        { Fr::fromaltstack() }
+        { Fr::mul() }
+        // { Fr::copy(0) }
+        // { Fr::toaltstack() }
+        // [beta, gamma, alpha, y, pH0w8_0, pH0w8_1, pH0w8_2, pH0w8_3, pH0w8_4, pH0w8_5, pH0w8_6, pH0w8_7,
+        // pH1w4_0, pH1w4_1, pH1w4_2, pH1w4_3, pH2w3_0]
+
+        // pH2w3_1
+        { Fr::copy(0) }
+        // push constant w3
+        { Fr::push_dec(w3) }
+        { Fr::mul() }
+        // [beta, gamma, alpha, y, pH0w8_0, pH0w8_1, pH0w8_2, pH0w8_3, pH0w8_4, pH0w8_5, pH0w8_6, pH0w8_7,
+        // pH1w4_0, pH1w4_1, pH1w4_2, pH1w4_3, pH2w3_0, pH2w3_1]
+
+        // pH2w3_2
+        { Fr::copy(1) }
+        // push constant w3_2
+        { Fr::push_dec(w3_2) }
+        { Fr::mul() }
+        // [beta, gamma, alpha, y, pH0w8_0, pH0w8_1, pH0w8_2, pH0w8_3, pH0w8_4, pH0w8_5, pH0w8_6, pH0w8_7,
+        // pH1w4_0, pH1w4_1, pH1w4_2, pH1w4_3, pH2w3_0, pH2w3_1, pH2w3_2]
+
+        // pH3w3_0 = xiseed^8 * ω^{1/3}
+        { Fr::copy(2) }
+        { Fr::push_dec(wr) }
+        { Fr::mul() }
+        // [beta, gamma, alpha, y, pH0w8_0, pH0w8_1, pH0w8_2, pH0w8_3, pH0w8_4, pH0w8_5, pH0w8_6, pH0w8_7,
+        // pH1w4_0, pH1w4_1, pH1w4_2, pH1w4_3, pH2w3_0, pH2w3_1, pH2w3_2, pH3w3_0]
+
+        // pH3w3_1
+        { Fr::copy(0) }
+        // push constant w3
+        { Fr::push_dec(w3) }
+        { Fr::mul() }
+        // [beta, gamma, alpha, y, pH0w8_0, pH0w8_1, pH0w8_2, pH0w8_3, pH0w8_4, pH0w8_5, pH0w8_6, pH0w8_7,
+        // pH1w4_0, pH1w4_1, pH1w4_2, pH1w4_3, pH2w3_0, pH2w3_1, pH2w3_2, pH3w3_0, pH3w3_1]
+
+        // pH2w3_2
+        // push constant w3_2
+        { Fr::copy(1) }
+        { Fr::push_dec(w3_2) }
+        { Fr::mul() }
+        // [beta, gamma, alpha, y, pH0w8_0, pH0w8_1, pH0w8_2, pH0w8_3, pH0w8_4, pH0w8_5, pH0w8_6, pH0w8_7,
+        // pH1w4_0, pH1w4_1, pH1w4_2, pH1w4_3, pH2w3_0, pH2w3_1, pH2w3_2, pH3w3_0, pH3w3_1, pH2w3_2]
+
+        // { Fr::fromaltstack() }
+        { Fr::copy(5) }
+
+        // xi = xi_seeder^24
+        { Fr::copy(0) }
+        { Fr::square() }
+        { Fr::mul() }
+        // [beta, gamma, alpha, y, pH0w8_0, pH0w8_1, pH0w8_2, pH0w8_3, pH0w8_4, pH0w8_5, pH0w8_6, pH0w8_7,
+        // pH1w4_0, pH1w4_1, pH1w4_2, pH1w4_3, pH2w3_0, pH2w3_1, pH2w3_2, pH3w3_0, pH3w3_1, pH2w3_2, xi]
+
+        // xiN
+        { Fr::copy(0) }
+        for _ in 0..power {
+            { Fr::square() }
+        }
+        // [beta, gamma, alpha, y, pH0w8_0, pH0w8_1, pH0w8_2, pH0w8_3, pH0w8_4, pH0w8_5, pH0w8_6, pH0w8_7,
+        // pH1w4_0, pH1w4_1, pH1w4_2, pH1w4_3, pH2w3_0, pH2w3_1, pH2w3_2, pH3w3_0, pH3w3_1, pH2w3_2, xi, xiN]
+
+        // zh
+        { Fr::push_one() }
+        { Fr::sub(1, 0) }
+        // [beta, gamma, alpha, y, pH0w8_0, pH0w8_1, pH0w8_2, pH0w8_3, pH0w8_4, pH0w8_5, pH0w8_6, pH0w8_7,
+        // pH1w4_0, pH1w4_1, pH1w4_2, pH1w4_3, pH2w3_0, pH2w3_1, pH2w3_2, pH3w3_0, pH3w3_1, pH2w3_2, xi, zh]
+    }
+}
+// [beta, gamma, alpha, y, pH0w8_0, pH0w8_1, pH0w8_2, pH0w8_3, pH0w8_4, pH0w8_5, pH0w8_6, pH0w8_7,
+// pH1w4_0, pH1w4_1, pH1w4_2, pH1w4_3, pH2w3_0, pH2w3_1, pH2w3_2, pH3w3_0, pH3w3_1, pH2w3_2, xi, zh]
+// pH3w3_0(3), pH2w3_0(6), pH1w4_0(10), pH0w8_0(18)
+
+/// compute inversions
+// The denominators of the verifier, with one Li_i = n * (xi - w^(i - 1)) per public input and at
+// least Li_1, inverted at once with the inverse `inv` of their product.
+fn compute_inversions(w: &str, inv: &Script, power: u32, n_public: usize) -> Script {
+    let w_powers = powers(w, n_public.max(1));
+    let len = 21 + w_powers.len() as u32;
+
+    script! {
+        // push Z_H
+        { Fr::copy(0) }
+        { Fr::toaltstack() }
+
+        // push y
+        { Fr::copy(20) }
+        // push H1w4_0, H1w4_1, H1w4_2, H1w4_3
+        { Fr::copy(12) }
+        { Fr::copy(12) }
+        { Fr::copy(12) }
+        { Fr::copy(12) }
+        // [..., xi, zh, y, pH1w4_0, pH1w4_1, pH1w4_2, pH1w4_3 | Z_H]
+
+        { Fr::copy(4) }
+        { Fr::sub(0, 1) }
+        // [xi, zh, y, pH1w4_0, pH1w4_1, pH1w4_2, y - pH1w4_3 | Z_H]
+        { Fr::copy(4) }
+        { Fr::sub(0, 2) }
+        // [xi, zh, y, pH1w4_0, pH1w4_1, y - pH1w4_3, y - pH1w4_2 | Z_H]
+        { Fr::copy(4) }
+        { Fr::sub(0, 3) }
+        // [xi, zh, y, pH1w4_0, y - pH1w4_3, y - pH1w4_2, y - pH1w4_1 | Z_H]
+        { Fr::copy(4) }
+        { Fr::sub(0, 4) }
+        // [xi, zh, y, y - pH1w4_3, y - pH1w4_2, y - pH1w4_1, y - pH1w4_0 | Z_H]
+
+        { Fr::mul() }
+        { Fr::mul() }
+        { Fr::mul() }
+        // [y, (y - pH1w4_3) * (y - pH1w4_2) * (y - pH1w4_1) * (y - pH1w4_0)]
+        { Fr::toaltstack() }
+        // [y | Z_H, (y - pH1w4_3) * (y - pH1w4_2) * (y - pH1w4_1) * (y - pH1w4_0)]
+
+        // push H2w3_0, H2w3_1, H2w3_2, H3w3_0, H3w3_1, H3w3_2
+        { Fr::copy(8) }
+        { Fr::copy(8) }
+        { Fr::copy(8) }
+        { Fr::copy(8) }
+        { Fr::copy(8) }
+        { Fr::copy(8) }
+        // [y, H2w3_0, H2w3_1, H2w3_2, H3w3_0, H3w3_1, H3w3_2 | Z_H, prod_1]
+
+        { Fr::copy(6) }
+        { Fr::sub(0, 1) }
+        // [y, H2w3_0, H2w3_1, H2w3_2, H3w3_0, H3w3_1, y -  H3w3_2]
+        { Fr::copy(6) }
+        { Fr::sub(0, 2) }
+        // [y, H2w3_0, H2w3_1, H2w3_2, H3w3_0, y -  H3w3_2, y - H3w3_1]
+        { Fr::copy(6) }
+        { Fr::sub(0, 3) }
+        // [y, H2w3_0, H2w3_1, H2w3_2, y -  H3w3_2, y - H3w3_1, y - H3w3_0]
+        { Fr::copy(6) }
+        { Fr::sub(0, 4) }
+        // [y, H2w3_0, H2w3_1, y -  H3w3_2, y - H3w3_1, y - H3w3_0, y - H2w3_2]
+        { Fr::copy(6) }
+        { Fr::sub(0, 5) }
+        // [y, H2w3_0, y -  H3w3_2, y - H3w3_1, y - H3w3_0, y - H2w3_2, y - H2w3_1]
+        { Fr::copy(6) }
+        { Fr::sub(0, 6) }
+        // [y, y -  H3w3_2, y - H3w3_1, y - H3w3_0, y - H2w3_2, y - H2w3_1, y - H2w3_0]
+
+        { Fr::mul() }
+        { Fr::mul() }
+        { Fr::mul() }
+        { Fr::mul() }
+        { Fr::mul() }
+        // [y, (y -  H3w3_2) * (y - H3w3_1) * (y - H3w3_0) * (y - H2w3_2) * (y - H2w3_1) * (y - H2w3_0)]
+        { Fr::toaltstack() }
+        // [y | Z_H, prod_1, prod_2]
+
+        // push H0w8_0, H0w8_1, H0w8_2, H0w8_3, H0w8_4, H0w8_5, H0w8_6, H0w8_7
+        { Fr::copy(20) }
+        { Fr::copy(20) }
+        { Fr::copy(20) }
+        { Fr::copy(20) }
+        { Fr::copy(20) }
+        { Fr::copy(20) }
+        { Fr::copy(20) }
+        { Fr::copy(20) }
+        // [y, H0w8_0, H0w8_1, H0w8_2, H0w8_3, H0w8_4, H0w8_5, H0w8_6, H0w8_7 | Z_H, prod_1, prod_2]
+
+        // den1 = Fr.mul(Fr.e(len), Fr.exp(roots[0], len - 2)) = = 8 * H0w8_0 ^ 6
+        { Fr::copy(7) }
+        { Fr::square() }
+        { Fr::copy(0) }
+        { Fr::square() }
+        { Fr::mul() }
+        { Fr::double(0) }
+        { Fr::double(0) }
+        { Fr::double(0) }
+        { Fr::toaltstack() }
+        // [y, H0w8_0, H0w8_1, H0w8_2, H0w8_3, H0w8_4, H0w8_5, H0w8_6, H0w8_7 | Z_H, prod_1, prod_2, den1]
+
+        // den2 = roots[7 * 0 % 8] = roots[0]
+        { Fr::copy(7) }
+        { Fr::toaltstack() }
+        // [y, H0w8_0, H0w8_1, H0w8_2, H0w8_3, H0w8_4, H0w8_5, H0w8_6, H0w8_7 | prod_1, prod_2, den1, den2]
+
+        // den3 = x - roots[0]
+        { Fr::copy(8) }
+        { Fr::toaltstack() }
+        { Fr::copy(7) }
+        { Fr::fromaltstack() }
+        { Fr::sub(0, 1) }
+        // [y, H0w8_0, H0w8_1, H0w8_2, H0w8_3, H0w8_4, H0w8_5, H0w8_6, H0w8_7, y - H0w8_0 | Z_H, prod_1, prod_2, den1, den2]
+
+        // LiS0_1 = den1 * den2 * den3, keep den1 in the altstack
+        { Fr::fromaltstack() }
+        { Fr::mul() }
+        { Fr::fromaltstack() }
+        { Fr::copy(0) }
+        { Fr::roll(2) }
+        { Fr::mul() }
+        { Fr::toaltstack() }
+        { Fr::toaltstack() }
+        // [y, H0w8_0, H0w8_1, H0w8_2, H0w8_3, H0w8_4, H0w8_5, H0w8_6, H0w8_7 | Z_H, prod_1, prod_2, LiS0_1, den1]
+
+        // den2 = roots[7 * 1 % 8] = roots[7]
+        { Fr::copy(0) }
+        { Fr::toaltstack() }
+        // [y, H0w8_0, H0w8_1, H0w8_2, H0w8_3, H0w8_4, H0w8_5, H0w8_6, H0w8_7 | Z_H, prod_1, prod_2, LiS0_1, den1, H0w8_7]
+
+        // den3 = x - roots[1]
+        { Fr::copy(8) }
+        { Fr::toaltstack() }
+        { Fr::copy(6) }
+        { Fr::fromaltstack() }
+        { Fr::sub(0, 1) }
+        // [y, H0w8_0, H0w8_1, H0w8_2, H0w8_3, H0w8_4, H0w8_5, H0w8_6, H0w8_7, y - H0w8_1 | Z_H, prod_1, prod_2, LiS0_1, den1, den2]
+
+        // LiS0_2 = den1 * den2 * den3, keep den1 in the altstack
+        { Fr::fromaltstack() }
+        { Fr::mul() }
+        { Fr::fromaltstack() }
+        { Fr::copy(0) }
+        { Fr::roll(2) }
+        { Fr::mul() }
+        { Fr::toaltstack() }
+        { Fr::toaltstack() }
+        // [y, H0w8_0, H0w8_1, H0w8_2, H0w8_3, H0w8_4, H0w8_5, H0w8_6, H0w8_7 | Z_H, prod_1, prod_2, LiS0_1, LiS0_2, den1]
+
+        // den2 = roots[7 * 2 % 8] = roots[6]
+        { Fr::copy(1) }
+        { Fr::toaltstack() }
+
+        // den3 = x - roots[2]
+        { Fr::copy(8) }
+        { Fr::toaltstack() }
+        { Fr::copy(5) }
+        { Fr::fromaltstack() }
+        { Fr::sub(0, 1) }
+
+        // LiS0_3 = den1 * den2 * den3, keep den1 in the altstack
+        { Fr::fromaltstack() }
+        { Fr::mul() }
+        { Fr::fromaltstack() }
+        { Fr::copy(0) }
+        { Fr::roll(2) }
+        { Fr::mul() }
+        { Fr::toaltstack() }
+        { Fr::toaltstack() }
+        // [y, H0w8_0, H0w8_1, H0w8_2, H0w8_3, H0w8_4, H0w8_5, H0w8_6, H0w8_7 | Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, den1]
+
+        // den2 = roots[7 * 3 % 8] = roots[5]
+        { Fr::copy(2) }
+        { Fr::toaltstack() }
+
+        // den3 = x - roots[3]
+        { Fr::copy(8) }
+        { Fr::toaltstack() }
+        { Fr::copy(4) }
+        { Fr::fromaltstack() }
+        { Fr::sub(0, 1) }
+
+        // LiS0_4 = den1 * den2 * den3, keep den1 in the altstack
+        { Fr::fromaltstack() }
+        { Fr::mul() }
+        { Fr::fromaltstack() }
+        { Fr::copy(0) }
+        { Fr::roll(2) }
+        { Fr::mul() }
+        { Fr::toaltstack() }
+        { Fr::toaltstack() }
+        // [y, H0w8_0, H0w8_1, H0w8_2, H0w8_3, H0w8_4, H0w8_5, H0w8_6, H0w8_7 | Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, den1]
+
+        // den2 = roots[7 * 4 % 8] = roots[4]
+        { Fr::copy(3) }
+        { Fr::toaltstack() }
+
+        // den3 = x - roots[4]
+        { Fr::copy(8) }
+        { Fr::toaltstack() }
+        { Fr::copy(3) }
+        { Fr::fromaltstack() }
+        { Fr::sub(0, 1) }
+
+        // LiS0_5 = den1 * den2 * den3, keep den1 in the altstack
+        { Fr::fromaltstack() }
+        { Fr::mul() }
+        { Fr::fromaltstack() }
+        { Fr::copy(0) }
+        { Fr::roll(2) }
+        { Fr::mul() }
+        { Fr::toaltstack() }
+        { Fr::toaltstack() }
+        // [y, H0w8_0, H0w8_1, H0w8_2, H0w8_3, H0w8_4, H0w8_5, H0w8_6, H0w8_7 | Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, den1]
+
+        // den2 = roots[7 * 5 % 8] = roots[3]
+        { Fr::copy(4) }
+        { Fr::toaltstack() }
+
+        // den3 = x - roots[5]
+        { Fr::copy(8) }
+        { Fr::toaltstack() }
+        { Fr::copy(2) }
+        { Fr::fromaltstack() }
+        { Fr::sub(0, 1) }
+
+        // LiS0_6 = den1 * den2 * den3, keep den1 in the altstack
+        { Fr::fromaltstack() }
+        { Fr::mul() }
+        { Fr::fromaltstack() }
+        { Fr::copy(0) }
+        { Fr::roll(2) }
+        { Fr::mul() }
+        { Fr::toaltstack() }
+        { Fr::toaltstack() }
+        // [y, H0w8_0, H0w8_1, H0w8_2, H0w8_3, H0w8_4, H0w8_5, H0w8_6, H0w8_7 | Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, den1]
+
+        // den2 = roots[7 * 6 % 8] = roots[2]
+        { Fr::copy(5) }
+        { Fr::toaltstack() }
+
+        // den3 = x - roots[6]
+        { Fr::copy(8) }
+        { Fr::toaltstack() }
+        { Fr::copy(1) }
+        { Fr::fromaltstack() }
+        { Fr::sub(0, 1) }
+
+        // LiS0_7 = den1 * den2 * den3, keep den1 in the altstack
+        { Fr::fromaltstack() }
+        { Fr::mul() }
+        { Fr::fromaltstack() }
+        { Fr::copy(0) }
+        { Fr::roll(2) }
+        { Fr::mul() }
+        { Fr::toaltstack() }
+        { Fr::toaltstack() }
+       // [y, H0w8_0, H0w8_1, H0w8_2, H0w8_3, H0w8_4, H0w8_5, H0w8_6, H0w8_7 | Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, den1]
+
+        // den2 = roots[7 * 7 % 8] = roots[1]
+        { Fr::copy(6) }
+        { Fr::toaltstack() }
+
+        // den3 = x - roots[7]
+        { Fr::copy(8) }
+        { Fr::toaltstack() }
+        { Fr::copy(0) }
+        { Fr::fromaltstack() }
+        { Fr::sub(0, 1) }
+
+        // LiS0_8 = den1 * den2 * den3, remove den1 in the altstack
+        { Fr::fromaltstack() }
+        { Fr::mul() }
+        { Fr::fromaltstack() }
+        { Fr::mul() }
+        { Fr::toaltstack() }
+       // [y, H0w8_0, H0w8_1, H0w8_2, H0w8_3, H0w8_4, H0w8_5, H0w8_6, H0w8_7 | Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8]
+
+        // drop H0w8_0, H0w8_1, H0w8_2, H0w8_3, H0w8_4, H0w8_5, H0w8_6, H0w8_7
+        { Fr::drop() }
+        { Fr::drop() }
+        { Fr::drop() }
+        { Fr::drop() }
+        { Fr::drop() }
+        { Fr::drop() }
+        { Fr::drop() }
+        { Fr::drop() }
+       // [y | Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8]
+
+        // push H1w4_0, H1w4_1, H1w4_2, H1w4_3
+        { Fr::copy(12) }
+        { Fr::copy(12) }
+        { Fr::copy(12) }
+        { Fr::copy(12) }
+
+        // den1 = Fr.mul(Fr.e(len), Fr.exp(roots[0], len - 2)) = = 4 * H0w8_0 ^ 2
+        { Fr::copy(3) }
+        { Fr::square() }
+        { Fr::double(0) }
+        { Fr::double(0) }
+        { Fr::toaltstack() }
+       // [y, H1w4_0, H1w4_1, H1w4_2, H1w4_3 | Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, den1]
+
+        // den2 = roots[3 * 0 % 4] = roots[0]
+        { Fr::copy(3) }
+        { Fr::toaltstack() }
+       // [y, H1w4_0, H1w4_1, H1w4_2, H1w4_3 | Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, den1, den2]
+
+        // den3 = x - roots[0]
+        { Fr::copy(4) }
+        { Fr::toaltstack() }
+        { Fr::copy(3) }
+        { Fr::fromaltstack() }
+        { Fr::sub(0, 1) }
+       // [y, H1w4_0, H1w4_1, H1w4_2, H1w4_3, den3 | Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, den1, den2]
+
+        // LiS1_1 = den1 * den2 * den3, keep den1 in the altstack
+        { Fr::fromaltstack() }
+        { Fr::mul() }
+        { Fr::fromaltstack() }
+        { Fr::copy(0) }
+        { Fr::roll(2) }
+        { Fr::mul() }
+        { Fr::toaltstack() }
+        { Fr::toaltstack() }
+       // [y, H1w4_0, H1w4_1, H1w4_2, H1w4_3 | Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, den1]
+
+        // den2 = roots[3 * 1 % 4] = roots[3]
+        { Fr::copy(0) }
+        { Fr::toaltstack() }
+
+        // den3 = x - roots[1]
+        { Fr::copy(4) }
+        { Fr::toaltstack() }
+        { Fr::copy(2) }
+        { Fr::fromaltstack() }
+        { Fr::sub(0, 1) }
+
+        // LiS1_2 = den1 * den2 * den3, keep den1 in the altstack
+        { Fr::fromaltstack() }
+        { Fr::mul() }
+        { Fr::fromaltstack() }
+        { Fr::copy(0) }
+        { Fr::roll(2) }
+        { Fr::mul() }
+        { Fr::toaltstack() }
+        { Fr::toaltstack() }
+       // [y, H1w4_0, H1w4_1, H1w4_2, H1w4_3 | Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, den1]
+
+        // den2 = roots[3 * 2 % 4] = roots[2]
+        { Fr::copy(1) }
+        { Fr::toaltstack() }
+
+        // den3 = x - roots[2]
+        { Fr::copy(4) }
+        { Fr::toaltstack() }
+        { Fr::copy(1) }
+        { Fr::fromaltstack() }
+        { Fr::sub(0, 1) }
+
+        // LiS1_3 = den1 * den2 * den3, keep den1 in the altstack
+        { Fr::fromaltstack() }
+        { Fr::mul() }
+        { Fr::fromaltstack() }
+        { Fr::copy(0) }
+        { Fr::roll(2) }
+        { Fr::mul() }
+        { Fr::toaltstack() }
+        { Fr::toaltstack() }
+       // [y, H1w4_0, H1w4_1, H1w4_2, H1w4_3 | Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, LiS1_3, den1]
+
+        // den2 = roots[3 * 3 % 4] = roots[1]
+        { Fr::copy(2) }
+        { Fr::toaltstack() }
+
+        // den3 = x - roots[3]
+        { Fr::copy(4) }
+        { Fr::toaltstack() }
+        { Fr::copy(0) }
+        { Fr::fromaltstack() }
+        { Fr::sub(0, 1) }
+
+        // LiS1_4 = den1 * den2 * den3, remove den1 in the altstack
+        { Fr::fromaltstack() }
+        { Fr::mul() }
+        { Fr::fromaltstack() }
+        { Fr::mul() }
+        { Fr::toaltstack() }
+       // [y, H1w4_0, H1w4_1, H1w4_2, H1w4_3 | Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, LiS1_3, LiS1_4]
+
+        // drop H1w4_0, H1w4_1, H1w4_2, H1w4_3
+        { Fr::drop() }
+        { Fr::drop() }
+        { Fr::drop() }
+        { Fr::drop() }
+       // [y | prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, LiS1_3, LiS1_4]
+
+        // push H2w3_0, H2w3_1, H2w3_2
+        { Fr::copy(8) }
+        { Fr::copy(8) }
+        { Fr::copy(8) }
+        // [y, H2w3_0, H2w3_1, H2w3_2 | Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, LiS1_3, LiS1_4]
+
+        // push xi
+        // { Fr::push_dec("14814634099415170872937750660683266261347419959225231219985478027287965492246") }
+        { Fr::copy(5) }
+        // [y, H2w3_0, H2w3_1, H2w3_2, xi | Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, LiS1_3, LiS1_4]
+
+        // compute xiw
+        { Fr::copy(0) }
+        { Fr::push_dec(w) }
+        { Fr::mul() }
+        // [y, H2w3_0, H2w3_1, H2w3_2, xi, xiw | Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, LiS1_3, LiS1_4]
+
+        // compute xi - xiw
+        { Fr::sub(1, 0) }
+        { Fr::copy(0) }
+        { Fr::toaltstack() }
+        // [y, H2w3_0, H2w3_1, H2w3_2, xi - xiw | Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, LiS1_3, LiS1_4, xi - xiw]
+
+        // move xi - xiw to before y
+        { Fr::roll(4) }
+        { Fr::roll(4) }
+        { Fr::roll(4) }
+        { Fr::roll(4) }
+        // [xi - xiw, y, H2w3_0, H2w3_1, H2w3_2 | Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, LiS1_3, LiS1_4, xi - xiw]
+
+        // _3h2 = Fr.mul(Fr.e(len), Fr.exp(roots[0], len - 2)) = = 3 * H2w3_0
+        { Fr::copy(2) }
+        { Fr::copy(0) }
+        { Fr::double(0) }
+        { Fr::add(1, 0) }
+
+        // compute den1 = _3h2 * (xi - xiw)
+        { Fr::fromaltstack() }
+        { Fr::mul() }
+        { Fr::toaltstack() }
+        // [xi - xiw, y, H2w3_0, H2w3_1, H2w3_2 |
+        // Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, LiS1_3, LiS1_4, den1]
+
+        // den2 = roots[2 * 0 % 3] = roots[0]
+        { Fr::copy(2) }
+        { Fr::toaltstack() }
+        // [xi - xiw, y, H2w3_0, H2w3_1, H2w3_2 |
+        // Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, LiS1_3, LiS1_4, den1, den2]
+
+        // den3 = x - roots[0]
+        { Fr::copy(3) }
+        { Fr::toaltstack() }
+        { Fr::copy(2) }
+        { Fr::fromaltstack() }
+        { Fr::sub(0, 1) }
+        // [xi - xiw, y, H2w3_0, H2w3_1, H2w3_2, y - H2w3_0 |
+        // Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, LiS1_3, LiS1_4, den1, den2]
+
+        // LiS2_1 = den1 * den2 * den3, keep den1 in the altstack
+        { Fr::fromaltstack() }
+        { Fr::mul() }
+        { Fr::fromaltstack() }
+        { Fr::copy(0) }
+        { Fr::roll(2) }
+        { Fr::mul() }
+        { Fr::toaltstack() }
+        { Fr::toaltstack() }
+        // [xi - xiw, y, H2w3_0, H2w3_1, H2w3_2 |
+        // Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, LiS1_3, LiS1_4, LiS2_1, den1]
+
+        // den2 = roots[2 * 1 % 3] = roots[2]
+        { Fr::copy(0) }
+        { Fr::toaltstack() }
+        // [xi - xiw, y, H2w3_0, H2w3_1, H2w3_2 |
+        // Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, LiS1_3, LiS1_4, LiS2_1, den1, den2]
+
+        // den3 = x - roots[1]
+        { Fr::copy(3) }
+        { Fr::toaltstack() }
+        { Fr::copy(1) }
+        { Fr::fromaltstack() }
+        { Fr::sub(0, 1) }
+
+        // LiS2_2 = den1 * den2 * den3, keep den1 in the altstack
+        { Fr::fromaltstack() }
+        { Fr::mul() }
+        { Fr::fromaltstack() }
+        { Fr::copy(0) }
+        { Fr::roll(2) }
+        { Fr::mul() }
+        { Fr::toaltstack() }
+        { Fr::toaltstack() }
+        // [xi - xiw, y, H2w3_0, H2w3_1, H2w3_2 |
+        // Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, LiS1_3, LiS1_4, LiS2_1, LiS2_2, den1]
+
+        // den2 = roots[2 * 2 % 3] = roots[1]
+        { Fr::copy(1) }
+        { Fr::toaltstack() }
+
+        // den3 = x - roots[2]
+        { Fr::copy(3) }
+        { Fr::toaltstack() }
+        { Fr::copy(0) }
+        { Fr::fromaltstack() }
+        { Fr::sub(0, 1) }
+
+        // LiS2_3 = den1 * den2 * den3, keep den1 in the altstack
+        { Fr::fromaltstack() }
+        { Fr::mul() }
+        { Fr::fromaltstack() }
+        { Fr::mul() }
+        { Fr::toaltstack() }
+        // [xi - xiw, y, H2w3_0, H2w3_1, H2w3_2 |
+        // Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, LiS1_3, LiS1_4, LiS2_1, LiS2_2, LiS2_3]
+
+        // drop H2w3_0, H2w3_1, H2w3_2
+        { Fr::drop() }
+        { Fr::drop() }
+        { Fr::drop() }
+        // [xi - xiw, y |
+        // Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, LiS1_3, LiS1_4, LiS2_1, LiS2_2, LiS2_3]
+
+        // push H3w3_0, H3w3_1, H3w3_2
+        { Fr::copy(6) }
+        { Fr::copy(6) }
+        { Fr::copy(6) }
+        // [xi - xiw, y, H3w3_0, H3w3_1, H3w3_2 |
+        // Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, LiS1_3, LiS1_4, LiS2_1, LiS2_2, LiS2_3]
+
+        // obtain xiw - xi
+        { Fr::neg(4) }
+        { Fr::toaltstack() }
+        // [y, H3w3_0, H3w3_1, H3w3_2 |
+        // Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, LiS1_3, LiS1_4, LiS2_1, LiS2_2, LiS2_3, xiw - xi]
+
+        // _3h2 = Fr.mul(Fr.e(len), Fr.exp(roots[0], len - 2)) = = 3 * H3w3_0
+        { Fr::copy(2) }
+        { Fr::copy(0) }
+        { Fr::double(0) }
+        { Fr::add(1, 0) }
+
+        // compute den1 = _3h2 * (xiw - xi)
+        { Fr::fromaltstack() }
+        { Fr::mul() }
+        { Fr::toaltstack() }
+        // [y, H3w3_0, H3w3_1, H3w3_2 |
+        // Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, LiS1_3, LiS1_4, LiS2_1, LiS2_2, LiS2_3, den1]
+
+        // den2 = roots[2 * 0 % 3] = roots[0]
+        { Fr::copy(2) }
+        { Fr::toaltstack() }
+        // [y, H3w3_0, H3w3_1, H3w3_2 |
+        // Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, LiS1_3, LiS1_4, LiS2_1, LiS2_2, LiS2_3, den1, den2]
+
+        // den3 = x - roots[0]
+        { Fr::copy(3) }
+        { Fr::toaltstack() }
+        { Fr::copy(2) }
+        { Fr::fromaltstack() }
+        { Fr::sub(0, 1) }
+        // [y, H3w3_0, H3w3_1, H3w3_2, den3 |
+        // Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, LiS1_3, LiS1_4, LiS2_1, LiS2_2, LiS2_3, den1, den2]
+
+        // LiS3_1 = den1 * den2 * den3, keep den1 in the altstack
+        { Fr::fromaltstack() }
+        { Fr::mul() }
+        { Fr::fromaltstack() }
+        { Fr::copy(0) }
+        { Fr::roll(2) }
+        { Fr::mul() }
+        { Fr::toaltstack() }
+        { Fr::toaltstack() }
+        // [y, H3w3_0, H3w3_1, H3w3_2 |
+        // Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, LiS1_3, LiS1_4, LiS2_1, LiS2_2, LiS2_3, LiS3_1, den1]
+
+        // den2 = roots[2 * 1 % 3] = roots[2]
+        { Fr::copy(0) }
+        { Fr::toaltstack() }
+
+        // den3 = x - roots[1]
+        { Fr::copy(3) }
+        { Fr::toaltstack() }
+        { Fr::copy(1) }
+        { Fr::fromaltstack() }
+        { Fr::sub(0, 1) }
+
+        // LiS3_2 = den1 * den2 * den3, keep den1 in the altstack
+        { Fr::fromaltstack() }
+        { Fr::mul() }
+        { Fr::fromaltstack() }
+        { Fr::copy(0) }
+        { Fr::roll(2) }
+        { Fr::mul() }
+        { Fr::toaltstack() }
+        { Fr::toaltstack() }
+        // [y, H3w3_0, H3w3_1, H3w3_2 |
+        // Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, LiS1_3, LiS1_4, LiS2_1, LiS2_2, LiS2_3, LiS3_1, LiS3_2, den1]
+
+        // den2 = roots[2 * 2 % 3] = roots[1]
+        { Fr::copy(1) }
+        { Fr::toaltstack() }
+
+        // den3 = x - roots[2]
+        { Fr::copy(3) }
+        { Fr::toaltstack() }
+        { Fr::copy(0) }
+        { Fr::fromaltstack() }
+        { Fr::sub(0, 1) }
+
+        // LiS3_3 = den1 * den2 * den3, keep den1 in the altstack
+        { Fr::fromaltstack() }
+        { Fr::mul() }
+        { Fr::fromaltstack() }
+        { Fr::mul() }
+        { Fr::toaltstack() }
+        // [y, H3w3_0, H3w3_1, H3w3_2 |
+        // Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, LiS1_3, LiS1_4, LiS2_1, LiS2_2, LiS2_3, LiS3_1, LiS3_2, LiS3_3]
+
+        // drop H3w3_0, H3w3_1, H3w3_2
+        { Fr::drop() }
+        { Fr::drop() }
+        { Fr::drop() }
+
+        // drop y
+        { Fr::drop() }
+        // [ xi, zh | Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, LiS1_3, LiS1_4, LiS2_1, LiS2_2, LiS2_3, LiS3_1, LiS3_2, LiS3_3]
+
+        // push xi again
+        // { Fr::push_dec("14814634099415170872937750660683266261347419959225231219985478027287965492246") }
+        { Fr::copy(1) }
+        // [ xi | Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, LiS1_3, LiS1_4, LiS2_1, LiS2_2, LiS2_3, LiS3_1, LiS3_2, LiS3_3]
+
+        // Li_i = n * (xi - w^(i - 1))
+        for w_i in &w_powers {
+            { Fr::copy(0) }
+            { Fr::push_dec(w_i) }
+            { Fr::sub(1, 0) }
+            { Fr::push_u32_le(&[1 << power]) }
+            { Fr::mul() }
+            { Fr::toaltstack() }
+        }
+        // [ xi | Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, LiS1_3, LiS1_4, LiS2_1, LiS2_2, LiS2_3, LiS3_1, LiS3_2, LiS3_3, Li_1, Li_2, ...]
+
+
+        // Get all the elements back to the stack
+        for _ in 0..len {
+            { Fr::fromaltstack() }
+        }
+        // [ xi , Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, LiS1_3, LiS1_4, LiS2_1, LiS2_2, LiS2_3, LiS3_1, LiS3_2, LiS3_3, Li_1, Li_2]
+
+        // build up the accumulator
+        { Fr::copy(0) }
+        for i in 1..len {
+            { Fr::copy(0) }
+            // [ xi, Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8,
+            // LiS1_1, LiS1_2, LiS1_3, LiS1_4, LiS2_1, LiS2_2, LiS2_3, LiS3_1, LiS3_2, LiS3_3, Li_1, Li_2, Li_2, Li_2]
+            { Fr::copy(i + 1 + i) }
+            { Fr::mul() }
+            // [ xi, Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8,
+            // LiS1_1, LiS1_2, LiS1_3, LiS1_4, LiS2_1, LiS2_2, LiS2_3, LiS3_1, LiS3_2, LiS3_3, Li_1, Li_2, Li_2, Li_2 * Li_1]
+        }
+
+        // push the inv from the proof and verify the inv
+        { Fr::copy(0) }
+        { inv.clone() }
+        { Fr::copy(0) } { Fr::toaltstack() }
+        { Fr::mul() }
+        { Fr::is_one_keep_element(0) }
+        OP_VERIFY
+        { Fr::drop() } // is_one does not consume the input
+
+        // current stack:
+        //   inputs (Li_2 down to ZH)
+        //   accumulators (ZH down to prod of all)
+        // altstack:
+        //   inv
+
+        // compute the inverses now
+        { Fr::drop() }
+        { Fr::fromaltstack() }
+        // [ Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8,
+        // LiS1_1, LiS1_2, LiS1_3, LiS1_4, LiS2_1, LiS2_2, LiS2_3, LiS3_1, LiS3_2, LiS3_3, Li_1, Li_2, Li_2, Li_2 * Li_1, Li_2 * Li_1 * LiS3_3, ...
+        // inv ]
+
+        for i in 0..len - 1 {
+            { Fr::copy(0) }
+            { Fr::roll(2) }
+            { Fr::mul() }
+            { Fr::toaltstack() }
+            { Fr::roll(len - 1 - i + len - 1 - i) }
+            { Fr::mul() }
+        }
+        { Fr::roll(1) }
+        { Fr::drop() }
+        { Fr::roll(1) }
+        { Fr::drop() }
+        // [ZH | ..., LiS0_3, LiS0_2, LiS0_1, DenH2, DenH1]
+
+        for _ in 0..len - 1 {
+            { Fr::fromaltstack() }
+        }
+        // [..., xi, ZH, DenH1, DenH2, LiS0_1, LiS0_2, LiS0_3, ...]
+    }
+}
+
+/// compute lagranges
+// L_i = w^(i - 1) * zh / Li_i for the inverses of the Li_i on top of the stack
+// [beta, gamma, alpha, y, pH0w8_0, pH0w8_1, pH0w8_2, pH0w8_3, pH0w8_4, pH0w8_5, pH0w8_6, pH0w8_7,
+// pH1w4_0, pH1w4_1, pH1w4_2, pH1w4_3, pH2w3_0, pH2w3_1, pH2w3_2, pH3w3_0, pH3w3_1, pH2w3_2, xi, zh,
+// ZH, DenH1, DenH2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8,
+// LiS1_1, LiS1_2, LiS1_3, LiS1_4, LiS2_1, LiS2_2, LiS2_3, LiS3_1, LiS3_2, LiS3_3, Li_1, Li_2, ...]
+fn compute_lagranges(w: &str, n_public: usize) -> Script {
+    let w_powers = powers(w, n_public.max(1));
+    let n_li = w_powers.len() as u32;
+
+    script! {
+        // push zh
+        { Fr::copy(21 + n_li) }
+        { Fr::toaltstack() }
+
+        for (i, w_i) in w_powers.iter().enumerate() {
+            // multiply the inverse of Li_i by zh
+            { Fr::copy(n_li - 1) }
+            { Fr::fromaltstack() }
+            { Fr::copy(0) }
+            { Fr::toaltstack() }
+            { Fr::mul() }
+            if i > 0 {
+                { Fr::push_dec(w_i) }
+                { Fr::mul() }
+            }
+        }
+
+        { Fr::fromaltstack() }
+        { Fr::drop() }
+    }
+}
+
+/// compute pi = -sum(L_i * input_i)
+// The following steps expect exactly two Li and L, of which only L1 is still used, so that the
+// ones past the second are dropped and zeros fill in for a single one.
+// [..., LiS3_3, Li_1, ..., Li_m, L1, ..., Lm] -> [..., LiS3_3, Li_1, Li_2, L1, L2, PI]
+fn compute_pi(inputs: &[Script]) -> Script {
+    let n_li = inputs.len().max(1) as u32;
+
+    script! {
+        { Fr::push_zero() }
+        for (i, input) in inputs.iter().enumerate() {
+            { Fr::copy(n_li - i as u32) }
+            { input.clone() }
+            { Fr::mul() }
+            { Fr::add(1, 0) }
+        }
+        { Fr::neg(0) }
+        { Fr::toaltstack() }
+
+        if n_li == 1 {
+            { Fr::push_zero() }
+            { Fr::roll(1) }
+            { Fr::push_zero() }
+        }
+        if n_li > 2 {
+            for _ in 2..n_li {
+                { Fr::drop() }
+            }
+            { Fr::toaltstack() }
+            { Fr::toaltstack() }
+            for _ in 2..n_li {
+                { Fr::drop() }
+            }
+            { Fr::fromaltstack() }
+            { Fr::fromaltstack() }
+        }
+        { Fr::fromaltstack() }
+    }
+}
+
+/// compute R0 {50 elements} ql, qr, qo, qm, qc, s1, s2, s3
+// [beta, gamma, alpha, y, pH0w8_0, pH0w8_1, pH0w8_2, pH0w8_3, pH0w8_4, pH0w8_5, pH0w8_6, pH0w8_7,
+// pH1w4_0(37), pH1w4_1, pH1w4_2, pH1w4_3, pH2w3_0, pH2w3_1, pH2w3_2, pH3w3_0, pH3w3_1, pH2w3_2, xi, zh,
+// ZH(25), DenH1, DenH2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8,
+// LiS1_1, LiS1_2, LiS1_3, LiS1_4, LiS2_1, LiS2_2, LiS2_3, LiS3_1, LiS3_2, LiS3_3, Li_1, Li_2, L1, L2, PI]
+fn compute_r0(
+    ql: &Script,
+    qr: &Script,
+    qo: &Script,
+    qm: &Script,
+    qc: &Script,
+    s1: &Script,
+    s2: &Script,
+    s3: &Script,
+) -> Script {
+    script! {
+        // push ql, qr, qo, qm, qc, s1, s2, s3
+        { ql.clone() }
+        { qr.clone() }
+        { qo.clone() }
+        { qm.clone() }
+        { qc.clone() }
+        { s1.clone() }
+        { s2.clone() }
+        { s3.clone() }
+
+        // push H0w8_0, H0w8_1, H0w8_2, H0w8_3, H0w8_4, H0w8_5, H0w8_6, H0w8_7
+        { Fr::copy(53) }
+        { Fr::copy(53) }
+        { Fr::copy(53) }
+        { Fr::copy(53) }
+        { Fr::copy(53) }
+        { Fr::copy(53) }
+        { Fr::copy(53) }
+        { Fr::copy(53) }
+
+        // push lis0_1_inv, ...
+        { Fr::copy(38) }
+        { Fr::copy(38) }
+        { Fr::copy(38) }
+        { Fr::copy(38) }
+        { Fr::copy(38) }
+        { Fr::copy(38) }
+        { Fr::copy(38) }
+        { Fr::copy(38) }
+
+        // push y, xi
+        { Fr::copy(70) }
+        { Fr::copy(52) }
+
+        // compute num = y^8 - xi, push to altstack
+        { Fr::roll(1) }
+        { Fr::square() }
+        { Fr::square() }
+        { Fr::square() }
+        { Fr::sub(0, 1) }
+        { Fr::toaltstack() }
+        // [ql, qr, ...., H0w8_0, H0w8_1, ..., lis0_1_inv, lis0_2_inv, ... | num]
+
+        // pick H0w8_0, ..., H0w8_7 and compute the corresponding c0Value
+        for i in 0..8 {
+            { Fr::copy(8 + 7 - i) }
+
+            { Fr::copy(0) } { Fr::copy(1) } { Fr::mul() }
+            { Fr::copy(0) } { Fr::copy(2) } { Fr::mul() }
+            { Fr::copy(0) } { Fr::copy(3) } { Fr::mul() }
+            { Fr::copy(0) } { Fr::copy(4) } { Fr::mul() }
+            { Fr::copy(0) } { Fr::copy(5) } { Fr::mul() }
+            { Fr::copy(0) } { Fr::copy(6) } { Fr::mul() }
+            // H0w8_0, H0w8_0^2, H0w8_0^3, ...
+
+            for _ in 0..7 {
+                { Fr::toaltstack() }
+            }
+
+            // c0Value starts with ql
+            { Fr::copy(16 + 7) }
+            { Fr::copy(16 + 6 + 1) } { Fr::fromaltstack() } { Fr::mul() } { Fr::add(1, 0) }
+            { Fr::copy(16 + 5 + 1) } { Fr::fromaltstack() } { Fr::mul() } { Fr::add(1, 0) }
+            { Fr::copy(16 + 4 + 1) } { Fr::fromaltstack() } { Fr::mul() } { Fr::add(1, 0) }
+            { Fr::copy(16 + 3 + 1) } { Fr::fromaltstack() } { Fr::mul() } { Fr::add(1, 0) }
+            { Fr::copy(16 + 2 + 1) } { Fr::fromaltstack() } { Fr::mul() } { Fr::add(1, 0) }
+            { Fr::copy(16 + 1 + 1) } { Fr::fromaltstack() } { Fr::mul() } { Fr::add(1, 0) }
+            { Fr::copy(16 + 1) } { Fr::fromaltstack() } { Fr::mul() } { Fr::add(1, 0) }
+
+            // push this c0Value to the altstack
+            { Fr::toaltstack() }
+        }
+
+        // get all the c0Values out
+        for _ in 0..8 {
+            { Fr::fromaltstack() }
+        }
+
+        // multiply the corresponding LiS0Inv
+        for i in 0..8 {
+            { Fr::roll(8 - i + 7 - i) }
+            { Fr::mul() }
+            { Fr::toaltstack() }
+        }
+
+        // drop all the intermediate values
+        for _ in 0..16 {
+            { Fr::drop() }
+        }
+
+        // add all the c0Values together
+        { Fr::fromaltstack() }
+        for _ in 1..8 {
+            { Fr::fromaltstack() }
+            { Fr::add(1, 0) }
+        }
+
+        // multiply by the num
+        { Fr::fromaltstack() }
+        { Fr::mul() }
+    }
+}
+
+/// compute R1 {51 elements} ql, qr, qo, qm, qc, a, b, c
+// [beta, gamma, alpha, y, pH0w8_0, pH0w8_1, pH0w8_2, pH0w8_3, pH0w8_4, pH0w8_5, pH0w8_6, pH0w8_7,
+// pH1w4_0(38), pH1w4_1, pH1w4_2, pH1w4_3, pH2w3_0, pH2w3_1, pH2w3_2, pH3w3_0, pH3w3_1, pH2w3_2, xi, zh,
+// ZH(26), DenH1, DenH2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8,
+// LiS1_1(15), LiS1_2, LiS1_3, LiS1_4, LiS2_1, LiS2_2, LiS2_3, LiS3_1, LiS3_2, LiS3_3, Li_1, Li_2, L1, L2, PI, r0]
+fn compute_r1(
+    ql: &Script,
+    qr: &Script,
+    qm: &Script,
+    qo: &Script,
+    qc: &Script,
+    a: &Script,
+    b: &Script,
+    c: &Script,
+) -> Script {
+    script! {
+
+        { ql.clone() }
+        { qr.clone() }
+        { qm.clone() }
+        { qo.clone() }
+        { qc.clone() }
+        { a.clone() }
+        { b.clone() }
+        { c.clone() }
+        // pi, zh
+        { Fr::copy(9)}
+        { Fr::copy(35)}
+        // pH1w4_0->3
+        { Fr::copy(48)}
+        { Fr::copy(48)}
+        { Fr::copy(48)}
+        { Fr::copy(48)}
+        // LiS1_1 -> 4
+        { Fr::copy(29)}
+        { Fr::copy(29)}
+        { Fr::copy(29)}
+        { Fr::copy(29)}
+        // y, xi
+        { Fr::copy(65)}
+        { Fr::copy(47)}
+        // compute num = y^4 - xi, push to altstack
+        { Fr::roll(1) }
+        { Fr::square() }
+        { Fr::square() }
+        { Fr::sub(0, 1) }
+        { Fr::toaltstack() }
+
+        // compute t0
+
+        // ql * evalA
+        { Fr::copy(10 + 7) }
+        { Fr::copy(10 + 2 + 1) }
+        { Fr::mul() }
+        { Fr::toaltstack() }
+
+        // qr * evalB
+        { Fr::copy(10 + 6) }
+        { Fr::copy(10 + 1 + 1) }
+        { Fr::mul() }
+        { Fr::toaltstack() }
+
+        // qm * evalA * evalB
+        { Fr::copy(10 + 5) }
+        { Fr::copy(10 + 2 + 1) }
+        { Fr::mul() }
+        { Fr::copy(10 + 1 + 1) }
+        { Fr::mul() }
+        { Fr::toaltstack() }
+
+        // qo * evalC
+        { Fr::copy(10 + 4) }
+        { Fr::copy(10 + 1) }
+        { Fr::mul() }
+
+        // t0 := ql * evalA + qr * evalB + qm * evalA * evalB + qo * evalC + qc + pi
+        { Fr::fromaltstack() }
+        { Fr::add(1, 0) }
+        { Fr::fromaltstack() }
+        { Fr::add(1, 0) }
+        { Fr::fromaltstack() }
+        { Fr::add(1, 0) }
+        { Fr::copy(10 + 3 + 1) }
+        { Fr::add(1, 0) }
+        { Fr::copy(8 + 1 + 1) }
+        { Fr::add(1, 0) }
+
+        // t0 := t0 * zhInv
+        { Fr::copy(8 + 1) }
+        { Fr::mul() }
+
+        // the stack should look like:
+        //    ql, qr, qm, qo, qc, a, b, c
+        //    pi, zhInv
+        //    H1w4_0, H1w4_1, H1w4_2, H1w4_3
+        //    LiS1Inv 1-4
+        //    t0
+        //
+        // altstack: num
+
+        // pick H1w4_0, ..., H1w4_3 and compute the corresponding c1Value
+        for i in 0..4 {
+            { Fr::copy(1 + 4 + 3 - i) }
+
+            { Fr::copy(0) } { Fr::copy(1) } { Fr::mul() }
+            { Fr::copy(0) } { Fr::copy(2) } { Fr::mul() }
+
+            for _ in 0..3 {
+                { Fr::toaltstack() }
+            }
+
+            // c1Value starts with a
+            { Fr::copy(1 + 4 + 4 + 2 + 2) }
+            { Fr::copy(1 + 4 + 4 + 2 + 1 + 1) } { Fr::fromaltstack() } { Fr::mul() } { Fr::add(1, 0) }
+            { Fr::copy(1 + 4 + 4 + 2 + 1) } { Fr::fromaltstack() } { Fr::mul() } { Fr::add(1, 0) }
+            { Fr::copy(1) } { Fr::fromaltstack() } { Fr::mul() } { Fr::add(1, 0) }
+
+            // push this c1Value to the altstack
+            { Fr::toaltstack() }
+        }
+
+        // get all the c1Values out
+        for _ in 0..4 {
+            { Fr::fromaltstack() }
+        }
+
+        // multiply the corresponding LiS1Inv
+        for i in 0..4 {
+            { Fr::roll(4 - i + 1 + 3 - i) }
+            { Fr::mul() }
+            { Fr::toaltstack() }
+        }
+
+        // drop all the intermediate values
+        for _ in 0..(1 + 4 + 2 + 8) {
+            { Fr::drop() }
+        }
+
+        // add all the c0Values together
+        { Fr::fromaltstack() }
+        for _ in 1..4 {
+            { Fr::fromaltstack() }
+            { Fr::add(1, 0) }
+        }
+
+        // multiply by the num
+        { Fr::fromaltstack() }
+        { Fr::mul() }
+    }
+}
+
+/// compute R2 {52 elements} a, b, c, z, zw, s1, s2, s3, t1w, t2w
+// [beta(51), gamma, alpha, y, pH0w8_0, pH0w8_1, pH0w8_2, pH0w8_3, pH0w8_4, pH0w8_5, pH0w8_6, pH0w8_7,
+// pH1w4_0(39), pH1w4_1, pH1w4_2, pH1w4_3, pH2w3_0, pH2w3_1, pH2w3_2, pH3w3_0, pH3w3_1, pH2w3_2, xi, zh,
+// ZH(27), DenH1, DenH2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8,
+// LiS1_1(16), LiS1_2, LiS1_3, LiS1_4, LiS2_1, LiS2_2, LiS2_3, LiS3_1, LiS3_2, LiS3_3, Li_1, Li_2, L1, L2, PI, r0, r1]
+fn compute_r2(
+    a: &Script,
+    b: &Script,
+    c: &Script,
+    z: &Script,
+    zw: &Script,
+    s1: &Script,
+    s2: &Script,
+    s3: &Script,
+    t1w: &Script,
+    t2w: &Script,
+    w1: &str,
+    k1: &str,
+    k2: &str,
+) -> Script {
+    script! {
+
+        { a.clone() }
+        { b.clone() }
+        { c.clone() }
+        { z.clone() }
+        { zw.clone() }
+        { s1.clone() }
+        { s2.clone() }
+        { s3.clone() }
+        { t1w.clone() }
+        { t2w.clone() }
+        // beta, y, xi, gamma, zhinv, L[1]
+        { Fr::copy(61)}
+        { Fr::copy(59)}
+        { Fr::copy(41)}
+        { Fr::copy(63)}
+        { Fr::copy(41)}
+        { Fr::copy(19)}
+        // todo push from stack
+        //{ Fr::push_dec("19264250262515049392118907974032894668050943806280011767302681470321758079402") }
+        //  H2w3_0, H2w3_1, H2w3_2, H3w3_0, H3w3_1, H3w3_2 (6 elements)
+        { Fr::copy(51)}
+        { Fr::copy(51)}
+        { Fr::copy(51)}
+        { Fr::copy(51)}
+        { Fr::copy(51)}
+        { Fr::copy(51)}
+        // LiS2Inv 1-6 (6 elements)
+        { Fr::copy(34)}
+        { Fr::copy(34)}
+        { Fr::copy(34)}
+        { Fr::copy(34)}
+        { Fr::copy(34)}
+        { Fr::copy(34)}
+
+        // compute num2 := y^3
+        { Fr::copy(6 + 6 + 4) }
+        { Fr::copy(0) }
+        { Fr::square() }
+        { Fr::mul() }
+
+        // compute num := num2^2 = y^6
+        { Fr::copy(0) }
+        { Fr::square() }
+
+        // compute xi * w1 + xi = xi * (w1 + 1)
+        { Fr::copy(6 + 6 + 3 + 2) }
+        // { Fr::push_dec("11699596668367776675346610687704220591435078791727316319397053191800576917728") }
+        { Fr::push_dec(w1) }
+        { Fr::push_one() }
+        { Fr::add(1, 0) }
+        { Fr::mul() }
+
+        // compute num2 := num2 * (xi * (w1 + 1))
+        { Fr::roll(2) }
+        { Fr::mul() }
+
+        // compute num := num - num2
+        { Fr::sub(1, 0) }
+
+        // compute xi^2 * w1
+        { Fr::copy(6 + 6 + 3 + 1) }
+        { Fr::square() }
+        // { Fr::push_dec("11699596668367776675346610687704220591435078791727316319397053191800576917728") }
+        { Fr::push_dec(w1) }
+        { Fr::mul() }
+
+        // compute num := num +  xi^2 * w1 and move to altstack
+        { Fr::add(1, 0) }
+        { Fr::toaltstack() }
+
+        // compute betaxi
+        { Fr::copy(6 + 6 + 5) }
+        { Fr::copy(6 + 6 + 3 + 1) }
+        { Fr::mul() }
+
+        // compute betaxi + gamma
+        { Fr::copy(0) }
+        { Fr::copy(6 + 6 + 2 + 2) }
+        { Fr::add(1, 0) }
+
+        // compute a + betaxi + gamma and send to altstack
+        { Fr::copy(6 + 6 + 6 + 9 + 2) }
+        { Fr::add(1, 0) }
+        { Fr::toaltstack() }
+
+        // compute betaxi * k1 + gamma
+        { Fr::copy(0) }
+        { Fr::push_dec(k1) }
+        { Fr::mul() }
+        { Fr::copy(6 + 6 + 2 + 2) }
+        { Fr::add(1, 0) }
+
+        // compute b + betaxi * k1 + gamma and send to altstack
+        { Fr::copy(6 + 6 + 6 + 8 + 2) }
+        { Fr::add(1, 0) }
+        { Fr::toaltstack() }
+
+        // compute betaxi * k2 + gamma
+        { Fr::push_dec(k2) }
+        { Fr::mul() }
+        { Fr::copy(6 + 6 + 2 + 1) }
+        { Fr::add(1, 0) }
+
+        // compute c + betaxi * k2 + gamma and send to altstack
+        { Fr::copy(6 + 6 + 6 + 7 + 1) }
+        { Fr::add(1, 0) }
+
+        // compute t2 = (a + betaxi + gamma) * (b + betaxi * k1 + gamma) * (c + betaxi * k2 + gamma) * z
+        { Fr::fromaltstack() }
+        { Fr::mul() }
+        { Fr::fromaltstack() }
+        { Fr::mul() }
+        { Fr::copy(6 + 6 + 6 + 6 + 1) }
+        { Fr::mul() }
+
+        // send t2 to the altstack
+        { Fr::toaltstack() }
+
+        // compute beta * s1 + gamma + a
+        { Fr::copy(6 + 6 + 5) }
+        { Fr::copy(6 + 6 + 6 + 4 + 1) }
+        { Fr::mul() }
+        { Fr::copy(6 + 6 + 2 + 1) }
+        { Fr::add(1, 0) }
+        { Fr::copy(6 + 6 + 6 + 9 + 1) }
+        { Fr::add(1, 0) }
+        { Fr::toaltstack() }
+
+        // compute beta * s2 + gamma + b
+        { Fr::copy(6 + 6 + 5) }
+        { Fr::copy(6 + 6 + 6 + 3 + 1) }
+        { Fr::mul() }
+        { Fr::copy(6 + 6 + 2 + 1) }
+        { Fr::add(1, 0) }
+        { Fr::copy(6 + 6 + 6 + 8 + 1) }
+        { Fr::add(1, 0) }
+        { Fr::toaltstack() }
+
+        // compute beta * s3 + gamma + c
+        { Fr::copy(6 + 6 + 5) }
+        { Fr::copy(6 + 6 + 6 + 2 + 1) }
+        { Fr::mul() }
+        { Fr::copy(6 + 6 + 2 + 1) }
+        { Fr::add(1, 0) }
+        { Fr::copy(6 + 6 + 6 + 7 + 1) }
+        { Fr::add(1, 0) }
+
+        // compute t2' = (beta * s1 + gamma + a) * (beta * s2 + gamma + b) * (beta * s3 + gamma + c) * zw
+        { Fr::fromaltstack() }
+        { Fr::mul() }
+        { Fr::fromaltstack() }
+        { Fr::mul() }
+        { Fr::copy(6 + 6 + 6 + 5 + 1) }
+        { Fr::mul() }
+
+        // compute t2 := t2 - t2'
+        { Fr::fromaltstack() }
+        { Fr::sub(0, 1) }
+
+        // compute t2 := t2 * zhinv
+        { Fr::copy(6 + 6 + 1 + 1) }
+        { Fr::mul() }
+
+        // send the updated t2 to the altstack
+        { Fr::toaltstack() }
+
+        // compute t1 = (z - 1) * L[1] * zhinv
+        { Fr::copy(6 + 6 + 6 + 6) }
+        { Fr::push_one() }
+        { Fr::sub(1, 0) }
+        { Fr::copy(6 + 6 + 1) }
+        { Fr::mul() }
+        { Fr::copy(6 + 6 + 1 + 1) }
+        { Fr::mul() }
+
+        // pull t2 from the altstack
+        { Fr::fromaltstack() }
+
+        // the stack now looks:
+        //   10 + 6 + 6 + 6 Fr elements
+        //   t1
+        //   t2
+        // altstack: num
+
+        // pick H2w3_0, ..., H2w3_2 and compute the corresponding c2Value
+        for i in 0..3 {
+            { Fr::copy(2 + 6 + 5 - i) }
+
+            { Fr::copy(0) } { Fr::square() }
+            { Fr::toaltstack() } { Fr::toaltstack() }
+
+            // c2Value starts with z
+            { Fr::copy(2 + 6 + 6 + 6 + 6) }
+            { Fr::copy(1 + 1) } { Fr::fromaltstack() } { Fr::mul() } { Fr::add(1, 0) }
+            { Fr::copy(1) } { Fr::fromaltstack() } { Fr::mul() } { Fr::add(1, 0) }
+
+            // push this c2Value to the altstack
+            { Fr::toaltstack() }
+        }
+
+        // pick H3w3_0, ..., H3w3_2 and compute the corresponding c2Value
+        for i in 0..3 {
+            { Fr::copy(2 + 6 + 2 - i) }
+
+            { Fr::copy(0) } { Fr::square() }
+            { Fr::toaltstack() } { Fr::toaltstack() }
+
+            // c2Value starts with zw
+            { Fr::copy(2 + 6 + 6 + 6 + 5) }
+            { Fr::copy(2 + 6 + 6 + 6 + 1 + 1) } { Fr::fromaltstack() } { Fr::mul() } { Fr::add(1, 0) }
+            { Fr::copy(2 + 6 + 6 + 6 + 1) } { Fr::fromaltstack() } { Fr::mul() } { Fr::add(1, 0) }
+
+            // push this c2Value to the altstack
+            { Fr::toaltstack() }
+        }
+
+        // get all the c1Values out
+        for _ in 0..6 {
+            { Fr::fromaltstack() }
+        }
+
+        // multiply the corresponding LiS1Inv
+        for i in 0..6 {
+            { Fr::roll(6 - i + 2 + 5 - i) }
+            { Fr::mul() }
+            { Fr::toaltstack() }
+        }
+
+        // drop all the intermediate values
+        for _ in 0..(2 + 6 + 6 + 10) {
+            { Fr::drop() }
+        }
+
+        // add all the c0Values together
+        { Fr::fromaltstack() }
+        for _ in 1..6 {
+            { Fr::fromaltstack() }
+            { Fr::add(1, 0) }
+        }
+
+        // multiply by the num
+        { Fr::fromaltstack() }
+        { Fr::mul() }
+    }
+}
+
+/// compute fej {53 elements}
+// [beta(52), gamma, alpha, y, pH0w8_0, pH0w8_1, pH0w8_2, pH0w8_3, pH0w8_4, pH0w8_5, pH0w8_6, pH0w8_7,
+// pH1w4_0(40), pH1w4_1, pH1w4_2, pH1w4_3, pH2w3_0, pH2w3_1, pH2w3_2, pH3w3_0, pH3w3_1, pH2w3_2, xi, zh,
+// ZH(28), DenH1, DenH2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8,
+// LiS1_1(17), LiS1_2, LiS1_3, LiS1_4, LiS2_1, LiS2_2, LiS2_3, LiS3_1, LiS3_2, LiS3_3, Li_1, Li_2, L1, L2, PI, r0, r1, r2]
+fn compute_fej() -> Script {
+    script! {
+
+        // push alpha, denh1, denh2, y (4 elements)
+        { Fr::copy(50)}
+        { Fr::copy(28)}
+        { Fr::copy(28)}
+        { Fr::copy(52)}
+
+        // push R0, R1, R2 (3 elements)
+        { Fr::copy(6)}
+        { Fr::copy(6)}
+        { Fr::copy(6)}
+
+        // push H0w8_0, H0w8_1, H0w8_2, H0w8_3, H0w8_4, H0w8_5, H0w8_6, H0w8_7 (8 elements)
+        { Fr::copy(55)}
+        { Fr::copy(55)}
+        { Fr::copy(55)}
+        { Fr::copy(55)}
+        { Fr::copy(55)}
+        { Fr::copy(55)}
+        { Fr::copy(55)}
+        { Fr::copy(55)}
+
+        // roll y
+        { Fr::roll(8 + 3) }
+
+        // compute numerator entries
+        for i in 0..8 {
+            { Fr::copy(0) }
+            { Fr::roll(7 - i + 2) }
+            { Fr::sub(1, 0) }
+            { Fr::toaltstack() }
+        }
+
+        // drop y
+        { Fr::drop() }
+
+        // compute numerator
+        { Fr::fromaltstack() }
+        for _ in 0..7 {
+            { Fr::fromaltstack() }
+            { Fr::mul() }
+        }
+
+        // copy the numerator in the altstack
+        { Fr::copy(0) }
+        { Fr::toaltstack() }
+
+        // compute quotient1 = alpha * numerator * denh1
+        { Fr::copy(0) }
+        { Fr::copy(3 + 2 + 2) }
+        { Fr::mul() }
+        { Fr::roll(3 + 1 + 2) }
+        { Fr::mul() }
+
+        // compute quotient2 = alpha * alpha * numerator * denh2
+        { Fr::roll(1) }
+        { Fr::roll(3 + 2) }
+        { Fr::mul() }
+        { Fr::roll(3 + 2) }
+        { Fr::square() }
+        { Fr::mul() }
+
+        // the stack now looks:
+        //    R0, R1, R2
+        //    quotient1, quotient2
+        // altstack: numerator
+
+        // compute the scalar = R0 + quotient1 * R1 + quotient2 * R2
+        { Fr::copy(1) }
+        { Fr::roll(2 + 1 + 1) }
+        { Fr::mul() }
+        { Fr::copy(1) }
+        { Fr::roll(2 + 2) }
+        { Fr::mul() }
+        { Fr::add(1, 0) }
+        { Fr::roll(2 + 1) }
+        { Fr::add(1, 0) }
+
+        { Fr::fromaltstack() }
+
+        // Drop useless elements, only reserve y
+        // ... ] } [scalar_j, scalar_e, scalar_f2, scalar_f1]
+        { Fr::toaltstack() }
+        { Fr::toaltstack() }
+        { Fr::toaltstack() }
+        { Fr::toaltstack() }
+        // ... y ] } [scalar_j, scalar_e, scalar_f2, scalar_f1]
+        { Fr::copy(49) }
+
+        // ... ] } [scalar_j, scalar_e, scalar_f2, scalar_f1, y]
+        { Fr::toaltstack() }
+
+        for _ in 0..53 {
+
+            {Fr::drop()}
+        }
+
+        // [ y, scalar_f1, scalar_f2, scalar_e, scalar_j ] }
+        { Fr::fromaltstack() }
+        { Fr::fromaltstack() }
+        { Fr::fromaltstack() }
+        { Fr::fromaltstack() }
+        { Fr::fromaltstack() }
+    }
+}
+
+/// compute f (5)
+//[ y scalar_f1, scalar_f2, scalar_e, scalar_j]
+fn compute_f_opt(c0x: &str, c0y: &str, c1: &Script, c2: &Script) -> Script {
+    script! {
+
+        // push quotient1, quotient2 (2 elements)
+        { Fr::copy(3)}
+        { Fr::copy(3)}
+
+        { Fr::toaltstack() }
+        { Fr::toaltstack() }
+
+        // push (C0x, C0y), C1, C2 (9 elements)
+        { Fq::push_dec(c0x)}
+        { Fq::push_dec(c0y)}
+        { Fq::push_one() }
+        { c1.clone() }
+        { Fq::push_one() }
+        { c2.clone() }
+        { Fq::push_one() }
+
+        { G1Projective::roll(1) } // [c0, c2, c1, q1; q2]
+        { Fr::fromaltstack() }
+        { Fq::roll(6)} {Fq::roll(6)} {Fq::roll(6)} // [c0, c1, q1, c2]
+        { Fr::fromaltstack() } // [c0, c1, q1, c2, q2]
+        { G1Projective::batched_scalar_mul::<2>() }
+        { G1Projective::add() }
+
+    }
+}
+
 #[allow(non_snake_case)]
 #[cfg(test)]
 mod test {
@@ -2875,3 +5334,156 @@ mod test {
         assert!(exec_result.success);
     }
 }
+
+#[cfg(test)]
+mod verifier_test {
+    use super::*;
+    use crate::bigint::U254;
+    use crate::fflonk::snarkjs;
+    use crate::{
+        execute_script_as_chunks, execute_script_with_witness_without_stack_limit,
+        execute_script_without_stack_limit,
+    };
+    use ark_ec::CurveGroup;
+    use num_traits::Num;
+    use std::str::FromStr;
+
+    fn circom_ref() -> (VerifyingKey, Proof, Vec<ark_bn254::Fr>) {
+        (
+            snarkjs::load_verifying_key(include_str!("circom_ref/verification_key.json")).unwrap(),
+            snarkjs::load_proof(include_str!("circom_ref/proof.json")).unwrap(),
+            snarkjs::load_public_inputs(include_str!("circom_ref/public.json")).unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_fflonk_verify_proof() {
+        let (vk, proof, public_inputs) = circom_ref();
+
+        let script = Verifier::verify_proof(&vk, &proof, &public_inputs).unwrap();
+        println!("fflonk.verify_proof = {} bytes", script.len());
+        // the witness stays on the stack until A1 is computed
+        let exec_result = execute_script_without_stack_limit(script);
+        println!("{}", exec_result);
+        assert!(exec_result.success);
+    }
+
+    #[test]
+    fn test_fflonk_verify_proof_as_chunks() {
+        let (vk, proof, public_inputs) = circom_ref();
+
+        let script = Verifier::verify_proof(&vk, &proof, &public_inputs).unwrap();
+        println!("fflonk.verify_proof = {} bytes", script.len());
+        let interval = script.max_op_if_interval();
+        println!("Max if interval: {:?} difference: {}, debug info: {}, {}", interval, interval.1 - interval.0, script.debug_info(interval.0), script.debug_info(interval.1));
+        let exec_result = execute_script_as_chunks(script, 3_000_000, 2_000_000);
+        println!("{}", exec_result);
+        assert!(exec_result.success);
+    }
+
+    #[test]
+    fn test_fflonk_max_public_inputs() {
+        let (mut vk, proof, _) = circom_ref();
+
+        // the transcript of beta is 64 + 32 * n_public bytes, blake3 hashes at most 512
+        vk.n_public = Verifier::MAX_PUBLIC;
+        assert!(Verifier::locking_script(&vk).is_ok());
+
+        vk.n_public = Verifier::MAX_PUBLIC + 1;
+        assert_eq!(
+            Verifier::locking_script(&vk).unwrap_err(),
+            "Expected at most 14 public inputs, got 15"
+        );
+        let public_inputs = vec![ark_bn254::Fr::ONE; Verifier::MAX_PUBLIC + 1];
+        assert!(Verifier::witness(&vk, &proof, &public_inputs).is_err());
+    }
+
+    #[test]
+    fn test_fflonk_verifier_api() {
+        let (vk, proof, public_inputs) = circom_ref();
+
+        let script = Verifier::locking_script(&vk).unwrap();
+        println!("fflonk.locking_script = {} bytes", script.len());
+
+        let witness = Verifier::witness(&vk, &proof, &public_inputs).unwrap();
+        let exec_result = execute_script_with_witness_without_stack_limit(script, witness);
+        assert!(exec_result.success);
+
+        // the witness is generated for the given public inputs only
+        let mut other_inputs = public_inputs.clone();
+        other_inputs[0] += ark_bn254::Fr::ONE;
+        assert!(Verifier::witness(&vk, &proof, &other_inputs).is_err());
+        assert!(Verifier::witness(&vk, &proof, &public_inputs[1..]).is_err());
+    }
+
+    #[test]
+    fn test_fflonk_native_a1() {
+        let (vk, proof, public_inputs) = circom_ref();
+
+        // the values the script computes for circom_ref
+        let challenges = Challenges::new(&vk, &proof, &public_inputs);
+        assert_eq!(
+            challenges.xiseed,
+            ark_bn254::Fr::from_str(
+                "12675309311304482509247823029963782393309524866265275290730041635615278736000"
+            )
+            .unwrap()
+        );
+        let a1 = ark_bn254::G1Projective::new(
+            ark_bn254::Fq::from_str(
+                "21025932300722401404248737517866966587837387913191004025854702115722286998035",
+            )
+            .unwrap(),
+            ark_bn254::Fq::from_str(
+                "5748766770337880144484917096976043621609890780406924686031233755006782215858",
+            )
+            .unwrap(),
+            ark_bn254::Fq::from_str(
+                "18747233771850556311508953762939425433543524671221692065979284256379095132287",
+            )
+            .unwrap(),
+        );
+        assert_eq!(
+            compute_a1_native(&vk, &proof, &public_inputs, &challenges).unwrap(),
+            a1.into_affine()
+        );
+
+        // inv must be the inverse of the denominators
+        let mut invalid_proof = proof.clone();
+        invalid_proof.inv += ark_bn254::Fr::ONE;
+        assert!(compute_a1_native(&vk, &invalid_proof, &public_inputs, &challenges).is_err());
+    }
+
+    #[test]
+    fn test_fflonk_locking_script_rejects_invalid_witness() {
+        let (vk, proof, public_inputs) = circom_ref();
+
+        let locking_script = Verifier::locking_script(&vk).unwrap();
+        let witness = Verifier::witness(&vk, &proof, &public_inputs).unwrap();
+        // the witness starts with the two public inputs (18 items) and C1 (18 items)
+        let c1_start = 18;
+
+        // the public input isn't reduced modulo r
+        let r = BigUint::from_str_radix(Fr::MODULUS, 16).unwrap();
+        let unreduced = U254::witness_u32_le(&r.to_u32_digits());
+        let invalid_witness = [&unreduced[..], &witness[9..]].concat();
+        let exec_result = execute_script_with_witness_without_stack_limit(
+            locking_script.clone(),
+            invalid_witness,
+        );
+        assert!(!exec_result.success);
+
+        // C1 isn't on the curve
+        let off_curve =
+            ark_bn254::G1Affine::new_unchecked(proof.c1.x + ark_bn254::Fq::ONE, proof.c1.y);
+        let invalid_witness = [
+            &witness[..c1_start],
+            &utils::g1_affine_witness(off_curve)[..],
+            &witness[c1_start + 18..],
+        ]
+        .concat();
+        let exec_result =
+            execute_script_with_witness_without_stack_limit(locking_script, invalid_witness);
+        assert!(!exec_result.success);
+    }
+}
@@ -0,0 +1,450 @@
+use crate::bn254::curves::{G1Affine, G1Projective};
+use crate::bn254::fp254impl::Fp254Impl;
+use crate::bn254::fq::Fq;
+use crate::bn254::fq2::Fq2;
+use crate::bn254::fr::Fr;
+use crate::bn254::g2::G2Affine;
+use crate::bn254::pairing_check::{FinalExponentiation, PairingCheck};
+use crate::bn254::utils;
+use crate::groth16::verifier::Verifier;
+use crate::hash::blake3::blake3_var_length;
+use crate::treepp::{script, Script};
+use ark_bn254::{Bn254, G1Projective as ArkG1Projective};
+use ark_ec::pairing::Pairing as ark_Pairing;
+use ark_ec::{AffineRepr, CurveGroup, VariableBaseMSM};
+use ark_ff::Field;
+use ark_groth16::{Proof, VerifyingKey};
+use num_bigint::BigUint;
+
+/// Verifies k Groth16 proofs for the same verifying key with a single pairing check.
+///
+/// With coefficients r_i = rho^i, where rho is a blake3 hash of all the proofs and public inputs
+/// computed in script, the k equations e(A_i, B_i) = e(alpha, beta) * e(L_i, gamma) * e(C_i, delta)
+/// are batched into
+///
+/// e(sum(r_i * L_i), -gamma) * e(sum(r_i * C_i), -delta) * e(sum(r_i) * alpha, -beta)
+///     * e(r_0 * A_0, B_0) * ... * e(r_{k-1} * A_{k-1}, B_{k-1}) = 1
+///
+/// which is a `PairingCheck` with three fixed and k variable G2 points.
+#[derive(Clone, Copy, Debug)]
+pub struct BatchVerifier;
+
+impl BatchVerifier {
+    /// Script verifying any `num_proofs` proofs for `vk`, the proofs are provided by
+    /// `BatchVerifier::witness`.
+    ///
+    /// Input stack: [public_inputs_0, C_0, A_0, ..., public_inputs_{k-1}, C_{k-1}, A_{k-1},
+    ///               B_0, ..., B_{k-1}, c, c_inv, wi, L(B_0, ..., B_{k-1})]
+    /// c, c_inv and wi are left out with `FinalExponentiation::Full`.
+    pub fn locking_script(
+        vk: &VerifyingKey<Bn254>,
+        num_proofs: usize,
+        final_exponentiation: FinalExponentiation,
+    ) -> Script {
+        assert!(num_proofs > 0, "no proof to verify");
+        let num_public_inputs = vk.gamma_abc_g1.len() - 1;
+
+        let check = Self::pairing_check(vk, num_proofs, final_exponentiation);
+        let (l, k) = (num_public_inputs as u32, num_proofs as u32);
+        let num_hint_elements = check.num_hint_elements();
+        // public inputs, C and A of a proof
+        let e = l + 4;
+        let bases = vk
+            .gamma_abc_g1
+            .iter()
+            .map(|base| base.into_group())
+            .collect::<Vec<_>>();
+
+        script! {
+            // the coefficients are only binding for valid points and scalars
+            { check_witness(l, k, num_hint_elements - 4 * k) }
+            { transcript(l, k, num_hint_elements - 4 * k) }
+
+            // move the hints out of the way
+            { Fr::toaltstack() }
+            for _ in 0..num_hint_elements {
+                { Fq::toaltstack() }
+            }
+            { Fr::fromaltstack() }
+
+            // coefficients r_i = rho^i for i > 0, r_0 = 1 is implicit
+            for j in 2..k {
+                { Fr::copy(0) }
+                { Fr::copy(j - 1) }
+                { Fr::mul() }
+            }
+            if k == 1 {
+                { Fr::drop() }
+            }
+
+            // s_0 = sum(r_i), under the coefficients
+            { Fr::push_one() }
+            for j in 1..k {
+                { Fr::copy(k - j) }
+                { Fr::add(1, 0) }
+            }
+            for _ in 1..k {
+                { Fr::roll(k - 1) }
+            }
+
+            // s_j = sum(r_i * public_inputs_i[j - 1]) and sum(r_i * C_i)
+            for _ in 0..l {
+                { Fr::push_zero() }
+            }
+            { G1Projective::push_zero() }
+            // stack: [public_inputs_0, C_0, A_0, ..., s_0, r_1, ..., r_{k-1}, s_1, ..., s_l, sum(r_i * C_i)]
+
+            // the proofs from the last one, whose coefficient is on top of the others
+            for i in (0..k).rev() {
+                for _ in 0..e {
+                    { Fq::roll(i + l + 4 + e - 1) }
+                }
+                // stack: [..., r_i, s_1, ..., s_l, sum(r_i * C_i), public_inputs_i, C_i, A_i]
+
+                // r_i * A_i, kept on altstack
+                if i > 0 {
+                    { G1Affine::into_projective() }
+                    { Fr::copy(2 * l + 8) }
+                    { G1Projective::scalar_mul() }
+                    { G1Projective::into_affine() }
+                }
+                { Fq::toaltstack() }
+                { Fq::toaltstack() }
+
+                // r_i * C_i
+                { G1Affine::into_projective() }
+                if i > 0 {
+                    { Fr::copy(2 * l + 6) }
+                    { G1Projective::scalar_mul() }
+                }
+                for _ in 0..3 {
+                    { Fq::roll(l + 5) }
+                }
+                { G1Projective::add() }
+                { G1Projective::toaltstack() }
+
+                // r_i * public_inputs_i[j]
+                for j in 0..l {
+                    { Fr::roll(l - 1) }
+                    if i > 0 {
+                        { Fr::copy(2 * l - j) }
+                        { Fr::mul() }
+                    }
+                    { Fr::add(2 * l - j - 1, 0) }
+                }
+                { G1Projective::fromaltstack() }
+
+                if i > 0 {
+                    { Fr::roll(l + 3) }
+                    { Fr::drop() }
+                }
+            }
+            // stack: [s_0, s_1, ..., s_l, sum(r_i * C_i) | r_0 * A_0, ..., r_{k-1} * A_{k-1}, hints]
+
+            { G1Projective::into_affine() }
+            { Fr::copy(l + 2) }
+            { G1Projective::scalar_mul_by_constant_g1(vk.alpha_g1.into_group()) }
+            { G1Projective::into_affine() }
+            for _ in 0..4 {
+                { Fq::toaltstack() }
+            }
+
+            // sum(r_i * L_i) = s_0 * gamma_abc_g1[0] + ... + s_l * gamma_abc_g1[l]
+            for _ in 0..l {
+                { Fr::toaltstack() }
+            }
+            { G1Projective::scalar_mul_by_constant_g1(bases[0]) }
+            for base in bases.iter().skip(1) {
+                { Fr::fromaltstack() }
+                { G1Projective::scalar_mul_by_constant_g1(*base) }
+                { G1Projective::add() }
+            }
+            { G1Projective::into_affine() }
+
+            for _ in 0..4 + 2 * k + num_hint_elements {
+                { Fq::fromaltstack() }
+            }
+            // stack: [sum(r_i * L_i), sum(r_i * C_i), sum(r_i) * alpha, r_0 * A_0, ..., r_{k-1} * A_{k-1},
+            //         B_0, ..., B_{k-1}, c, c_inv, wi, L(B_0, ..., B_{k-1})]
+
+            { check.script() }
+            OP_TRUE
+        }
+    }
+
+    /// Sizes in bytes of the locking script for `num_proofs` proofs and of `num_proofs` separate
+    /// `Verifier::locking_script`s, the difference is what batching saves.
+    pub fn size_comparison(
+        vk: &VerifyingKey<Bn254>,
+        num_proofs: usize,
+        final_exponentiation: FinalExponentiation,
+    ) -> (usize, usize) {
+        (
+            Self::locking_script(vk, num_proofs, final_exponentiation).len(),
+            num_proofs * Verifier::locking_script(vk, final_exponentiation).len(),
+        )
+    }
+
+    /// Stack items satisfying `BatchVerifier::locking_script(vk, proofs.len(),
+    /// final_exponentiation)` for the proofs, first item at the bottom. Fails unless every proof
+    /// is valid for its public inputs.
+    pub fn witness(
+        proofs: &[Proof<Bn254>],
+        public_inputs: &[Vec<<Bn254 as ark_Pairing>::ScalarField>],
+        vk: &VerifyingKey<Bn254>,
+        final_exponentiation: FinalExponentiation,
+    ) -> Result<Vec<Vec<u8>>, String> {
+        if proofs.len() != public_inputs.len() {
+            return Err(format!(
+                "Expected the public inputs of {} proofs, got {}",
+                proofs.len(),
+                public_inputs.len()
+            ));
+        }
+        for inputs in public_inputs {
+            if inputs.len() + 1 != vk.gamma_abc_g1.len() {
+                return Err(format!(
+                    "Expected {} public inputs, got {}",
+                    vk.gamma_abc_g1.len() - 1,
+                    inputs.len()
+                ));
+            }
+        }
+
+        let check = Self::pairing_check(vk, proofs.len(), final_exponentiation);
+        let points = Self::points(proofs, public_inputs, vk);
+        let b = proofs.iter().map(|proof| proof.b).collect::<Vec<_>>();
+
+        Ok([
+            proofs
+                .iter()
+                .zip(public_inputs)
+                .flat_map(|(proof, inputs)| {
+                    [
+                        inputs
+                            .iter()
+                            .flat_map(|input| {
+                                Fr::witness_u32_le(&BigUint::from(*input).to_u32_digits())
+                            })
+                            .collect(),
+                        utils::g1_affine_witness(proof.c),
+                        utils::g1_affine_witness(proof.a),
+                    ]
+                    .concat()
+                })
+                .collect(),
+            check.hints_witness(&points, &b)?,
+        ]
+        .concat())
+    }
+
+    /// The coefficients r_i of the proofs, as derived by the locking script, computed natively.
+    pub fn coefficients(
+        proofs: &[Proof<Bn254>],
+        public_inputs: &[Vec<<Bn254 as ark_Pairing>::ScalarField>],
+        vk: &VerifyingKey<Bn254>,
+    ) -> Vec<<Bn254 as ark_Pairing>::ScalarField> {
+        assert_eq!(
+            proofs.len(),
+            public_inputs.len(),
+            "wrong number of public inputs"
+        );
+        for inputs in public_inputs {
+            assert_eq!(
+                inputs.len() + 1,
+                vk.gamma_abc_g1.len(),
+                "wrong number of public inputs"
+            );
+        }
+
+        // chunks of the words of the proofs in the order they are pushed by `transcript`, the
+        // topmost bytes of the stack come first in the hashed message
+        let chunks = proofs
+            .iter()
+            .zip(public_inputs)
+            .flat_map(|(proof, inputs)| {
+                [
+                    inputs
+                        .iter()
+                        .map(|input| utils::fr_be_bytes(*input))
+                        .collect(),
+                    vec![
+                        utils::g1_compressed_bytes(proof.c),
+                        utils::g1_compressed_bytes(proof.a),
+                        utils::fq_be_bytes(proof.b.x.c0),
+                        utils::fq_be_bytes(proof.b.x.c1),
+                        utils::fq_be_bytes(proof.b.y.c0),
+                        utils::fq_be_bytes(proof.b.y.c1),
+                    ],
+                ]
+                .concat()
+                .chunks(TRANSCRIPT_CHUNK_WORDS)
+                .map(|chunk| chunk.to_vec())
+                .collect::<Vec<_>>()
+            });
+        let rho = chunks.fold(None, |h, chunk| {
+            let message = [
+                chunk.into_iter().rev().flatten().collect::<Vec<_>>(),
+                h.map_or(vec![], utils::fr_be_bytes),
+            ]
+            .concat();
+            Some(utils::hash_to_fr(&message))
+        });
+
+        match rho {
+            Some(rho) => (0..proofs.len()).map(|i| rho.pow([i as u64])).collect(),
+            None => vec![],
+        }
+    }
+
+    // The G1 points of the pairing check: sum(r_i * L_i), sum(r_i * C_i), sum(r_i) * alpha and
+    // r_i * A_i.
+    fn points(
+        proofs: &[Proof<Bn254>],
+        public_inputs: &[Vec<<Bn254 as ark_Pairing>::ScalarField>],
+        vk: &VerifyingKey<Bn254>,
+    ) -> Vec<ark_bn254::G1Affine> {
+        let r = Self::coefficients(proofs, public_inputs, vk);
+
+        let scalars = (0..vk.gamma_abc_g1.len())
+            .map(|j| {
+                r.iter()
+                    .zip(public_inputs)
+                    .map(|(r, inputs)| if j == 0 { *r } else { *r * inputs[j - 1] })
+                    .sum::<ark_bn254::Fr>()
+            })
+            .collect::<Vec<_>>();
+        let sum_l =
+            ArkG1Projective::msm(&vk.gamma_abc_g1, &scalars).expect("failed to calculate msm");
+        let sum_c = r
+            .iter()
+            .zip(proofs)
+            .map(|(r, proof)| proof.c * r)
+            .sum::<ArkG1Projective>();
+        let alpha = vk.alpha_g1 * scalars[0];
+
+        [
+            vec![
+                sum_l.into_affine(),
+                sum_c.into_affine(),
+                alpha.into_affine(),
+            ],
+            r.iter()
+                .zip(proofs)
+                .map(|(r, proof)| (proof.a * r).into_affine())
+                .collect(),
+        ]
+        .concat()
+    }
+
+    fn pairing_check(
+        vk: &VerifyingKey<Bn254>,
+        num_proofs: usize,
+        final_exponentiation: FinalExponentiation,
+    ) -> PairingCheck {
+        PairingCheck::new(vec![-vk.gamma_g2, -vk.delta_g2, -vk.beta_g2], num_proofs)
+            .with_final_exponentiation(final_exponentiation)
+    }
+}
+
+// Number of 32-byte words of a proof hashed along with the previous hash by one
+// `blake3_var_length` call of the transcript, which takes at most 512 bytes.
+const TRANSCRIPT_CHUNK_WORDS: usize = 15;
+
+// Checks that the public inputs of the proofs are field elements, that every A_i and C_i is on G1
+// and that every B_i is on G2, in the subgroup of order r. The line coefficients of the B_i are
+// checked by the miller loop.
+//
+// Input stack: [public_inputs_0, C_0, A_0, ..., public_inputs_{k-1}, C_{k-1}, A_{k-1},
+//               B_0, ..., B_{k-1}, X] with X made of `num_elements_above` field elements
+// Output stack: [public_inputs_0, C_0, A_0, ..., B_{k-1}, X]
+fn check_witness(num_public_inputs: u32, num_proofs: u32, num_elements_above: u32) -> Script {
+    let (l, k) = (num_public_inputs, num_proofs);
+    let e = l + 4;
+    // indices of the topmost element of B_i and of A_i
+    let b_index = |i: u32| num_elements_above + 4 * (k - 1 - i);
+    let proof_index = |i: u32| num_elements_above + 4 * k + (k - 1 - i) * e;
+
+    script! {
+        for i in 0..k {
+            for _ in 0..4 {
+                { Fq::copy(b_index(i) + 3) }
+            }
+            { G2Affine::is_valid() }
+            OP_VERIFY
+        }
+        for i in 0..k {
+            // A_i and C_i
+            for j in 0..2 {
+                { Fq2::copy(proof_index(i) + 2 * j) }
+                { G1Affine::is_valid() }
+                OP_VERIFY
+            }
+            for j in 0..l {
+                { Fr::copy(proof_index(i) + 4 + j) }
+                { Fr::is_canonical() }
+                OP_VERIFY
+            }
+        }
+    }
+}
+
+// Fiat-Shamir transcript of the proofs. The words of a proof, its public inputs, C, A and B with
+// the field elements as big-endian bytes and C, A compressed, are hashed in chunks of at most
+// `TRANSCRIPT_CHUNK_WORDS` words, each one along with the previous hash h: h = blake3(h, chunk),
+// and rho is the last h. As `blake3_var_length` hashes the bytes from the top of the stack, the
+// message is actually the words of the chunk in reverse order, then h.
+//
+// Input stack: [public_inputs_0, C_0, A_0, ..., public_inputs_{k-1}, C_{k-1}, A_{k-1},
+//               B_0, ..., B_{k-1}, X] with X made of `num_elements_above` field elements
+// Output stack: [public_inputs_0, C_0, A_0, ..., B_{k-1}, X, rho]
+fn transcript(num_public_inputs: u32, num_proofs: u32, num_elements_above: u32) -> Script {
+    let (l, k) = (num_public_inputs, num_proofs);
+    let e = l + 4;
+    // indices of the deepest element of public_inputs_i and B_i, with h on top if i > 0
+    let proof_index =
+        |i: u32| (i > 0) as u32 + num_elements_above + 4 * k + (k - 1 - i) * e + e - 1;
+    let b_index = |i: u32| (i > 0) as u32 + num_elements_above + 4 * (k - 1 - i) + 3;
+    let chunks = (0..l as usize + 6)
+        .collect::<Vec<_>>()
+        .chunks(TRANSCRIPT_CHUNK_WORDS)
+        .map(|chunk| chunk.to_vec())
+        .collect::<Vec<_>>();
+
+    script! {
+        for i in 0..k {
+            for _ in 0..e {
+                { Fq::copy(proof_index(i)) }
+            }
+            for _ in 0..4 {
+                { Fq::copy(b_index(i) + e) }
+            }
+            // stack: [..., h, public_inputs_i, C_i, A_i, B_i]
+
+            for _ in 0..e + 4 {
+                { Fq::toaltstack() }
+            }
+            for (j, chunk) in chunks.iter().enumerate() {
+                if i > 0 || j > 0 {
+                    { Fr::convert_to_be_bytes() }
+                }
+                for word in chunk.iter().map(|word| *word as u32) {
+                    if word < l {
+                        { Fr::fromaltstack() }
+                        { Fr::convert_to_be_bytes() }
+                    } else if word < l + 2 {
+                        { Fq::fromaltstack() }
+                        { Fq::fromaltstack() }
+                        { G1Affine::convert_to_compressed() }
+                    } else {
+                        { Fq::fromaltstack() }
+                        { Fq::convert_to_be_bytes() }
+                    }
+                }
+
+                { blake3_var_length(32 * ((i > 0 || j > 0) as usize + chunk.len())) }
+                { Fr::from_hash() }
+            }
+        }
+    }
+}
@@ -0,0 +1,282 @@
+// Loaders for the JSON encoding of gnark's BN254 Groth16 proofs and verifying keys, as written by
+// `json.Marshal` on `groth16_bn254.Proof` and `groth16_bn254.VerifyingKey`.
+//
+// Points are affine, the point at infinity is (0, 0). Field elements are decimal strings, except
+// for small values which gnark writes as JSON numbers.
+use crate::groth16::snarkjs::parse_field;
+use ark_bn254::Bn254;
+use ark_ec::AffineRepr;
+use ark_groth16::{Proof, VerifyingKey};
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Element {
+    String(String),
+    Number(serde_json::Number),
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct G1 {
+    x: Element,
+    y: Element,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct E2 {
+    a0: Element,
+    a1: Element,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct G2 {
+    x: E2,
+    y: E2,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct GnarkProof {
+    ar: G1,
+    bs: G2,
+    krs: G1,
+    #[serde(default)]
+    commitments: Vec<G1>,
+    commitment_pok: Option<G1>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct GnarkVerifyingKeyG1 {
+    alpha: G1,
+    k: Vec<G1>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct GnarkVerifyingKeyG2 {
+    beta: G2,
+    gamma: G2,
+    delta: G2,
+}
+
+#[derive(Deserialize)]
+struct GnarkVerifyingKey {
+    #[serde(rename = "G1")]
+    g1: GnarkVerifyingKeyG1,
+    #[serde(rename = "G2")]
+    g2: GnarkVerifyingKeyG2,
+    #[serde(rename = "CommitmentKeys", default)]
+    commitment_keys: Vec<serde_json::Value>,
+}
+
+/// Loads a BN254 Groth16 proof exported by gnark. Proofs with commitments, which gnark adds for
+/// `api.Commit`, are rejected since `Verifier` doesn't check them.
+pub fn load_proof(json: &str) -> Result<Proof<Bn254>, String> {
+    let proof: GnarkProof =
+        serde_json::from_str(json).map_err(|err| format!("Invalid gnark proof: {}", err))?;
+    let commitment_pok = match &proof.commitment_pok {
+        Some(point) => parse_g1(point)?,
+        None => ark_bn254::G1Affine::zero(),
+    };
+    if !proof.commitments.is_empty() || !commitment_pok.is_zero() {
+        return Err("Proofs with commitments aren't supported".to_string());
+    }
+
+    Ok(Proof {
+        a: parse_g1(&proof.ar)?,
+        b: parse_g2(&proof.bs)?,
+        c: parse_g1(&proof.krs)?,
+    })
+}
+
+/// Loads a BN254 Groth16 verifying key exported by gnark, rejected if it has commitment keys.
+pub fn load_verifying_key(json: &str) -> Result<VerifyingKey<Bn254>, String> {
+    let vk: GnarkVerifyingKey = serde_json::from_str(json)
+        .map_err(|err| format!("Invalid gnark verifying key: {}", err))?;
+    if vk.g1.k.is_empty() {
+        return Err("Verifying key has no K points".to_string());
+    }
+    if !vk.commitment_keys.is_empty() {
+        return Err("Verifying keys with commitment keys aren't supported".to_string());
+    }
+
+    Ok(VerifyingKey {
+        alpha_g1: parse_g1(&vk.g1.alpha)?,
+        beta_g2: parse_g2(&vk.g2.beta)?,
+        gamma_g2: parse_g2(&vk.g2.gamma)?,
+        delta_g2: parse_g2(&vk.g2.delta)?,
+        gamma_abc_g1: vk
+            .g1
+            .k
+            .iter()
+            .map(parse_g1)
+            .collect::<Result<Vec<_>, _>>()?,
+    })
+}
+
+fn parse_element<F: std::str::FromStr>(element: &Element) -> Result<F, String> {
+    match element {
+        Element::String(value) => parse_field(value),
+        Element::Number(value) => match value.as_u64() {
+            Some(value) => parse_field(&value.to_string()),
+            None => Err(format!("Invalid field element: {}", value)),
+        },
+    }
+}
+
+fn parse_g1(point: &G1) -> Result<ark_bn254::G1Affine, String> {
+    let (x, y) = (parse_element(&point.x)?, parse_element(&point.y)?);
+    if x == ark_bn254::Fq::from(0_u64) && y == ark_bn254::Fq::from(0_u64) {
+        return Ok(ark_bn254::G1Affine::zero());
+    }
+    let point = ark_bn254::G1Affine::new_unchecked(x, y);
+    if !point.is_on_curve() || !point.is_in_correct_subgroup_assuming_on_curve() {
+        return Err(format!("G1 point ({}, {}) isn't on the curve", x, y));
+    }
+    Ok(point)
+}
+
+fn parse_g2(point: &G2) -> Result<ark_bn254::G2Affine, String> {
+    let parse_e2 = |value: &E2| -> Result<ark_bn254::Fq2, String> {
+        Ok(ark_bn254::Fq2::new(
+            parse_element(&value.a0)?,
+            parse_element(&value.a1)?,
+        ))
+    };
+    let (x, y) = (parse_e2(&point.x)?, parse_e2(&point.y)?);
+    if x == ark_bn254::Fq2::from(0_u64) && y == ark_bn254::Fq2::from(0_u64) {
+        return Ok(ark_bn254::G2Affine::zero());
+    }
+    let point = ark_bn254::G2Affine::new_unchecked(x, y);
+    if !point.is_on_curve() || !point.is_in_correct_subgroup_assuming_on_curve() {
+        return Err(format!("G2 point ({}, {}) isn't on the curve", x, y));
+    }
+    Ok(point)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::bn254::pairing_check::FinalExponentiation;
+    use crate::execute_script_with_witness_without_stack_limit;
+    use crate::groth16::test::dummy_proof;
+    use crate::groth16::verifier::Verifier;
+    use ark_ec::CurveGroup;
+    use ark_std::UniformRand;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+
+    fn g1_json(point: ark_bn254::G1Affine) -> String {
+        format!("{{\"X\": \"{}\", \"Y\": \"{}\"}}", point.x, point.y)
+    }
+
+    fn g2_json(point: ark_bn254::G2Affine) -> String {
+        format!(
+            "{{\"X\": {{\"A0\": \"{}\", \"A1\": \"{}\"}}, \"Y\": {{\"A0\": \"{}\", \"A1\": \"{}\"}}}}",
+            point.x.c0, point.x.c1, point.y.c0, point.y.c1
+        )
+    }
+
+    fn proof_json(proof: &Proof<Bn254>) -> String {
+        format!(
+            "{{\"Ar\": {}, \"Krs\": {}, \"Bs\": {}, \"Commitments\": [], \"CommitmentPok\": {}}}",
+            g1_json(proof.a),
+            g1_json(proof.c),
+            g2_json(proof.b),
+            g1_json(ark_bn254::G1Affine::zero())
+        )
+    }
+
+    // G1.Beta and G1.Delta aren't used by the verifier, they are set to the generator.
+    fn verifying_key_json(vk: &VerifyingKey<Bn254>) -> String {
+        let k = vk
+            .gamma_abc_g1
+            .iter()
+            .map(|point| g1_json(*point))
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!(
+            "{{\"G1\": {{\"Alpha\": {}, \"Beta\": {}, \"Delta\": {}, \"K\": [{}]}}, \"G2\": {{\"Beta\": {}, \"Delta\": {}, \"Gamma\": {}}}, \"CommitmentKeys\": [], \"PublicAndCommitmentCommitted\": []}}",
+            g1_json(vk.alpha_g1),
+            g1_json(ark_bn254::G1Affine::generator()),
+            g1_json(ark_bn254::G1Affine::generator()),
+            k,
+            g2_json(vk.beta_g2),
+            g2_json(vk.delta_g2),
+            g2_json(vk.gamma_g2)
+        )
+    }
+
+    #[test]
+    fn test_load_proof_and_verifying_key() {
+        let mut prng = ChaCha20Rng::seed_from_u64(0);
+        let g1 = |prng: &mut ChaCha20Rng| ark_bn254::G1Projective::rand(prng).into_affine();
+        let g2 = |prng: &mut ChaCha20Rng| ark_bn254::G2Projective::rand(prng).into_affine();
+
+        let proof = Proof::<Bn254> {
+            a: g1(&mut prng),
+            b: g2(&mut prng),
+            c: g1(&mut prng),
+        };
+        let json = proof_json(&proof);
+        assert_eq!(load_proof(&json).unwrap(), proof);
+
+        let vk = VerifyingKey::<Bn254> {
+            alpha_g1: g1(&mut prng),
+            beta_g2: g2(&mut prng),
+            gamma_g2: g2(&mut prng),
+            delta_g2: g2(&mut prng),
+            gamma_abc_g1: vec![g1(&mut prng), g1(&mut prng), g1(&mut prng)],
+        };
+        let json = verifying_key_json(&vk);
+        assert_eq!(load_verifying_key(&json).unwrap(), vk);
+
+        let point = format!("\"{}\"", vk.alpha_g1.x);
+        assert!(load_verifying_key(&json.replacen(&point, "1", 1)).is_err());
+        assert!(load_verifying_key(&json.replacen(&point, "1e3", 1)).is_err());
+    }
+
+    #[test]
+    fn test_load_commitments() {
+        let mut prng = ChaCha20Rng::seed_from_u64(0);
+        let point = ark_bn254::G1Projective::rand(&mut prng).into_affine();
+        let (proof, _, vk) = dummy_proof();
+
+        let json = proof_json(&proof);
+        let commitments = format!("\"Commitments\": [{}]", g1_json(point));
+        assert!(load_proof(&json.replace("\"Commitments\": []", &commitments)).is_err());
+        let commitment_pok = format!("\"CommitmentPok\": {}", g1_json(point));
+        let zero = format!(
+            "\"CommitmentPok\": {}",
+            g1_json(ark_bn254::G1Affine::zero())
+        );
+        assert!(load_proof(&json.replace(&zero, &commitment_pok)).is_err());
+
+        let json = verifying_key_json(&vk);
+        let commitment_keys = format!(
+            "\"CommitmentKeys\": [{{\"G\": {}, \"GSigmaNeg\": {}}}]",
+            g2_json(vk.gamma_g2),
+            g2_json(vk.delta_g2)
+        );
+        assert!(
+            load_verifying_key(&json.replace("\"CommitmentKeys\": []", &commitment_keys)).is_err()
+        );
+    }
+
+    #[test]
+    fn test_verify_loaded_proof() {
+        let (proof, public_inputs, vk) = dummy_proof();
+        let proof = load_proof(&proof_json(&proof)).unwrap();
+        let vk = load_verifying_key(&verifying_key_json(&vk)).unwrap();
+
+        let locking_script = Verifier::locking_script(&vk, FinalExponentiation::Hinted);
+        let witness =
+            Verifier::witness(&proof, &public_inputs, &vk, FinalExponentiation::Hinted).unwrap();
+        let exec_result = execute_script_with_witness_without_stack_limit(locking_script, witness);
+        assert!(exec_result.success);
+    }
+}
@@ -1,6 +1,8 @@
+pub mod batch;
 pub mod constants;
+pub mod gnark;
 pub mod offchain_checker;
-
+pub mod snarkjs;
 
 #[cfg(test)]
 mod test;
@@ -3,8 +3,8 @@ use crate::bn254::fp254impl::Fp254Impl;
 use crate::bn254::fq::Fq;
 
 use crate::groth16::constants::LAMBDA;
+use ark_ff::Field;
 use ark_ff::UniformRand;
-use ark_ff::{Field, One};
 use num_bigint::BigUint;
 use num_traits::{Num, ToPrimitive};
 use rand::SeedableRng;
@@ -61,7 +61,10 @@ fn tonelli_shanks_cubic(
 
 // Finding C
 // refer from Algorithm 5 of "On Proving Pairings"(https://eprint.iacr.org/2024/640.pdf)
-pub fn compute_c_wi(f: ark_bn254::Fq12) -> (ark_bn254::Fq12, ark_bn254::Fq12) {
+// The 27-th root of unity w, which is 3-th non-residue and r-th residue. It is sampled from a
+// fixed seed, so every call returns the same w. The scalar wi of f is 1, w or w^2, see
+// `compute_c_wi`.
+pub fn compute_w() -> ark_bn254::Fq12 {
     let p = BigUint::from_str_radix(Fq::MODULUS, 16).unwrap();
     let r = BigUint::from_str(
         "21888242871839275222246405745257275088548364400416034343698204186575808495617",
@@ -71,18 +74,10 @@ pub fn compute_c_wi(f: ark_bn254::Fq12) -> (ark_bn254::Fq12, ark_bn254::Fq12) {
     let exp = p.pow(12_u32) - 1_u32;
     let h = &exp / &r;
     let t = &exp / 3_u32.pow(s);
-    let k = (&t + 1_u32) / 3_u32;
-    let m = &*LAMBDA / &r;
-    let d = 3_u32;
-    let mm = &m / d;
 
     let mut prng = ChaCha20Rng::seed_from_u64(0);
     let cofactor_cubic = 3_u32.pow(s - 1) * &t;
 
-    // make f is r-th residue, but it's not cubic residue
-    assert_eq!(f.pow(h.to_u64_digits()), ark_bn254::Fq12::ONE);
-    assert_ne!(f.pow(cofactor_cubic.to_u64_digits()), ark_bn254::Fq12::ONE);
-
     // sample a proper scalar w which is cubic non-residue
     let w = {
         let (mut w, mut z) = (ark_bn254::Fq12::ONE, ark_bn254::Fq12::ONE);
@@ -102,16 +97,40 @@ pub fn compute_c_wi(f: ark_bn254::Fq12) -> (ark_bn254::Fq12, ark_bn254::Fq12) {
     assert_ne!(w.pow(cofactor_cubic.to_u64_digits()), ark_bn254::Fq12::ONE);
     assert_eq!(w.pow(h.to_u64_digits()), ark_bn254::Fq12::ONE);
 
-    // just two option, w and w^2, since w^3 must be cubic residue, leading f*w^3 must not be cubic residue
-    let mut wi = w;
-    if (f * wi).pow(cofactor_cubic.to_u64_digits()) != ark_bn254::Fq12::ONE {
-        assert_eq!(
-            (f * w * w).pow(cofactor_cubic.to_u64_digits()),
-            ark_bn254::Fq12::ONE
-        );
-        wi = w * w;
+    w
+}
+
+// Hints c and wi of the final exponentiation of the miller loop output f, with c^lambda = f * wi.
+// Returns an error if f isn't an r-th residue, namely if the pairing doesn't hold.
+pub fn compute_c_wi(f: ark_bn254::Fq12) -> Result<(ark_bn254::Fq12, ark_bn254::Fq12), String> {
+    let p = BigUint::from_str_radix(Fq::MODULUS, 16).unwrap();
+    let r = BigUint::from_str(
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+    )
+    .unwrap();
+    let s = 3_u32;
+    let exp = p.pow(12_u32) - 1_u32;
+    let h = &exp / &r;
+    let t = &exp / 3_u32.pow(s);
+    let k = (&t + 1_u32) / 3_u32;
+    let m = &*LAMBDA / &r;
+    let d = 3_u32;
+    let mm = &m / d;
+
+    let cofactor_cubic = 3_u32.pow(s - 1) * &t;
+
+    // make sure f is r-th residue
+    if f.pow(h.to_u64_digits()) != ark_bn254::Fq12::ONE {
+        return Err("The pairing doesn't hold".to_string());
     }
-    assert_eq!(wi.pow(h.to_u64_digits()), ark_bn254::Fq12::ONE);
+
+    let w = compute_w();
+
+    // scale f into a cubic residue: wi is 1 if f already is one, w or w^2 otherwise
+    let wi = [ark_bn254::Fq12::ONE, w, w * w]
+        .into_iter()
+        .find(|wi| (f * wi).pow(cofactor_cubic.to_u64_digits()) == ark_bn254::Fq12::ONE)
+        .ok_or("No scalar wi makes f a cubic residue".to_string())?;
 
     assert_eq!(LAMBDA.clone(), d * &mm * &r);
     // f1 is scaled f
@@ -119,23 +138,17 @@ pub fn compute_c_wi(f: ark_bn254::Fq12) -> (ark_bn254::Fq12, ark_bn254::Fq12) {
 
     // r-th root of f1, say f2
     let r_inv = r.modinv(&h).unwrap();
-    assert_ne!(r_inv, BigUint::one());
     let f2 = f1.pow(r_inv.to_u64_digits());
-    assert_ne!(f2, ark_bn254::Fq12::ONE);
 
     // m'-th root of f, say f3
     let mm_inv = mm.modinv(&(r * h)).unwrap();
-    assert_ne!(mm_inv, BigUint::one());
     let f3 = f2.pow(mm_inv.to_u64_digits());
-    assert_eq!(f3.pow(cofactor_cubic.to_u64_digits()), ark_bn254::Fq12::ONE);
-    assert_ne!(f3, ark_bn254::Fq12::ONE);
 
     // d-th (cubic) root, say c
     let c = tonelli_shanks_cubic(f3, w, s, t, k);
-    assert_ne!(c, ark_bn254::Fq12::ONE);
     assert_eq!(c.pow(LAMBDA.to_u64_digits()), f * wi);
 
-    (c, wi)
+    Ok((c, wi))
 }
 
 #[cfg(test)]
@@ -197,7 +210,7 @@ mod test {
         // equivalently (f * c_inv)^{lambda - p^3} * wi = c_inv^{-p^3} = c^{p^3}
         let f = Bn254::multi_miller_loop_affine([P1, P2, P3, -P4], [Q1, Q2, Q3, Q4]).0;
         println!("Bn254::multi_miller_loop done!");
-        let (c, wi) = compute_c_wi(f);
+        let (c, wi) = compute_c_wi(f).unwrap();
         let c_inv = c.inverse().unwrap();
         let hint = if sign {
             f * wi * (c_inv.pow(exp.to_u64_digits()))
@@ -261,4 +274,25 @@ mod test {
 
         assert!(exec_result.success);
     }
+
+    #[test]
+    fn test_compute_c_wi() {
+        let mut prng = ChaCha20Rng::seed_from_u64(0);
+        let w = compute_w();
+
+        // f = c^lambda is already a cubic residue
+        let f = ark_bn254::Fq12::rand(&mut prng).pow(LAMBDA.to_u64_digits());
+        let (c, wi) = compute_c_wi(f).unwrap();
+        assert_eq!(wi, ark_bn254::Fq12::ONE);
+        assert_eq!(c.pow(LAMBDA.to_u64_digits()), f);
+
+        for scalar in [w, w * w] {
+            let (c, wi) = compute_c_wi(f * scalar).unwrap();
+            assert!(wi == w || wi == w * w);
+            assert_eq!(c.pow(LAMBDA.to_u64_digits()), f * scalar * wi);
+        }
+
+        // a random f isn't an r-th residue
+        assert!(compute_c_wi(ark_bn254::Fq12::rand(&mut prng)).is_err());
+    }
 }
@@ -0,0 +1,280 @@
+// Loaders for the JSON files snarkjs writes for BN254 (`bn128` in snarkjs) circuits:
+// `proof.json`, `public.json` and `verification_key.json`.
+//
+// snarkjs writes points in projective coordinates, where z is "1" for affine points and "0" for
+// the point at infinity. Field elements are decimal strings.
+use ark_bn254::Bn254;
+use ark_ec::AffineRepr;
+use ark_groth16::{Proof, VerifyingKey};
+use serde::Deserialize;
+use std::str::FromStr;
+
+#[derive(Deserialize)]
+struct SnarkjsProof {
+    protocol: String,
+    curve: String,
+    pi_a: Vec<String>,
+    pi_b: Vec<Vec<String>>,
+    pi_c: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct SnarkjsVerifyingKey {
+    protocol: String,
+    curve: String,
+    #[serde(rename = "nPublic")]
+    n_public: usize,
+    vk_alpha_1: Vec<String>,
+    vk_beta_2: Vec<Vec<String>>,
+    vk_gamma_2: Vec<Vec<String>>,
+    vk_delta_2: Vec<Vec<String>>,
+    #[serde(rename = "IC")]
+    ic: Vec<Vec<String>>,
+}
+
+/// Loads a Groth16 `proof.json` written by snarkjs.
+pub fn load_proof(json: &str) -> Result<Proof<Bn254>, String> {
+    let proof: SnarkjsProof =
+        serde_json::from_str(json).map_err(|err| format!("Invalid snarkjs proof: {}", err))?;
+    check_header(&proof.protocol, &proof.curve, "groth16")?;
+
+    Ok(Proof {
+        a: parse_g1(&proof.pi_a)?,
+        b: parse_g2(&proof.pi_b)?,
+        c: parse_g1(&proof.pi_c)?,
+    })
+}
+
+/// Loads a Groth16 `verification_key.json` written by snarkjs.
+pub fn load_verifying_key(json: &str) -> Result<VerifyingKey<Bn254>, String> {
+    let vk: SnarkjsVerifyingKey = serde_json::from_str(json)
+        .map_err(|err| format!("Invalid snarkjs verifying key: {}", err))?;
+    check_header(&vk.protocol, &vk.curve, "groth16")?;
+    if vk.ic.len() != vk.n_public + 1 {
+        return Err(format!(
+            "Expected {} IC points for {} public inputs, got {}",
+            vk.n_public + 1,
+            vk.n_public,
+            vk.ic.len()
+        ));
+    }
+
+    Ok(VerifyingKey {
+        alpha_g1: parse_g1(&vk.vk_alpha_1)?,
+        beta_g2: parse_g2(&vk.vk_beta_2)?,
+        gamma_g2: parse_g2(&vk.vk_gamma_2)?,
+        delta_g2: parse_g2(&vk.vk_delta_2)?,
+        gamma_abc_g1: vk
+            .ic
+            .iter()
+            .map(|point| parse_g1(point))
+            .collect::<Result<Vec<_>, _>>()?,
+    })
+}
+
+/// Loads the `public.json` written by snarkjs, the public inputs of a proof.
+pub fn load_public_inputs(json: &str) -> Result<Vec<ark_bn254::Fr>, String> {
+    let inputs: Vec<String> = serde_json::from_str(json)
+        .map_err(|err| format!("Invalid snarkjs public inputs: {}", err))?;
+
+    inputs.iter().map(|input| parse_field(input)).collect()
+}
+
+pub(crate) fn check_header(
+    protocol: &str,
+    curve: &str,
+    expected_protocol: &str,
+) -> Result<(), String> {
+    if protocol != expected_protocol {
+        return Err(format!(
+            "Expected a {} file, got protocol {}",
+            expected_protocol, protocol
+        ));
+    }
+    if curve != "bn128" {
+        return Err(format!("Unsupported curve: {}", curve));
+    }
+    Ok(())
+}
+
+pub(crate) fn parse_field<F: FromStr>(value: &str) -> Result<F, String> {
+    if value.is_empty() || !value.chars().all(|char| char.is_ascii_digit()) {
+        return Err(format!("Invalid field element: {:?}", value));
+    }
+    F::from_str(value).map_err(|_| format!("Invalid field element: {:?}", value))
+}
+
+pub(crate) fn parse_g1(coordinates: &[String]) -> Result<ark_bn254::G1Affine, String> {
+    let [x, y, z] = coordinates else {
+        return Err(format!(
+            "Expected 3 coordinates for a G1 point, got {}",
+            coordinates.len()
+        ));
+    };
+    if is_infinity(z)? {
+        return Ok(ark_bn254::G1Affine::zero());
+    }
+
+    let point = ark_bn254::G1Affine::new_unchecked(parse_field(x)?, parse_field(y)?);
+    if !point.is_on_curve() || !point.is_in_correct_subgroup_assuming_on_curve() {
+        return Err(format!("G1 point ({}, {}) isn't on the curve", x, y));
+    }
+    Ok(point)
+}
+
+pub(crate) fn parse_g2(coordinates: &[Vec<String>]) -> Result<ark_bn254::G2Affine, String> {
+    let [x, y, z] = coordinates else {
+        return Err(format!(
+            "Expected 3 coordinates for a G2 point, got {}",
+            coordinates.len()
+        ));
+    };
+    let parse_fq2 = |value: &[String]| match value {
+        [c0, c1] => Ok(ark_bn254::Fq2::new(parse_field(c0)?, parse_field(c1)?)),
+        _ => Err(format!(
+            "Expected 2 elements for an Fq2, got {}",
+            value.len()
+        )),
+    };
+    if parse_fq2(z)? == ark_bn254::Fq2::from(0_u64) {
+        return Ok(ark_bn254::G2Affine::zero());
+    }
+    if parse_fq2(z)? != ark_bn254::Fq2::from(1_u64) {
+        return Err(format!("Expected an affine G2 point, got z = {:?}", z));
+    }
+
+    let point = ark_bn254::G2Affine::new_unchecked(parse_fq2(x)?, parse_fq2(y)?);
+    if !point.is_on_curve() || !point.is_in_correct_subgroup_assuming_on_curve() {
+        return Err(format!("G2 point ({:?}, {:?}) isn't on the curve", x, y));
+    }
+    Ok(point)
+}
+
+fn is_infinity(z: &str) -> Result<bool, String> {
+    match z {
+        "0" => Ok(true),
+        "1" => Ok(false),
+        _ => Err(format!("Expected an affine point, got z = {:?}", z)),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::bn254::pairing_check::FinalExponentiation;
+    use crate::execute_script_with_witness_without_stack_limit;
+    use crate::groth16::test::dummy_proof;
+    use crate::groth16::verifier::Verifier;
+    use ark_ec::CurveGroup;
+    use ark_std::UniformRand;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+
+    fn g1_json(point: ark_bn254::G1Affine) -> String {
+        format!("[\"{}\", \"{}\", \"1\"]", point.x, point.y)
+    }
+
+    fn g2_json(point: ark_bn254::G2Affine) -> String {
+        format!(
+            "[[\"{}\", \"{}\"], [\"{}\", \"{}\"], [\"1\", \"0\"]]",
+            point.x.c0, point.x.c1, point.y.c0, point.y.c1
+        )
+    }
+
+    fn proof_json(proof: &Proof<Bn254>) -> String {
+        format!(
+            "{{\"pi_a\": {}, \"pi_b\": {}, \"pi_c\": {}, \"protocol\": \"groth16\", \"curve\": \"bn128\"}}",
+            g1_json(proof.a),
+            g2_json(proof.b),
+            g1_json(proof.c)
+        )
+    }
+
+    fn verifying_key_json(vk: &VerifyingKey<Bn254>) -> String {
+        let ic = vk
+            .gamma_abc_g1
+            .iter()
+            .map(|point| g1_json(*point))
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!(
+            "{{\"protocol\": \"groth16\", \"curve\": \"bn128\", \"nPublic\": {}, \"vk_alpha_1\": {}, \"vk_beta_2\": {}, \"vk_gamma_2\": {}, \"vk_delta_2\": {}, \"vk_alphabeta_12\": [], \"IC\": [{}]}}",
+            vk.gamma_abc_g1.len() - 1,
+            g1_json(vk.alpha_g1),
+            g2_json(vk.beta_g2),
+            g2_json(vk.gamma_g2),
+            g2_json(vk.delta_g2),
+            ic
+        )
+    }
+
+    #[test]
+    fn test_load_proof_and_verifying_key() {
+        let mut prng = ChaCha20Rng::seed_from_u64(0);
+        let g1 = |prng: &mut ChaCha20Rng| ark_bn254::G1Projective::rand(prng).into_affine();
+        let g2 = |prng: &mut ChaCha20Rng| ark_bn254::G2Projective::rand(prng).into_affine();
+
+        let proof = Proof::<Bn254> {
+            a: g1(&mut prng),
+            b: g2(&mut prng),
+            c: g1(&mut prng),
+        };
+        assert_eq!(load_proof(&proof_json(&proof)).unwrap(), proof);
+
+        let vk = VerifyingKey::<Bn254> {
+            alpha_g1: g1(&mut prng),
+            beta_g2: g2(&mut prng),
+            gamma_g2: g2(&mut prng),
+            delta_g2: g2(&mut prng),
+            gamma_abc_g1: vec![g1(&mut prng), g1(&mut prng)],
+        };
+        assert_eq!(load_verifying_key(&verifying_key_json(&vk)).unwrap(), vk);
+
+        // fflonk files aren't Groth16 ones
+        assert!(
+            load_verifying_key(include_str!("../fflonk/circom_ref/verification_key.json")).is_err()
+        );
+        assert!(load_proof(include_str!("../fflonk/circom_ref/proof.json")).is_err());
+    }
+
+    #[test]
+    fn test_verify_loaded_proof() {
+        let (proof, public_inputs, vk) = dummy_proof();
+        let proof = load_proof(&proof_json(&proof)).unwrap();
+        let vk = load_verifying_key(&verifying_key_json(&vk)).unwrap();
+        let public_inputs = load_public_inputs(&format!("[\"{}\"]", public_inputs[0])).unwrap();
+
+        let locking_script = Verifier::locking_script(&vk, FinalExponentiation::Hinted);
+        let witness =
+            Verifier::witness(&proof, &public_inputs, &vk, FinalExponentiation::Hinted).unwrap();
+        let exec_result = execute_script_with_witness_without_stack_limit(locking_script, witness);
+        assert!(exec_result.success);
+    }
+
+    #[test]
+    fn test_load_public_inputs() {
+        let inputs = load_public_inputs(include_str!("../fflonk/circom_ref/public.json")).unwrap();
+        assert_eq!(
+            inputs,
+            vec![
+                ark_bn254::Fr::from(246513590391103489634602289097178521809_u128),
+                ark_bn254::Fr::from(138371009144214353742010089705444713455_u128),
+            ]
+        );
+
+        assert!(load_public_inputs("[\"0x12\"]").is_err());
+        assert!(load_public_inputs("[12]").is_err());
+    }
+
+    #[test]
+    fn test_parse_point_not_on_curve() {
+        let coordinates = ["1", "3", "1"].map(String::from);
+        assert!(parse_g1(&coordinates).is_err());
+
+        let coordinates = ["1", "2", "1"].map(String::from);
+        assert_eq!(
+            parse_g1(&coordinates).unwrap(),
+            ark_bn254::G1Affine::generator()
+        );
+    }
+}
@@ -1,13 +1,24 @@
-use crate::{execute_script_as_chunks, execute_script_without_stack_limit};
+use crate::bigint::U254;
+use crate::bn254::fp254impl::Fp254Impl;
+use crate::bn254::fr::Fr;
+use crate::bn254::pairing_check::FinalExponentiation;
+use crate::bn254::utils::{g1_affine_witness, g2_affine_witness};
+use crate::groth16::batch::BatchVerifier;
 use crate::groth16::verifier::Verifier;
+use crate::{
+    execute_script_as_chunks, execute_script_with_witness_without_stack_limit,
+    execute_script_without_stack_limit,
+};
 use ark_bn254::Bn254;
 use ark_crypto_primitives::snark::{CircuitSpecificSetupSNARK, SNARK};
 use ark_ec::pairing::Pairing;
-use ark_ff::PrimeField;
-use ark_groth16::Groth16;
+use ark_ff::{AdditiveGroup, Field, PrimeField};
+use ark_groth16::{Groth16, Proof, VerifyingKey};
 use ark_relations::lc;
 use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
 use ark_std::{end_timer, start_timer, test_rng, UniformRand};
+use num_bigint::BigUint;
+use num_traits::Num;
 use rand::{RngCore, SeedableRng};
 
 #[derive(Copy)]
@@ -54,6 +65,46 @@ impl<F: PrimeField> ConstraintSynthesizer<F> for DummyCircuit<F> {
     }
 }
 
+// A circuit with public inputs x_i, proving the knowledge of their squares.
+#[derive(Clone)]
+struct SquaresCircuit<F: PrimeField> {
+    pub inputs: Vec<F>,
+}
+
+impl<F: PrimeField> ConstraintSynthesizer<F> for SquaresCircuit<F> {
+    fn generate_constraints(self, cs: ConstraintSystemRef<F>) -> Result<(), SynthesisError> {
+        for input in self.inputs {
+            let x = cs.new_input_variable(|| Ok(input))?;
+            let y = cs.new_witness_variable(|| Ok(input.square()))?;
+            cs.enforce_constraint(lc!() + x, lc!() + x, lc!() + y)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A proof of a `DummyCircuit` with its public input and its verifying key.
+pub(super) fn dummy_proof() -> (
+    Proof<Bn254>,
+    Vec<<Bn254 as Pairing>::ScalarField>,
+    VerifyingKey<Bn254>,
+) {
+    type E = Bn254;
+    let k = 6;
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+    let circuit = DummyCircuit::<<E as Pairing>::ScalarField> {
+        a: Some(<E as Pairing>::ScalarField::rand(&mut rng)),
+        b: Some(<E as Pairing>::ScalarField::rand(&mut rng)),
+        num_variables: 10,
+        num_constraints: 1 << k,
+    };
+    let (pk, vk) = Groth16::<E>::setup(circuit, &mut rng).unwrap();
+    let c = circuit.a.unwrap() * circuit.b.unwrap();
+    let proof = Groth16::<E>::prove(&pk, circuit, &mut rng).unwrap();
+
+    (proof, vec![c], vk)
+}
+
 #[test]
 fn test_groth16_verifier() {
     type E = Bn254;
@@ -72,7 +123,7 @@ fn test_groth16_verifier() {
     let proof = Groth16::<E>::prove(&pk, circuit, &mut rng).unwrap();
 
     let start = start_timer!(|| "collect_script");
-    let script = Verifier::verify_proof(&vec![c], &proof, &vk);
+    let script = Verifier::verify_proof(&vec![c], &proof, &vk).unwrap();
     end_timer!(start);
 
     println!("groth16::test_verify_proof = {} bytes", script.len());
@@ -102,7 +153,7 @@ fn test_groth16_verifier_as_chunks() {
     let proof = Groth16::<E>::prove(&pk, circuit, &mut rng).unwrap();
 
     let start = start_timer!(|| "collect_script");
-    let script = Verifier::verify_proof(&vec![c], &proof, &vk);
+    let script = Verifier::verify_proof(&vec![c], &proof, &vk).unwrap();
     end_timer!(start);
 
     println!("groth16::test_verify_proof = {} bytes", script.len());
@@ -115,3 +166,320 @@ fn test_groth16_verifier_as_chunks() {
 
     assert!(exec_result.success);
 }
+
+#[test]
+fn test_groth16_locking_script_with_different_proofs() {
+    type E = Bn254;
+    let k = 6;
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+    let circuits = (0..2)
+        .map(|_| DummyCircuit::<<E as Pairing>::ScalarField> {
+            a: Some(<E as Pairing>::ScalarField::rand(&mut rng)),
+            b: Some(<E as Pairing>::ScalarField::rand(&mut rng)),
+            num_variables: 10,
+            num_constraints: 1 << k,
+        })
+        .collect::<Vec<_>>();
+    let (pk, vk) = Groth16::<E>::setup(circuits[0], &mut rng).unwrap();
+
+    let start = start_timer!(|| "collect_script");
+    let locking_script = Verifier::locking_script(&vk, FinalExponentiation::Hinted);
+    end_timer!(start);
+
+    let witnesses = circuits
+        .iter()
+        .map(|circuit| {
+            let c = circuit.a.unwrap() * circuit.b.unwrap();
+            let proof = Groth16::<E>::prove(&pk, *circuit, &mut rng).unwrap();
+            Verifier::witness(&proof, &vec![c], &vk, FinalExponentiation::Hinted).unwrap()
+        })
+        .collect::<Vec<_>>();
+
+    for witness in witnesses.iter() {
+        let exec_result = execute_script_with_witness_without_stack_limit(
+            locking_script.clone(),
+            witness.clone(),
+        );
+        assert!(exec_result.success);
+    }
+
+    // the public input of one proof doesn't go with the other proof, it takes the first
+    // 9 items (the limbs of one Fr) of the witness
+    let witness = [&witnesses[1][..9], &witnesses[0][9..]].concat();
+    let exec_result = execute_script_with_witness_without_stack_limit(locking_script, witness);
+    assert!(!exec_result.success);
+}
+
+#[test]
+fn test_groth16_locking_script_rejects_invalid_witness() {
+    type E = Bn254;
+    let k = 6;
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+    let circuit = DummyCircuit::<<E as Pairing>::ScalarField> {
+        a: Some(<E as Pairing>::ScalarField::rand(&mut rng)),
+        b: Some(<E as Pairing>::ScalarField::rand(&mut rng)),
+        num_variables: 10,
+        num_constraints: 1 << k,
+    };
+    let (pk, vk) = Groth16::<E>::setup(circuit, &mut rng).unwrap();
+    let c = circuit.a.unwrap() * circuit.b.unwrap();
+    let proof = Groth16::<E>::prove(&pk, circuit, &mut rng).unwrap();
+
+    let locking_script = Verifier::locking_script(&vk, FinalExponentiation::Hinted);
+    let witness = Verifier::witness(&proof, &vec![c], &vk, FinalExponentiation::Hinted).unwrap();
+    // the witness starts with the public input (9 items), C (18 items), A (18 items) and B
+    let (a_start, b_start) = (27, 45);
+
+    // the public input isn't reduced modulo r
+    let r = BigUint::from_str_radix(Fr::MODULUS, 16).unwrap();
+    let unreduced = U254::witness_u32_le(&r.to_u32_digits());
+    let invalid_witness = [&unreduced[..], &witness[9..]].concat();
+    let exec_result =
+        execute_script_with_witness_without_stack_limit(locking_script.clone(), invalid_witness);
+    assert!(!exec_result.success);
+
+    // A isn't on the curve
+    let off_curve = ark_bn254::G1Affine::new_unchecked(proof.a.x + ark_bn254::Fq::ONE, proof.a.y);
+    let invalid_witness = [
+        &witness[..a_start],
+        &g1_affine_witness(off_curve)[..],
+        &witness[b_start..],
+    ]
+    .concat();
+    let exec_result =
+        execute_script_with_witness_without_stack_limit(locking_script.clone(), invalid_witness);
+    assert!(!exec_result.success);
+
+    // B is on the curve, but not in the subgroup of order r
+    let not_in_subgroup = (1u64..)
+        .filter_map(|x| {
+            let x = ark_bn254::Fq2::new(ark_bn254::Fq::from(x), ark_bn254::Fq::ZERO);
+            ark_bn254::G2Affine::get_point_from_x_unchecked(x, false)
+        })
+        .find(|point| !point.is_in_correct_subgroup_assuming_on_curve())
+        .unwrap();
+    let invalid_witness = [
+        &witness[..b_start],
+        &g2_affine_witness(not_in_subgroup)[..],
+        &witness[b_start + 36..],
+    ]
+    .concat();
+    let exec_result =
+        execute_script_with_witness_without_stack_limit(locking_script, invalid_witness);
+    assert!(!exec_result.success);
+}
+
+#[test]
+fn test_groth16_witness_of_invalid_proof() {
+    type E = Bn254;
+    let k = 6;
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+    let circuit = DummyCircuit::<<E as Pairing>::ScalarField> {
+        a: Some(<E as Pairing>::ScalarField::rand(&mut rng)),
+        b: Some(<E as Pairing>::ScalarField::rand(&mut rng)),
+        num_variables: 10,
+        num_constraints: 1 << k,
+    };
+    let (pk, vk) = Groth16::<E>::setup(circuit, &mut rng).unwrap();
+    let c = circuit.a.unwrap() * circuit.b.unwrap();
+    let proof = Groth16::<E>::prove(&pk, circuit, &mut rng).unwrap();
+
+    assert!(Verifier::witness(&proof, &vec![], &vk, FinalExponentiation::Hinted).is_err());
+    assert!(Verifier::witness(&proof, &vec![c, c], &vk, FinalExponentiation::Hinted).is_err());
+    // the pairing doesn't hold for another public input
+    assert!(Verifier::witness(&proof, &vec![c + c], &vk, FinalExponentiation::Hinted).is_err());
+    assert!(Verifier::verify_proof(&vec![c + c], &proof, &vk).is_err());
+}
+
+#[test]
+fn test_groth16_packed_witness() {
+    let (proof, public_inputs, vk) = dummy_proof();
+
+    let locking_script = Verifier::packed_locking_script(&vk, FinalExponentiation::Hinted);
+    let witness =
+        Verifier::packed_witness(&proof, &public_inputs, &vk, FinalExponentiation::Hinted).unwrap();
+    // A, B and C take 39 stack items instead of 72
+    let unpacked_witness =
+        Verifier::witness(&proof, &public_inputs, &vk, FinalExponentiation::Hinted).unwrap();
+    assert_eq!(unpacked_witness.len() - witness.len(), 72 - 39);
+
+    let exec_result =
+        execute_script_with_witness_without_stack_limit(locking_script.clone(), witness.clone());
+    assert!(exec_result.success);
+
+    // the flag of A is flipped, the witness starts with the public input (9 items), C (10 items)
+    // and the x coordinate of A (9 items)
+    let mut invalid_witness = witness;
+    invalid_witness[28] = if invalid_witness[28].is_empty() {
+        vec![1]
+    } else {
+        vec![]
+    };
+    let exec_result =
+        execute_script_with_witness_without_stack_limit(locking_script, invalid_witness);
+    assert!(!exec_result.success);
+}
+
+#[test]
+fn test_groth16_verifier_with_full_final_exponentiation() {
+    let (proof, public_inputs, vk) = dummy_proof();
+
+    // one script at a time, each takes GBs
+    let hinted_len = Verifier::locking_script(&vk, FinalExponentiation::Hinted).len();
+    let full_len = Verifier::locking_script(&vk, FinalExponentiation::Full).len();
+    assert!(hinted_len < full_len);
+    println!(
+        "groth16::test_verifier_with_full_final_exponentiation = {} bytes with the hinted final exponentiation, {} bytes with the full one",
+        hinted_len,
+        full_len
+    );
+
+    // the witness goes without c, c_inv and wi
+    let witness =
+        Verifier::witness(&proof, &public_inputs, &vk, FinalExponentiation::Full).unwrap();
+    let hinted_witness =
+        Verifier::witness(&proof, &public_inputs, &vk, FinalExponentiation::Hinted).unwrap();
+    assert_eq!(hinted_witness.len() - witness.len(), 36 * 9);
+    let exec_result = execute_script_with_witness_without_stack_limit(
+        Verifier::locking_script(&vk, FinalExponentiation::Full),
+        witness.clone(),
+    );
+    assert!(exec_result.success);
+
+    // C is replaced by A, the witness starts with the public input (9 items), C (18 items) and A
+    let invalid_witness = [&witness[..9], &witness[27..45], &witness[27..]].concat();
+    let exec_result = execute_script_with_witness_without_stack_limit(
+        Verifier::locking_script(&vk, FinalExponentiation::Full),
+        invalid_witness,
+    );
+    assert!(!exec_result.success);
+}
+
+#[test]
+fn test_groth16_batch_verifier() {
+    type E = Bn254;
+    let k = 6;
+    let num_proofs = 2;
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+    let circuits = (0..num_proofs)
+        .map(|_| DummyCircuit::<<E as Pairing>::ScalarField> {
+            a: Some(<E as Pairing>::ScalarField::rand(&mut rng)),
+            b: Some(<E as Pairing>::ScalarField::rand(&mut rng)),
+            num_variables: 10,
+            num_constraints: 1 << k,
+        })
+        .collect::<Vec<_>>();
+    let (pk, vk) = Groth16::<E>::setup(circuits[0], &mut rng).unwrap();
+
+    let public_inputs = circuits
+        .iter()
+        .map(|circuit| vec![circuit.a.unwrap() * circuit.b.unwrap()])
+        .collect::<Vec<_>>();
+    let proofs = circuits
+        .iter()
+        .map(|circuit| Groth16::<E>::prove(&pk, *circuit, &mut rng).unwrap())
+        .collect::<Vec<_>>();
+
+    let start = start_timer!(|| "collect_script");
+    let locking_script =
+        BatchVerifier::locking_script(&vk, num_proofs, FinalExponentiation::Hinted);
+    end_timer!(start);
+
+    let (batched, separate) =
+        BatchVerifier::size_comparison(&vk, num_proofs, FinalExponentiation::Hinted);
+    assert_eq!(batched, locking_script.len());
+    assert!(batched < separate);
+    println!(
+        "groth16::test_batch_verifier = {} bytes for {} proofs, {} bytes for separate verifications, {} bytes saved",
+        batched,
+        num_proofs,
+        separate,
+        separate - batched
+    );
+
+    let witness =
+        BatchVerifier::witness(&proofs, &public_inputs, &vk, FinalExponentiation::Hinted).unwrap();
+    let start = start_timer!(|| "execute_script");
+    let exec_result =
+        execute_script_with_witness_without_stack_limit(locking_script.clone(), witness.clone());
+    end_timer!(start);
+    assert!(exec_result.success);
+
+    // the hints are bound to the proofs through the coefficients, swapping the public input of
+    // the first proof, the first 9 items of the witness, makes the check fail
+    let other = Fr::witness_u32_le(&BigUint::from(public_inputs[1][0]).to_u32_digits());
+    let invalid_witness = [other, witness[9..].to_vec()].concat();
+    let exec_result =
+        execute_script_with_witness_without_stack_limit(locking_script.clone(), invalid_witness);
+    assert!(!exec_result.success);
+
+    // B_0 is on the curve, but not in the subgroup of order r, it follows the public input, C
+    // and A of both proofs
+    let b_start = num_proofs * 45;
+    let not_in_subgroup = (1u64..)
+        .filter_map(|x| {
+            let x = ark_bn254::Fq2::new(ark_bn254::Fq::from(x), ark_bn254::Fq::ZERO);
+            ark_bn254::G2Affine::get_point_from_x_unchecked(x, false)
+        })
+        .find(|point| !point.is_in_correct_subgroup_assuming_on_curve())
+        .unwrap();
+    let invalid_witness = [
+        &witness[..b_start],
+        &g2_affine_witness(not_in_subgroup)[..],
+        &witness[b_start + 36..],
+    ]
+    .concat();
+    let exec_result =
+        execute_script_with_witness_without_stack_limit(locking_script, invalid_witness);
+    assert!(!exec_result.success);
+}
+
+#[test]
+fn test_groth16_batch_verifier_with_many_public_inputs() {
+    type E = Bn254;
+    let num_proofs = 2;
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+    // 10 public inputs, C, A and B make 16 words, hashed in two chunks per proof
+    let circuits = (0..num_proofs)
+        .map(|_| SquaresCircuit::<<E as Pairing>::ScalarField> {
+            inputs: (0..10)
+                .map(|_| <E as Pairing>::ScalarField::rand(&mut rng))
+                .collect(),
+        })
+        .collect::<Vec<_>>();
+    let (pk, vk) = Groth16::<E>::setup(circuits[0].clone(), &mut rng).unwrap();
+
+    let public_inputs = circuits
+        .iter()
+        .map(|circuit| circuit.inputs.clone())
+        .collect::<Vec<_>>();
+    let proofs = circuits
+        .iter()
+        .map(|circuit| Groth16::<E>::prove(&pk, circuit.clone(), &mut rng).unwrap())
+        .collect::<Vec<_>>();
+
+    let locking_script =
+        BatchVerifier::locking_script(&vk, num_proofs, FinalExponentiation::Hinted);
+    let witness =
+        BatchVerifier::witness(&proofs, &public_inputs, &vk, FinalExponentiation::Hinted).unwrap();
+    let exec_result = execute_script_with_witness_without_stack_limit(locking_script, witness);
+    assert!(exec_result.success);
+
+    assert!(BatchVerifier::witness(
+        &proofs,
+        &public_inputs[..1],
+        &vk,
+        FinalExponentiation::Hinted
+    )
+    .is_err());
+    assert!(BatchVerifier::witness(
+        &proofs,
+        &[public_inputs[0].clone(), vec![]],
+        &vk,
+        FinalExponentiation::Hinted
+    )
+    .is_err());
+    // the proofs don't go with each other's public inputs
+    let swapped = [public_inputs[1].clone(), public_inputs[0].clone()];
+    assert!(BatchVerifier::witness(&proofs, &swapped, &vk, FinalExponentiation::Hinted).is_err());
+}
@@ -1,35 +1,274 @@
+use crate::bn254::curves::{G1Affine, G1Projective};
 use crate::bn254::ell_coeffs::G2Prepared;
 use crate::bn254::fp254impl::Fp254Impl;
 use crate::bn254::fq::Fq;
 use crate::bn254::fq12::Fq12;
+use crate::bn254::fq2::Fq2;
+use crate::bn254::fr::Fr;
+use crate::bn254::g2::G2Affine;
 use crate::bn254::msm::msm_with_constant_bases;
 use crate::bn254::pairing::Pairing;
+use crate::bn254::pairing_check::FinalExponentiation;
 use crate::bn254::utils;
 use crate::bn254::utils::fq12_push;
 use crate::groth16::constants::{LAMBDA, P_POW3};
 use crate::groth16::offchain_checker::compute_c_wi;
 use crate::treepp::{script, Script};
-use ark_bn254::{Bn254, G1Projective};
+use ark_bn254::{Bn254, G1Projective as ArkG1Projective};
 use ark_ec::pairing::Pairing as ark_Pairing;
 use ark_ec::short_weierstrass::Projective;
 use ark_ec::{AffineRepr, CurveGroup, VariableBaseMSM};
 use ark_ff::Field;
 use ark_groth16::{Proof, VerifyingKey};
 use core::ops::Neg;
+use num_bigint::BigUint;
 
 #[derive(Clone, Copy, Debug)]
 pub struct Verifier;
 
 impl Verifier {
+    /// Script verifying any proof for `vk`, it only depends on the verifying key and can be
+    /// committed to before the proof exists. The proof is provided by `Verifier::witness`.
+    ///
+    /// Input stack: [public_inputs, C, A, B, c, c_inv, wi, L(B)]
+    /// c, c_inv and wi are left out with `FinalExponentiation::Full`.
+    pub fn locking_script(
+        vk: &VerifyingKey<Bn254>,
+        final_exponentiation: FinalExponentiation,
+    ) -> Script {
+        let (q1, q2, q3) = (
+            vk.gamma_g2.into_group().neg().into_affine(),
+            vk.delta_g2.into_group().neg().into_affine(),
+            -vk.beta_g2,
+        );
+        let num_line_elements = 4 * Pairing::num_hinted_lines() as u32;
+        let hinted = final_exponentiation == FinalExponentiation::Hinted;
+        let num_exp_hints = final_exponentiation.num_hint_elements();
+        // Q4 and the hints of the final exponentiation
+        let num_tail_elements = 4 + num_exp_hints;
+        let fixed = vec![
+            G2Prepared::from_affine(q1),
+            G2Prepared::from_affine(q2),
+            G2Prepared::from_affine(q3),
+        ];
+
+        script! {
+            { check_witness(vk.gamma_abc_g1.len() as u32 - 1, num_line_elements, num_exp_hints) }
+
+            if hinted {
+                // expected final_f, say c^{p^3}
+                { Fq12::copy(num_line_elements + 24) }
+                { Fq12::frobenius_map(3) }
+                { Fq12::toaltstack() }
+            }
+
+            // the line coefficients of Q4 are consumed by the miller loop from altstack
+            for _ in 0..num_line_elements {
+                { Fq::toaltstack() }
+            }
+
+            // move Q4, c, c_inv and wi out of the way
+            for _ in 0..num_tail_elements {
+                { Fq::toaltstack() }
+            }
+
+            // variants of the proof's G1 points, say -p.x / p.y, 1 / p.y
+            { utils::from_eval_point_in_stack() }
+            { Fq2::toaltstack() }
+            { utils::from_eval_point_in_stack() }
+            { Fq2::toaltstack() }
+            // stack: [public_inputs | c^{p^3}, L(Q4), Q4, c, c_inv, wi, P4, P2]
+
+            // P1 from the public inputs
+            { Self::public_inputs_point(vk) }
+            { utils::from_eval_point_in_stack() }
+
+            // constants
+            { constants() }
+            { Fq2::roll(6) }
+            { Fq2::fromaltstack() }
+            { utils::from_eval_point(vk.alpha_g1) }
+            { Fq2::fromaltstack() }
+            for _ in 0..num_tail_elements {
+                { Fq::fromaltstack() }
+            }
+            // stack: [beta_12, beta_13, beta_22, P1, P2, P3, P4, Q4, c, c_inv, wi | c^{p^3}, L(Q4)]
+
+            if hinted {
+                // c_inv is the inverse of c and wi is 1, w or w^2
+                { Pairing::check_c_wi() }
+            }
+
+            // accumulator of q4, say t4
+            { Fq2::copy(num_exp_hints + 2) }
+            { Fq2::copy(num_exp_hints + 2) }
+            // stack: [beta_12, beta_13, beta_22, P1, P2, P3, P4, Q4, c, c_inv, wi, T4 | c^{p^3}, L(Q4)]
+
+            if hinted {
+                { Pairing::quad_miller_loop_with_c_wi_and_hinted_lines(fixed) }
+
+                // check final_f == c^{p^3}
+                { Fq12::fromaltstack() }
+                { Fq12::equalverify() }
+            } else {
+                { Pairing::multi_miller_loop_with_hinted_lines(fixed, 1) }
+
+                // check f^((p^12 - 1) / r) == 1
+                { Pairing::final_exponentiation() }
+                { Fq12::push_one() }
+                { Fq12::equalverify() }
+            }
+            OP_TRUE
+        }
+    }
+
+    /// Same as `Verifier::locking_script` for the witness of `Verifier::packed_witness`, whose
+    /// proof points are packed into 39 stack items instead of 72.
+    ///
+    /// Input stack: [public_inputs, C', A', B', c, c_inv, wi, L(B)]
+    pub fn packed_locking_script(
+        vk: &VerifyingKey<Bn254>,
+        final_exponentiation: FinalExponentiation,
+    ) -> Script {
+        let num_line_elements = 4 * Pairing::num_hinted_lines() as u32;
+        let num_exp_hints = final_exponentiation.num_hint_elements();
+
+        script! {
+            // move c, c_inv, wi and L(B) out of the way
+            for _ in 0..num_line_elements + num_exp_hints {
+                { Fq::toaltstack() }
+            }
+
+            // unpack B, A and C
+            { G2Affine::decompress() }
+            { Fq2::toaltstack() }
+            { Fq2::toaltstack() }
+            { G1Affine::decompress_packed() }
+            { Fq2::toaltstack() }
+            { G1Affine::decompress_packed() }
+            for _ in 0..3 {
+                { Fq2::fromaltstack() }
+            }
+
+            for _ in 0..num_line_elements + num_exp_hints {
+                { Fq::fromaltstack() }
+            }
+            { Self::locking_script(vk, final_exponentiation) }
+        }
+    }
+
+    /// Stack items satisfying `Verifier::locking_script(vk, final_exponentiation)` for the proof:
+    /// the public inputs, the proof points, the hints of the final exponentiation if hinted and
+    /// the line coefficients of proof.b, first item at the bottom. Fails unless the proof is
+    /// valid for the public inputs.
+    pub fn witness(
+        proof: &Proof<Bn254>,
+        public_inputs: &Vec<<Bn254 as ark_Pairing>::ScalarField>,
+        vk: &VerifyingKey<Bn254>,
+        final_exponentiation: FinalExponentiation,
+    ) -> Result<Vec<Vec<u8>>, String> {
+        let proof_points = [
+            utils::g1_affine_witness(proof.c),
+            utils::g1_affine_witness(proof.a),
+            utils::g2_affine_witness(proof.b),
+        ];
+        Self::witness_with_proof_points(
+            proof,
+            public_inputs,
+            vk,
+            final_exponentiation,
+            proof_points.concat(),
+        )
+    }
+
+    /// Same as `Verifier::witness` for `Verifier::packed_locking_script`: the proof points
+    /// are packed as their x coordinate and the flag of their y coordinate, see
+    /// `G1Affine::decompress_packed` and `G2Affine::decompress`.
+    pub fn packed_witness(
+        proof: &Proof<Bn254>,
+        public_inputs: &Vec<<Bn254 as ark_Pairing>::ScalarField>,
+        vk: &VerifyingKey<Bn254>,
+        final_exponentiation: FinalExponentiation,
+    ) -> Result<Vec<Vec<u8>>, String> {
+        let proof_points = [
+            utils::g1_affine_packed_witness(proof.c),
+            utils::g1_affine_packed_witness(proof.a),
+            utils::g2_affine_packed_witness(proof.b),
+        ];
+        Self::witness_with_proof_points(
+            proof,
+            public_inputs,
+            vk,
+            final_exponentiation,
+            proof_points.concat(),
+        )
+    }
+
+    fn witness_with_proof_points(
+        proof: &Proof<Bn254>,
+        public_inputs: &Vec<<Bn254 as ark_Pairing>::ScalarField>,
+        vk: &VerifyingKey<Bn254>,
+        final_exponentiation: FinalExponentiation,
+        proof_points: Vec<Vec<u8>>,
+    ) -> Result<Vec<Vec<u8>>, String> {
+        // the hints only exist for valid proofs
+        let (c, c_inv, wi) = Self::hints(proof, public_inputs, vk)?;
+        let exp_hints = match final_exponentiation {
+            FinalExponentiation::Hinted => [
+                utils::fq12_witness(c),
+                utils::fq12_witness(c_inv),
+                utils::fq12_witness(wi),
+            ]
+            .concat(),
+            FinalExponentiation::Full => vec![],
+        };
+
+        Ok([
+            public_inputs
+                .iter()
+                .flat_map(|input| Fr::witness_u32_le(&BigUint::from(*input).to_u32_digits()))
+                .collect(),
+            proof_points,
+            exp_hints,
+            Pairing::hinted_line_coeffs(proof.b)
+                .into_iter()
+                .flat_map(|(c3, c4)| [utils::fq2_witness(c3), utils::fq2_witness(c4)].concat())
+                .collect(),
+        ]
+        .concat())
+    }
+
+    /// Locking script with the witness pushed in front of it, with the hinted final
+    /// exponentiation.
     pub fn verify_proof(
         public_inputs: &Vec<<Bn254 as ark_Pairing>::ScalarField>,
         proof: &Proof<Bn254>,
         vk: &VerifyingKey<Bn254>,
-    ) -> Script {
-        let (msm_script, msm_g1) = Self::prepare_inputs(public_inputs, vk);
-        Self::verify_proof_with_prepared_inputs(proof, vk, msm_script, msm_g1)
+    ) -> Result<Script, String> {
+        let (c, c_inv, wi) = Self::hints(proof, public_inputs, vk)?;
+
+        Ok(script! {
+            for input in public_inputs {
+                { Fr::push_u32_le(&BigUint::from(*input).to_u32_digits()) }
+            }
+            { Fq::push_u32_le(&BigUint::from(proof.c.x).to_u32_digits()) }
+            { Fq::push_u32_le(&BigUint::from(proof.c.y).to_u32_digits()) }
+            { Fq::push_u32_le(&BigUint::from(proof.a.x).to_u32_digits()) }
+            { Fq::push_u32_le(&BigUint::from(proof.a.y).to_u32_digits()) }
+            { utils::fq2_push(proof.b.x) }
+            { utils::fq2_push(proof.b.y) }
+            { fq12_push(c) }
+            { fq12_push(c_inv) }
+            { fq12_push(wi) }
+            for (c3, c4) in Pairing::hinted_line_coeffs(proof.b) {
+                { utils::fq2_push(c3) }
+                { utils::fq2_push(c4) }
+            }
+            { Self::locking_script(vk, FinalExponentiation::Hinted) }
+        })
     }
 
+    #[deprecated(note = "bakes the public inputs into the script, use `Verifier::locking_script`")]
     pub fn prepare_inputs(
         public_inputs: &Vec<<Bn254 as ark_Pairing>::ScalarField>,
         vk: &VerifyingKey<Bn254>,
@@ -40,24 +279,25 @@ impl Verifier {
         ]
         .concat();
         let sum_ai_abc_gamma =
-            G1Projective::msm(&vk.gamma_abc_g1, &scalars).expect("failed to calculate msm");
-        (msm_with_constant_bases(&vk.gamma_abc_g1, &scalars), sum_ai_abc_gamma)
+            ArkG1Projective::msm(&vk.gamma_abc_g1, &scalars).expect("failed to calculate msm");
+        (
+            msm_with_constant_bases(&vk.gamma_abc_g1, &scalars),
+            sum_ai_abc_gamma,
+        )
     }
 
+    #[deprecated(
+        note = "bakes the proof into the script, use `Verifier::locking_script` and `Verifier::witness`"
+    )]
+    #[allow(deprecated)]
     pub fn verify_proof_with_prepared_inputs(
         proof: &Proof<Bn254>,
         vk: &VerifyingKey<Bn254>,
         msm_script: Script,
         msm_g1: Projective<ark_bn254::g1::Config>,
     ) -> Script {
-        let (exp, sign) = if LAMBDA.gt(&P_POW3) {
-            (&*LAMBDA - &*P_POW3, true)
-        } else {
-            (&*P_POW3 - &*LAMBDA, false)
-        };
-
         // G1/G2 points for pairings
-        let (p1, p2, p3, p4) = (msm_g1.into_affine(), proof.c, vk.alpha_g1, proof.a);
+        let (p2, p3, p4) = (proof.c, vk.alpha_g1, proof.a);
         let (q1, q2, q3, q4) = (
             vk.gamma_g2.into_group().neg().into_affine(),
             vk.delta_g2.into_group().neg().into_affine(),
@@ -66,16 +306,9 @@ impl Verifier {
         );
         let t4 = q4;
 
-        // hint from arkworks
-        let f = Bn254::multi_miller_loop_affine([p1, p2, p3, p4], [q1, q2, q3, q4]).0;
-        let (c, wi) = compute_c_wi(f);
-        let c_inv = c.inverse().unwrap();
-        let hint = if sign {
-            f * wi * (c_inv.pow((exp).to_u64_digits()))
-        } else {
-            f * wi * (c_inv.pow((exp).to_u64_digits()).inverse().unwrap())
-        };
-        assert_eq!(hint, c.pow(P_POW3.to_u64_digits()), "hint isn't correct!");
+        let (c, c_inv, wi) =
+            Self::hints_with_prepared_inputs(proof, vk, msm_g1).expect("the pairing doesn't hold");
+        let hint = c.pow(P_POW3.to_u64_digits());
 
         let q_prepared = vec![
             G2Prepared::from_affine(q1),
@@ -119,6 +352,133 @@ impl Verifier {
             { check_pairing(&q_prepared, hint) }
         }
     }
+
+    // Computes P1 = gamma_abc_g1[0] + sum(public_inputs[i] * gamma_abc_g1[i + 1]).
+    //
+    // Input stack: [public_inputs]
+    // Output stack: [x, y]
+    fn public_inputs_point(vk: &VerifyingKey<Bn254>) -> Script {
+        let bases = vk
+            .gamma_abc_g1
+            .iter()
+            .map(|base| base.into_group())
+            .collect::<Vec<_>>();
+
+        script! {
+            for _ in 1..bases.len() {
+                { Fr::toaltstack() }
+            }
+            { G1Projective::push(bases[0]) }
+            for base in bases.iter().skip(1) {
+                { Fr::fromaltstack() }
+                { G1Projective::scalar_mul_by_constant_g1(*base) }
+                { G1Projective::add() }
+            }
+            { G1Projective::into_affine() }
+        }
+    }
+
+    // Hints for verifying the final exponentiation: c, c_inv and wi with c^lambda = f * wi.
+    fn hints(
+        proof: &Proof<Bn254>,
+        public_inputs: &[<Bn254 as ark_Pairing>::ScalarField],
+        vk: &VerifyingKey<Bn254>,
+    ) -> Result<(ark_bn254::Fq12, ark_bn254::Fq12, ark_bn254::Fq12), String> {
+        if public_inputs.len() + 1 != vk.gamma_abc_g1.len() {
+            return Err(format!(
+                "Expected {} public inputs, got {}",
+                vk.gamma_abc_g1.len() - 1,
+                public_inputs.len()
+            ));
+        }
+        let scalars = [
+            vec![<Bn254 as ark_Pairing>::ScalarField::ONE],
+            public_inputs.to_vec(),
+        ]
+        .concat();
+        let msm_g1 =
+            ArkG1Projective::msm(&vk.gamma_abc_g1, &scalars).expect("failed to calculate msm");
+
+        Self::hints_with_prepared_inputs(proof, vk, msm_g1)
+    }
+
+    // Same as `Verifier::hints` for the point of the public inputs computed by
+    // `Verifier::prepare_inputs`.
+    fn hints_with_prepared_inputs(
+        proof: &Proof<Bn254>,
+        vk: &VerifyingKey<Bn254>,
+        msm_g1: Projective<ark_bn254::g1::Config>,
+    ) -> Result<(ark_bn254::Fq12, ark_bn254::Fq12, ark_bn254::Fq12), String> {
+        let (exp, sign) = if LAMBDA.gt(&P_POW3) {
+            (&*LAMBDA - &*P_POW3, true)
+        } else {
+            (&*P_POW3 - &*LAMBDA, false)
+        };
+
+        // G1/G2 points for pairings
+        let (p1, p2, p3, p4) = (msm_g1.into_affine(), proof.c, vk.alpha_g1, proof.a);
+        let (q1, q2, q3, q4) = (
+            vk.gamma_g2.into_group().neg().into_affine(),
+            vk.delta_g2.into_group().neg().into_affine(),
+            -vk.beta_g2,
+            proof.b,
+        );
+
+        // hint from arkworks
+        let f = Bn254::multi_miller_loop_affine([p1, p2, p3, p4], [q1, q2, q3, q4]).0;
+        let (c, wi) = compute_c_wi(f)?;
+        let c_inv = c.inverse().unwrap();
+        let hint = if sign {
+            f * wi * (c_inv.pow((exp).to_u64_digits()))
+        } else {
+            f * wi * (c_inv.pow((exp).to_u64_digits()).inverse().unwrap())
+        };
+        assert_eq!(hint, c.pow(P_POW3.to_u64_digits()), "hint isn't correct!");
+
+        Ok((c, c_inv, wi))
+    }
+}
+
+// Checks that the public inputs, the proof and the hints of the final exponentiation in the
+// witness are field elements, that A and C are on G1 and that B is on G2, in the subgroup of
+// order r. The line coefficients of B are checked by the miller loop.
+//
+// Input stack: [public_inputs, C, A, B, c, c_inv, wi, L(B)], without c, c_inv and wi if
+//              `num_exp_hints` is zero
+// Output stack: [public_inputs, C, A, B, c, c_inv, wi, L(B)]
+fn check_witness(num_public_inputs: u32, num_line_elements: u32, num_exp_hints: u32) -> Script {
+    // number of Fq elements of B and the hints
+    let num_tail_elements = 4 + num_exp_hints;
+
+    script! {
+        for i in 0..num_tail_elements + 4 {
+            { Fq::copy(num_line_elements + i) }
+            { Fq::is_field() }
+            OP_VERIFY
+        }
+        for i in 0..num_public_inputs {
+            { Fr::copy(num_line_elements + num_tail_elements + 4 + i) }
+            { Fr::is_field() }
+            OP_VERIFY
+        }
+
+        // A and C
+        for i in 0..2 {
+            { Fq2::copy(num_line_elements + num_tail_elements + 2 * i) }
+            { G1Affine::is_on_curve() }
+            OP_VERIFY
+        }
+
+        // B
+        { Fq2::copy(num_line_elements + num_exp_hints + 2) }
+        { Fq2::copy(num_line_elements + num_exp_hints + 2) }
+        { G2Affine::is_on_curve() }
+        OP_VERIFY
+        { Fq2::copy(num_line_elements + num_exp_hints + 2) }
+        { Fq2::copy(num_line_elements + num_exp_hints + 2) }
+        { G2Affine::is_in_subgroup() }
+        OP_VERIFY
+    }
 }
 
 // Groth16's pairing verifier
@@ -137,6 +497,7 @@ impl Verifier {
 //  @hint: expect final_f
 //
 // verify c^lambda = f * wi, namely c_inv^lambda * f * wi = 1
+#[deprecated(note = "takes the hint as a parameter, use `Verifier::locking_script`")]
 pub fn check_pairing(precompute_lines: &Vec<G2Prepared>, hint: ark_bn254::Fq12) -> Script {
     script! {
         // Input stack: [beta_12, beta_13, beta_22, P1, P2, P3, P4, Q4, c, c_inv, wi, T4]
@@ -151,7 +512,7 @@ pub fn check_pairing(precompute_lines: &Vec<G2Prepared>, hint: ark_bn254::Fq12)
 }
 
 // Push constants to stack
-// Return Stack: [beta_12, beta_13, beta_22, 1/2, B]
+// Return Stack: [beta_12, beta_13, beta_22]
 fn constants() -> Script {
     script! {
         // beta_12
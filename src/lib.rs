@@ -13,6 +13,7 @@ use bitcoin::{hashes::Hash, hex::DisplayHex, Opcode, ScriptBuf, TapLeafHash, Tra
 use bitcoin_scriptexec::{Exec, ExecCtx, ExecError, ExecStats, Options, Stack, TxTemplate};
 
 pub mod bigint;
+pub mod bls12_381;
 pub mod bn254;
 pub mod bridge;
 pub mod fflonk;
@@ -157,6 +158,17 @@ pub fn run(script: treepp::Script) {
 //
 // NOTE: Only for test purposes.
 pub fn execute_script_without_stack_limit(script: treepp::Script) -> ExecuteInfo {
+    execute_script_with_witness_without_stack_limit(script, vec![])
+}
+
+// Execute a script on top of the witness stack items, the first item being at the bottom of the
+// stack, without `MAX_STACK_SIZE` limit.
+//
+// NOTE: Only for test purposes.
+pub fn execute_script_with_witness_without_stack_limit(
+    script: treepp::Script,
+    witness: Vec<Vec<u8>>,
+) -> ExecuteInfo {
     // Get the default options for the script exec.
     let mut opts = Options::default();
     // Do not enforce the stack limit.
@@ -177,7 +189,7 @@ pub fn execute_script_without_stack_limit(script: treepp::Script) -> ExecuteInfo
             taproot_annex_scriptleaf: Some((TapLeafHash::all_zeros(), None)),
         },
         script.compile(),
-        vec![],
+        witness,
     )
     .expect("error creating exec");
 
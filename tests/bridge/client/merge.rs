@@ -11,7 +11,7 @@ use bitvm::bridge::{
     transactions::base::Input,
 };
 
-use crate::bridge::setup::setup_test;
+use crate::bridge::setup::{setup_test, test_timelock_config};
 
 #[tokio::test]
 // TODO: test merging signatures after Musig2 feature is ready
@@ -23,6 +23,7 @@ async fn test_merge_add_new_graph() {
         version: data.version + 1,
         peg_in_graphs: vec![new_peg_in_graph.clone()],
         peg_out_graphs: vec![new_peg_out_graph.clone()],
+        operator_liquidity: vec![],
     };
 
     assert_eq!(data.peg_in_graphs.len(), 1);
@@ -87,7 +88,7 @@ async fn setup_and_create_graphs() -> (BitVMClient, PegInGraph, PegOutGraph) {
         amount,
     };
     let peg_in_graph_id = client
-        .create_peg_in_graph(input, &depositor_evm_address)
+        .create_peg_in_graph(input, &depositor_evm_address, &test_timelock_config())
         .await;
 
     client
@@ -107,6 +108,7 @@ async fn setup_and_create_graphs() -> (BitVMClient, PegInGraph, PegOutGraph) {
             amount: Amount::from_sat(INITIAL_AMOUNT),
         },
         &depositor_evm_address,
+        &test_timelock_config(),
     );
 
     let new_peg_out_graph = PegOutGraph::new(
@@ -2,5 +2,7 @@ pub mod merge;
 pub mod musig2_keys;
 pub mod musig2_peg_in;
 pub mod musig2_peg_out;
+pub mod operator_registry;
 pub mod sync;
 pub mod validate;
+pub mod withdrawer;
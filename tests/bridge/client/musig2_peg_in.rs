@@ -10,7 +10,10 @@ use bitvm::bridge::{
 
 use tokio::time::sleep;
 
-use super::super::{helper::generate_stub_outpoint, setup::setup_test};
+use super::super::{
+    helper::generate_stub_outpoint,
+    setup::{setup_test, test_timelock_config},
+};
 
 #[tokio::test]
 async fn test_musig2_peg_in() {
@@ -49,7 +52,11 @@ async fn test_musig2_peg_in() {
     .await;
 
     let graph_id = depositor_operator_verifier_0_client
-        .create_peg_in_graph(Input { outpoint, amount }, &depositor_evm_address)
+        .create_peg_in_graph(
+            Input { outpoint, amount },
+            &depositor_evm_address,
+            &test_timelock_config(),
+        )
         .await;
     println!("Depositor: Created new graph {graph_id}");
 
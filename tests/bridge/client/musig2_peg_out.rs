@@ -12,7 +12,7 @@ use tokio::time::sleep;
 
 use crate::bridge::{
     helper::{generate_stub_outpoint, verify_funding_inputs, TX_WAIT_TIME},
-    setup::setup_test,
+    setup::{setup_test, test_timelock_config},
 };
 
 #[tokio::test]
@@ -304,6 +304,7 @@ async fn create_peg_in_graph(
                 amount: deposit_amount,
             },
             depositor_evm_address,
+            &test_timelock_config(),
         )
         .await;
 
@@ -0,0 +1,222 @@
+use bitcoin::{hashes::Hash, Amount, Network, OutPoint, PublicKey, Txid};
+use std::collections::HashSet;
+
+use bitvm::bridge::{
+    client::operator_registry::{
+        retain_valid_operator_liquidity, OperatorLiquidity, OperatorRegistry, SelectionPolicy,
+    },
+    contexts::{
+        base::generate_keys_from_secret, depositor::DepositorContext, operator::OperatorContext,
+        verifier::VerifierContext, withdrawer::WithdrawerContext,
+    },
+    graphs::{
+        base::{
+            BaseGraph, DEPOSITOR_EVM_ADDRESS, DEPOSITOR_SECRET, INITIAL_AMOUNT, OPERATOR_SECRET,
+            VERIFIER_0_SECRET, VERIFIER_1_SECRET, WITHDRAWER_EVM_ADDRESS, WITHDRAWER_SECRET,
+        },
+        peg_in::PegInGraph,
+        peg_out::PegOutGraph,
+    },
+    signing_session::transport::run_local_session,
+    timelocks::TimelockConfig,
+    transactions::base::Input,
+};
+
+const SECOND_OPERATOR_SECRET: &str =
+    "fc294c70faf210d4d0807ea7a3dba8f7e41700d90c119e1ae82a0687d89d297f";
+
+fn stub_input(vout: u32) -> Input {
+    Input {
+        outpoint: OutPoint {
+            txid: Txid::all_zeros(),
+            vout,
+        },
+        amount: Amount::from_sat(INITIAL_AMOUNT),
+    }
+}
+
+struct Federation {
+    peg_in_graph: PegInGraph,
+    peg_out_graphs: Vec<PegOutGraph>,
+    operator_contexts: Vec<OperatorContext>,
+    withdrawer_context: WithdrawerContext,
+}
+
+// One peg-in graph with a presigned peg-out graph for each of two operators.
+fn setup() -> Federation {
+    let network = Network::Regtest;
+    let n_of_n_public_keys: Vec<PublicKey> = [VERIFIER_0_SECRET, VERIFIER_1_SECRET]
+        .iter()
+        .map(|secret| generate_keys_from_secret(network, secret).2)
+        .collect();
+    let verifier_contexts: Vec<VerifierContext> = [VERIFIER_0_SECRET, VERIFIER_1_SECRET]
+        .iter()
+        .map(|secret| VerifierContext::new(network, secret, &n_of_n_public_keys))
+        .collect();
+    let depositor_context = DepositorContext::new(network, DEPOSITOR_SECRET, &n_of_n_public_keys);
+    let operator_contexts: Vec<OperatorContext> = [OPERATOR_SECRET, SECOND_OPERATOR_SECRET]
+        .iter()
+        .map(|secret| OperatorContext::new(network, secret, &n_of_n_public_keys))
+        .collect();
+
+    let peg_in_graph = PegInGraph::new(
+        &depositor_context,
+        stub_input(0),
+        DEPOSITOR_EVM_ADDRESS,
+        &TimelockConfig::regtest(),
+    );
+    let peg_out_graphs = operator_contexts
+        .iter()
+        .zip(1..)
+        .map(|(operator_context, vout)| {
+            let peg_out_graph = PegOutGraph::new(operator_context, &peg_in_graph, stub_input(vout));
+            run_local_session(&peg_out_graph, &verifier_contexts).unwrap()
+        })
+        .collect();
+
+    Federation {
+        peg_in_graph,
+        peg_out_graphs,
+        operator_contexts,
+        withdrawer_context: WithdrawerContext::new(network, WITHDRAWER_SECRET, &n_of_n_public_keys),
+    }
+}
+
+fn registry(
+    federation: &Federation,
+    operator_liquidity: &[OperatorLiquidity],
+    settled_graph_ids: &HashSet<String>,
+) -> OperatorRegistry {
+    OperatorRegistry::new(
+        &[federation.peg_in_graph.clone()],
+        &federation.peg_out_graphs,
+        operator_liquidity,
+        settled_graph_ids,
+    )
+}
+
+fn selected_operator(
+    registry: &OperatorRegistry,
+    federation: &Federation,
+    policy: SelectionPolicy,
+) -> PublicKey {
+    let (record, peg_out_graph_id) = registry
+        .select_operator(federation.peg_in_graph.id(), policy)
+        .unwrap();
+    assert!(federation
+        .peg_out_graphs
+        .iter()
+        .any(|graph| graph.id() == peg_out_graph_id
+            && *graph.operator_public_key() == record.operator_public_key));
+
+    record.operator_public_key
+}
+
+#[test]
+fn test_select_operator_by_liquidity() {
+    let federation = setup();
+    let [first, second] = [0, 1].map(|i| &federation.operator_contexts[i]);
+
+    let result = registry(&federation, &[], &HashSet::new())
+        .select_operator(federation.peg_in_graph.id(), SelectionPolicy::default());
+    assert!(result.is_err_and(|err| err.contains("No operator with enough liquidity")));
+
+    let declarations = [
+        OperatorLiquidity::new(first, Amount::from_sat(2 * INITIAL_AMOUNT), 0),
+        OperatorLiquidity::new(second, Amount::from_sat(3 * INITIAL_AMOUNT), 0),
+    ];
+    let registry_0 = registry(&federation, &declarations, &HashSet::new());
+    assert_eq!(
+        selected_operator(
+            &registry_0,
+            &federation,
+            SelectionPolicy::MostAvailableLiquidity
+        ),
+        second.operator_public_key
+    );
+
+    // A later declaration replaces the earlier one, a forged one is ignored.
+    let mut forged = OperatorLiquidity::new(second, Amount::from_sat(1), 2);
+    forged.liquidity = 4 * INITIAL_AMOUNT;
+    let declarations = [
+        declarations[0].clone(),
+        declarations[1].clone(),
+        OperatorLiquidity::new(second, Amount::from_sat(1), 1),
+        forged,
+    ];
+    let registry_1 = registry(&federation, &declarations, &HashSet::new());
+    for policy in [
+        SelectionPolicy::MostAvailableLiquidity,
+        SelectionPolicy::FewestOpenPegOuts,
+    ] {
+        assert_eq!(
+            selected_operator(&registry_1, &federation, policy),
+            first.operator_public_key
+        );
+    }
+}
+
+#[test]
+fn test_requested_peg_out_commits_liquidity() {
+    let mut federation = setup();
+    let first = &federation.operator_contexts[0];
+    let declarations = [OperatorLiquidity::new(
+        first,
+        Amount::from_sat(INITIAL_AMOUNT),
+        0,
+    )];
+    let first = first.operator_public_key;
+
+    let requested_graph = federation
+        .peg_out_graphs
+        .iter_mut()
+        .find(|graph| *graph.operator_public_key() == first)
+        .unwrap();
+    requested_graph
+        .request_peg_out(&federation.withdrawer_context, WITHDRAWER_EVM_ADDRESS)
+        .unwrap();
+    let requested_graph_id = requested_graph.id().clone();
+
+    let open_registry = registry(&federation, &declarations, &HashSet::new());
+    let record = open_registry.operator(&first).unwrap();
+    assert_eq!(record.graph_ids, vec![requested_graph_id.clone()]);
+    assert_eq!(record.open_peg_outs, vec![requested_graph_id.clone()]);
+    assert!(record.requestable_graphs.is_empty());
+    assert!(record.committed > Amount::ZERO);
+    assert!(record.available_liquidity() < Amount::from_sat(INITIAL_AMOUNT));
+    assert!(open_registry
+        .select_operator(federation.peg_in_graph.id(), SelectionPolicy::default())
+        .is_err());
+
+    let settled_registry = registry(
+        &federation,
+        &declarations,
+        &HashSet::from([requested_graph_id]),
+    );
+    let record = settled_registry.operator(&first).unwrap();
+    assert!(record.open_peg_outs.is_empty());
+    assert_eq!(
+        record.available_liquidity(),
+        Amount::from_sat(INITIAL_AMOUNT)
+    );
+}
+
+#[test]
+fn test_retain_valid_operator_liquidity() {
+    let federation = setup();
+    let [first, second] = [0, 1].map(|index| &federation.operator_contexts[index]);
+    let mut forged = OperatorLiquidity::new(second, Amount::from_sat(1), 1);
+    forged.liquidity = 4 * INITIAL_AMOUNT;
+    let mut declarations = vec![
+        OperatorLiquidity::new(first, Amount::from_sat(INITIAL_AMOUNT), 0),
+        forged,
+        OperatorLiquidity::new(second, Amount::from_sat(INITIAL_AMOUNT), 0),
+    ];
+
+    retain_valid_operator_liquidity(&mut declarations);
+
+    assert_eq!(declarations.len(), 2);
+    assert!(declarations
+        .iter()
+        .all(|declaration| declaration.verify() && declaration.sequence == 0));
+}
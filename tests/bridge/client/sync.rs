@@ -6,7 +6,10 @@ use bitvm::bridge::{
     transactions::base::Input,
 };
 
-use super::super::{helper::generate_stub_outpoint, setup::setup_test};
+use super::super::{
+    helper::generate_stub_outpoint,
+    setup::{setup_test, test_timelock_config},
+};
 
 #[tokio::test]
 async fn test_sync() {
@@ -48,7 +51,11 @@ async fn test_sync() {
     .await;
 
     let peg_in_graph_id = client
-        .create_peg_in_graph(Input { outpoint, amount }, &depositor_evm_address)
+        .create_peg_in_graph(
+            Input { outpoint, amount },
+            &depositor_evm_address,
+            &test_timelock_config(),
+        )
         .await;
 
     client
@@ -13,7 +13,7 @@ use bitvm::bridge::{
     transactions::{base::Input, pre_signed::PreSignedTransaction},
 };
 
-use super::super::setup::setup_test;
+use super::super::setup::{setup_test, test_timelock_config};
 
 #[tokio::test]
 async fn test_validate_success() {
@@ -132,6 +132,7 @@ async fn setup_and_create_graphs() -> (BitVMClientPublicData, OutPoint) {
             amount: amount_0,
         },
         &depositor_evm_address,
+        &test_timelock_config(),
     );
 
     let peg_in_graph_1 = PegInGraph::new(
@@ -141,6 +142,7 @@ async fn setup_and_create_graphs() -> (BitVMClientPublicData, OutPoint) {
             amount: amount_1,
         },
         &depositor_evm_address,
+        &test_timelock_config(),
     );
 
     let peg_out_graph = PegOutGraph::new(
@@ -156,6 +158,7 @@ async fn setup_and_create_graphs() -> (BitVMClientPublicData, OutPoint) {
         version: 1,
         peg_in_graphs: vec![peg_in_graph_0, peg_in_graph_1],
         peg_out_graphs: vec![peg_out_graph],
+        operator_liquidity: vec![],
     };
 
     return (data, peg_in_outpoint);
@@ -0,0 +1,202 @@
+use bitcoin::{hashes::Hash, Amount, Network, OutPoint, PublicKey, Txid};
+
+use bitvm::bridge::{
+    client::chain::chain::PegOutEvent,
+    contexts::{
+        base::generate_keys_from_secret, depositor::DepositorContext, operator::OperatorContext,
+        verifier::VerifierContext, withdrawer::WithdrawerContext,
+    },
+    graphs::{
+        base::{
+            BaseGraph, DEPOSITOR_EVM_ADDRESS, DEPOSITOR_SECRET, INITIAL_AMOUNT, OPERATOR_SECRET,
+            VERIFIER_0_SECRET, VERIFIER_1_SECRET, WITHDRAWER_EVM_ADDRESS, WITHDRAWER_SECRET,
+        },
+        peg_in::PegInGraph,
+        peg_out::PegOutGraph,
+    },
+    signing_session::transport::run_local_session,
+    timelocks::TimelockConfig,
+    transactions::{base::Input, pre_signed::PreSignedTransaction},
+};
+
+fn stub_input(vout: u32) -> Input {
+    Input {
+        outpoint: OutPoint {
+            txid: Txid::all_zeros(),
+            vout,
+        },
+        amount: Amount::from_sat(INITIAL_AMOUNT),
+    }
+}
+
+fn create_peg_out_graph() -> (PegOutGraph, WithdrawerContext) {
+    let (_, peg_out_graph, withdrawer_context) = create_graphs();
+
+    (peg_out_graph, withdrawer_context)
+}
+
+fn create_graphs() -> (PegInGraph, PegOutGraph, WithdrawerContext) {
+    let network = Network::Regtest;
+    let n_of_n_public_keys: Vec<PublicKey> = [VERIFIER_0_SECRET, VERIFIER_1_SECRET]
+        .iter()
+        .map(|secret| generate_keys_from_secret(network, secret).2)
+        .collect();
+    let depositor_context = DepositorContext::new(network, DEPOSITOR_SECRET, &n_of_n_public_keys);
+    let operator_context = OperatorContext::new(network, OPERATOR_SECRET, &n_of_n_public_keys);
+
+    let peg_in_graph = PegInGraph::new(
+        &depositor_context,
+        stub_input(0),
+        DEPOSITOR_EVM_ADDRESS,
+        &TimelockConfig::regtest(),
+    );
+    let peg_out_graph = PegOutGraph::new(&operator_context, &peg_in_graph, stub_input(1));
+
+    let withdrawer_context =
+        WithdrawerContext::new(network, WITHDRAWER_SECRET, &n_of_n_public_keys);
+
+    (peg_in_graph, peg_out_graph, withdrawer_context)
+}
+
+fn presign(peg_out_graph: &PegOutGraph, withdrawer_context: &WithdrawerContext) -> PegOutGraph {
+    let verifier_contexts: Vec<VerifierContext> = [VERIFIER_0_SECRET, VERIFIER_1_SECRET]
+        .iter()
+        .map(|secret| {
+            VerifierContext::new(
+                withdrawer_context.network,
+                secret,
+                &withdrawer_context.n_of_n_public_keys,
+            )
+        })
+        .collect();
+
+    run_local_session(peg_out_graph, &verifier_contexts).unwrap()
+}
+
+#[test]
+fn test_request_peg_out_requires_presigned_graph() {
+    let (mut peg_out_graph, withdrawer_context) = create_peg_out_graph();
+
+    let result = peg_out_graph.request_peg_out(&withdrawer_context, WITHDRAWER_EVM_ADDRESS);
+
+    assert!(result.is_err_and(|err| err.contains("not presigned")));
+    assert!(peg_out_graph.withdrawer_public_key().is_none());
+}
+
+#[test]
+fn test_request_peg_out() {
+    let (peg_out_graph, withdrawer_context) = create_peg_out_graph();
+    let mut peg_out_graph = presign(&peg_out_graph, &withdrawer_context);
+
+    peg_out_graph
+        .request_peg_out(&withdrawer_context, WITHDRAWER_EVM_ADDRESS)
+        .unwrap();
+    assert_eq!(
+        peg_out_graph.withdrawer_public_key(),
+        Some(&withdrawer_context.withdrawer_public_key)
+    );
+    assert_eq!(
+        peg_out_graph.withdrawer_evm_address().map(String::as_str),
+        Some(WITHDRAWER_EVM_ADDRESS)
+    );
+    assert!(peg_out_graph.validate());
+
+    // The same withdrawer may update its request, nobody else can take it over.
+    assert!(peg_out_graph
+        .request_peg_out(&withdrawer_context, DEPOSITOR_EVM_ADDRESS)
+        .is_ok());
+    let other_withdrawer_context = WithdrawerContext::new(
+        withdrawer_context.network,
+        DEPOSITOR_SECRET,
+        &withdrawer_context.n_of_n_public_keys,
+    );
+    assert!(peg_out_graph
+        .request_peg_out(&other_withdrawer_context, WITHDRAWER_EVM_ADDRESS)
+        .is_err_and(|err| err.contains("another withdrawer")));
+}
+
+#[test]
+fn test_merge_peg_out_request() {
+    let (peg_out_graph, withdrawer_context) = create_peg_out_graph();
+    let mut peg_out_graph = presign(&peg_out_graph, &withdrawer_context);
+    let mut requested_peg_out_graph = peg_out_graph.clone();
+    requested_peg_out_graph
+        .request_peg_out(&withdrawer_context, WITHDRAWER_EVM_ADDRESS)
+        .unwrap();
+
+    peg_out_graph.merge(&requested_peg_out_graph);
+
+    assert_eq!(peg_out_graph.id(), requested_peg_out_graph.id());
+    assert_eq!(
+        peg_out_graph.withdrawer_public_key(),
+        Some(&withdrawer_context.withdrawer_public_key)
+    );
+    assert!(peg_out_graph.peg_out_transaction_ref().is_none());
+}
+
+#[test]
+fn test_peg_out_request_is_signed_by_withdrawer() {
+    let (peg_out_graph, withdrawer_context) = create_peg_out_graph();
+    let mut peg_out_graph = presign(&peg_out_graph, &withdrawer_context);
+    assert!(peg_out_graph.verify_peg_out_request().is_err());
+
+    peg_out_graph
+        .request_peg_out(&withdrawer_context, WITHDRAWER_EVM_ADDRESS)
+        .unwrap();
+    assert!(peg_out_graph.verify_peg_out_request().is_ok());
+
+    // Redirecting the request to another EVM address invalidates the withdrawer's signature.
+    let mut tampered = serde_json::to_value(&peg_out_graph).unwrap();
+    tampered["withdrawer_evm_address"] = DEPOSITOR_EVM_ADDRESS.into();
+    let tampered_peg_out_graph: PegOutGraph = serde_json::from_value(tampered).unwrap();
+    assert!(tampered_peg_out_graph
+        .verify_peg_out_request()
+        .is_err_and(|err| err.contains("Invalid withdrawer signature")));
+    assert!(!tampered_peg_out_graph.validate());
+}
+
+#[test]
+fn test_find_peg_out_event() {
+    let (peg_in_graph, peg_out_graph, withdrawer_context) = create_graphs();
+    let mut peg_out_graph = presign(&peg_out_graph, &withdrawer_context);
+    peg_out_graph
+        .request_peg_out(&withdrawer_context, WITHDRAWER_EVM_ADDRESS)
+        .unwrap();
+
+    let amount = Amount::from_sat(INITIAL_AMOUNT);
+    let peg_out_event = || PegOutEvent {
+        withdrawer_chain_address: WITHDRAWER_EVM_ADDRESS.to_lowercase(),
+        withdrawer_public_key_hash: withdrawer_context.withdrawer_public_key.pubkey_hash(),
+        source_outpoint: OutPoint {
+            txid: peg_in_graph
+                .peg_in_confirm_transaction_ref()
+                .tx()
+                .compute_txid(),
+            vout: 0,
+        },
+        amount,
+        operator_public_key: *peg_out_graph.operator_public_key(),
+        timestamp: 1722328130,
+    };
+
+    let events = [peg_out_event()];
+    assert_eq!(
+        peg_out_graph
+            .find_peg_out_event(&events, amount)
+            .map(|event| event.timestamp),
+        Some(1722328130)
+    );
+    assert!(peg_out_graph
+        .find_peg_out_event(&events, amount - Amount::from_sat(1))
+        .is_none());
+
+    let mut other_withdrawer = peg_out_event();
+    other_withdrawer.withdrawer_public_key_hash = peg_out_graph.operator_public_key().pubkey_hash();
+    let mut other_address = peg_out_event();
+    other_address.withdrawer_chain_address = DEPOSITOR_EVM_ADDRESS.to_string();
+    let mut other_peg_in = peg_out_event();
+    other_peg_in.source_outpoint.txid = Txid::all_zeros();
+    assert!(peg_out_graph
+        .find_peg_out_event(&[other_withdrawer, other_address, other_peg_in], amount)
+        .is_none());
+}
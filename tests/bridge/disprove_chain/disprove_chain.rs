@@ -15,7 +15,10 @@ mod tests {
         },
     };
 
-    use super::super::super::{helper::generate_stub_outpoint, setup::setup_test};
+    use super::super::super::{
+        helper::generate_stub_outpoint,
+        setup::{setup_test, test_timelock_config},
+    };
 
     #[tokio::test]
     async fn test_should_be_able_to_submit_disprove_chain_tx_successfully() {
@@ -45,8 +48,11 @@ mod tests {
         let outpoint =
             generate_stub_outpoint(&client, &connector_b.generate_taproot_address(), amount).await;
 
-        let mut disprove_chain_tx =
-            DisproveChainTransaction::new(&operator_context, Input { outpoint, amount });
+        let mut disprove_chain_tx = DisproveChainTransaction::new(
+            &operator_context,
+            Input { outpoint, amount },
+            &test_timelock_config(),
+        );
 
         let secret_nonces_0 = disprove_chain_tx.push_nonces(&verifier_0_context);
         let secret_nonces_1 = disprove_chain_tx.push_nonces(&verifier_1_context);
@@ -93,8 +99,11 @@ mod tests {
         let outpoint =
             generate_stub_outpoint(&client, &connector_b.generate_taproot_address(), amount).await;
 
-        let mut disprove_chain_tx =
-            DisproveChainTransaction::new(&operator_context, Input { outpoint, amount });
+        let mut disprove_chain_tx = DisproveChainTransaction::new(
+            &operator_context,
+            Input { outpoint, amount },
+            &test_timelock_config(),
+        );
 
         let secret_nonces_0 = disprove_chain_tx.push_nonces(&verifier_0_context);
         let secret_nonces_1 = disprove_chain_tx.push_nonces(&verifier_1_context);
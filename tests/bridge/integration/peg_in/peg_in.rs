@@ -16,7 +16,10 @@ use bitvm::bridge::{
 use esplora_client::Error;
 use tokio::time::sleep;
 
-use crate::bridge::{helper::generate_stub_outpoint, setup::setup_test};
+use crate::bridge::{
+    helper::generate_stub_outpoint,
+    setup::{setup_test, test_timelock_config},
+};
 
 #[tokio::test]
 async fn test_peg_in_success() {
@@ -57,8 +60,12 @@ async fn test_peg_in_success() {
         amount: deposit_input_amount,
     };
 
-    let peg_in_deposit =
-        PegInDepositTransaction::new(&depositor_context, &depositor_evm_address, deposit_input);
+    let peg_in_deposit = PegInDepositTransaction::new(
+        &depositor_context,
+        &depositor_evm_address,
+        deposit_input,
+        &test_timelock_config(),
+    );
 
     let peg_in_deposit_tx = peg_in_deposit.finalize();
     let deposit_txid = peg_in_deposit_tx.compute_txid();
@@ -78,8 +85,12 @@ async fn test_peg_in_success() {
         outpoint: confirm_funding_outpoint,
         amount: peg_in_deposit_tx.output[output_index as usize].value,
     };
-    let mut peg_in_confirm =
-        PegInConfirmTransaction::new(&depositor_context, &depositor_evm_address, confirm_input);
+    let mut peg_in_confirm = PegInConfirmTransaction::new(
+        &depositor_context,
+        &depositor_evm_address,
+        confirm_input,
+        &test_timelock_config(),
+    );
 
     let secret_nonces_0 = peg_in_confirm.push_nonces(&verifier_0_context);
     let secret_nonces_1 = peg_in_confirm.push_nonces(&verifier_1_context);
@@ -162,8 +173,12 @@ async fn test_peg_in_time_lock_not_surpassed() {
         amount: deposit_input_amount,
     };
 
-    let peg_in_deposit =
-        PegInDepositTransaction::new(&depositor_context, &depositor_evm_address, deposit_input);
+    let peg_in_deposit = PegInDepositTransaction::new(
+        &depositor_context,
+        &depositor_evm_address,
+        deposit_input,
+        &test_timelock_config(),
+    );
     let peg_in_deposit_tx = peg_in_deposit.finalize();
     let deposit_txid = peg_in_deposit_tx.compute_txid();
 
@@ -181,8 +196,12 @@ async fn test_peg_in_time_lock_not_surpassed() {
         outpoint: refund_funding_outpoint,
         amount: peg_in_deposit_tx.output[output_index as usize].value,
     };
-    let peg_in_refund =
-        PegInRefundTransaction::new(&depositor_context, &depositor_evm_address, refund_input);
+    let peg_in_refund = PegInRefundTransaction::new(
+        &depositor_context,
+        &depositor_evm_address,
+        refund_input,
+        &test_timelock_config(),
+    );
     let peg_in_refund_tx = peg_in_refund.finalize();
 
     // mine peg-in refund
@@ -237,8 +256,12 @@ async fn test_peg_in_time_lock_surpassed() {
         amount: deposit_input_amount,
     };
 
-    let peg_in_deposit =
-        PegInDepositTransaction::new(&depositor_context, &depositor_evm_address, deposit_input);
+    let peg_in_deposit = PegInDepositTransaction::new(
+        &depositor_context,
+        &depositor_evm_address,
+        deposit_input,
+        &test_timelock_config(),
+    );
     let peg_in_deposit_tx = peg_in_deposit.finalize();
     let deposit_txid = peg_in_deposit_tx.compute_txid();
 
@@ -256,8 +279,12 @@ async fn test_peg_in_time_lock_surpassed() {
         outpoint: refund_funding_outpoint,
         amount: peg_in_deposit_tx.output[output_index as usize].value,
     };
-    let peg_in_refund =
-        PegInRefundTransaction::new(&depositor_context, &depositor_evm_address, refund_input);
+    let peg_in_refund = PegInRefundTransaction::new(
+        &depositor_context,
+        &depositor_evm_address,
+        refund_input,
+        &test_timelock_config(),
+    );
     let peg_in_refund_tx = peg_in_refund.finalize();
     let refund_txid = peg_in_refund_tx.compute_txid();
 
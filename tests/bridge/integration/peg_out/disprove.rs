@@ -10,8 +10,9 @@ use bitvm::bridge::{
 };
 
 use crate::bridge::{
-    helper::verify_funding_inputs, integration::peg_out::utils::create_and_mine_kick_off_2_tx,
-    setup::setup_test,
+    helper::verify_funding_inputs,
+    integration::peg_out::utils::create_and_mine_kick_off_2_tx,
+    setup::{setup_test, test_timelock_config},
 };
 
 #[tokio::test]
@@ -67,7 +68,8 @@ async fn test_disprove_success() {
         },
         amount: kick_off_2_tx.output[vout as usize].value,
     };
-    let mut assert = AssertTransaction::new(&operator_context, assert_input_0);
+    let mut assert =
+        AssertTransaction::new(&operator_context, assert_input_0, &test_timelock_config());
 
     let secret_nonces_0 = assert.push_nonces(&verifier_0_context);
     let secret_nonces_1 = assert.push_nonces(&verifier_1_context);
@@ -12,8 +12,9 @@ use bitvm::bridge::{
 };
 
 use crate::bridge::{
-    helper::verify_funding_inputs, integration::peg_out::utils::create_and_mine_kick_off_2_tx,
-    setup::setup_test,
+    helper::verify_funding_inputs,
+    integration::peg_out::utils::create_and_mine_kick_off_2_tx,
+    setup::{setup_test, test_timelock_config},
 };
 
 #[tokio::test]
@@ -70,8 +71,11 @@ async fn test_disprove_chain_success() {
         amount: kick_off_2_tx.output[vout as usize].value,
     };
 
-    let mut disprove_chain =
-        DisproveChainTransaction::new(&operator_context, disprove_chain_input_0);
+    let mut disprove_chain = DisproveChainTransaction::new(
+        &operator_context,
+        disprove_chain_input_0,
+        &test_timelock_config(),
+    );
 
     let secret_nonces_0 = disprove_chain.push_nonces(&verifier_0_context);
     let secret_nonces_1 = disprove_chain.push_nonces(&verifier_1_context);
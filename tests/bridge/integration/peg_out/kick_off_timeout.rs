@@ -12,8 +12,9 @@ use bitvm::bridge::{
 };
 
 use crate::bridge::{
-    helper::verify_funding_inputs, integration::peg_out::utils::create_and_mine_kick_off_1_tx,
-    setup::setup_test,
+    helper::verify_funding_inputs,
+    integration::peg_out::utils::create_and_mine_kick_off_1_tx,
+    setup::{setup_test, test_timelock_config},
 };
 
 #[tokio::test]
@@ -70,8 +71,11 @@ async fn test_kick_off_timeout_success() {
         amount: kick_off_1_tx.output[vout as usize].value,
     };
 
-    let mut kick_off_timeout =
-        KickOffTimeoutTransaction::new(&operator_context, kick_off_timeout_input_0);
+    let mut kick_off_timeout = KickOffTimeoutTransaction::new(
+        &operator_context,
+        kick_off_timeout_input_0,
+        &test_timelock_config(),
+    );
 
     let secret_nonces_0 = kick_off_timeout.push_nonces(&verifier_0_context);
     let secret_nonces_1 = kick_off_timeout.push_nonces(&verifier_1_context);
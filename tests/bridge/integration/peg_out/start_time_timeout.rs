@@ -12,8 +12,9 @@ use bitvm::bridge::{
 };
 
 use crate::bridge::{
-    helper::verify_funding_inputs, integration::peg_out::utils::create_and_mine_kick_off_1_tx,
-    setup::setup_test,
+    helper::verify_funding_inputs,
+    integration::peg_out::utils::create_and_mine_kick_off_1_tx,
+    setup::{setup_test, test_timelock_config},
 };
 
 #[tokio::test]
@@ -81,6 +82,7 @@ async fn test_start_time_timeout_success() {
         &operator_context,
         start_time_timeout_input_0,
         start_time_timeout_input_1,
+        &test_timelock_config(),
     );
 
     let secret_nonces_0 = start_time_timeout.push_nonces(&verifier_0_context);
@@ -18,7 +18,7 @@ use crate::bridge::{
     integration::peg_out::utils::{
         create_and_mine_kick_off_1_tx, create_and_mine_peg_in_confirm_tx,
     },
-    setup::setup_test,
+    setup::{setup_test, test_timelock_config},
 };
 
 #[tokio::test]
@@ -91,7 +91,11 @@ async fn test_take_1_success() {
         },
         amount: kick_off_1_tx.output[vout as usize].value,
     };
-    let kick_off_2 = KickOff2Transaction::new(&operator_context, kick_off_2_input_0);
+    let kick_off_2 = KickOff2Transaction::new(
+        &operator_context,
+        kick_off_2_input_0,
+        &test_timelock_config(),
+    );
     let kick_off_2_tx = kick_off_2.finalize();
     let kick_off_2_txid = kick_off_2_tx.compute_txid();
 
@@ -140,6 +144,7 @@ async fn test_take_1_success() {
         take_1_input_1,
         take_1_input_2,
         take_1_input_3,
+        &test_timelock_config(),
     );
 
     let secret_nonces_0 = take_1.push_nonces(&verifier_0_context);
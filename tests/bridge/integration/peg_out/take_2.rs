@@ -15,7 +15,7 @@ use tokio::time::sleep;
 use crate::bridge::{
     helper::verify_funding_inputs,
     integration::peg_out::utils::{create_and_mine_assert_tx, create_and_mine_peg_in_confirm_tx},
-    setup::setup_test,
+    setup::{setup_test, test_timelock_config},
 };
 
 #[tokio::test]
@@ -118,6 +118,7 @@ async fn test_take_2_success() {
         take_2_input_1,
         take_2_input_2,
         take_2_input_3,
+        &test_timelock_config(),
     );
 
     let secret_nonces_0 = take_2.push_nonces(&verifier_0_context);
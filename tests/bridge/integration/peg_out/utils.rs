@@ -11,7 +11,7 @@ use bitvm::bridge::{
     },
 };
 
-use crate::bridge::helper::generate_stub_outpoint;
+use crate::bridge::{helper::generate_stub_outpoint, setup::test_timelock_config};
 
 pub async fn create_and_mine_kick_off_1_tx(
     client: &BitVMClient,
@@ -25,7 +25,8 @@ pub async fn create_and_mine_kick_off_1_tx(
         outpoint: kick_off_1_funding_outpoint,
         amount: input_amount,
     };
-    let kick_off_1 = KickOff1Transaction::new(&operator_context, kick_off_1_input);
+    let kick_off_1 =
+        KickOff1Transaction::new(&operator_context, kick_off_1_input, &test_timelock_config());
     let kick_off_1_tx = kick_off_1.finalize();
     let kick_off_1_txid = kick_off_1_tx.compute_txid();
 
@@ -48,7 +49,8 @@ pub async fn create_and_mine_kick_off_2_tx(
         outpoint: kick_off_2_funding_outpoint,
         amount: input_amount,
     };
-    let kick_off_2 = KickOff2Transaction::new(&operator_context, kick_off_2_input);
+    let kick_off_2 =
+        KickOff2Transaction::new(&operator_context, kick_off_2_input, &test_timelock_config());
     let kick_off_2_tx = kick_off_2.finalize();
     let kick_off_2_txid = kick_off_2_tx.compute_txid();
 
@@ -74,7 +76,8 @@ pub async fn create_and_mine_assert_tx(
         outpoint: assert_funding_outpoint,
         amount: input_amount,
     };
-    let mut assert = AssertTransaction::new(&operator_context, assert_input);
+    let mut assert =
+        AssertTransaction::new(&operator_context, assert_input, &test_timelock_config());
 
     let secret_nonces_0 = assert.push_nonces(&verifier_0_context);
     let secret_nonces_1 = assert.push_nonces(&verifier_1_context);
@@ -108,8 +111,12 @@ pub async fn create_and_mine_peg_in_confirm_tx(
         outpoint: peg_in_confirm_funding_outpoint,
         amount: input_amount,
     };
-    let mut peg_in_confirm =
-        PegInConfirmTransaction::new(depositor_context, evm_address, confirm_input);
+    let mut peg_in_confirm = PegInConfirmTransaction::new(
+        depositor_context,
+        evm_address,
+        confirm_input,
+        &test_timelock_config(),
+    );
 
     let secret_nonces_0 = peg_in_confirm.push_nonces(&verifier_0_context);
     let secret_nonces_1 = peg_in_confirm.push_nonces(&verifier_1_context);
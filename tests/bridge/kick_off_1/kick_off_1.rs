@@ -11,7 +11,7 @@ use bitvm::bridge::{
 
 use crate::bridge::helper::generate_stub_outpoint;
 
-use super::super::setup::setup_test;
+use super::super::setup::{setup_test, test_timelock_config};
 
 #[tokio::test]
 async fn test_kick_off_1_tx() {
@@ -30,7 +30,7 @@ async fn test_kick_off_1_tx() {
         amount: input_amount,
     };
 
-    let kick_off_1_tx = KickOff1Transaction::new(&operator_context, input);
+    let kick_off_1_tx = KickOff1Transaction::new(&operator_context, input, &test_timelock_config());
 
     let tx = kick_off_1_tx.finalize();
     println!("Script Path Spend Transaction: {:?}\n", tx);
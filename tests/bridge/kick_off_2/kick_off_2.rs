@@ -9,7 +9,10 @@ use bitvm::bridge::{
     },
 };
 
-use super::super::{helper::generate_stub_outpoint, setup::setup_test};
+use super::super::{
+    helper::generate_stub_outpoint,
+    setup::{setup_test, test_timelock_config},
+};
 
 #[tokio::test]
 async fn test_kick_off_2_tx() {
@@ -27,6 +30,7 @@ async fn test_kick_off_2_tx() {
             outpoint: funding_outpoint0,
             amount: input_value0,
         },
+        &test_timelock_config(),
     );
 
     let tx = kick_off_2_tx.finalize();
@@ -9,7 +9,10 @@ use bitvm::bridge::{
     },
 };
 
-use super::super::{helper::generate_stub_outpoint, setup::setup_test};
+use super::super::{
+    helper::generate_stub_outpoint,
+    setup::{setup_test, test_timelock_config},
+};
 
 #[tokio::test]
 async fn test_kick_off_timeout_tx() {
@@ -49,6 +52,7 @@ async fn test_kick_off_timeout_tx() {
             outpoint: outpoint_0,
             amount: input_value0,
         },
+        &test_timelock_config(),
     );
 
     let secret_nonces_0 = kick_off_timeout_tx.push_nonces(&verifier_0_context);
@@ -9,7 +9,10 @@ use bitvm::bridge::{
     },
 };
 
-use super::super::{helper::generate_stub_outpoint, setup::setup_test};
+use super::super::{
+    helper::generate_stub_outpoint,
+    setup::{setup_test, test_timelock_config},
+};
 
 #[tokio::test]
 async fn test_peg_in_deposit_tx() {
@@ -50,6 +53,7 @@ async fn test_peg_in_deposit_tx() {
         &depositor_context,
         &depositor_evm_address,
         Input { outpoint, amount },
+        &test_timelock_config(),
     );
 
     println!(
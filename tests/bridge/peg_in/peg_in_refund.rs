@@ -9,7 +9,10 @@ use bitvm::bridge::{
     },
 };
 
-use super::super::{helper::generate_stub_outpoint, setup::setup_test};
+use super::super::{
+    helper::generate_stub_outpoint,
+    setup::{setup_test, test_timelock_config},
+};
 
 #[tokio::test]
 async fn test_peg_in_refund_tx() {
@@ -43,6 +46,7 @@ async fn test_peg_in_refund_tx() {
         &depositor_context,
         &depositor_evm_address,
         Input { outpoint, amount },
+        &test_timelock_config(),
     );
 
     let tx = peg_in_refund_tx.finalize();
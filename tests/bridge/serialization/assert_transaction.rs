@@ -7,7 +7,10 @@ use bitvm::bridge::{
     transactions::{assert::AssertTransaction, base::Input},
 };
 
-use super::super::{helper::generate_stub_outpoint, setup::setup_test};
+use super::super::{
+    helper::generate_stub_outpoint,
+    setup::{setup_test, test_timelock_config},
+};
 
 #[tokio::test]
 async fn test_assert_tx_serialization() {
@@ -37,7 +40,11 @@ async fn test_assert_tx_serialization() {
     let outpoint =
         generate_stub_outpoint(&client, &connector_b.generate_taproot_address(), amount).await;
 
-    let mut assert_tx = AssertTransaction::new(&operator_context, Input { outpoint, amount });
+    let mut assert_tx = AssertTransaction::new(
+        &operator_context,
+        Input { outpoint, amount },
+        &test_timelock_config(),
+    );
 
     let secret_nonces_0 = assert_tx.push_nonces(&verifier_0_context);
     let secret_nonces_1 = assert_tx.push_nonces(&verifier_1_context);
@@ -0,0 +1,99 @@
+use std::str::FromStr;
+
+use bitcoin::{Amount, OutPoint, Txid};
+use serde_json::{json, Value};
+
+use bitvm::bridge::{
+    client::client::BitVMClientPublicData,
+    graphs::{
+        base::{FEE_AMOUNT, INITIAL_AMOUNT},
+        migration::migrate_client_data,
+        peg_in::PegInGraph,
+        peg_out::PegOutGraph,
+    },
+    serialization::serialize,
+    transactions::base::Input,
+};
+
+use super::{
+    super::setup::{setup_test, test_timelock_config},
+    into_graph_layout_0_1,
+};
+
+#[tokio::test]
+async fn test_client_data_0_1_round_trip() {
+    let (
+        _,
+        _,
+        depositor_context,
+        operator_context,
+        _,
+        _,
+        _,
+        _,
+        _,
+        _,
+        _,
+        _,
+        _,
+        _,
+        _,
+        _,
+        _,
+        depositor_evm_address,
+        _,
+    ) = setup_test().await;
+
+    let amount = Amount::from_sat(INITIAL_AMOUNT + FEE_AMOUNT);
+    let peg_in_outpoint = OutPoint {
+        txid: Txid::from_str("0e6719ac074b0e3cac76d057643506faa1c266b322aa9cf4c6f635fe63b14327")
+            .unwrap(),
+        vout: 0,
+    };
+    let peg_out_outpoint = OutPoint {
+        txid: Txid::from_str("4e254eab8a41f14f56491813a7100cebe305d84edf09488001d9dd3d180a4900")
+            .unwrap(),
+        vout: 0,
+    };
+
+    let peg_in_graph = PegInGraph::new(
+        &depositor_context,
+        Input {
+            outpoint: peg_in_outpoint,
+            amount,
+        },
+        &depositor_evm_address,
+        &test_timelock_config(),
+    );
+    let peg_out_graph = PegOutGraph::new(
+        &operator_context,
+        &peg_in_graph,
+        Input {
+            outpoint: peg_out_outpoint,
+            amount,
+        },
+    );
+
+    // Client data as written by a client that serialized graphs with version 0.1
+    let mut legacy_peg_in_graph = serde_json::from_str::<Value>(&serialize(&peg_in_graph)).unwrap();
+    into_graph_layout_0_1(&mut legacy_peg_in_graph);
+    let mut legacy_peg_out_graph =
+        serde_json::from_str::<Value>(&serialize(&peg_out_graph)).unwrap();
+    into_graph_layout_0_1(&mut legacy_peg_out_graph);
+    let mut data = json!({
+        "version": 1,
+        "peg_in_graphs": [legacy_peg_in_graph],
+        "peg_out_graphs": [legacy_peg_out_graph],
+    });
+
+    assert!(migrate_client_data(&mut data).is_ok());
+    let migrated_data = serde_json::from_value::<BitVMClientPublicData>(data).unwrap();
+    assert!(migrated_data.peg_in_graphs == vec![peg_in_graph]);
+    assert!(migrated_data.peg_out_graphs == vec![peg_out_graph]);
+
+    // Migrated data is saved with the current graph version and loads back unchanged
+    let mut saved_data = serde_json::from_str::<Value>(&serialize(&migrated_data)).unwrap();
+    assert!(migrate_client_data(&mut saved_data).is_ok());
+    let reloaded_data = serde_json::from_value::<BitVMClientPublicData>(saved_data).unwrap();
+    assert!(reloaded_data == migrated_data);
+}
@@ -1,3 +1,53 @@
+use bitcoin::{Network, XOnlyPublicKey};
+use serde_json::Value;
+
+use bitvm::bridge::connectors::{connector::TaprootConnector, connector_c::ConnectorC};
+
 pub mod assert_transaction;
+pub mod client_data;
 pub mod peg_in_graph;
 pub mod peg_out_graph;
+
+const CONNECTOR_C_LEAF_COUNT: u32 = 1000;
+
+// Rewrites graph data into the layout the client wrote with graph version 0.1: no timelock config
+// on the graph, and the leaves of connector c serialized instead of its output key.
+pub fn into_graph_layout_0_1(graph: &mut Value) {
+    graph["version"] = Value::from("0.1");
+    graph.as_object_mut().unwrap().remove("timelock_config");
+
+    for pointer in [
+        "/disprove_transaction/connector_c",
+        "/take_2_transaction/connector_c",
+    ] {
+        if let Some(connector_c) = graph.pointer_mut(pointer).and_then(Value::as_object_mut) {
+            let network =
+                serde_json::from_value::<Network>(connector_c["network"].clone()).unwrap();
+            let operator_taproot_public_key = serde_json::from_value::<XOnlyPublicKey>(
+                connector_c["operator_taproot_public_key"].clone(),
+            )
+            .unwrap();
+            let leaves = ConnectorC::new(network, &operator_taproot_public_key);
+
+            connector_c.remove("taproot_output_key");
+            connector_c.insert(
+                String::from("lock_scripts"),
+                serde_json::to_value(
+                    (0..CONNECTOR_C_LEAF_COUNT)
+                        .map(|index| leaves.generate_taproot_leaf_script(index))
+                        .collect::<Vec<_>>(),
+                )
+                .unwrap(),
+            );
+            connector_c.insert(
+                String::from("unlock_witnesses"),
+                serde_json::to_value(
+                    (0..CONNECTOR_C_LEAF_COUNT)
+                        .map(|index| leaves.generate_taproot_leaf_script_witness(index))
+                        .collect::<Vec<_>>(),
+                )
+                .unwrap(),
+            );
+        }
+    }
+}
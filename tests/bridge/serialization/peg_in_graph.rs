@@ -1,16 +1,24 @@
 use bitcoin::Amount;
+use serde_json::Value;
 
 use bitvm::bridge::{
     graphs::{
-        base::{FEE_AMOUNT, INITIAL_AMOUNT},
+        base::{FEE_AMOUNT, GRAPH_VERSION, INITIAL_AMOUNT},
+        migration::try_deserialize_peg_in_graph,
         peg_in::PegInGraph,
     },
     scripts::generate_pay_to_pubkey_script_address,
-    serialization::{deserialize, serialize},
+    serialization::{binary, deserialize, serialize},
     transactions::base::Input,
 };
 
-use super::super::{helper::generate_stub_outpoint, setup::setup_test};
+use super::{
+    super::{
+        helper::generate_stub_outpoint,
+        setup::{setup_test, test_timelock_config},
+    },
+    into_graph_layout_0_1,
+};
 
 #[tokio::test]
 async fn test_peg_in_graph_serialization() {
@@ -52,10 +60,29 @@ async fn test_peg_in_graph_serialization() {
         &depositor_context,
         Input { outpoint, amount },
         &depositor_evm_address,
+        &test_timelock_config(),
     );
 
     let json = serialize(&peg_in_graph);
     assert!(json.len() > 0);
     let deserialized_peg_in_graph = deserialize::<PegInGraph>(&json);
     assert!(peg_in_graph == deserialized_peg_in_graph);
+
+    let binary = binary::serialize(&peg_in_graph);
+    // Hex strings are stored as bytes and field names only once
+    assert!(binary.len() * 2 < json.len());
+    let deserialized_peg_in_graph = binary::deserialize::<PegInGraph>(&binary);
+    assert!(peg_in_graph == deserialized_peg_in_graph);
+    assert_eq!(binary, binary::serialize(&deserialized_peg_in_graph));
+
+    let mut legacy_peg_in_graph = serde_json::from_str::<Value>(&json).unwrap();
+    assert_eq!(legacy_peg_in_graph["version"], GRAPH_VERSION);
+    into_graph_layout_0_1(&mut legacy_peg_in_graph);
+    let migrated_peg_in_graph =
+        try_deserialize_peg_in_graph(&serialize(&legacy_peg_in_graph)).unwrap();
+    assert!(peg_in_graph == migrated_peg_in_graph);
+
+    let mut future_peg_in_graph = serde_json::from_str::<Value>(&json).unwrap();
+    future_peg_in_graph["version"] = Value::from("99.0");
+    assert!(try_deserialize_peg_in_graph(&serialize(&future_peg_in_graph)).is_err());
 }
@@ -1,17 +1,26 @@
 use bitcoin::Amount;
+use serde_json::Value;
 
 use bitvm::bridge::{
     graphs::{
-        base::{FEE_AMOUNT, INITIAL_AMOUNT},
+        base::{FEE_AMOUNT, GRAPH_VERSION, INITIAL_AMOUNT},
+        migration::{migrate_graphs, try_deserialize_peg_out_graph},
         peg_in::PegInGraph,
         peg_out::PegOutGraph,
     },
     scripts::generate_pay_to_pubkey_script_address,
-    serialization::{deserialize, serialize},
+    serialization::{binary, deserialize, serialize},
+    timelocks::TimelockConfig,
     transactions::base::Input,
 };
 
-use super::super::{helper::generate_stub_outpoint, setup::setup_test};
+use super::{
+    super::{
+        helper::generate_stub_outpoint,
+        setup::{setup_test, test_timelock_config},
+    },
+    into_graph_layout_0_1,
+};
 
 #[tokio::test]
 async fn test_peg_out_graph_serialization() {
@@ -53,6 +62,11 @@ async fn test_peg_out_graph_serialization() {
         &depositor_context,
         Input { outpoint, amount },
         &depositor_evm_address,
+        &TimelockConfig {
+            // Differs from the network default the peg-out migration would fall back to
+            num_blocks_peg_in_refund: 6,
+            ..test_timelock_config()
+        },
     );
 
     let kick_off_amount = Amount::from_sat(INITIAL_AMOUNT + FEE_AMOUNT); // Arbitrary amount
@@ -80,4 +94,35 @@ async fn test_peg_out_graph_serialization() {
     assert!(json.len() > 0);
     let deserialized_peg_out_graph = deserialize::<PegOutGraph>(&json);
     assert!(peg_out_graph == deserialized_peg_out_graph);
+
+    let binary = binary::serialize(&peg_out_graph);
+    // Hex strings are stored as bytes and field names only once
+    assert!(binary.len() * 2 < json.len());
+    let deserialized_peg_out_graph = binary::deserialize::<PegOutGraph>(&binary);
+    assert!(peg_out_graph == deserialized_peg_out_graph);
+    assert_eq!(binary, binary::serialize(&deserialized_peg_out_graph));
+
+    // Graphs serialized before 0.2 only stored the peg-in refund timelock on the peg-in graph
+    let mut legacy_peg_in_graph = serde_json::from_str::<Value>(&serialize(&peg_in_graph)).unwrap();
+    into_graph_layout_0_1(&mut legacy_peg_in_graph);
+    let mut legacy_peg_out_graph = serde_json::from_str::<Value>(&json).unwrap();
+    assert_eq!(legacy_peg_out_graph["version"], GRAPH_VERSION);
+    into_graph_layout_0_1(&mut legacy_peg_out_graph);
+    let mut peg_in_graphs = vec![legacy_peg_in_graph];
+    let mut peg_out_graphs = vec![legacy_peg_out_graph];
+    assert!(migrate_graphs(&mut peg_in_graphs, &mut peg_out_graphs).is_ok());
+    let migrated_peg_in_graph =
+        serde_json::from_value::<PegInGraph>(peg_in_graphs.pop().unwrap()).unwrap();
+    let migrated_peg_out_graph =
+        serde_json::from_value::<PegOutGraph>(peg_out_graphs.pop().unwrap()).unwrap();
+    assert!(peg_in_graph == migrated_peg_in_graph);
+    assert!(peg_out_graph == migrated_peg_out_graph);
+    assert_eq!(
+        migrated_peg_in_graph.timelock_config(),
+        migrated_peg_out_graph.timelock_config()
+    );
+
+    let mut future_peg_out_graph = serde_json::from_str::<Value>(&json).unwrap();
+    future_peg_out_graph["version"] = Value::from("99.0");
+    assert!(try_deserialize_peg_out_graph(&serialize(&future_peg_out_graph)).is_err());
 }
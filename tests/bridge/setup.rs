@@ -17,8 +17,11 @@ use bitvm::bridge::{
         DEPOSITOR_EVM_ADDRESS, DEPOSITOR_SECRET, OPERATOR_SECRET, VERIFIER_0_SECRET,
         VERIFIER_1_SECRET, WITHDRAWER_EVM_ADDRESS, WITHDRAWER_SECRET,
     },
+    timelocks::TimelockConfig,
 };
 
+pub fn test_timelock_config() -> TimelockConfig { TimelockConfig::regtest() }
+
 pub async fn setup_test() -> (
     BitVMClient,
     BitVMClient,
@@ -89,7 +92,11 @@ pub async fn setup_test() -> (
         &operator_context.operator_taproot_public_key,
         &operator_context.n_of_n_taproot_public_key,
     );
-    let connector_b = ConnectorB::new(source_network, &operator_context.n_of_n_taproot_public_key);
+    let connector_b = ConnectorB::new(
+        source_network,
+        &operator_context.n_of_n_taproot_public_key,
+        &test_timelock_config(),
+    );
     let connector_c = ConnectorC::new(
         source_network,
         &operator_context.operator_taproot_public_key,
@@ -99,20 +106,30 @@ pub async fn setup_test() -> (
         DEPOSITOR_EVM_ADDRESS,
         &depositor_context.depositor_taproot_public_key,
         &operator_context.n_of_n_taproot_public_key,
+        &test_timelock_config(),
     );
     let connector_0 = Connector0::new(source_network, &operator_context.n_of_n_taproot_public_key);
     let connector_1 = Connector1::new(
         source_network,
         &operator_context.operator_taproot_public_key,
         &operator_context.n_of_n_taproot_public_key,
+        &test_timelock_config(),
     );
     let connector_2 = Connector2::new(
         source_network,
         &operator_context.operator_taproot_public_key,
         &operator_context.n_of_n_taproot_public_key,
     );
-    let connector_3 = Connector3::new(source_network, &operator_context.operator_public_key);
-    let connector_4 = Connector4::new(source_network, &operator_context.operator_public_key);
+    let connector_3 = Connector3::new(
+        source_network,
+        &operator_context.operator_public_key,
+        &test_timelock_config(),
+    );
+    let connector_4 = Connector4::new(
+        source_network,
+        &operator_context.operator_public_key,
+        &test_timelock_config(),
+    );
     let connector_5 = Connector5::new(source_network, &operator_context.n_of_n_taproot_public_key);
 
     return (
@@ -9,7 +9,10 @@ use bitvm::bridge::{
     },
 };
 
-use super::super::{helper::generate_stub_outpoint, setup::setup_test};
+use super::super::{
+    helper::generate_stub_outpoint,
+    setup::{setup_test, test_timelock_config},
+};
 
 #[tokio::test]
 async fn test_start_time_timeout_tx() {
@@ -55,6 +58,7 @@ async fn test_start_time_timeout_tx() {
             outpoint: funding_outpoint1,
             amount: input_value1,
         },
+        &test_timelock_config(),
     );
 
     let secret_nonces_0 = start_time_timeout_tx.push_nonces(&verifier_0_context);
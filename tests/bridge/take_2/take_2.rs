@@ -9,7 +9,10 @@ use bitvm::bridge::{
     },
 };
 
-use super::super::{helper::generate_stub_outpoint, setup::setup_test};
+use super::super::{
+    helper::generate_stub_outpoint,
+    setup::{setup_test, test_timelock_config},
+};
 
 #[tokio::test]
 async fn test_take_2_tx() {
@@ -73,6 +76,7 @@ async fn test_take_2_tx() {
             outpoint: funding_outpoint3,
             amount: input_value3,
         },
+        &test_timelock_config(),
     );
 
     let secret_nonces_0 = take_2_tx.push_nonces(&verifier_0_context);
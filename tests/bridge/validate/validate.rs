@@ -12,7 +12,7 @@ use bitvm::bridge::{
     transactions::{base::Input, pre_signed::PreSignedTransaction},
 };
 
-use super::super::setup::setup_test;
+use super::super::setup::{setup_test, test_timelock_config};
 
 #[tokio::test]
 async fn test_validate_success() {
@@ -25,6 +25,22 @@ async fn test_validate_success() {
     assert!(is_peg_out_data_valid);
 }
 
+#[tokio::test]
+async fn test_validate_scripts_success() {
+    let (peg_in_graph, peg_out_graph, _) = setup_and_create_graphs().await;
+
+    let peg_in_report = peg_in_graph.validate_scripts();
+    let peg_out_report = peg_out_graph.validate_scripts();
+
+    assert!(peg_in_report.is_valid(), "{}", peg_in_report);
+    assert!(peg_out_report.is_valid(), "{}", peg_out_report);
+    // The depositor signs the refund and the operator signs the challenge, kick-off 2 and start
+    // time transactions when the graphs are created, n-of-n inputs are still unsigned.
+    assert!(peg_in_report.checked_inputs > 0);
+    assert!(peg_out_report.checked_inputs > 0);
+    assert!(peg_out_report.unsigned_inputs > 0);
+}
+
 #[tokio::test]
 async fn test_validate_invalid_previous_output() {
     let (mut peg_in_graph, _, peg_in_outpoint) = setup_and_create_graphs().await;
@@ -132,6 +148,7 @@ async fn setup_and_create_graphs() -> (PegInGraph, PegOutGraph, OutPoint) {
             amount,
         },
         &depositor_evm_address,
+        &test_timelock_config(),
     );
 
     let peg_out_graph = PegOutGraph::new(